@@ -7,9 +7,34 @@ use std::env;
 use std::fs;
 use std::path::Path;
 
+// Copies every translation file into `<dest>/locales`
+fn copy_locales(dest: &Path) {
+    let locales_dir = Path::new("locales");
+    let Ok(entries) = fs::read_dir(locales_dir) else {
+        println!("Warning: locales folder not found");
+        return;
+    };
+
+    let dest_dir = dest.join("locales");
+    if let Err(e) = fs::create_dir_all(&dest_dir) {
+        eprintln!("Failed to create {:?}: {}", dest_dir, e);
+        return;
+    }
+    for entry in entries.flatten() {
+        let src_path = entry.path();
+        if src_path.extension().is_some_and(|ext| ext == "txt") {
+            if let Err(e) = fs::copy(&src_path, dest_dir.join(entry.file_name())) {
+                eprintln!("Failed to copy {:?}: {}", src_path, e);
+            }
+        }
+    }
+}
+
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=locales");
     println!("cargo:rerun-if-changed=movenet_singlepose_thunder.onnx");
+    println!("cargo:rerun-if-changed=movenet_singlepose_lightning.onnx");
 
     // Get the target directory
     let out_dir = env::var_os("OUT_DIR").unwrap();
@@ -22,6 +47,7 @@ fn main() {
     // List of files to copy
     let files_to_copy = vec![
         ("movenet_singlepose_thunder.onnx", "MoveNet Thunder model"),
+        ("movenet_singlepose_lightning.onnx", "MoveNet Lightning model (low-memory mode)"),
         // Note: ONNX runtime DLL is typically handled by the ort crate,
         // but we'll include a fallback copy if needed
     ];
@@ -40,9 +66,10 @@ fn main() {
                     println!("Copied {} to debug directory", description);
                 }
             } else {
-                println!("Warning: {} not found at {:?}", description, src_path);
+                println!("Warning: {} not found at {:?} (it will be downloaded on first run)", description, src_path);
             }
         }
+        copy_locales(&debug_dir);
     }
 
     // Copy files to release directory (if it exists)
@@ -60,6 +87,7 @@ fn main() {
                 }
             }
         }
+        copy_locales(&release_dir);
     }
 
     // Also copy to the out directory for cargo run
@@ -0,0 +1,223 @@
+/// Arbitration between the alert channels (overlay, sound, toast): while a channel is busy,
+/// the ones ranked below it stay quiet, so enabling everything doesn't stack alerts.
+/// Channels can also have their own alert profile, firing earlier or later than the rest.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::{self, SharedClock};
+use crate::config::{SOUND_BUSY_SECS, TOAST_AFTER_SECS, TOAST_BUSY_SECS};
+use crate::engine::{PostureEngine, PostureStatus, SlouchTrigger};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSink {
+    Overlay,
+    Sound,
+    Toast,
+}
+
+pub const ALL_SINKS: [AlertSink; 3] = [AlertSink::Overlay, AlertSink::Sound, AlertSink::Toast];
+
+/// `priority` without duplicates, with missing channels appended in their default order
+pub fn normalize_priority(priority: &[AlertSink]) -> Vec<AlertSink> {
+    let mut normalized = Vec::with_capacity(ALL_SINKS.len());
+    for sink in priority.iter().chain(&ALL_SINKS) {
+        if !normalized.contains(sink) {
+            normalized.push(*sink);
+        }
+    }
+    normalized
+}
+
+pub struct AlertArbiter {
+    clock: SharedClock,
+    /// Highest priority first
+    priority: Vec<AlertSink>,
+    overlay_full: bool,
+    sound_until: Option<Instant>,
+    toast_until: Option<Instant>,
+}
+
+impl AlertArbiter {
+    pub fn new(clock: SharedClock, priority: &[AlertSink]) -> Self {
+        Self {
+            clock,
+            priority: normalize_priority(priority),
+            overlay_full: false,
+            sound_until: None,
+            toast_until: None,
+        }
+    }
+
+    pub fn set_priority(&mut self, priority: &[AlertSink]) {
+        self.priority = normalize_priority(priority);
+    }
+
+    /// Feed the shown overlay intensity once per frame. The overlay fades continuously and is
+    /// never held back; it only counts as busy once fully faded in.
+    pub fn set_overlay_intensity(&mut self, intensity: f32) {
+        self.overlay_full = intensity >= 1.0;
+    }
+
+    /// Whether `sink` is currently holding the user's attention
+    pub fn is_busy(&self, sink: AlertSink) -> bool {
+        let now = self.clock.now();
+        match sink {
+            AlertSink::Overlay => self.overlay_full,
+            AlertSink::Sound => self.sound_until.is_some_and(|until| now < until),
+            AlertSink::Toast => self.toast_until.is_some_and(|until| now < until),
+        }
+    }
+
+    /// The busy channel that keeps `sink` from firing, if any
+    pub fn blocked_by(&self, sink: AlertSink) -> Option<AlertSink> {
+        self.priority
+            .iter()
+            .take_while(|higher| **higher != sink)
+            .find(|higher| self.is_busy(**higher))
+            .copied()
+    }
+
+    /// Asks to fire `sink` now. Returns false when a higher-priority channel is busy;
+    /// otherwise the channel counts as busy for its usual duration.
+    pub fn request(&mut self, sink: AlertSink) -> bool {
+        if self.blocked_by(sink).is_some() {
+            return false;
+        }
+        let now = self.clock.now();
+        match sink {
+            AlertSink::Overlay => {}
+            AlertSink::Sound => self.sound_until = Some(now + Duration::from_secs(SOUND_BUSY_SECS)),
+            AlertSink::Toast => self.toast_until = Some(now + Duration::from_secs(TOAST_BUSY_SECS)),
+        }
+        true
+    }
+}
+
+impl Default for AlertArbiter {
+    fn default() -> Self {
+        Self::new(clock::system(), &ALL_SINKS)
+    }
+}
+
+/// When one channel fires, overriding the shared `sensitivity_px` and `debounce_ms`.
+/// For the toast, `debounce_ms` is how long slouching lasts before it (TOAST_AFTER_SECS by default).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertProfile {
+    pub sensitivity_px: Option<f32>,
+    pub debounce_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertProfiles {
+    pub overlay: AlertProfile,
+    pub sound: AlertProfile,
+    pub toast: AlertProfile,
+}
+
+impl AlertProfiles {
+    pub fn get(&self, sink: AlertSink) -> AlertProfile {
+        match sink {
+            AlertSink::Overlay => self.overlay,
+            AlertSink::Sound => self.sound,
+            AlertSink::Toast => self.toast,
+        }
+    }
+
+    /// Continuous slouching before a toast
+    pub fn toast_delay(&self) -> Duration {
+        self.toast.debounce_ms.map_or(Duration::from_secs(TOAST_AFTER_SECS), Duration::from_millis)
+    }
+}
+
+/// Slouch triggers for the channels whose profile overrides the sensitivity or debounce;
+/// the other channels follow the engine's own alert. All share the engine's grace period. Profiles only tune the eye drop, so the
+/// engine's other checks (e.g. the neck angle) still alert every channel.
+pub struct ChannelTriggers {
+    clock: SharedClock,
+    triggers: Vec<(AlertSink, SlouchTrigger)>,
+}
+
+impl ChannelTriggers {
+    /// `engine` supplies the shared sensitivity, recovery and debounce
+    pub fn new(clock: SharedClock, profiles: &AlertProfiles, engine: &PostureEngine) -> Self {
+        let mut channels = Self { clock, triggers: Vec::new() };
+        channels.configure(profiles, engine);
+        channels
+    }
+
+    /// Rebuilds the triggers after the profiles or the shared settings changed
+    pub fn configure(&mut self, profiles: &AlertProfiles, engine: &PostureEngine) {
+        self.triggers = ALL_SINKS
+            .iter()
+            .filter_map(|&sink| {
+                let profile = profiles.get(sink);
+                // The toast's debounce is waited out by SlouchNotifier; only a sensitivity needs a trigger
+                let debounce_ms = profile.debounce_ms.filter(|_| sink != AlertSink::Toast);
+                if profile.sensitivity_px.is_none() && debounce_ms.is_none() {
+                    return None;
+                }
+                let trigger = SlouchTrigger::new(
+                    profile.sensitivity_px.unwrap_or(engine.deviation()),
+                    engine.recovery(),
+                    debounce_ms.map_or(engine.debounce(), Duration::from_millis),
+                )
+                .with_grace(engine.grace());
+                Some((sink, trigger))
+            })
+            .collect();
+    }
+
+    /// Feed once per frame, after the engine
+    pub fn update(&mut self, engine: &PostureEngine) {
+        let now = self.clock.now();
+        for (_, trigger) in &mut self.triggers {
+            trigger.update(now, engine.judged_delta());
+            if engine.is_moving() {
+                trigger.hold_off(now);
+            }
+        }
+    }
+
+    /// Forgets every channel's slouch streak (after a pause, camera switch, ...)
+    pub fn reset(&mut self) {
+        for (_, trigger) in &mut self.triggers {
+            trigger.reset();
+        }
+    }
+
+    fn trigger(&self, sink: AlertSink) -> Option<&SlouchTrigger> {
+        self.triggers.iter().find(|(channel, _)| *channel == sink).map(|(_, trigger)| trigger)
+    }
+
+    /// Whether `sink` should be alerting now
+    pub fn is_alerting(&self, sink: AlertSink, engine: &PostureEngine) -> bool {
+        match self.trigger(sink) {
+            Some(trigger) => trigger.is_alerting() || engine.extra_checks().any(|(_, check)| check.is_alerting()),
+            None => engine.is_alerting(),
+        }
+    }
+
+    /// Whether the user is past `sink`'s sensitivity, debounce or not
+    pub fn is_slouching(&self, sink: AlertSink, engine: &PostureEngine) -> bool {
+        match self.trigger(sink) {
+            Some(trigger) => trigger.is_slouching() || engine.extra_checks().any(|(_, check)| check.is_slouching()),
+            None => matches!(engine.status(), PostureStatus::Slouching | PostureStatus::Alert),
+        }
+    }
+
+    /// Overlay strength for `sink`'s alert, 0.0 to 1.0
+    pub fn intensity(&self, sink: AlertSink, engine: &PostureEngine) -> f32 {
+        let now = self.clock.now();
+        match self.trigger(sink) {
+            Some(trigger) => engine.extra_checks()
+                .map(|(_, check)| check.intensity(now, None))
+                .fold(trigger.intensity(now, engine.judged_delta()), f32::max),
+            None => engine.alert_intensity(),
+        }
+    }
+}
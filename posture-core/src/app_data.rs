@@ -0,0 +1,35 @@
+/// Per-user application data directory (e.g. %APPDATA%\PostureAI)
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+const APP_DIR_NAME: &str = "PostureAI";
+
+/// Returns the app-data directory, creating it if needed
+pub fn dir() -> Result<PathBuf> {
+    let base = dirs::data_dir().ok_or_else(|| anyhow!("Could not locate the user app-data directory"))?;
+    let dir = base.join(APP_DIR_NAME);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Path of a file inside the app-data directory
+pub fn file_path(name: &str) -> Result<PathBuf> {
+    Ok(dir()?.join(name))
+}
+
+/// Replaces `path` with `contents` via a temp file + rename, so a crash mid-write
+/// never leaves a truncated file behind (stale `.tmp` files are cleaned by maintenance)
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
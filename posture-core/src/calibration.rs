@@ -0,0 +1,167 @@
+/// Posture calibration: averages an upright pose over a few seconds and stores it on disk
+
+use std::fs;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::app_data;
+use crate::config::{CALIBRATION_MIN_SAMPLES, CALIBRATION_SECS, HEIGHT};
+use crate::engine::{self, BodySize};
+use crate::pose::{Flip, Keypoint, Pose, KEYPOINT_COUNT, RIGHT_EYE};
+
+const CALIBRATION_FILE: &str = "calibration.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Calibration {
+    /// Baseline eye height in debug-window pixels
+    pub eye_y: f32,
+    /// Averaged upright pose in normalized coordinates
+    pub keypoints: Vec<Keypoint>,
+    /// Number of frames that went into the average
+    pub samples: usize,
+    /// Camera the baseline was recorded with
+    #[serde(default)]
+    pub camera_index: u32,
+    /// Rotation applied to frames when the baseline was recorded
+    #[serde(default)]
+    pub rotation_degrees: u32,
+    /// Flips applied to frames (after the rotation) when the baseline was recorded
+    #[serde(default)]
+    pub flip: Flip,
+    /// Unix timestamp of when the calibration was taken
+    #[serde(default)]
+    pub calibrated_at: u64,
+}
+
+impl Calibration {
+    /// Loads the saved calibration for `camera_index` at `rotation_degrees`, if any.
+    /// A baseline recorded with a different camera or rotation is ignored,
+    /// since its pixel coordinates no longer line up with the current frames; one recorded
+    /// with other flips is flipped to match.
+    pub fn load(camera_index: u32, rotation_degrees: u32, flip: Flip) -> Option<Self> {
+        let path = app_data::file_path(CALIBRATION_FILE).ok()?;
+        let json = fs::read_to_string(path).ok()?;
+        let calibration: Self = match serde_json::from_str(&json) {
+            Ok(calibration) => calibration,
+            Err(e) => {
+                warn!("Ignoring unreadable calibration file: {}", e);
+                return None;
+            }
+        };
+
+        if calibration.camera_index != camera_index || calibration.rotation_degrees != rotation_degrees {
+            info!("Saved calibration was made with a different camera setup - ignoring it.");
+            return None;
+        }
+        Some(calibration.reflipped(flip))
+    }
+
+    /// The same baseline as seen in frames flipped by `flip`
+    pub fn reflipped(self, flip: Flip) -> Self {
+        if flip == self.flip {
+            return self;
+        }
+        let keypoints = flip.relative_to(self.flip).keypoints(&self.keypoints);
+        let eye_y = keypoints.get(RIGHT_EYE).map_or(self.eye_y, |eye| eye.y * HEIGHT as f32);
+        Self { eye_y, keypoints, flip, ..self }
+    }
+
+    /// Upright ear-to-shoulder gap in preview pixels, the shoulder hunch baseline
+    pub fn ear_shoulder_gap(&self) -> Option<f32> {
+        let pose: &Pose = self.keypoints.as_slice().try_into().ok()?;
+        engine::ear_shoulder_gap(pose)
+    }
+
+    /// Eye spacing and shoulder width at calibration, the reference for the distance from the screen
+    pub fn body_size(&self) -> Option<BodySize> {
+        let pose: &Pose = self.keypoints.as_slice().try_into().ok()?;
+        Some(engine::body_size(pose))
+    }
+
+    /// Deletes the saved calibration file
+    pub fn clear() -> Result<()> {
+        let path = app_data::file_path(CALIBRATION_FILE)?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = app_data::file_path(CALIBRATION_FILE)?;
+        app_data::write_atomic(&path, serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Collects poses while the user sits upright
+pub struct CalibrationSession {
+    started: Instant,
+    poses: Vec<Pose>,
+}
+
+impl CalibrationSession {
+    pub fn start(now: Instant) -> Self {
+        Self {
+            started: now,
+            poses: Vec::new(),
+        }
+    }
+
+    pub fn add_sample(&mut self, pose: &Pose) {
+        // Frames without a usable eye keypoint would skew the baseline
+        if pose[RIGHT_EYE].is_confident() {
+            self.poses.push(*pose);
+        }
+    }
+
+    pub fn seconds_remaining(&self, now: Instant) -> u64 {
+        Duration::from_secs(CALIBRATION_SECS)
+            .saturating_sub(now.saturating_duration_since(self.started))
+            .as_secs_f32()
+            .ceil() as u64
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started) >= Duration::from_secs(CALIBRATION_SECS)
+    }
+
+    /// Averages the collected poses. Returns None if too few usable frames were seen.
+    pub fn finish(self, camera_index: u32, rotation_degrees: u32) -> Option<Calibration> {
+        if self.poses.len() < CALIBRATION_MIN_SAMPLES {
+            return None;
+        }
+
+        let mut keypoints = vec![Keypoint::default(); KEYPOINT_COUNT];
+        for (i, averaged) in keypoints.iter_mut().enumerate() {
+            let confident: Vec<&Keypoint> = self.poses.iter()
+                .map(|pose| &pose[i])
+                .filter(|kp| kp.is_confident())
+                .collect();
+            if confident.is_empty() {
+                continue;
+            }
+
+            let n = confident.len() as f32;
+            averaged.x = confident.iter().map(|kp| kp.x).sum::<f32>() / n;
+            averaged.y = confident.iter().map(|kp| kp.y).sum::<f32>() / n;
+            averaged.score = confident.iter().map(|kp| kp.score).sum::<f32>() / n;
+        }
+
+        Some(Calibration {
+            eye_y: keypoints[RIGHT_EYE].y * HEIGHT as f32,
+            keypoints,
+            samples: self.poses.len(),
+            camera_index,
+            rotation_degrees,
+            flip: Flip::default(),
+            calibrated_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        })
+    }
+}
@@ -0,0 +1,130 @@
+/// Time source for debounce, fades, cooldowns and periodic checks
+///
+/// Everything time-dependent asks a `Clock` instead of calling `Instant::now()`, so the
+/// integration tests can fast-forward minutes of behavior with a `ManualClock`.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The real monotonic clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+pub fn system() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// A clock that only moves when told to; clones share the same time
+#[derive(Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self { now: Arc::new(Mutex::new(Instant::now())) }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Fires at most once per period, e.g. "re-check the geofence every 30s"
+pub struct Interval {
+    clock: SharedClock,
+    period: Duration,
+    last: Instant,
+}
+
+impl Interval {
+    /// The first tick is one full period from now
+    pub fn new(clock: &SharedClock, period: Duration) -> Self {
+        Self {
+            clock: Arc::clone(clock),
+            period,
+            last: clock.now(),
+        }
+    }
+
+    /// True (and restarts the period) once the period has passed
+    pub fn is_due(&mut self) -> bool {
+        let now = self.clock.now();
+        if now.saturating_duration_since(self.last) >= self.period {
+            self.last = now;
+            return true;
+        }
+        false
+    }
+
+    /// Restarts the period from now
+    pub fn reset(&mut self) {
+        self.last = self.clock.now();
+    }
+}
+
+/// Retry timing whose wait doubles after each failed attempt, up to a cap, e.g. reopening a
+/// camera that was unplugged
+pub struct Backoff {
+    clock: SharedClock,
+    max: Duration,
+    delay: Duration,
+    next: Instant,
+    failures: u32,
+}
+
+impl Backoff {
+    /// The first attempt is due after `min`
+    pub fn new(clock: &SharedClock, min: Duration, max: Duration) -> Self {
+        Self {
+            clock: Arc::clone(clock),
+            max,
+            delay: min,
+            next: clock.now() + min,
+            failures: 0,
+        }
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.clock.now() >= self.next
+    }
+
+    /// Records a failed attempt; the next one waits twice as long as the last (at most `max`)
+    pub fn failed(&mut self) {
+        self.failures += 1;
+        self.delay = (self.delay * 2).min(self.max);
+        self.next = self.clock.now() + self.delay;
+    }
+
+    /// Failed attempts so far
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+}
@@ -0,0 +1,98 @@
+/// Tuning constants for the posture decision: thresholds, debounce, calibration, presence and
+/// the adaptive inference rate. Frontends can override the per-user ones at runtime (e.g.
+/// `PostureEngine::set_deviation`).
+
+// Preview size the pose is scaled to; pixel thresholds below are in this space
+pub const WIDTH: usize = 640;
+pub const HEIGHT: usize = 480;
+
+// Posture detection
+pub const GOOD_POSTURE_DEVIATION: f32 = 10.0; // Sensitivity: a slouch starts this far below the baseline...
+pub const GOOD_POSTURE_RECOVERY: f32 = 5.0;   // ...and ends only once back within this (hysteresis)
+pub const KEYPOINT_CONFIDENCE: f32 = 0.3;     // Min score to trust a keypoint
+pub const DEBOUNCE_MS: u64 = 500;             // How long bad posture must last before the overlay triggers
+pub const GRACE_MS: u64 = 0;                  // After sitting back up, brief dips this long don't re-trigger (0 = off)
+pub const APPROACHING_FRACTION: f32 = 0.5;    // Share of the deviation the eyes must drop to count as drifting (ignores jitter)
+
+// Tracking quality: keypoint confidence averaged over a few seconds (the eyes weigh most). While it's
+// poor (dim light, occlusion) posture isn't judged - no slouching, alerts or bad frames.
+pub const TRACKING_QUALITY_SECS: f32 = 2.0; // Time constant of the average
+pub const TRACKING_POOR_BELOW: f32 = 0.35;  // Tracking turns poor below this weighted confidence...
+pub const TRACKING_GOOD_ABOVE: f32 = 0.45;  // ...and recovers only above this
+
+// Neck angle: the nose-to-mid-shoulder line leaning further than this from vertical counts as
+// craning, whatever the eye height (which a changed chair height throws off)
+pub const NECK_ANGLE_DEGREES: Option<f32> = Some(25.0); // None = check off
+pub const NECK_ANGLE_HYSTERESIS_DEGREES: f32 = 5.0;     // A crane ends only this far back inside the limit
+
+// Head tilt: the line between the eyes rolled away from horizontal (head leaning to one side)
+pub const HEAD_TILT_DEGREES: Option<f32> = Some(15.0);  // Eye line this far from horizontal (None = check off)
+pub const HEAD_TILT_HYSTERESIS_DEGREES: f32 = 5.0;      // A tilt ends only this far back inside the limit
+pub const HEAD_TILT_SECS: u64 = 10;                     // How long a tilt must last before it alerts
+
+// Shoulder hunch: shoulders pulled up toward the ears, measured as how much the ear-to-shoulder
+// gap shrank from the calibrated one. Tension builds slowly, so it has to last a while.
+pub const SHOULDER_HUNCH_PERCENT: Option<f32> = Some(25.0); // Gap shrunk by this much (None = check off)
+pub const SHOULDER_HUNCH_HYSTERESIS_PERCENT: f32 = 10.0;    // A hunch ends only this far back inside the limit
+pub const SHOULDER_HUNCH_SECS: u64 = 30;                    // How long a hunch must last before it alerts
+
+// Distance from the screen: leaning in makes the eyes (or, with the face turned, the shoulders)
+// look further apart than at calibration. Sitting too close is warned about on its own, not as slouching.
+pub const TOO_CLOSE_PERCENT: Option<f32> = Some(30.0); // This much wider than calibrated (None = off)
+pub const TOO_CLOSE_HYSTERESIS_PERCENT: f32 = 10.0;    // Too close ends only this far back inside the limit
+pub const TOO_CLOSE_SECS: u64 = 20;                    // How long before the warning
+
+// Adaptive inference rate: full rate near the threshold or right after a change, throttled
+// once posture has been stably good for a while
+pub const ADAPTIVE_INFERENCE_ENABLED: bool = true;
+pub const INFERENCE_MIN_INTERVAL_MS: u64 = 0;    // Gap between inferences at full rate (0 = every frame)
+pub const INFERENCE_MAX_INTERVAL_MS: u64 = 700;  // Gap once stable (~1.5 per second)
+pub const INFERENCE_STABLE_SECS: u64 = 10;       // Calm, upright time before throttling
+pub const INFERENCE_NEAR_THRESHOLD_PX: f32 = 5.0; // Closer than this to the slouch threshold keeps the full rate
+
+// Presence: nobody confidently in view for this long counts as away - no alerts or bad frames
+// and the slowest inference rate until someone is back
+pub const AWAY_AFTER_SECS: u64 = 10;
+
+// Standing: the eyes rising far above the calibrated height, or the head leaving the top of the
+// frame while the shoulders or hips are still in view, counts as standing up
+pub const STANDING_RISE_PX: f32 = 80.0;     // Eyes this far above the baseline
+pub const STANDING_SHOULDER_TOP: f32 = 0.3; // Shoulders without a face above this (normalized y)
+pub const STANDING_SWITCH_SECS: u64 = 3;    // How long a new stance must last before it counts
+
+// Movement: fast head and shoulder motion (picking something up, turning to talk) isn't counted as
+// slouching, during it and for a moment after, so only a slouch held still alerts
+pub const IGNORE_MOVEMENT: bool = false;
+pub const MOTION_SPEED_PX_PER_SEC: f32 = 150.0; // Average keypoint speed that counts as moving
+pub const MOTION_WINDOW_MS: u64 = 250;          // Speed measured over at least this long (evens out jitter)
+pub const MOTION_SETTLE_MS: u64 = 1500;         // Still for this long after moving before slouching counts
+
+// Overlay escalation: intensity grows with how long and how far you've been slouching
+pub const OVERLAY_ESCALATION_ENABLED: bool = true; // false = jump straight to full strength
+pub const OVERLAY_MIN_INTENSITY: f32 = 0.3;        // Fraction of full strength when the alert starts
+pub const OVERLAY_FULL_AFTER_SECS: u64 = 30;       // Alert duration that reaches full intensity
+pub const OVERLAY_FULL_EXTRA_DEVIATION: f32 = 30.0; // Pixels past GOOD_POSTURE_DEVIATION that reach full intensity
+
+// Escalation ladder (off by default): continuous slouching leads to a toast, then a light dim,
+// then the full acrylic blur and optionally an overlay that takes the mouse clicks
+pub const ESCALATION_ENABLED: bool = false;
+pub const ESCALATION_TOAST_AFTER_SECS: u64 = 10;
+pub const ESCALATION_DIM_AFTER_SECS: u64 = 30;
+pub const ESCALATION_BLUR_AFTER_SECS: u64 = 60;
+pub const ESCALATION_BLOCK_AFTER_SECS: Option<u64> = None; // Input block step (None = off)
+pub const ESCALATION_DIM_INTENSITY: f32 = 0.5;              // Fraction of full strength for the dim step
+
+// Posture exercises: repetitions are counted from keypoint motion
+pub const CHIN_TUCK_MIN_SHRINK: f32 = 0.06; // Eye spacing drop (fraction) that counts as a tuck
+pub const NECK_ROLL_MIN_TURN: f32 = 0.35;   // Nose offset from the shoulders' center, in shoulder widths
+
+// Alert channel timing
+pub const TOAST_AFTER_SECS: u64 = 120; // Continuous slouching before a toast
+pub const SOUND_BUSY_SECS: u64 = 3;    // How long a played sound counts as busy
+pub const TOAST_BUSY_SECS: u64 = 7;    // How long a toast stays on screen
+
+// Calibration
+pub const CALIBRATION_SECS: u64 = 5;          // How long to sit upright while calibrating
+pub const CALIBRATION_MIN_SAMPLES: usize = 10; // Frames with a visible face needed to succeed
+pub const SETTLE_SECS: u64 = 3;               // Automatic recalibration waits until the eyes held still this long...
+pub const SETTLE_TOLERANCE_PX: f32 = 8.0;     // ...within this many pixels
@@ -0,0 +1,893 @@
+/// Posture engine: calibration, slouch detection and debounce, free of camera/window/tray code
+///
+/// The main loop feeds it one pose per frame and applies the returned events; the integration
+/// tests drive it the same way with scripted poses and a manual clock.
+
+use std::time::{Duration, Instant};
+
+use crate::calibration::{Calibration, CalibrationSession};
+use crate::clock::SharedClock;
+use crate::config::{
+    APPROACHING_FRACTION, AWAY_AFTER_SECS, CALIBRATION_SECS, DEBOUNCE_MS, GOOD_POSTURE_DEVIATION, GRACE_MS,
+    GOOD_POSTURE_RECOVERY, HEAD_TILT_DEGREES, HEAD_TILT_HYSTERESIS_DEGREES, HEAD_TILT_SECS, HEIGHT, IGNORE_MOVEMENT,
+    NECK_ANGLE_DEGREES, NECK_ANGLE_HYSTERESIS_DEGREES,
+    OVERLAY_ESCALATION_ENABLED, OVERLAY_FULL_AFTER_SECS, OVERLAY_FULL_EXTRA_DEVIATION, OVERLAY_MIN_INTENSITY, SETTLE_SECS,
+    SETTLE_TOLERANCE_PX, SHOULDER_HUNCH_HYSTERESIS_PERCENT, SHOULDER_HUNCH_PERCENT, SHOULDER_HUNCH_SECS,
+    TOO_CLOSE_HYSTERESIS_PERCENT, TOO_CLOSE_PERCENT, TOO_CLOSE_SECS, WIDTH,
+};
+use crate::motion::MotionDetector;
+use crate::pose::{Keypoint, Pose, LEFT_EAR, LEFT_EYE, LEFT_SHOULDER, NOSE, RIGHT_EAR, RIGHT_EYE, RIGHT_SHOULDER};
+use crate::quality::TrackingQuality;
+use crate::stance::{Stance, StanceDetector};
+
+/// Eye height in preview pixels, or None when the eye isn't confidently detected
+pub fn eye_y(pose: &Pose) -> Option<f32> {
+    Some(pose[RIGHT_EYE]).filter(|kp| kp.is_confident()).map(|kp| kp.y * HEIGHT as f32)
+}
+
+/// Angle in degrees between the line from the shoulders' midpoint up to the nose and vertical
+/// (0 = head straight above the shoulders), or None without a confident nose and shoulders
+pub fn neck_angle(pose: &Pose) -> Option<f32> {
+    let [nose, left, right] = [pose[NOSE], pose[LEFT_SHOULDER], pose[RIGHT_SHOULDER]];
+    if !(nose.is_confident() && left.is_confident() && right.is_confident()) {
+        return None;
+    }
+    // In preview pixels, so the frame's aspect ratio doesn't skew the angle
+    let dx = (nose.x - (left.x + right.x) / 2.0) * WIDTH as f32;
+    let rise = ((left.y + right.y) / 2.0 - nose.y) * HEIGHT as f32;
+    Some(dx.abs().atan2(rise).to_degrees())
+}
+
+/// Roll of the line between the eyes from horizontal in degrees (0 = level, either side counts
+/// the same), or None without both eyes confidently detected
+pub fn head_tilt(pose: &Pose) -> Option<f32> {
+    let [left, right] = [pose[LEFT_EYE], pose[RIGHT_EYE]];
+    if !(left.is_confident() && right.is_confident()) {
+        return None;
+    }
+    let dx = (left.x - right.x) * WIDTH as f32;
+    let dy = (left.y - right.y) * HEIGHT as f32;
+    Some(dy.abs().atan2(dx.abs()).to_degrees())
+}
+
+/// Vertical gap between ears and shoulders in preview pixels, averaged over the sides where
+/// both are confidently detected
+pub fn ear_shoulder_gap(pose: &Pose) -> Option<f32> {
+    let gaps: Vec<f32> = [(LEFT_EAR, LEFT_SHOULDER), (RIGHT_EAR, RIGHT_SHOULDER)]
+        .iter()
+        .filter(|&&(ear, shoulder)| pose[ear].is_confident() && pose[shoulder].is_confident())
+        .map(|&(ear, shoulder)| (pose[shoulder].y - pose[ear].y) * HEIGHT as f32)
+        .collect();
+    (!gaps.is_empty()).then(|| gaps.iter().sum::<f32>() / gaps.len() as f32)
+}
+
+/// How much (percent) the ear-to-shoulder gap shrank from the upright `baseline` gap
+pub fn hunch_percent(gap: f32, baseline: f32) -> Option<f32> {
+    (baseline > 0.0).then(|| (baseline - gap) / baseline * 100.0)
+}
+
+/// Sizes in preview pixels that grow as the user gets closer to the camera
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BodySize {
+    pub eye_spacing: Option<f32>,
+    pub shoulder_width: Option<f32>,
+}
+
+impl BodySize {
+    /// How much (percent) larger than `reference`: by the eye spacing if both have it,
+    /// else by the shoulder width
+    pub fn closer_percent(&self, reference: &BodySize) -> Option<f32> {
+        let grown = |now: Option<f32>, then: Option<f32>| match (now, then) {
+            (Some(now), Some(then)) if then > 0.0 => Some((now / then - 1.0) * 100.0),
+            _ => None,
+        };
+        grown(self.eye_spacing, reference.eye_spacing).or_else(|| grown(self.shoulder_width, reference.shoulder_width))
+    }
+}
+
+/// Eye spacing and shoulder width of `pose`, each where both keypoints are confident
+pub fn body_size(pose: &Pose) -> BodySize {
+    let span = |a: &Keypoint, b: &Keypoint| {
+        (a.is_confident() && b.is_confident())
+            .then(|| ((a.x - b.x) * WIDTH as f32).hypot((a.y - b.y) * HEIGHT as f32))
+    };
+    BodySize {
+        eye_spacing: span(&pose[LEFT_EYE], &pose[RIGHT_EYE]),
+        shoulder_width: span(&pose[LEFT_SHOULDER], &pose[RIGHT_SHOULDER]),
+    }
+}
+
+/// Where the user currently stands, as shown by the tray icon and debug window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostureStatus {
+    NotCalibrated,
+    Calibrating,
+    Good,
+    /// Past APPROACHING_FRACTION of the allowed deviation below the baseline, but still inside it
+    Approaching,
+    /// Past the deviation (and not yet back within the recovery distance), waiting out the debounce
+    Slouching,
+    /// Slouching for longer than the debounce - the overlay should be shown
+    Alert,
+    /// Nobody confidently in view for a while; posture isn't judged until someone is back
+    Away,
+    /// Someone is in view but the keypoints are too unreliable to judge (dim light, occlusion)
+    TrackingPoor,
+}
+
+/// Which check raised an alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertRule {
+    /// Eyes dropped more than the allowed deviation below the calibrated baseline
+    EyeDrop,
+    /// The neck leaned further from vertical than its limit
+    NeckAngle,
+    /// Shoulders stayed raised toward the ears
+    ShoulderHunch,
+    /// The head stayed tilted to one side
+    HeadTilt,
+}
+
+impl AlertRule {
+    /// Stable name stored in the history database
+    pub fn key(self) -> &'static str {
+        match self {
+            AlertRule::EyeDrop => "eye_drop",
+            AlertRule::NeckAngle => "neck_angle",
+            AlertRule::ShoulderHunch => "shoulder_hunch",
+            AlertRule::HeadTilt => "head_tilt",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "eye_drop" => Some(AlertRule::EyeDrop),
+            "neck_angle" => Some(AlertRule::NeckAngle),
+            "shoulder_hunch" => Some(AlertRule::ShoulderHunch),
+            "head_tilt" => Some(AlertRule::HeadTilt),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trigger {
+    Upright,
+    /// Back up after an alert; a new slouch can't alert before `until`
+    Grace { until: Instant },
+    /// Past the deviation since `since`, waiting out the debounce and any grace period left
+    Slouching { since: Instant, held_until: Option<Instant> },
+    Alert { since: Instant },
+}
+
+/// Slouch trigger with hysteresis: a slouch starts once the eyes drop past the deviation and
+/// only ends once they're back within the (smaller) recovery distance, so hovering around the
+/// line doesn't toggle the overlay. The engine has one; alert channels with their own profile
+/// run another on the same eye drop. After an alert ends, a grace period keeps brief dips (reaching
+/// for a cup) from bringing it straight back; a slouch that outlasts it still alerts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlouchTrigger {
+    /// Allowed eye drop in pixels
+    pub deviation: f32,
+    /// Eye drop a slouch must get back within to end (capped at the deviation)
+    pub recovery: f32,
+    /// How long slouching must last before an alert
+    pub debounce: Duration,
+    /// How long after an alert ended a new one is held back (zero = none)
+    pub grace: Duration,
+    state: Trigger,
+}
+
+impl SlouchTrigger {
+    pub fn new(deviation: f32, recovery: f32, debounce: Duration) -> Self {
+        Self { deviation, recovery, debounce, grace: Duration::ZERO, state: Trigger::Upright }
+    }
+
+    /// The same trigger with a grace period after each alert
+    pub fn with_grace(mut self, grace: Duration) -> Self {
+        self.grace = grace;
+        self
+    }
+
+    pub fn recovery(&self) -> f32 {
+        self.recovery.min(self.deviation)
+    }
+
+    /// Forgets the current slouch streak
+    pub fn reset(&mut self) {
+        self.state = Trigger::Upright;
+    }
+
+    /// Feeds one frame's eye drop below the baseline (None = nobody in view or calibrating)
+    pub fn update(&mut self, now: Instant, delta: Option<f32>) {
+        let grace_until = match self.state {
+            Trigger::Grace { until } | Trigger::Slouching { held_until: Some(until), .. } => Some(until).filter(|&until| now < until),
+            _ => None,
+        };
+        // What's left of a grace period, or upright once it ran out
+        let upright = grace_until.map_or(Trigger::Upright, |until| Trigger::Grace { until });
+        self.state = match (self.state, delta) {
+            // Losing sight of the user for a moment doesn't end the grace period
+            (_, None) => upright,
+            (Trigger::Upright | Trigger::Grace { .. }, Some(delta)) if delta > self.deviation => {
+                Trigger::Slouching { since: now, held_until: grace_until }
+            }
+            (Trigger::Slouching { .. }, Some(delta)) if delta <= self.recovery() => upright,
+            (Trigger::Alert { .. }, Some(delta)) if delta <= self.recovery() => {
+                if self.grace.is_zero() { Trigger::Upright } else { Trigger::Grace { until: now + self.grace } }
+            }
+            (Trigger::Grace { .. }, _) => upright,
+            (state, _) => state,
+        };
+        if let Trigger::Slouching { since, held_until } = self.state {
+            let held = held_until.is_some_and(|until| now < until);
+            if now.saturating_duration_since(since) >= self.debounce && !held {
+                self.state = Trigger::Alert { since };
+            }
+        }
+    }
+
+    /// Past the deviation, whether or not the debounce has run out
+    pub fn is_slouching(&self) -> bool {
+        matches!(self.state, Trigger::Slouching { .. } | Trigger::Alert { .. })
+    }
+
+    /// Back up after an alert, inside the grace period
+    pub fn is_in_grace(&self) -> bool {
+        matches!(self.state, Trigger::Grace { .. })
+    }
+
+    pub fn is_alerting(&self) -> bool {
+        matches!(self.state, Trigger::Alert { .. })
+    }
+
+    /// Forgets a slouch still waiting out its debounce, e.g. while the user moves; an alert
+    /// already shown and a grace period carry on
+    pub fn hold_off(&mut self, now: Instant) {
+        if let Trigger::Slouching { held_until, .. } = self.state {
+            self.state = match held_until.filter(|&until| now < until) {
+                Some(until) => Trigger::Grace { until },
+                None => Trigger::Upright,
+            };
+        }
+    }
+
+    /// How long the current slouch has lasted, debounce included
+    pub fn slouch_duration(&self, now: Instant) -> Option<Duration> {
+        match self.state {
+            Trigger::Upright | Trigger::Grace { .. } => None,
+            Trigger::Slouching { since, .. } | Trigger::Alert { since } => Some(now.saturating_duration_since(since)),
+        }
+    }
+
+    /// How strongly to show the overlay, 0.0 (hidden) to 1.0 (MAX_ALPHA). Starts at
+    /// OVERLAY_MIN_INTENSITY and rises with the alert's duration or the slouch depth,
+    /// whichever is further along.
+    pub fn intensity(&self, now: Instant, delta: Option<f32>) -> f32 {
+        if !self.is_alerting() {
+            return 0.0;
+        }
+        if !OVERLAY_ESCALATION_ENABLED {
+            return 1.0;
+        }
+
+        let alert_age = self.slouch_duration(now)
+            .unwrap_or_default()
+            .saturating_sub(self.debounce);
+        let by_time = alert_age.as_secs_f32() / OVERLAY_FULL_AFTER_SECS.max(1) as f32;
+        let by_depth = delta.map_or(0.0, |delta| (delta - self.deviation) / OVERLAY_FULL_EXTRA_DEVIATION);
+
+        let progress = by_time.max(by_depth).clamp(0.0, 1.0);
+        OVERLAY_MIN_INTENSITY + (1.0 - OVERLAY_MIN_INTENSITY) * progress
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum EngineEvent {
+    CalibrationFinished(Calibration),
+    /// Too few usable frames were seen; the previous baseline (if any) is kept
+    CalibrationFailed,
+    AlertStarted,
+    AlertEnded,
+    /// Nobody has been in view for the away delay
+    UserAway,
+    /// Someone is in view again after being away for `away_for`
+    UserReturned { away_for: Duration },
+    /// Tracking turned too unreliable to judge posture
+    TrackingPoor,
+    /// Tracking is reliable again
+    TrackingRecovered,
+}
+
+/// A check beside the eye drop, with its own limit and slouch streak
+#[derive(Debug, Clone, Copy)]
+struct RuleCheck {
+    rule: AlertRule,
+    trigger: SlouchTrigger,
+    /// How long it must last, if not the shared debounce
+    debounce: Option<Duration>,
+}
+
+/// Waiting for the eyes to hold still before an automatic recalibration
+#[derive(Debug, Clone, Copy)]
+struct Settling {
+    since: Instant,
+    eye_y: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngineStats {
+    pub frames: u64,
+    /// Frames judged against a baseline and found upright
+    pub good_frames: u64,
+    pub bad_frames: u64,
+    pub alerts: u32,
+}
+
+pub struct PostureEngine {
+    clock: SharedClock,
+    camera_index: u32,
+    rotation_degrees: u32,
+    baseline: Option<f32>,
+    /// GOOD_POSTURE_DEVIATION, GOOD_POSTURE_RECOVERY and DEBOUNCE_MS unless changed in the settings
+    trigger: SlouchTrigger,
+    /// The enabled checks besides the eye drop, e.g. the neck angle
+    checks: Vec<RuleCheck>,
+    calibration: Option<CalibrationSession>,
+    settling: Option<Settling>,
+    eye_y: Option<f32>,
+    neck_angle: Option<f32>,
+    head_tilt: Option<f32>,
+    ear_shoulder_gap: Option<f32>,
+    /// Upright ear-to-shoulder gap from the calibration
+    hunch_baseline: Option<f32>,
+    body_size: BodySize,
+    /// Body size at the calibrated distance from the screen
+    distance_baseline: Option<BodySize>,
+    /// Sitting too close to the screen; a warning of its own, not part of the posture alert
+    too_close: Option<SlouchTrigger>,
+    /// Whether AlertStarted was the last alert event sent
+    alerting: bool,
+    /// How long nobody may be in view before the user counts as away
+    away_after: Duration,
+    last_present: Instant,
+    /// When the user was last seen, while away
+    away_since: Option<Instant>,
+    quality: TrackingQuality,
+    stance: StanceDetector,
+    /// Slouches only count while the user holds still
+    ignore_movement: bool,
+    motion: MotionDetector,
+    stats: EngineStats,
+}
+
+impl PostureEngine {
+    /// `baseline` is the eye height from a saved calibration for this camera and rotation, if there is one
+    pub fn new(camera_index: u32, rotation_degrees: u32, baseline: Option<f32>, clock: SharedClock) -> Self {
+        let mut engine = Self {
+            trigger: SlouchTrigger::new(GOOD_POSTURE_DEVIATION, GOOD_POSTURE_RECOVERY, Duration::from_millis(DEBOUNCE_MS))
+                .with_grace(Duration::from_millis(GRACE_MS)),
+            checks: Vec::new(),
+            calibration: None,
+            settling: None,
+            eye_y: None,
+            neck_angle: None,
+            head_tilt: None,
+            ear_shoulder_gap: None,
+            hunch_baseline: None,
+            body_size: BodySize::default(),
+            distance_baseline: None,
+            too_close: None,
+            alerting: false,
+            away_after: Duration::from_secs(AWAY_AFTER_SECS),
+            last_present: clock.now(),
+            away_since: None,
+            quality: TrackingQuality::new(),
+            stance: StanceDetector::new(),
+            ignore_movement: IGNORE_MOVEMENT,
+            motion: MotionDetector::new(),
+            stats: EngineStats::default(),
+            clock,
+            camera_index,
+            rotation_degrees,
+            baseline,
+        };
+        engine.set_neck_angle(NECK_ANGLE_DEGREES);
+        engine.set_shoulder_hunch(SHOULDER_HUNCH_PERCENT);
+        engine.set_head_tilt(HEAD_TILT_DEGREES);
+        engine.set_too_close(TOO_CLOSE_PERCENT);
+        engine
+    }
+
+    pub fn start_calibration(&mut self) {
+        self.calibration = Some(CalibrationSession::start(self.clock.now()));
+        self.settling = None;
+        self.reset_triggers();
+    }
+
+    /// Recalibrates once the user has sat still for SETTLE_SECS, e.g. after sitting back down;
+    /// posture isn't judged in the meantime
+    pub fn start_calibration_when_settled(&mut self) {
+        self.calibration = None;
+        self.settling = Some(Settling { since: self.clock.now(), eye_y: None });
+        self.reset_triggers();
+    }
+
+    pub fn clear_baseline(&mut self) {
+        self.baseline = None;
+        self.hunch_baseline = None;
+        self.distance_baseline = None;
+        self.calibration = None;
+        self.settling = None;
+        self.reset_triggers();
+    }
+
+    /// Switches to another camera or rotation; baselines are per camera setup,
+    /// so the caller passes the matching one
+    pub fn set_camera(&mut self, camera_index: u32, rotation_degrees: u32, baseline: Option<f32>) {
+        self.camera_index = camera_index;
+        self.rotation_degrees = rotation_degrees;
+        self.baseline = baseline;
+        self.hunch_baseline = None;
+        self.distance_baseline = None;
+        self.calibration = None;
+        self.settling = None;
+        self.reset_triggers();
+    }
+
+    /// Pixels the eyes may drop below the baseline before it counts as slouching
+    pub fn set_deviation(&mut self, deviation: f32) {
+        self.trigger.deviation = deviation;
+    }
+
+    pub fn deviation(&self) -> f32 {
+        self.trigger.deviation
+    }
+
+    /// Pixels below the baseline the eyes must get back within to end a slouch (capped at the deviation)
+    pub fn set_recovery(&mut self, recovery: f32) {
+        self.trigger.recovery = recovery;
+    }
+
+    pub fn recovery(&self) -> f32 {
+        self.trigger.recovery()
+    }
+
+    /// How long slouching must last before an alert, for every check
+    pub fn set_debounce(&mut self, debounce: Duration) {
+        self.trigger.debounce = debounce;
+        for check in &mut self.checks {
+            check.trigger.debounce = check.debounce.unwrap_or(debounce);
+        }
+    }
+
+    pub fn debounce(&self) -> Duration {
+        self.trigger.debounce
+    }
+
+    /// How long after an alert ends a new one is held back, for every check
+    pub fn set_grace(&mut self, grace: Duration) {
+        self.trigger.grace = grace;
+        for check in &mut self.checks {
+            check.trigger.grace = grace;
+        }
+    }
+
+    pub fn grace(&self) -> Duration {
+        self.trigger.grace
+    }
+
+    /// Whether large, fast movements (picking something up, turning to talk) hold off slouch
+    /// counting until the user is still again
+    pub fn set_ignore_movement(&mut self, ignore: bool) {
+        self.ignore_movement = ignore;
+        self.motion.reset();
+    }
+
+    pub fn ignores_movement(&self) -> bool {
+        self.ignore_movement
+    }
+
+    /// Moving too fast to judge posture, or only just stopped (always false unless movement is ignored)
+    pub fn is_moving(&self) -> bool {
+        self.motion.is_moving()
+    }
+
+    /// Forgets the current slouch streak (after a pause, camera switch, ...)
+    pub fn reset_debounce(&mut self) {
+        self.reset_triggers();
+    }
+
+    fn reset_triggers(&mut self) {
+        self.trigger.reset();
+        for check in &mut self.checks {
+            check.trigger.reset();
+        }
+        if let Some(too_close) = self.too_close.as_mut() {
+            too_close.reset();
+        }
+    }
+
+    /// Turns the neck-angle check on with a limit in degrees from vertical, or off with None
+    pub fn set_neck_angle(&mut self, limit: Option<f32>) {
+        self.set_check(AlertRule::NeckAngle, limit.map(|limit| (limit, limit - NECK_ANGLE_HYSTERESIS_DEGREES)), None);
+    }
+
+    /// The neck-angle limit in degrees, if the check is on
+    pub fn neck_angle_limit(&self) -> Option<f32> {
+        self.rule_trigger(AlertRule::NeckAngle).map(|trigger| trigger.deviation)
+    }
+
+    /// Turns the shoulder hunch check on with a limit in percent the ear-to-shoulder gap may
+    /// shrink, or off with None. It alerts after SHOULDER_HUNCH_SECS, not the shared debounce.
+    pub fn set_shoulder_hunch(&mut self, limit: Option<f32>) {
+        let limits = limit.map(|limit| (limit, limit - SHOULDER_HUNCH_HYSTERESIS_PERCENT));
+        self.set_check(AlertRule::ShoulderHunch, limits, Some(Duration::from_secs(SHOULDER_HUNCH_SECS)));
+    }
+
+    /// The shoulder hunch limit in percent, if the check is on
+    pub fn shoulder_hunch_limit(&self) -> Option<f32> {
+        self.rule_trigger(AlertRule::ShoulderHunch).map(|trigger| trigger.deviation)
+    }
+
+    /// The upright ear-to-shoulder gap to judge hunching against, from the calibration
+    /// (`Calibration::ear_shoulder_gap`); without one the hunch check waits
+    pub fn set_hunch_baseline(&mut self, gap: Option<f32>) {
+        self.hunch_baseline = gap;
+    }
+
+    /// Turns the head tilt check on with a limit in degrees the eye line may roll from horizontal,
+    /// or off with None. It alerts after HEAD_TILT_SECS, not the shared debounce.
+    pub fn set_head_tilt(&mut self, limit: Option<f32>) {
+        let limits = limit.map(|limit| (limit, limit - HEAD_TILT_HYSTERESIS_DEGREES));
+        self.set_check(AlertRule::HeadTilt, limits, Some(Duration::from_secs(HEAD_TILT_SECS)));
+    }
+
+    /// The head tilt limit in degrees, if the check is on
+    pub fn head_tilt_limit(&self) -> Option<f32> {
+        self.rule_trigger(AlertRule::HeadTilt).map(|trigger| trigger.deviation)
+    }
+
+    /// Turns the too-close warning on with a limit in percent the body may look larger than at
+    /// calibration, or off with None. It warns after TOO_CLOSE_SECS.
+    pub fn set_too_close(&mut self, limit: Option<f32>) {
+        self.too_close = limit.map(|limit| {
+            SlouchTrigger::new(limit, limit - TOO_CLOSE_HYSTERESIS_PERCENT, Duration::from_secs(TOO_CLOSE_SECS))
+        });
+    }
+
+    /// The too-close limit in percent, if the warning is on
+    pub fn too_close_limit(&self) -> Option<f32> {
+        self.too_close.map(|trigger| trigger.deviation)
+    }
+
+    pub fn too_close_trigger(&self) -> Option<&SlouchTrigger> {
+        self.too_close.as_ref()
+    }
+
+    /// Whether the user has been too close to the screen for TOO_CLOSE_SECS
+    pub fn is_too_close(&self) -> bool {
+        self.too_close.is_some_and(|trigger| trigger.is_alerting())
+    }
+
+    /// Overlay strength for the too-close warning, 0.0 to 1.0, escalating by time
+    pub fn too_close_intensity(&self) -> f32 {
+        self.too_close.map_or(0.0, |trigger| trigger.intensity(self.clock.now(), None))
+    }
+
+    /// The body size at the calibrated distance (`Calibration::body_size`); without one the
+    /// too-close warning waits
+    pub fn set_distance_baseline(&mut self, size: Option<BodySize>) {
+        self.distance_baseline = size;
+    }
+
+    fn set_check(&mut self, rule: AlertRule, limits: Option<(f32, f32)>, debounce: Option<Duration>) {
+        self.checks.retain(|check| check.rule != rule);
+        if let Some((limit, recovery)) = limits {
+            let trigger = SlouchTrigger::new(limit, recovery, debounce.unwrap_or(self.trigger.debounce))
+                .with_grace(self.trigger.grace);
+            self.checks.push(RuleCheck { rule, trigger, debounce });
+        }
+    }
+
+    /// The trigger behind `rule`, if that check is on
+    pub fn rule_trigger(&self, rule: AlertRule) -> Option<&SlouchTrigger> {
+        match rule {
+            AlertRule::EyeDrop => Some(&self.trigger),
+            _ => self.checks.iter().find(|check| check.rule == rule).map(|check| &check.trigger),
+        }
+    }
+
+    /// The enabled checks besides the eye drop, with their triggers
+    pub fn extra_checks(&self) -> impl Iterator<Item = (AlertRule, &SlouchTrigger)> {
+        self.checks.iter().map(|check| (check.rule, &check.trigger))
+    }
+
+    /// How long nobody may be in view before the user counts as away
+    pub fn set_away_after(&mut self, away_after: Duration) {
+        self.away_after = away_after;
+    }
+
+    pub fn is_away(&self) -> bool {
+        self.away_since.is_some()
+    }
+
+    /// Tracks whether anyone is in view; returns the presence event this frame caused, if any
+    fn update_presence(&mut self, now: Instant, pose: Option<&Pose>) -> Option<EngineEvent> {
+        let present = pose.is_some_and(|pose| pose.iter().any(|kp| kp.is_confident()));
+        if present {
+            self.last_present = now;
+            return self.away_since.take().map(|since| EngineEvent::UserReturned { away_for: now.saturating_duration_since(since) });
+        }
+        if self.away_since.is_none() && now.saturating_duration_since(self.last_present) >= self.away_after {
+            self.away_since = Some(self.last_present);
+            self.reset_triggers();
+            self.stance.reset();
+            return Some(EngineEvent::UserAway);
+        }
+        None
+    }
+
+    /// Averages the keypoint confidence while someone is in view (nobody is the presence
+    /// check's job); returns the tracking event this frame caused, if any
+    fn update_quality(&mut self, now: Instant, pose: Option<&Pose>) -> Option<EngineEvent> {
+        let pose = pose.filter(|pose| pose.iter().any(|kp| kp.is_confident()))?;
+        if self.quality.update(now, pose)? {
+            self.reset_triggers();
+            Some(EngineEvent::TrackingPoor)
+        } else {
+            Some(EngineEvent::TrackingRecovered)
+        }
+    }
+
+    /// Averaged keypoint confidence and whether it's too poor to judge posture
+    pub fn quality(&self) -> &TrackingQuality {
+        &self.quality
+    }
+
+    pub fn is_tracking_poor(&self) -> bool {
+        self.quality.is_poor()
+    }
+
+    /// Sitting or standing, or None while nobody is in view
+    pub fn stance(&self) -> Option<Stance> {
+        (!self.is_away()).then(|| self.stance.stance())
+    }
+
+    /// Feeds one frame's pose (None when the model found nobody)
+    pub fn process(&mut self, pose: Option<&Pose>) -> Vec<EngineEvent> {
+        let now = self.clock.now();
+        let mut events = Vec::new();
+        self.stats.frames += 1;
+        self.eye_y = pose.and_then(eye_y);
+        self.neck_angle = pose.and_then(neck_angle);
+        self.head_tilt = pose.and_then(head_tilt);
+        self.ear_shoulder_gap = pose.and_then(ear_shoulder_gap);
+        self.body_size = pose.map(body_size).unwrap_or_default();
+        events.extend(self.update_presence(now, pose));
+        events.extend(self.update_quality(now, pose));
+        if let Some(pose) = pose.filter(|_| !self.is_calibrating()) {
+            self.stance.update(now, pose, self.baseline);
+        }
+        match pose.filter(|_| self.ignore_movement) {
+            Some(pose) => {
+                self.motion.update(now, pose);
+            }
+            None => self.motion.reset(),
+        }
+
+        // Settling - Start the calibration once the eyes have held still long enough
+        if let Some(settling) = self.settling.as_mut() {
+            let still = match (settling.eye_y, self.eye_y) {
+                (Some(anchor), Some(eye_y)) => (eye_y - anchor).abs() <= SETTLE_TOLERANCE_PX,
+                _ => false,
+            };
+            if !still {
+                *settling = Settling { since: now, eye_y: self.eye_y };
+            } else if now.saturating_duration_since(settling.since) >= Duration::from_secs(SETTLE_SECS) {
+                self.settling = None;
+                self.calibration = Some(CalibrationSession::start(now));
+            }
+        }
+
+        // Calibration - Collect upright samples instead of judging posture
+        if let Some(session) = self.calibration.as_mut() {
+            if let Some(p) = pose {
+                session.add_sample(p);
+            }
+            if session.is_finished(now) {
+                match self.calibration.take().and_then(|s| s.finish(self.camera_index, self.rotation_degrees)) {
+                    Some(calibration) => {
+                        self.baseline = Some(calibration.eye_y);
+                        self.hunch_baseline = calibration.ear_shoulder_gap();
+                        self.distance_baseline = calibration.body_size();
+                        events.push(EngineEvent::CalibrationFinished(calibration));
+                    }
+                    None => events.push(EngineEvent::CalibrationFailed),
+                }
+            }
+        }
+
+        // Posture Check - Only trigger when slouching down (positive delta), or past another check's limit
+        let judged = self.judged_delta();
+        self.trigger.update(now, judged);
+        let mut any_judged = judged.is_some();
+        let measurements: Vec<_> = self.checks.iter().map(|check| self.judged(check.rule)).collect();
+        for (check, measurement) in self.checks.iter_mut().zip(measurements) {
+            check.trigger.update(now, measurement);
+            any_judged |= measurement.is_some();
+        }
+        // Movement - A slouch starts counting once the user holds still; sitting up still ends an alert
+        let moving = self.motion.is_moving();
+        if moving {
+            self.trigger.hold_off(now);
+            for check in &mut self.checks {
+                check.trigger.hold_off(now);
+            }
+        }
+        if any_judged && !moving {
+            if self.is_slouching() {
+                self.stats.bad_frames += 1;
+            } else {
+                self.stats.good_frames += 1;
+            }
+        }
+
+        // Distance - Judged like a check, but warned about separately and kept out of the stats
+        let judging = self.baseline.is_some() && !self.is_calibrating() && !self.is_away() && !self.is_tracking_poor();
+        let closer = self.closer_percent().filter(|_| judging);
+        if let Some(too_close) = self.too_close.as_mut() {
+            too_close.update(now, closer);
+        }
+
+        let alerting = self.trigger.is_alerting() || self.checks.iter().any(|check| check.trigger.is_alerting());
+        if alerting != self.alerting {
+            self.alerting = alerting;
+            if alerting {
+                self.stats.alerts += 1;
+                events.push(EngineEvent::AlertStarted);
+            } else {
+                events.push(EngineEvent::AlertEnded);
+            }
+        }
+
+        events
+    }
+
+    pub fn status(&self) -> PostureStatus {
+        if self.is_calibrating() {
+            return PostureStatus::Calibrating;
+        }
+        if self.is_away() {
+            return PostureStatus::Away;
+        }
+        if self.baseline.is_none() {
+            return PostureStatus::NotCalibrated;
+        }
+        if self.is_tracking_poor() {
+            return PostureStatus::TrackingPoor;
+        }
+        if self.alerting {
+            return PostureStatus::Alert;
+        }
+        if self.is_slouching() {
+            return PostureStatus::Slouching;
+        }
+        match self.delta() {
+            Some(delta) if delta > self.deviation() * APPROACHING_FRACTION => PostureStatus::Approaching,
+            _ => PostureStatus::Good,
+        }
+    }
+
+    pub fn is_alerting(&self) -> bool {
+        self.alerting
+    }
+
+    /// Past any check's limit, whether or not the debounce has run out
+    fn is_slouching(&self) -> bool {
+        self.trigger.is_slouching() || self.checks.iter().any(|check| check.trigger.is_slouching())
+    }
+
+    /// The rule behind the current alert; the eye drop first if several are alerting
+    pub fn alert_rule(&self) -> Option<AlertRule> {
+        if !self.alerting {
+            return None;
+        }
+        if self.trigger.is_alerting() {
+            return Some(AlertRule::EyeDrop);
+        }
+        self.checks.iter().find(|check| check.trigger.is_alerting()).map(|check| check.rule)
+    }
+
+    /// How long the current slouch has lasted, debounce included (the longest of the checks)
+    pub fn slouch_duration(&self) -> Option<Duration> {
+        let now = self.clock.now();
+        self.checks.iter().map(|check| check.trigger.slouch_duration(now)).fold(self.trigger.slouch_duration(now), Option::max)
+    }
+
+    /// How strongly to show the overlay, 0.0 (hidden) to 1.0 (MAX_ALPHA); see `SlouchTrigger::intensity`.
+    /// The other checks escalate by time only, since their depth isn't in pixels.
+    pub fn alert_intensity(&self) -> f32 {
+        if !self.alerting {
+            return 0.0;
+        }
+        let now = self.clock.now();
+        self.checks.iter()
+            .map(|check| check.trigger.intensity(now, None))
+            .fold(self.trigger.intensity(now, self.delta()), f32::max)
+    }
+
+    /// Calibrating, or waiting for the user to sit still before calibrating
+    pub fn is_calibrating(&self) -> bool {
+        self.calibration.is_some() || self.settling.is_some()
+    }
+
+    /// The full CALIBRATION_SECS while still waiting for the user to sit still
+    pub fn calibration_seconds_remaining(&self) -> Option<u64> {
+        let now = self.clock.now();
+        match &self.calibration {
+            Some(session) => Some(session.seconds_remaining(now)),
+            None => self.settling.map(|_| CALIBRATION_SECS),
+        }
+    }
+
+    /// Calibrated eye height in preview pixels
+    pub fn baseline(&self) -> Option<f32> {
+        self.baseline
+    }
+
+    /// Eye height in the last processed frame, in preview pixels
+    pub fn eye_y(&self) -> Option<f32> {
+        self.eye_y
+    }
+
+    /// How far the eyes sit below the baseline (positive = slouching)
+    pub fn delta(&self) -> Option<f32> {
+        Some(self.eye_y? - self.baseline?)
+    }
+
+    /// The delta posture is judged on: None while calibrating or away
+    pub fn judged_delta(&self) -> Option<f32> {
+        self.delta().filter(|_| !self.is_calibrating() && !self.is_away() && !self.is_tracking_poor())
+    }
+
+    /// Neck angle from vertical in the last processed frame, in degrees
+    pub fn neck_angle(&self) -> Option<f32> {
+        self.neck_angle
+    }
+
+    /// Eye line roll from horizontal in the last processed frame, in degrees
+    pub fn head_tilt(&self) -> Option<f32> {
+        self.head_tilt
+    }
+
+    /// How much (percent) the ear-to-shoulder gap shrank from the calibrated one in the last frame
+    pub fn hunch_percent(&self) -> Option<f32> {
+        hunch_percent(self.ear_shoulder_gap?, self.hunch_baseline?)
+    }
+
+    /// How much (percent) closer to the screen than at calibration in the last frame, by how much
+    /// larger the eye spacing (or shoulder width) looks; negative when further away
+    pub fn closer_percent(&self) -> Option<f32> {
+        self.body_size.closer_percent(self.distance_baseline.as_ref()?)
+    }
+
+    /// The measurement `rule` is judged on: None while calibrating, away, tracking poorly or not calibrated.
+    /// A hunch isn't judged while the eyes are past the sensitivity, since a dropping head
+    /// shrinks the ear-to-shoulder gap too.
+    pub fn judged(&self, rule: AlertRule) -> Option<f32> {
+        if self.baseline.is_none() || self.is_calibrating() || self.is_away() || self.is_tracking_poor() {
+            return None;
+        }
+        match rule {
+            AlertRule::EyeDrop => self.judged_delta(),
+            AlertRule::NeckAngle => self.neck_angle,
+            AlertRule::HeadTilt => self.head_tilt,
+            AlertRule::ShoulderHunch => self.hunch_percent().filter(|_| !self.trigger.is_slouching()),
+        }
+    }
+
+    pub fn stats(&self) -> EngineStats {
+        self.stats
+    }
+}
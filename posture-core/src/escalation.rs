@@ -0,0 +1,105 @@
+/// Escalating consequences: the longer a slouch lasts, the harder the alert is to ignore, from a
+/// toast to a dimmed screen, a full blur and, if enabled, an overlay that takes the mouse clicks.
+/// Everything drops back as soon as posture is corrected.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{
+    ESCALATION_BLOCK_AFTER_SECS, ESCALATION_BLUR_AFTER_SECS, ESCALATION_DIM_AFTER_SECS, ESCALATION_DIM_INTENSITY,
+    ESCALATION_ENABLED, ESCALATION_TOAST_AFTER_SECS,
+};
+
+/// One step of the ladder, mildest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Toast,
+    /// Light dim
+    Dim,
+    /// Full acrylic blur
+    Blur,
+    /// Full blur that also takes the mouse clicks
+    Block,
+}
+
+impl Severity {
+    /// Overlay strength for this step, 0.0 (hidden) to 1.0 (MAX_ALPHA)
+    pub fn overlay_intensity(self) -> f32 {
+        match self {
+            Severity::Toast => 0.0,
+            Severity::Dim => ESCALATION_DIM_INTENSITY,
+            Severity::Blur | Severity::Block => 1.0,
+        }
+    }
+}
+
+/// Seconds of continuous slouching before each step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EscalationLadder {
+    /// Replaces the gradual overlay and the slouching toast with the ladder
+    pub enabled: bool,
+    pub toast_secs: u64,
+    pub dim_secs: u64,
+    pub blur_secs: u64,
+    /// None = never block input
+    pub block_secs: Option<u64>,
+}
+
+impl Default for EscalationLadder {
+    fn default() -> Self {
+        Self {
+            enabled: ESCALATION_ENABLED,
+            toast_secs: ESCALATION_TOAST_AFTER_SECS,
+            dim_secs: ESCALATION_DIM_AFTER_SECS,
+            blur_secs: ESCALATION_BLUR_AFTER_SECS,
+            block_secs: ESCALATION_BLOCK_AFTER_SECS,
+        }
+    }
+}
+
+impl EscalationLadder {
+    /// The most severe step reached after slouching for `slouching_for` (None = not slouching)
+    pub fn severity(&self, slouching_for: Option<Duration>) -> Option<Severity> {
+        let slouching_for = slouching_for?;
+        [
+            (Severity::Block, self.block_secs),
+            (Severity::Blur, Some(self.blur_secs)),
+            (Severity::Dim, Some(self.dim_secs)),
+            (Severity::Toast, Some(self.toast_secs)),
+        ]
+        .into_iter()
+        .find(|(_, after)| after.is_some_and(|secs| slouching_for >= Duration::from_secs(secs)))
+        .map(|(severity, _)| severity)
+    }
+}
+
+/// Follows the ladder frame by frame
+#[derive(Debug, Clone, Default)]
+pub struct Escalation {
+    severity: Option<Severity>,
+}
+
+impl Escalation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed once per frame with the engine's slouch duration. Returns the step just climbed to,
+    /// if any; correcting posture drops straight back to none.
+    pub fn update(&mut self, ladder: &EscalationLadder, slouching_for: Option<Duration>) -> Option<Severity> {
+        let severity = ladder.severity(slouching_for);
+        let previous = std::mem::replace(&mut self.severity, severity);
+        severity.filter(|_| severity > previous)
+    }
+
+    pub fn severity(&self) -> Option<Severity> {
+        self.severity
+    }
+
+    /// Back to the bottom of the ladder (after a pause, camera switch, ...)
+    pub fn reset(&mut self) {
+        self.severity = None;
+    }
+}
@@ -0,0 +1,128 @@
+/// Guided neck exercises, counted from keypoint motion instead of taken on trust
+///
+/// A chin tuck pulls the head straight back, so the eyes get closer together in the image, then
+/// returns; a neck roll turns the nose past the shoulders' center to both sides and back. With
+/// EXERCISE_TO_CLEAR_ALERT an escalated alert stays up until one of them has been done.
+
+use crate::config::{CHIN_TUCK_MIN_SHRINK, NECK_ROLL_MIN_TURN};
+use crate::pose::{Pose, LEFT_EYE, LEFT_SHOULDER, NOSE, RIGHT_EYE, RIGHT_SHOULDER};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exercise {
+    ChinTuck,
+    NeckRoll,
+}
+
+impl Exercise {
+    /// Stable name, also the suffix of the exercise's locale keys
+    pub fn key(self) -> &'static str {
+        match self {
+            Exercise::ChinTuck => "chin_tuck",
+            Exercise::NeckRoll => "neck_roll",
+        }
+    }
+
+    /// The other exercise, so consecutive alerts alternate
+    pub fn next(self) -> Self {
+        match self {
+            Exercise::ChinTuck => Exercise::NeckRoll,
+            Exercise::NeckRoll => Exercise::ChinTuck,
+        }
+    }
+
+    /// The figure the exercise moves: eye spacing for a chin tuck, the nose's offset from the
+    /// shoulders' center (in shoulder widths) for a neck roll. None without the keypoints.
+    fn measure(self, pose: &Pose) -> Option<f32> {
+        let confident = |index: usize| Some(pose[index]).filter(|kp| kp.is_confident());
+        match self {
+            Exercise::ChinTuck => {
+                let (left, right) = (confident(LEFT_EYE)?, confident(RIGHT_EYE)?);
+                Some((left.x - right.x).hypot(left.y - right.y)).filter(|spacing| *spacing > 0.0)
+            }
+            Exercise::NeckRoll => {
+                let (nose, left, right) = (confident(NOSE)?, confident(LEFT_SHOULDER)?, confident(RIGHT_SHOULDER)?);
+                let width = (left.x - right.x).abs();
+                (width > 0.0).then(|| (nose.x - (left.x + right.x) / 2.0) / width)
+            }
+        }
+    }
+}
+
+/// One exercise in progress: counts repetitions until `reps_needed` are done
+#[derive(Debug, Clone)]
+pub struct ExerciseSession {
+    exercise: Exercise,
+    reps_needed: u32,
+    reps: u32,
+    /// The first measurement, taken in the normal head position
+    reference: Option<f32>,
+    /// Chin tuck: tucked far enough. Neck roll: turned far enough to one side...
+    reached_first: bool,
+    /// ...and to the other (neck roll only)
+    reached_second: bool,
+}
+
+impl ExerciseSession {
+    pub fn new(exercise: Exercise, reps_needed: u32) -> Self {
+        Self {
+            exercise,
+            reps_needed: reps_needed.max(1),
+            reps: 0,
+            reference: None,
+            reached_first: false,
+            reached_second: false,
+        }
+    }
+
+    pub fn exercise(&self) -> Exercise {
+        self.exercise
+    }
+
+    pub fn reps(&self) -> u32 {
+        self.reps
+    }
+
+    pub fn reps_needed(&self) -> u32 {
+        self.reps_needed
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.reps >= self.reps_needed
+    }
+
+    /// Feeds one frame's pose (None when nobody is in view). Returns true when this frame
+    /// finished a repetition.
+    pub fn update(&mut self, pose: Option<&Pose>) -> bool {
+        if self.is_complete() {
+            return false;
+        }
+        let Some(value) = pose.and_then(|pose| self.exercise.measure(pose)) else { return false };
+        let reference = *self.reference.get_or_insert(value);
+
+        // A repetition counts once the head is back within half the movement of where it started
+        let back_at_rest = match self.exercise {
+            Exercise::ChinTuck => {
+                let shrink = (reference - value) / reference;
+                self.reached_first |= shrink >= CHIN_TUCK_MIN_SHRINK;
+                shrink <= CHIN_TUCK_MIN_SHRINK / 2.0
+            }
+            Exercise::NeckRoll => {
+                let turn = value - reference;
+                self.reached_first |= turn <= -NECK_ROLL_MIN_TURN;
+                self.reached_second |= turn >= NECK_ROLL_MIN_TURN;
+                turn.abs() <= NECK_ROLL_MIN_TURN / 2.0
+            }
+        };
+        let moved = match self.exercise {
+            Exercise::ChinTuck => self.reached_first,
+            Exercise::NeckRoll => self.reached_first && self.reached_second,
+        };
+        if moved && back_at_rest {
+            self.reps += 1;
+            self.reached_first = false;
+            self.reached_second = false;
+            return true;
+        }
+        false
+    }
+}
@@ -0,0 +1,170 @@
+/// Keypoint smoothing before the posture decision: the raw per-frame eye position jitters by
+/// a few pixels, which otherwise makes the status flap when sitting near the threshold
+
+use serde::{Deserialize, Serialize};
+
+use crate::pose::{Pose, KEYPOINT_COUNT};
+
+/// How keypoint positions are smoothed (applied per frame)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Smoothing {
+    /// Raw model output
+    None,
+    /// Exponential moving average; `alpha` is the weight of the newest frame (0.0-1.0]
+    Ema { alpha: f32 },
+    /// Constant-position 1D Kalman filter per coordinate. A larger `process_noise` follows
+    /// real movement faster; a larger `measurement_noise` smooths harder.
+    Kalman { process_noise: f32, measurement_noise: f32 },
+}
+
+impl Smoothing {
+    /// Problem with the parameters, if any
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            Smoothing::None => Ok(()),
+            Smoothing::Ema { alpha } if alpha > 0.0 && alpha <= 1.0 => Ok(()),
+            Smoothing::Ema { alpha } => Err(format!("EMA alpha must be in (0, 1], not {}", alpha)),
+            Smoothing::Kalman { process_noise, measurement_noise } if process_noise > 0.0 && measurement_noise > 0.0 => Ok(()),
+            Smoothing::Kalman { .. } => Err("Kalman noise values must be positive".to_string()),
+        }
+    }
+}
+
+/// Exponential moving average of one value
+#[derive(Debug, Clone)]
+pub struct Ema {
+    alpha: f32,
+    value: Option<f32>,
+}
+
+impl Ema {
+    pub fn new(alpha: f32) -> Self {
+        Self { alpha: alpha.clamp(f32::EPSILON, 1.0), value: None }
+    }
+
+    pub fn update(&mut self, measurement: f32) -> f32 {
+        let value = match self.value {
+            Some(value) => value + self.alpha * (measurement - value),
+            None => measurement,
+        };
+        self.value = Some(value);
+        value
+    }
+
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+/// 1D Kalman filter for a value that is expected to stay put between frames
+#[derive(Debug, Clone)]
+pub struct Kalman {
+    process_noise: f32,
+    measurement_noise: f32,
+    estimate: Option<f32>,
+    error: f32,
+}
+
+impl Kalman {
+    pub fn new(process_noise: f32, measurement_noise: f32) -> Self {
+        Self {
+            process_noise: process_noise.max(f32::EPSILON),
+            measurement_noise: measurement_noise.max(f32::EPSILON),
+            estimate: None,
+            error: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, measurement: f32) -> f32 {
+        let Some(estimate) = self.estimate else {
+            // Trust the first measurement as much as any later one
+            self.estimate = Some(measurement);
+            self.error = self.measurement_noise;
+            return measurement;
+        };
+
+        let predicted_error = self.error + self.process_noise;
+        let gain = predicted_error / (predicted_error + self.measurement_noise);
+        let estimate = estimate + gain * (measurement - estimate);
+        self.estimate = Some(estimate);
+        self.error = (1.0 - gain) * predicted_error;
+        estimate
+    }
+
+    pub fn reset(&mut self) {
+        self.estimate = None;
+        self.error = 0.0;
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Scalar {
+    Raw,
+    Ema(Ema),
+    Kalman(Kalman),
+}
+
+impl Scalar {
+    fn new(smoothing: Smoothing) -> Self {
+        match smoothing {
+            Smoothing::None => Scalar::Raw,
+            Smoothing::Ema { alpha } => Scalar::Ema(Ema::new(alpha)),
+            Smoothing::Kalman { process_noise, measurement_noise } => Scalar::Kalman(Kalman::new(process_noise, measurement_noise)),
+        }
+    }
+
+    fn update(&mut self, measurement: f32) -> f32 {
+        match self {
+            Scalar::Raw => measurement,
+            Scalar::Ema(ema) => ema.update(measurement),
+            Scalar::Kalman(kalman) => kalman.update(measurement),
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Scalar::Raw => {}
+            Scalar::Ema(ema) => ema.reset(),
+            Scalar::Kalman(kalman) => kalman.reset(),
+        }
+    }
+}
+
+/// Smooths x and y of every keypoint; scores pass through unchanged
+pub struct PoseFilter {
+    coordinates: Vec<[Scalar; 2]>,
+}
+
+impl PoseFilter {
+    pub fn new(smoothing: Smoothing) -> Self {
+        let coordinates = (0..KEYPOINT_COUNT).map(|_| [Scalar::new(smoothing), Scalar::new(smoothing)]).collect();
+        Self { coordinates }
+    }
+
+    pub fn reset(&mut self) {
+        self.coordinates.iter_mut().flatten().for_each(Scalar::reset);
+    }
+
+    /// The smoothed pose for this frame. Only confident keypoints are filtered; one that
+    /// drops out (or a frame without anybody) restarts its filter, so it doesn't glide in
+    /// from a stale position when it's found again.
+    pub fn apply(&mut self, pose: Option<&Pose>) -> Option<Pose> {
+        let Some(pose) = pose else {
+            self.reset();
+            return None;
+        };
+
+        let mut smoothed = *pose;
+        for (keypoint, [x, y]) in smoothed.iter_mut().zip(&mut self.coordinates) {
+            if keypoint.is_confident() {
+                keypoint.x = x.update(keypoint.x);
+                keypoint.y = y.update(keypoint.y);
+            } else {
+                x.reset();
+                y.reset();
+            }
+        }
+        Some(smoothed)
+    }
+}
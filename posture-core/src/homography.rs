@@ -0,0 +1,228 @@
+/// Perspective correction for cameras mounted well above or beside the monitor
+///
+/// The user marks the corners of something rectangular in the camera view (a door frame,
+/// a poster, a sheet of paper on the wall behind them). The homography that turns those
+/// four points back into a rectangle is applied to every keypoint before thresholding,
+/// so "the head dropped" is measured as if the camera were facing the user.
+
+use std::fs;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::app_data;
+use crate::pose::{Flip, Keypoint, Pose};
+
+const MAPPING_FILE: &str = "camera_mapping.json";
+
+/// Projective transform in normalized frame coordinates (row-major 3x3, h[8] = 1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Homography {
+    h: [f64; 9],
+}
+
+/// Solves `a * x = b` in place (Gaussian elimination with partial pivoting)
+fn solve<const N: usize>(mut a: [[f64; N]; N], mut b: [f64; N]) -> Option<[f64; N]> {
+    for col in 0..N {
+        let pivot = (col..N).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        for row in col + 1..N {
+            let factor = a[row][col] / a[col][col];
+            let pivot_row = a[col];
+            for (value, pivot) in a[row].iter_mut().zip(pivot_row).skip(col) {
+                *value -= factor * pivot;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = [0.0; N];
+    for row in (0..N).rev() {
+        let sum: f64 = (row + 1..N).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+impl Homography {
+    pub fn identity() -> Self {
+        Self { h: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0] }
+    }
+
+    /// The transform taking each `from` point onto the matching `to` point.
+    /// None if three of the points are (nearly) collinear.
+    pub fn from_points(from: &[(f32, f32); 4], to: &[(f32, f32); 4]) -> Option<Self> {
+        let mut a = [[0.0; 8]; 8];
+        let mut b = [0.0; 8];
+        for (i, (&(x, y), &(u, v))) in from.iter().zip(to).enumerate() {
+            let (x, y, u, v) = (x as f64, y as f64, u as f64, v as f64);
+            a[2 * i] = [x, y, 1.0, 0.0, 0.0, 0.0, -u * x, -u * y];
+            a[2 * i + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -v * x, -v * y];
+            b[2 * i] = u;
+            b[2 * i + 1] = v;
+        }
+        let s = solve(a, b)?;
+        Some(Self { h: [s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7], 1.0] })
+    }
+
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let h = &self.h;
+        let (x, y) = (x as f64, y as f64);
+        let w = h[6] * x + h[7] * y + h[8];
+        (
+            ((h[0] * x + h[1] * y + h[2]) / w) as f32,
+            ((h[3] * x + h[4] * y + h[5]) / w) as f32,
+        )
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        let [a, b, c, d, e, f, g, h, i] = self.h;
+        let det = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let inv = [
+            (e * i - f * h) / det,
+            (c * h - b * i) / det,
+            (b * f - c * e) / det,
+            (f * g - d * i) / det,
+            (a * i - c * g) / det,
+            (c * d - a * f) / det,
+            (d * h - e * g) / det,
+            (b * g - a * h) / det,
+            (a * e - b * d) / det,
+        ];
+        Some(Self { h: inv.map(|v| v / inv[8]) })
+    }
+
+    pub fn map_keypoint(&self, keypoint: &Keypoint) -> Keypoint {
+        let (x, y) = self.apply(keypoint.x, keypoint.y);
+        Keypoint { x, y, score: keypoint.score }
+    }
+
+    pub fn map_pose(&self, pose: &Pose) -> Pose {
+        pose.map(|keypoint| self.map_keypoint(&keypoint))
+    }
+}
+
+/// Reference rectangle marked by the user, saved per camera setup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraMapping {
+    /// Marked corners in normalized frame coordinates: top-left, top-right, bottom-right, bottom-left
+    pub corners: [(f32, f32); 4],
+    pub camera_index: u32,
+    pub rotation_degrees: u32,
+    /// Flips applied to the frame the corners were marked in
+    #[serde(default)]
+    pub flip: Flip,
+}
+
+impl CameraMapping {
+    /// Builds a mapping from the marked corners. Fails if they don't form a usable quadrilateral.
+    pub fn new(corners: [(f32, f32); 4], camera_index: u32, rotation_degrees: u32, flip: Flip) -> Result<Self> {
+        let mapping = Self { corners, camera_index, rotation_degrees, flip };
+        mapping.homography().ok_or_else(|| anyhow!("the marked points don't form a rectangle outline"))?;
+        Ok(mapping)
+    }
+
+    /// Maps the marked corners onto an upright rectangle of the same average size and center,
+    /// so corrected keypoints stay in the usual range and thresholds keep their meaning
+    pub fn homography(&self) -> Option<Homography> {
+        let [tl, tr, br, bl] = self.corners;
+        let distance = |a: (f32, f32), b: (f32, f32)| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+        let width = (distance(tl, tr) + distance(bl, br)) / 2.0;
+        let height = (distance(tl, bl) + distance(tr, br)) / 2.0;
+        if width < 0.02 || height < 0.02 {
+            return None;
+        }
+        let cx = self.corners.iter().map(|p| p.0).sum::<f32>() / 4.0;
+        let cy = self.corners.iter().map(|p| p.1).sum::<f32>() / 4.0;
+        let (left, right) = (cx - width / 2.0, cx + width / 2.0);
+        let (top, bottom) = (cy - height / 2.0, cy + height / 2.0);
+
+        Homography::from_points(&self.corners, &[(left, top), (right, top), (right, bottom), (left, bottom)])
+    }
+
+    /// The same rectangle as seen in frames flipped by `flip`; the corners keep their
+    /// top-left, top-right, bottom-right, bottom-left order
+    pub fn reflipped(self, flip: Flip) -> Self {
+        let change = flip.relative_to(self.flip);
+        let [tl, tr, br, bl] = self.corners.map(|corner| change.point(corner));
+        let corners = match (change.horizontal, change.vertical) {
+            (false, false) => [tl, tr, br, bl],
+            (true, false) => [tr, tl, bl, br],
+            (false, true) => [bl, br, tr, tl],
+            (true, true) => [br, bl, tl, tr],
+        };
+        Self { corners, flip, ..self }
+    }
+
+    /// Loads the saved mapping if it was made with the current camera and rotation, flipped
+    /// to match the current flips
+    pub fn load(camera_index: u32, rotation_degrees: u32, flip: Flip) -> Option<Self> {
+        let path = app_data::file_path(MAPPING_FILE).ok()?;
+        let json = fs::read_to_string(path).ok()?;
+        let mapping: Self = match serde_json::from_str(&json) {
+            Ok(mapping) => mapping,
+            Err(e) => {
+                warn!("Ignoring unreadable camera mapping: {}", e);
+                return None;
+            }
+        };
+        (mapping.camera_index == camera_index && mapping.rotation_degrees == rotation_degrees)
+            .then(|| mapping.reflipped(flip))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = app_data::file_path(MAPPING_FILE)?;
+        app_data::write_atomic(&path, serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    /// Deletes the saved mapping file
+    pub fn clear() -> Result<()> {
+        let path = app_data::file_path(MAPPING_FILE)?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects the four corner clicks in the debug window
+#[derive(Debug, Default)]
+pub struct ReferenceMarking {
+    corners: Vec<(f32, f32)>,
+}
+
+impl ReferenceMarking {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Locale key for the corner to click next
+    pub fn prompt(&self) -> &'static str {
+        match self.corners.len() {
+            0 => "mapping.click_top_left",
+            1 => "mapping.click_top_right",
+            2 => "mapping.click_bottom_right",
+            _ => "mapping.click_bottom_left",
+        }
+    }
+
+    pub fn marked(&self) -> &[(f32, f32)] {
+        &self.corners
+    }
+
+    /// Adds a click (normalized coordinates). Returns all four corners once complete.
+    pub fn add(&mut self, point: (f32, f32)) -> Option<[(f32, f32); 4]> {
+        self.corners.push(point);
+        self.corners.as_slice().try_into().ok()
+    }
+}
@@ -0,0 +1,103 @@
+/// Adaptive inference rate: the model runs on every frame while posture is near the threshold
+/// or just changed, and only every INFERENCE_MAX_INTERVAL_MS once the user has sat calmly
+/// upright for INFERENCE_STABLE_SECS, which saves most of the CPU time in the common case
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::clock::SharedClock;
+use crate::config::{
+    ADAPTIVE_INFERENCE_ENABLED, INFERENCE_MAX_INTERVAL_MS, INFERENCE_MIN_INTERVAL_MS, INFERENCE_NEAR_THRESHOLD_PX,
+    INFERENCE_STABLE_SECS,
+};
+use crate::engine::PostureStatus;
+
+// Window for the measured rate shown in the debug window
+const RATE_WINDOW: Duration = Duration::from_secs(2);
+
+pub struct InferenceRate {
+    clock: SharedClock,
+    min_interval: Duration,
+    max_interval: Duration,
+    last_run: Option<Instant>,
+    last_status: Option<PostureStatus>,
+    /// Start of the current calm stretch (upright and well clear of the threshold)
+    calm_since: Option<Instant>,
+    recent_runs: VecDeque<Instant>,
+}
+
+impl InferenceRate {
+    pub fn new(clock: SharedClock) -> Self {
+        let max_interval = if ADAPTIVE_INFERENCE_ENABLED { INFERENCE_MAX_INTERVAL_MS } else { INFERENCE_MIN_INTERVAL_MS };
+        Self::with_intervals(clock, Duration::from_millis(INFERENCE_MIN_INTERVAL_MS), Duration::from_millis(max_interval))
+    }
+
+    pub fn with_intervals(clock: SharedClock, min_interval: Duration, max_interval: Duration) -> Self {
+        Self {
+            clock,
+            min_interval,
+            max_interval: max_interval.max(min_interval),
+            last_run: None,
+            last_status: None,
+            calm_since: None,
+            recent_runs: VecDeque::new(),
+        }
+    }
+
+    /// Time between inferences right now
+    pub fn interval(&self) -> Duration {
+        // Nobody to watch: only check now and then whether someone is back
+        if self.last_status == Some(PostureStatus::Away) {
+            return self.max_interval;
+        }
+        let stable = self.calm_since
+            .is_some_and(|since| self.clock.now().saturating_duration_since(since) >= Duration::from_secs(INFERENCE_STABLE_SECS));
+        if stable {
+            self.max_interval
+        } else {
+            self.min_interval
+        }
+    }
+
+    /// Whether to run the model on this frame; records the run when it is
+    pub fn start_if_due(&mut self) -> bool {
+        let now = self.clock.now();
+        if self.last_run.is_some_and(|last| now.saturating_duration_since(last) < self.interval()) {
+            return false;
+        }
+        self.last_run = Some(now);
+        self.recent_runs.push_back(now);
+        while self.recent_runs.front().is_some_and(|run| now.saturating_duration_since(*run) > RATE_WINDOW) {
+            self.recent_runs.pop_front();
+        }
+        true
+    }
+
+    /// Call after each inference. `headroom_px` is how far the eyes are from the slouch
+    /// threshold (None without a judged pose).
+    pub fn observe(&mut self, status: PostureStatus, headroom_px: Option<f32>) {
+        let clear_of_threshold = match headroom_px {
+            Some(headroom) => headroom >= INFERENCE_NEAR_THRESHOLD_PX,
+            None => true,
+        };
+        let calm = matches!(status, PostureStatus::Good | PostureStatus::Approaching) && clear_of_threshold;
+        let changed = self.last_status.is_some_and(|last| last != status);
+        self.last_status = Some(status);
+
+        if !calm || changed {
+            self.calm_since = None;
+        } else if self.calm_since.is_none() {
+            self.calm_since = Some(self.clock.now());
+        }
+    }
+
+    /// Back to full rate (after a pause, while positioning the camera, ...)
+    pub fn wake(&mut self) {
+        self.calm_since = None;
+    }
+
+    /// Inferences per second over the last couple of seconds
+    pub fn measured_per_sec(&self) -> f32 {
+        self.recent_runs.len() as f32 / RATE_WINDOW.as_secs_f32()
+    }
+}
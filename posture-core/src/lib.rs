@@ -0,0 +1,20 @@
+/// Posture detection core: turns per-frame keypoints into posture decisions, alerts and
+/// statistics, with no camera, model, window or tray code, so other frontends can reuse it
+
+pub mod config;
+pub mod clock;
+pub mod app_data;
+pub mod local_time;
+pub mod pose;
+pub mod filter;
+pub mod quality;
+pub mod homography;
+pub mod calibration;
+pub mod engine;
+pub mod exercise;
+pub mod stance;
+pub mod motion;
+pub mod inference_rate;
+pub mod alert_sinks;
+pub mod escalation;
+pub mod stats;
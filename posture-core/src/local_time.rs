@@ -0,0 +1,122 @@
+/// Local wall-clock time for daily log files and timestamps users read
+///
+/// Read from Windows; other platforms get UTC until they have a time zone source.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(windows)]
+use windows::Win32::System::SystemInformation::GetLocalTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalTime {
+    pub year: u16,
+    pub month: u16,
+    pub day: u16,
+    pub hour: u16,
+    pub minute: u16,
+    pub second: u16,
+}
+
+impl LocalTime {
+    #[cfg(windows)]
+    pub fn now() -> Self {
+        let st = unsafe { GetLocalTime() };
+        Self {
+            year: st.wYear,
+            month: st.wMonth,
+            day: st.wDay,
+            hour: st.wHour,
+            minute: st.wMinute,
+            second: st.wSecond,
+        }
+    }
+
+    #[cfg(not(windows))]
+    pub fn now() -> Self {
+        Self::from_unix_secs((unix_millis() / 1000) as i64, 0)
+    }
+
+    /// Local time of a Unix timestamp, given the UTC offset in effect
+    pub fn from_unix_secs(unix_secs: i64, utc_offset_secs: i64) -> Self {
+        let local = unix_secs + utc_offset_secs;
+        let days = local.div_euclid(86_400);
+        let secs_of_day = local.rem_euclid(86_400);
+
+        // Days since 1970-01-01 to a civil date (proleptic Gregorian, H. Hinnant's algorithm)
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year: year as u16,
+            month: month as u16,
+            day: day as u16,
+            hour: (secs_of_day / 3600) as u16,
+            minute: (secs_of_day % 3600 / 60) as u16,
+            second: (secs_of_day % 60) as u16,
+        }
+    }
+
+    /// "2024-05-31" - used in daily file names
+    pub fn date_string(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+
+    /// Midnight of a "2024-05-31" date
+    pub fn parse_date(text: &str) -> Option<Self> {
+        let mut parts = text.trim().splitn(3, '-').map(|part| part.parse::<u16>().ok());
+        let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        Some(Self { year, month, day, hour: 0, minute: 0, second: 0 })
+    }
+
+    /// Days since 1970-01-01 (the inverse of the date part of `from_unix_secs`)
+    pub fn day_number(&self) -> i64 {
+        let (month, day) = (self.month as i64, self.day as i64);
+        let year = self.year as i64 - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// 0 = Monday ... 6 = Sunday
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday
+        (self.day_number() + 3).rem_euclid(7) as u32
+    }
+}
+
+/// Local time minus UTC in seconds (includes daylight saving)
+pub fn utc_offset_secs() -> i64 {
+    let local = LocalTime::now();
+    let local_secs = local.hour as i64 * 3600 + local.minute as i64 * 60 + local.second as i64;
+    let utc_secs = (unix_millis() / 1000 % 86_400) as i64;
+
+    // The two clocks can sit on different dates; offsets range from -12 h to +14 h
+    let offset = local_secs - utc_secs;
+    if offset > 14 * 3600 {
+        offset - 86_400
+    } else if offset < -12 * 3600 {
+        offset + 86_400
+    } else {
+        offset
+    }
+}
+
+/// Milliseconds since the Unix epoch (UTC), for machine-readable records
+pub fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,82 @@
+/// Movement detection: large, fast keypoint motion (leaning over to pick something up, turning to
+/// talk to someone) isn't a posture. While the head and shoulders move faster than
+/// MOTION_SPEED_PX_PER_SEC, and for MOTION_SETTLE_MS after, slouching isn't counted, so only a
+/// slouch held still is enforced.
+///
+/// Speed is measured over at least MOTION_WINDOW_MS rather than frame to frame, so keypoint jitter
+/// at a high frame rate doesn't add up to movement.
+
+use std::time::{Duration, Instant};
+
+use crate::config::{HEIGHT, MOTION_SETTLE_MS, MOTION_SPEED_PX_PER_SEC, MOTION_WINDOW_MS, WIDTH};
+use crate::pose::{Pose, LEFT_EYE, LEFT_SHOULDER, NOSE, RIGHT_EYE, RIGHT_SHOULDER};
+
+/// Keypoints whose motion counts
+const MOVING_KEYPOINTS: [usize; 5] = [NOSE, LEFT_EYE, RIGHT_EYE, LEFT_SHOULDER, RIGHT_SHOULDER];
+
+/// Positions of MOVING_KEYPOINTS in preview pixels, None where not confident
+type Positions = [Option<(f32, f32)>; MOVING_KEYPOINTS.len()];
+
+fn positions(pose: &Pose) -> Positions {
+    MOVING_KEYPOINTS.map(|index| {
+        let keypoint = pose[index];
+        keypoint.is_confident().then_some((keypoint.x * WIDTH as f32, keypoint.y * HEIGHT as f32))
+    })
+}
+
+/// Average distance in preview pixels the keypoints seen in both moved, or None without any
+pub fn mean_displacement(from: &Pose, to: &Pose) -> Option<f32> {
+    let distances: Vec<f32> = positions(from)
+        .into_iter()
+        .zip(positions(to))
+        .filter_map(|(from, to)| Some((from?, to?)))
+        .map(|((x0, y0), (x1, y1))| (x1 - x0).hypot(y1 - y0))
+        .collect();
+    (!distances.is_empty()).then(|| distances.iter().sum::<f32>() / distances.len() as f32)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MotionDetector {
+    /// The pose the next speed is measured from
+    reference: Option<(Instant, Pose)>,
+    /// When the last fast movement was seen
+    last_moved: Option<Instant>,
+    moving: bool,
+}
+
+impl MotionDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a frame with someone in view. Returns whether the user counts as moving.
+    pub fn update(&mut self, now: Instant, pose: &Pose) -> bool {
+        match self.reference {
+            Some((since, reference)) => {
+                let elapsed = now.saturating_duration_since(since);
+                if elapsed >= Duration::from_millis(MOTION_WINDOW_MS) {
+                    // Keypoints that came or went say nothing; the speed waits for the next window
+                    if let Some(distance) = mean_displacement(&reference, pose) {
+                        if distance / elapsed.as_secs_f32() > MOTION_SPEED_PX_PER_SEC {
+                            self.last_moved = Some(now);
+                        }
+                    }
+                    self.reference = Some((now, *pose));
+                }
+            }
+            None => self.reference = Some((now, *pose)),
+        }
+        self.moving = self.last_moved
+            .is_some_and(|moved| now.saturating_duration_since(moved) < Duration::from_millis(MOTION_SETTLE_MS));
+        self.moving
+    }
+
+    /// Forgets the movement so far (nobody in view, a new calibration, ...)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.moving
+    }
+}
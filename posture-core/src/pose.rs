@@ -0,0 +1,105 @@
+/// MoveNet keypoint definitions and output parsing
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::KEYPOINT_CONFIDENCE;
+
+pub const KEYPOINT_COUNT: usize = 17;
+
+// MoveNet keypoint indices (COCO order)
+pub const NOSE: usize = 0;
+pub const LEFT_EYE: usize = 1;
+pub const RIGHT_EYE: usize = 2;
+pub const LEFT_EAR: usize = 3;
+pub const RIGHT_EAR: usize = 4;
+pub const LEFT_SHOULDER: usize = 5;
+pub const RIGHT_SHOULDER: usize = 6;
+pub const LEFT_ELBOW: usize = 7;
+pub const RIGHT_ELBOW: usize = 8;
+pub const LEFT_WRIST: usize = 9;
+pub const RIGHT_WRIST: usize = 10;
+pub const LEFT_HIP: usize = 11;
+pub const RIGHT_HIP: usize = 12;
+pub const LEFT_KNEE: usize = 13;
+pub const RIGHT_KNEE: usize = 14;
+pub const LEFT_ANKLE: usize = 15;
+pub const RIGHT_ANKLE: usize = 16;
+
+/// Keypoint names by index, as COCO spells them
+pub const KEYPOINT_NAMES: [&str; KEYPOINT_COUNT] = [
+    "nose", "left_eye", "right_eye", "left_ear", "right_ear", "left_shoulder", "right_shoulder", "left_elbow",
+    "right_elbow", "left_wrist", "right_wrist", "left_hip", "right_hip", "left_knee", "right_knee", "left_ankle",
+    "right_ankle",
+];
+
+/// A single keypoint in normalized frame coordinates (0.0..1.0)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Keypoint {
+    pub x: f32,
+    pub y: f32,
+    pub score: f32,
+}
+
+impl Keypoint {
+    pub fn is_confident(&self) -> bool {
+        self.score > KEYPOINT_CONFIDENCE
+    }
+}
+
+pub type Pose = [Keypoint; KEYPOINT_COUNT];
+
+/// Horizontal (mirror) and vertical flips applied to camera frames after rotating them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Flip {
+    pub horizontal: bool,
+    pub vertical: bool,
+}
+
+impl Flip {
+    /// The flip that turns frames flipped by `from` into frames flipped by `self`
+    pub fn relative_to(self, from: Flip) -> Flip {
+        Flip { horizontal: self.horizontal != from.horizontal, vertical: self.vertical != from.vertical }
+    }
+
+    /// Where a normalized point lands in the flipped frame (flipping twice undoes it)
+    pub fn point(self, (x, y): (f32, f32)) -> (f32, f32) {
+        (if self.horizontal { 1.0 - x } else { x }, if self.vertical { 1.0 - y } else { y })
+    }
+
+    /// Keypoints as they'd be found in the flipped frame. A single flip mirrors the body, so
+    /// left and right swap, the way the model labels a mirrored person.
+    pub fn keypoints(self, keypoints: &[Keypoint]) -> Vec<Keypoint> {
+        let swap = self.horizontal != self.vertical;
+        (0..keypoints.len())
+            .map(|i| {
+                // COCO pairs: odd indices are left, the next one is the matching right
+                let source = match i {
+                    NOSE => i,
+                    _ if !swap => i,
+                    _ if i % 2 == 1 => i + 1,
+                    _ => i - 1,
+                };
+                let keypoint = keypoints.get(source).unwrap_or(&keypoints[i]);
+                let (x, y) = self.point((keypoint.x, keypoint.y));
+                Keypoint { x, y, score: keypoint.score }
+            })
+            .collect()
+    }
+}
+
+/// Parses the MoveNet SinglePose output tensor ([1, 1, 17, 3] as y, x, score)
+pub fn parse_movenet_output(data: &[f32]) -> Option<Pose> {
+    if data.len() < KEYPOINT_COUNT * 3 {
+        return None;
+    }
+
+    let mut pose = [Keypoint::default(); KEYPOINT_COUNT];
+    for (i, keypoint) in pose.iter_mut().enumerate() {
+        let base_idx = i * 3;
+        keypoint.y = data[base_idx];
+        keypoint.x = data[base_idx + 1];
+        keypoint.score = data[base_idx + 2];
+    }
+    Some(pose)
+}
@@ -0,0 +1,78 @@
+/// Tracking quality: how confidently the keypoints the posture checks rely on were detected over
+/// the last few seconds. A single frame passing KEYPOINT_CONFIDENCE says little in a dim room or
+/// with a hand in front of the face; while the weighted average is poor, posture isn't judged,
+/// so unreliable tracking never ends in an alert.
+
+use std::time::Instant;
+
+use crate::config::{TRACKING_GOOD_ABOVE, TRACKING_POOR_BELOW, TRACKING_QUALITY_SECS};
+use crate::pose::{Pose, LEFT_EAR, LEFT_EYE, LEFT_SHOULDER, NOSE, RIGHT_EAR, RIGHT_EYE, RIGHT_SHOULDER};
+
+/// The keypoints the checks use, with their weight; the right eye carries the eye drop
+pub const TRACKED_KEYPOINTS: [(usize, f32); 7] = [
+    (RIGHT_EYE, 3.0),
+    (LEFT_EYE, 1.0),
+    (NOSE, 1.0),
+    (LEFT_EAR, 0.5),
+    (RIGHT_EAR, 0.5),
+    (LEFT_SHOULDER, 1.0),
+    (RIGHT_SHOULDER, 1.0),
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct TrackingQuality {
+    /// Averaged score per entry of TRACKED_KEYPOINTS
+    scores: Option<[f32; TRACKED_KEYPOINTS.len()]>,
+    last_update: Option<Instant>,
+    poor: bool,
+}
+
+impl TrackingQuality {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a frame with someone in view. Returns the new state when tracking just turned
+    /// poor (true) or recovered (false).
+    pub fn update(&mut self, now: Instant, pose: &Pose) -> Option<bool> {
+        let current = TRACKED_KEYPOINTS.map(|(index, _)| pose[index].score);
+        // Time-based, so the adaptive inference rate doesn't change how fast it reacts
+        let weight = match self.last_update {
+            Some(last) => {
+                let elapsed = now.saturating_duration_since(last).as_secs_f32();
+                1.0 - (-elapsed / TRACKING_QUALITY_SECS.max(f32::EPSILON)).exp()
+            }
+            None => 1.0,
+        };
+        self.last_update = Some(now);
+        let scores = self.scores.get_or_insert(current);
+        for (score, current) in scores.iter_mut().zip(current) {
+            *score += (current - *score) * weight;
+        }
+
+        let score = self.score();
+        let poor = if self.poor { score < TRACKING_GOOD_ABOVE } else { score < TRACKING_POOR_BELOW };
+        (poor != self.poor).then(|| {
+            self.poor = poor;
+            poor
+        })
+    }
+
+    /// Weighted average confidence, 0.0 to 1.0 (1.0 before the first frame)
+    pub fn score(&self) -> f32 {
+        let Some(scores) = self.scores else { return 1.0 };
+        let total: f32 = TRACKED_KEYPOINTS.iter().map(|(_, weight)| weight).sum();
+        TRACKED_KEYPOINTS.iter().zip(scores).map(|((_, weight), score)| weight * score).sum::<f32>() / total
+    }
+
+    /// Averaged score of one keypoint, if it's tracked
+    pub fn keypoint_score(&self, index: usize) -> Option<f32> {
+        let position = TRACKED_KEYPOINTS.iter().position(|&(tracked, _)| tracked == index)?;
+        self.scores.map(|scores| scores[position])
+    }
+
+    /// Too unreliable to judge posture
+    pub fn is_poor(&self) -> bool {
+        self.poor
+    }
+}
@@ -0,0 +1,83 @@
+/// Standing detection: whether the user sits at the desk or stood up, for the sitting and
+/// standing time in the history
+///
+/// Standing shows as the eyes rising far above the calibrated height, or as the head leaving
+/// the top of the frame while the shoulders or hips are still in view. A new stance only
+/// counts once it has lasted STANDING_SWITCH_SECS, so leaning back or a stretch doesn't.
+
+use std::time::{Duration, Instant};
+
+use crate::config::{STANDING_RISE_PX, STANDING_SHOULDER_TOP, STANDING_SWITCH_SECS};
+use crate::engine::eye_y;
+use crate::pose::{Pose, LEFT_EAR, LEFT_EYE, LEFT_HIP, LEFT_SHOULDER, NOSE, RIGHT_EAR, RIGHT_EYE, RIGHT_HIP, RIGHT_SHOULDER};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Stance {
+    #[default]
+    Sitting,
+    Standing,
+}
+
+/// What one frame suggests, or None when it shows neither (e.g. only an arm in view).
+/// `baseline` is the calibrated eye height in preview pixels.
+pub fn frame_stance(pose: &Pose, baseline: Option<f32>) -> Option<Stance> {
+    if let Some(eye_y) = eye_y(pose) {
+        let risen = baseline.is_some_and(|baseline| baseline - eye_y >= STANDING_RISE_PX);
+        return Some(if risen { Stance::Standing } else { Stance::Sitting });
+    }
+
+    let face_visible = [NOSE, LEFT_EYE, RIGHT_EYE, LEFT_EAR, RIGHT_EAR].iter().any(|&i| pose[i].is_confident());
+    if face_visible {
+        return Some(Stance::Sitting);
+    }
+    // No face: the head is above the frame if the shoulders are near its top or the hips came into view
+    let shoulders_high = [LEFT_SHOULDER, RIGHT_SHOULDER]
+        .iter()
+        .any(|&i| pose[i].is_confident() && pose[i].y < STANDING_SHOULDER_TOP);
+    let hips_visible = [LEFT_HIP, RIGHT_HIP].iter().any(|&i| pose[i].is_confident());
+    (shoulders_high || hips_visible).then_some(Stance::Standing)
+}
+
+/// Debounces the per-frame stance
+#[derive(Debug, Clone, Default)]
+pub struct StanceDetector {
+    stance: Stance,
+    /// A different stance seen since then
+    candidate: Option<(Stance, Instant)>,
+}
+
+impl StanceDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a frame with someone in view; returns the new stance when it just changed
+    pub fn update(&mut self, now: Instant, pose: &Pose, baseline: Option<f32>) -> Option<Stance> {
+        let seen = frame_stance(pose, baseline)?;
+        if seen == self.stance {
+            self.candidate = None;
+            return None;
+        }
+        let since = match self.candidate {
+            Some((candidate, since)) if candidate == seen => since,
+            _ => {
+                self.candidate = Some((seen, now));
+                now
+            }
+        };
+        (now.saturating_duration_since(since) >= Duration::from_secs(STANDING_SWITCH_SECS)).then(|| {
+            self.stance = seen;
+            self.candidate = None;
+            seen
+        })
+    }
+
+    /// Back to sitting, e.g. after the user was away
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn stance(&self) -> Stance {
+        self.stance
+    }
+}
@@ -0,0 +1,104 @@
+/// Posture statistics: per-minute aggregates appended to a daily CSV in the app-data directory
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::app_data;
+use crate::clock::{Interval, SharedClock};
+use crate::engine::EngineStats;
+use crate::local_time::LocalTime;
+
+const CSV_HEADER: &str = "time,avg_delta_px,bad_posture_percent,overlay_triggers,frames";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MinuteStats {
+    /// Mean distance below the baseline (None if no frame could be judged)
+    pub avg_delta: Option<f32>,
+    /// Share of judged frames with bad posture, 0-100
+    pub bad_percent: f32,
+    pub overlay_triggers: u32,
+    /// Frames judged against a baseline
+    pub frames: u64,
+}
+
+/// Collects per-frame deltas and closes a `MinuteStats` once per minute
+pub struct StatsCollector {
+    minute: Interval,
+    delta_sum: f64,
+    delta_samples: u32,
+    minute_start: EngineStats,
+}
+
+impl StatsCollector {
+    pub fn new(clock: &SharedClock) -> Self {
+        Self {
+            minute: Interval::new(clock, Duration::from_secs(60)),
+            delta_sum: 0.0,
+            delta_samples: 0,
+            minute_start: EngineStats::default(),
+        }
+    }
+
+    /// Call for every frame judged against the baseline
+    pub fn add_delta(&mut self, delta: f32) {
+        self.delta_sum += delta as f64;
+        self.delta_samples += 1;
+    }
+
+    /// Call every frame with the engine's running totals. Returns the finished
+    /// minute, or None mid-minute and for minutes without any activity.
+    pub fn poll(&mut self, totals: EngineStats) -> Option<MinuteStats> {
+        if !self.minute.is_due() {
+            return None;
+        }
+
+        let good = totals.good_frames - self.minute_start.good_frames;
+        let bad = totals.bad_frames - self.minute_start.bad_frames;
+        let triggers = totals.alerts - self.minute_start.alerts;
+        let avg_delta = (self.delta_samples > 0).then(|| (self.delta_sum / self.delta_samples as f64) as f32);
+
+        self.minute_start = totals;
+        self.delta_sum = 0.0;
+        self.delta_samples = 0;
+
+        // Nothing to report while away, paused or calibrating
+        if good + bad == 0 && triggers == 0 {
+            return None;
+        }
+
+        Some(MinuteStats {
+            avg_delta,
+            bad_percent: if good + bad > 0 { bad as f32 * 100.0 / (good + bad) as f32 } else { 0.0 },
+            overlay_triggers: triggers,
+            frames: good + bad,
+        })
+    }
+}
+
+/// Appends one row to `stats/posture-YYYY-MM-DD.csv`, writing the header for a new file
+pub fn append_csv(minute: &MinuteStats) -> Result<()> {
+    let now = LocalTime::now();
+    let dir = app_data::dir()?.join("stats");
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("posture-{}.csv", now.date_string()));
+
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    if is_new {
+        writeln!(file, "{}", CSV_HEADER)?;
+    }
+    writeln!(
+        file,
+        "{:02}:{:02},{},{:.1},{},{}",
+        now.hour,
+        now.minute,
+        minute.avg_delta.map(|d| format!("{:.1}", d)).unwrap_or_default(),
+        minute.bad_percent,
+        minute.overlay_triggers,
+        minute.frames,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,24 @@
+//! Clock helpers: retry backoff on a manual clock.
+
+use std::time::Duration;
+
+use posture_core::clock::{Backoff, ManualClock};
+
+#[test]
+fn backoff_doubles_the_wait_up_to_the_cap() {
+    let clock = ManualClock::new();
+    let mut backoff = Backoff::new(&clock.shared(), Duration::from_secs(1), Duration::from_secs(5));
+    assert!(!backoff.is_due());
+    clock.advance(Duration::from_secs(1));
+    assert!(backoff.is_due());
+
+    // Waits of 2, 4, then 5 s (capped)
+    for wait in [2, 4, 5, 5] {
+        backoff.failed();
+        clock.advance(Duration::from_secs(wait) - Duration::from_millis(1));
+        assert!(!backoff.is_due(), "due before {} s", wait);
+        clock.advance(Duration::from_millis(1));
+        assert!(backoff.is_due(), "not due after {} s", wait);
+    }
+    assert_eq!(backoff.failures(), 4);
+}
@@ -0,0 +1,296 @@
+//! Engine checks beside the eye drop: neck angle, shoulder hunch, head tilt and distance from the screen,
+//! and when a small eye drop counts as drifting.
+
+use std::time::Duration;
+
+use posture_core::clock::ManualClock;
+use posture_core::config::{APPROACHING_FRACTION, DEBOUNCE_MS, GOOD_POSTURE_DEVIATION, HEAD_TILT_SECS, HEIGHT, SHOULDER_HUNCH_SECS, TOO_CLOSE_SECS, WIDTH};
+use posture_core::engine::{
+    body_size, ear_shoulder_gap, head_tilt, neck_angle, AlertRule, BodySize, PostureEngine, PostureStatus,
+};
+use posture_core::pose::{
+    Keypoint, Pose, KEYPOINT_COUNT, LEFT_EAR, LEFT_EYE, LEFT_SHOULDER, NOSE, RIGHT_EAR, RIGHT_EYE, RIGHT_SHOULDER,
+};
+
+const FRAME: Duration = Duration::from_millis(33);
+const EYE_Y: f32 = 0.4;
+const EAR_Y: f32 = 0.42;
+// Ears 0.33 of 480 px above the shoulders in `pose_leaning`
+const UPRIGHT_GAP: f32 = 158.4;
+
+/// A pose with the eyes at the baseline and the nose `lean` (normalized x) beside the shoulders' midpoint
+fn pose_leaning(lean: f32) -> Pose {
+    let mut pose = [Keypoint::default(); KEYPOINT_COUNT];
+    pose[RIGHT_EYE] = Keypoint { x: 0.45, y: EYE_Y, score: 0.9 };
+    pose[NOSE] = Keypoint { x: 0.5 + lean, y: 0.45, score: 0.9 };
+    pose[LEFT_SHOULDER] = Keypoint { x: 0.65, y: 0.75, score: 0.9 };
+    pose[RIGHT_SHOULDER] = Keypoint { x: 0.35, y: 0.75, score: 0.9 };
+    pose[LEFT_EYE] = Keypoint { x: 0.55, y: EYE_Y, score: 0.9 };
+    pose[LEFT_EAR] = Keypoint { x: 0.58, y: EAR_Y, score: 0.9 };
+    pose[RIGHT_EAR] = Keypoint { x: 0.42, y: EAR_Y, score: 0.9 };
+    pose
+}
+
+/// An upright pose with the shoulders raised by `rise` (normalized y)
+fn pose_hunched(rise: f32) -> Pose {
+    let mut pose = pose_leaning(0.0);
+    pose[LEFT_SHOULDER].y -= rise;
+    pose[RIGHT_SHOULDER].y -= rise;
+    pose
+}
+
+/// An upright pose with the eyes `scale` times as far apart as in `pose_leaning`
+fn pose_closer(scale: f32) -> Pose {
+    let mut pose = pose_leaning(0.0);
+    pose[LEFT_EYE].x = 0.5 + 0.05 * scale;
+    pose[RIGHT_EYE].x = 0.5 - 0.05 * scale;
+    pose
+}
+
+/// An upright pose with the left eye `rise` (normalized y) higher than the right one
+fn pose_tilted(rise: f32) -> Pose {
+    let mut pose = pose_leaning(0.0);
+    pose[LEFT_EYE].y -= rise;
+    pose
+}
+
+fn run(engine: &mut PostureEngine, clock: &ManualClock, pose: &Pose, duration: Duration) {
+    let mut elapsed = Duration::ZERO;
+    while elapsed < duration {
+        clock.advance(FRAME);
+        engine.process(Some(pose));
+        elapsed += FRAME;
+    }
+}
+
+fn calibrated_engine(clock: &ManualClock) -> PostureEngine {
+    PostureEngine::new(0, 0, Some(EYE_Y * HEIGHT as f32), clock.shared())
+}
+
+/// An upright pose with the eyes `drop` preview pixels below the baseline
+fn pose_dropped(drop: f32) -> Pose {
+    let mut pose = pose_leaning(0.0);
+    pose[LEFT_EYE].y += drop / HEIGHT as f32;
+    pose[RIGHT_EYE].y += drop / HEIGHT as f32;
+    pose
+}
+
+#[test]
+fn jitter_below_the_baseline_is_not_drifting() {
+    let clock = ManualClock::new();
+    let mut engine = calibrated_engine(&clock);
+    run(&mut engine, &clock, &pose_dropped(1.0), Duration::from_secs(2));
+    assert_eq!(engine.status(), PostureStatus::Good);
+
+    let drifting = GOOD_POSTURE_DEVIATION * (APPROACHING_FRACTION + 1.0) / 2.0;
+    run(&mut engine, &clock, &pose_dropped(drifting), Duration::from_secs(2));
+    assert_eq!(engine.status(), PostureStatus::Approaching);
+}
+
+#[test]
+fn angle_is_measured_from_vertical_in_preview_pixels() {
+    let upright = neck_angle(&pose_leaning(0.0)).unwrap();
+    assert!(upright.abs() < 0.01, "{}", upright);
+
+    // 0.18 of 640 px sideways over 0.30 of 480 px up: 115.2 / 144 -> about 38.7 degrees
+    let craned = neck_angle(&pose_leaning(0.18)).unwrap();
+    assert!((craned - 38.66).abs() < 0.1, "{}", craned);
+    assert_eq!(neck_angle(&pose_leaning(-0.18)), Some(craned));
+
+    let mut unsure = pose_leaning(0.0);
+    unsure[LEFT_SHOULDER].score = 0.1;
+    assert_eq!(neck_angle(&unsure), None);
+}
+
+#[test]
+fn craned_neck_alerts_while_the_eyes_stay_at_the_baseline() {
+    let clock = ManualClock::new();
+    let mut engine = calibrated_engine(&clock);
+    run(&mut engine, &clock, &pose_leaning(0.18), Duration::from_millis(DEBOUNCE_MS) + FRAME * 2);
+
+    assert_eq!(engine.delta(), Some(0.0));
+    assert_eq!(engine.status(), PostureStatus::Alert);
+    assert_eq!(engine.alert_rule(), Some(AlertRule::NeckAngle));
+    assert!(engine.alert_intensity() > 0.0);
+
+    // Back upright ends it
+    run(&mut engine, &clock, &pose_leaning(0.0), FRAME * 2);
+    assert!(!engine.is_alerting());
+    assert_eq!(engine.status(), PostureStatus::Good);
+}
+
+#[test]
+fn neck_limit_has_hysteresis() {
+    let clock = ManualClock::new();
+    let mut engine = calibrated_engine(&clock);
+    engine.set_neck_angle(Some(30.0));
+    run(&mut engine, &clock, &pose_leaning(0.18), Duration::from_millis(DEBOUNCE_MS) + FRAME * 2);
+    assert!(engine.is_alerting());
+
+    // About 28 degrees: inside the limit but not back within the 5-degree hysteresis
+    run(&mut engine, &clock, &pose_leaning(0.12), FRAME * 2);
+    assert!(engine.is_alerting());
+}
+
+#[test]
+fn neck_check_can_be_turned_off() {
+    let clock = ManualClock::new();
+    let mut engine = calibrated_engine(&clock);
+    engine.set_neck_angle(None);
+    assert_eq!(engine.neck_angle_limit(), None);
+    run(&mut engine, &clock, &pose_leaning(0.18), Duration::from_secs(2));
+    assert!(!engine.is_alerting());
+    assert_eq!(engine.status(), PostureStatus::Good);
+}
+
+#[test]
+fn neck_is_not_judged_without_a_baseline() {
+    let clock = ManualClock::new();
+    let mut engine = PostureEngine::new(0, 0, None, clock.shared());
+    run(&mut engine, &clock, &pose_leaning(0.18), Duration::from_secs(2));
+    assert!(engine.neck_angle().is_some());
+    assert_eq!(engine.judged(AlertRule::NeckAngle), None);
+    assert!(!engine.is_alerting());
+}
+
+#[test]
+fn ear_shoulder_gap_averages_the_confident_sides() {
+    let gap = ear_shoulder_gap(&pose_leaning(0.0)).unwrap();
+    assert!((gap - UPRIGHT_GAP).abs() < 0.01, "{}", gap);
+
+    let mut one_side = pose_leaning(0.0);
+    one_side[LEFT_EAR].score = 0.1;
+    one_side[RIGHT_SHOULDER].y -= 0.1;
+    let gap = ear_shoulder_gap(&one_side).unwrap();
+    assert!((gap - 0.23 * HEIGHT as f32).abs() < 0.01, "{}", gap);
+
+    let mut no_ears = pose_leaning(0.0);
+    no_ears[LEFT_EAR].score = 0.1;
+    no_ears[RIGHT_EAR].score = 0.1;
+    assert_eq!(ear_shoulder_gap(&no_ears), None);
+}
+
+#[test]
+fn hunched_shoulders_alert_only_after_their_own_duration() {
+    let clock = ManualClock::new();
+    let mut engine = calibrated_engine(&clock);
+    engine.set_hunch_baseline(Some(UPRIGHT_GAP));
+    // 0.1 of 0.33 closer: about 30% hunched, past the 25% default
+    let hunched = pose_hunched(0.1);
+    run(&mut engine, &clock, &hunched, Duration::from_millis(DEBOUNCE_MS) + FRAME * 2);
+    assert!((engine.hunch_percent().unwrap() - 30.3).abs() < 0.1);
+    assert!(!engine.is_alerting());
+
+    run(&mut engine, &clock, &hunched, Duration::from_secs(SHOULDER_HUNCH_SECS));
+    assert_eq!(engine.alert_rule(), Some(AlertRule::ShoulderHunch));
+
+    run(&mut engine, &clock, &pose_leaning(0.0), FRAME * 2);
+    assert!(!engine.is_alerting());
+}
+
+#[test]
+fn head_drop_is_not_taken_for_a_hunch() {
+    let clock = ManualClock::new();
+    let mut engine = calibrated_engine(&clock);
+    engine.set_hunch_baseline(Some(UPRIGHT_GAP));
+    // Eyes and ears drop together, shrinking the gap from above
+    let mut dropped = pose_leaning(0.0);
+    dropped[LEFT_EYE].y += 0.1;
+    dropped[RIGHT_EYE].y += 0.1;
+    dropped[LEFT_EAR].y += 0.1;
+    dropped[RIGHT_EAR].y += 0.1;
+    run(&mut engine, &clock, &dropped, Duration::from_secs(SHOULDER_HUNCH_SECS) + FRAME * 2);
+
+    assert_eq!(engine.alert_rule(), Some(AlertRule::EyeDrop));
+    assert_eq!(engine.judged(AlertRule::ShoulderHunch), None);
+    assert!(!engine.rule_trigger(AlertRule::ShoulderHunch).unwrap().is_slouching());
+}
+
+#[test]
+fn hunch_waits_for_a_calibrated_gap() {
+    let clock = ManualClock::new();
+    let mut engine = calibrated_engine(&clock);
+    run(&mut engine, &clock, &pose_hunched(0.1), Duration::from_secs(SHOULDER_HUNCH_SECS) + FRAME * 2);
+    assert_eq!(engine.hunch_percent(), None);
+    assert!(!engine.is_alerting());
+
+    engine.set_shoulder_hunch(None);
+    assert_eq!(engine.shoulder_hunch_limit(), None);
+}
+
+#[test]
+fn closeness_prefers_the_eye_spacing() {
+    let upright = body_size(&pose_leaning(0.0));
+    assert!((upright.eye_spacing.unwrap() - 0.1 * WIDTH as f32).abs() < 0.01);
+    assert!((upright.shoulder_width.unwrap() - 0.3 * WIDTH as f32).abs() < 0.01);
+
+    let closer = body_size(&pose_closer(1.5)).closer_percent(&upright).unwrap();
+    assert!((closer - 50.0).abs() < 0.1, "{}", closer);
+
+    // Without the eyes the shoulders tell the distance
+    let shoulders_only = BodySize { eye_spacing: None, shoulder_width: Some(0.36 * WIDTH as f32) };
+    let closer = shoulders_only.closer_percent(&upright).unwrap();
+    assert!((closer - 20.0).abs() < 0.1, "{}", closer);
+    assert_eq!(BodySize::default().closer_percent(&upright), None);
+}
+
+#[test]
+fn too_close_warns_without_a_posture_alert() {
+    let clock = ManualClock::new();
+    let mut engine = calibrated_engine(&clock);
+    engine.set_distance_baseline(Some(body_size(&pose_leaning(0.0))));
+    run(&mut engine, &clock, &pose_closer(1.5), Duration::from_secs(TOO_CLOSE_SECS) + FRAME * 2);
+
+    assert!(engine.is_too_close());
+    assert!(engine.too_close_intensity() > 0.0);
+    assert!(!engine.is_alerting());
+    assert_eq!(engine.stats().alerts, 0);
+
+    // 25% is inside the 30% limit but not back within the 10-point hysteresis
+    run(&mut engine, &clock, &pose_closer(1.25), FRAME * 2);
+    assert!(engine.is_too_close());
+    run(&mut engine, &clock, &pose_leaning(0.0), FRAME * 2);
+    assert!(!engine.is_too_close());
+}
+
+#[test]
+fn too_close_can_be_turned_off() {
+    let clock = ManualClock::new();
+    let mut engine = calibrated_engine(&clock);
+    engine.set_distance_baseline(Some(body_size(&pose_leaning(0.0))));
+    engine.set_too_close(None);
+    assert_eq!(engine.too_close_limit(), None);
+    run(&mut engine, &clock, &pose_closer(1.5), Duration::from_secs(TOO_CLOSE_SECS) + FRAME * 2);
+    assert!(!engine.is_too_close());
+}
+
+#[test]
+fn tilt_is_the_eye_line_roll_in_preview_pixels() {
+    assert_eq!(head_tilt(&pose_leaning(0.0)), Some(0.0));
+
+    // 0.1 of 640 px across, 0.05 of 480 px up: atan(24 / 64) -> about 20.6 degrees either way
+    let tilted = head_tilt(&pose_tilted(0.05)).unwrap();
+    assert!((tilted - 20.56).abs() < 0.1, "{}", tilted);
+    assert!((head_tilt(&pose_tilted(-0.05)).unwrap() - tilted).abs() < 0.01);
+
+    let mut one_eye = pose_tilted(0.05);
+    one_eye[LEFT_EYE].score = 0.1;
+    assert_eq!(head_tilt(&one_eye), None);
+}
+
+#[test]
+fn tilted_head_alerts_only_after_its_own_duration() {
+    let clock = ManualClock::new();
+    let mut engine = calibrated_engine(&clock);
+    // The lowered right eye stays at the baseline, so only the tilt is past its limit
+    run(&mut engine, &clock, &pose_tilted(0.05), Duration::from_millis(DEBOUNCE_MS) + FRAME * 2);
+    assert!(!engine.is_alerting());
+
+    run(&mut engine, &clock, &pose_tilted(0.05), Duration::from_secs(HEAD_TILT_SECS));
+    assert_eq!(engine.alert_rule(), Some(AlertRule::HeadTilt));
+
+    engine.set_head_tilt(None);
+    assert_eq!(engine.head_tilt_limit(), None);
+    run(&mut engine, &clock, &pose_tilted(0.05), FRAME * 2);
+    assert!(!engine.is_alerting());
+}
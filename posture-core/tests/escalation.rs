@@ -0,0 +1,40 @@
+//! The escalation ladder: toast, dim, blur and input block by slouch duration, reset on recovery.
+
+use std::time::Duration;
+
+use posture_core::escalation::{Escalation, EscalationLadder, Severity};
+
+fn ladder(block_secs: Option<u64>) -> EscalationLadder {
+    EscalationLadder { enabled: true, toast_secs: 10, dim_secs: 30, blur_secs: 60, block_secs }
+}
+
+fn secs(secs: u64) -> Option<Duration> {
+    Some(Duration::from_secs(secs))
+}
+
+#[test]
+fn each_step_follows_the_slouch_duration() {
+    let blocking = ladder(Some(120));
+    assert_eq!(blocking.severity(None), None);
+    assert_eq!(blocking.severity(secs(9)), None);
+    assert_eq!(blocking.severity(secs(10)), Some(Severity::Toast));
+    assert_eq!(blocking.severity(secs(30)), Some(Severity::Dim));
+    assert_eq!(blocking.severity(secs(60)), Some(Severity::Blur));
+    assert_eq!(blocking.severity(secs(120)), Some(Severity::Block));
+    // Without the block step the blur is as far as it goes
+    assert_eq!(ladder(None).severity(secs(3600)), Some(Severity::Blur));
+}
+
+#[test]
+fn steps_are_reported_once_and_sitting_up_starts_over() {
+    let ladder = ladder(None);
+    let mut escalation = Escalation::new();
+    assert_eq!(escalation.update(&ladder, secs(10)), Some(Severity::Toast));
+    assert_eq!(escalation.update(&ladder, secs(11)), None);
+    assert_eq!(escalation.update(&ladder, secs(30)), Some(Severity::Dim));
+    assert_eq!(escalation.severity(), Some(Severity::Dim));
+
+    assert_eq!(escalation.update(&ladder, None), None);
+    assert_eq!(escalation.severity(), None);
+    assert_eq!(escalation.update(&ladder, secs(10)), Some(Severity::Toast));
+}
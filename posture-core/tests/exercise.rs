@@ -0,0 +1,59 @@
+//! Exercise verification: repetitions only count when the keypoints show the movement.
+
+use posture_core::exercise::{Exercise, ExerciseSession};
+use posture_core::pose::{Keypoint, Pose, KEYPOINT_COUNT, LEFT_EYE, LEFT_SHOULDER, NOSE, RIGHT_EYE, RIGHT_SHOULDER};
+
+/// Eyes `spacing` apart, nose `turn` shoulder widths right of the shoulders' center
+fn pose(spacing: f32, turn: f32) -> Pose {
+    let mut pose = [Keypoint::default(); KEYPOINT_COUNT];
+    let at = |x: f32, y: f32| Keypoint { x, y, score: 0.9 };
+    pose[LEFT_EYE] = at(0.5 + spacing / 2.0, 0.4);
+    pose[RIGHT_EYE] = at(0.5 - spacing / 2.0, 0.4);
+    pose[NOSE] = at(0.5 + turn * 0.3, 0.45);
+    pose[LEFT_SHOULDER] = at(0.65, 0.7);
+    pose[RIGHT_SHOULDER] = at(0.35, 0.7);
+    pose
+}
+
+fn feed(session: &mut ExerciseSession, poses: &[Pose]) -> u32 {
+    poses.iter().filter(|pose| session.update(Some(pose))).count() as u32
+}
+
+#[test]
+fn chin_tucks_count_once_the_head_is_back() {
+    let mut session = ExerciseSession::new(Exercise::ChinTuck, 2);
+    // Rest, tuck, still tucked, release
+    assert_eq!(feed(&mut session, &[pose(0.10, 0.0), pose(0.09, 0.0), pose(0.09, 0.0)]), 0);
+    assert_eq!(feed(&mut session, &[pose(0.10, 0.0)]), 1);
+    assert!(!session.is_complete());
+    feed(&mut session, &[pose(0.088, 0.0), pose(0.099, 0.0)]);
+    assert!(session.is_complete());
+    assert_eq!(session.reps(), 2);
+}
+
+#[test]
+fn small_head_movements_and_missing_poses_do_not_count() {
+    let mut session = ExerciseSession::new(Exercise::ChinTuck, 1);
+    feed(&mut session, &[pose(0.10, 0.0), pose(0.097, 0.0), pose(0.10, 0.0), pose(0.097, 0.0)]);
+    assert!(!session.update(None));
+    assert_eq!(session.reps(), 0);
+}
+
+#[test]
+fn neck_rolls_need_both_sides() {
+    let mut session = ExerciseSession::new(Exercise::NeckRoll, 1);
+    // Only one side, several times
+    feed(&mut session, &[pose(0.1, 0.0), pose(0.1, -0.5), pose(0.1, 0.0), pose(0.1, -0.5), pose(0.1, 0.0)]);
+    assert_eq!(session.reps(), 0);
+    feed(&mut session, &[pose(0.1, 0.5), pose(0.1, 0.05)]);
+    assert!(session.is_complete());
+    // Finished sessions ignore further movement
+    assert!(!session.update(Some(&pose(0.1, -0.5))));
+}
+
+#[test]
+fn exercises_alternate_and_need_at_least_one_rep() {
+    assert_eq!(Exercise::ChinTuck.next(), Exercise::NeckRoll);
+    assert_eq!(Exercise::NeckRoll.next(), Exercise::ChinTuck);
+    assert_eq!(ExerciseSession::new(Exercise::ChinTuck, 0).reps_needed(), 1);
+}
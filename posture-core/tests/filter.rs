@@ -0,0 +1,68 @@
+//! Keypoint smoothing.
+
+use posture_core::filter::{Ema, Kalman, PoseFilter, Smoothing};
+use posture_core::pose::{Keypoint, Pose, RIGHT_EYE};
+
+fn pose_with_eye(y: f32, score: f32) -> Pose {
+    let mut pose = [Keypoint { x: 0.5, y: 0.5, score: 0.9 }; 17];
+    pose[RIGHT_EYE] = Keypoint { x: 0.5, y, score };
+    pose
+}
+
+/// Eye height alternating by ±`amplitude` around `center`
+fn jitter(center: f32, amplitude: f32, frames: usize) -> impl Iterator<Item = f32> {
+    (0..frames).map(move |i| if i % 2 == 0 { center + amplitude } else { center - amplitude })
+}
+
+#[test]
+fn ema_and_kalman_damp_jitter_but_follow_a_real_move() {
+    let mut ema = Ema::new(0.3);
+    let mut kalman = Kalman::new(0.0001, 0.01);
+
+    let mut worst = (0.0f32, 0.0f32);
+    for (i, y) in jitter(0.5, 0.02, 60).enumerate() {
+        let (e, k) = (ema.update(y), kalman.update(y));
+        if i > 20 {
+            worst = (worst.0.max((e - 0.5).abs()), worst.1.max((k - 0.5).abs()));
+        }
+    }
+    assert!(worst.0 < 0.01, "EMA still jitters by {}", worst.0);
+    assert!(worst.1 < 0.01, "Kalman still jitters by {}", worst.1);
+
+    // Slouching for real: both settle near the new height
+    for _ in 0..100 {
+        ema.update(0.6);
+        kalman.update(0.6);
+    }
+    assert!((ema.update(0.6) - 0.6).abs() < 0.001);
+    assert!((kalman.update(0.6) - 0.6).abs() < 0.01);
+}
+
+#[test]
+fn lost_keypoints_restart_instead_of_gliding_in() {
+    let mut filter = PoseFilter::new(Smoothing::Ema { alpha: 0.2 });
+    filter.apply(Some(&pose_with_eye(0.4, 0.9)));
+
+    // The eye drops out, then reappears somewhere else
+    let hidden = filter.apply(Some(&pose_with_eye(0.9, 0.1))).unwrap();
+    assert_eq!(hidden[RIGHT_EYE].y, 0.9, "unconfident keypoints pass through");
+    let found = filter.apply(Some(&pose_with_eye(0.6, 0.9))).unwrap();
+    assert_eq!(found[RIGHT_EYE].y, 0.6);
+
+    // Nobody in the frame resets everything
+    assert!(filter.apply(None).is_none());
+    let back = filter.apply(Some(&pose_with_eye(0.3, 0.9))).unwrap();
+    assert_eq!(back[RIGHT_EYE].y, 0.3);
+    assert_eq!(back[RIGHT_EYE].score, 0.9);
+}
+
+#[test]
+fn smoothing_settings_are_validated() {
+    assert!(Smoothing::None.validate().is_ok());
+    assert!(Smoothing::Ema { alpha: 1.0 }.validate().is_ok());
+    assert!(Smoothing::Ema { alpha: 0.0 }.validate().is_err());
+    assert!(Smoothing::Kalman { process_noise: 0.0, measurement_noise: 1.0 }.validate().is_err());
+
+    let json = serde_json::to_string(&Smoothing::Ema { alpha: 0.5 }).unwrap();
+    assert_eq!(json, r#"{"ema":{"alpha":0.5}}"#);
+}
@@ -0,0 +1,65 @@
+//! The grace period after sitting back up: brief dips are let go, a sustained slouch still alerts.
+
+use std::time::{Duration, Instant};
+
+use posture_core::engine::SlouchTrigger;
+
+const DEVIATION: f32 = 10.0;
+const DEBOUNCE: Duration = Duration::from_millis(500);
+const GRACE: Duration = Duration::from_secs(5);
+const FRAME: Duration = Duration::from_millis(100);
+
+/// Feeds `delta` every frame from `start` for `duration`; returns the time after the last frame
+fn hold(trigger: &mut SlouchTrigger, start: Instant, delta: Option<f32>, duration: Duration) -> Instant {
+    let mut now = start;
+    while now < start + duration {
+        trigger.update(now, delta);
+        now += FRAME;
+    }
+    now
+}
+
+fn alerted_trigger(start: Instant) -> (SlouchTrigger, Instant) {
+    let mut trigger = SlouchTrigger::new(DEVIATION, 5.0, DEBOUNCE).with_grace(GRACE);
+    let now = hold(&mut trigger, start, Some(20.0), DEBOUNCE + FRAME);
+    assert!(trigger.is_alerting());
+    (trigger, now)
+}
+
+#[test]
+fn a_brief_dip_after_sitting_up_does_not_alert_again() {
+    let (mut trigger, now) = alerted_trigger(Instant::now());
+    let now = hold(&mut trigger, now, Some(0.0), FRAME);
+    assert!(!trigger.is_alerting());
+    assert!(trigger.is_in_grace());
+
+    // Reaching for a cup: longer than the debounce, shorter than the grace period
+    let now = hold(&mut trigger, now, Some(20.0), DEBOUNCE * 3);
+    assert!(trigger.is_slouching());
+    assert!(!trigger.is_alerting());
+    // Back up, and the rest of the grace period still applies; losing the face doesn't end it
+    let now = hold(&mut trigger, now, None, FRAME * 3);
+    assert!(trigger.is_in_grace());
+    hold(&mut trigger, now, Some(20.0), DEBOUNCE * 3);
+    assert!(!trigger.is_alerting());
+}
+
+#[test]
+fn a_slouch_that_outlasts_the_grace_period_alerts() {
+    let (mut trigger, now) = alerted_trigger(Instant::now());
+    let now = hold(&mut trigger, now, Some(0.0), FRAME);
+    let now = hold(&mut trigger, now, Some(20.0), GRACE - FRAME * 2);
+    assert!(!trigger.is_alerting());
+    hold(&mut trigger, now, Some(20.0), FRAME * 2);
+    assert!(trigger.is_alerting());
+}
+
+#[test]
+fn without_a_grace_period_the_next_slouch_alerts_after_the_debounce() {
+    let (mut trigger, now) = alerted_trigger(Instant::now());
+    trigger.grace = Duration::ZERO;
+    let now = hold(&mut trigger, now, Some(0.0), FRAME);
+    assert!(!trigger.is_in_grace());
+    hold(&mut trigger, now, Some(20.0), DEBOUNCE + FRAME);
+    assert!(trigger.is_alerting());
+}
@@ -0,0 +1,78 @@
+//! Perspective correction used for tilted camera mounts.
+
+use posture_core::homography::{CameraMapping, Homography, ReferenceMarking};
+use posture_core::pose::Flip;
+
+fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+    assert!(
+        (actual.0 - expected.0).abs() < 1e-4 && (actual.1 - expected.1).abs() < 1e-4,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+// A rectangle seen from above: the far (top) edge looks narrower
+const TRAPEZOID: [(f32, f32); 4] = [(0.35, 0.2), (0.65, 0.2), (0.8, 0.7), (0.2, 0.7)];
+
+#[test]
+fn corners_map_onto_an_upright_rectangle() {
+    let mapping = CameraMapping::new(TRAPEZOID, 0, 0, Flip::default()).unwrap();
+    let homography = mapping.homography().unwrap();
+
+    let [tl, tr, br, bl] = TRAPEZOID.map(|(x, y)| homography.apply(x, y));
+    assert!((tl.1 - tr.1).abs() < 1e-4 && (bl.1 - br.1).abs() < 1e-4);
+    assert!((tl.0 - bl.0).abs() < 1e-4 && (tr.0 - br.0).abs() < 1e-4);
+    // Same center as the marked shape, so corrected keypoints stay in frame
+    assert_close(((tl.0 + br.0) / 2.0, (tl.1 + br.1) / 2.0), (0.5, 0.45));
+}
+
+#[test]
+fn inverse_round_trips_points() {
+    let homography = CameraMapping::new(TRAPEZOID, 0, 0, Flip::default()).unwrap().homography().unwrap();
+    let inverse = homography.inverse().unwrap();
+    for point in [(0.5, 0.5), (0.1, 0.9), (0.42, 0.33)] {
+        let (x, y) = homography.apply(point.0, point.1);
+        assert_close(inverse.apply(x, y), point);
+    }
+}
+
+#[test]
+fn a_square_view_needs_no_correction() {
+    let square = [(0.25, 0.25), (0.75, 0.25), (0.75, 0.75), (0.25, 0.75)];
+    let homography = CameraMapping::new(square, 0, 0, Flip::default()).unwrap().homography().unwrap();
+    for point in [(0.5, 0.5), (0.1, 0.9)] {
+        assert_close(homography.apply(point.0, point.1), Homography::identity().apply(point.0, point.1));
+    }
+}
+
+#[test]
+fn degenerate_markings_are_rejected() {
+    // Three points on one line
+    assert!(CameraMapping::new([(0.1, 0.1), (0.5, 0.5), (0.9, 0.9), (0.1, 0.9)], 0, 0, Flip::default()).is_err());
+    // All clicks in the same spot
+    assert!(CameraMapping::new([(0.5, 0.5); 4], 0, 0, Flip::default()).is_err());
+}
+
+#[test]
+fn marking_completes_after_four_clicks() {
+    let mut marking = ReferenceMarking::new();
+    assert_eq!(marking.prompt(), "mapping.click_top_left");
+    for point in &TRAPEZOID[..3] {
+        assert_eq!(marking.add(*point), None);
+    }
+    assert_eq!(marking.prompt(), "mapping.click_bottom_left");
+    assert_eq!(marking.add(TRAPEZOID[3]), Some(TRAPEZOID));
+}
+
+#[test]
+fn flipped_mapping_corrects_the_flipped_view_the_same_way() {
+    let original = CameraMapping::new(TRAPEZOID, 0, 0, Flip::default()).unwrap();
+    for flip in [Flip { horizontal: true, vertical: false }, Flip { horizontal: false, vertical: true }] {
+        let flipped = original.clone().reflipped(flip);
+        assert_eq!(flipped.flip, flip);
+        let (expected, actual) = (original.homography().unwrap(), flipped.homography().unwrap());
+        let (x, y) = flip.point((0.4, 0.3));
+        assert_close(actual.apply(x, y), flip.point(expected.apply(0.4, 0.3)));
+    }
+}
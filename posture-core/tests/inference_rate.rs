@@ -0,0 +1,80 @@
+//! Adaptive inference rate: throttled while posture is stable, full rate near the threshold.
+
+use std::time::Duration;
+
+use posture_core::clock::ManualClock;
+use posture_core::config::INFERENCE_STABLE_SECS;
+use posture_core::engine::PostureStatus;
+use posture_core::inference_rate::InferenceRate;
+
+const FRAME: Duration = Duration::from_millis(33);
+const MAX_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Feeds frames for `duration`, observing `status` after each inference; returns the inference count
+fn run(rate: &mut InferenceRate, clock: &ManualClock, duration: Duration, status: PostureStatus, headroom: f32) -> u32 {
+    let mut inferences = 0;
+    let mut elapsed = Duration::ZERO;
+    while elapsed < duration {
+        if rate.start_if_due() {
+            inferences += 1;
+            rate.observe(status, Some(headroom));
+        }
+        clock.advance(FRAME);
+        elapsed += FRAME;
+    }
+    inferences
+}
+
+#[test]
+fn stable_good_posture_slows_down() {
+    let clock = ManualClock::new();
+    let mut rate = InferenceRate::with_intervals(clock.shared(), Duration::ZERO, MAX_INTERVAL);
+    let stable = Duration::from_secs(INFERENCE_STABLE_SECS);
+
+    // Every frame until posture has been calm for a while
+    let frames = run(&mut rate, &clock, stable, PostureStatus::Good, 10.0);
+    assert!(frames as u64 >= stable.as_millis() as u64 / 33 - 1, "{}", frames);
+    assert_eq!(rate.interval(), MAX_INTERVAL);
+
+    let throttled = run(&mut rate, &clock, Duration::from_secs(4), PostureStatus::Good, 10.0);
+    assert!((7..=9).contains(&throttled), "{}", throttled);
+    assert!((1.5..=2.5).contains(&rate.measured_per_sec()), "{}", rate.measured_per_sec());
+}
+
+#[test]
+fn changes_and_the_threshold_restore_the_full_rate() {
+    let clock = ManualClock::new();
+    let mut rate = InferenceRate::with_intervals(clock.shared(), Duration::ZERO, MAX_INTERVAL);
+    let settle = Duration::from_secs(INFERENCE_STABLE_SECS + 1);
+
+    run(&mut rate, &clock, settle, PostureStatus::Good, 10.0);
+    assert_eq!(rate.interval(), MAX_INTERVAL);
+
+    // Drifting close to the threshold
+    run(&mut rate, &clock, MAX_INTERVAL, PostureStatus::Approaching, 2.0);
+    assert_eq!(rate.interval(), Duration::ZERO);
+
+    // A status change also counts as unstable, even when it's back to good
+    run(&mut rate, &clock, settle, PostureStatus::Approaching, 8.0);
+    assert_eq!(rate.interval(), MAX_INTERVAL);
+    run(&mut rate, &clock, MAX_INTERVAL, PostureStatus::Good, 10.0);
+    assert_eq!(rate.interval(), Duration::ZERO);
+
+    // Waking (pause, placement check) starts the calm stretch over
+    run(&mut rate, &clock, settle, PostureStatus::Good, 10.0);
+    rate.wake();
+    assert_eq!(rate.interval(), Duration::ZERO);
+}
+
+#[test]
+fn nobody_in_view_uses_the_slowest_rate() {
+    let clock = ManualClock::new();
+    let mut rate = InferenceRate::with_intervals(clock.shared(), Duration::ZERO, MAX_INTERVAL);
+
+    let away = run(&mut rate, &clock, Duration::from_secs(2), PostureStatus::Away, 10.0);
+    assert!((4..=5).contains(&away), "{}", away);
+
+    // Someone back in view is judged at the full rate again
+    rate.observe(PostureStatus::Good, Some(10.0));
+    assert_eq!(rate.interval(), Duration::ZERO);
+}
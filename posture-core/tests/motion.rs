@@ -0,0 +1,100 @@
+//! Movement detection: fast motion holds off slouch counting, keypoint jitter and stillness don't.
+
+use std::time::{Duration, Instant};
+
+use posture_core::clock::ManualClock;
+use posture_core::config::{DEBOUNCE_MS, HEIGHT, MOTION_SETTLE_MS};
+use posture_core::engine::PostureEngine;
+use posture_core::motion::{mean_displacement, MotionDetector};
+use posture_core::pose::{Keypoint, Pose, KEYPOINT_COUNT, LEFT_EYE, LEFT_SHOULDER, NOSE, RIGHT_EYE, RIGHT_SHOULDER};
+
+const FRAME: Duration = Duration::from_millis(50);
+const BASELINE: f32 = 200.0;
+
+/// Head and shoulders shifted `x` (normalized) sideways, with the eyes at `eye_y` preview pixels
+fn pose_at(x: f32, eye_y: f32) -> Pose {
+    let eye_y = eye_y / HEIGHT as f32;
+    let mut pose = [Keypoint::default(); KEYPOINT_COUNT];
+    pose[RIGHT_EYE] = Keypoint { x: x + 0.45, y: eye_y, score: 0.9 };
+    pose[LEFT_EYE] = Keypoint { x: x + 0.55, y: eye_y, score: 0.9 };
+    pose[NOSE] = Keypoint { x: x + 0.5, y: eye_y + 0.05, score: 0.9 };
+    pose[LEFT_SHOULDER] = Keypoint { x: x + 0.65, y: 0.8, score: 0.9 };
+    pose[RIGHT_SHOULDER] = Keypoint { x: x + 0.35, y: 0.8, score: 0.9 };
+    pose
+}
+
+/// Sideways positions for `duration`, 0.02 of the frame width per frame (about 250 px/s)
+fn sweep(duration: Duration) -> impl Iterator<Item = f32> {
+    (0..duration.as_millis() / FRAME.as_millis()).map(|frame| (frame % 10) as f32 * 0.02)
+}
+
+#[test]
+fn displacement_only_counts_keypoints_seen_in_both_poses() {
+    let from = pose_at(0.0, BASELINE);
+    let mut to = pose_at(0.1, BASELINE);
+    assert!((mean_displacement(&from, &to).unwrap() - 64.0).abs() < 0.01);
+    for keypoint in to.iter_mut() {
+        keypoint.score = 0.0;
+    }
+    assert_eq!(mean_displacement(&from, &to), None);
+}
+
+#[test]
+fn fast_motion_counts_until_the_user_settles_and_jitter_does_not() {
+    let mut motion = MotionDetector::new();
+    let mut now = Instant::now();
+    // Keypoint jitter of about a pixel either way
+    for frame in 0..40 {
+        let jitter = if frame % 2 == 0 { 0.002 } else { -0.002 };
+        assert!(!motion.update(now, &pose_at(jitter, BASELINE)));
+        now += FRAME;
+    }
+
+    for x in sweep(Duration::from_secs(1)) {
+        motion.update(now, &pose_at(x, BASELINE));
+        now += FRAME;
+    }
+    assert!(motion.is_moving());
+
+    // Still again: moving for a moment longer, then settled
+    let settled = now + Duration::from_millis(MOTION_SETTLE_MS) + Duration::from_millis(500);
+    while now < settled {
+        motion.update(now, &pose_at(0.0, BASELINE));
+        now += FRAME;
+    }
+    assert!(!motion.is_moving());
+}
+
+#[test]
+fn only_a_slouch_held_still_alerts() {
+    let clock = ManualClock::new();
+    let mut engine = PostureEngine::new(0, 0, Some(BASELINE), clock.shared());
+    engine.set_ignore_movement(true);
+    let slouched = BASELINE + 40.0;
+    let frame = |engine: &mut PostureEngine, pose: Pose| {
+        engine.process(Some(&pose));
+        clock.advance(FRAME);
+    };
+
+    // Leaning over to pick something up
+    for x in sweep(Duration::from_secs(2)) {
+        frame(&mut engine, pose_at(x, slouched));
+    }
+    assert!(engine.is_moving());
+    assert!(!engine.is_alerting());
+
+    // Staying down once still alerts after settling and the debounce
+    let still = Duration::from_millis(MOTION_SETTLE_MS + DEBOUNCE_MS) + Duration::from_secs(1);
+    for _ in 0..still.as_millis() / FRAME.as_millis() {
+        frame(&mut engine, pose_at(0.0, slouched));
+    }
+    assert!(!engine.is_moving());
+    assert!(engine.is_alerting());
+
+    // Without it the same movement alerts
+    let mut engine = PostureEngine::new(0, 0, Some(BASELINE), clock.shared());
+    for x in sweep(Duration::from_secs(2)) {
+        frame(&mut engine, pose_at(x, slouched));
+    }
+    assert!(engine.is_alerting());
+}
@@ -0,0 +1,31 @@
+//! Keypoints moved into flipped frames.
+
+use posture_core::pose::{Flip, Keypoint, KEYPOINT_COUNT, LEFT_EYE, NOSE, RIGHT_EYE};
+
+fn pose() -> Vec<Keypoint> {
+    let mut pose = vec![Keypoint { x: 0.5, y: 0.5, score: 0.9 }; KEYPOINT_COUNT];
+    pose[LEFT_EYE] = Keypoint { x: 0.625, y: 0.375, score: 0.8 };
+    pose[RIGHT_EYE] = Keypoint { x: 0.375, y: 0.25, score: 0.7 };
+    pose
+}
+
+#[test]
+fn a_mirror_swaps_left_and_right() {
+    let mirrored = Flip { horizontal: true, vertical: false }.keypoints(&pose());
+    assert_eq!(mirrored[RIGHT_EYE], Keypoint { x: 0.375, y: 0.375, score: 0.8 });
+    assert_eq!(mirrored[LEFT_EYE], Keypoint { x: 0.625, y: 0.25, score: 0.7 });
+    assert_eq!(mirrored[NOSE], pose()[NOSE]);
+}
+
+#[test]
+fn both_flips_turn_the_body_without_swapping() {
+    let turned = Flip { horizontal: true, vertical: true }.keypoints(&pose());
+    assert_eq!(turned[LEFT_EYE], Keypoint { x: 0.375, y: 0.625, score: 0.8 });
+}
+
+#[test]
+fn flipping_back_restores_the_pose() {
+    let flip = Flip { horizontal: false, vertical: true };
+    assert_eq!(flip.keypoints(&flip.keypoints(&pose())), pose());
+    assert_eq!(flip.relative_to(flip), Flip::default());
+}
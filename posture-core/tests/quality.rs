@@ -0,0 +1,85 @@
+//! Tracking quality: time-averaged keypoint confidence, and no judging while it's poor.
+
+use std::time::Duration;
+
+use posture_core::clock::{Clock, ManualClock};
+use posture_core::config::{DEBOUNCE_MS, HEIGHT, TRACKING_QUALITY_SECS};
+use posture_core::engine::{EngineEvent, PostureEngine, PostureStatus};
+use posture_core::pose::{Keypoint, Pose, KEYPOINT_COUNT, LEFT_EYE, LEFT_SHOULDER, NOSE, RIGHT_EYE, RIGHT_SHOULDER};
+use posture_core::quality::TrackingQuality;
+
+const FRAME: Duration = Duration::from_millis(33);
+const EYE_Y: f32 = 0.4;
+
+/// An upright pose with every keypoint at `score`, the eyes `drop` (normalized y) below the baseline
+fn pose(score: f32, drop: f32) -> Pose {
+    let mut pose = [Keypoint { x: 0.5, y: 0.5, score }; KEYPOINT_COUNT];
+    pose[RIGHT_EYE] = Keypoint { x: 0.45, y: EYE_Y + drop, score };
+    pose[LEFT_EYE] = Keypoint { x: 0.55, y: EYE_Y + drop, score };
+    pose[NOSE] = Keypoint { x: 0.5, y: 0.45 + drop, score };
+    pose[LEFT_SHOULDER] = Keypoint { x: 0.65, y: 0.75, score };
+    pose[RIGHT_SHOULDER] = Keypoint { x: 0.35, y: 0.75, score };
+    pose
+}
+
+fn feed(quality: &mut TrackingQuality, clock: &ManualClock, pose: &Pose, duration: Duration) -> Vec<bool> {
+    let mut changes = Vec::new();
+    let mut elapsed = Duration::ZERO;
+    while elapsed < duration {
+        clock.advance(FRAME);
+        changes.extend(quality.update(clock.now(), pose));
+        elapsed += FRAME;
+    }
+    changes
+}
+
+#[test]
+fn score_weighs_the_right_eye_most() {
+    let clock = ManualClock::new();
+    let mut quality = TrackingQuality::new();
+    assert_eq!(quality.score(), 1.0);
+
+    let mut unsure_eye = pose(0.8, 0.0);
+    unsure_eye[RIGHT_EYE].score = 0.0;
+    quality.update(clock.now(), &unsure_eye);
+    // 3 of the 8 weight units at 0
+    assert!((quality.score() - 0.5).abs() < 0.001, "{}", quality.score());
+    assert_eq!(quality.keypoint_score(RIGHT_EYE), Some(0.0));
+    assert_eq!(quality.keypoint_score(LEFT_SHOULDER), Some(0.8));
+}
+
+#[test]
+fn a_single_bad_frame_doesnt_make_tracking_poor() {
+    let clock = ManualClock::new();
+    let mut quality = TrackingQuality::new();
+    feed(&mut quality, &clock, &pose(0.8, 0.0), Duration::from_secs(1));
+    assert!(feed(&mut quality, &clock, &pose(0.1, 0.0), FRAME).is_empty());
+    assert!(!quality.is_poor());
+
+    // Sustained low confidence does, and it recovers only past the upper mark
+    assert_eq!(feed(&mut quality, &clock, &pose(0.1, 0.0), Duration::from_secs_f32(TRACKING_QUALITY_SECS * 3.0)), [true]);
+    assert!(feed(&mut quality, &clock, &pose(0.4, 0.0), Duration::from_secs_f32(TRACKING_QUALITY_SECS * 5.0)).is_empty());
+    assert!(quality.is_poor());
+    assert_eq!(feed(&mut quality, &clock, &pose(0.8, 0.0), Duration::from_secs_f32(TRACKING_QUALITY_SECS * 3.0)), [false]);
+}
+
+#[test]
+fn poor_tracking_never_alerts() {
+    let clock = ManualClock::new();
+    let mut engine = PostureEngine::new(0, 0, Some(EYE_Y * HEIGHT as f32), clock.shared());
+    let mut events = Vec::new();
+    // Just above KEYPOINT_CONFIDENCE: every keypoint is usable, but the average is poor
+    let slouched = pose(0.32, 0.1);
+    let mut elapsed = Duration::ZERO;
+    while elapsed < Duration::from_millis(DEBOUNCE_MS) + Duration::from_secs(5) {
+        clock.advance(FRAME);
+        events.extend(engine.process(Some(&slouched)));
+        elapsed += FRAME;
+    }
+
+    assert!(events.iter().any(|event| matches!(event, EngineEvent::TrackingPoor)));
+    assert!(!events.iter().any(|event| matches!(event, EngineEvent::AlertStarted)));
+    assert_eq!(engine.status(), PostureStatus::TrackingPoor);
+    assert_eq!(engine.judged_delta(), None);
+    assert_eq!(engine.stats().bad_frames, 0);
+}
@@ -0,0 +1,90 @@
+//! Standing detection from the eye height and from the head leaving the frame.
+
+use std::time::{Duration, Instant};
+
+use posture_core::clock::ManualClock;
+use posture_core::config::{HEIGHT, STANDING_RISE_PX, STANDING_SWITCH_SECS};
+use posture_core::engine::PostureEngine;
+use posture_core::pose::{Keypoint, Pose, KEYPOINT_COUNT, LEFT_HIP, LEFT_SHOULDER, RIGHT_EYE, RIGHT_HIP, RIGHT_SHOULDER};
+use posture_core::stance::{frame_stance, Stance, StanceDetector};
+
+const FRAME: Duration = Duration::from_millis(100);
+const BASELINE: f32 = 200.0;
+
+/// A pose with the right eye at `eye_y` preview pixels and the shoulders below it
+fn seated(eye_y: f32) -> Pose {
+    let mut pose = [Keypoint::default(); KEYPOINT_COUNT];
+    pose[RIGHT_EYE] = Keypoint { x: 0.45, y: eye_y / HEIGHT as f32, score: 0.9 };
+    pose[LEFT_SHOULDER] = Keypoint { x: 0.65, y: 0.8, score: 0.9 };
+    pose[RIGHT_SHOULDER] = Keypoint { x: 0.35, y: 0.8, score: 0.9 };
+    pose
+}
+
+/// Head above the frame: only the torso in view
+fn torso() -> Pose {
+    let mut pose = [Keypoint::default(); KEYPOINT_COUNT];
+    pose[LEFT_SHOULDER] = Keypoint { x: 0.65, y: 0.1, score: 0.9 };
+    pose[RIGHT_SHOULDER] = Keypoint { x: 0.35, y: 0.1, score: 0.9 };
+    pose[LEFT_HIP] = Keypoint { x: 0.6, y: 0.9, score: 0.9 };
+    pose[RIGHT_HIP] = Keypoint { x: 0.4, y: 0.9, score: 0.9 };
+    pose
+}
+
+#[test]
+fn eyes_far_above_the_baseline_or_a_headless_torso_look_like_standing() {
+    assert_eq!(frame_stance(&seated(BASELINE + 20.0), Some(BASELINE)), Some(Stance::Sitting));
+    assert_eq!(frame_stance(&seated(BASELINE - STANDING_RISE_PX), Some(BASELINE)), Some(Stance::Standing));
+    // Without a calibration only the head leaving the frame tells
+    assert_eq!(frame_stance(&seated(BASELINE - STANDING_RISE_PX), None), Some(Stance::Sitting));
+    assert_eq!(frame_stance(&torso(), None), Some(Stance::Standing));
+
+    // Shoulders low in the frame without a face (looking down, hand over the face) say nothing
+    let mut pose = seated(BASELINE);
+    pose[RIGHT_EYE].score = 0.0;
+    assert_eq!(frame_stance(&pose, Some(BASELINE)), None);
+    pose[RIGHT_HIP] = Keypoint { x: 0.4, y: 0.95, score: 0.9 };
+    assert_eq!(frame_stance(&pose, Some(BASELINE)), Some(Stance::Standing));
+}
+
+#[test]
+fn a_new_stance_only_counts_once_it_lasts() {
+    let mut detector = StanceDetector::new();
+    let start = Instant::now();
+    let switch = Duration::from_secs(STANDING_SWITCH_SECS);
+
+    // Leaning back for a moment
+    assert_eq!(detector.update(start, &torso(), Some(BASELINE)), None);
+    assert_eq!(detector.update(start + switch / 2, &seated(BASELINE), Some(BASELINE)), None);
+    assert_eq!(detector.update(start + switch, &torso(), Some(BASELINE)), None);
+    assert_eq!(detector.stance(), Stance::Sitting);
+
+    assert_eq!(detector.update(start + 2 * switch, &torso(), Some(BASELINE)), Some(Stance::Standing));
+    assert_eq!(detector.update(start + 3 * switch, &torso(), Some(BASELINE)), None);
+    assert_eq!(detector.stance(), Stance::Standing);
+}
+
+#[test]
+fn the_engine_reports_standing_and_nothing_while_away() {
+    let clock = ManualClock::new();
+    let mut engine = PostureEngine::new(0, 0, Some(BASELINE), clock.shared());
+    let feed = |engine: &mut PostureEngine, pose: Option<&Pose>, duration: Duration| {
+        let mut elapsed = Duration::ZERO;
+        while elapsed <= duration {
+            engine.process(pose);
+            clock.advance(FRAME);
+            elapsed += FRAME;
+        }
+    };
+
+    feed(&mut engine, Some(&seated(BASELINE)), Duration::from_secs(1));
+    assert_eq!(engine.stance(), Some(Stance::Sitting));
+    feed(&mut engine, Some(&seated(BASELINE - 2.0 * STANDING_RISE_PX)), Duration::from_secs(STANDING_SWITCH_SECS));
+    assert_eq!(engine.stance(), Some(Stance::Standing));
+
+    feed(&mut engine, None, Duration::from_secs(60));
+    assert!(engine.is_away());
+    assert_eq!(engine.stance(), None);
+    // Back at the desk, sitting again
+    feed(&mut engine, Some(&seated(BASELINE)), Duration::from_secs(1));
+    assert_eq!(engine.stance(), Some(Stance::Sitting));
+}
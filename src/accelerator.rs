@@ -0,0 +1,52 @@
+/// ONNX Runtime execution providers: which ones to try for the pose model, in which order,
+/// and how the one that ended up running it is named in the debug window and tray menu
+
+use ort::execution_providers::{
+    CPUExecutionProvider, CUDAExecutionProvider, DirectMLExecutionProvider, ExecutionProviderDispatch,
+};
+use serde::{Deserialize, Serialize};
+
+/// Where the pose model runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Accelerator {
+    /// The first GPU provider that loads, else the CPU
+    Auto,
+    /// Any DirectX 12 GPU (Windows 10 1903+)
+    DirectMl,
+    /// NVIDIA GPUs with the CUDA runtime installed
+    Cuda,
+    Cpu,
+}
+
+impl Accelerator {
+    /// Providers to try, in order; the CPU always comes last so the model loads somewhere
+    pub fn candidates(self) -> &'static [Accelerator] {
+        match self {
+            Accelerator::Auto => &[Accelerator::DirectMl, Accelerator::Cuda, Accelerator::Cpu],
+            Accelerator::DirectMl => &[Accelerator::DirectMl, Accelerator::Cpu],
+            Accelerator::Cuda => &[Accelerator::Cuda, Accelerator::Cpu],
+            Accelerator::Cpu => &[Accelerator::Cpu],
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Accelerator::Auto => "Auto",
+            Accelerator::DirectMl => "DirectML",
+            Accelerator::Cuda => "CUDA",
+            Accelerator::Cpu => "CPU",
+        }
+    }
+
+    /// The provider to register; fails the session build instead of silently using the CPU,
+    /// so the caller knows which one is actually running
+    pub fn execution_provider(self) -> ExecutionProviderDispatch {
+        match self {
+            // Auto never reaches a session; it's expanded by `candidates`
+            Accelerator::Auto | Accelerator::Cpu => CPUExecutionProvider::default().build(),
+            Accelerator::DirectMl => DirectMLExecutionProvider::default().build().error_on_failure(),
+            Accelerator::Cuda => CUDAExecutionProvider::default().build().error_on_failure(),
+        }
+    }
+}
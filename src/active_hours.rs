@@ -0,0 +1,167 @@
+/// Active hours: a daily window outside which detection sleeps and the camera is released.
+/// The window is learned from when the user was actually at the desk (seconds with a judged
+/// pose in the history database) and proposed once a day when it changes.
+
+use std::collections::BTreeSet;
+use std::fs;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use windows::core::HSTRING;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::{MessageBoxW, IDYES, MB_ICONQUESTION, MB_YESNO};
+
+use crate::app_data;
+use crate::config::{ACTIVE_HOURS_MIN_DAYS, ACTIVE_HOURS_TYPICAL_PERCENT};
+use crate::history::HistoryReader;
+use crate::i18n::{tr, tr_args};
+use crate::local_time::{self, LocalTime};
+use crate::notifications;
+use crate::settings::{SettingChange, Settings};
+use crate::tasks::CancelToken;
+
+const STATE_FILE: &str = "active-hours.json";
+const LEARN_DAYS: i64 = 28;
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+// Less presence than this in an hour doesn't count as being at the desk then
+const MIN_PRESENT_SECS: u32 = 5 * 60;
+
+/// Daily window in local hours, `start_hour` inclusive and `end_hour` exclusive.
+/// A window may wrap past midnight (22 to 6).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveHours {
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+impl ActiveHours {
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+
+    /// "08:00-18:00"
+    pub fn describe(&self) -> String {
+        format!("{:02}:00-{:02}:00", self.start_hour, self.end_hour)
+    }
+}
+
+/// Whether detection should run at `now`; no schedule means always
+pub fn is_active(hours: Option<ActiveHours>, now: &LocalTime) -> bool {
+    hours.is_none_or(|hours| hours.contains(now.hour as u32))
+}
+
+/// Seconds with someone in view during one local clock hour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PresenceHour {
+    /// Hours since the Unix epoch, in local time
+    pub local_hour: i64,
+    pub present_secs: u32,
+}
+
+/// The window covering the hours the user is typically at the desk, plus an hour on either side
+/// so a drifting routine still shows up in the history. None until ACTIVE_HOURS_MIN_DAYS days
+/// were recorded, or when the typical hours leave no real gap to sleep in.
+pub fn learn(presence: &[PresenceHour]) -> Option<ActiveHours> {
+    let active: Vec<(i64, usize)> = presence.iter()
+        .filter(|hour| hour.present_secs >= MIN_PRESENT_SECS)
+        .map(|hour| (hour.local_hour.div_euclid(24), hour.local_hour.rem_euclid(24) as usize))
+        .collect();
+    let days: BTreeSet<i64> = active.iter().map(|(day, _)| *day).collect();
+    if (days.len() as u32) < ACTIVE_HOURS_MIN_DAYS {
+        return None;
+    }
+
+    let mut days_by_hour = [0u32; 24];
+    for (_, hour) in &active {
+        days_by_hour[*hour] += 1;
+    }
+    let typical = days_by_hour.map(|count| count * 100 >= days.len() as u32 * ACTIVE_HOURS_TYPICAL_PERCENT);
+
+    // The longest run of untypical hours (wrapping past midnight) is the time to sleep
+    let mut gap: Option<(usize, usize)> = None; // (first hour, length)
+    for start in (0..24).filter(|&hour| !typical[hour] && typical[(hour + 23) % 24]) {
+        let length = (0..24).take_while(|offset| !typical[(start + offset) % 24]).count();
+        if gap.is_none_or(|(_, longest)| length > longest) {
+            gap = Some((start, length));
+        }
+    }
+    let (gap_start, gap_length) = gap?;
+    // The margins take an hour off each end of the gap
+    if gap_length <= 2 {
+        return None;
+    }
+    Some(ActiveHours {
+        start_hour: ((gap_start + gap_length + 23) % 24) as u32,
+        end_hour: ((gap_start + 1) % 24) as u32,
+    })
+}
+
+/// When the last proposal check ran and which proposal the user turned down
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+struct ProposalState {
+    last_checked: Option<String>,
+    declined: Option<ActiveHours>,
+}
+
+fn load_state() -> ProposalState {
+    app_data::file_path(STATE_FILE)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn store_state(state: &ProposalState) -> Result<()> {
+    app_data::write_atomic(&app_data::file_path(STATE_FILE)?, serde_json::to_string_pretty(state)?.as_bytes())
+}
+
+/// Whether today's check has yet to run
+pub fn is_check_due(today: &LocalTime) -> bool {
+    load_state().last_checked != Some(today.date_string())
+}
+
+pub fn mark_checked(today: &LocalTime) -> Result<()> {
+    store_state(&ProposalState { last_checked: Some(today.date_string()), ..load_state() })
+}
+
+fn ask(proposal: ActiveHours, current: Option<ActiveHours>) -> bool {
+    let current = current.map_or_else(|| tr("active_hours.always"), |hours| hours.describe());
+    let text = format!(
+        "{}\n\n{}",
+        tr_args("active_hours.proposal", &[("hours", &proposal.describe())]),
+        tr_args("active_hours.current", &[("hours", &current)]),
+    );
+    let (text, title) = (HSTRING::from(text), HSTRING::from(tr("active_hours.title")));
+    unsafe { MessageBoxW(HWND::default(), &text, &title, MB_YESNO | MB_ICONQUESTION) == IDYES }
+}
+
+/// Learns the window from the last four weeks and, if it differs from the current one, applies it
+/// (`auto_apply_active_hours`) or asks first. A declined proposal isn't offered again. Blocks while
+/// the question is open, so call it on a worker thread.
+pub fn run(history: &HistoryReader, settings: &Settings, cancel: &CancelToken) -> Result<Vec<SettingChange>> {
+    let since_ms = local_time::unix_millis() as i64 - LEARN_DAYS * DAY_MS;
+    let presence = history.presence_by_hour(since_ms, local_time::utc_offset_secs())?;
+    cancel.check()?;
+
+    let Some(proposal) = learn(&presence) else { return Ok(Vec::new()) };
+    if Some(proposal) == settings.active_hours || Some(proposal) == load_state().declined {
+        return Ok(Vec::new());
+    }
+
+    if settings.auto_apply_active_hours {
+        let message = tr_args("active_hours.applied", &[("hours", &proposal.describe())]);
+        if let Err(e) = notifications::show_toast(&tr("notification.title"), &message) {
+            warn!("Failed to show notification: {}", e);
+        }
+    } else if !ask(proposal, settings.active_hours) {
+        store_state(&ProposalState { declined: Some(proposal), ..load_state() })?;
+        return Ok(Vec::new());
+    }
+    Ok(vec![SettingChange::ActiveHours(Some(proposal))])
+}
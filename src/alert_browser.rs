@@ -0,0 +1,245 @@
+/// Alert history browser: every alert with its rule, duration and response time as an HTML page,
+/// filterable by day and rule, with an annotated camera thumbnail when snapshots are enabled
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use image::{imageops::FilterType, Rgb, RgbImage};
+
+use crate::app_data;
+use crate::history::{AlertRecord, HistoryReader};
+use crate::i18n::{tr, tr_args};
+use crate::local_time::{self, LocalTime};
+use crate::pipeline::FrameMeta;
+use crate::pose::Pose;
+use crate::tasks::CancelToken;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+const HISTORY_DAYS: i64 = 30;
+const THUMBNAIL_SIZE: (u32, u32) = (240, 180);
+
+/// Builds the `AlertRecord` for the alert in progress
+#[derive(Default)]
+pub struct AlertTracker {
+    current: Option<AlertRecord>,
+}
+
+impl AlertTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The overlay was triggered at `now_ms` after `slouch_ms` of slouching
+    pub fn start(&mut self, rule: &str, now_ms: i64, slouch_ms: i64, snapshot: Option<String>) {
+        self.current = Some(AlertRecord {
+            rule: rule.to_string(),
+            slouch_started_ms: now_ms - slouch_ms,
+            started_ms: now_ms,
+            ended_ms: now_ms,
+            peak_delta: None,
+            snapshot,
+            false_positive: false,
+        });
+    }
+
+    /// Tags the alert in progress as wrong. Returns false when none is in progress.
+    pub fn mark_false_positive(&mut self) -> bool {
+        match self.current.as_mut() {
+            Some(alert) => {
+                alert.false_positive = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Call every frame while alerting to track the deepest slouch
+    pub fn update(&mut self, delta: Option<f32>) {
+        if let (Some(alert), Some(delta)) = (self.current.as_mut(), delta) {
+            alert.peak_delta = Some(alert.peak_delta.map_or(delta, |peak| peak.max(delta)));
+        }
+    }
+
+    /// The finished alert, if one was in progress
+    pub fn finish(&mut self, now_ms: i64) -> Option<AlertRecord> {
+        let mut alert = self.current.take()?;
+        alert.ended_ms = now_ms;
+        Some(alert)
+    }
+}
+
+/// `recordings` in the app-data directory, created if missing
+pub fn recordings_dir() -> Result<PathBuf> {
+    let dir = app_data::dir()?.join("recordings");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn mark(image: &mut RgbImage, x: f32, y: f32, color: Rgb<u8>) {
+    let (cx, cy) = ((x * image.width() as f32) as i64, (y * image.height() as f32) as i64);
+    for py in cy - 2..=cy + 2 {
+        for px in cx - 2..=cx + 2 {
+            if px >= 0 && py >= 0 && (px as u32) < image.width() && (py as u32) < image.height() {
+                image.put_pixel(px as u32, py as u32, color);
+            }
+        }
+    }
+}
+
+/// Saves a small copy of `frame` with the detected keypoints and the baseline eye line
+/// (`baseline_y`, normalized) to the recordings folder. The name carries the frame's sequence
+/// number so it can be matched with the keypoint log. Returns the file name.
+pub fn save_snapshot(frame: &RgbImage, meta: &FrameMeta, pose: Option<&Pose>, baseline_y: Option<f32>, now_ms: i64) -> Result<String> {
+    let mut thumbnail = image::imageops::resize(frame, THUMBNAIL_SIZE.0, THUMBNAIL_SIZE.1, FilterType::Triangle);
+
+    if let Some(baseline_y) = baseline_y {
+        let y = (baseline_y * thumbnail.height() as f32) as u32;
+        if y < thumbnail.height() {
+            for x in 0..thumbnail.width() {
+                thumbnail.put_pixel(x, y, Rgb([0, 255, 255]));
+            }
+        }
+    }
+    for keypoint in pose.iter().flat_map(|pose| pose.iter()).filter(|kp| kp.is_confident()) {
+        mark(&mut thumbnail, keypoint.x, keypoint.y, Rgb([255, 40, 40]));
+    }
+
+    let name = format!("alert-{}-f{}.png", now_ms, meta.sequence);
+    thumbnail.save(recordings_dir()?.join(&name))?;
+    Ok(name)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn format_secs(ms: i64) -> String {
+    tr_args("alerts.seconds", &[("seconds", &format!("{:.1}", ms.max(0) as f64 / 1000.0))])
+}
+
+/// Display name of a rule key
+pub fn rule_label(rule: &str) -> String {
+    let key = format!("alerts.rule_{}", rule);
+    let label = tr(&key);
+    // Unknown rules (from a newer version) fall back to their stored name
+    if label == key { rule.to_string() } else { label }
+}
+
+fn rule_cell(alert: &AlertRecord) -> String {
+    let label = escape(&rule_label(&alert.rule));
+    if alert.false_positive {
+        format!("{} <span class=\"wrong\">({})</span>", label, escape(&tr("alerts.marked_wrong")))
+    } else {
+        label
+    }
+}
+
+/// Builds the alert page for `alerts` (newest first). Thumbnails are linked from
+/// `../recordings/`, relative to the reports folder the page is written to.
+pub fn render_html(alerts: &[AlertRecord], utc_offset_secs: i64) -> String {
+    let local = |ms: i64| LocalTime::from_unix_secs(ms.div_euclid(1000), utc_offset_secs);
+    let days: BTreeSet<String> = alerts.iter().map(|alert| local(alert.started_ms).date_string()).collect();
+    let rules: BTreeSet<&str> = alerts.iter().map(|alert| alert.rule.as_str()).collect();
+
+    let mut html = String::new();
+    let _ = writeln!(
+        html,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title>\n<style>\n\
+         body {{ font-family: 'Segoe UI', sans-serif; margin: 2em auto; max-width: 900px; color: #222; }}\n\
+         th {{ text-align: left; font-weight: normal; color: #555; padding-right: 1em; }}\n\
+         td {{ padding: 4px 1em 4px 0; vertical-align: top; }}\n\
+         img {{ width: 160px; border-radius: 4px; }}\n\
+         .empty, .generated, .wrong {{ color: #777; }}\n\
+         </style></head><body>\n<h1>{title}</h1>",
+        title = escape(&tr("alerts.title")),
+    );
+
+    if alerts.is_empty() {
+        let _ = writeln!(html, "<p class=\"empty\">{}</p>", escape(&tr("alerts.none")));
+    } else {
+        // Filters: plain selects that hide non-matching rows
+        let _ = writeln!(html, "<p><select id=\"day\" onchange=\"filter()\"><option value=\"\">{}</option>", escape(&tr("alerts.all_days")));
+        for day in days.iter().rev() {
+            let _ = writeln!(html, "<option>{}</option>", escape(day));
+        }
+        let _ = writeln!(html, "</select> <select id=\"rule\" onchange=\"filter()\"><option value=\"\">{}</option>", escape(&tr("alerts.all_rules")));
+        for rule in &rules {
+            let _ = writeln!(html, "<option value=\"{}\">{}</option>", escape(rule), escape(&rule_label(rule)));
+        }
+        let _ = writeln!(html, "</select></p>");
+
+        let _ = writeln!(html, "<table>\n<tr>");
+        for key in ["alerts.time", "alerts.rule", "alerts.duration", "alerts.response", "alerts.peak", "alerts.snapshot"] {
+            let _ = write!(html, "<th>{}</th>", escape(&tr(key)));
+        }
+        let _ = writeln!(html, "</tr>");
+
+        for alert in alerts {
+            let started = local(alert.started_ms);
+            let peak = alert.peak_delta.map(|delta| format!("{:.0} px", delta)).unwrap_or_default();
+            let snapshot = alert.snapshot.as_deref()
+                .map(|name| format!("<img src=\"../recordings/{}\" alt=\"\">", escape(name)))
+                .unwrap_or_default();
+            let _ = writeln!(
+                html,
+                "<tr data-day=\"{day}\" data-rule=\"{rule}\"><td>{day} {:02}:{:02}:{:02}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                started.hour,
+                started.minute,
+                started.second,
+                rule_cell(alert),
+                escape(&format_secs(alert.duration_ms())),
+                escape(&format_secs(alert.response_ms())),
+                escape(&peak),
+                snapshot,
+                day = escape(&started.date_string()),
+                rule = escape(&alert.rule),
+            );
+        }
+        let _ = writeln!(
+            html,
+            "</table>\n<script>\n\
+             function filter() {{\n\
+               const day = document.getElementById('day').value;\n\
+               const rule = document.getElementById('rule').value;\n\
+               for (const row of document.querySelectorAll('tr[data-day]')) {{\n\
+                 row.hidden = (day && row.dataset.day !== day) || (rule && row.dataset.rule !== rule);\n\
+               }}\n\
+             }}\n\
+             </script>"
+        );
+    }
+
+    let now = LocalTime::now();
+    let generated = format!("{} {:02}:{:02}", now.date_string(), now.hour, now.minute);
+    let _ = writeln!(
+        html,
+        "<p class=\"generated\">{}</p>\n</body></html>",
+        escape(&tr_args("report.generated", &[("time", &generated)])),
+    );
+    html
+}
+
+/// Writes `reports/alert-history.html` for the last 30 days and returns its path.
+/// Runs on a worker thread; stops between steps once `cancel` fires.
+pub fn generate(history: &HistoryReader, cancel: &CancelToken) -> Result<PathBuf> {
+    let now_ms = local_time::unix_millis() as i64;
+    let mut alerts = history.alerts_since(now_ms - HISTORY_DAYS * DAY_MS)?;
+    cancel.check()?;
+
+    // Thumbnails may already have been removed by the retention cleanup
+    let recordings = recordings_dir()?;
+    for alert in &mut alerts {
+        if alert.snapshot.as_ref().is_some_and(|name| !recordings.join(name).exists()) {
+            alert.snapshot = None;
+        }
+    }
+    cancel.check()?;
+
+    let dir = app_data::dir()?.join("reports");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("alert-history.html");
+    app_data::write_atomic(&path, render_html(&alerts, local_time::utc_offset_secs()).as_bytes())?;
+    Ok(path)
+}
@@ -0,0 +1,240 @@
+/// Local API for external dashboards and stream overlays: an HTTP server reachable from this PC
+/// only. `GET /state` answers the live state, score, delta and counters, `POST /pause`,
+/// `POST /resume` and `POST /calibrate` control the app, and `/stream` upgrades to a WebSocket
+/// that gets every frame's keypoints as JSON.
+
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::dashboard::{self, LiveState, Request, Response};
+use crate::engine::EngineStats;
+use crate::integrations;
+use crate::pose::Pose;
+use crate::websocket;
+
+// A stream client that can't take a frame this fast is dropped rather than stalling the others
+const STREAM_WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// What a client asked the app to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiCommand {
+    /// Pause detection, for this many minutes or until resumed
+    Pause { minutes: Option<u64> },
+    Resume,
+    Calibrate,
+}
+
+/// Optional body of `POST /pause`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PauseRequest {
+    minutes: Option<u64>,
+}
+
+/// `GET /state`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ApiState {
+    pub state: LiveState,
+    /// Share of judged frames with good posture since the app started, in percent
+    pub score: Option<u64>,
+    /// How far (pixels) the eyes are below the baseline
+    pub delta_px: Option<f32>,
+    pub frames: u64,
+    pub good_frames: u64,
+    pub bad_frames: u64,
+    pub alerts: u32,
+}
+
+impl ApiState {
+    pub fn new(state: LiveState, delta_px: Option<f32>, stats: &EngineStats) -> Self {
+        Self {
+            state,
+            score: integrations::posture_score(stats),
+            delta_px,
+            frames: stats.frames,
+            good_frames: stats.good_frames,
+            bad_frames: stats.bad_frames,
+            alerts: stats.alerts,
+        }
+    }
+}
+
+/// One message on `/stream`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StreamFrame<'a> {
+    /// Unix milliseconds
+    pub timestamp: i64,
+    pub state: LiveState,
+    /// The 17 keypoints in frame coordinates (0..1), COCO order; None when nobody was found
+    pub keypoints: Option<&'a Pose>,
+}
+
+/// Whether `host` ("name" or "name:port") is this PC
+fn is_loopback(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    matches!(name, "localhost" | "127.0.0.1" | "::1")
+}
+
+/// Whether the request was addressed to this PC by name, so a web page can't reach the API
+/// through a domain that resolves to 127.0.0.1 (DNS rebinding)
+pub fn host_allowed(request: &Request) -> bool {
+    match request.header("host") {
+        Some(host) => is_loopback(host),
+        None => true,
+    }
+}
+
+/// Whether a request may control the app or open the stream: browsers name the page's origin,
+/// and only pages from this PC (or local files, origin "null") are let in
+pub fn origin_allowed(request: &Request) -> bool {
+    match request.header("origin") {
+        None | Some("null") => true,
+        Some(origin) => origin.strip_prefix("http://").is_some_and(is_loopback),
+    }
+}
+
+/// The answer to a plain (non-WebSocket) request
+pub fn route(request: &Request, state: &ApiState, commands: &Sender<ApiCommand>) -> Response {
+    let is_command = matches!(request.path.as_str(), "/pause" | "/resume" | "/calibrate");
+    if is_command && !origin_allowed(request) {
+        return Response::error(403, "Only pages on this PC may control the app");
+    }
+    let command = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/state") => return Response::json(state),
+        ("POST", "/pause") if request.body.iter().all(u8::is_ascii_whitespace) => ApiCommand::Pause { minutes: None },
+        ("POST", "/pause") => match serde_json::from_slice::<PauseRequest>(&request.body) {
+            Ok(PauseRequest { minutes: Some(0) }) => return Response::error(400, "minutes must be above 0"),
+            Ok(body) => ApiCommand::Pause { minutes: body.minutes },
+            Err(e) => return Response::error(400, &e.to_string()),
+        },
+        ("POST", "/resume") => ApiCommand::Resume,
+        ("POST", "/calibrate") => ApiCommand::Calibrate,
+        (_, "/state" | "/pause" | "/resume" | "/calibrate" | "/stream") => return Response::error(405, "Method not allowed"),
+        _ => return Response::error(404, "Not found"),
+    };
+    let _ = commands.send(command);
+    Response::json(&serde_json::json!({ "ok": true }))
+}
+
+/// State shared between the main loop and the server threads
+struct Shared {
+    state: ApiState,
+    /// New `/stream` connections, picked up by the broadcaster
+    streams: Vec<TcpStream>,
+    /// Connections the broadcaster writes to
+    stream_clients: usize,
+}
+
+fn serve(mut stream: TcpStream, shared: &Mutex<Shared>, commands: &Sender<ApiCommand>) -> Result<()> {
+    let request = match dashboard::read_request(&mut stream)? {
+        Ok(request) => request,
+        Err(response) => return Ok(stream.write_all(&response.to_bytes())?),
+    };
+    if !host_allowed(&request) {
+        return Ok(stream.write_all(&Response::error(403, "Unknown host").to_bytes())?);
+    }
+    let lock = || shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let upgrade = request.header("upgrade").is_some_and(|upgrade| upgrade.eq_ignore_ascii_case("websocket"));
+    match (request.path.as_str(), request.header("sec-websocket-key")) {
+        ("/stream", Some(key)) if request.method == "GET" && upgrade => {
+            if !origin_allowed(&request) {
+                return Ok(stream.write_all(&Response::error(403, "Only pages on this PC may open the stream").to_bytes())?);
+            }
+            stream.write_all(websocket::handshake_response(key).as_bytes())?;
+            stream.set_write_timeout(Some(STREAM_WRITE_TIMEOUT))?;
+            lock().streams.push(stream);
+        }
+        ("/stream", _) => stream.write_all(&Response::error(400, "Expected a WebSocket upgrade").to_bytes())?,
+        _ => {
+            let state = lock().state;
+            stream.write_all(&route(&request, &state, commands).to_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+pub struct Api {
+    shared: Arc<Mutex<Shared>>,
+    commands: Receiver<ApiCommand>,
+    frames: Sender<String>,
+}
+
+impl Api {
+    /// Starts serving on `port` of 127.0.0.1
+    pub fn start(port: u16) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port)).map_err(|e| anyhow!("Port {} unavailable: {}", port, e))?;
+        let shared = Arc::new(Mutex::new(Shared {
+            state: ApiState::new(LiveState::NotCalibrated, None, &EngineStats::default()),
+            streams: Vec::new(),
+            stream_clients: 0,
+        }));
+
+        let (command_sender, commands) = mpsc::channel();
+        let server_shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            // One request at a time, like the dashboard; streams are handed to the broadcaster
+            for stream in listener.incoming().flatten() {
+                if let Err(e) = serve(stream, &server_shared, &command_sender) {
+                    debug!("API request failed: {}", e);
+                }
+            }
+        });
+
+        // Writes each frame to every stream client, dropping those that went away
+        let (frames, frame_receiver) = mpsc::channel::<String>();
+        let broadcast_shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            let mut clients: Vec<TcpStream> = Vec::new();
+            for json in frame_receiver {
+                let mut shared = broadcast_shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                clients.append(&mut shared.streams);
+                drop(shared);
+                let frame = websocket::text_frame(&json);
+                clients.retain_mut(|client| client.write_all(&frame).is_ok());
+                broadcast_shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).stream_clients = clients.len();
+            }
+        });
+
+        Ok(Self { shared, commands, frames })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Shared> {
+        self.shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Updates what `GET /state` answers
+    pub fn publish(&self, state: ApiState) {
+        self.lock().state = state;
+    }
+
+    /// Sends this frame's keypoints to the `/stream` clients, if there are any
+    pub fn stream(&self, frame: &StreamFrame) {
+        let shared = self.lock();
+        if shared.stream_clients == 0 && shared.streams.is_empty() {
+            return;
+        }
+        drop(shared);
+        match serde_json::to_string(frame) {
+            Ok(json) => {
+                let _ = self.frames.send(json);
+            }
+            Err(e) => warn!("Failed to serialize a stream frame: {}", e),
+        }
+    }
+
+    /// Commands received since the last call
+    pub fn poll_commands(&self) -> Vec<ApiCommand> {
+        self.commands.try_iter().collect()
+    }
+}
@@ -0,0 +1,52 @@
+/// Per-application exemptions: no blur (or no detection at all) while a listed app is in the
+/// foreground, e.g. a full-screen video player, a game or a video call
+
+use serde::{Deserialize, Serialize};
+
+use crate::processes;
+
+/// What a foreground app switches off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Exemption {
+    /// The overlay stays clear; detection, toasts and sounds carry on
+    Overlay,
+    /// Detection pauses as if paused from the tray
+    Detection,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppExemption {
+    /// Executable name, e.g. "vlc.exe" ("vlc" matches too)
+    pub process: String,
+    pub exempt: Exemption,
+}
+
+impl AppExemption {
+    pub fn new(process: &str, exempt: Exemption) -> Self {
+        Self { process: process.to_string(), exempt }
+    }
+}
+
+fn without_exe(name: &str) -> &str {
+    match name.len().checked_sub(4) {
+        Some(stem) if name.is_char_boundary(stem) && name[stem..].eq_ignore_ascii_case(".exe") => &name[..stem],
+        _ => name,
+    }
+}
+
+/// The exemption for `process`, matched case-insensitively with or without ".exe";
+/// the first matching entry wins
+pub fn exemption_for(exemptions: &[AppExemption], process: &str) -> Option<Exemption> {
+    let process = without_exe(process);
+    exemptions.iter()
+        .find(|app| without_exe(&app.process).eq_ignore_ascii_case(process))
+        .map(|app| app.exempt)
+}
+
+/// The foreground app's executable name and its exemption, if it has one
+pub fn foreground_exemption(exemptions: &[AppExemption]) -> Option<(String, Exemption)> {
+    let process = processes::foreground_process_name()?;
+    let exempt = exemption_for(exemptions, &process)?;
+    Some((process, exempt))
+}
@@ -0,0 +1,97 @@
+/// "Start with Windows": a value under HKCU\...\Run pointing at this executable
+///
+/// The entry is the only state; the tray checkbox reads it back. If the executable the entry
+/// starts no longer exists (an update or a new install folder), `migrate` points it at this one.
+/// An entry for a path that still exists is left alone, so a dev build can't take it over.
+
+use std::ffi::c_void;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use windows::core::{w, HSTRING, PCWSTR};
+use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+use windows::Win32::System::Registry::{
+    RegDeleteKeyValueW, RegGetValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ, RRF_RT_REG_SZ,
+};
+
+const RUN_KEY: PCWSTR = w!("Software\\Microsoft\\Windows\\CurrentVersion\\Run");
+const VALUE_NAME: PCWSTR = w!("PostureAI");
+
+/// The Run entry's command for `exe`, quoted since install paths often contain spaces
+pub fn command_for(exe: &Path) -> String {
+    format!("\"{}\"", exe.display())
+}
+
+/// The executable a Run entry's command starts, without quotes or arguments
+pub fn executable_of(command: &str) -> PathBuf {
+    let command = command.trim();
+    match command.strip_prefix('"') {
+        Some(rest) => PathBuf::from(rest.split('"').next().unwrap_or(rest)),
+        None => PathBuf::from(command.split_whitespace().next().unwrap_or(command)),
+    }
+}
+
+/// Whether a `registered` entry starts something other than `command` and its executable is
+/// gone, per `exists` (paths compare case-insensitively, like Windows does)
+pub fn needs_migration(registered: Option<&str>, command: &str, exists: impl Fn(&Path) -> bool) -> bool {
+    registered.is_some_and(|registered| {
+        !registered.trim().eq_ignore_ascii_case(command) && !exists(&executable_of(registered))
+    })
+}
+
+fn current_command() -> Result<String> {
+    let exe = std::env::current_exe().context("Executable path unavailable")?;
+    Ok(command_for(&exe))
+}
+
+/// The command the Run entry starts, if there is one
+pub fn registered_command() -> Option<String> {
+    unsafe {
+        let mut size = 0u32;
+        RegGetValueW(HKEY_CURRENT_USER, RUN_KEY, VALUE_NAME, RRF_RT_REG_SZ, None, None, Some(&mut size)).ok()?;
+        let mut buffer = vec![0u16; size as usize / 2];
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            RUN_KEY,
+            VALUE_NAME,
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr() as *mut c_void),
+            Some(&mut size),
+        )
+        .ok()?;
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        Some(String::from_utf16_lossy(&buffer[..len]))
+    }
+}
+
+pub fn is_enabled() -> bool {
+    registered_command().is_some()
+}
+
+/// Starts this executable at sign-in
+pub fn enable() -> Result<()> {
+    let command = HSTRING::from(current_command()?);
+    // REG_SZ data includes the terminating null
+    let bytes = (command.len() + 1) * std::mem::size_of::<u16>();
+    unsafe {
+        RegSetKeyValueW(HKEY_CURRENT_USER, RUN_KEY, VALUE_NAME, REG_SZ.0, Some(command.as_ptr() as *const c_void), bytes as u32)
+            .context("Failed to write the Run registry entry")
+    }
+}
+
+pub fn disable() -> Result<()> {
+    match unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, RUN_KEY, VALUE_NAME) } {
+        Err(e) if e.code() == ERROR_FILE_NOT_FOUND.to_hresult() => Ok(()),
+        result => result.context("Failed to remove the Run registry entry"),
+    }
+}
+
+/// Rewrites an existing entry whose executable no longer exists. Returns true if it did.
+pub fn migrate() -> Result<bool> {
+    if !needs_migration(registered_command().as_deref(), &current_command()?, Path::exists) {
+        return Ok(false);
+    }
+    enable()?;
+    Ok(true)
+}
@@ -1,63 +1,466 @@
 /// Windows API blur overlay functionality for posture detection
 
+use std::time::Instant;
+
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::error;
 use windows::core::s;
-use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::Foundation::{BOOL, COLORREF, HWND, LPARAM, LRESULT, POINT, RECT, SIZE, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    CombineRgn, CreateCompatibleDC, CreateDIBSection, CreateRectRgn, CreateSolidBrush, DeleteDC, DeleteObject,
+    EnumDisplayMonitors, FillRect, GetMonitorInfoW, MonitorFromWindow, SelectObject, SetWindowRgn, AC_SRC_ALPHA,
+    AC_SRC_OVER, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, BLENDFUNCTION, DIB_RGB_COLORS, HBITMAP, HDC, HGDIOBJ, HMONITOR,
+    MONITORINFO, MONITOR_DEFAULTTONEAREST, RGN_DIFF,
+};
 use windows::Win32::System::LibraryLoader::{GetModuleHandleA, GetProcAddress, LoadLibraryA};
 use windows::Win32::UI::WindowsAndMessaging::*;
 
-use crate::config::{MAX_ALPHA, FADE_SPEED};
+use crate::clock::SharedClock;
+use crate::config::{MAX_ALPHA, FADE_MS, OVERLAY_TINT_COLOR};
+use crate::error::PostureError;
+use crate::escalation::Severity;
 
-pub struct BlurOverlay {
+/// How the overlay obscures the screen while posture is bad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayEffect {
+    /// Frosted-glass blur (undocumented DWM accent; broken on some Windows builds)
+    AcrylicBlur,
+    /// Darkens the screen
+    Dim,
+    /// Colored wash (`OVERLAY_TINT_COLOR`)
+    Tint,
+    /// Red edges fading into a clear center
+    RedVignette,
+}
+
+/// A screen area in virtual-screen pixels (right and bottom exclusive); the primary monitor's
+/// top-left corner is (0, 0)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenRect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl ScreenRect {
+    pub fn is_empty(&self) -> bool {
+        self.right <= self.left || self.bottom <= self.top
+    }
+
+    /// The part of `self` on `monitor`, relative to the monitor's top-left corner;
+    /// None if they don't overlap
+    pub fn local_to(&self, monitor: &ScreenRect) -> Option<ScreenRect> {
+        let clipped = ScreenRect {
+            left: self.left.max(monitor.left) - monitor.left,
+            top: self.top.max(monitor.top) - monitor.top,
+            right: self.right.min(monitor.right) - monitor.left,
+            bottom: self.bottom.min(monitor.bottom) - monitor.top,
+        };
+        (!clipped.is_empty()).then_some(clipped)
+    }
+}
+
+impl From<RECT> for ScreenRect {
+    fn from(rect: RECT) -> Self {
+        Self { left: rect.left, top: rect.top, right: rect.right, bottom: rect.bottom }
+    }
+}
+
+/// Areas the overlay leaves clear so critical information stays readable during alerts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Exclusions {
+    pub rects: Vec<ScreenRect>,
+    /// Leave each monitor's taskbar (everything outside its work area) clear
+    pub taskbar: bool,
+}
+
+// Marks a GWLP_USERDATA value as a background color to paint (0 = don't paint)
+const PAINT_FLAG: isize = 1 << 24;
+
+/// 0xRRGGBB -> COLORREF (0x00BBGGRR)
+fn colorref(rgb: u32) -> COLORREF {
+    COLORREF(((rgb & 0xFF) << 16) | (rgb & 0xFF00) | ((rgb >> 16) & 0xFF))
+}
+
+/// Vignette opacity at (x, y): clear in the middle, strongest in the corners
+fn vignette_alpha(x: i32, y: i32, width: i32, height: i32) -> u8 {
+    let dx = (x as f32 + 0.5) / width as f32 * 2.0 - 1.0;
+    let dy = (y as f32 + 0.5) / height as f32 * 2.0 - 1.0;
+    let distance = (dx * dx + dy * dy).sqrt() / std::f32::consts::SQRT_2;
+    let t = ((distance - 0.45) / 0.55).clamp(0.0, 1.0);
+    (t * t * 255.0) as u8
+}
+
+/// Premultiplied 32-bit image shown with `UpdateLayeredWindow` (per-pixel alpha)
+struct LayeredBitmap {
+    dc: HDC,
+    bitmap: HBITMAP,
+    previous: HGDIOBJ,
+    size: SIZE,
+}
+
+impl LayeredBitmap {
+    fn vignette(width: i32, height: i32) -> Result<Self> {
+        unsafe {
+            let info = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: width,
+                    biHeight: -height, // top-down rows
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let dc = CreateCompatibleDC(HDC::default());
+            let mut bits = std::ptr::null_mut();
+            let bitmap = match CreateDIBSection(dc, &info, DIB_RGB_COLORS, &mut bits, None, 0) {
+                Ok(bitmap) => bitmap,
+                Err(e) => {
+                    DeleteDC(dc);
+                    return Err(e.into());
+                }
+            };
+            let previous = SelectObject(dc, bitmap);
+
+            // BGRA, premultiplied: red scaled by its own alpha
+            let pixels = std::slice::from_raw_parts_mut(bits as *mut u32, (width * height) as usize);
+            for y in 0..height {
+                for x in 0..width {
+                    let alpha = vignette_alpha(x, y, width, height) as u32;
+                    let red = 200 * alpha / 255;
+                    pixels[(y * width + x) as usize] = (alpha << 24) | (red << 16);
+                }
+            }
+
+            Ok(Self { dc, bitmap, previous, size: SIZE { cx: width, cy: height } })
+        }
+    }
+
+    /// Shows the image on `hwnd`, scaled by `alpha` overall
+    fn show(&self, hwnd: HWND, alpha: u8) {
+        let blend = BLENDFUNCTION {
+            BlendOp: AC_SRC_OVER as u8,
+            BlendFlags: 0,
+            SourceConstantAlpha: alpha,
+            AlphaFormat: AC_SRC_ALPHA as u8,
+        };
+        unsafe {
+            let _ = UpdateLayeredWindow(
+                hwnd,
+                HDC::default(),
+                None,
+                Some(&self.size),
+                self.dc,
+                Some(&POINT::default()),
+                COLORREF(0),
+                Some(&blend),
+                ULW_ALPHA,
+            );
+        }
+    }
+}
+
+impl Drop for LayeredBitmap {
+    fn drop(&mut self) {
+        unsafe {
+            SelectObject(self.dc, self.previous);
+            DeleteObject(self.bitmap);
+            DeleteDC(self.dc);
+        }
+    }
+}
+
+/// One overlay window covering a single monitor
+struct MonitorOverlay {
     hwnd: HWND,
+    monitor: HMONITOR,
+    bounds: ScreenRect,
+    /// The monitor minus its taskbar
+    work_area: ScreenRect,
     current_alpha: u32,
+    /// Per-pixel image for `RedVignette`
+    vignette: Option<LayeredBitmap>,
+}
+
+pub struct BlurOverlay {
+    effect: OverlayEffect,
+    windows: Vec<MonitorOverlay>,
+    monitor_count: i32,
+    /// Only blur the monitor with the foreground window
+    foreground_only: bool,
+    exclusions: Exclusions,
+    /// Take mouse clicks instead of letting them through (the ladder's `Severity::Block`)
+    blocks_input: bool,
+    /// Duration of a full fade (FADE_MS unless changed in the settings)
+    fade_ms: u64,
     target_alpha: u32,
+    clock: SharedClock,
+    last_update: Instant,
+}
+
+unsafe extern "system" fn collect_monitor(monitor: HMONITOR, _: HDC, rect: *mut RECT, data: LPARAM) -> BOOL {
+    let monitors = &mut *(data.0 as *mut Vec<(HMONITOR, RECT)>);
+    monitors.push((monitor, *rect));
+    true.into()
+}
+
+/// Every attached monitor with its bounds in virtual-screen coordinates
+fn monitors() -> Vec<(HMONITOR, RECT)> {
+    let mut monitors: Vec<(HMONITOR, RECT)> = Vec::new();
+    unsafe {
+        EnumDisplayMonitors(HDC::default(), None, Some(collect_monitor), LPARAM(&mut monitors as *mut _ as isize));
+    }
+    monitors
+}
+
+/// The monitor's work area (without taskbar and docked toolbars); `bounds` if unknown
+fn work_area(monitor: HMONITOR, bounds: RECT) -> RECT {
+    let mut info = MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+    if unsafe { GetMonitorInfoW(monitor, &mut info) }.as_bool() {
+        info.rcWork
+    } else {
+        bounds
+    }
 }
 
 impl BlurOverlay {
-    pub fn new() -> Result<Self> {
+    pub fn new(clock: SharedClock, effect: OverlayEffect, foreground_only: bool) -> Result<Self, PostureError> {
         unsafe {
-            let instance = GetModuleHandleA(None)?;
-            let class_name = s!("PostureBlurClass");
-
+            let instance = GetModuleHandleA(None).map_err(PostureError::overlay)?;
             let wc = WNDCLASSA {
                 hInstance: instance.into(),
-                lpszClassName: class_name,
+                lpszClassName: s!("PostureBlurClass"),
                 lpfnWndProc: Some(Self::wnd_proc),
                 ..Default::default()
             };
             RegisterClassA(&wc);
+        }
 
-            // Create window: Topmost, Transparent (Click-through), ToolWindow (No Taskbar)
-            let hwnd = CreateWindowExA(
-                WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_LAYERED | WS_EX_TRANSPARENT,
-                class_name,
-                s!(""),
-                WS_POPUP,
-                0, 0,
-                GetSystemMetrics(SM_CXSCREEN),
-                GetSystemMetrics(SM_CYSCREEN),
-                None,
-                None,
-                instance,
-                None,
-            );
+        let last_update = clock.now();
+        let mut overlay = Self {
+            effect,
+            windows: Vec::new(),
+            monitor_count: 0,
+            foreground_only,
+            exclusions: Exclusions::default(),
+            blocks_input: false,
+            fade_ms: FADE_MS,
+            target_alpha: 0,
+            clock,
+            last_update,
+        };
+        overlay.create_windows().map_err(PostureError::Overlay)?;
+        Ok(overlay)
+    }
 
-            Ok(Self {
-                hwnd,
-                current_alpha: 0,
-                target_alpha: 0
-            })
+    /// (Re)creates one window per monitor, e.g. after a display was plugged in
+    fn create_windows(&mut self) -> Result<()> {
+        self.destroy_windows();
+        unsafe {
+            let instance = GetModuleHandleA(None)?;
+            self.monitor_count = GetSystemMetrics(SM_CMONITORS);
+
+            for (monitor, rect) in monitors() {
+                let vignette = match self.effect {
+                    OverlayEffect::RedVignette => Some(LayeredBitmap::vignette(rect.right - rect.left, rect.bottom - rect.top)?),
+                    _ => None,
+                };
+
+                // Create window: Topmost, Transparent (Click-through), ToolWindow (No Taskbar)
+                let click_through = if self.blocks_input { WINDOW_EX_STYLE(0) } else { WS_EX_TRANSPARENT };
+                let hwnd = CreateWindowExA(
+                    WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_LAYERED | click_through,
+                    s!("PostureBlurClass"),
+                    s!(""),
+                    WS_POPUP,
+                    rect.left,
+                    rect.top,
+                    rect.right - rect.left,
+                    rect.bottom - rect.top,
+                    None,
+                    None,
+                    instance,
+                    None,
+                );
+
+                // Keep the overlay out of screenshots and recordings (Graphics Capture, OBS, etc.).
+                // Requires Windows 10 2004+; older builds just ignore it.
+                let _ = SetWindowDisplayAffinity(hwnd, WDA_EXCLUDEFROMCAPTURE);
+
+                // Background painted by wnd_proc for the solid effects
+                let paint = match self.effect {
+                    OverlayEffect::Dim => PAINT_FLAG,
+                    OverlayEffect::Tint => PAINT_FLAG | colorref(OVERLAY_TINT_COLOR).0 as isize,
+                    OverlayEffect::AcrylicBlur | OverlayEffect::RedVignette => 0,
+                };
+                SetWindowLongPtrW(hwnd, GWLP_USERDATA, paint);
+
+                let window = MonitorOverlay {
+                    hwnd,
+                    monitor,
+                    bounds: rect.into(),
+                    work_area: work_area(monitor, rect).into(),
+                    current_alpha: 0,
+                    vignette,
+                };
+                window.apply_exclusions(&self.exclusions);
+                self.windows.push(window);
+            }
+        }
+        Ok(())
+    }
+
+    fn destroy_windows(&mut self) {
+        for window in self.windows.drain(..) {
+            unsafe {
+                let _ = DestroyWindow(window.hwnd);
+            }
         }
     }
 
-    pub fn set_target_visible(&mut self, visible: bool) {
-        self.target_alpha = if visible { MAX_ALPHA } else { 0 };
+    /// Every overlay window exists and is still a layered (alpha-blended) window
+    pub fn is_ready(&self) -> bool {
+        !self.windows.is_empty() && self.windows.iter().all(|window| unsafe {
+            IsWindow(window.hwnd).as_bool() && (GetWindowLongW(window.hwnd, GWL_EXSTYLE) as u32 & WS_EX_LAYERED.0) != 0
+        })
+    }
+
+    /// Switches the effect, recreating the overlay windows
+    pub fn set_effect(&mut self, effect: OverlayEffect) -> Result<(), PostureError> {
+        if effect != self.effect {
+            self.effect = effect;
+            self.create_windows().map_err(PostureError::Overlay)?;
+        }
+        Ok(())
+    }
+
+    /// Switches to an escalation step's look: the dim or the acrylic blur, taking the mouse clicks
+    /// at `Severity::Block`. None keeps the current effect to fade out with. The strength is set
+    /// separately (`Severity::overlay_intensity`).
+    pub fn set_severity(&mut self, severity: Option<Severity>) -> Result<(), PostureError> {
+        self.set_blocks_input(severity == Some(Severity::Block));
+        match severity {
+            Some(Severity::Dim) => self.set_effect(OverlayEffect::Dim)?,
+            Some(Severity::Blur | Severity::Block) => self.set_effect(OverlayEffect::AcrylicBlur)?,
+            Some(Severity::Toast) | None => {}
+        }
+        Ok(())
+    }
+
+    fn set_blocks_input(&mut self, blocks_input: bool) {
+        if blocks_input == self.blocks_input {
+            return;
+        }
+        self.blocks_input = blocks_input;
+        for window in &self.windows {
+            unsafe {
+                let style = GetWindowLongW(window.hwnd, GWL_EXSTYLE) as u32;
+                let style = if blocks_input { style & !WS_EX_TRANSPARENT.0 } else { style | WS_EX_TRANSPARENT.0 };
+                SetWindowLongW(window.hwnd, GWL_EXSTYLE, style as i32);
+            }
+        }
+    }
+
+    pub fn set_fade_ms(&mut self, fade_ms: u64) {
+        self.fade_ms = fade_ms.max(1);
+    }
+
+    /// Cuts `exclusions` out of the overlay windows
+    pub fn set_exclusions(&mut self, exclusions: Exclusions) {
+        self.exclusions = exclusions;
+        for window in &self.windows {
+            window.apply_exclusions(&self.exclusions);
+        }
+    }
+
+    /// Intensity currently shown (0.0 = hidden, 1.0 = MAX_ALPHA) on the most visible monitor
+    pub fn intensity(&self) -> f32 {
+        let alpha = self.windows.iter().map(|window| window.current_alpha).max().unwrap_or(0);
+        alpha as f32 / MAX_ALPHA as f32
+    }
+
+    /// Fades towards `intensity` (0.0 = hidden, 1.0 = MAX_ALPHA)
+    pub fn set_target_intensity(&mut self, intensity: f32) {
+        self.target_alpha = (MAX_ALPHA as f32 * intensity.clamp(0.0, 1.0)).round() as u32;
     }
 
     // Runs every frame to smooth out the alpha transition
     pub fn update(&mut self) {
-        if self.current_alpha == self.target_alpha {
+        let now = self.clock.now();
+        let elapsed = now.saturating_duration_since(self.last_update);
+        self.last_update = now;
+
+        // Follow monitors being attached or removed (only while nothing is shown)
+        if self.target_alpha == 0 && unsafe { GetSystemMetrics(SM_CMONITORS) } != self.monitor_count {
+            if let Err(e) = self.create_windows() {
+                error!("Failed to recreate overlay windows: {}", e);
+            }
+        }
+
+        let active_monitor = self.foreground_only
+            .then(|| unsafe { MonitorFromWindow(GetForegroundWindow(), MONITOR_DEFAULTTONEAREST) });
+
+        // Interpolate Alpha - a full fade takes `fade_ms` regardless of frame rate
+        let step = ((MAX_ALPHA as u128 * elapsed.as_millis()) / self.fade_ms as u128).max(1) as u32;
+        for window in &mut self.windows {
+            let target = match active_monitor {
+                Some(monitor) if monitor != window.monitor => 0,
+                _ => self.target_alpha,
+            };
+            window.fade_towards(target, step, self.effect);
+        }
+    }
+
+    extern "system" fn wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        unsafe {
+            // Dim and Tint paint a solid background; the layered alpha does the fading
+            let paint = GetWindowLongPtrW(hwnd, GWLP_USERDATA);
+            if msg == WM_ERASEBKGND && paint & PAINT_FLAG != 0 {
+                let mut rect = RECT::default();
+                let _ = GetClientRect(hwnd, &mut rect);
+                let brush = CreateSolidBrush(COLORREF((paint & 0xFF_FFFF) as u32));
+                FillRect(HDC(wparam.0 as isize), &rect, brush);
+                DeleteObject(brush);
+                return LRESULT(1);
+            }
+            DefWindowProcA(hwnd, msg, wparam, lparam)
+        }
+    }
+}
+
+impl MonitorOverlay {
+    /// Sets the window region to the monitor minus the excluded areas (a hole in the overlay
+    /// lets clicks and pixels through untouched)
+    fn apply_exclusions(&self, exclusions: &Exclusions) {
+        let visible = if exclusions.taskbar {
+            self.work_area.local_to(&self.bounds)
+        } else {
+            self.bounds.local_to(&self.bounds)
+        };
+        unsafe {
+            let Some(visible) = visible else {
+                let _ = SetWindowRgn(self.hwnd, CreateRectRgn(0, 0, 0, 0), true);
+                return;
+            };
+            let region = CreateRectRgn(visible.left, visible.top, visible.right, visible.bottom);
+            for hole in exclusions.rects.iter().filter_map(|rect| rect.local_to(&self.bounds)) {
+                let cut = CreateRectRgn(hole.left, hole.top, hole.right, hole.bottom);
+                CombineRgn(region, region, cut, RGN_DIFF);
+                DeleteObject(cut);
+            }
+            // The window owns the region from here on
+            let _ = SetWindowRgn(self.hwnd, region, true);
+        }
+    }
+
+    fn fade_towards(&mut self, target: u32, step: u32, effect: OverlayEffect) {
+        if self.current_alpha == target {
             // Optimization: Hide window if fully transparent
             if self.current_alpha == 0 {
                 unsafe { ShowWindow(self.hwnd, SW_HIDE) };
@@ -66,22 +469,33 @@ impl BlurOverlay {
         }
 
         // Show window if we are starting to fade in
-        if self.current_alpha == 0 && self.target_alpha > 0 {
+        if self.current_alpha == 0 && target > 0 {
             unsafe { ShowWindow(self.hwnd, SW_SHOW) };
         }
 
-        // Interpolate Alpha
-        if self.current_alpha < self.target_alpha {
-            self.current_alpha = (self.current_alpha + FADE_SPEED).min(self.target_alpha);
+        if self.current_alpha < target {
+            self.current_alpha = (self.current_alpha + step).min(target);
         } else {
-            self.current_alpha = self.current_alpha.saturating_sub(FADE_SPEED).max(self.target_alpha);
+            self.current_alpha = self.current_alpha.saturating_sub(step).max(target);
         }
 
-        // Apply Neutral Acrylic Blur (more effective visual punishment)
-        // Color Format: ABGR -> 0xAA000000 (AA=Alpha, BB=Blue=00, GG=Green=00, RR=Red=00)
-        // Using neutral color instead of red for better readability preservation
-        let color = (self.current_alpha << 24) | 0x00000000;
-        self.set_acrylic(color);
+        match effect {
+            OverlayEffect::AcrylicBlur => {
+                // Apply Neutral Acrylic Blur (more effective visual punishment)
+                // Color Format: ABGR -> 0xAA000000 (AA=Alpha, BB=Blue=00, GG=Green=00, RR=Red=00)
+                // Using neutral color instead of red for better readability preservation
+                let color = (self.current_alpha << 24) | 0x00000000;
+                self.set_acrylic(color);
+            }
+            OverlayEffect::Dim | OverlayEffect::Tint => unsafe {
+                let _ = SetLayeredWindowAttributes(self.hwnd, COLORREF(0), self.current_alpha as u8, LWA_ALPHA);
+            },
+            OverlayEffect::RedVignette => {
+                if let Some(vignette) = &self.vignette {
+                    vignette.show(self.hwnd, self.current_alpha as u8);
+                }
+            }
+        }
     }
 
     fn set_acrylic(&self, color: u32) {
@@ -110,9 +524,11 @@ impl BlurOverlay {
             }
         }
     }
+}
 
-    extern "system" fn wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
-        unsafe { DefWindowProcA(hwnd, msg, wparam, lparam) }
+impl Drop for BlurOverlay {
+    fn drop(&mut self) {
+        self.destroy_windows();
     }
 }
 
@@ -0,0 +1,183 @@
+/// Break prompt panel: a small always-on-top banner at the top of the primary monitor, for
+/// break reminders set to `BreakPrompt::Overlay`
+///
+/// Clicks pass through it and it never takes the focus, so typing carries on underneath.
+
+use std::cell::RefCell;
+
+use anyhow::{bail, Result};
+use windows::core::w;
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    BeginPaint, EndPaint, InvalidateRect, StretchDIBits, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, PAINTSTRUCT,
+    SRCCOPY,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+use crate::canvas::Canvas;
+use crate::config::{BREAK_PANEL_ALPHA, BREAK_PANEL_COLOR};
+use crate::error::PostureError;
+
+const TEXT_SCALE: i32 = 2;
+const LINE_HEIGHT: i32 = 24;
+const PADDING: i32 = 16;
+const TITLE_COLOR: u32 = 0xFFFFFF;
+const TEXT_COLOR: u32 = 0xD8E4F0;
+
+/// Shared with the window procedure through GWLP_USERDATA
+struct PanelState {
+    width: usize,
+    height: usize,
+    pixels: Vec<u32>,
+}
+
+pub struct BreakPanel {
+    hwnd: HWND,
+    // Boxed so the pointer handed to the window procedure stays put
+    state: Box<RefCell<PanelState>>,
+}
+
+impl BreakPanel {
+    /// Creates the (hidden) panel
+    pub fn new() -> Result<Self, PostureError> {
+        let state = Box::new(RefCell::new(PanelState { width: 0, height: 0, pixels: Vec::new() }));
+        let hwnd = unsafe { Self::create(&state) }.map_err(PostureError::Overlay)?;
+        Ok(Self { hwnd, state })
+    }
+
+    unsafe fn create(state: &RefCell<PanelState>) -> Result<HWND> {
+        let instance = GetModuleHandleW(None)?;
+        let wc = WNDCLASSW {
+            hInstance: instance.into(),
+            lpszClassName: w!("PostureBreakPanelClass"),
+            lpfnWndProc: Some(Self::wnd_proc),
+            ..Default::default()
+        };
+        RegisterClassW(&wc);
+
+        let hwnd = CreateWindowExW(
+            WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_NOACTIVATE,
+            w!("PostureBreakPanelClass"),
+            w!(""),
+            WS_POPUP,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            instance,
+            None,
+        );
+        if hwnd.0 == 0 {
+            bail!("Failed to create the break panel");
+        }
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), BREAK_PANEL_ALPHA, LWA_ALPHA)?;
+        // Like the blur overlay, kept out of screenshots and recordings
+        let _ = SetWindowDisplayAffinity(hwnd, WDA_EXCLUDEFROMCAPTURE);
+        SetWindowLongPtrW(hwnd, GWLP_USERDATA, state as *const RefCell<PanelState> as isize);
+        Ok(hwnd)
+    }
+
+    /// Shows `title` above `lines`, centered at the top of the primary monitor's work area
+    pub fn show(&self, title: &str, lines: &[String]) {
+        let text_width = lines.iter().map(|line| Canvas::text_width(line, TEXT_SCALE)).chain([Canvas::text_width(title, TEXT_SCALE)]).max();
+        let mut work_area = RECT::default();
+        unsafe {
+            let _ = SystemParametersInfoW(SPI_GETWORKAREA, 0, Some(&mut work_area as *mut RECT as *mut _), SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0));
+        }
+        let max_width = (work_area.right - work_area.left).max(1);
+        let width = (text_width.unwrap_or(0) + 2 * PADDING).min(max_width);
+        let height = (lines.len() as i32 + 1) * LINE_HEIGHT + 2 * PADDING;
+
+        {
+            let mut state = self.state.borrow_mut();
+            state.width = width as usize;
+            state.height = height as usize;
+            let mut pixels = vec![BREAK_PANEL_COLOR; state.width * state.height];
+            let mut canvas = Canvas { buffer: &mut pixels, width: state.width, height: state.height };
+            canvas.draw_text(title, PADDING, PADDING, TEXT_SCALE, TITLE_COLOR);
+            for (i, line) in lines.iter().enumerate() {
+                canvas.draw_text(line, PADDING, PADDING + (i as i32 + 1) * LINE_HEIGHT, TEXT_SCALE, TEXT_COLOR);
+            }
+            state.pixels = pixels;
+        }
+
+        let left = work_area.left + (max_width - width) / 2;
+        unsafe {
+            let _ = SetWindowPos(self.hwnd, HWND_TOPMOST, left, work_area.top + PADDING, width, height, SWP_NOACTIVATE | SWP_SHOWWINDOW);
+            InvalidateRect(self.hwnd, None, false);
+        }
+    }
+
+    pub fn hide(&self) {
+        unsafe { ShowWindow(self.hwnd, SW_HIDE) };
+    }
+
+    fn paint(hwnd: HWND, state: &PanelState) {
+        unsafe {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+            let (width, height) = (state.width as i32, state.height as i32);
+            if width > 0 && height > 0 {
+                let info = BITMAPINFO {
+                    bmiHeader: BITMAPINFOHEADER {
+                        biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                        biWidth: width,
+                        biHeight: -height, // top-down rows
+                        biPlanes: 1,
+                        biBitCount: 32,
+                        biCompression: BI_RGB.0,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                };
+                StretchDIBits(
+                    hdc,
+                    0,
+                    0,
+                    width,
+                    height,
+                    0,
+                    0,
+                    width,
+                    height,
+                    Some(state.pixels.as_ptr() as *const _),
+                    &info,
+                    DIB_RGB_COLORS,
+                    SRCCOPY,
+                );
+            }
+            let _ = EndPaint(hwnd, &ps);
+        }
+    }
+
+    extern "system" fn wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        unsafe {
+            let state = GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *const RefCell<PanelState>;
+            let Some(state) = state.as_ref() else {
+                return DefWindowProcW(hwnd, msg, wparam, lparam);
+            };
+            match msg {
+                WM_PAINT => {
+                    Self::paint(hwnd, &state.borrow());
+                    LRESULT(0)
+                }
+                // The image covers the whole panel; skipping the erase avoids flicker
+                WM_ERASEBKGND => LRESULT(1),
+                _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+            }
+        }
+    }
+}
+
+impl Drop for BreakPanel {
+    fn drop(&mut self) {
+        unsafe {
+            // Detach the state first so late messages don't reach a freed pointer
+            SetWindowLongPtrW(self.hwnd, GWLP_USERDATA, 0);
+            let _ = DestroyWindow(self.hwnd);
+        }
+    }
+}
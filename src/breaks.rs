@@ -0,0 +1,165 @@
+/// Break reminders, separate from posture alerts: continuous time in front of the camera is
+/// counted, and after a while a 20-20-20 eye break or a stretch break is suggested
+///
+/// Leaving the camera's view for a break's length counts as taking it and restarts its timer.
+/// A stretch break sat through is suggested again after a snooze.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::SharedClock;
+use crate::config::{
+    BREAK_PROMPT, BREAK_REMINDERS_ENABLED, BREAK_SNOOZE_MINS, EYE_BREAK_EVERY_MINS, EYE_BREAK_SECS, STRETCH_BREAK_EVERY_MINS,
+    STRETCH_BREAK_MINS, STRETCH_PROMPT_SECS,
+};
+
+/// How a break is suggested
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakPrompt {
+    /// A Windows toast
+    Toast,
+    /// A panel at the top of the screen that stays for the break
+    Overlay,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BreakSettings {
+    pub enabled: bool,
+    /// Minutes between 20-20-20 eye breaks; None = none
+    pub eye_break_every_mins: Option<u64>,
+    /// Minutes of sitting before a stretch break; None = none
+    pub stretch_break_every_mins: Option<u64>,
+    pub prompt: BreakPrompt,
+}
+
+impl Default for BreakSettings {
+    fn default() -> Self {
+        Self {
+            enabled: BREAK_REMINDERS_ENABLED,
+            eye_break_every_mins: EYE_BREAK_EVERY_MINS,
+            stretch_break_every_mins: STRETCH_BREAK_EVERY_MINS,
+            prompt: BREAK_PROMPT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakKind {
+    /// Look about 6 m (20 ft) away for EYE_BREAK_SECS
+    Eye,
+    /// Stand up and move for STRETCH_BREAK_MINS
+    Stretch,
+}
+
+impl BreakKind {
+    /// How long the break lasts; being out of view this long also counts as taking it
+    pub fn length(self) -> Duration {
+        match self {
+            BreakKind::Eye => Duration::from_secs(EYE_BREAK_SECS),
+            BreakKind::Stretch => Duration::from_secs(STRETCH_BREAK_MINS * 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakEvent {
+    /// Time for this break; `sitting_for` is the time in view since the last stretch break
+    Due { kind: BreakKind, sitting_for: Duration },
+    /// The prompt has been up long enough or the user got up; hide it
+    Over,
+}
+
+/// Counts time in view and decides when a break is due
+pub struct BreakTimer {
+    clock: SharedClock,
+    eye_every: Option<Duration>,
+    stretch_every: Option<Duration>,
+    eye_due: Option<Instant>,
+    stretch_due: Option<Instant>,
+    /// Since the last stretch break (or the start)
+    sitting_since: Instant,
+    away_since: Option<Instant>,
+    /// The break being prompted and until when
+    prompt: Option<(BreakKind, Instant)>,
+}
+
+impl BreakTimer {
+    pub fn new(clock: SharedClock, settings: &BreakSettings) -> Self {
+        let minutes = |every: Option<u64>| every.filter(|minutes| *minutes > 0).map(|minutes| Duration::from_secs(minutes * 60));
+        let now = clock.now();
+        let mut timer = Self {
+            clock,
+            eye_every: minutes(settings.eye_break_every_mins).filter(|_| settings.enabled),
+            stretch_every: minutes(settings.stretch_break_every_mins).filter(|_| settings.enabled),
+            eye_due: None,
+            stretch_due: None,
+            sitting_since: now,
+            away_since: None,
+            prompt: None,
+        };
+        timer.restart(BreakKind::Stretch, now);
+        timer
+    }
+
+    /// Restarts `kind`'s timer; a stretch break rests the eyes too
+    fn restart(&mut self, kind: BreakKind, now: Instant) {
+        self.eye_due = self.eye_every.map(|every| now + every);
+        if kind == BreakKind::Stretch {
+            self.stretch_due = self.stretch_every.map(|every| now + every);
+            self.sitting_since = now;
+        }
+    }
+
+    /// Feed once per frame with whether the user is in view; while detection is paused, feed
+    /// false, since nobody is watched
+    pub fn update(&mut self, present: bool) -> Option<BreakEvent> {
+        let now = self.clock.now();
+        if !present {
+            self.away_since.get_or_insert(now);
+            return self.prompt.take().map(|_| BreakEvent::Over);
+        }
+
+        if let Some(since) = self.away_since.take() {
+            let away = now.saturating_duration_since(since);
+            if away >= BreakKind::Stretch.length() {
+                self.restart(BreakKind::Stretch, now);
+            } else if away >= BreakKind::Eye.length() {
+                self.restart(BreakKind::Eye, now);
+            }
+        }
+
+        if let Some((kind, until)) = self.prompt {
+            if now < until {
+                return None;
+            }
+            // An eye break only needs the user to look away, so it's taken once the prompt is done
+            if kind == BreakKind::Eye {
+                self.restart(BreakKind::Eye, now);
+            }
+            self.prompt = None;
+            return Some(BreakEvent::Over);
+        }
+
+        let sitting_for = now.saturating_duration_since(self.sitting_since);
+        if self.stretch_due.is_some_and(|due| now >= due) {
+            self.stretch_due = Some(now + Duration::from_secs(BREAK_SNOOZE_MINS * 60));
+            self.restart(BreakKind::Eye, now);
+            self.prompt = Some((BreakKind::Stretch, now + Duration::from_secs(STRETCH_PROMPT_SECS)));
+            return Some(BreakEvent::Due { kind: BreakKind::Stretch, sitting_for });
+        }
+        if self.eye_due.is_some_and(|due| now >= due) {
+            self.restart(BreakKind::Eye, now);
+            self.prompt = Some((BreakKind::Eye, now + BreakKind::Eye.length()));
+            return Some(BreakEvent::Due { kind: BreakKind::Eye, sitting_for });
+        }
+        None
+    }
+
+    /// The break being prompted, if any
+    pub fn prompting(&self) -> Option<BreakKind> {
+        self.prompt.map(|(kind, _)| kind)
+    }
+}
@@ -0,0 +1,24 @@
+/// Video call detection: a conferencing app (or a browser, for Meet and other web calls) using
+/// the webcam or microphone, during which detection pauses and the camera is left to the call
+
+use crate::device_usage;
+
+/// Whether a consent store user is `app`: an executable name, or a packaged app's name
+/// without its publisher suffix (e.g. "MSTeams" for "MSTeams_8wekyb3d8bbwe")
+fn is_app(user: &str, app: &str) -> bool {
+    user.eq_ignore_ascii_case(app) || user.split_once('_').is_some_and(|(package, _)| package.eq_ignore_ascii_case(app))
+}
+
+/// The first of `users` that is one of the `call_apps`
+pub fn call_app<'a>(users: &'a [String], call_apps: &[String]) -> Option<&'a str> {
+    users.iter()
+        .find(|user| call_apps.iter().any(|app| is_app(user, app)))
+        .map(String::as_str)
+}
+
+/// The conferencing app currently using the webcam or a microphone, if any
+pub fn active_call(call_apps: &[String]) -> Option<String> {
+    let mut users = device_usage::webcam_users();
+    users.extend(device_usage::microphone_users());
+    call_app(&users, call_apps).map(str::to_string)
+}
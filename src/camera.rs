@@ -0,0 +1,298 @@
+/// Camera enumeration, selection and opening
+///
+/// Virtual cameras (OBS Virtual Camera, phone apps) often offer a single odd format and pad
+/// their rows, so opening walks a list of formats until one delivers a frame that decodes.
+
+use anyhow::anyhow;
+use image::{Rgb, RgbImage};
+use nokhwa::{
+    pixel_format::RgbFormat,
+    query,
+    utils::{
+        ApiBackend, CameraFormat, CameraIndex, CameraInfo, ControlValueSetter, FrameFormat, KnownCameraControl,
+        RequestedFormat, RequestedFormatType,
+    },
+    Buffer, Camera,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::camera_controls;
+use crate::config::{
+    CAMERA_EXPOSURE, CAMERA_FPS, CAMERA_GAIN, CAMERA_INDEX, CAMERA_LOCK_EXPOSURE, CAMERA_LOCK_GAIN, CAMERA_NAME,
+    CAMERA_RESOLUTION, CAMERA_SETTLE_FRAMES, VIRTUAL_CAMERAS,
+};
+use crate::error::{PostureError, Result};
+
+/// All cameras the native backend can see (empty if enumeration fails)
+pub fn list_cameras() -> Vec<CameraInfo> {
+    match query(ApiBackend::Auto) {
+        Ok(cameras) => cameras,
+        Err(e) => {
+            warn!("Failed to enumerate cameras: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Numeric index of a camera, if the backend reports one
+pub fn camera_index(info: &CameraInfo) -> Option<u32> {
+    info.index().as_index().ok()
+}
+
+/// Picks the camera to use: `preferred` (the tray selection) if it is still connected,
+/// else the first whose name contains `CAMERA_NAME` (case-insensitive), else `CAMERA_INDEX`.
+pub fn resolve_camera_index(cameras: &[CameraInfo], preferred: Option<u32>) -> u32 {
+    if let Some(index) = preferred {
+        if cameras.iter().any(|info| camera_index(info) == Some(index)) {
+            return index;
+        }
+        info!("Saved camera {} is not connected - picking another.", index);
+    }
+
+    if !CAMERA_NAME.is_empty() {
+        let wanted = CAMERA_NAME.to_lowercase();
+        let matched = cameras.iter()
+            .find(|info| info.human_name().to_lowercase().contains(&wanted))
+            .and_then(camera_index);
+        match matched {
+            Some(index) => return index,
+            None => info!("No camera matching '{}' - falling back to index {}.", CAMERA_NAME, CAMERA_INDEX),
+        }
+    }
+    CAMERA_INDEX
+}
+
+/// The camera to switch to while `busy` is in use by another app: the first of `wanted` (name
+/// substrings, in order of preference) that matches a connected camera other than `busy`
+pub fn fallback_camera(cameras: &[(u32, String)], wanted: &[String], busy: u32) -> Option<u32> {
+    wanted.iter().filter(|name| !name.is_empty()).find_map(|name| {
+        let name = name.to_lowercase();
+        cameras.iter()
+            .find(|(index, human_name)| *index != busy && human_name.to_lowercase().contains(&name))
+            .map(|(index, _)| *index)
+    })
+}
+
+/// How the stream is requested and set up when it opens (per user in settings.json)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureOptions {
+    /// Frame size to ask for; None = whatever size gives the highest frame rate
+    pub resolution: Option<(u32, u32)>,
+    pub fps: u32,
+    pub exposure: ControlSetting,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            resolution: CAMERA_RESOLUTION,
+            fps: CAMERA_FPS,
+            exposure: ControlSetting::from_config(CAMERA_LOCK_EXPOSURE, CAMERA_EXPOSURE),
+        }
+    }
+}
+
+/// The format asked of the driver: the closest it offers to the wanted size and rate (MJPEG,
+/// which most webcams need for anything above 640x480 at 30 fps)
+pub fn requested_format(options: &CaptureOptions) -> RequestedFormatType {
+    match options.resolution {
+        Some((width, height)) => {
+            RequestedFormatType::Closest(CameraFormat::new_from(width, height, FrameFormat::MJPEG, options.fps))
+        }
+        None => RequestedFormatType::AbsoluteHighestFrameRate,
+    }
+}
+
+/// Formats to try in order: the wanted one, the same size as YUYV and NV12 (the uncompressed
+/// formats virtual cameras send), then the fastest and finally the first the camera lists
+pub fn format_fallbacks(options: &CaptureOptions) -> Vec<RequestedFormatType> {
+    let mut formats = vec![requested_format(options)];
+    if let Some((width, height)) = options.resolution {
+        for format in [FrameFormat::YUYV, FrameFormat::NV12] {
+            formats.push(RequestedFormatType::Closest(CameraFormat::new_from(width, height, format, options.fps)));
+        }
+    }
+    for fallback in [RequestedFormatType::AbsoluteHighestFrameRate, RequestedFormatType::None] {
+        if !formats.contains(&fallback) {
+            formats.push(fallback);
+        }
+    }
+    formats
+}
+
+/// Whether `name` is one of the VIRTUAL_CAMERAS (case-insensitive substring)
+pub fn is_virtual(name: &str) -> bool {
+    let name = name.to_lowercase();
+    VIRTUAL_CAMERAS.iter().any(|virtual_name| name.contains(&virtual_name.to_lowercase()))
+}
+
+pub fn open(index: u32, options: &CaptureOptions) -> Result<Camera> {
+    info!("Opening Camera {}...", index);
+    let mut last_error = None;
+    for format_type in format_fallbacks(options) {
+        match open_as(index, format_type) {
+            Ok(mut camera) => {
+                info!("Camera {} streams {}.", index, camera.camera_format());
+                camera_controls::apply_saved(&mut camera);
+                if is_virtual(&camera.info().human_name()) {
+                    // Nothing to expose or settle; writing controls would only log failures
+                    debug!("Camera {} is a virtual camera - leaving its controls alone.", index);
+                } else {
+                    apply_controls(&mut camera, options.exposure);
+                }
+                return Ok(camera);
+            }
+            Err(e) => {
+                debug!("Camera {} with {:?} failed: {}", index, format_type, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(last_error.unwrap_or_else(|| PostureError::camera(anyhow!("no format to try"))))
+}
+
+/// The formats camera `index` offers, asked without starting its stream
+pub fn formats(index: u32) -> Result<Vec<CameraFormat>> {
+    let requested = RequestedFormat::new::<RgbFormat>(RequestedFormatType::None);
+    let mut camera = Camera::new(CameraIndex::Index(index), requested).map_err(PostureError::camera)?;
+    camera.compatible_camera_formats().map_err(PostureError::camera)
+}
+
+/// Opens the stream in one format and checks that its first frame decodes
+fn open_as(index: u32, format_type: RequestedFormatType) -> Result<Camera> {
+    let requested = RequestedFormat::new::<RgbFormat>(format_type);
+    let mut camera = Camera::new(CameraIndex::Index(index), requested).map_err(PostureError::camera)?;
+    camera.open_stream().map_err(PostureError::camera)?;
+    let first = camera.frame().map_err(PostureError::camera)?;
+    decode_frame(&first)?;
+    Ok(camera)
+}
+
+/// Decodes a captured frame to RGB. nokhwa's decoder is tried first; frames it rejects,
+/// usually rows padded past the width, are converted by `convert_raw`.
+pub fn decode_frame(buffer: &Buffer) -> Result<RgbImage> {
+    buffer.decode_image::<RgbFormat>().or_else(|e| {
+        let resolution = buffer.resolution();
+        convert_raw(buffer.buffer(), resolution.width(), resolution.height(), buffer.source_frame_format())
+            .ok_or_else(|| PostureError::camera(anyhow!("{} ({} bytes of {})", e, buffer.buffer().len(), buffer.source_frame_format())))
+    })
+}
+
+/// `decode_frame` into an image kept across frames, reallocated only when the resolution
+/// changes. Frames nokhwa can't decode in place take the `convert_raw` route.
+pub fn decode_frame_into(buffer: &Buffer, frame: &mut RgbImage) -> Result<()> {
+    let resolution = buffer.resolution();
+    if frame.dimensions() != (resolution.width(), resolution.height()) {
+        *frame = RgbImage::new(resolution.width(), resolution.height());
+    }
+    if buffer.decode_image_to_buffer::<RgbFormat>(frame).is_err() {
+        *frame = decode_frame(buffer)?;
+    }
+    Ok(())
+}
+
+/// Converts uncompressed YUYV, NV12, gray or RGB data to an RGB frame, allowing each row to
+/// be padded (the row stride is taken from the data length). None if the data is too short.
+pub fn convert_raw(data: &[u8], width: u32, height: u32, format: FrameFormat) -> Option<RgbImage> {
+    let (w, h) = (width as usize, height as usize);
+    if w == 0 || h == 0 {
+        return None;
+    }
+    // Rows of luma (or pixels) in the data; NV12 adds half as many chroma rows
+    let rows = if format == FrameFormat::NV12 { h + h.div_ceil(2) } else { h };
+    let stride = data.len() / rows;
+    let pixel = |x: usize, y: usize| -> Rgb<u8> {
+        match format {
+            FrameFormat::YUYV => {
+                let pair = y * stride + x / 2 * 4;
+                yuv_to_rgb(data[pair + (x % 2) * 2], data[pair + 1], data[pair + 3])
+            }
+            FrameFormat::NV12 => {
+                let chroma = (h + y / 2) * stride + x / 2 * 2;
+                yuv_to_rgb(data[y * stride + x], data[chroma], data[chroma + 1])
+            }
+            FrameFormat::GRAY => Rgb([data[y * stride + x]; 3]),
+            _ => {
+                let i = y * stride + x * 3;
+                Rgb([data[i], data[i + 1], data[i + 2]])
+            }
+        }
+    };
+    let row_bytes = match format {
+        FrameFormat::YUYV => w.div_ceil(2) * 4,
+        FrameFormat::NV12 => w.div_ceil(2) * 2,
+        FrameFormat::GRAY => w,
+        FrameFormat::RAWRGB => w * 3,
+        _ => return None,
+    };
+    (stride >= row_bytes).then(|| RgbImage::from_fn(width, height, |x, y| pixel(x as usize, y as usize)))
+}
+
+/// BT.601 studio-range YUV to RGB
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> Rgb<u8> {
+    let (y, u, v) = (1.164 * (y as f32 - 16.0), u as f32 - 128.0, v as f32 - 128.0);
+    let channel = |value: f32| value.round().clamp(0.0, 255.0) as u8;
+    Rgb([channel(y + 1.596 * v), channel(y - 0.392 * u - 0.813 * v), channel(y + 2.017 * u)])
+}
+
+/// How a camera control should be set at stream open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlSetting {
+    /// Leave the driver's automatic mode alone
+    Auto,
+    /// Keep whatever value the automatic mode settled on
+    Lock,
+    Fixed(i64),
+}
+
+impl ControlSetting {
+    pub fn from_config(lock: bool, fixed: Option<i64>) -> Self {
+        match (fixed, lock) {
+            (Some(value), _) => Self::Fixed(value),
+            (None, true) => Self::Lock,
+            (None, false) => Self::Auto,
+        }
+    }
+}
+
+/// Applies the chosen exposure and the configured gain. Writing a value switches the control to manual
+/// mode on Media Foundation, which is what disables the automatic adjustment. Unsupported
+/// controls are reported and skipped - detection still works, just less steadily.
+fn apply_controls(camera: &mut Camera, exposure: ControlSetting) {
+    let controls = [
+        (KnownCameraControl::Exposure, exposure),
+        (KnownCameraControl::Gain, ControlSetting::from_config(CAMERA_LOCK_GAIN, CAMERA_GAIN)),
+    ];
+    if controls.iter().all(|(_, setting)| *setting == ControlSetting::Auto) {
+        return;
+    }
+
+    if controls.iter().any(|(_, setting)| *setting == ControlSetting::Lock) {
+        // Give auto-exposure a moment to adapt to the room before freezing it
+        for _ in 0..CAMERA_SETTLE_FRAMES {
+            if camera.frame().is_err() {
+                break;
+            }
+        }
+    }
+
+    for (control, setting) in controls {
+        let value = match setting {
+            ControlSetting::Auto => continue,
+            ControlSetting::Fixed(value) => ControlValueSetter::Integer(value),
+            ControlSetting::Lock => match camera.camera_control(control) {
+                Ok(current) => current.value(),
+                Err(e) => {
+                    debug!("Camera does not report {}: {}", control, e);
+                    continue;
+                }
+            },
+        };
+        match camera.set_camera_control(control, value.clone()) {
+            Ok(()) => debug!("Camera {} set to {:?}.", control, value),
+            Err(e) => warn!("Camera does not support setting {}: {}", control, e),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+/// Raw camera controls (brightness, contrast, zoom, pan/tilt, ...) picked from the tray
+///
+/// Chosen values are saved per camera name in `camera_controls.json` and written back to
+/// the device every time its stream is opened.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use nokhwa::{
+    utils::{ControlValueDescription, ControlValueSetter, KnownCameraControl},
+    Camera,
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::app_data;
+
+const PROFILES_FILE: &str = "camera_controls.json";
+
+/// Controls offered in the tray. Exposure is set in the settings window, gain by `CAMERA_GAIN` in config.rs.
+pub const ADJUSTABLE: [KnownCameraControl; 11] = [
+    KnownCameraControl::Brightness,
+    KnownCameraControl::Contrast,
+    KnownCameraControl::Saturation,
+    KnownCameraControl::Sharpness,
+    KnownCameraControl::Gamma,
+    KnownCameraControl::WhiteBalance,
+    KnownCameraControl::BacklightComp,
+    KnownCameraControl::Zoom,
+    KnownCameraControl::Pan,
+    KnownCameraControl::Tilt,
+    KnownCameraControl::Focus,
+];
+
+/// Stable name used in saved profiles, menu ids and locale keys
+pub fn key(control: KnownCameraControl) -> &'static str {
+    match control {
+        KnownCameraControl::Brightness => "brightness",
+        KnownCameraControl::Contrast => "contrast",
+        KnownCameraControl::Hue => "hue",
+        KnownCameraControl::Saturation => "saturation",
+        KnownCameraControl::Sharpness => "sharpness",
+        KnownCameraControl::Gamma => "gamma",
+        KnownCameraControl::WhiteBalance => "white_balance",
+        KnownCameraControl::BacklightComp => "backlight_comp",
+        KnownCameraControl::Gain => "gain",
+        KnownCameraControl::Pan => "pan",
+        KnownCameraControl::Tilt => "tilt",
+        KnownCameraControl::Zoom => "zoom",
+        KnownCameraControl::Exposure => "exposure",
+        KnownCameraControl::Iris => "iris",
+        KnownCameraControl::Focus => "focus",
+        KnownCameraControl::Other(_) => "other",
+    }
+}
+
+pub fn from_key(key_name: &str) -> Option<KnownCameraControl> {
+    ADJUSTABLE.into_iter().find(|control| key(*control) == key_name)
+}
+
+/// A tray preset: the driver default or a fixed point of the control's range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlLevel {
+    Default,
+    Percent(u8),
+}
+
+pub const PRESET_PERCENTS: [u8; 5] = [0, 25, 50, 75, 100];
+
+/// Integer range reported by the driver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlRange {
+    pub min: i64,
+    pub max: i64,
+    pub step: i64,
+    pub default: i64,
+}
+
+impl ControlRange {
+    /// Only ranged integer controls can be mapped onto presets
+    pub fn from_description(description: &ControlValueDescription) -> Option<Self> {
+        match *description {
+            ControlValueDescription::IntegerRange { min, max, step, default, .. } if max > min => {
+                Some(Self { min, max, step: step.max(1), default })
+            }
+            _ => None,
+        }
+    }
+
+    /// Device value for `level`, snapped to the control's step
+    pub fn value(&self, level: ControlLevel) -> i64 {
+        match level {
+            ControlLevel::Default => self.default,
+            ControlLevel::Percent(percent) => {
+                let raw = self.min + (self.max - self.min) * percent.min(100) as i64 / 100;
+                let snapped = self.min + (raw - self.min + self.step / 2) / self.step * self.step;
+                snapped.clamp(self.min, self.max)
+            }
+        }
+    }
+
+    /// The preset a saved value corresponds to (None = a value set outside the tray)
+    pub fn level_of(&self, value: Option<i64>) -> Option<ControlLevel> {
+        let Some(value) = value else { return Some(ControlLevel::Default) };
+        PRESET_PERCENTS.into_iter()
+            .map(ControlLevel::Percent)
+            .find(|level| self.value(*level) == value)
+            .or((value == self.default).then_some(ControlLevel::Default))
+    }
+}
+
+/// An adjustable control of the open camera, as shown in the tray
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvailableControl {
+    pub control: KnownCameraControl,
+    pub range: ControlRange,
+    pub level: Option<ControlLevel>,
+}
+
+/// Saved control values: camera name -> control key -> device value
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CameraProfiles {
+    pub cameras: BTreeMap<String, BTreeMap<String, i64>>,
+}
+
+impl CameraProfiles {
+    pub fn path() -> Result<PathBuf> {
+        app_data::file_path(PROFILES_FILE)
+    }
+
+    /// Loads the saved profiles; a missing or unreadable file means no overrides
+    pub fn load() -> Self {
+        match Self::path() {
+            Ok(path) => Self::load_from(&path),
+            Err(e) => {
+                warn!("Camera profiles unavailable: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        let Ok(json) = fs::read_to_string(path) else { return Self::default() };
+        serde_json::from_str(&json).unwrap_or_else(|e| {
+            warn!("Ignoring unreadable camera profiles: {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        app_data::write_atomic(path, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+
+    pub fn value(&self, camera: &str, control: KnownCameraControl) -> Option<i64> {
+        self.cameras.get(camera)?.get(key(control)).copied()
+    }
+
+    /// Stores `value` for the camera; None drops the override (back to the driver default)
+    pub fn set(&mut self, camera: &str, control: KnownCameraControl, value: Option<i64>) {
+        let values = self.cameras.entry(camera.to_string()).or_default();
+        match value {
+            Some(value) => {
+                values.insert(key(control).to_string(), value);
+            }
+            None => {
+                values.remove(key(control));
+            }
+        }
+        if values.is_empty() {
+            self.cameras.remove(camera);
+        }
+    }
+}
+
+fn camera_name(camera: &Camera) -> String {
+    camera.info().human_name()
+}
+
+/// Writes the saved values for this camera to the device (called on every stream open)
+pub fn apply_saved(camera: &mut Camera) {
+    let name = camera_name(camera);
+    let profiles = CameraProfiles::load();
+    let Some(values) = profiles.cameras.get(&name) else { return };
+    for (key_name, value) in values {
+        let Some(control) = from_key(key_name) else { continue };
+        if let Err(e) = camera.set_camera_control(control, ControlValueSetter::Integer(*value)) {
+            warn!("Failed to restore camera {}: {}", key_name, e);
+        }
+    }
+}
+
+/// The ADJUSTABLE controls this camera supports, with the preset currently in effect
+pub fn available(camera: &Camera) -> Vec<AvailableControl> {
+    let name = camera_name(camera);
+    let profiles = CameraProfiles::load();
+    ADJUSTABLE.into_iter()
+        .filter_map(|control| {
+            let info = camera.camera_control(control).ok()?;
+            let range = ControlRange::from_description(info.description())?;
+            Some(AvailableControl { control, range, level: range.level_of(profiles.value(&name, control)) })
+        })
+        .collect()
+}
+
+/// Sets `control` on the device and remembers the choice for this camera
+pub fn set(camera: &mut Camera, control: KnownCameraControl, range: &ControlRange, level: ControlLevel) -> Result<()> {
+    let value = range.value(level);
+    camera.set_camera_control(control, ControlValueSetter::Integer(value))?;
+
+    let name = camera_name(camera);
+    let mut profiles = CameraProfiles::load();
+    let saved = match level {
+        ControlLevel::Default => None,
+        ControlLevel::Percent(_) => Some(value),
+    };
+    profiles.set(&name, control, saved);
+    profiles.save()
+}
@@ -1,5 +1,10 @@
 /// Canvas drawing utilities for the posture detection application
 
+use crate::font::{self, GLYPH_SIZE};
+
+// Drop shadow behind text, so it stays readable on a bright camera image
+const TEXT_SHADOW: u32 = 0x000000;
+
 pub struct Canvas<'a> {
     pub buffer: &'a mut Vec<u32>,
     pub width: usize,
@@ -7,6 +12,11 @@ pub struct Canvas<'a> {
 }
 
 impl<'a> Canvas<'a> {
+    /// Pixel position of a normalized (0.0..1.0) frame coordinate; the preview shows the whole frame
+    pub fn to_display(&self, x: f32, y: f32) -> (i32, i32) {
+        ((x * self.width as f32) as i32, (y * self.height as f32) as i32)
+    }
+
     pub fn plot(&mut self, x: i32, y: i32, color: u32) {
         if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
             self.buffer[y as usize * self.width + x as usize] = color;
@@ -28,6 +38,136 @@ impl<'a> Canvas<'a> {
             if e2 <= dx { err += dx; y0 += sy; }
         }
     }
+
+    /// Rectangle outline; `width`/`height` include the border
+    pub fn draw_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: u32) {
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let (right, bottom) = (x + width - 1, y + height - 1);
+        self.draw_line(x, y, right, y, color);
+        self.draw_line(x, bottom, right, bottom, color);
+        self.draw_line(x, y, x, bottom, color);
+        self.draw_line(right, y, right, bottom, color);
+    }
+
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: u32) {
+        for py in y.max(0)..(y + height).min(self.height as i32) {
+            for px in x.max(0)..(x + width).min(self.width as i32) {
+                self.buffer[py as usize * self.width + px as usize] = color;
+            }
+        }
+    }
+
+    /// Translucent rectangle, e.g. a panel behind text or a tolerance band
+    pub fn fill_rect_blended(&mut self, x: i32, y: i32, width: i32, height: i32, color: u32, opacity: f32) {
+        for py in y..y + height {
+            for px in x..x + width {
+                self.plot_blend(px, py, color, opacity);
+            }
+        }
+    }
+
+    /// Circle outline
+    pub fn draw_circle(&mut self, cx: i32, cy: i32, radius: i32, color: u32) {
+        for (x, y) in circle_points(cx, cy, radius) {
+            self.plot(x, y, color);
+        }
+    }
+
+    pub fn fill_circle(&mut self, cx: i32, cy: i32, radius: i32, color: u32) {
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if dx * dx + dy * dy <= radius * radius {
+                    self.plot(cx + dx, cy + dy, color);
+                }
+            }
+        }
+    }
+
+    /// Draws `text` with the embedded 8x8 font, each font pixel `scale` pixels wide, starting at
+    /// the top-left corner (`x`, `y`). Text is not wrapped.
+    pub fn draw_text(&mut self, text: &str, x: i32, y: i32, scale: i32, color: u32) {
+        let scale = scale.max(1);
+        self.draw_glyphs(text, x + scale, y + scale, scale, TEXT_SHADOW);
+        self.draw_glyphs(text, x, y, scale, color);
+    }
+
+    /// Width of `text` in pixels at `scale`
+    pub fn text_width(text: &str, scale: i32) -> i32 {
+        text.chars().count() as i32 * GLYPH_SIZE * scale.max(1)
+    }
+
+    fn draw_glyphs(&mut self, text: &str, x: i32, y: i32, scale: i32, color: u32) {
+        for (i, c) in text.chars().enumerate() {
+            let left = x + i as i32 * GLYPH_SIZE * scale;
+            for (row, bits) in font::glyph(c).into_iter().enumerate() {
+                for column in 0..GLYPH_SIZE {
+                    if bits & (1 << column) == 0 {
+                        continue;
+                    }
+                    let (px, py) = (left + column * scale, y + row as i32 * scale);
+                    for dy in 0..scale {
+                        for dx in 0..scale {
+                            self.plot(px + dx, py + dy, color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Mixes `color` over the existing pixel; `opacity` is 0.0 (invisible) to 1.0 (opaque)
+    pub fn plot_blend(&mut self, x: i32, y: i32, color: u32, opacity: f32) {
+        if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
+            let pixel = &mut self.buffer[y as usize * self.width + x as usize];
+            let mix = |shift: u32| {
+                let under = ((*pixel >> shift) & 0xFF) as f32;
+                let over = ((color >> shift) & 0xFF) as f32;
+                ((under + (over - under) * opacity) as u32) << shift
+            };
+            *pixel = mix(16) | mix(8) | mix(0);
+        }
+    }
+
+    /// Blended line, `thickness` pixels wide (parallel 1px lines, so no pixel is blended twice)
+    pub fn draw_line_blended(&mut self, (x0, y0): (i32, i32), (x1, y1): (i32, i32), thickness: i32, color: u32, opacity: f32) {
+        let steps = (x1 - x0).abs().max((y1 - y0).abs()).max(1);
+        let mostly_horizontal = (x1 - x0).abs() >= (y1 - y0).abs();
+        for offset in -(thickness / 2)..=(thickness - 1) / 2 {
+            let (ox, oy) = if mostly_horizontal { (0, offset) } else { (offset, 0) };
+            for i in 0..=steps {
+                let x = x0 + (x1 - x0) * i / steps;
+                let y = y0 + (y1 - y0) * i / steps;
+                self.plot_blend(x + ox, y + oy, color, opacity);
+            }
+        }
+    }
+
+    /// Blended circle outline
+    pub fn draw_circle_blended(&mut self, cx: i32, cy: i32, radius: i32, color: u32, opacity: f32) {
+        for (x, y) in circle_points(cx, cy, radius) {
+            self.plot_blend(x, y, color, opacity);
+        }
+    }
+}
+
+/// Pixels on a circle's outline, each once (so blending doesn't double up)
+fn circle_points(cx: i32, cy: i32, radius: i32) -> Vec<(i32, i32)> {
+    // One point per pixel of circumference
+    let steps = ((radius as f32 * std::f32::consts::TAU) as i32).max(8);
+    let mut points: Vec<(i32, i32)> = Vec::with_capacity(steps as usize);
+    for i in 0..steps {
+        let angle = i as f32 * std::f32::consts::TAU / steps as f32;
+        let point = (
+            cx + (angle.cos() * radius as f32).round() as i32,
+            cy + (angle.sin() * radius as f32).round() as i32,
+        );
+        if points.last() != Some(&point) && points.first() != Some(&point) {
+            points.push(point);
+        }
+    }
+    points
 }
 
 pub fn from_u8_rgb(r: u8, g: u8, b: u8) -> u32 {
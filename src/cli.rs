@@ -0,0 +1,69 @@
+/// Command-line options
+
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::logging::LogLevel;
+use crate::settings::Overrides;
+
+#[derive(Debug, Clone, PartialEq, Eq, Parser)]
+#[command(name = "posture_ai_oc", version, about = "Webcam posture monitor that reminds you to sit up straight")]
+pub struct Cli {
+    /// Print whether Posture AI is running (JSON) and exit; exit code 0 = running
+    #[arg(long)]
+    pub status: bool,
+
+    /// Run detection, toasts and sounds without the tray icon, debug window or overlay,
+    /// e.g. as a background task or under a service wrapper. Ctrl+C stops it.
+    #[arg(long, conflicts_with = "status")]
+    pub headless: bool,
+
+    /// Replay a recording instead of using the camera: a video file (needs ffmpeg) or a folder
+    /// of image frames. Prints the verdicts and exits at the end; nothing is saved.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["status", "headless"])]
+    pub input: Option<PathBuf>,
+
+    /// Frame rate the recording was made at, which sets how fast time passes during a replay
+    #[arg(long, value_name = "FPS", default_value_t = 30, requires = "input", value_parser = clap::value_parser!(u32).range(1..))]
+    pub fps: u32,
+
+    /// Print the cameras found, with the index `--camera` takes and the formats each offers,
+    /// and exit
+    #[arg(long, conflicts_with_all = ["status", "headless", "input"])]
+    pub list_cameras: bool,
+
+    /// Check the setup and exit: list the cameras, load the pose model and run it once on
+    /// IMAGE (or a blank frame), printing a JSON report; exit codes as for --status
+    #[arg(long, value_name = "IMAGE", num_args = 0..=1, conflicts_with_all = ["status", "headless", "input", "list_cameras"])]
+    pub probe: Option<Option<PathBuf>>,
+
+    /// Settings file to use instead of settings.json in the app-data directory; created with
+    /// the defaults if missing, and edits are saved there
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Camera to use this run (see --list-cameras)
+    #[arg(long, value_name = "INDEX")]
+    pub camera: Option<u32>,
+
+    /// ONNX model file to load instead of the bundled or downloaded one; it must be of the kind
+    /// `pose_model` in the settings names (MoveNet or YOLO-pose)
+    #[arg(long, value_name = "PATH")]
+    pub model: Option<PathBuf>,
+
+    /// Never blur or dim the screen this run; toasts and sounds still alert
+    #[arg(long)]
+    pub no_overlay: bool,
+
+    /// Log level for this run
+    #[arg(long, value_name = "LEVEL")]
+    pub log_level: Option<LogLevel>,
+}
+
+impl Cli {
+    /// The settings this run changes without saving them
+    pub fn overrides(&self) -> Overrides {
+        Overrides { camera_index: self.camera, overlay_off: self.no_overlay, log_level: self.log_level }
+    }
+}
@@ -1,13 +1,370 @@
 /// Configuration constants for the posture detection application
+///
+/// The posture decision itself (thresholds, debounce, calibration, presence, inference rate) is
+/// configured in `posture-core/src/config.rs`; its constants are re-exported here, so the app
+/// reads every setting from `config::`.
+
+pub use posture_core::config::*;
+
+pub use crate::accelerator::Accelerator;
+pub use crate::alert_sinks::AlertSink;
+pub use crate::app_exemptions::Exemption;
+pub use crate::blur_overlay::OverlayEffect;
+pub use crate::breaks::BreakPrompt;
+pub use crate::focus_assist::Enforcement;
+pub use crate::logging::LogLevel;
+pub use crate::filter::Smoothing;
+pub use crate::pose_model::PoseModel;
+
+use crate::model_download;
+use crate::schedule::WorkSchedule;
+
+// Low-memory preset for 4GB machines (settings.json "low_memory", or the Settings window): these
+// replace the model, camera frame size and preview interval below, the ONNX Runtime session is
+// built lean and no frames are recorded around alerts
+pub const LOW_MEMORY_MODE: bool = false; // Default for new settings
+pub const LOW_MEMORY_MODEL: PoseModel = PoseModel::Lightning;
+pub const LOW_MEMORY_RESOLUTION: (u32, u32) = (640, 480);
+pub const LOW_MEMORY_PREVIEW_INTERVAL: u64 = 3;
 
 // Model and image processing configuration
-pub const MOVENET_SIZE: u32 = 256;
-pub const WIDTH: usize = 640;
-pub const HEIGHT: usize = 480;
-pub const GOOD_POSTURE_DEVIATION: f32 = 10.0; // Sensitivity
-pub const CAMERA_ROTATION_DEGREES: u32 = 180; // 0, 90, 180, or 270 degrees
-
-// Debounce and fade settings
-pub const DEBOUNCE_FRAMES: usize = 15; // How many bad frames before trigger?
-pub const MAX_ALPHA: u32 = 180;        // Max opacity (0-255)
-pub const FADE_SPEED: u32 = 15;        // How fast it fades in/out
+pub const POSE_MODEL: PoseModel = PoseModel::Thunder; // Or override in settings.json
+pub const LETTERBOX_MODEL_INPUT: bool = true; // Keep the frame's aspect ratio in the square model input, padding with black
+pub const PREVIEW_FRAME_INTERVAL: u64 = 1; // Redraw debug window every Nth frame
+pub const MODEL_DOWNLOAD_URL: &str = "https://github.com/dEN5-tech/posture_ai_oc/releases/latest/download/{file}"; // Missing models come from here
+// SHA-256 of each released model file (`sha256sum` of the release asset). A download is only
+// saved if it matches the digest pinned for its file; files without one aren't downloaded.
+pub const MODEL_SHA256: &[(&str, &str)] = &[];
+pub const HTTP_CONNECT_TIMEOUT_SECS: u64 = 15; // Downloads and webhooks give up on a server that doesn't answer...
+pub const HTTP_RECEIVE_TIMEOUT_SECS: u64 = 30; // ...or stops sending for this long
+pub const EXECUTION_PROVIDER: Accelerator = Accelerator::Auto; // Auto, DirectMl, Cuda or Cpu; falls back to the CPU
+pub const SHOW_BASELINE_GHOST: bool = true; // Faint outline of the calibrated head/shoulders in the debug window
+pub const GHOST_COLOR: u32 = 0x00FFFF;
+pub const GHOST_OPACITY: f32 = 0.35;
+pub const SHOW_SKELETON: bool = true;        // Detected keypoints and bones, colored by confidence
+pub const SKELETON_MIN_SCORE: f32 = 0.1;     // Keypoints below this score aren't drawn at all
+pub const YOLO_MIN_PERSON_CONFIDENCE: f32 = 0.25; // YOLOv8-pose: min detection score to use a person
+pub const YOLO_NMS_IOU: f32 = 0.5; // YOLOv8-pose: boxes overlapping more than this are one person
+pub const KEYPOINT_SMOOTHING: Smoothing = Smoothing::Ema { alpha: 0.4 }; // None, Ema or Kalman; stops flapping at the threshold
+pub const CAMERA_ROTATION_DEGREES: u32 = 180; // Clockwise, 0-359 (e.g. 90 for a sideways camera, 5 for a slightly askew one)
+pub const CAMERA_INDEX: u32 = 0;
+pub const CAMERA_NAME: &str = "";              // Prefer a camera whose name contains this (e.g. "Logitech")
+
+// Capture format. The model sees 192-256px anyway, so big frames only cost decoding time.
+// Per user in settings.json "camera_resolution", "camera_fps", "camera_mirror" and "camera_flip_vertical".
+pub const CAMERA_RESOLUTION: Option<(u32, u32)> = Some((1280, 720)); // None = highest frame rate at any size
+pub const CAMERA_FPS: u32 = 30;
+pub const CAMERA_MIRROR: bool = false;         // Flip frames horizontally, so the preview looks like a mirror
+pub const CAMERA_FLIP_VERTICAL: bool = false;  // Flip frames upside down (after the rotation)
+
+// Virtual cameras (name substrings) get no exposure or gain writes. Like any camera they fall
+// back through YUYV, NV12 and their own first format if the requested one fails to open or
+// its frames don't decode.
+pub const VIRTUAL_CAMERAS: &[&str] = &[
+    "OBS Virtual Camera", "OBS-Camera", "DroidCam", "Iriun", "Camo", "EpocCam",
+    "Windows Virtual Camera", "NVIDIA Broadcast", "XSplit VCam", "ManyCam", "Snap Camera",
+];
+
+// Camera controls set when the stream opens, where the driver supports them.
+// Auto-exposure and auto-gain swings make keypoints jitter; fixing them steadies detection.
+// Exposure per user in settings.json "camera_exposure" (or the settings window).
+pub const CAMERA_LOCK_EXPOSURE: bool = false;  // Freeze exposure at the value auto-exposure settled on
+pub const CAMERA_EXPOSURE: Option<i64> = None; // Fixed exposure in driver units (often -11..-2); overrides the lock
+pub const CAMERA_LOCK_GAIN: bool = false;      // Freeze gain at the value auto-gain settled on
+pub const CAMERA_GAIN: Option<i64> = None;     // Fixed gain in driver units; overrides the lock
+pub const CAMERA_SETTLE_FRAMES: u32 = 15;      // Frames to let auto-exposure settle before locking
+
+// Reconnecting a camera that was unplugged or taken by another app: the wait doubles after
+// each failed attempt, up to the maximum
+pub const CAMERA_RETRY_MIN_SECS: u64 = 1;
+pub const CAMERA_RETRY_MAX_SECS: u64 = 30;
+
+// While the camera is in use by another app (e.g. a video call) detection pauses, or switches
+// to the first of these cameras (name substrings) that is connected. Per user in settings.json
+// "fallback_cameras".
+pub const FALLBACK_CAMERAS: &[&str] = &[];
+pub const CAMERA_BUSY_CHECK_SECS: u64 = 5; // How often a fallback checks whether the camera is free again
+
+// Presence (AWAY_AFTER_SECS in posture-core): coming back after a long absence often means
+// sitting at a different height, so a new baseline is suggested or taken
+pub const REBASELINE_AFTER_AWAY_MINS: u64 = 15; // Away at least this long (0 = never)
+pub const AUTO_REBASELINE: bool = false;        // true = recalibrate once you sit still; false = ask with a toast
+
+// Fade settings
+pub const MAX_ALPHA: u32 = 180;    // Max opacity (0-255)
+pub const FADE_MS: u64 = 400;      // Duration of a full fade in/out
+
+// Posture exercises (tray "Posture Exercise"): repetitions are counted from keypoint motion.
+// With EXERCISE_TO_CLEAR_ALERT, a fully escalated overlay stays until a chin tuck or neck roll set is done.
+pub const EXERCISE_TO_CLEAR_ALERT: bool = false;
+pub const EXERCISE_REPS: u32 = 3;
+
+// Break reminders, apart from posture alerts: time in front of the camera is counted and a break
+// suggested after a while. Out of view for a break's length counts as taking it.
+// Per user in settings.json "breaks".
+pub const BREAK_REMINDERS_ENABLED: bool = false;
+pub const EYE_BREAK_EVERY_MINS: Option<u64> = Some(20);     // 20-20-20: look 20 ft (6 m) away for 20 s (None = off)
+pub const EYE_BREAK_SECS: u64 = 20;
+pub const STRETCH_BREAK_EVERY_MINS: Option<u64> = Some(50); // Sitting this long suggests standing up (None = off)
+pub const STRETCH_BREAK_MINS: u64 = 5;                      // Length of a stretch break
+pub const STRETCH_PROMPT_SECS: u64 = 60;                    // The prompt stays this long unless you get up
+pub const BREAK_SNOOZE_MINS: u64 = 10;                      // A stretch break sat through is suggested again after this
+pub const BREAK_PROMPT: BreakPrompt = BreakPrompt::Toast;   // Toast or Overlay (a banner at the top of the screen)
+pub const BREAK_PANEL_COLOR: u32 = 0x1E3C78;                // 0xRRGGBB background of the banner
+pub const BREAK_PANEL_ALPHA: u8 = 230;                      // Banner opacity (0-255)
+
+// Alerts: full-screen blur, a Windows toast and/or a sound when slouching persists
+pub const BLUR_OVERLAY_ENABLED: bool = true;
+pub const OVERLAY_FOREGROUND_MONITOR_ONLY: bool = false; // Blur only the monitor with the active window
+pub const OVERLAY_EFFECT: OverlayEffect = OverlayEffect::AcrylicBlur; // AcrylicBlur, Dim, Tint or RedVignette
+pub const OVERLAY_TINT_COLOR: u32 = 0x1E3C78;              // 0xRRGGBB, used by OverlayEffect::Tint
+pub const OVERLAY_EXCLUDE_TASKBAR: bool = false;           // Keep the taskbar unblurred during alerts
+pub const TOAST_NOTIFICATIONS_ENABLED: bool = true;
+pub const TOAST_COOLDOWN_SECS: u64 = 600;  // Minimum gap between two toasts
+pub const SOUND_ALERTS_ENABLED: bool = false;
+pub const SOUND_FILE: &str = "";           // WAV file to play; empty = system warning beep
+pub const SOUND_VOLUME: u8 = 80;           // 0-100, WAV playback only
+pub const SOUND_REPEAT_SECS: u64 = 30;     // Replay while still slouching (0 = once per alert)
+// While a channel is busy, the ones after it stay quiet (the overlay is busy once fully faded in)
+pub const ALERT_PRIORITY: [AlertSink; 3] = [AlertSink::Overlay, AlertSink::Sound, AlertSink::Toast];
+// Sitting too close to the screen (TOO_CLOSE_PERCENT in posture-core) is warned about through one channel
+pub const TOO_CLOSE_ALERT: AlertSink = AlertSink::Toast; // Overlay, Sound or Toast
+
+// Weekly goals, reviewed every Monday against the previous week
+pub const GOAL_MAX_SLOUCH_PERCENT: u32 = 15;  // Share of monitored time spent slouching
+pub const GOAL_MAX_ALERTS_PER_DAY: u32 = 10;  // Overlay alerts per monitored day
+pub const WEEKLY_REVIEW_ENABLED: bool = true;
+pub const WEEKLY_REVIEW_MIN_HOURS: u64 = 2;   // Less monitoring than this in a week skips the review
+
+// Daily goals and good-posture streaks: each goal reached and each new best streak of the day is
+// celebrated with a toast and kept in the history
+pub const GOAL_GOOD_MINUTES_PER_DAY: Option<u32> = Some(120);     // Upright time per day (None = no goal)
+pub const GOAL_STREAK_MINUTES: Option<u32> = Some(30);            // One unbroken good-posture streak (None = no goal)
+pub const CELEBRATIONS_ENABLED: bool = true;                      // false = record goals and streaks without toasts
+pub const STREAK_MILESTONES_MINS: [u32; 4] = [15, 30, 60, 120];   // Streak lengths worth a toast
+pub const STREAK_MAX_GAP_SECS: i64 = 5;                           // Longer gaps (away, paused) end a streak
+
+// False-positive feedback ("This Alert Was Wrong" in the tray)
+pub const FALSE_POSITIVE_LOOSEN_PERCENT: u32 = 30; // Share of a rule's alerts marked wrong that suggests loosening it
+pub const FALSE_POSITIVE_MIN_ALERTS: u32 = 5;      // Fewer alerts than this are too few to judge a rule
+
+// Habit trends in the report (time to the first alert after sitting down, time ignoring alerts)
+pub const TRENDS_WEEKS: u32 = 8;
+pub const SITTING_GAP_SECS: i64 = 5 * 60; // A longer break out of view ends a sitting
+
+// Camera placement check
+pub const PLACEMENT_EDGE_MARGIN: f32 = 0.05;     // Keypoints closer than this to an edge (normalized) count as cut off
+pub const PLACEMENT_MIN_BRIGHTNESS: f32 = 60.0;  // Average luma below this is too dark
+pub const PLACEMENT_MAX_BRIGHTNESS: f32 = 200.0; // Average luma above this is washed out
+
+// Low light: a scene this dark for a while gets a toast and a banner in the debug window, and
+// keypoints then need more confidence, since the model guesses in the dark. Per user in
+// settings.json "low_light_raise_confidence".
+pub const LOW_LIGHT_BRIGHTNESS: f32 = 45.0;         // Average luma below this is too dark...
+pub const LOW_LIGHT_RECOVER_BRIGHTNESS: f32 = 60.0; // ...until back above this
+pub const LOW_LIGHT_SECS: u64 = 10;                 // How long it must stay dark before the warning
+pub const LOW_LIGHT_RAISE_CONFIDENCE: bool = true;
+pub const LOW_LIGHT_KEYPOINT_CONFIDENCE: f32 = 0.5; // Min keypoint score while it's dark (usually KEYPOINT_CONFIDENCE)
+
+// Region of interest: the model sees a crop around where the person was last found, so someone
+// small in a wide-angle view gets more of the model's input; the whole frame is used again
+// whenever they're lost
+pub const ROI_CROP: bool = true;
+pub const ROI_MIN_KEYPOINTS: usize = 3; // Confident keypoints needed to crop the next frame
+pub const ROI_MARGIN: f32 = 0.6;        // Room around the keypoints, as a share of their spread on each side
+pub const ROI_MIN_SIZE: f32 = 0.4;      // Smallest crop, as a share of the frame's sides
+
+// Primary subject: with several people in view (or a passerby the model locks on to), the user
+// is whoever is closest to where the user was last seen
+pub const PRIMARY_MAX_JUMP: f32 = 0.15;       // How far the user can move between frames (share of the frame)
+pub const PRIMARY_MIN_SIZE_RATIO: f32 = 0.6;  // Anyone else must be at least this big relative to the user to take over...
+pub const PRIMARY_FORGET_FRAMES: u32 = 10;    // ...until the user has been missing this many frames
+
+// Screen recording: no blur while an app uses Graphics Capture or one of these recorders is open.
+// OBS, Streamlabs and XSplit aren't listed - they stay open to serve the virtual camera.
+pub const SCREEN_RECORDER_PROCESSES: &[&str] = &[
+    "CamRecorder.exe", "bdcam.exe", "ScreenToGif.exe",
+];
+pub const SCREEN_CAPTURE_CHECK_SECS: u64 = 5;
+
+// App exemptions: while one of these apps is in the foreground the overlay stays clear
+// (Overlay) or detection pauses (Detection). Per user in settings.json "app_exemptions".
+pub const APP_EXEMPTIONS: &[(&str, Exemption)] = &[
+    ("vlc.exe", Exemption::Overlay), ("mpc-hc64.exe", Exemption::Overlay),
+    ("Zoom.exe", Exemption::Overlay), ("ms-teams.exe", Exemption::Overlay),
+];
+pub const FOREGROUND_APP_CHECK_SECS: u64 = 1;
+
+// Video calls: detection pauses and the camera is released while one of these apps uses the
+// webcam or a microphone, so the call gets the camera. Browsers are listed for Meet and other
+// web calls. Per user in settings.json "pause_during_calls" / "call_apps".
+pub const PAUSE_DURING_CALLS: bool = true;
+pub const CALL_APPS: &[&str] = &[
+    "Zoom.exe", "ms-teams.exe", "Teams.exe", "MSTeams", "Webex.exe", "CiscoCollabHost.exe",
+    "Slack.exe", "Discord.exe", "Skype.exe", "chrome.exe", "msedge.exe", "firefox.exe",
+];
+pub const CALL_CHECK_SECS: u64 = 5;
+
+// Full screen: no overlay while a game, presentation or video runs full screen (exclusive or
+// borderless), optionally with a toast per alert instead
+pub const FULLSCREEN_SUPPRESSES_OVERLAY: bool = true;
+pub const FULLSCREEN_TOAST_INSTEAD: bool = true;
+
+// Focus Assist: what alerting is turned down to at each level (Full, NotificationsOnly or
+// Silent, which only logs). Per user in settings.json "focus_assist".
+pub const FOCUS_ASSIST_PRIORITY_ONLY: Enforcement = Enforcement::NotificationsOnly;
+pub const FOCUS_ASSIST_ALARMS_ONLY: Enforcement = Enforcement::Silent;
+pub const FOCUS_ASSIST_CHECK_SECS: u64 = 5;
+
+// Alert recordings: a small annotated camera frame per alert, shown in the alert history, plus
+// the frames around the alert as a PNG sequence. Off by default for privacy (settings.json
+// "alert_recording_enabled"); kept in %APPDATA%\PostureAI\recordings for RECORDING_RETENTION_DAYS
+pub const ALERT_SNAPSHOTS_ENABLED: bool = false;
+pub const EPISODE_FRAMES_BEFORE: usize = 15; // Inferred frames kept from before the alert (none in low-memory mode)
+pub const EPISODE_FRAMES_AFTER: usize = 30;  // ...and recorded after it starts
+pub const EPISODE_FRAME_WIDTH: u32 = 320;    // Sequence frames are scaled down to this width
+
+// Log file (%APPDATA%\PostureAI\logs, one per day) and console output
+pub const LOG_LEVEL: LogLevel = LogLevel::Info; // Or override "log_level" in settings.json
+pub const LOG_TO_CONSOLE: bool = true;          // Also write to stderr (release builds have no console)
+
+// Debug logging: per-inference keypoints as ndjson in %APPDATA%\PostureAI\logs
+pub const KEYPOINT_LOG_ENABLED: bool = false;
+pub const KEYPOINT_LOG_SAMPLE_EVERY: u64 = 10; // Write every Nth inference (1 = all)
+
+// Safe mode: after this many launches in a row that crashed, start with default settings,
+// CPU-only inference and no overlay
+pub const SAFE_MODE_AFTER_CRASHES: u32 = 3;
+
+// Background maintenance of the app-data directory
+pub const SHUTDOWN_GRACE_MS: u64 = 2000;     // How long Quit waits for report/maintenance jobs to stop
+pub const MAINTENANCE_INTERVAL_HOURS: u64 = 6;
+pub const LOG_RETENTION_DAYS: u32 = 14;
+pub const RECORDING_RETENTION_DAYS: u32 = 7; // Default for settings.json "recording_retention_days"
+
+// Geofencing: only activate on these Wi-Fi networks or machine names.
+// Leave both lists empty to run everywhere.
+pub const ACTIVE_SSIDS: &[&str] = &[];     // e.g. &["OfficeWiFi"]
+pub const ACTIVE_HOSTNAMES: &[&str] = &[]; // e.g. &["WORK-LAPTOP"]
+pub const GEOFENCE_CHECK_SECS: u64 = 30;   // How often to re-check location
+
+// Active hours: detection sleeps and the camera is released outside this daily window
+// (local start and end hour, e.g. Some((8, 19)); None = always). A window is learned from
+// when you're at the desk and proposed once enough days were recorded.
+pub const ACTIVE_HOURS: Option<(u32, u32)> = None;
+pub const ACTIVE_HOURS_LEARNING_ENABLED: bool = true;
+pub const ACTIVE_HOURS_AUTO_APPLY: bool = false;  // Apply a learned window without asking
+pub const ACTIVE_HOURS_MIN_DAYS: u32 = 7;         // Days at the desk needed before proposing
+pub const ACTIVE_HOURS_TYPICAL_PERCENT: u32 = 30; // An hour is typical when you were there on this share of days
+
+// Work schedule: detection and the overlay only run in these windows (days, then local hours;
+// several separated by ";"). The tray can force detection on outside them. None = always.
+pub const WORK_SCHEDULE: Option<&str> = None; // e.g. Some("Mon-Fri 09:00-18:00")
+
+// Companion web dashboard: live status, charts and settings in a browser, e.g. on a phone on
+// the same network. Opt-in per user with "dashboard_enabled" in settings.json.
+pub const DASHBOARD_ENABLED: bool = false;
+pub const DASHBOARD_PORT: u16 = 8765;
+pub const DASHBOARD_LAN: bool = true; // false = only reachable from this PC
+
+// Local API: state and control over HTTP plus a WebSocket stream of keypoints, for external
+// dashboards and stream overlays. Only reachable from this PC; opt-in with "api_enabled".
+pub const API_ENABLED: bool = false;
+pub const API_PORT: u16 = 8766;
+
+// Integrations: posture events (bad posture start/end, away, calibrated) POSTed as JSON to a
+// webhook, e.g. Slack, Discord or Home Assistant. Per user with "webhook_url" in settings.json.
+pub const WEBHOOK_URL: Option<&str> = None;
+pub const WEBHOOK_RETRIES: u32 = 3;      // Extra attempts after a failed POST...
+pub const WEBHOOK_RETRY_SECS: u64 = 2;   // ...waiting this long before the first, twice as long each time after
+
+// MQTT: live state, score and presence as retained topics below MQTT_TOPIC, plus each posture
+// event, e.g. to flash a smart bulb. Per user with "mqtt" in settings.json.
+pub const MQTT_BROKER: Option<&str> = None;              // "host:port", e.g. Some("192.168.1.10:1883")
+pub const MQTT_TOPIC: &str = "posture_ai";
+pub const MQTT_DISCOVERY: bool = true;                   // Announce the sensors to Home Assistant...
+pub const MQTT_DISCOVERY_PREFIX: &str = "homeassistant"; // ...below its discovery prefix
+pub const MQTT_KEEP_ALIVE_SECS: u64 = 60;
+pub const MQTT_RETRY_SECS: u64 = 10;                     // Wait before reconnecting to the broker
+
+// OSC: every frame's keypoints as an OSC bundle over UDP, for VTuber rigs or TouchDesigner.
+// Per user with "osc" in settings.json.
+pub const OSC_TARGET: Option<&str> = None; // "host:port", e.g. Some("127.0.0.1:9000")
+pub const OSC_PREFIX: &str = "/posture";
+
+/// Rejects values the app can't run with (checked at startup and by `--status`)
+pub fn validate() -> Result<(), String> {
+    if CAMERA_ROTATION_DEGREES >= 360 {
+        return Err(format!("CAMERA_ROTATION_DEGREES must be 0-359, not {}", CAMERA_ROTATION_DEGREES));
+    }
+    if WIDTH == 0 || HEIGHT == 0 {
+        return Err("WIDTH and HEIGHT must be non-zero".to_string());
+    }
+    if matches!(CAMERA_RESOLUTION, Some((0, _)) | Some((_, 0))) || matches!(LOW_MEMORY_RESOLUTION, (0, _) | (_, 0)) || CAMERA_FPS == 0 {
+        return Err("CAMERA_RESOLUTION, LOW_MEMORY_RESOLUTION and CAMERA_FPS must be non-zero".to_string());
+    }
+    if MAX_ALPHA > 255 {
+        return Err(format!("MAX_ALPHA must be 0-255, not {}", MAX_ALPHA));
+    }
+    if SOUND_VOLUME > 100 {
+        return Err(format!("SOUND_VOLUME must be 0-100, not {}", SOUND_VOLUME));
+    }
+    for (name, value) in [
+        ("KEYPOINT_CONFIDENCE", KEYPOINT_CONFIDENCE),
+        ("YOLO_MIN_PERSON_CONFIDENCE", YOLO_MIN_PERSON_CONFIDENCE),
+        ("YOLO_NMS_IOU", YOLO_NMS_IOU),
+        ("PRIMARY_MIN_SIZE_RATIO", PRIMARY_MIN_SIZE_RATIO),
+        ("GHOST_OPACITY", GHOST_OPACITY),
+        ("SKELETON_MIN_SCORE", SKELETON_MIN_SCORE),
+        ("OVERLAY_MIN_INTENSITY", OVERLAY_MIN_INTENSITY),
+        ("ROI_MIN_SIZE", ROI_MIN_SIZE),
+    ] {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(format!("{} must be between 0 and 1, not {}", name, value));
+        }
+    }
+    if !(0.0..=GOOD_POSTURE_DEVIATION).contains(&GOOD_POSTURE_RECOVERY) {
+        return Err("GOOD_POSTURE_RECOVERY must be between 0 and GOOD_POSTURE_DEVIATION".to_string());
+    }
+    // The history records per second, so every second needs at least one inference
+    if !(INFERENCE_MIN_INTERVAL_MS..1000).contains(&INFERENCE_MAX_INTERVAL_MS) {
+        return Err("INFERENCE_MIN_INTERVAL_MS must not exceed INFERENCE_MAX_INTERVAL_MS, which must be below 1000".to_string());
+    }
+    if let Some((start, end)) = ACTIVE_HOURS {
+        if start >= 24 || end >= 24 || start == end {
+            return Err(format!("ACTIVE_HOURS must be two different hours 0-23, not ({}, {})", start, end));
+        }
+    }
+    if let Some(schedule) = WORK_SCHEDULE {
+        WorkSchedule::parse(schedule).map_err(|e| format!("WORK_SCHEDULE: {}", e))?;
+    }
+    if TRENDS_WEEKS == 0 || SITTING_GAP_SECS <= 0 {
+        return Err("TRENDS_WEEKS and SITTING_GAP_SECS must be positive".to_string());
+    }
+    if EYE_BREAK_SECS == 0 || STRETCH_BREAK_MINS == 0 || BREAK_SNOOZE_MINS == 0 {
+        return Err("EYE_BREAK_SECS, STRETCH_BREAK_MINS and BREAK_SNOOZE_MINS must be positive".to_string());
+    }
+    if !MODEL_DOWNLOAD_URL.contains("{file}") || !MODEL_DOWNLOAD_URL.starts_with("https://") {
+        return Err("MODEL_DOWNLOAD_URL must be an https:// URL containing {file}".to_string());
+    }
+    if let Some((file, _)) = MODEL_SHA256.iter().find(|(_, digest)| !model_download::is_sha256(digest)) {
+        return Err(format!("MODEL_SHA256 for {} must be 64 hex digits", file));
+    }
+    if HTTP_CONNECT_TIMEOUT_SECS == 0 || HTTP_RECEIVE_TIMEOUT_SECS == 0 {
+        return Err("HTTP_CONNECT_TIMEOUT_SECS and HTTP_RECEIVE_TIMEOUT_SECS must be positive".to_string());
+    }
+    if DASHBOARD_PORT == 0 {
+        return Err("DASHBOARD_PORT must be a fixed port, not 0".to_string());
+    }
+    if PLACEMENT_MIN_BRIGHTNESS >= PLACEMENT_MAX_BRIGHTNESS {
+        return Err("PLACEMENT_MIN_BRIGHTNESS must be below PLACEMENT_MAX_BRIGHTNESS".to_string());
+    }
+    if LOW_LIGHT_BRIGHTNESS > LOW_LIGHT_RECOVER_BRIGHTNESS {
+        return Err("LOW_LIGHT_BRIGHTNESS must not exceed LOW_LIGHT_RECOVER_BRIGHTNESS".to_string());
+    }
+    KEYPOINT_SMOOTHING.validate().map_err(|e| format!("KEYPOINT_SMOOTHING: {}", e))?;
+    if PREVIEW_FRAME_INTERVAL == 0 || LOW_MEMORY_PREVIEW_INTERVAL == 0 || KEYPOINT_LOG_SAMPLE_EVERY == 0 {
+        return Err("PREVIEW_FRAME_INTERVAL, LOW_MEMORY_PREVIEW_INTERVAL and KEYPOINT_LOG_SAMPLE_EVERY must be at least 1".to_string());
+    }
+    Ok(())
+}
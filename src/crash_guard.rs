@@ -0,0 +1,77 @@
+/// Crash-loop protection: a marker file exists while the app runs and is removed when it
+/// exits cleanly, so markers found at startup count launches that crashed. After
+/// SAFE_MODE_AFTER_CRASHES of them in a row the app starts in safe mode.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use anyhow::Result;
+use tracing::warn;
+use windows::core::HSTRING;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::{MessageBoxW, MB_ICONWARNING, MB_OK};
+
+use crate::app_data;
+use crate::config::SAFE_MODE_AFTER_CRASHES;
+use crate::i18n::{tr, tr_args};
+
+const MARKER_FILE: &str = "running.marker";
+
+/// Present for as long as this launch runs. Dropping it (a normal return, or an error that
+/// was reported) removes the marker; a panic or a hard crash leaves it behind.
+pub struct LaunchMarker {
+    path: PathBuf,
+    unclean_launches: u32,
+}
+
+impl LaunchMarker {
+    pub fn begin() -> Result<Self> {
+        Self::begin_at(&app_data::file_path(MARKER_FILE)?)
+    }
+
+    /// Call only once the single-instance lock is held, or a second instance would count
+    /// the running one as a crash
+    pub fn begin_at(path: &Path) -> Result<Self> {
+        // The marker holds how many launches in a row had crashed before the one that wrote it
+        let unclean_launches = match fs::read_to_string(path) {
+            Ok(text) => text.trim().parse::<u32>().unwrap_or(0) + 1,
+            Err(_) => 0,
+        };
+        app_data::write_atomic(path, unclean_launches.to_string().as_bytes())?;
+        Ok(Self { path: path.to_path_buf(), unclean_launches })
+    }
+
+    /// Consecutive earlier launches that didn't exit cleanly
+    pub fn unclean_launches(&self) -> u32 {
+        self.unclean_launches
+    }
+
+    pub fn needs_safe_mode(&self) -> bool {
+        self.unclean_launches >= SAFE_MODE_AFTER_CRASHES
+    }
+}
+
+impl Drop for LaunchMarker {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            return;
+        }
+        if let Err(e) = fs::remove_file(&self.path) {
+            warn!("Failed to remove the launch marker: {}", e);
+        }
+    }
+}
+
+/// Explains safe mode and how to leave it, on its own thread so startup continues
+pub fn show_safe_mode_dialog(unclean_launches: u32) {
+    let text = format!(
+        "{}\n\n{}",
+        tr_args("safe_mode.message", &[("count", &unclean_launches)]),
+        tr("safe_mode.how_to_leave"),
+    );
+    let title = tr("safe_mode.title");
+    thread::spawn(move || unsafe {
+        MessageBoxW(HWND::default(), &HSTRING::from(text), &HSTRING::from(title), MB_OK | MB_ICONWARNING);
+    });
+}
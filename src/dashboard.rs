@@ -0,0 +1,484 @@
+/// Companion web dashboard: a small HTTP server with a built-in page showing the live status,
+/// charts from the history database and the main settings, so a phone on the same network can
+/// follow along. Every request must carry the access token stored in the app-data directory:
+/// the link has it in the query, and loading the page moves it into a cookie.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use windows::core::{w, HSTRING, PCWSTR};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Shell::ShellExecuteW;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+use crate::app_data;
+use crate::blur_overlay::OverlayEffect;
+use crate::engine::PostureStatus;
+use crate::history::{HistoryReader, SecondRow};
+use crate::i18n::tr;
+use crate::local_time::{self, LocalTime};
+use crate::report::PeriodSummary;
+use crate::settings::{SettingChange, Settings};
+
+const PAGE: &str = include_str!("../assets/dashboard.html");
+const TOKEN_FILE: &str = "dashboard-token.txt";
+const TOKEN_COOKIE: &str = "posture_token";
+const TOKEN_COOKIE_MAX_AGE_SECS: u64 = 365 * 24 * 60 * 60;
+const DAY_SECS: i64 = 24 * 60 * 60;
+// Requests are tiny; anything bigger is refused rather than buffered
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+// Strings the page shows, fetched in the app's language
+const STRING_KEYS: &[&str] = &[
+    "dashboard.title", "dashboard.live", "dashboard.score", "dashboard.today_by_hour", "dashboard.settings",
+    "dashboard.slouch_goal", "dashboard.save", "dashboard.saved", "dashboard.phone", "dashboard.below_baseline",
+    "dashboard.state.good", "dashboard.state.approaching", "dashboard.state.slouching", "dashboard.state.alert",
+    "dashboard.state.calibrating", "dashboard.state.not_calibrated", "dashboard.state.paused", "dashboard.state.dormant",
+    "dashboard.state.away", "dashboard.state.tracking_poor",
+    "report.last_7_days", "settings_window.sensitivity", "settings_window.debounce", "settings_window.fade",
+    "settings_window.effect", "effect.acrylic_blur", "effect.dim", "effect.tint", "effect.red_vignette",
+];
+
+/// What the app is doing right now, as shown on the page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiveState {
+    Good,
+    Approaching,
+    Slouching,
+    Alert,
+    Calibrating,
+    NotCalibrated,
+    Paused,
+    /// Outside the geofence, the active hours or the work schedule
+    Dormant,
+    /// Nobody at the desk
+    Away,
+    /// Too dark or blocked to judge posture
+    TrackingPoor,
+}
+
+impl From<PostureStatus> for LiveState {
+    fn from(status: PostureStatus) -> Self {
+        match status {
+            PostureStatus::NotCalibrated => LiveState::NotCalibrated,
+            PostureStatus::Calibrating => LiveState::Calibrating,
+            PostureStatus::Good => LiveState::Good,
+            PostureStatus::Approaching => LiveState::Approaching,
+            PostureStatus::Slouching => LiveState::Slouching,
+            PostureStatus::Alert => LiveState::Alert,
+            PostureStatus::Away => LiveState::Away,
+            PostureStatus::TrackingPoor => LiveState::TrackingPoor,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LiveStatus {
+    pub state: LiveState,
+    /// How far (pixels) the eyes are below the baseline
+    pub delta_px: Option<f32>,
+    pub sensitivity_px: f32,
+}
+
+/// The settings the page can change; absent fields stay as they are
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SettingsForm {
+    pub sensitivity_px: Option<f32>,
+    pub debounce_ms: Option<u64>,
+    pub fade_ms: Option<u64>,
+    pub overlay_effect: Option<OverlayEffect>,
+    pub slouch_goal_percent: Option<u32>,
+}
+
+impl SettingsForm {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            sensitivity_px: Some(settings.sensitivity_px),
+            debounce_ms: Some(settings.debounce_ms),
+            fade_ms: Some(settings.fade_ms),
+            overlay_effect: Some(settings.overlay_effect),
+            slouch_goal_percent: Some(settings.goals.max_slouch_percent),
+        }
+    }
+
+    /// The edits to apply, in the same form the settings window sends them
+    pub fn changes(&self) -> Vec<SettingChange> {
+        let mut changes = Vec::new();
+        changes.extend(self.sensitivity_px.map(SettingChange::Sensitivity));
+        changes.extend(self.debounce_ms.map(SettingChange::DebounceMs));
+        changes.extend(self.fade_ms.map(SettingChange::FadeMs));
+        changes.extend(self.overlay_effect.map(SettingChange::OverlayEffect));
+        changes.extend(self.slouch_goal_percent.map(SettingChange::SlouchGoalPercent));
+        changes
+    }
+}
+
+/// Monitored and slouching time on one local day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DayTotals {
+    pub date: String,
+    pub monitored_secs: u64,
+    pub slouching_secs: u64,
+}
+
+/// History shown in the charts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    /// Share of today's monitored time spent sitting well; None before anything was monitored
+    pub score: Option<u64>,
+    /// Today's slouching share per local hour; None for hours that weren't monitored
+    pub slouch_percent_by_hour: Vec<Option<u64>>,
+    /// The last 7 days, oldest first
+    pub days: Vec<DayTotals>,
+}
+
+impl Summary {
+    /// Summarizes `rows` (oldest first) for the 7 local days up to `now_secs`
+    pub fn from_rows(rows: &[SecondRow], now_secs: i64, utc_offset_secs: i64) -> Self {
+        let local_secs = now_secs + utc_offset_secs;
+        let today_start = local_secs - local_secs.rem_euclid(DAY_SECS) - utc_offset_secs;
+
+        let periods: Vec<(i64, PeriodSummary)> = (0..7)
+            .map(|days_ago| {
+                let start = today_start - (6 - days_ago) * DAY_SECS;
+                let first = rows.partition_point(|row| row.second < start);
+                let end = rows.partition_point(|row| row.second < start + DAY_SECS);
+                (start, PeriodSummary::from_rows(&rows[first..end], 0, utc_offset_secs))
+            })
+            .collect();
+        let today = &periods[6].1;
+
+        Self {
+            score: (today.monitored_secs > 0).then(|| 100 - today.slouching_secs * 100 / today.monitored_secs),
+            slouch_percent_by_hour: (0..24)
+                .map(|hour| {
+                    let monitored = today.monitored_by_hour[hour];
+                    (monitored > 0).then(|| today.slouching_by_hour[hour] * 100 / monitored)
+                })
+                .collect(),
+            days: periods.iter()
+                .map(|(start, summary)| DayTotals {
+                    date: LocalTime::from_unix_secs(*start, utc_offset_secs).date_string(),
+                    monitored_secs: summary.monitored_secs,
+                    slouching_secs: summary.slouching_secs,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A parsed HTTP request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    /// The `token` query parameter
+    pub token: Option<String>,
+    /// Names as sent, values trimmed
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// The first header called `name`, in any case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(header, _)| header.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+
+    /// The value of cookie `name`, if the browser sent it
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.header("cookie")?
+            .split(';')
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(cookie, _)| *cookie == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Whether `given` is `token`, compared in constant time so response times don't reveal how
+/// much of a guess was right
+pub fn token_matches(given: &str, token: &str) -> bool {
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn header_end(raw: &[u8]) -> Option<usize> {
+    raw.windows(4).position(|window| window == b"\r\n\r\n").map(|at| at + 4)
+}
+
+fn headers(head: &str) -> Vec<(String, String)> {
+    head.lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// Parses a complete request; None while the headers or body are still incomplete, or if
+/// it isn't HTTP at all
+pub fn parse_request(raw: &[u8]) -> Option<Request> {
+    let body_start = header_end(raw)?;
+    let head = std::str::from_utf8(&raw[..body_start]).ok()?;
+    let headers = headers(head);
+    let content_length = headers.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    let body = raw.get(body_start..body_start + content_length)?;
+
+    let mut parts = head.lines().next()?.split(' ');
+    let (method, target) = (parts.next()?, parts.next()?);
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let token = query.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| *name == "token")
+        .map(|(_, value)| value.to_string());
+    Some(Request { method: method.to_string(), path: path.to_string(), token, headers, body: body.to_vec() })
+}
+
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    /// Sent after the standard ones
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub(crate) fn json(value: &impl Serialize) -> Self {
+        match serde_json::to_vec(value) {
+            Ok(body) => Self { status: 200, content_type: "application/json", headers: Vec::new(), body },
+            Err(e) => Self::error(500, &e.to_string()),
+        }
+    }
+
+    pub(crate) fn error(status: u16, message: &str) -> Self {
+        Self { status, content_type: "text/plain; charset=utf-8", headers: Vec::new(), body: message.as_bytes().to_vec() }
+    }
+
+    /// Back to the page without the token in the address, which the browser keeps as a cookie
+    fn token_cookie(token: &str) -> Self {
+        let cookie = format!("{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Strict", TOKEN_COOKIE, token, TOKEN_COOKIE_MAX_AGE_SECS);
+        Self {
+            status: 303,
+            content_type: "text/plain; charset=utf-8",
+            headers: vec![("Location", "/".to_string()), ("Set-Cookie", cookie)],
+            body: Vec::new(),
+        }
+    }
+
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let reason = match self.status {
+            200 => "OK",
+            303 => "See Other",
+            400 => "Bad Request",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            _ => "Internal Server Error",
+        };
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n",
+            self.status,
+            reason,
+            self.content_type,
+            self.body.len(),
+        );
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("\r\n");
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(&self.body);
+        bytes
+    }
+}
+
+/// State shared between the main loop and the server thread
+struct Shared {
+    live: LiveStatus,
+    settings: SettingsForm,
+    lan_url: Option<String>,
+}
+
+#[derive(Serialize)]
+struct LiveReply<'a> {
+    #[serde(flatten)]
+    live: LiveStatus,
+    lan_url: &'a Option<String>,
+}
+
+fn route(request: &Request, token: &str, shared: &Mutex<Shared>, changes: &Sender<SettingChange>) -> Response {
+    // The link's token is only taken once, by the page, and swapped for the cookie
+    let page = request.method == "GET" && request.path == "/";
+    if page && request.token.as_deref().is_some_and(|given| token_matches(given, token)) {
+        return Response::token_cookie(token);
+    }
+    if !request.cookie(TOKEN_COOKIE).is_some_and(|given| token_matches(given, token)) {
+        return Response::error(403, "Missing or wrong token");
+    }
+    let shared_state = || shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/") => Response { status: 200, content_type: "text/html; charset=utf-8", headers: Vec::new(), body: PAGE.as_bytes().to_vec() },
+        ("GET", "/api/strings") => {
+            Response::json(&STRING_KEYS.iter().map(|key| (*key, tr(key))).collect::<HashMap<_, _>>())
+        }
+        ("GET", "/api/live") => {
+            let shared = shared_state();
+            Response::json(&LiveReply { live: shared.live, lan_url: &shared.lan_url })
+        }
+        ("GET", "/api/summary") => {
+            let now_secs = (local_time::unix_millis() / 1000) as i64;
+            let rows = HistoryReader::open().and_then(|history| history.seconds_since((now_secs - 7 * DAY_SECS) * 1000));
+            match rows {
+                Ok(rows) => Response::json(&Summary::from_rows(&rows, now_secs, local_time::utc_offset_secs())),
+                Err(e) => Response::error(500, &e.to_string()),
+            }
+        }
+        ("GET", "/api/settings") => Response::json(&shared_state().settings),
+        ("POST", "/api/settings") => match serde_json::from_slice::<SettingsForm>(&request.body) {
+            Ok(form) => {
+                for change in form.changes() {
+                    let _ = changes.send(change);
+                }
+                Response::json(&form)
+            }
+            Err(e) => Response::error(400, &e.to_string()),
+        },
+        (_, "/" | "/api/strings" | "/api/live" | "/api/summary" | "/api/settings") => Response::error(405, "Method not allowed"),
+        _ => Response::error(404, "Not found"),
+    }
+}
+
+/// Reads one request from `stream`; the error response instead if it's too large or cut off
+pub(crate) fn read_request(stream: &mut TcpStream) -> Result<std::result::Result<Request, Response>> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut raw = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(request) = parse_request(&raw) {
+            return Ok(Ok(request));
+        }
+        if raw.len() > MAX_REQUEST_BYTES {
+            return Ok(Err(Response::error(413, "Request too large")));
+        }
+        match stream.read(&mut chunk)? {
+            0 => return Ok(Err(Response::error(400, "Incomplete request"))),
+            read => raw.extend_from_slice(&chunk[..read]),
+        }
+    }
+}
+
+fn serve(mut stream: TcpStream, token: &str, shared: &Mutex<Shared>, changes: &Sender<SettingChange>) -> Result<()> {
+    let response = match read_request(&mut stream)? {
+        Ok(request) => route(&request, token, shared, changes),
+        Err(response) => response,
+    };
+    stream.write_all(&response.to_bytes())?;
+    Ok(())
+}
+
+/// The token in the app-data directory, created on first use
+fn load_token() -> Result<String> {
+    let path = app_data::file_path(TOKEN_FILE)?;
+    if let Ok(token) = fs::read_to_string(&path) {
+        if !token.trim().is_empty() {
+            return Ok(token.trim().to_string());
+        }
+    }
+    let token = format!("{:032x}", rand::random::<u128>());
+    app_data::write_atomic(&path, token.as_bytes())?;
+    Ok(token)
+}
+
+/// This PC's address on the local network (no packet is sent)
+fn lan_address() -> Option<std::net::IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    Some(socket.local_addr().ok()?.ip()).filter(|ip| !ip.is_loopback() && !ip.is_unspecified())
+}
+
+pub struct Dashboard {
+    shared: Arc<Mutex<Shared>>,
+    changes: Receiver<SettingChange>,
+    local_url: String,
+}
+
+impl Dashboard {
+    /// Starts serving on `port`, on every network interface if `lan` or only this PC otherwise
+    pub fn start(port: u16, lan: bool, settings: &Settings) -> Result<Self> {
+        let token = load_token()?;
+        let host = if lan { "0.0.0.0" } else { "127.0.0.1" };
+        let listener = TcpListener::bind((host, port)).map_err(|e| anyhow!("Port {} unavailable: {}", port, e))?;
+        let lan_url = lan.then(lan_address).flatten().map(|ip| format!("http://{}:{}/?token={}", ip, port, token));
+
+        let shared = Arc::new(Mutex::new(Shared {
+            live: LiveStatus { state: LiveState::NotCalibrated, delta_px: None, sensitivity_px: settings.sensitivity_px },
+            settings: SettingsForm::from_settings(settings),
+            lan_url,
+        }));
+        let (sender, changes) = mpsc::channel();
+        let server_shared = Arc::clone(&shared);
+        let server_token = token.clone();
+        thread::spawn(move || {
+            // One connection at a time; the read timeout keeps a stalled client from blocking others for long
+            for stream in listener.incoming().flatten() {
+                if let Err(e) = serve(stream, &server_token, &server_shared, &sender) {
+                    warn!("Dashboard request failed: {}", e);
+                }
+            }
+        });
+
+        Ok(Self { shared, changes, local_url: format!("http://127.0.0.1:{}/?token={}", port, token) })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Shared> {
+        self.shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Updates the live status the page polls
+    pub fn publish(&self, state: LiveState, delta_px: Option<f32>) {
+        let mut shared = self.lock();
+        shared.live.state = state;
+        shared.live.delta_px = delta_px;
+    }
+
+    /// Call after settings changed, wherever the change came from
+    pub fn set_settings(&self, settings: &Settings) {
+        let mut shared = self.lock();
+        shared.live.sensitivity_px = settings.sensitivity_px;
+        shared.settings = SettingsForm::from_settings(settings);
+    }
+
+    /// Edits made on the page since the last call
+    pub fn poll_changes(&self) -> Vec<SettingChange> {
+        self.changes.try_iter().collect()
+    }
+
+    /// The address on the local network, if the dashboard is reachable there
+    pub fn lan_url(&self) -> Option<String> {
+        self.lock().lan_url.clone()
+    }
+
+    /// Opens the dashboard in the default browser
+    pub fn open(&self) -> Result<()> {
+        let url = HSTRING::from(self.local_url.as_str());
+        let result = unsafe { ShellExecuteW(HWND::default(), w!("open"), &url, PCWSTR::null(), PCWSTR::null(), SW_SHOWNORMAL) };
+        // Values up to 32 are error codes
+        if result.0 <= 32 {
+            bail!("ShellExecute failed with code {}", result.0);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+/// Debug preview window (raw Win32)
+///
+/// Closing the window or pressing Esc only hides it, so the app keeps running in the tray;
+/// "Show/Hide Debug Window" brings it back, recreating it if Windows destroyed it meanwhile.
+/// The window also pumps the thread's message queue, which the tray icon relies on.
+
+use std::cell::RefCell;
+
+use anyhow::{bail, Result};
+use tracing::{info, warn};
+use windows::core::{w, HSTRING};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    BeginPaint, EndPaint, InvalidateRect, StretchDIBits, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+    PAINTSTRUCT, SRCCOPY,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Input::KeyboardAndMouse::VIRTUAL_KEY;
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+/// Input collected since the last `poll_events`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindowEvent {
+    /// The close button was clicked (the window is already hidden)
+    CloseRequested,
+    /// A key went down (auto-repeat is filtered out)
+    KeyPressed(VIRTUAL_KEY),
+    /// Left click, normalized to the preview image (0.0 - 1.0)
+    Clicked(f32, f32),
+}
+
+/// Shared with the window procedure through GWLP_USERDATA
+struct WindowState {
+    width: usize,
+    height: usize,
+    pixels: Vec<u32>,
+    events: Vec<WindowEvent>,
+}
+
+pub struct DebugWindow {
+    hwnd: HWND,
+    title: String,
+    // Boxed so the pointer handed to the window procedure stays put
+    state: Box<RefCell<WindowState>>,
+}
+
+impl DebugWindow {
+    /// Creates the (hidden) window with a client area of `width` x `height` pixels
+    pub fn new(title: &str, width: usize, height: usize) -> Result<Self> {
+        unsafe {
+            let instance = GetModuleHandleW(None)?;
+            let wc = WNDCLASSW {
+                hInstance: instance.into(),
+                lpszClassName: w!("PostureDebugClass"),
+                lpfnWndProc: Some(Self::wnd_proc),
+                hCursor: LoadCursorW(None, IDC_ARROW)?,
+                ..Default::default()
+            };
+            RegisterClassW(&wc);
+        }
+
+        let state = Box::new(RefCell::new(WindowState {
+            width,
+            height,
+            pixels: vec![0; width * height],
+            events: Vec::new(),
+        }));
+        let mut window = Self { hwnd: HWND::default(), title: title.to_string(), state };
+        window.create()?;
+        Ok(window)
+    }
+
+    fn create(&mut self) -> Result<()> {
+        unsafe {
+            let instance = GetModuleHandleW(None)?;
+            let style = WS_OVERLAPPEDWINDOW;
+            let (width, height) = {
+                let state = self.state.borrow();
+                (state.width as i32, state.height as i32)
+            };
+            let mut rect = RECT { left: 0, top: 0, right: width, bottom: height };
+            AdjustWindowRectEx(&mut rect, style, false, WINDOW_EX_STYLE::default())?;
+
+            let hwnd = CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                w!("PostureDebugClass"),
+                &HSTRING::from(self.title.as_str()),
+                style,
+                CW_USEDEFAULT,
+                CW_USEDEFAULT,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+                None,
+                None,
+                instance,
+                None,
+            );
+            if hwnd.0 == 0 {
+                bail!("Failed to create the debug window");
+            }
+            SetWindowLongPtrW(hwnd, GWLP_USERDATA, &*self.state as *const RefCell<WindowState> as isize);
+            self.hwnd = hwnd;
+        }
+        Ok(())
+    }
+
+    /// Shows the window, recreating it if it no longer exists
+    pub fn show(&mut self) {
+        if !unsafe { IsWindow(self.hwnd) }.as_bool() {
+            info!("Recreating debug window.");
+            if let Err(e) = self.create() {
+                warn!("{}", e);
+                return;
+            }
+        }
+        unsafe {
+            ShowWindow(self.hwnd, SW_SHOW);
+            let _ = SetForegroundWindow(self.hwnd);
+        }
+    }
+
+    pub fn hide(&self) {
+        unsafe { ShowWindow(self.hwnd, SW_HIDE) };
+    }
+
+    pub fn is_visible(&self) -> bool {
+        unsafe { IsWindowVisible(self.hwnd) }.as_bool()
+    }
+
+    /// Dispatches all pending messages for this thread. Call once per loop iteration,
+    /// visible or not - tray menu clicks arrive through the same queue.
+    pub fn pump(&self) {
+        unsafe {
+            let mut msg = MSG::default();
+            while PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_REMOVE).as_bool() {
+                TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        }
+    }
+
+    /// Input received since the last call
+    pub fn poll_events(&self) -> Vec<WindowEvent> {
+        std::mem::take(&mut self.state.borrow_mut().events)
+    }
+
+    /// Replaces the preview image (0x00RRGGBB per pixel, `width` x `height`) and repaints
+    pub fn present(&self, buffer: &[u32]) {
+        self.state.borrow_mut().pixels.copy_from_slice(buffer);
+        unsafe { InvalidateRect(self.hwnd, None, false) };
+    }
+
+    fn paint(hwnd: HWND, state: &WindowState) {
+        unsafe {
+            let mut client = RECT::default();
+            let _ = GetClientRect(hwnd, &mut client);
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+
+            let (width, height) = (state.width as i32, state.height as i32);
+            let info = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: width,
+                    biHeight: -height, // top-down rows
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            // Scaled to whatever size the user dragged the window to
+            StretchDIBits(
+                hdc,
+                0,
+                0,
+                client.right - client.left,
+                client.bottom - client.top,
+                0,
+                0,
+                width,
+                height,
+                Some(state.pixels.as_ptr() as *const _),
+                &info,
+                DIB_RGB_COLORS,
+                SRCCOPY,
+            );
+            let _ = EndPaint(hwnd, &ps);
+        }
+    }
+
+    extern "system" fn wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        unsafe {
+            let state = GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *const RefCell<WindowState>;
+            let Some(state) = state.as_ref() else {
+                return DefWindowProcW(hwnd, msg, wparam, lparam);
+            };
+
+            match msg {
+                WM_CLOSE => {
+                    // Hide instead of destroying - the app lives on in the tray
+                    ShowWindow(hwnd, SW_HIDE);
+                    state.borrow_mut().events.push(WindowEvent::CloseRequested);
+                    LRESULT(0)
+                }
+                WM_KEYDOWN => {
+                    // Bit 30 is set for auto-repeat
+                    if lparam.0 & (1 << 30) == 0 {
+                        state.borrow_mut().events.push(WindowEvent::KeyPressed(VIRTUAL_KEY(wparam.0 as u16)));
+                    }
+                    LRESULT(0)
+                }
+                WM_LBUTTONDOWN => {
+                    let mut client = RECT::default();
+                    let _ = GetClientRect(hwnd, &mut client);
+                    let x = (lparam.0 & 0xFFFF) as i16 as f32;
+                    let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as f32;
+                    let (width, height) = ((client.right - client.left).max(1) as f32, (client.bottom - client.top).max(1) as f32);
+                    state.borrow_mut().events.push(WindowEvent::Clicked(x / width, y / height));
+                    LRESULT(0)
+                }
+                WM_PAINT => {
+                    Self::paint(hwnd, &state.borrow());
+                    LRESULT(0)
+                }
+                // The image covers the whole client area; skipping the erase avoids flicker
+                WM_ERASEBKGND => LRESULT(1),
+                _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+            }
+        }
+    }
+}
+
+impl Drop for DebugWindow {
+    fn drop(&mut self) {
+        unsafe {
+            // Detach the state first so late messages don't reach a freed pointer
+            SetWindowLongPtrW(self.hwnd, GWLP_USERDATA, 0);
+            let _ = DestroyWindow(self.hwnd);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+/// Which other apps are using the webcam, microphone or screen capture right now, from the
+/// Windows privacy consent store
+///
+/// Windows records every app's camera use under HKCU\...\CapabilityAccessManager\ConsentStore\webcam
+/// (and microphone use under `microphone`, Graphics Capture sessions under `graphicsCapture*`),
+/// packaged apps by package name and desktop apps under `NonPackaged` by path (with `#` for `\`).
+/// An entry whose `LastUsedTimeStop` is still 0 is using the device now.
+
+use std::ffi::c_void;
+use std::path::Path;
+
+use windows::core::{w, HSTRING, PCWSTR, PWSTR};
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegEnumKeyExW, RegGetValueW, RegOpenKeyExW, HKEY, HKEY_CURRENT_USER, KEY_READ, RRF_RT_REG_QWORD,
+};
+
+const CONSENT_STORE_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\CapabilityAccessManager\\ConsentStore";
+
+/// Whether a consent store entry with these FILETIME stamps is in use (started, never stopped)
+pub fn is_in_use(last_start: u64, last_stop: u64) -> bool {
+    last_start != 0 && last_stop == 0
+}
+
+/// The consent store key of a desktop app at `exe`
+pub fn key_for_exe(exe: &Path) -> String {
+    exe.display().to_string().replace('\\', "#")
+}
+
+/// A readable app name for a consent store key: the executable of a desktop app path,
+/// else the package name
+pub fn app_name(key: &str) -> &str {
+    key.rsplit('#').next().unwrap_or(key)
+}
+
+/// Names of the apps other than this one that have the webcam open
+pub fn webcam_users() -> Vec<String> {
+    users_of("webcam")
+}
+
+/// Names of the apps other than this one that are recording from a microphone
+pub fn microphone_users() -> Vec<String> {
+    users_of("microphone")
+}
+
+/// Names of the apps other than this one capturing the screen or a window through the Windows
+/// Graphics Capture API (screen recorders, OBS window capture, the Snipping Tool's recorder)
+pub fn screen_capture_users() -> Vec<String> {
+    let mut users = users_of("graphicsCaptureProgrammatic");
+    users.extend(users_of("graphicsCaptureWithoutBorder"));
+    users.sort();
+    users.dedup();
+    users
+}
+
+fn users_of(capability: &str) -> Vec<String> {
+    let own_key = std::env::current_exe().map(|exe| key_for_exe(&exe)).unwrap_or_default();
+    let device_key = format!("{}\\{}", CONSENT_STORE_KEY, capability);
+    let mut users = Vec::new();
+    for parent in [device_key.clone(), format!("{}\\NonPackaged", device_key)] {
+        for key in subkeys(&parent) {
+            if key == "NonPackaged" || key.eq_ignore_ascii_case(&own_key) {
+                continue;
+            }
+            let path = HSTRING::from(format!("{}\\{}", parent, key));
+            if is_in_use(read_stamp(&path, w!("LastUsedTimeStart")), read_stamp(&path, w!("LastUsedTimeStop"))) {
+                users.push(app_name(&key).to_string());
+            }
+        }
+    }
+    users
+}
+
+fn subkeys(path: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    unsafe {
+        let mut key = HKEY::default();
+        if RegOpenKeyExW(HKEY_CURRENT_USER, &HSTRING::from(path), 0, KEY_READ, &mut key).is_err() {
+            return names;
+        }
+        let mut buffer = [0u16; 512];
+        for index in 0.. {
+            let mut len = buffer.len() as u32;
+            let result =
+                RegEnumKeyExW(key, index, PWSTR(buffer.as_mut_ptr()), &mut len, None, PWSTR::null(), None, None);
+            if result.is_err() {
+                break;
+            }
+            names.push(String::from_utf16_lossy(&buffer[..len as usize]));
+        }
+        let _ = RegCloseKey(key);
+    }
+    names
+}
+
+// 0 if the value is missing
+fn read_stamp(path: &HSTRING, value: PCWSTR) -> u64 {
+    let mut stamp = 0u64;
+    let mut size = std::mem::size_of::<u64>() as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            path,
+            value,
+            RRF_RT_REG_QWORD,
+            None,
+            Some(&mut stamp as *mut u64 as *mut c_void),
+            Some(&mut size),
+        )
+    };
+    if result.is_ok() { stamp } else { 0 }
+}
@@ -0,0 +1,226 @@
+/// "What's wrong?" diagnostics: quick health checks explained in plain language
+///
+/// `FrameHealth` watches the frame loop; `run_checks` turns a snapshot of the app's
+/// state into findings, and `show_dialog` presents them without blocking detection.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use windows::core::HSTRING;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::{MessageBoxW, MB_ICONERROR, MB_ICONINFORMATION, MB_ICONWARNING, MB_OK};
+
+use crate::active_hours::ActiveHours;
+use crate::clock::SharedClock;
+use crate::i18n::{tr, tr_args};
+use crate::pose::{Pose, RIGHT_SHOULDER};
+use crate::schedule::WorkSchedule;
+
+// Weight of the newest frame in the running averages
+const SMOOTHING: f32 = 0.1;
+// No frame for this long means the camera has stalled
+const STALLED_AFTER: Duration = Duration::from_secs(3);
+const MIN_DETECTION_RATE: f32 = 0.5;
+const MIN_CONFIDENCE: f32 = 0.4;
+const SLOW_INFERENCE_MS: f32 = 200.0;
+
+fn smooth(average: Option<f32>, sample: f32) -> Option<f32> {
+    Some(average.map_or(sample, |average| average + (sample - average) * SMOOTHING))
+}
+
+/// Running view of the frame loop: frame rate, detection rate, confidence and inference time
+pub struct FrameHealth {
+    clock: SharedClock,
+    last_frame: Option<Instant>,
+    frame_interval_ms: Option<f32>,
+    detection_rate: Option<f32>,
+    confidence: Option<f32>,
+    inference_ms: Option<f32>,
+}
+
+impl FrameHealth {
+    pub fn new(clock: SharedClock) -> Self {
+        Self {
+            clock,
+            last_frame: None,
+            frame_interval_ms: None,
+            detection_rate: None,
+            confidence: None,
+            inference_ms: None,
+        }
+    }
+
+    /// Call once per processed frame
+    pub fn record(&mut self, pose: Option<&Pose>, inference: Duration) {
+        let now = self.clock.now();
+        if let Some(last) = self.last_frame {
+            self.frame_interval_ms = smooth(self.frame_interval_ms, now.duration_since(last).as_secs_f32() * 1000.0);
+        }
+        self.last_frame = Some(now);
+        self.inference_ms = smooth(self.inference_ms, inference.as_secs_f32() * 1000.0);
+
+        self.detection_rate = smooth(self.detection_rate, if pose.is_some() { 1.0 } else { 0.0 });
+        if let Some(pose) = pose {
+            // Face and shoulders are what the posture check relies on
+            let upper_body = &pose[..=RIGHT_SHOULDER];
+            let mean = upper_body.iter().map(|kp| kp.score).sum::<f32>() / upper_body.len() as f32;
+            self.confidence = smooth(self.confidence, mean);
+        }
+    }
+
+    pub fn report(&self) -> FrameReport {
+        FrameReport {
+            last_frame_age: self.last_frame.map(|last| self.clock.now().duration_since(last)),
+            fps: self.frame_interval_ms.filter(|ms| *ms > 0.0).map(|ms| 1000.0 / ms),
+            detection_rate: self.detection_rate,
+            confidence: self.confidence,
+            inference_ms: self.inference_ms,
+        }
+    }
+}
+
+/// Frame-loop figures at the time of the check (None until frames have been seen)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameReport {
+    pub last_frame_age: Option<Duration>,
+    pub fps: Option<f32>,
+    /// Share of recent frames with a detected person, 0-1
+    pub detection_rate: Option<f32>,
+    /// Average face/shoulder keypoint score, 0-1
+    pub confidence: Option<f32>,
+    pub inference_ms: Option<f32>,
+}
+
+/// Everything the checks look at, gathered by the main loop
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub model_name: String,
+    pub camera_open: bool,
+    pub frames: FrameReport,
+    pub overlay_enabled: bool,
+    pub overlay_ready: bool,
+    pub recorder: Option<String>,
+    pub calibrated: bool,
+    pub paused: bool,
+    pub location_allowed: bool,
+    /// The active-hours window while outside it
+    pub active_hours: Option<ActiveHours>,
+    /// The work schedule while idle outside it (not forced on)
+    pub work_schedule: Option<WorkSchedule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Problem,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+fn finding(severity: Severity, message: String) -> Finding {
+    Finding { severity, message }
+}
+
+/// Runs every check against `s`, most fundamental first
+pub fn run_checks(s: &Snapshot) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    if s.paused {
+        findings.push(finding(Severity::Warning, tr("diagnostics.paused")));
+    }
+    if !s.location_allowed {
+        findings.push(finding(Severity::Warning, tr("diagnostics.dormant")));
+    }
+    if let Some(hours) = s.active_hours {
+        findings.push(finding(Severity::Warning, tr_args("diagnostics.off_hours", &[("hours", &hours.describe())])));
+    }
+    if let Some(schedule) = &s.work_schedule {
+        findings.push(finding(Severity::Warning, tr_args("diagnostics.off_schedule", &[("schedule", &schedule.describe())])));
+    }
+
+    // Camera: only meaningful while detection is running
+    let watching = !s.paused && s.location_allowed && s.active_hours.is_none() && s.work_schedule.is_none();
+    if watching {
+        findings.push(match (s.camera_open, s.frames.last_frame_age) {
+            (false, _) => finding(Severity::Problem, tr("diagnostics.camera_closed")),
+            (true, None) => finding(Severity::Problem, tr("diagnostics.no_frames")),
+            (true, Some(age)) if age > STALLED_AFTER => finding(
+                Severity::Problem,
+                tr_args("diagnostics.camera_stalled", &[("seconds", &age.as_secs())]),
+            ),
+            (true, Some(_)) => finding(
+                Severity::Ok,
+                tr_args("diagnostics.camera_ok", &[("fps", &format!("{:.0}", s.frames.fps.unwrap_or(0.0)))]),
+            ),
+        });
+
+        if let (Some(rate), Some(confidence)) = (s.frames.detection_rate, s.frames.confidence) {
+            let percent = format!("{:.0}", confidence * 100.0);
+            findings.push(if rate < MIN_DETECTION_RATE {
+                finding(Severity::Problem, tr("diagnostics.not_detected"))
+            } else if confidence < MIN_CONFIDENCE {
+                finding(Severity::Warning, tr_args("diagnostics.low_confidence", &[("percent", &percent)]))
+            } else {
+                finding(Severity::Ok, tr_args("diagnostics.confidence_ok", &[("percent", &percent)]))
+            });
+        }
+    }
+
+    // The model is loaded before the loop starts, so only its speed can go wrong
+    let ms = format!("{:.0}", s.frames.inference_ms.unwrap_or(0.0));
+    let model_args: [(&str, &dyn std::fmt::Display); 2] = [("model", &s.model_name), ("ms", &ms)];
+    findings.push(match s.frames.inference_ms {
+        Some(ms) if ms > SLOW_INFERENCE_MS => finding(Severity::Warning, tr_args("diagnostics.model_slow", &model_args)),
+        Some(_) => finding(Severity::Ok, tr_args("diagnostics.model_ok", &model_args)),
+        None => finding(Severity::Ok, tr_args("diagnostics.model_loaded", &model_args)),
+    });
+
+    findings.push(if !s.overlay_enabled {
+        finding(Severity::Warning, tr("diagnostics.overlay_disabled"))
+    } else if !s.overlay_ready {
+        finding(Severity::Problem, tr("diagnostics.overlay_broken"))
+    } else if let Some(recorder) = &s.recorder {
+        finding(Severity::Warning, tr_args("diagnostics.overlay_suppressed", &[("name", recorder)]))
+    } else {
+        finding(Severity::Ok, tr("diagnostics.overlay_ok"))
+    });
+
+    if !s.calibrated {
+        findings.push(finding(Severity::Warning, tr("diagnostics.not_calibrated")));
+    }
+
+    findings
+}
+
+/// Shows the findings in a message box on its own thread, so detection keeps running
+pub fn show_dialog(findings: Vec<Finding>) {
+    let worst = findings.iter().map(|f| f.severity).max().unwrap_or(Severity::Ok);
+    let mut text = match worst {
+        Severity::Ok => tr("diagnostics.all_good"),
+        _ => tr("diagnostics.found_issues"),
+    };
+    text.push_str("\n\n");
+    for finding in &findings {
+        let mark = match finding.severity {
+            Severity::Ok => "✔",
+            Severity::Warning => "⚠",
+            Severity::Problem => "✖",
+        };
+        text.push_str(&format!("{}  {}\n", mark, finding.message));
+    }
+    let icon = match worst {
+        Severity::Ok => MB_ICONINFORMATION,
+        Severity::Warning => MB_ICONWARNING,
+        Severity::Problem => MB_ICONERROR,
+    };
+    let title = tr("diagnostics.title");
+
+    thread::spawn(move || unsafe {
+        MessageBoxW(HWND::default(), &HSTRING::from(text), &HSTRING::from(title), MB_OK | icon);
+    });
+}
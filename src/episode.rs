@@ -0,0 +1,106 @@
+/// Bad-posture episode recording: the frames just before and after an alert started, saved as a
+/// PNG sequence in `recordings\episode-<ms>\` that `--input` can replay
+///
+/// Frames are kept scaled down in a short ring buffer, so nothing reaches the disk unless an
+/// alert fires, and only while `alert_recording_enabled` is set.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use image::{imageops::FilterType, RgbImage};
+
+use crate::pipeline::FrameMeta;
+
+/// Prefix of episode folder names, which the retention cleanup looks for
+pub const EPISODE_PREFIX: &str = "episode-";
+
+struct ActiveEpisode {
+    dir: PathBuf,
+    remaining: usize,
+}
+
+pub struct EpisodeRecorder {
+    frames_before: usize,
+    frames_after: usize,
+    width: u32,
+    recent: VecDeque<(u64, RgbImage)>,
+    active: Option<ActiveEpisode>,
+}
+
+/// File name of the frame with sequence number `sequence`; zero-padded so name order is time order
+fn frame_name(sequence: u64) -> String {
+    format!("frame-{:08}.png", sequence)
+}
+
+impl EpisodeRecorder {
+    /// Keeps `frames_before` frames, records `frames_after` more once an episode starts, and
+    /// scales frames down to `width` pixels
+    pub fn new(frames_before: usize, frames_after: usize, width: u32) -> Self {
+        Self {
+            frames_before,
+            frames_after,
+            width: width.max(1),
+            recent: VecDeque::with_capacity(frames_before),
+            active: None,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.active.is_some()
+    }
+
+    fn scaled(&self, frame: &RgbImage) -> RgbImage {
+        if frame.width() <= self.width {
+            return frame.clone();
+        }
+        let height = (frame.height() as u64 * self.width as u64 / frame.width() as u64).max(1) as u32;
+        image::imageops::resize(frame, self.width, height, FilterType::Triangle)
+    }
+
+    /// Call for every inferred frame: buffers it, or saves it while an episode is being recorded
+    pub fn push(&mut self, frame: &RgbImage, meta: &FrameMeta) -> Result<()> {
+        if let Some(active) = self.active.as_mut() {
+            let dir = active.dir.clone();
+            active.remaining = active.remaining.saturating_sub(1);
+            if active.remaining == 0 {
+                self.active = None;
+            }
+            self.scaled(frame).save(dir.join(frame_name(meta.sequence)))?;
+            return Ok(());
+        }
+        if self.frames_before == 0 {
+            return Ok(());
+        }
+        if self.recent.len() == self.frames_before {
+            self.recent.pop_front();
+        }
+        let scaled = self.scaled(frame);
+        self.recent.push_back((meta.sequence, scaled));
+        Ok(())
+    }
+
+    /// Starts an episode in `recordings/episode-<now_ms>`: writes the buffered frames and
+    /// records the next ones. Returns the folder. An episode already running keeps going.
+    pub fn start(&mut self, recordings: &Path, now_ms: i64) -> Result<PathBuf> {
+        if let Some(active) = &self.active {
+            return Ok(active.dir.clone());
+        }
+        let dir = recordings.join(format!("{}{}", EPISODE_PREFIX, now_ms));
+        fs::create_dir_all(&dir)?;
+        for (sequence, frame) in self.recent.drain(..) {
+            frame.save(dir.join(frame_name(sequence)))?;
+        }
+        if self.frames_after > 0 {
+            self.active = Some(ActiveEpisode { dir: dir.clone(), remaining: self.frames_after });
+        }
+        Ok(dir)
+    }
+
+    /// Drops the buffered frames (after a pause, when recording was switched off, ...)
+    pub fn clear(&mut self) {
+        self.recent.clear();
+        self.active = None;
+    }
+}
@@ -0,0 +1,49 @@
+/// Library error type: the failure category a consumer (or the app's recovery logic) can
+/// match on, with the underlying error kept for the message
+///
+/// Camera, model and overlay code returns `PostureError`; modules outside these categories
+/// (history, reports, settings) still use `anyhow`. Both convert into `anyhow::Error` with `?`.
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum PostureError {
+    /// The camera couldn't be opened or stopped delivering frames
+    Camera(anyhow::Error),
+    /// The pose model couldn't be found, downloaded, loaded or run
+    Model(anyhow::Error),
+    /// The overlay windows couldn't be created
+    Overlay(anyhow::Error),
+    /// A configuration or settings value can't be used
+    Config(String),
+}
+
+pub type Result<T, E = PostureError> = std::result::Result<T, E>;
+
+impl PostureError {
+    pub fn camera(error: impl Into<anyhow::Error>) -> Self {
+        Self::Camera(error.into())
+    }
+
+    pub fn model(error: impl Into<anyhow::Error>) -> Self {
+        Self::Model(error.into())
+    }
+
+    pub fn overlay(error: impl Into<anyhow::Error>) -> Self {
+        Self::Overlay(error.into())
+    }
+}
+
+impl fmt::Display for PostureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The whole cause chain, so a plain `{}` still says what went wrong
+        match self {
+            PostureError::Camera(e) => write!(f, "Camera error: {:#}", e),
+            PostureError::Model(e) => write!(f, "Model error: {:#}", e),
+            PostureError::Overlay(e) => write!(f, "Overlay error: {:#}", e),
+            PostureError::Config(reason) => write!(f, "Invalid configuration: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PostureError {}
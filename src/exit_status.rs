@@ -0,0 +1,158 @@
+/// Process exit codes, the single-instance lock and the `--status` query
+///
+/// Scripts and the scheduled-task wrapper read the exit code to tell "no camera" from
+/// "already running" without parsing log output.
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use windows::core::w;
+use windows::Win32::Foundation::{CloseHandle, GetLastError, ERROR_ALREADY_EXISTS, HANDLE};
+use windows::Win32::System::Threading::{CreateMutexW, OpenMutexW, SYNCHRONIZATION_SYNCHRONIZE};
+
+use crate::config;
+
+pub const EXIT_OK: u8 = 0;
+/// Any error without a more specific code below
+pub const EXIT_ERROR: u8 = 1;
+/// `--status` only: nothing is running, but a start would succeed
+pub const EXIT_NOT_RUNNING: u8 = 2;
+pub const EXIT_CAMERA_MISSING: u8 = 10;
+pub const EXIT_MODEL_MISSING: u8 = 11;
+pub const EXIT_CONFIG_INVALID: u8 = 12;
+pub const EXIT_ALREADY_RUNNING: u8 = 13;
+
+/// Startup failures with their own exit code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    CameraMissing,
+    ModelMissing(String),
+    ConfigInvalid(String),
+    AlreadyRunning,
+}
+
+impl Failure {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Failure::CameraMissing => EXIT_CAMERA_MISSING,
+            Failure::ModelMissing(_) => EXIT_MODEL_MISSING,
+            Failure::ConfigInvalid(_) => EXIT_CONFIG_INVALID,
+            Failure::AlreadyRunning => EXIT_ALREADY_RUNNING,
+        }
+    }
+
+    /// Short identifier for the `--status` output
+    pub fn name(&self) -> &'static str {
+        match self {
+            Failure::CameraMissing => "camera_missing",
+            Failure::ModelMissing(_) => "model_missing",
+            Failure::ConfigInvalid(_) => "config_invalid",
+            Failure::AlreadyRunning => "already_running",
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::CameraMissing => write!(f, "No camera found"),
+            Failure::ModelMissing(path) => write!(f, "Model file not found: {}", path),
+            Failure::ConfigInvalid(reason) => write!(f, "Invalid configuration: {}", reason),
+            Failure::AlreadyRunning => write!(f, "Posture AI is already running"),
+        }
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// Exit code for the result of a run: the `Failure` code if the error is (or wraps) one
+pub fn exit_code(result: &Result<()>) -> u8 {
+    match result {
+        Ok(()) => EXIT_OK,
+        Err(e) => e.downcast_ref::<Failure>()
+            .or_else(|| e.chain().find_map(|cause| cause.downcast_ref::<Failure>()))
+            .map_or(EXIT_ERROR, Failure::exit_code),
+    }
+}
+
+/// Checks that don't need the camera stream or the model session: config values,
+/// the model file and at least one camera (`camera_count` from enumeration)
+pub fn preflight(model_path: &Path, camera_count: usize) -> Result<(), Failure> {
+    config::validate().map_err(Failure::ConfigInvalid)?;
+    if !model_path.is_file() {
+        return Err(Failure::ModelMissing(model_path.display().to_string()));
+    }
+    if camera_count == 0 {
+        return Err(Failure::CameraMissing);
+    }
+    Ok(())
+}
+
+/// Named mutex held for the lifetime of the running app
+pub struct InstanceLock(HANDLE);
+
+impl InstanceLock {
+    /// Fails with `Failure::AlreadyRunning` if another instance holds the lock
+    pub fn acquire() -> Result<Self> {
+        unsafe {
+            let handle = CreateMutexW(None, true, w!("Local\\PostureAI.Instance"))?;
+            // The mutex is opened rather than created when another instance owns it
+            if GetLastError().is_err_and(|e| e.code() == ERROR_ALREADY_EXISTS.to_hresult()) {
+                let _ = CloseHandle(handle);
+                bail!(Failure::AlreadyRunning);
+            }
+            Ok(Self(handle))
+        }
+    }
+
+    /// Whether some instance currently holds the lock (used by `--status`)
+    pub fn is_held() -> bool {
+        match unsafe { OpenMutexW(SYNCHRONIZATION_SYNCHRONIZE, false, w!("Local\\PostureAI.Instance")) } {
+            Ok(handle) => {
+                let _ = unsafe { CloseHandle(handle) };
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        let _ = unsafe { CloseHandle(self.0) };
+    }
+}
+
+/// Result of `--status`, printed as one line of JSON
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusReport {
+    pub running: bool,
+    /// Why a new start would fail, if it would (not checked while running)
+    pub problem: Option<&'static str>,
+    pub message: Option<String>,
+    pub exit_code: u8,
+}
+
+impl StatusReport {
+    /// 0 while running; otherwise the code a start would exit with, or `EXIT_NOT_RUNNING`
+    pub fn new(running: bool, preflight: Result<(), Failure>) -> Self {
+        let problem = if running { None } else { preflight.err() };
+        let exit_code = match (&problem, running) {
+            (_, true) => EXIT_OK,
+            (Some(failure), false) => failure.exit_code(),
+            (None, false) => EXIT_NOT_RUNNING,
+        };
+        Self {
+            running,
+            problem: problem.as_ref().map(Failure::name),
+            message: problem.as_ref().map(Failure::to_string),
+            exit_code,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
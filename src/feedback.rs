@@ -0,0 +1,78 @@
+/// False-positive feedback: per-rule alert counts with how many the user marked as wrong, and
+/// which rule thresholds those rates suggest loosening
+
+use std::collections::BTreeMap;
+
+use crate::config::{FALSE_POSITIVE_LOOSEN_PERCENT, FALSE_POSITIVE_MIN_ALERTS};
+use crate::engine::AlertRule;
+use crate::goals::{
+    Suggestion, HEAD_TILT_STEP_DEGREES, NECK_ANGLE_STEP_DEGREES, SENSITIVITY_STEP_PX, SHOULDER_HUNCH_STEP_PERCENT,
+};
+use crate::history::AlertRecord;
+use crate::settings::{
+    Settings, HEAD_TILT_RANGE_DEGREES, NECK_ANGLE_RANGE_DEGREES, SENSITIVITY_RANGE_PX, SHOULDER_HUNCH_RANGE_PERCENT,
+};
+
+/// Alerts of one rule over a period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleStats {
+    /// `AlertRule::key`
+    pub rule: String,
+    pub alerts: u32,
+    pub false_positives: u32,
+}
+
+impl RuleStats {
+    pub fn false_positive_percent(&self) -> u32 {
+        self.false_positives * 100 / self.alerts.max(1)
+    }
+}
+
+/// Counts `alerts` by rule, sorted by rule key
+pub fn per_rule(alerts: &[AlertRecord]) -> Vec<RuleStats> {
+    let mut counts: BTreeMap<&str, (u32, u32)> = BTreeMap::new();
+    for alert in alerts {
+        let (total, wrong) = counts.entry(alert.rule.as_str()).or_default();
+        *total += 1;
+        *wrong += alert.false_positive as u32;
+    }
+    counts.into_iter()
+        .map(|(rule, (alerts, false_positives))| RuleStats { rule: rule.to_string(), alerts, false_positives })
+        .collect()
+}
+
+/// Looser thresholds for the rules the user often marked as wrong
+pub fn suggest(rules: &[RuleStats], settings: &Settings) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    for stats in rules {
+        let percent = stats.false_positive_percent();
+        if stats.alerts < FALSE_POSITIVE_MIN_ALERTS || percent < FALSE_POSITIVE_LOOSEN_PERCENT {
+            continue;
+        }
+        // Rules from a newer version have no threshold here
+        let Some(rule) = AlertRule::from_key(&stats.rule) else { continue };
+        let (from, to) = match rule {
+            AlertRule::EyeDrop => {
+                let from = settings.sensitivity_px;
+                (from, (from + SENSITIVITY_STEP_PX).min(*SENSITIVITY_RANGE_PX.end()))
+            }
+            // A check that's off since can't be loosened
+            AlertRule::NeckAngle => {
+                let Some(from) = settings.neck_angle_degrees else { continue };
+                (from, (from + NECK_ANGLE_STEP_DEGREES).min(*NECK_ANGLE_RANGE_DEGREES.end()))
+            }
+            AlertRule::ShoulderHunch => {
+                let Some(from) = settings.shoulder_hunch_percent else { continue };
+                (from, (from + SHOULDER_HUNCH_STEP_PERCENT).min(*SHOULDER_HUNCH_RANGE_PERCENT.end()))
+            }
+            AlertRule::HeadTilt => {
+                let Some(from) = settings.head_tilt_degrees else { continue };
+                (from, (from + HEAD_TILT_STEP_DEGREES).min(*HEAD_TILT_RANGE_DEGREES.end()))
+            }
+        };
+        if to > from {
+            suggestions.push(Suggestion::FalseAlarms { rule, percent, from, to });
+        }
+    }
+    suggestions
+}
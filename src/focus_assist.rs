@@ -0,0 +1,111 @@
+/// Windows Focus Assist (Do Not Disturb): alerts are toned down while it's on, by level
+///
+/// Windows has no public API for the Focus Assist level; it's published as the
+/// `WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED` notification state, read through ntdll.
+
+use std::ffi::c_void;
+
+use serde::{Deserialize, Serialize};
+use windows::core::s;
+use windows::Win32::System::LibraryLoader::{GetModuleHandleA, GetProcAddress};
+
+use crate::alert_sinks::AlertSink;
+use crate::config::{FOCUS_ASSIST_ALARMS_ONLY, FOCUS_ASSIST_PRIORITY_ONLY};
+
+const WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED: u64 = 0x0D83_063E_A3BF_1C75;
+
+/// The Focus Assist level set in Windows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusAssist {
+    Off,
+    PriorityOnly,
+    AlarmsOnly,
+}
+
+impl FocusAssist {
+    /// The level from the published profile number (0 = off, 1 = priority only, 2 = alarms only)
+    pub fn from_profile(profile: u32) -> Self {
+        match profile {
+            1 => FocusAssist::PriorityOnly,
+            2 => FocusAssist::AlarmsOnly,
+            _ => FocusAssist::Off,
+        }
+    }
+
+    /// The current level; Off if Windows doesn't publish it
+    pub fn current() -> Self {
+        type NtQueryWnfStateData =
+            unsafe extern "system" fn(*const u64, *const c_void, *const c_void, *mut u32, *mut c_void, *mut u32) -> i32;
+        unsafe {
+            let Ok(ntdll) = GetModuleHandleA(s!("ntdll.dll")) else {
+                return FocusAssist::Off;
+            };
+            let Some(func) = GetProcAddress(ntdll, s!("NtQueryWnfStateData")) else {
+                return FocusAssist::Off;
+            };
+            let func: NtQueryWnfStateData = std::mem::transmute(func);
+
+            let mut change_stamp = 0u32;
+            let mut profile = 0u32;
+            let mut size = std::mem::size_of::<u32>() as u32;
+            let status = func(
+                &WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED,
+                std::ptr::null(),
+                std::ptr::null(),
+                &mut change_stamp,
+                &mut profile as *mut u32 as *mut c_void,
+                &mut size,
+            );
+            if status < 0 {
+                return FocusAssist::Off;
+            }
+            Self::from_profile(profile)
+        }
+    }
+}
+
+/// How much of the alerting still happens; detection and history always carry on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Enforcement {
+    /// Overlay, sound and toasts as usual
+    Full,
+    /// Toasts only: no overlay, no sound
+    NotificationsOnly,
+    /// Nothing shown or played; slouching is only logged
+    Silent,
+}
+
+impl Enforcement {
+    pub fn allows(self, sink: AlertSink) -> bool {
+        match self {
+            Enforcement::Full => true,
+            Enforcement::NotificationsOnly => sink == AlertSink::Toast,
+            Enforcement::Silent => false,
+        }
+    }
+}
+
+/// What each Focus Assist level turns alerting down to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusAssistRules {
+    pub priority_only: Enforcement,
+    pub alarms_only: Enforcement,
+}
+
+impl Default for FocusAssistRules {
+    fn default() -> Self {
+        Self { priority_only: FOCUS_ASSIST_PRIORITY_ONLY, alarms_only: FOCUS_ASSIST_ALARMS_ONLY }
+    }
+}
+
+impl FocusAssistRules {
+    pub fn enforcement(&self, level: FocusAssist) -> Enforcement {
+        match level {
+            FocusAssist::Off => Enforcement::Full,
+            FocusAssist::PriorityOnly => self.priority_only,
+            FocusAssist::AlarmsOnly => self.alarms_only,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+/// Full-screen detection: games, presentations and videos in exclusive or borderless full
+/// screen, during which the overlay stays clear
+
+use windows::Win32::Foundation::RECT;
+use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST};
+use windows::Win32::UI::Shell::{
+    SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_RUNNING_D3D_FULL_SCREEN,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetClassNameW, GetDesktopWindow, GetForegroundWindow, GetWindowLongW, GetWindowRect, GWL_STYLE, WS_CAPTION,
+};
+
+use crate::blur_overlay::ScreenRect;
+
+// Window classes of the desktop background, which covers the monitor too
+const DESKTOP_CLASSES: [&str; 2] = ["Progman", "WorkerW"];
+
+/// Whether a window at `window` fills all of `monitor`; borderless windows may overhang it
+pub fn covers_monitor(window: &ScreenRect, monitor: &ScreenRect) -> bool {
+    !monitor.is_empty()
+        && window.left <= monitor.left
+        && window.top <= monitor.top
+        && window.right >= monitor.right
+        && window.bottom >= monitor.bottom
+}
+
+/// Whether the foreground app runs full screen: exclusive Direct3D, presentation mode, or a
+/// window without a title bar covering its whole monitor (maximized windows keep theirs)
+pub fn foreground_is_fullscreen() -> bool {
+    unsafe {
+        let state = SHQueryUserNotificationState();
+        if matches!(state, Ok(QUNS_RUNNING_D3D_FULL_SCREEN | QUNS_PRESENTATION_MODE | QUNS_BUSY)) {
+            return true;
+        }
+
+        let window = GetForegroundWindow();
+        if window.0 == 0 || window == GetDesktopWindow() {
+            return false;
+        }
+        let mut class = [0u16; 64];
+        let len = GetClassNameW(window, &mut class) as usize;
+        if DESKTOP_CLASSES.contains(&String::from_utf16_lossy(&class[..len]).as_str()) {
+            return false;
+        }
+        if GetWindowLongW(window, GWL_STYLE) as u32 & WS_CAPTION.0 == WS_CAPTION.0 {
+            return false;
+        }
+
+        let mut rect = RECT::default();
+        if GetWindowRect(window, &mut rect).is_err() {
+            return false;
+        }
+        let mut info = MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+        if !GetMonitorInfoW(MonitorFromWindow(window, MONITOR_DEFAULTTONEAREST), &mut info).as_bool() {
+            return false;
+        }
+        covers_monitor(&rect.into(), &info.rcMonitor.into())
+    }
+}
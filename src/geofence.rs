@@ -0,0 +1,64 @@
+/// Location geofencing: only run detection on allowed networks or machines
+
+use std::process::Command;
+
+use crate::config::{ACTIVE_HOSTNAMES, ACTIVE_SSIDS};
+
+// Hides the console window that `netsh` would otherwise flash in release mode
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+pub fn current_hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .filter(|name| !name.is_empty())
+}
+
+/// SSIDs of the currently connected Wi-Fi interfaces (empty when wired/offline)
+pub fn current_ssids() -> Vec<String> {
+    let mut command = Command::new("netsh");
+    command.args(["wlan", "show", "interfaces"]);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = match command.output() {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+
+    // Lines look like "    SSID                   : OfficeWiFi" (BSSID lines are skipped)
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(key, _)| key.trim() == "SSID")
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|ssid| !ssid.is_empty())
+        .collect()
+}
+
+/// Returns true when detection should run at the current location.
+/// With both lists empty the geofence is disabled and every location is allowed.
+pub fn is_location_allowed() -> bool {
+    if ACTIVE_HOSTNAMES.is_empty() && ACTIVE_SSIDS.is_empty() {
+        return true;
+    }
+
+    if let Some(hostname) = current_hostname() {
+        if ACTIVE_HOSTNAMES.iter().any(|allowed| allowed.eq_ignore_ascii_case(&hostname)) {
+            return true;
+        }
+    }
+
+    if !ACTIVE_SSIDS.is_empty() {
+        let ssids = current_ssids();
+        if ssids.iter().any(|ssid| ACTIVE_SSIDS.contains(&ssid.as_str())) {
+            return true;
+        }
+    }
+
+    false
+}
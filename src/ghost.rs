@@ -0,0 +1,55 @@
+/// Baseline ghost: a faint outline of the calibrated head and shoulders in the debug preview,
+/// so drift from the calibrated pose is visible at a glance
+
+use crate::canvas::Canvas;
+use crate::config::{GHOST_COLOR, GHOST_OPACITY};
+use crate::pose::{
+    Keypoint, LEFT_EAR, LEFT_EYE, LEFT_SHOULDER, NOSE, RIGHT_EAR, RIGHT_EYE, RIGHT_SHOULDER,
+};
+
+// Ear-eye-eye-ear contour and the shoulder line; the neck is added separately
+const SEGMENTS: [(usize, usize); 4] = [
+    (LEFT_EAR, LEFT_EYE),
+    (LEFT_EYE, RIGHT_EYE),
+    (RIGHT_EYE, RIGHT_EAR),
+    (LEFT_SHOULDER, RIGHT_SHOULDER),
+];
+
+fn to_canvas(canvas: &Canvas, keypoint: &Keypoint) -> (i32, i32) {
+    canvas.to_display(keypoint.x, keypoint.y)
+}
+
+/// Draws `baseline` (normalized keypoints from the calibration) over the preview
+pub fn draw(canvas: &mut Canvas, baseline: &[Keypoint]) {
+    let point = |index: usize| baseline.get(index).filter(|kp| kp.is_confident());
+
+    for (a, b) in SEGMENTS {
+        if let (Some(a), Some(b)) = (point(a), point(b)) {
+            let (from, to) = (to_canvas(canvas, a), to_canvas(canvas, b));
+            canvas.draw_line_blended(from, to, 3, GHOST_COLOR, GHOST_OPACITY);
+        }
+    }
+
+    // Neck: nose down to the middle of the shoulders
+    if let (Some(nose), Some(left), Some(right)) = (point(NOSE), point(LEFT_SHOULDER), point(RIGHT_SHOULDER)) {
+        let (lx, ly) = to_canvas(canvas, left);
+        let (rx, ry) = to_canvas(canvas, right);
+        canvas.draw_line_blended(to_canvas(canvas, nose), ((lx + rx) / 2, (ly + ry) / 2), 3, GHOST_COLOR, GHOST_OPACITY);
+    }
+
+    // Head: circle around the nose sized by the ear (or eye) distance
+    if let Some(nose) = point(NOSE) {
+        let width = match (point(LEFT_EAR), point(RIGHT_EAR), point(LEFT_EYE), point(RIGHT_EYE)) {
+            (Some(l), Some(r), _, _) => (l.x - r.x).abs(),
+            (_, _, Some(l), Some(r)) => (l.x - r.x).abs() * 2.0,
+            _ => 0.0,
+        };
+        let radius = (width * canvas.width as f32 * 0.6) as i32;
+        if radius > 4 {
+            let (cx, cy) = to_canvas(canvas, nose);
+            for r in radius - 1..=radius + 1 {
+                canvas.draw_circle_blended(cx, cy, r, GHOST_COLOR, GHOST_OPACITY);
+            }
+        }
+    }
+}
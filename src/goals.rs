@@ -0,0 +1,313 @@
+/// Weekly goals and the Monday review: last week's numbers against the goals, with threshold and
+/// goal adjustments suggested from the data that the user can apply in one click
+
+use std::fs;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use windows::core::HSTRING;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::{MessageBoxW, IDYES, MB_ICONINFORMATION, MB_ICONQUESTION, MB_OK, MB_YESNO};
+
+use crate::alert_browser;
+use crate::app_data;
+use crate::config::{
+    CELEBRATIONS_ENABLED, GOAL_GOOD_MINUTES_PER_DAY, GOAL_MAX_ALERTS_PER_DAY, GOAL_MAX_SLOUCH_PERCENT, GOAL_STREAK_MINUTES,
+    GOOD_POSTURE_DEVIATION, WEEKLY_REVIEW_MIN_HOURS,
+};
+use crate::engine::AlertRule;
+use crate::feedback::{self, RuleStats};
+use crate::history::{HistoryEvent, HistoryReader, SecondRow};
+use crate::i18n::{tr, tr_args};
+use crate::local_time::{self, LocalTime};
+use crate::report::{self, PeriodSummary};
+use crate::settings::{SettingChange, Settings, DEBOUNCE_RANGE_MS, SENSITIVITY_RANGE_PX, SLOUCH_GOAL_RANGE_PERCENT};
+use crate::tasks::CancelToken;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+const REVIEW_STATE_FILE: &str = "weekly-review.txt";
+
+// Size of one suggested adjustment
+pub const SENSITIVITY_STEP_PX: f32 = 2.0;
+pub const NECK_ANGLE_STEP_DEGREES: f32 = 5.0;
+pub const SHOULDER_HUNCH_STEP_PERCENT: f32 = 5.0;
+pub const HEAD_TILT_STEP_DEGREES: f32 = 5.0;
+const DEBOUNCE_STEP_MS: u64 = 500;
+const GOAL_STEP_PERCENT: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Goals {
+    /// Highest acceptable share of monitored time spent slouching
+    pub max_slouch_percent: u32,
+    /// Most overlay alerts per monitored day
+    pub max_alerts_per_day: u32,
+    /// Daily goal: minutes of upright posture; None = none
+    pub good_minutes_per_day: Option<u32>,
+    /// Daily goal: one good-posture streak this long; None = none
+    pub streak_minutes: Option<u32>,
+    /// Toasts for goals reached and new best streaks
+    pub celebrate: bool,
+}
+
+impl Default for Goals {
+    fn default() -> Self {
+        Self {
+            max_slouch_percent: GOAL_MAX_SLOUCH_PERCENT,
+            max_alerts_per_day: GOAL_MAX_ALERTS_PER_DAY,
+            good_minutes_per_day: GOAL_GOOD_MINUTES_PER_DAY,
+            streak_minutes: GOAL_STREAK_MINUTES,
+            celebrate: CELEBRATIONS_ENABLED,
+        }
+    }
+}
+
+/// What happened during one week
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekStats {
+    pub monitored_secs: u64,
+    pub slouching_secs: u64,
+    pub alerts: u32,
+    /// Local days with any monitoring
+    pub days_monitored: u32,
+}
+
+impl WeekStats {
+    /// Same rules as the report (see `PeriodSummary::from_rows`); rows are oldest first
+    pub fn from_rows(rows: &[SecondRow], alerts: u32, utc_offset_secs: i64) -> Self {
+        let summary = PeriodSummary::from_rows(rows, alerts, utc_offset_secs);
+        let mut days: Vec<i64> = rows.iter()
+            .filter(|row| row.frames > 0)
+            .map(|row| (row.second + utc_offset_secs).div_euclid(DAY_SECS))
+            .collect();
+        days.dedup();
+        Self {
+            monitored_secs: summary.monitored_secs,
+            slouching_secs: summary.slouching_secs,
+            alerts,
+            days_monitored: days.len() as u32,
+        }
+    }
+
+    pub fn slouch_percent(&self) -> u32 {
+        (self.slouching_secs * 100 / self.monitored_secs.max(1)) as u32
+    }
+
+    pub fn alerts_per_day(&self) -> f32 {
+        self.alerts as f32 / self.days_monitored.max(1) as f32
+    }
+}
+
+/// One proposed change, with the values before and after
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Suggestion {
+    /// Flagged most of the time with frequent alerts: the threshold is stricter than the user's
+    /// natural posture
+    LoosenThreshold { from: f32, to: f32 },
+    /// Hardly any slouching with a threshold that was loosened earlier
+    TightenThreshold { from: f32, to: f32 },
+    /// Many alerts but little slouching: short dips trip the overlay
+    LongerDebounce { from: u64, to: u64 },
+    /// Slouch goal beaten by a wide margin
+    StricterGoal { from: u32, to: u32 },
+    /// Slouch goal missed by far; a reachable step first
+    RelaxedGoal { from: u32, to: u32 },
+    /// Many of a rule's alerts were marked as wrong
+    FalseAlarms { rule: AlertRule, percent: u32, from: f32, to: f32 },
+}
+
+impl Suggestion {
+    /// The same edit the settings window would make
+    pub fn change(&self) -> SettingChange {
+        match *self {
+            Suggestion::LoosenThreshold { to, .. }
+            | Suggestion::TightenThreshold { to, .. }
+            | Suggestion::FalseAlarms { rule: AlertRule::EyeDrop, to, .. } => SettingChange::Sensitivity(to),
+            Suggestion::FalseAlarms { rule: AlertRule::NeckAngle, to, .. } => SettingChange::NeckAngle(to),
+            Suggestion::FalseAlarms { rule: AlertRule::ShoulderHunch, to, .. } => SettingChange::ShoulderHunch(to),
+            Suggestion::FalseAlarms { rule: AlertRule::HeadTilt, to, .. } => SettingChange::HeadTilt(to),
+            Suggestion::LongerDebounce { to, .. } => SettingChange::DebounceMs(to),
+            Suggestion::StricterGoal { to, .. } | Suggestion::RelaxedGoal { to, .. } => SettingChange::SlouchGoalPercent(to),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match *self {
+            Suggestion::LoosenThreshold { from, to } => tr_args("review.loosen_threshold", &[("from", &from), ("to", &to)]),
+            Suggestion::TightenThreshold { from, to } => tr_args("review.tighten_threshold", &[("from", &from), ("to", &to)]),
+            Suggestion::LongerDebounce { from, to } => tr_args("review.longer_debounce", &[("from", &from), ("to", &to)]),
+            Suggestion::StricterGoal { from, to } => tr_args("review.stricter_goal", &[("from", &from), ("to", &to)]),
+            Suggestion::RelaxedGoal { from, to } => tr_args("review.relaxed_goal", &[("from", &from), ("to", &to)]),
+            Suggestion::FalseAlarms { rule, percent, from, to } => tr_args(
+                match rule {
+                    AlertRule::EyeDrop => "review.false_alarms",
+                    AlertRule::NeckAngle => "review.false_alarms_degrees",
+                    AlertRule::ShoulderHunch => "review.false_alarms_percent",
+                    AlertRule::HeadTilt => "review.false_alarms_tilt",
+                },
+                &[("rule", &alert_browser::rule_label(rule.key())), ("percent", &percent), ("from", &from), ("to", &to)],
+            ),
+        }
+    }
+}
+
+/// Adjustments that last week's numbers call for; empty when the settings fit
+pub fn suggest(stats: &WeekStats, settings: &Settings) -> Vec<Suggestion> {
+    let goals = settings.goals;
+    let slouch = stats.slouch_percent();
+    let too_many_alerts = stats.alerts_per_day() > goals.max_alerts_per_day as f32;
+    let sensitivity = settings.sensitivity_px;
+    let mut suggestions = Vec::new();
+
+    if slouch > goals.max_slouch_percent * 2 && too_many_alerts {
+        let to = (sensitivity + SENSITIVITY_STEP_PX).min(*SENSITIVITY_RANGE_PX.end());
+        if to > sensitivity {
+            suggestions.push(Suggestion::LoosenThreshold { from: sensitivity, to });
+        }
+    }
+    if slouch * 4 <= goals.max_slouch_percent && sensitivity > GOOD_POSTURE_DEVIATION {
+        let to = (sensitivity - SENSITIVITY_STEP_PX).max(GOOD_POSTURE_DEVIATION);
+        suggestions.push(Suggestion::TightenThreshold { from: sensitivity, to });
+    }
+    if too_many_alerts && slouch <= goals.max_slouch_percent {
+        let to = (settings.debounce_ms + DEBOUNCE_STEP_MS).min(*DEBOUNCE_RANGE_MS.end());
+        if to > settings.debounce_ms {
+            suggestions.push(Suggestion::LongerDebounce { from: settings.debounce_ms, to });
+        }
+    }
+
+    if slouch * 2 <= goals.max_slouch_percent && !too_many_alerts {
+        let to = goals.max_slouch_percent
+            .saturating_sub(GOAL_STEP_PERCENT)
+            .max(slouch + 1)
+            .max(*SLOUCH_GOAL_RANGE_PERCENT.start());
+        if to < goals.max_slouch_percent {
+            suggestions.push(Suggestion::StricterGoal { from: goals.max_slouch_percent, to });
+        }
+    } else if slouch > goals.max_slouch_percent * 2 {
+        let to = (goals.max_slouch_percent + GOAL_STEP_PERCENT).min(slouch).min(*SLOUCH_GOAL_RANGE_PERCENT.end());
+        suggestions.push(Suggestion::RelaxedGoal { from: goals.max_slouch_percent, to });
+    }
+    suggestions
+}
+
+/// Last week against the goals, plus what to change
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeeklyReview {
+    pub stats: WeekStats,
+    pub goals: Goals,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl WeeklyReview {
+    pub fn new(stats: WeekStats, settings: &Settings) -> Self {
+        Self { stats, goals: settings.goals, suggestions: suggest(&stats, settings) }
+    }
+
+    /// Adds the rule changes the user's "this alert was wrong" feedback calls for. They replace a
+    /// suggestion for the same setting, as the feedback is more direct than the weekly numbers.
+    pub fn with_feedback(mut self, rules: &[RuleStats], settings: &Settings) -> Self {
+        for suggestion in feedback::suggest(rules, settings) {
+            let setting = std::mem::discriminant(&suggestion.change());
+            self.suggestions.retain(|existing| std::mem::discriminant(&existing.change()) != setting);
+            self.suggestions.insert(0, suggestion);
+        }
+        self
+    }
+
+    pub fn changes(&self) -> Vec<SettingChange> {
+        self.suggestions.iter().map(Suggestion::change).collect()
+    }
+
+    /// Message box text
+    pub fn text(&self) -> String {
+        let mark = |met: bool| if met { "✔" } else { "✖" };
+        let slouch = self.stats.slouch_percent();
+        let alerts_per_day = self.stats.alerts_per_day();
+
+        let mut text = tr_args(
+            "review.monitored",
+            &[("duration", &report::format_duration(self.stats.monitored_secs)), ("days", &self.stats.days_monitored)],
+        );
+        text.push_str("\n\n");
+        text.push_str(&format!(
+            "{}  {}\n",
+            mark(slouch <= self.goals.max_slouch_percent),
+            tr_args("review.slouching", &[("percent", &slouch), ("goal", &self.goals.max_slouch_percent)]),
+        ));
+        text.push_str(&format!(
+            "{}  {}\n\n",
+            mark(alerts_per_day <= self.goals.max_alerts_per_day as f32),
+            tr_args("review.alerts", &[("alerts", &format!("{:.1}", alerts_per_day)), ("goal", &self.goals.max_alerts_per_day)]),
+        ));
+
+        if self.suggestions.is_empty() {
+            text.push_str(&tr("review.no_suggestions"));
+            return text;
+        }
+        text.push_str(&tr("review.suggestions"));
+        text.push('\n');
+        for suggestion in &self.suggestions {
+            text.push_str(&format!("•  {}\n", suggestion.describe()));
+        }
+        text.push('\n');
+        text.push_str(&tr("review.apply"));
+        text
+    }
+}
+
+/// Whether this week's review is still outstanding. Weeks start on Monday, so a review
+/// missed on Monday (app not running) comes up on the next day the app runs.
+pub fn is_due(today: &LocalTime, last_reviewed: Option<&LocalTime>) -> bool {
+    let monday = today.day_number() - today.weekday() as i64;
+    match last_reviewed {
+        Some(last) => last.day_number() < monday,
+        None => true,
+    }
+}
+
+/// Date of the last review shown (or skipped for lack of data)
+pub fn last_reviewed() -> Option<LocalTime> {
+    let path = app_data::file_path(REVIEW_STATE_FILE).ok()?;
+    LocalTime::parse_date(&fs::read_to_string(path).ok()?)
+}
+
+pub fn mark_reviewed(today: &LocalTime) -> Result<()> {
+    app_data::write_atomic(&app_data::file_path(REVIEW_STATE_FILE)?, today.date_string().as_bytes())
+}
+
+/// Shows the review and returns whether the user accepted the suggestions. Blocks until the
+/// box is closed, so call it on a worker thread.
+pub fn show_dialog(review: &WeeklyReview) -> bool {
+    let (text, title) = (HSTRING::from(review.text()), HSTRING::from(tr("review.title")));
+    if review.suggestions.is_empty() {
+        unsafe { MessageBoxW(HWND::default(), &text, &title, MB_OK | MB_ICONINFORMATION) };
+        return false;
+    }
+    unsafe { MessageBoxW(HWND::default(), &text, &title, MB_YESNO | MB_ICONQUESTION) == IDYES }
+}
+
+/// Builds last week's review (Monday to Sunday, local time) and shows it. Returns the
+/// changes the user accepted; nothing when there's too little data for a fair review.
+pub fn run(history: &HistoryReader, settings: &Settings, cancel: &CancelToken) -> Result<Vec<SettingChange>> {
+    let offset = local_time::utc_offset_secs();
+    let today = LocalTime::now();
+    let week_end_ms = ((today.day_number() - today.weekday() as i64) * DAY_SECS - offset) * 1000;
+    let week_start_ms = week_end_ms - 7 * DAY_SECS * 1000;
+
+    let rows = history.seconds_since(week_start_ms)?;
+    cancel.check()?;
+    let end = rows.partition_point(|row| row.second * 1000 < week_end_ms);
+    let alerts = history.count_events_since(&HistoryEvent::AlertStarted, week_start_ms)?
+        .saturating_sub(history.count_events_since(&HistoryEvent::AlertStarted, week_end_ms)?);
+    let stats = WeekStats::from_rows(&rows[..end], alerts, offset);
+    if stats.monitored_secs < WEEKLY_REVIEW_MIN_HOURS * 3600 {
+        return Ok(Vec::new());
+    }
+    let mut alert_records = history.alerts_since(week_start_ms)?;
+    alert_records.retain(|alert| alert.started_ms < week_end_ms);
+    cancel.check()?;
+
+    let review = WeeklyReview::new(stats, settings).with_feedback(&feedback::per_rule(&alert_records), settings);
+    Ok(if show_dialog(&review) { review.changes() } else { Vec::new() })
+}
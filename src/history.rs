@@ -0,0 +1,513 @@
+/// Posture history database (SQLite in the app-data directory)
+///
+/// Records sessions, posture events, calibration changes and one summary row per second
+/// of frames, so reports and future UI can query history without parsing CSV files.
+
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use rusqlite::{params, Connection};
+
+use crate::app_data;
+use crate::active_hours::PresenceHour;
+use crate::calibration::Calibration;
+use crate::engine::PostureStatus;
+use crate::local_time;
+use crate::stance::Stance;
+use crate::standing::StanceDay;
+use crate::streaks::Achievement;
+use crate::trends::Sitting;
+
+const HISTORY_FILE: &str = "history.db";
+
+// Each entry upgrades the schema by one version (tracked in PRAGMA user_version).
+// Never edit a shipped entry - append a new one instead.
+const MIGRATIONS: &[&str] = &[
+    // v1: initial schema
+    "CREATE TABLE sessions (
+        id            INTEGER PRIMARY KEY,
+        started_at_ms INTEGER NOT NULL,
+        ended_at_ms   INTEGER
+    );
+    CREATE TABLE events (
+        id         INTEGER PRIMARY KEY,
+        session_id INTEGER NOT NULL REFERENCES sessions(id),
+        at_ms      INTEGER NOT NULL,
+        kind       TEXT NOT NULL,
+        detail     TEXT
+    );
+    CREATE INDEX events_at ON events(at_ms);
+    CREATE TABLE calibrations (
+        id               INTEGER PRIMARY KEY,
+        session_id       INTEGER NOT NULL REFERENCES sessions(id),
+        at_ms            INTEGER NOT NULL,
+        camera_index     INTEGER NOT NULL,
+        rotation_degrees INTEGER NOT NULL,
+        eye_y            REAL NOT NULL,
+        samples          INTEGER NOT NULL
+    );
+    CREATE TABLE frame_summaries (
+        second     INTEGER PRIMARY KEY,
+        session_id INTEGER NOT NULL REFERENCES sessions(id),
+        frames     INTEGER NOT NULL,
+        bad_frames INTEGER NOT NULL,
+        avg_delta  REAL,
+        alerting   INTEGER NOT NULL
+    );",
+    // v2: one row per finished alert, for the alert history browser
+    "CREATE TABLE alerts (
+        id                INTEGER PRIMARY KEY,
+        session_id        INTEGER NOT NULL REFERENCES sessions(id),
+        rule              TEXT NOT NULL,
+        slouch_started_ms INTEGER NOT NULL,
+        started_ms        INTEGER NOT NULL,
+        ended_ms          INTEGER NOT NULL,
+        peak_delta        REAL,
+        snapshot          TEXT
+    );
+    CREATE INDEX alerts_started ON alerts(started_ms);",
+    // v3: alerts the user marked as wrong
+    "ALTER TABLE alerts ADD COLUMN false_positive INTEGER NOT NULL DEFAULT 0;",
+    // v4: whether the user was standing (NULL = nobody in view)
+    "ALTER TABLE frame_summaries ADD COLUMN standing INTEGER;",
+    // v5: daily goals reached and best streaks, celebrated once each
+    "CREATE TABLE achievements (
+        id         INTEGER PRIMARY KEY,
+        session_id INTEGER NOT NULL REFERENCES sessions(id),
+        at_ms      INTEGER NOT NULL,
+        kind       TEXT NOT NULL,
+        minutes    INTEGER NOT NULL
+    );
+    CREATE INDEX achievements_at ON achievements(at_ms);",
+];
+
+/// Something worth remembering about the session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryEvent {
+    AlertStarted,
+    AlertEnded,
+    CalibrationFailed,
+    BaselineCleared,
+    CameraChanged(u32),
+    Paused,
+    Resumed,
+    /// Left the allowed location (geofence)
+    Dormant,
+    /// Back inside the allowed location
+    Active,
+    /// Outside the active hours
+    OffHours,
+    /// Active hours started
+    OnHours,
+    /// Outside the work schedule
+    OffSchedule,
+    /// A work schedule window started
+    OnSchedule,
+    /// Nobody in view for the away delay
+    Away,
+    /// Someone in view again
+    Returned,
+}
+
+impl HistoryEvent {
+    fn kind(&self) -> &'static str {
+        match self {
+            HistoryEvent::AlertStarted => "alert_started",
+            HistoryEvent::AlertEnded => "alert_ended",
+            HistoryEvent::CalibrationFailed => "calibration_failed",
+            HistoryEvent::BaselineCleared => "baseline_cleared",
+            HistoryEvent::CameraChanged(_) => "camera_changed",
+            HistoryEvent::Paused => "paused",
+            HistoryEvent::Resumed => "resumed",
+            HistoryEvent::Dormant => "dormant",
+            HistoryEvent::Active => "active",
+            HistoryEvent::OffHours => "off_hours",
+            HistoryEvent::OnHours => "on_hours",
+            HistoryEvent::OffSchedule => "off_schedule",
+            HistoryEvent::OnSchedule => "on_schedule",
+            HistoryEvent::Away => "away",
+            HistoryEvent::Returned => "returned",
+        }
+    }
+
+    fn detail(&self) -> Option<String> {
+        match self {
+            HistoryEvent::CameraChanged(index) => Some(index.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// A stored per-second summary, as read back for reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecondRow {
+    /// Unix time in seconds
+    pub second: i64,
+    pub frames: u32,
+    pub bad_frames: u32,
+    pub alerting: bool,
+}
+
+/// A finished alert (all times are Unix milliseconds)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRecord {
+    /// `AlertRule::key` of the check that fired
+    pub rule: String,
+    /// When the slouch began (the alert follows after the debounce)
+    pub slouch_started_ms: i64,
+    /// When the overlay was triggered
+    pub started_ms: i64,
+    /// When posture was corrected (or detection stopped)
+    pub ended_ms: i64,
+    /// Largest distance below the baseline during the alert, in preview pixels
+    pub peak_delta: Option<f32>,
+    /// File name of the annotated thumbnail in the recordings folder
+    pub snapshot: Option<String>,
+    /// The user marked the alert as wrong ("This Alert Was Wrong" in the tray)
+    pub false_positive: bool,
+}
+
+impl AlertRecord {
+    /// Total slouch length, debounce included
+    pub fn duration_ms(&self) -> i64 {
+        self.ended_ms - self.slouch_started_ms
+    }
+
+    /// How long it took to sit up once the overlay appeared
+    pub fn response_ms(&self) -> i64 {
+        self.ended_ms - self.started_ms
+    }
+}
+
+/// Frames seen during the current wall-clock second
+#[derive(Default)]
+struct FrameSummary {
+    second: u64,
+    frames: u32,
+    bad_frames: u32,
+    delta_sum: f64,
+    delta_samples: u32,
+    alerting: bool,
+    /// Frames with someone in view, and how many of them standing
+    stance_frames: u32,
+    standing_frames: u32,
+}
+
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > MIGRATIONS.len() {
+        bail!("history database was created by a newer version (schema v{})", version);
+    }
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", (index + 1) as u32)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+fn connect(path: &std::path::Path) -> Result<Connection> {
+    let mut conn = Connection::open(path)?;
+    conn.busy_timeout(Duration::from_secs(5))?;
+    // WAL keeps the per-second inserts cheap and lets maintenance read concurrently
+    conn.query_row("PRAGMA journal_mode = WAL", params![], |_| Ok(()))?;
+    conn.execute_batch("PRAGMA synchronous = NORMAL; PRAGMA foreign_keys = ON;")?;
+    migrate(&mut conn)?;
+    Ok(conn)
+}
+
+/// One app session's connection to the history database
+pub struct History {
+    conn: Connection,
+    session_id: i64,
+    summary: FrameSummary,
+}
+
+impl History {
+    pub fn open() -> Result<Self> {
+        Self::open_at(&app_data::file_path(HISTORY_FILE)?)
+    }
+
+    /// Opens (creating and migrating as needed) the database and starts a new session
+    pub fn open_at(path: &std::path::Path) -> Result<Self> {
+        let conn = connect(path)?;
+        conn.execute("INSERT INTO sessions (started_at_ms) VALUES (?1)", params![local_time::unix_millis() as i64])?;
+        let session_id = conn.last_insert_rowid();
+        Ok(Self {
+            conn,
+            session_id,
+            summary: FrameSummary::default(),
+        })
+    }
+
+    pub fn record_event(&self, event: &HistoryEvent) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO events (session_id, at_ms, kind, detail) VALUES (?1, ?2, ?3, ?4)",
+            params![self.session_id, local_time::unix_millis() as i64, event.kind(), event.detail()],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_calibration(&self, calibration: &Calibration) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO calibrations (session_id, at_ms, camera_index, rotation_degrees, eye_y, samples)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                self.session_id,
+                local_time::unix_millis() as i64,
+                calibration.camera_index,
+                calibration.rotation_degrees,
+                calibration.eye_y as f64,
+                calibration.samples as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Call once per processed frame with the engine's stance; rows are written once per second
+    pub fn record_frame(&mut self, status: PostureStatus, delta: Option<f32>, stance: Option<Stance>) -> Result<()> {
+        let second = local_time::unix_millis() / 1000;
+        if second != self.summary.second {
+            self.flush_frames()?;
+            self.summary.second = second;
+        }
+
+        let summary = &mut self.summary;
+        summary.frames += 1;
+        if matches!(status, PostureStatus::Slouching | PostureStatus::Alert) {
+            summary.bad_frames += 1;
+        }
+        // Calibration samples and unreliable tracking aren't posture
+        if let Some(delta) = delta.filter(|_| !matches!(status, PostureStatus::Calibrating | PostureStatus::TrackingPoor)) {
+            summary.delta_sum += delta as f64;
+            summary.delta_samples += 1;
+        }
+        summary.alerting |= status == PostureStatus::Alert;
+        if let Some(stance) = stance {
+            summary.stance_frames += 1;
+            summary.standing_frames += (stance == Stance::Standing) as u32;
+        }
+        Ok(())
+    }
+
+    pub fn record_alert(&self, alert: &AlertRecord) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO alerts (session_id, rule, slouch_started_ms, started_ms, ended_ms, peak_delta, snapshot, false_positive)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                self.session_id,
+                alert.rule,
+                alert.slouch_started_ms,
+                alert.started_ms,
+                alert.ended_ms,
+                alert.peak_delta.map(|delta| delta as f64),
+                alert.snapshot,
+                alert.false_positive,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_achievement(&self, achievement: &Achievement) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO achievements (session_id, at_ms, kind, minutes) VALUES (?1, ?2, ?3, ?4)",
+            params![self.session_id, local_time::unix_millis() as i64, achievement.key(), achievement.minutes()],
+        )?;
+        Ok(())
+    }
+
+    /// Marks this session's most recent finished alert as wrong. Returns false when there is none.
+    pub fn mark_last_alert_false_positive(&self) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE alerts SET false_positive = 1
+             WHERE id = (SELECT MAX(id) FROM alerts WHERE session_id = ?1)",
+            params![self.session_id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Writes the second in progress, so other connections see it
+    pub fn flush(&mut self) -> Result<()> {
+        self.flush_frames()
+    }
+
+    fn flush_frames(&mut self) -> Result<()> {
+        let summary = std::mem::take(&mut self.summary);
+        if summary.frames == 0 {
+            return Ok(());
+        }
+
+        let avg_delta = (summary.delta_samples > 0).then(|| summary.delta_sum / summary.delta_samples as f64);
+        // The second counts as standing when most of its frames were
+        let standing = (summary.stance_frames > 0).then(|| summary.standing_frames * 2 > summary.stance_frames);
+        self.conn.execute(
+            "INSERT OR REPLACE INTO frame_summaries (second, session_id, frames, bad_frames, avg_delta, alerting, standing)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                summary.second as i64,
+                self.session_id,
+                summary.frames,
+                summary.bad_frames,
+                avg_delta,
+                summary.alerting,
+                standing,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl Drop for History {
+    fn drop(&mut self) {
+        let _ = self.flush_frames();
+        let _ = self.conn.execute(
+            "UPDATE sessions SET ended_at_ms = ?1 WHERE id = ?2",
+            params![local_time::unix_millis() as i64, self.session_id],
+        );
+    }
+}
+
+/// Queries on a connection of its own, for report jobs on worker threads
+pub struct HistoryReader {
+    conn: Connection,
+}
+
+impl HistoryReader {
+    pub fn open() -> Result<Self> {
+        Self::open_at(&app_data::file_path(HISTORY_FILE)?)
+    }
+
+    pub fn open_at(path: &std::path::Path) -> Result<Self> {
+        Ok(Self { conn: connect(path)? })
+    }
+
+    /// Per-second summaries from `since_ms` on, oldest first (the current second once the writer flushed it)
+    pub fn seconds_since(&self, since_ms: i64) -> Result<Vec<SecondRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT second, frames, bad_frames, alerting FROM frame_summaries WHERE second >= ?1 ORDER BY second",
+        )?;
+        let rows = stmt
+            .query_map(params![since_ms / 1000], |row| {
+                Ok(SecondRow {
+                    second: row.get(0)?,
+                    frames: row.get(1)?,
+                    bad_frames: row.get(2)?,
+                    alerting: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Alerts that started from `since_ms` on, newest first
+    pub fn alerts_since(&self, since_ms: i64) -> Result<Vec<AlertRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT rule, slouch_started_ms, started_ms, ended_ms, peak_delta, snapshot, false_positive
+             FROM alerts WHERE started_ms >= ?1 ORDER BY started_ms DESC",
+        )?;
+        let rows = stmt
+            .query_map(params![since_ms], |row| {
+                Ok(AlertRecord {
+                    rule: row.get(0)?,
+                    slouch_started_ms: row.get(1)?,
+                    started_ms: row.get(2)?,
+                    ended_ms: row.get(3)?,
+                    peak_delta: row.get::<_, Option<f64>>(4)?.map(|delta| delta as f32),
+                    snapshot: row.get(5)?,
+                    false_positive: row.get(6)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Seconds with a judged pose per local clock hour from `since_ms` on, oldest first
+    pub fn presence_by_hour(&self, since_ms: i64, utc_offset_secs: i64) -> Result<Vec<PresenceHour>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT (second + ?2) / 3600 AS local_hour, COUNT(*) FROM frame_summaries
+             WHERE second >= ?1 AND avg_delta IS NOT NULL GROUP BY local_hour ORDER BY local_hour",
+        )?;
+        let rows = stmt
+            .query_map(params![since_ms / 1000, utc_offset_secs], |row| {
+                Ok(PresenceHour { local_hour: row.get(0)?, present_secs: row.get(1)? })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Stretches of seconds with a judged pose from `since_ms` on, split wherever the user was
+    /// out of view for more than `gap_secs`; oldest first
+    pub fn sittings_since(&self, since_ms: i64, gap_secs: i64) -> Result<Vec<Sitting>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT MIN(second), MAX(second) FROM (
+                SELECT second, SUM(starts_sitting) OVER (ORDER BY second) AS sitting FROM (
+                    SELECT second, COALESCE(second - LAG(second) OVER (ORDER BY second) > ?2, 1) AS starts_sitting
+                    FROM frame_summaries WHERE second >= ?1 AND avg_delta IS NOT NULL
+                )
+             ) GROUP BY sitting ORDER BY 1",
+        )?;
+        let rows = stmt
+            .query_map(params![since_ms / 1000, gap_secs], |row| {
+                Ok(Sitting { first_second: row.get(0)?, last_second: row.get(1)? })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Seconds spent sitting and standing per local day from `since_ms` on, oldest first
+    pub fn stance_by_day(&self, since_ms: i64, utc_offset_secs: i64) -> Result<Vec<StanceDay>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT (second + ?2) / 86400 AS local_day, SUM(standing = 0), SUM(standing = 1) FROM frame_summaries
+             WHERE second >= ?1 AND standing IS NOT NULL GROUP BY local_day ORDER BY local_day",
+        )?;
+        let rows = stmt
+            .query_map(params![since_ms / 1000, utc_offset_secs], |row| {
+                Ok(StanceDay { local_day: row.get(0)?, sitting_secs: row.get(1)?, standing_secs: row.get(2)? })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Seconds judged upright (a pose without bad frames) from `since_ms` on
+    pub fn good_secs_since(&self, since_ms: i64) -> Result<u64> {
+        Ok(self.conn.query_row(
+            "SELECT COUNT(*) FROM frame_summaries WHERE second >= ?1 AND avg_delta IS NOT NULL AND bad_frames = 0",
+            params![since_ms / 1000],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Goals reached and best streaks from `since_ms` on with their time (Unix ms), oldest first
+    pub fn achievements_since(&self, since_ms: i64) -> Result<Vec<(i64, Achievement)>> {
+        let mut stmt = self.conn.prepare("SELECT at_ms, kind, minutes FROM achievements WHERE at_ms >= ?1 ORDER BY at_ms")?;
+        let rows = stmt
+            .query_map(params![since_ms], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, u32>(2)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        // Kinds from a newer version are skipped
+        Ok(rows.into_iter()
+            .filter_map(|(at_ms, kind, minutes)| Achievement::from_key(&kind, minutes).map(|achievement| (at_ms, achievement)))
+            .collect())
+    }
+
+    /// How often `event` was recorded from `since_ms` on (across all sessions)
+    pub fn count_events_since(&self, event: &HistoryEvent, since_ms: i64) -> Result<u32> {
+        Ok(self.conn.query_row(
+            "SELECT COUNT(*) FROM events WHERE kind = ?1 AND at_ms >= ?2",
+            params![event.kind(), since_ms],
+            |row| row.get(0),
+        )?)
+    }
+}
+
+/// Rebuilds the database file to reclaim free pages and refreshes the query planner statistics
+/// (run by the maintenance task)
+pub fn compact() -> Result<()> {
+    let path = app_data::file_path(HISTORY_FILE)?;
+    if !path.exists() {
+        return Ok(());
+    }
+    connect(&path)?.execute_batch("VACUUM; ANALYZE;")?;
+    Ok(())
+}
@@ -0,0 +1,107 @@
+/// System-wide hotkeys so the app can be controlled while hidden in the tray
+
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use anyhow::Result;
+use tracing::warn;
+use windows::core::s;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleA;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    RegisterHotKey, UnregisterHotKey, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, VIRTUAL_KEY, VK_P, VK_R,
+};
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    Recalibrate,  // Ctrl+Alt+R
+    TogglePause,  // Ctrl+Alt+P
+}
+
+const BINDINGS: [(i32, VIRTUAL_KEY, HotkeyAction); 2] = [
+    (1, VK_R, HotkeyAction::Recalibrate),
+    (2, VK_P, HotkeyAction::TogglePause),
+];
+
+// Set by the window procedure in case another message pump dispatches WM_HOTKEY first
+static PENDING_HOTKEY: AtomicI32 = AtomicI32::new(0);
+
+pub struct GlobalHotkeys {
+    hwnd: HWND,
+    registered: Vec<i32>,
+}
+
+impl GlobalHotkeys {
+    /// Registers the hotkeys on a hidden message-only window.
+    /// Hotkeys already taken by another app are skipped with a warning.
+    pub fn register() -> Result<Self> {
+        unsafe {
+            let instance = GetModuleHandleA(None)?;
+            let class_name = s!("PostureHotkeyClass");
+
+            let wc = WNDCLASSA {
+                hInstance: instance.into(),
+                lpszClassName: class_name,
+                lpfnWndProc: Some(Self::wnd_proc),
+                ..Default::default()
+            };
+            RegisterClassA(&wc);
+
+            let hwnd = CreateWindowExA(
+                WINDOW_EX_STYLE::default(),
+                class_name,
+                s!(""),
+                WINDOW_STYLE::default(),
+                0, 0, 0, 0,
+                HWND_MESSAGE,
+                None,
+                instance,
+                None,
+            );
+
+            let mut registered = Vec::new();
+            for (id, key, action) in BINDINGS {
+                match RegisterHotKey(hwnd, id, MOD_CONTROL | MOD_ALT | MOD_NOREPEAT, key.0 as u32) {
+                    Ok(()) => registered.push(id),
+                    Err(e) => warn!("Could not register hotkey for {:?}: {}", action, e),
+                }
+            }
+
+            Ok(Self { hwnd, registered })
+        }
+    }
+
+    /// Returns the next pressed hotkey, if any. Call once per frame.
+    pub fn poll(&self) -> Option<HotkeyAction> {
+        unsafe {
+            let mut msg = MSG::default();
+            while PeekMessageW(&mut msg, self.hwnd, WM_HOTKEY, WM_HOTKEY, PM_REMOVE).as_bool() {
+                DispatchMessageW(&msg);
+            }
+        }
+
+        let id = PENDING_HOTKEY.swap(0, Ordering::SeqCst);
+        BINDINGS.iter()
+            .find(|(binding_id, _, _)| *binding_id == id)
+            .map(|(_, _, action)| *action)
+    }
+
+    extern "system" fn wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if msg == WM_HOTKEY {
+            PENDING_HOTKEY.store(wparam.0 as i32, Ordering::SeqCst);
+            return LRESULT(0);
+        }
+        unsafe { DefWindowProcA(hwnd, msg, wparam, lparam) }
+    }
+}
+
+impl Drop for GlobalHotkeys {
+    fn drop(&mut self) {
+        unsafe {
+            for id in &self.registered {
+                let _ = UnregisterHotKey(self.hwnd, *id);
+            }
+            let _ = DestroyWindow(self.hwnd);
+        }
+    }
+}
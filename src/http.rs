@@ -0,0 +1,136 @@
+/// Minimal HTTP client over WinHTTP (system proxy settings, no extra TLS stack): GET for model
+/// downloads and JSON POSTs for webhooks. Every request times out instead of hanging on a dead
+/// server, and a GET stops between reads once cancelled.
+
+use std::ffi::c_void;
+use std::{mem, ptr, slice};
+
+use anyhow::bail;
+use windows::core::{w, Error, HSTRING, PCWSTR};
+use windows::Win32::Networking::WinHttp::{
+    WinHttpCloseHandle, WinHttpConnect, WinHttpCrackUrl, WinHttpOpen, WinHttpOpenRequest, WinHttpQueryHeaders,
+    WinHttpReadData, WinHttpReceiveResponse, WinHttpSendRequest, WinHttpSetTimeouts, URL_COMPONENTS, WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY,
+    WINHTTP_FLAG_SECURE, WINHTTP_INTERNET_SCHEME_HTTPS, WINHTTP_OPEN_REQUEST_FLAGS, WINHTTP_QUERY_CONTENT_LENGTH,
+    WINHTTP_QUERY_FLAG_NUMBER64, WINHTTP_QUERY_STATUS_CODE,
+};
+
+use crate::config::{HTTP_CONNECT_TIMEOUT_SECS, HTTP_RECEIVE_TIMEOUT_SECS};
+use crate::tasks::CancelToken;
+
+/// An open WinHTTP handle, closed on drop
+struct Internet(*mut c_void);
+
+impl Internet {
+    fn new(handle: *mut c_void) -> anyhow::Result<Self> {
+        if handle.is_null() {
+            bail!(Error::from_win32());
+        }
+        Ok(Self(handle))
+    }
+}
+
+impl Drop for Internet {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = WinHttpCloseHandle(self.0);
+        }
+    }
+}
+
+/// A sent request whose response headers have arrived; the handles it needs close together
+struct Response {
+    request: Internet,
+    _connection: Internet,
+    _session: Internet,
+}
+
+impl Response {
+    fn header_number(&self, info: u32) -> Option<u64> {
+        let mut value = 0u64;
+        let mut length = mem::size_of::<u64>() as u32;
+        unsafe {
+            WinHttpQueryHeaders(
+                self.request.0,
+                info | WINHTTP_QUERY_FLAG_NUMBER64,
+                PCWSTR::null(),
+                Some(&mut value as *mut u64 as *mut c_void),
+                &mut length,
+                ptr::null_mut(),
+            )
+            .ok()
+            .map(|_| value)
+        }
+    }
+
+    fn status(&self) -> u16 {
+        self.header_number(WINHTTP_QUERY_STATUS_CODE).unwrap_or(0) as u16
+    }
+}
+
+/// Sends `method` to `url` (redirects are followed) with optional extra headers ("Name: value",
+/// CRLF-separated) and body, and waits for the response headers
+fn send(method: PCWSTR, url: &str, headers: Option<&str>, body: &[u8]) -> anyhow::Result<Response> {
+    let wide_url: Vec<u16> = url.encode_utf16().collect();
+    // Lengths of u32::MAX ask for pointers into `wide_url`
+    let mut parts = URL_COMPONENTS {
+        dwStructSize: mem::size_of::<URL_COMPONENTS>() as u32,
+        dwHostNameLength: u32::MAX,
+        dwUrlPathLength: u32::MAX,
+        dwExtraInfoLength: u32::MAX,
+        ..Default::default()
+    };
+    unsafe {
+        WinHttpCrackUrl(&wide_url, 0, &mut parts)?;
+        let host = HSTRING::from_wide(slice::from_raw_parts(parts.lpszHostName.0, parts.dwHostNameLength as usize))?;
+        // The query string directly follows the path
+        let path_length = (parts.dwUrlPathLength + parts.dwExtraInfoLength) as usize;
+        let object = HSTRING::from_wide(slice::from_raw_parts(parts.lpszUrlPath.0, path_length))?;
+        let flags = if parts.nScheme == WINHTTP_INTERNET_SCHEME_HTTPS { WINHTTP_FLAG_SECURE } else { WINHTTP_OPEN_REQUEST_FLAGS(0) };
+
+        let session = Internet::new(WinHttpOpen(w!("PostureAI"), WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY, PCWSTR::null(), PCWSTR::null(), 0))?;
+        // Name resolution and connecting share the connect timeout; sending and each read the other
+        let connect_ms = (HTTP_CONNECT_TIMEOUT_SECS * 1000) as i32;
+        let receive_ms = (HTTP_RECEIVE_TIMEOUT_SECS * 1000) as i32;
+        WinHttpSetTimeouts(session.0, connect_ms, connect_ms, receive_ms, receive_ms)?;
+        let connection = Internet::new(WinHttpConnect(session.0, &host, parts.nPort, 0))?;
+        let request = Internet::new(WinHttpOpenRequest(connection.0, method, &object, PCWSTR::null(), PCWSTR::null(), ptr::null(), flags))?;
+        let wide_headers: Option<Vec<u16>> = headers.map(|headers| headers.encode_utf16().collect());
+        let optional = (!body.is_empty()).then_some(body.as_ptr() as *const c_void);
+        WinHttpSendRequest(request.0, wide_headers.as_deref(), optional, body.len() as u32, body.len() as u32, 0)?;
+        WinHttpReceiveResponse(request.0, ptr::null_mut())?;
+        Ok(Response { request, _connection: connection, _session: session })
+    }
+}
+
+/// GET `url` and return the body; anything but HTTP 200 is an error, and so is `cancel` firing
+/// before the body is complete. `on_progress` gets the bytes received so far and the
+/// Content-Length, if the server sent one.
+pub fn get(url: &str, cancel: &CancelToken, on_progress: &mut dyn FnMut(u64, Option<u64>)) -> anyhow::Result<Vec<u8>> {
+    let response = send(w!("GET"), url, None, &[])?;
+    let status = response.status();
+    if status != 200 {
+        bail!("{} answered HTTP {}", url, status);
+    }
+    let total = response.header_number(WINHTTP_QUERY_CONTENT_LENGTH);
+    let mut body = Vec::new();
+    let mut chunk = vec![0u8; 64 * 1024];
+    loop {
+        cancel.check()?;
+        let mut read = 0u32;
+        unsafe {
+            WinHttpReadData(response.request.0, chunk.as_mut_ptr().cast(), chunk.len() as u32, &mut read)?;
+        }
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read as usize]);
+        on_progress(body.len() as u64, total);
+    }
+    Ok(body)
+}
+
+/// POST `json` to `url` and return the HTTP status; the response body is ignored
+pub fn post_json(url: &str, json: &str) -> anyhow::Result<u16> {
+    let response = send(w!("POST"), url, Some("Content-Type: application/json\r\n"), json.as_bytes())?;
+    Ok(response.status())
+}
@@ -0,0 +1,112 @@
+/// Localized UI strings loaded from `locales/<code>.txt` files
+///
+/// Locale files are plain `key = value` lines. They are read from a `locales`
+/// folder next to the executable (or the working directory), so adding a
+/// translation only means dropping in a new file. English is embedded as the
+/// fallback for missing files and keys.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use windows::Win32::Globalization::GetUserDefaultLocaleName;
+
+const FALLBACK_LOCALE: &str = "en";
+const FALLBACK_STRINGS: &str = include_str!("../locales/en.txt");
+
+struct Locale {
+    code: String,
+    strings: HashMap<String, String>,
+}
+
+static CURRENT: RwLock<Option<Locale>> = RwLock::new(None);
+
+fn parse(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+fn locale_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(exe_dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(PathBuf::from)) {
+        dirs.push(exe_dir.join("locales"));
+    }
+    dirs.push(PathBuf::from("locales"));
+    dirs
+}
+
+fn read_locale_file(code: &str) -> Option<String> {
+    locale_dirs().into_iter()
+        .map(|dir| dir.join(format!("{}.txt", code)))
+        .find_map(|path| fs::read_to_string(path).ok())
+}
+
+/// The user's Windows locale, e.g. "ru-RU"
+pub fn system_locale() -> String {
+    let mut buffer = [0u16; 85]; // LOCALE_NAME_MAX_LENGTH
+    let len = unsafe { GetUserDefaultLocaleName(&mut buffer) };
+    if len <= 1 {
+        return FALLBACK_LOCALE.to_string();
+    }
+    String::from_utf16_lossy(&buffer[..len as usize - 1])
+}
+
+/// Loads strings for `code` ("ru-RU" falls back to "ru", then English)
+pub fn load(code: &str) {
+    let mut strings = parse(FALLBACK_STRINGS);
+    let language = code.split('-').next().unwrap_or(code);
+    for candidate in [language, code] {
+        if candidate == FALLBACK_LOCALE {
+            continue;
+        }
+        if let Some(text) = read_locale_file(candidate) {
+            strings.extend(parse(&text));
+        }
+    }
+
+    *CURRENT.write().unwrap() = Some(Locale {
+        code: code.to_string(),
+        strings,
+    });
+}
+
+/// Code of the loaded locale, if any
+pub fn current_code() -> Option<String> {
+    CURRENT.read().unwrap().as_ref().map(|locale| locale.code.clone())
+}
+
+/// Reloads strings if the Windows locale changed. Returns true when it did.
+pub fn refresh() -> bool {
+    let code = system_locale();
+    if current_code().as_deref() == Some(code.as_str()) {
+        return false;
+    }
+    load(&code);
+    true
+}
+
+/// Translated string for `key` (the key itself if it is missing everywhere)
+pub fn tr(key: &str) -> String {
+    if CURRENT.read().unwrap().is_none() {
+        load(&system_locale());
+    }
+    CURRENT.read().unwrap()
+        .as_ref()
+        .and_then(|locale| locale.strings.get(key).cloned())
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Translated string with `{name}` placeholders filled in
+pub fn tr_args(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut text = tr(key);
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    text
+}
@@ -0,0 +1,200 @@
+/// Frame inputs: a live camera, a folder of image frames or a recorded video, behind one
+/// `FrameSource` trait so the pipeline can replay recordings (`--input`) exactly like live video
+///
+/// Videos are decoded by `ffmpeg` (which must be on the PATH) into raw RGB frames; image
+/// folders are read in file-name order, so `frame-0001.png, frame-0002.png, ...` plays back
+/// as recorded.
+
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, Stdio};
+
+use anyhow::anyhow;
+use image::RgbImage;
+use nokhwa::Camera;
+
+use crate::camera;
+use crate::error::{PostureError, Result};
+use crate::pipeline;
+
+const IMAGE_EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "bmp"];
+const VIDEO_EXTENSIONS: [&str; 6] = ["mp4", "avi", "mkv", "mov", "webm", "wmv"];
+
+// Hides the console window that `ffmpeg` would otherwise flash in release mode
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Where the pipeline's frames come from
+pub trait FrameSource {
+    /// Name recorded in each frame's metadata
+    fn name(&self) -> String;
+
+    /// The next frame; None once a recording has been played to the end
+    fn next_frame(&mut self) -> Result<Option<RgbImage>>;
+}
+
+/// An open camera; never runs out of frames
+pub struct CameraSource {
+    index: u32,
+    camera: Camera,
+}
+
+impl CameraSource {
+    pub fn new(index: u32, camera: Camera) -> Self {
+        Self { index, camera }
+    }
+
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+}
+
+impl FrameSource for CameraSource {
+    fn name(&self) -> String {
+        pipeline::camera_source(self.index)
+    }
+
+    fn next_frame(&mut self) -> Result<Option<RgbImage>> {
+        let buffer = self.camera.frame().map_err(PostureError::camera)?;
+        Ok(Some(camera::decode_frame(&buffer)?))
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|known| ext.eq_ignore_ascii_case(known)))
+}
+
+/// Image files in a folder, played in file-name order
+pub struct ImageSequence {
+    name: String,
+    files: VecDeque<PathBuf>,
+}
+
+impl ImageSequence {
+    pub fn open(dir: &Path) -> Result<Self> {
+        let entries = std::fs::read_dir(dir).map_err(PostureError::camera)?;
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && has_extension(path, &IMAGE_EXTENSIONS))
+            .collect();
+        if files.is_empty() {
+            return Err(PostureError::camera(anyhow!("No image frames in {}", dir.display())));
+        }
+        files.sort();
+        Ok(Self { name: format!("images:{}", dir.display()), files: files.into() })
+    }
+
+    /// Frames left to play
+    pub fn remaining(&self) -> usize {
+        self.files.len()
+    }
+}
+
+impl FrameSource for ImageSequence {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn next_frame(&mut self) -> Result<Option<RgbImage>> {
+        let Some(path) = self.files.pop_front() else { return Ok(None) };
+        let frame = image::open(&path)
+            .map_err(|e| PostureError::camera(anyhow!("Failed to read {}: {}", path.display(), e)))?;
+        Ok(Some(frame.to_rgb8()))
+    }
+}
+
+/// A recorded video, decoded frame by frame by an `ffmpeg` child process
+pub struct VideoFile {
+    name: String,
+    child: Child,
+    stdout: ChildStdout,
+    width: u32,
+    height: u32,
+}
+
+fn hidden_command(program: &str) -> Command {
+    let mut command = Command::new(program);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
+
+/// Parses ffprobe's `<width>x<height>` output
+pub fn parse_dimensions(output: &str) -> Option<(u32, u32)> {
+    let (width, height) = output.lines().next()?.trim().split_once('x')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+impl VideoFile {
+    pub fn open(path: &Path) -> Result<Self> {
+        let probe = hidden_command("ffprobe")
+            .args(["-v", "error", "-select_streams", "v:0", "-show_entries", "stream=width,height", "-of", "csv=s=x:p=0"])
+            .arg(path)
+            .output()
+            .map_err(|e| PostureError::camera(anyhow!("Video input needs ffmpeg/ffprobe on the PATH: {}", e)))?;
+        let (width, height) = parse_dimensions(&String::from_utf8_lossy(&probe.stdout))
+            .ok_or_else(|| PostureError::camera(anyhow!("{} has no readable video stream", path.display())))?;
+
+        let mut child = hidden_command("ffmpeg")
+            .args(["-v", "error", "-i"])
+            .arg(path)
+            .args(["-f", "rawvideo", "-pix_fmt", "rgb24", "-"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| PostureError::camera(anyhow!("Video input needs ffmpeg on the PATH: {}", e)))?;
+        let stdout = child.stdout.take().expect("ffmpeg stdout is piped");
+        Ok(Self { name: format!("video:{}", path.display()), child, stdout, width, height })
+    }
+}
+
+impl FrameSource for VideoFile {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn next_frame(&mut self) -> Result<Option<RgbImage>> {
+        let mut buffer = vec![0u8; (self.width * self.height * 3) as usize];
+        match self.stdout.read_exact(&mut buffer) {
+            Ok(()) => Ok(RgbImage::from_raw(self.width, self.height, buffer)),
+            // A partial last frame is dropped like the end of the stream
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+            Err(e) => Err(PostureError::camera(e)),
+        }
+    }
+}
+
+impl Drop for VideoFile {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Opens `path` as a recording: a folder of frames, a single image or a video file
+pub fn open(path: &Path) -> Result<Box<dyn FrameSource>> {
+    if path.is_dir() {
+        return Ok(Box::new(ImageSequence::open(path)?));
+    }
+    if !path.is_file() {
+        return Err(PostureError::Config(format!("Input {} does not exist", path.display())));
+    }
+    if has_extension(path, &IMAGE_EXTENSIONS) {
+        let name = format!("images:{}", path.display());
+        return Ok(Box::new(ImageSequence { name, files: VecDeque::from([path.to_path_buf()]) }));
+    }
+    if has_extension(path, &VIDEO_EXTENSIONS) {
+        return Ok(Box::new(VideoFile::open(path)?));
+    }
+    Err(PostureError::Config(format!(
+        "Unsupported input {} (expected a folder of frames, an image or a video)",
+        path.display()
+    )))
+}
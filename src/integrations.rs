@@ -0,0 +1,326 @@
+/// Integrations: posture events passed on to other software. A webhook gets each event POSTed
+/// as JSON, so alerts can go to Slack, Discord or a home-automation system; an MQTT broker gets
+/// the live state, score and presence too, announced to Home Assistant.
+
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, info, warn};
+
+use crate::config::{
+    MQTT_BROKER, MQTT_DISCOVERY, MQTT_DISCOVERY_PREFIX, MQTT_KEEP_ALIVE_SECS, MQTT_RETRY_SECS, MQTT_TOPIC, WEBHOOK_RETRIES,
+    WEBHOOK_RETRY_SECS,
+};
+use crate::dashboard::LiveState;
+use crate::engine::{EngineEvent, EngineStats, PostureEngine};
+use crate::http;
+use crate::i18n::{tr, tr_args};
+use crate::local_time;
+use crate::mqtt::{Message, MqttOptions, MqttPublisher};
+use crate::settings::Settings;
+use crate::tasks::{self, CancelToken, Cancelled};
+
+/// An event integrations are told about
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum PostureEvent {
+    /// An alert started; `rule` is the check that fired, e.g. "eye_drop"
+    BadPostureStart { rule: String },
+    BadPostureEnd,
+    Away,
+    Calibrated { samples: usize },
+}
+
+impl PostureEvent {
+    /// The integration event for an engine event, if it has one
+    pub fn from_engine(event: &EngineEvent, engine: &PostureEngine) -> Option<Self> {
+        match event {
+            EngineEvent::AlertStarted => Some(PostureEvent::BadPostureStart {
+                rule: engine.alert_rule().map_or("", |rule| rule.key()).to_string(),
+            }),
+            EngineEvent::AlertEnded => Some(PostureEvent::BadPostureEnd),
+            EngineEvent::UserAway => Some(PostureEvent::Away),
+            EngineEvent::CalibrationFinished(calibration) => Some(PostureEvent::Calibrated { samples: calibration.samples }),
+            _ => None,
+        }
+    }
+
+    /// A sentence for chat apps
+    pub fn message(&self) -> String {
+        match self {
+            PostureEvent::BadPostureStart { rule } => tr_args("webhook.bad_posture_start", &[("rule", rule)]),
+            PostureEvent::BadPostureEnd => tr("webhook.bad_posture_end"),
+            PostureEvent::Away => tr("webhook.away"),
+            PostureEvent::Calibrated { .. } => tr("webhook.calibrated"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct WebhookBody<'a> {
+    #[serde(flatten)]
+    event: &'a PostureEvent,
+    /// Unix milliseconds
+    timestamp: i64,
+    /// The message under the keys Slack (`text`) and Discord (`content`) show
+    text: String,
+    content: String,
+}
+
+/// The JSON POSTed for `event`, e.g.
+/// `{"event":"bad_posture_start","rule":"eye_drop","timestamp":...,"text":"...","content":"..."}`
+pub fn webhook_body(event: &PostureEvent, timestamp_ms: i64) -> String {
+    let message = event.message();
+    let body = WebhookBody { event, timestamp: timestamp_ms, text: message.clone(), content: message };
+    serde_json::to_string(&body).unwrap_or_default()
+}
+
+/// POSTs `body` with `post`, retrying WEBHOOK_RETRIES times with waits doubling from
+/// `retry_delay`. Other 4xx answers than 429 aren't retried, since the request itself is wrong.
+pub fn deliver(post: &mut dyn FnMut(&str) -> Result<u16>, body: &str, retry_delay: Duration, cancel: &CancelToken) -> Result<()> {
+    let mut delay = retry_delay;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let error = match post(body) {
+            Ok(status) if (200..300).contains(&status) => return Ok(()),
+            Ok(status) if (400..500).contains(&status) && status != 429 => bail!("Webhook answered HTTP {}", status),
+            Ok(status) => anyhow!("Webhook answered HTTP {}", status),
+            Err(e) => e,
+        };
+        if attempts > WEBHOOK_RETRIES {
+            return Err(error.context(format!("Gave up after {} attempts", attempts)));
+        }
+        debug!("Webhook attempt {} failed: {} - retrying", attempts, error);
+        if !cancel.sleep(delay) {
+            return Err(Cancelled.into());
+        }
+        delay *= 2;
+    }
+}
+
+/// Sends events to a webhook URL from a worker thread, one at a time and in order
+pub struct Webhook {
+    sender: Sender<String>,
+}
+
+impl Webhook {
+    /// The thread stops with `cancel` or when the webhook is dropped
+    pub fn start(url: String, cancel: CancelToken) -> Self {
+        let (sender, receiver) = mpsc::channel::<String>();
+        thread::spawn(move || {
+            for body in receiver {
+                let result = deliver(&mut |body| http::post_json(&url, body), &body, Duration::from_secs(WEBHOOK_RETRY_SECS), &cancel);
+                match result {
+                    Err(e) if tasks::is_cancelled(&e) => break,
+                    Err(e) => warn!("Webhook delivery failed: {:#}", e),
+                    Ok(()) => {}
+                }
+            }
+        });
+        Self { sender }
+    }
+
+    pub fn send(&self, event: &PostureEvent) {
+        let _ = self.sender.send(webhook_body(event, local_time::unix_millis() as i64));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttSettings {
+    /// "host:port"
+    pub broker: String,
+    /// Topics are published below this, e.g. "posture_ai/state"
+    pub topic: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Announce the sensors to Home Assistant
+    pub discovery: bool,
+}
+
+impl Default for MqttSettings {
+    fn default() -> Self {
+        Self {
+            broker: MQTT_BROKER.unwrap_or("localhost:1883").to_string(),
+            topic: MQTT_TOPIC.to_string(),
+            username: None,
+            password: None,
+            discovery: MQTT_DISCOVERY,
+        }
+    }
+}
+
+impl MqttSettings {
+    pub fn state_topic(&self) -> String {
+        format!("{}/state", self.topic)
+    }
+
+    pub fn score_topic(&self) -> String {
+        format!("{}/score", self.topic)
+    }
+
+    pub fn presence_topic(&self) -> String {
+        format!("{}/presence", self.topic)
+    }
+
+    pub fn event_topic(&self) -> String {
+        format!("{}/event", self.topic)
+    }
+
+    /// "online" while connected, "offline" (the last will) once the app is gone
+    pub fn availability_topic(&self) -> String {
+        format!("{}/availability", self.topic)
+    }
+
+    /// The topic made safe for Home Assistant ids, e.g. "home/posture" -> "home_posture"
+    fn node_id(&self) -> String {
+        self.topic.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect()
+    }
+
+    /// Retained Home Assistant discovery configs: a state and a score sensor and an occupancy
+    /// binary sensor, grouped as one device
+    pub fn discovery_messages(&self) -> Vec<Message> {
+        let node = self.node_id();
+        let device = json!({ "identifiers": [node], "name": "Posture AI", "manufacturer": "PostureAI" });
+        let entity = |component: &str, key: &str, name: &str, state_topic: String, extra: serde_json::Value| {
+            let mut config = json!({
+                "name": name,
+                "unique_id": format!("{}_{}", node, key),
+                "state_topic": state_topic,
+                "availability_topic": self.availability_topic(),
+                "device": device,
+            });
+            if let (Some(config), Some(extra)) = (config.as_object_mut(), extra.as_object()) {
+                config.extend(extra.clone());
+            }
+            Message::new(format!("{}/{}/{}/{}/config", MQTT_DISCOVERY_PREFIX, component, node, key), config.to_string(), true)
+        };
+        vec![
+            entity("sensor", "state", "Posture", self.state_topic(), json!({ "icon": "mdi:human-handsup" })),
+            entity("sensor", "score", "Posture score", self.score_topic(),
+                json!({ "unit_of_measurement": "%", "state_class": "measurement", "icon": "mdi:percent" })),
+            entity("binary_sensor", "presence", "At desk", self.presence_topic(),
+                json!({ "device_class": "occupancy", "payload_on": "ON", "payload_off": "OFF" })),
+        ]
+    }
+}
+
+/// `state` as published, e.g. "tracking_poor"
+pub fn state_key(state: LiveState) -> String {
+    serde_json::to_value(state).ok().and_then(|value| value.as_str().map(str::to_string)).unwrap_or_default()
+}
+
+/// Share of judged frames found upright since the app started, in percent; None before any
+pub fn posture_score(stats: &EngineStats) -> Option<u64> {
+    let judged = stats.good_frames + stats.bad_frames;
+    (judged > 0).then(|| stats.good_frames * 100 / judged)
+}
+
+/// Whether someone is at the desk; unknown while detection isn't running
+fn presence(state: LiveState) -> Option<bool> {
+    match state {
+        LiveState::Paused | LiveState::Dormant => None,
+        LiveState::Away => Some(false),
+        _ => Some(true),
+    }
+}
+
+/// Publishes to an MQTT broker: retained state, score and presence as they change, and each
+/// posture event
+struct Mqtt {
+    publisher: MqttPublisher,
+    settings: MqttSettings,
+    state: Option<LiveState>,
+    score: Option<u64>,
+    presence: Option<bool>,
+}
+
+impl Mqtt {
+    fn start(settings: MqttSettings, cancel: CancelToken) -> Self {
+        let options = MqttOptions {
+            broker: settings.broker.clone(),
+            client_id: settings.node_id(),
+            username: settings.username.clone(),
+            password: settings.password.clone(),
+            keep_alive: Duration::from_secs(MQTT_KEEP_ALIVE_SECS),
+            will: Some(Message::new(settings.availability_topic(), "offline", true)),
+            retry: Duration::from_secs(MQTT_RETRY_SECS),
+        };
+        let publisher = MqttPublisher::start(options, cancel);
+        if settings.discovery {
+            for message in settings.discovery_messages() {
+                publisher.publish(message);
+            }
+        }
+        publisher.publish(Message::new(settings.availability_topic(), "online", true));
+        Self { publisher, settings, state: None, score: None, presence: None }
+    }
+
+    fn update(&mut self, state: LiveState, score: Option<u64>) {
+        if self.state != Some(state) {
+            self.state = Some(state);
+            self.publisher.publish(Message::new(self.settings.state_topic(), state_key(state), true));
+        }
+        if let Some(score) = score.filter(|&score| self.score != Some(score)) {
+            self.score = Some(score);
+            self.publisher.publish(Message::new(self.settings.score_topic(), score.to_string(), true));
+        }
+        if let Some(present) = presence(state).filter(|&present| self.presence != Some(present)) {
+            self.presence = Some(present);
+            self.publisher.publish(Message::new(self.settings.presence_topic(), if present { "ON" } else { "OFF" }, true));
+        }
+    }
+
+    fn send(&self, event: &PostureEvent) {
+        let body = webhook_body(event, local_time::unix_millis() as i64);
+        self.publisher.publish(Message::new(self.settings.event_topic(), body, false));
+    }
+}
+
+/// Everything posture events go to, set up from the settings at startup
+#[derive(Default)]
+pub struct Integrations {
+    webhook: Option<Webhook>,
+    mqtt: Option<Mqtt>,
+}
+
+impl Integrations {
+    pub fn start(settings: &Settings, cancel: CancelToken) -> Self {
+        let webhook = settings.webhook_url.as_ref().and_then(|url| {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                warn!("Ignoring webhook_url, which must be http(s): {}", url);
+                return None;
+            }
+            info!("Sending posture events to a webhook.");
+            Some(Webhook::start(url.clone(), cancel.clone()))
+        });
+        let mqtt = settings.mqtt.clone().map(|mqtt| {
+            info!("Publishing posture state to MQTT broker {}.", mqtt.broker);
+            Mqtt::start(mqtt, cancel)
+        });
+        Self { webhook, mqtt }
+    }
+
+    /// Passes `event` on if integrations are told about it
+    pub fn publish(&self, event: &EngineEvent, engine: &PostureEngine) {
+        let Some(event) = PostureEvent::from_engine(event, engine) else { return };
+        if let Some(webhook) = &self.webhook {
+            webhook.send(&event);
+        }
+        if let Some(mqtt) = &self.mqtt {
+            mqtt.send(&event);
+        }
+    }
+
+    /// Call every frame with what the app is doing; only changes are passed on
+    pub fn update(&mut self, state: LiveState, stats: &EngineStats) {
+        if let Some(mqtt) = &mut self.mqtt {
+            mqtt.update(state, posture_score(stats));
+        }
+    }
+}
@@ -0,0 +1,106 @@
+/// Debug keypoint logging: per-inference coordinates as ndjson for offline analysis
+///
+/// Each line is `{"t":<unix ms>,"src":"camera:0","seq":<frame>,"rot":<degrees>,"mir":<bool>,
+/// "vflip":<bool>,"lum":<0-255>,"kp":[[x,y,score],...]}` (17 keypoints, normalized, rounded to 4 decimals) or `"kp":null`
+/// when no pose was found. Files rotate daily under `%APPDATA%\PostureAI\logs` and are
+/// pruned by the maintenance task.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::config::KEYPOINT_LOG_SAMPLE_EVERY;
+use crate::local_time::{self, LocalTime};
+use crate::logging;
+use crate::pipeline::FrameMeta;
+use crate::pose::Pose;
+
+// Buffered lines are flushed at least this often
+const FLUSH_EVERY: u64 = 50;
+
+#[derive(Serialize)]
+struct Record<'a> {
+    t: u64,
+    src: &'a str,
+    seq: u64,
+    rot: u32,
+    mir: bool,
+    vflip: bool,
+    lum: f32,
+    kp: Option<Vec<[f32; 3]>>,
+}
+
+fn round4(value: f32) -> f32 {
+    (value * 10_000.0).round() / 10_000.0
+}
+
+pub struct KeypointLogger {
+    writer: BufWriter<File>,
+    date: String,
+    inferences: u64,
+    written: u64,
+}
+
+impl KeypointLogger {
+    pub fn open() -> Result<Self> {
+        let date = LocalTime::now().date_string();
+        Ok(Self {
+            writer: Self::open_file(&date)?,
+            date,
+            inferences: 0,
+            written: 0,
+        })
+    }
+
+    fn open_file(date: &str) -> Result<BufWriter<File>> {
+        let dir = logging::logs_dir()?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(format!("keypoints-{}.ndjson", date)))?;
+        Ok(BufWriter::new(file))
+    }
+
+    /// Call once per inference; only every KEYPOINT_LOG_SAMPLE_EVERY-th call is written
+    pub fn log(&mut self, meta: &FrameMeta, pose: Option<&Pose>) -> Result<()> {
+        self.inferences += 1;
+        if !(self.inferences - 1).is_multiple_of(KEYPOINT_LOG_SAMPLE_EVERY.max(1)) {
+            return Ok(());
+        }
+
+        // Roll over to a new file after midnight
+        let date = LocalTime::now().date_string();
+        if date != self.date {
+            self.writer.flush()?;
+            self.writer = Self::open_file(&date)?;
+            self.date = date;
+        }
+
+        let record = Record {
+            t: local_time::unix_millis(),
+            src: &meta.source,
+            seq: meta.sequence,
+            rot: meta.rotation_degrees,
+            mir: meta.flip.horizontal,
+            vflip: meta.flip.vertical,
+            lum: meta.brightness.round(),
+            kp: pose.map(|p| p.iter().map(|kp| [round4(kp.x), round4(kp.y), round4(kp.score)]).collect()),
+        };
+        serde_json::to_writer(&mut self.writer, &record)?;
+        self.writer.write_all(b"\n")?;
+
+        self.written += 1;
+        if self.written.is_multiple_of(FLUSH_EVERY) {
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for KeypointLogger {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
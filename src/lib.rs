@@ -1,5 +1,77 @@
 /// Main library module for posture detection application
+///
+/// The posture decision lives in the `posture-core` crate; its modules are re-exported here so
+/// the app and its tests reach them as `crate::engine`, `posture_ai_oc::engine`, ...
+
+pub use posture_core::{
+    alert_sinks, app_data, calibration, clock, engine, escalation, exercise, filter, homography, inference_rate, local_time, motion,
+    pose, quality, stance, stats,
+};
 
 pub mod config;
 pub mod canvas;
+pub mod font;
+pub mod debug_window;
+pub mod ghost;
+pub mod skeleton;
 pub mod blur_overlay;
+pub mod break_overlay;
+pub mod breaks;
+pub mod standing;
+pub mod geofence;
+pub mod active_hours;
+pub mod schedule;
+pub mod camera;
+pub mod camera_controls;
+pub mod device_usage;
+pub mod calls;
+pub mod input;
+pub mod pose_model;
+pub mod http;
+pub mod model_download;
+pub mod yolo_pose;
+pub mod accelerator;
+pub mod placement;
+pub mod lighting;
+pub mod processes;
+pub mod screen_capture;
+pub mod app_exemptions;
+pub mod fullscreen;
+pub mod focus_assist;
+pub mod hotkeys;
+pub mod maintenance;
+pub mod pause;
+pub mod i18n;
+pub mod tray;
+pub mod episode;
+pub mod pipeline;
+pub mod roi;
+pub mod primary;
+pub mod notifications;
+pub mod sound;
+pub mod keypoint_log;
+pub mod logging;
+pub mod settings;
+pub mod settings_window;
+pub mod startup;
+pub mod autostart;
+pub mod tasks;
+pub mod history;
+pub mod report;
+pub mod goals;
+pub mod streaks;
+pub mod feedback;
+pub mod trends;
+pub mod alert_browser;
+pub mod dashboard;
+pub mod api;
+pub mod websocket;
+pub mod integrations;
+pub mod mqtt;
+pub mod osc;
+pub mod diagnostics;
+pub mod probe;
+pub mod cli;
+pub mod error;
+pub mod exit_status;
+pub mod crash_guard;
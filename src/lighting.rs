@@ -0,0 +1,62 @@
+/// Low-light detection from the frames' average brightness
+///
+/// In a dim room the pose model still reports keypoints, only wrong ones, and nothing tells the
+/// user why the alerts turned odd. After LOW_LIGHT_SECS of dark frames the user is warned and,
+/// if enabled, keypoints need LOW_LIGHT_KEYPOINT_CONFIDENCE instead of the usual cutoff.
+
+use std::time::{Duration, Instant};
+
+use crate::clock::{self, SharedClock};
+use crate::config::{LOW_LIGHT_BRIGHTNESS, LOW_LIGHT_KEYPOINT_CONFIDENCE, LOW_LIGHT_RECOVER_BRIGHTNESS, LOW_LIGHT_SECS};
+
+pub struct LowLightCheck {
+    clock: SharedClock,
+    /// Start of the current run of dark frames
+    dark_since: Option<Instant>,
+    dark: bool,
+}
+
+impl LowLightCheck {
+    pub fn new(clock: SharedClock) -> Self {
+        Self { clock, dark_since: None, dark: false }
+    }
+
+    /// Feed each frame's mean luma (`FrameMeta::brightness`). Returns the new state when the
+    /// scene just turned too dark (true) or is bright enough again (false).
+    pub fn update(&mut self, brightness: f32) -> Option<bool> {
+        let now = self.clock.now();
+        if self.dark {
+            if brightness > LOW_LIGHT_RECOVER_BRIGHTNESS {
+                self.dark = false;
+                self.dark_since = None;
+                return Some(false);
+            }
+            return None;
+        }
+        if brightness >= LOW_LIGHT_BRIGHTNESS {
+            self.dark_since = None;
+            return None;
+        }
+        let since = *self.dark_since.get_or_insert(now);
+        if now.saturating_duration_since(since) >= Duration::from_secs(LOW_LIGHT_SECS) {
+            self.dark = true;
+            return Some(true);
+        }
+        None
+    }
+
+    pub fn is_dark(&self) -> bool {
+        self.dark
+    }
+
+    /// The keypoint confidence to require now: the raised one while it's dark, if `raise` is on
+    pub fn min_confidence(&self, raise: bool) -> Option<f32> {
+        (self.dark && raise).then_some(LOW_LIGHT_KEYPOINT_CONFIDENCE)
+    }
+}
+
+impl Default for LowLightCheck {
+    fn default() -> Self {
+        Self::new(clock::system())
+    }
+}
@@ -0,0 +1,112 @@
+/// Logging: `tracing` events go to a daily log file in `%APPDATA%\PostureAI\logs` and,
+/// optionally, the console (stderr, so replay verdicts on stdout stay clean)
+///
+/// Release builds have no console window, so the log file is where problems show up; "Open Log
+/// Folder" in the tray gets there. Old files are pruned by the maintenance task.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, Registry};
+use windows::core::{w, HSTRING, PCWSTR};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Shell::ShellExecuteW;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+use crate::app_data;
+
+const LOG_FILE_PREFIX: &str = "posture_ai";
+
+/// The least severe events that are still written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// The log folder (shared with the keypoint logs), created if needed
+pub fn logs_dir() -> Result<PathBuf> {
+    let dir = app_data::dir()?.join("logs");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn file_appender(dir: &Path) -> Result<RollingFileAppender> {
+    Ok(RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .build(dir)?)
+}
+
+/// The installed logger; keep it alive until exit so buffered lines reach the file
+pub struct Logging {
+    level: reload::Handle<LevelFilter, Registry>,
+    _file_writer: Option<WorkerGuard>,
+}
+
+impl Logging {
+    /// Installs the global logger at `level`. Without a usable log folder only the console
+    /// (if enabled) gets the events.
+    pub fn init(level: LogLevel, console: bool) -> Self {
+        let (filter, handle) = reload::Layer::new(LevelFilter::from(level));
+
+        let (file_layer, guard) = match logs_dir().and_then(|dir| file_appender(&dir)) {
+            Ok(appender) => {
+                let (writer, guard) = tracing_appender::non_blocking(appender);
+                (Some(fmt::layer().with_writer(writer).with_ansi(false)), Some(guard))
+            }
+            Err(e) => {
+                eprintln!("Log file unavailable: {:#}", e);
+                (None, None)
+            }
+        };
+        let console_layer = console.then(|| fmt::layer().with_writer(std::io::stderr).with_target(false));
+
+        if let Err(e) = tracing_subscriber::registry().with(filter).with(file_layer).with(console_layer).try_init() {
+            eprintln!("Logging unavailable: {}", e);
+        }
+        Self { level: handle, _file_writer: guard }
+    }
+
+    /// Switches the level, e.g. to the one in settings.json once it's loaded
+    pub fn set_level(&self, level: LogLevel) {
+        if let Err(e) = self.level.modify(|filter| *filter = level.into()) {
+            warn!("Failed to change the log level: {}", e);
+        }
+    }
+}
+
+/// Opens the log folder in Explorer
+pub fn open_folder() -> Result<()> {
+    let dir = HSTRING::from(logs_dir()?.as_path());
+    let result = unsafe { ShellExecuteW(HWND::default(), w!("open"), &dir, PCWSTR::null(), PCWSTR::null(), SW_SHOWNORMAL) };
+    // Values up to 32 are error codes
+    if result.0 <= 32 {
+        bail!("ShellExecute failed with code {}", result.0);
+    }
+    Ok(())
+}
@@ -5,265 +5,2324 @@
 /// This application uses a camera and MoveNet model to detect posture
 /// and provide visual feedback when bad posture is detected.
 
-use anyhow::Result;
-use image::imageops::FilterType;
-use minifb::{Key, Window, WindowOptions};
-use ndarray::Array4;
-use nokhwa::{
-    pixel_format::RgbFormat,
-    utils::{CameraIndex, RequestedFormat, RequestedFormatType},
-    Camera,
-};
-use ort::{
-    session::{builder::GraphOptimizationLevel, Session},
-    value::Value,
-};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use image::RgbImage;
+use nokhwa::Camera;
+use tracing::{error, info, warn};
 
 // Tray & Menu Dependencies
-use tray_icon::{
-    menu::{Menu, MenuItem, MenuEvent},
-    TrayIconBuilder, Icon,
-};
+use tray_icon::{menu::MenuEvent, TrayIcon, TrayIconBuilder};
 
 // Windows API Dependencies
-use windows::Win32::Foundation::HWND;
-use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::Win32::UI::Input::KeyboardAndMouse::{VK_ESCAPE, VK_R};
+
+use posture_ai_oc::{
+    active_hours,
+    alert_browser::{self, AlertTracker},
+    alert_sinks::{AlertArbiter, AlertSink, ChannelTriggers},
+    autostart,
+    api::{Api, ApiCommand, ApiState, StreamFrame},
+    app_exemptions::{self, Exemption},
+    blur_overlay::BlurOverlay,
+    break_overlay::BreakPanel,
+    breaks::{BreakEvent, BreakKind, BreakPrompt, BreakTimer},
+    calibration::Calibration,
+    cli::Cli,
+    clock::{self, Backoff, Interval, ManualClock, SharedClock},
+    camera,
+    calls,
+    camera_controls::{self, AvailableControl},
+    device_usage,
+    canvas::Canvas,
+    config,
+    dashboard::{Dashboard, LiveState},
+    debug_window::{DebugWindow, WindowEvent},
+    crash_guard::{self, LaunchMarker},
+    diagnostics::{self, FrameHealth, Snapshot},
+    exit_status::{self, Failure, InstanceLock, StatusReport},
+    focus_assist::{Enforcement, FocusAssist},
+    fullscreen,
+    engine::{AlertRule, EngineEvent, PostureEngine, PostureStatus},
+    episode::EpisodeRecorder,
+    escalation::{Escalation, Severity},
+    exercise::{Exercise, ExerciseSession},
+    inference_rate::InferenceRate,
+    goals::{self, Goals},
+    geofence,
+    ghost,
+    skeleton,
+    history::{History, HistoryEvent, HistoryReader},
+    local_time::{self, LocalTime},
+    logging::{self, Logging},
+    homography::{CameraMapping, Homography, ReferenceMarking},
+    hotkeys::{GlobalHotkeys, HotkeyAction},
+    i18n::{self, tr, tr_args},
+    input,
+    integrations::Integrations,
+    keypoint_log::KeypointLogger,
+    maintenance,
+    model_download::{self, ModelSource},
+    notifications::{self, SlouchNotifier},
+    osc::OscSender,
+    pause::{self, Pause},
+    processes,
+    report,
+    schedule,
+    pipeline::{self, Detection, PosturePipeline},
+    placement::{PlacementCheck, PlacementReport},
+    probe,
+    lighting::LowLightCheck,
+    pose::{Keypoint, Pose, LEFT_EYE, LEFT_SHOULDER, NOSE, RIGHT_EYE, RIGHT_SHOULDER},
+    screen_capture,
+    settings::{Loaded, Recovery, SettingChange, Settings, SettingsStore},
+    settings_window::SettingsWindow,
+    startup::{self, Ready, SessionMode, Startup, StartupMessage, StartupStep},
+    sound::{self, SoundAlert},
+    standing::StanceTotals,
+    stats::{self, StatsCollector},
+    streaks::{Achievement, StreakTracker},
+    tasks::{self, BackgroundTasks, CancelToken, Finished},
+    tray::{self, TrayAction, TrayMenu, TrayStatus},
+};
+
+/// Retry timing after the camera was lost
+fn camera_backoff(clock: &SharedClock) -> Backoff {
+    Backoff::new(
+        clock,
+        Duration::from_secs(config::CAMERA_RETRY_MIN_SECS),
+        Duration::from_secs(config::CAMERA_RETRY_MAX_SECS),
+    )
+}
+
+// Swaps the tray icon only when the status actually changes
+fn set_tray_status(tray_icon: &TrayIcon, current: &mut TrayStatus, status: TrayStatus) -> Result<()> {
+    if *current != status {
+        tray_icon.set_icon(Some(tray::status_icon(status)?))?;
+        *current = status;
+    }
+    Ok(())
+}
+
+/// Records a history event; a failing history database never stops detection
+fn record_history(history: &Option<History>, event: HistoryEvent) {
+    if let Some(history) = history {
+        if let Err(e) = history.record_event(&event) {
+            warn!("Failed to record history event: {}", e);
+        }
+    }
+}
+
+/// Stores the alert in progress (if any) as finished now
+fn finish_alert(history: &Option<History>, tracker: &mut AlertTracker) {
+    let Some(alert) = tracker.finish(local_time::unix_millis() as i64) else { return };
+    if let Some(Err(e)) = history.as_ref().map(|h| h.record_alert(&alert)) {
+        warn!("Failed to record alert: {}", e);
+    }
+}
+
+/// Warns once about sitting too close to the screen through `sink`; an overlay warning is faded
+/// in with the posture alert's intensity instead
+fn warn_too_close(sink: AlertSink, enforcement: Enforcement, arbiter: &mut AlertArbiter) {
+    info!("Too close to the screen.");
+    if sink == AlertSink::Overlay || !enforcement.allows(sink) || !arbiter.request(sink) {
+        return;
+    }
+    let result = match sink {
+        AlertSink::Toast => notifications::show_toast(&tr("notification.title"), &tr("notification.too_close")),
+        _ => sound::play_alert(),
+    };
+    if let Err(e) = result {
+        warn!("Failed to warn about the screen distance: {}", e);
+    }
+}
+
+/// The slouching toast's text when one is due: the ladder's first step when it's enabled,
+/// otherwise once slouching has lasted the toast's delay
+fn slouch_toast(settings: &Settings, notifier: &mut SlouchNotifier, escalated: Option<Severity>, slouching: bool) -> Option<String> {
+    if settings.escalation.enabled {
+        return (escalated == Some(Severity::Toast)).then(|| tr("notification.escalation"));
+    }
+    let streak = notifier.update(slouching)?;
+    let minutes = (streak.as_secs() / 60).max(1);
+    Some(tr_args("notification.slouching", &[("minutes", &minutes)]))
+}
+
+/// Suggests a due break on the break panel if there is one (not over a full-screen app), else
+/// with a toast; Focus Assist turns it down like an alert
+fn prompt_break(event: BreakEvent, panel: Option<&BreakPanel>, enforcement: Enforcement, fullscreen: bool) {
+    let BreakEvent::Due { kind, sitting_for } = event else {
+        if let Some(panel) = panel {
+            panel.hide();
+        }
+        return;
+    };
+    let message = match kind {
+        BreakKind::Eye => tr_args("notification.eye_break", &[("seconds", &config::EYE_BREAK_SECS)]),
+        BreakKind::Stretch => tr_args("notification.stretch_break", &[("minutes", &(sitting_for.as_secs() / 60))]),
+    };
+    info!("{}", message);
+    let result = match panel {
+        Some(panel) if enforcement.allows(AlertSink::Overlay) && !fullscreen => {
+            panel.show(&tr("notification.break_title"), &[message]);
+            Ok(())
+        }
+        _ if enforcement.allows(AlertSink::Toast) => notifications::show_toast(&tr("notification.break_title"), &message),
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        warn!("Failed to show the break reminder: {}", e);
+    }
+}
+
+/// Today's streak and goal progress so far, from the history
+fn load_streaks() -> StreakTracker {
+    match HistoryReader::open().and_then(|history| StreakTracker::load(&history)) {
+        Ok(tracker) => tracker,
+        Err(e) => {
+            warn!("Failed to read today's goal progress: {:#}", e);
+            StreakTracker::new()
+        }
+    }
+}
+
+/// Records a reached goal or best streak and celebrates it with a toast, unless turned off in the
+/// goals or held back by Focus Assist
+fn celebrate(achievement: Achievement, history: &Option<History>, goals: &Goals, enforcement: Enforcement) {
+    let message = achievement.message();
+    info!("{}", message);
+    if let Some(Err(e)) = history.as_ref().map(|h| h.record_achievement(&achievement)) {
+        warn!("Failed to record achievement: {}", e);
+    }
+    if goals.celebrate && enforcement.allows(AlertSink::Toast) {
+        if let Err(e) = notifications::show_toast(&tr("notification.goal_title"), &message) {
+            warn!("Failed to show notification: {}", e);
+        }
+    }
+}
+
+/// The break panel, if breaks are prompted on it; a panel that can't be created falls back to toasts
+fn start_break_panel(settings: &Settings) -> Option<BreakPanel> {
+    if !settings.breaks.enabled || settings.breaks.prompt != BreakPrompt::Overlay {
+        return None;
+    }
+    match BreakPanel::new() {
+        Ok(panel) => Some(panel),
+        Err(e) => {
+            warn!("Break panel unavailable, reminding with toasts: {}", e);
+            None
+        }
+    }
+}
+
+/// Feeds a frame's brightness to the low-light check; once it stays dark the user is told and
+/// keypoints need more confidence until it's light again
+fn check_lighting(low_light: &mut LowLightCheck, brightness: f32, pipeline: &mut PosturePipeline, settings: &Settings) {
+    let Some(dark) = low_light.update(brightness) else { return };
+    if dark {
+        info!("Too dark for reliable detection (brightness {:.0}).", brightness);
+        if settings.toast_notifications_enabled {
+            if let Err(e) = notifications::show_toast(&tr("notification.title"), &tr("notification.low_light")) {
+                warn!("Failed to show notification: {}", e);
+            }
+        }
+    } else {
+        info!("Lighting is fine again (brightness {:.0}).", brightness);
+    }
+    pipeline.set_min_confidence(low_light.min_confidence(settings.low_light_raise_confidence));
+}
+
+/// Saves the settings, except in safe mode: its stand-in defaults must not replace the user's file
+fn save_settings(store: &mut SettingsStore, settings: &Settings, safe_mode: bool) {
+    if safe_mode {
+        return;
+    }
+    if let Err(e) = store.save(settings) {
+        warn!("Failed to save settings: {}", e);
+    }
+}
+
+/// Persists the pause so it survives a restart
+fn save_pause(pause: &Pause) {
+    if let Err(e) = pause::store(pause.to_saved(local_time::unix_millis())) {
+        warn!("Failed to save the pause state: {}", e);
+    }
+}
+
+/// Starts `exercise` and explains it; `required` adds that the overlay stays until it's done
+fn begin_exercise(exercise: Exercise, required: bool) -> ExerciseSession {
+    let key = format!("notification.exercise_{}", exercise.key());
+    let mut message = tr_args(&key, &[("reps", &config::EXERCISE_REPS)]);
+    if required {
+        message = format!("{}. {}", message, tr("notification.exercise_to_clear"));
+    }
+    info!("{}", message);
+    if let Err(e) = notifications::show_toast(&tr("notification.title"), &message) {
+        warn!("Failed to show notification: {}", e);
+    }
+    ExerciseSession::new(exercise, config::EXERCISE_REPS)
+}
+
+/// Whether coming back after `away_for` calls for a new baseline
+fn rebaseline_due(away_for: Duration) -> bool {
+    config::REBASELINE_AFTER_AWAY_MINS > 0 && away_for >= Duration::from_secs(config::REBASELINE_AFTER_AWAY_MINS * 60)
+}
+
+/// The OSC output set in the settings, if its target resolves
+fn start_osc(settings: &Settings) -> Option<OscSender> {
+    let osc = settings.osc.as_ref()?;
+    match OscSender::start(osc) {
+        Ok(sender) => {
+            info!("Sending keypoints over OSC to {}.", osc.target);
+            Some(sender)
+        }
+        Err(e) => {
+            warn!("OSC output disabled: {:#}", e);
+            None
+        }
+    }
+}
+
+/// A posture engine for the camera setup, with the saved calibration's baselines and the
+/// thresholds and trigger timing from the settings
+fn configure_engine(
+    settings: &Settings,
+    camera_index: u32,
+    rotation_degrees: u32,
+    calibration: Option<&Calibration>,
+    clock: SharedClock,
+) -> PostureEngine {
+    let mut engine = PostureEngine::new(camera_index, rotation_degrees, calibration.map(|c| c.eye_y), clock);
+    engine.set_hunch_baseline(calibration.and_then(Calibration::ear_shoulder_gap));
+    engine.set_distance_baseline(calibration.and_then(Calibration::body_size));
+    engine.set_deviation(settings.sensitivity_px);
+    engine.set_recovery(settings.recovery_px);
+    engine.set_neck_angle(settings.neck_angle_degrees);
+    engine.set_shoulder_hunch(settings.shoulder_hunch_percent);
+    engine.set_head_tilt(settings.head_tilt_degrees);
+    engine.set_too_close(settings.too_close_percent);
+    engine.set_debounce(Duration::from_millis(settings.debounce_ms));
+    engine.set_grace(Duration::from_millis(settings.grace_ms));
+    engine.set_ignore_movement(settings.ignore_movement);
+    engine
+}
+
+// Starts a calibration session and tells the user what to do
+fn begin_calibration(tray_icon: &TrayIcon, engine: &mut PostureEngine) -> Result<()> {
+    info!("Calibrating - sit upright for {} seconds...", config::CALIBRATION_SECS);
+    tray_icon.set_tooltip(Some(tr("tooltip.calibrating")))?;
+    engine.start_calibration();
+    Ok(())
+}
+
+/// Baselines and tilt corrections are stored per camera setup: reuses the saved ones for the
+/// new camera/rotation or starts a calibration. Returns the ghost pose.
+fn switch_camera_setup(
+    camera_index: u32,
+    rotation_degrees: u32,
+    pipeline: &mut PosturePipeline,
+    tray_icon: &TrayIcon,
+) -> Result<Option<Vec<Keypoint>>> {
+    let calibration = Calibration::load(camera_index, rotation_degrees, pipeline.flip());
+    pipeline.set_source(pipeline::camera_source(camera_index));
+    pipeline.set_rotation(rotation_degrees);
+    pipeline.set_homography(CameraMapping::load(camera_index, rotation_degrees, pipeline.flip()).and_then(|mapping| mapping.homography()));
+    let engine = pipeline.engine_mut();
+    engine.set_camera(camera_index, rotation_degrees, calibration.as_ref().map(|c| c.eye_y));
+    engine.set_hunch_baseline(calibration.as_ref().and_then(Calibration::ear_shoulder_gap));
+    engine.set_distance_baseline(calibration.as_ref().and_then(Calibration::body_size));
+    if engine.baseline().is_none() {
+        begin_calibration(tray_icon, engine)?;
+    }
+    Ok(calibration.map(|c| c.keypoints))
+}
+
+/// The alert channels besides the overlay: the slouch toast, the too-close warning, break
+/// prompts, streak celebrations and the alert sound, with the triggers and arbiter they share
+struct Alerts {
+    // Keeps the channels from firing on top of each other
+    arbiter: AlertArbiter,
+    // Channels with their own alert profile judge the slouch separately
+    channels: ChannelTriggers,
+    // Toast, dim, blur, block: how far the current slouch has escalated (settings.escalation)
+    escalation: Escalation,
+    slouch_notifier: SlouchNotifier,
+    sound: SoundAlert,
+    // Eye and stretch break reminders, apart from the posture alerts
+    break_timer: BreakTimer,
+    break_panel: Option<BreakPanel>,
+    // Good-posture streaks and daily goals
+    streaks: StreakTracker,
+    too_close_warned: bool,
+}
+
+impl Alerts {
+    fn new(clock: &SharedClock, settings: &Settings, engine: &PostureEngine, break_panel: Option<BreakPanel>) -> Self {
+        let mut slouch_notifier = SlouchNotifier::new(Arc::clone(clock));
+        slouch_notifier.set_delay(settings.alert_profiles.toast_delay());
+        Self {
+            arbiter: AlertArbiter::new(Arc::clone(clock), &settings.alert_priority),
+            channels: ChannelTriggers::new(Arc::clone(clock), &settings.alert_profiles, engine),
+            escalation: Escalation::new(),
+            slouch_notifier,
+            sound: SoundAlert::new(Arc::clone(clock)),
+            break_timer: BreakTimer::new(Arc::clone(clock), &settings.breaks),
+            break_panel,
+            streaks: load_streaks(),
+            too_close_warned: false,
+        }
+    }
+
+    /// Forgets the slouch in progress when detection stops
+    fn reset(&mut self) {
+        self.channels.reset();
+        self.escalation.reset();
+    }
+
+    /// Runs the channels for a judged frame. `escalated` is the ladder step just reached; the
+    /// overlay's intensity must already be with the arbiter, which weighs toasts against it.
+    fn update(
+        &mut self,
+        engine: &PostureEngine,
+        settings: &Settings,
+        history: &Option<History>,
+        enforcement: Enforcement,
+        escalated: Option<Severity>,
+        fullscreen: bool,
+    ) {
+        // Too Close - Its own warning, once each time the user stays too close to the screen
+        if engine.is_too_close() && !self.too_close_warned {
+            self.too_close_warned = true;
+            warn_too_close(settings.too_close_alert, enforcement, &mut self.arbiter);
+        }
+        self.too_close_warned &= engine.is_too_close();
+
+        // Toast - Gentler reminder once slouching has lasted the toast's delay, or the ladder's first step
+        if settings.toast_notifications_enabled {
+            let slouching = self.channels.is_slouching(AlertSink::Toast, engine);
+            if let Some(message) = slouch_toast(settings, &mut self.slouch_notifier, escalated, slouching) {
+                if enforcement.allows(AlertSink::Toast) && self.arbiter.request(AlertSink::Toast) {
+                    if let Err(e) = notifications::show_toast(&tr("notification.title"), &message) {
+                        warn!("Failed to show notification: {}", e);
+                    }
+                }
+            }
+        }
+
+        // Breaks - Suggested by time in view, whatever the posture
+        if let Some(event) = self.break_timer.update(!engine.is_away()) {
+            prompt_break(event, self.break_panel.as_ref(), enforcement, fullscreen);
+        }
+
+        // Streaks - Daily goals reached and new best streaks are celebrated
+        let now_secs = (local_time::unix_millis() / 1000) as i64;
+        for achievement in self.streaks.update(engine.status(), &settings.goals, now_secs, local_time::utc_offset_secs()) {
+            celebrate(achievement, history, &settings.goals, enforcement);
+        }
+
+        // Sound - Played with the overlay trigger, repeated while the alert lasts
+        if settings.sound_alerts_enabled
+            && self.sound.update(self.channels.is_alerting(AlertSink::Sound, engine))
+            && enforcement.allows(AlertSink::Sound)
+            && self.arbiter.request(AlertSink::Sound)
+        {
+            if let Err(e) = sound::play_alert() {
+                warn!("Failed to play alert sound: {}", e);
+            }
+        }
+    }
+}
+
+/// Where the live posture state goes besides the tray: the web dashboard, the webhook and other
+/// integrations, the local API and OSC
+struct Outputs {
+    dashboard: Option<Dashboard>,
+    api: Option<Api>,
+    osc: Option<OscSender>,
+    integrations: Integrations,
+}
+
+impl Outputs {
+    /// Detection is paused or dormant
+    fn publish_idle(&mut self, state: LiveState, engine: &PostureEngine) {
+        if let Some(dashboard) = &self.dashboard {
+            dashboard.publish(state, None);
+        }
+        self.integrations.update(state, &engine.stats());
+        if let Some(api) = &self.api {
+            api.publish(ApiState::new(state, None, &engine.stats()));
+        }
+    }
+
+    /// The verdict for a judged frame
+    fn publish_frame(&mut self, engine: &PostureEngine, pose: Option<&Pose>) {
+        let state = engine.status().into();
+        if let Some(dashboard) = &self.dashboard {
+            dashboard.publish(state, engine.delta());
+        }
+        self.integrations.update(state, &engine.stats());
+        if let Some(api) = &self.api {
+            api.publish(ApiState::new(state, engine.delta(), &engine.stats()));
+            api.stream(&StreamFrame { timestamp: local_time::unix_millis() as i64, state, keypoints: pose });
+        }
+        if let Some(osc) = &self.osc {
+            osc.send_frame(state, engine.delta(), pose);
+        }
+    }
+}
+
+/// What the debug window draws over the camera image
+struct DebugView<'a> {
+    engine: &'a PostureEngine,
+    homography: Option<&'a Homography>,
+    pose: Option<&'a Pose>,
+    raw_eye_y: Option<f32>,
+    baseline_pose: Option<&'a [Keypoint]>,
+    reference_marking: Option<&'a ReferenceMarking>,
+    placement: Option<&'a PlacementReport>,
+    too_dark: bool,
+    footer: Option<String>,
+}
+
+/// Draws the skeleton, then whichever of corner marking, placement check, calibration and the
+/// posture lines applies, then the low-light banner and the footer
+fn draw_debug_view(canvas: &mut Canvas, view: &DebugView) {
+    let engine = view.engine;
+
+    // Raw detection on the camera image, under the status overlays
+    if let Some(pose) = view.pose.filter(|_| config::SHOW_SKELETON) {
+        skeleton::draw(canvas, pose);
+    }
+
+    if let Some(marking) = view.reference_marking {
+        canvas.draw_text(&format!("MARK CORNER {}/4", marking.marked().len() + 1), 10, 10, 2, 0xFF00FFFF);
+        let points: Vec<(i32, i32)> = marking.marked().iter()
+            .map(|(x, y)| ((x * config::WIDTH as f32) as i32, (y * config::HEIGHT as f32) as i32))
+            .collect();
+        for pair in points.windows(2) {
+            canvas.draw_line(pair[0].0, pair[0].1, pair[1].0, pair[1].1, 0xFF00FFFF);
+        }
+        for &(x, y) in &points {
+            canvas.draw_circle(x, y, 4, 0x00FFFF);
+        }
+    } else if let Some(report) = view.placement {
+        let score_color = if report.score >= 80 { 0xFF00FF00 } else if report.score >= 50 { 0xFFFFFF00 } else { 0xFFFF0000 };
+        canvas.draw_text(&format!("PLACEMENT {}/100", report.score), 10, 10, 2, score_color);
+        for (i, hint) in report.hints.iter().enumerate() {
+            canvas.draw_text(&tr(hint), 10, 30 + i as i32 * 20, 1, 0xFFFFFFFF);
+        }
+    } else if let Some(seconds_left) = engine.calibration_seconds_remaining() {
+        canvas.draw_text("CALIBRATING - SIT UPRIGHT", 10, 10, 2, 0xFF00FFFF);
+        canvas.draw_text(&format!("{}s left", seconds_left), 10, 30, 1, 0xFFFFFFFF);
+    } else if engine.baseline().is_none() {
+        canvas.draw_text("NOT CALIBRATED - PRESS R", 10, 10, 2, 0xFFFFFF00);
+    } else if let (Some(curr_y), Some(baseline)) = (engine.eye_y(), engine.baseline()) {
+        draw_posture(canvas, view, curr_y, baseline);
+    }
+
+    if view.too_dark {
+        let banner = "TOO DARK - turn on a light or face a window";
+        canvas.fill_rect_blended(4, config::HEIGHT as i32 - 40, Canvas::text_width(banner, 1) + 12, 16, 0x000000, 0.6);
+        canvas.draw_text(banner, 10, config::HEIGHT as i32 - 36, 1, 0xFFFFAA00);
+    }
+
+    if let Some(footer) = &view.footer {
+        canvas.draw_text(footer, 10, config::HEIGHT as i32 - 20, 1, 0xFFAAAAAA);
+    }
+}
 
-use posture_ai_oc::{blur_overlay::BlurOverlay, canvas::Canvas, config};
+/// The baseline, tolerance band and current eye line, the neck and eye lines, and the status panel
+fn draw_posture(canvas: &mut Canvas, view: &DebugView, curr_y: f32, baseline: f32) {
+    let engine = view.engine;
 
-// Simple text drawing function for debug display
-fn draw_text(canvas: &mut Canvas, text: &str, x: i32, y: i32, color: u32) {
-    // Very basic text rendering - in a real app, use a proper font renderer
-    for (i, _) in text.chars().enumerate() {
-        // This is a placeholder - would need proper font rendering for production
-        // For now, just draw a small rectangle for each character
-        let char_x = x + i as i32 * 8;
-        for dy in 0..8 {
-            for dx in 0..6 {
-                canvas.plot(char_x + dx, y + dy, color);
+    // Ghost of the calibrated pose behind the guide lines
+    if let Some(pose) = view.baseline_pose.filter(|_| config::SHOW_BASELINE_GHOST) {
+        // The baseline lives in the corrected view; show it where it appears on camera
+        match view.homography.and_then(|h| h.inverse()) {
+            Some(inverse) => {
+                let on_camera: Vec<_> = pose.iter().map(|kp| inverse.map_keypoint(kp)).collect();
+                ghost::draw(canvas, &on_camera);
             }
+            None => ghost::draw(canvas, pose),
         }
     }
+
+    // Tolerance band around the baseline, behind the lines
+    let good_upper_bound = baseline + engine.deviation();
+    let good_lower_bound = baseline - engine.deviation();
+    let band_height = (good_upper_bound - good_lower_bound) as i32;
+    canvas.fill_rect_blended(0, good_lower_bound as i32, config::WIDTH as i32, band_height, 0x00FF00, 0.12);
+
+    // Draw baseline (white line)
+    canvas.draw_line(0, baseline as i32, config::WIDTH as i32, baseline as i32, 0xFFFFFFFF);
+
+    // Draw current position with color coding
+    let delta = curr_y - baseline;
+    let color = if matches!(engine.status(), PostureStatus::Slouching | PostureStatus::Alert) {
+        // Red: Bad posture (slouching, until back above the recovery line)
+        0xFFFF0000
+    } else if delta > engine.deviation() * config::APPROACHING_FRACTION {
+        // Yellow: Approaching bad posture
+        0xFFFFFF00
+    } else {
+        // Green: Good posture
+        0xFF00FF00
+    };
+
+    canvas.draw_line(0, curr_y as i32, config::WIDTH as i32, curr_y as i32, color);
+    // Unsmoothed eye height, to see how much jitter the filter removes
+    if let Some(raw_y) = view.raw_eye_y {
+        canvas.draw_line_blended((0, raw_y as i32), (config::WIDTH as i32, raw_y as i32), 1, 0xFFFFFF, 0.35);
+    }
+
+    // Draw threshold lines (faint, blended into the image)
+    for bound in [good_upper_bound, good_lower_bound] {
+        canvas.draw_line_blended((0, bound as i32), (config::WIDTH as i32, bound as i32), 1, 0xFFFFFF, 0.5);
+    }
+    // A slouch only clears above this line
+    let recovery_y = (baseline + engine.recovery()) as i32;
+    canvas.draw_line_blended((0, recovery_y), (config::WIDTH as i32, recovery_y), 1, 0xFFFF00, 0.4);
+
+    // Neck line from the shoulders' midpoint to the nose, red while past its limit
+    let neck_craned = engine.rule_trigger(AlertRule::NeckAngle).is_some_and(|trigger| trigger.is_slouching());
+    if let Some(pose) = view.pose.filter(|pose| posture_ai_oc::engine::neck_angle(pose).is_some()) {
+        let to_px = |kp: &Keypoint| ((kp.x * config::WIDTH as f32) as i32, (kp.y * config::HEIGHT as f32) as i32);
+        let (left, right) = (to_px(&pose[LEFT_SHOULDER]), to_px(&pose[RIGHT_SHOULDER]));
+        let mid = ((left.0 + right.0) / 2, (left.1 + right.1) / 2);
+        let nose = to_px(&pose[NOSE]);
+        canvas.draw_line_blended(mid, nose, 2, if neck_craned { 0xFF0000 } else { 0x00FFFF }, 0.8);
+    }
+
+    // Eye line, red while the head is tilted past its limit
+    let head_tilted = engine.rule_trigger(AlertRule::HeadTilt).is_some_and(|trigger| trigger.is_slouching());
+    if let Some(pose) = view.pose.filter(|pose| posture_ai_oc::engine::head_tilt(pose).is_some()) {
+        let to_px = |kp: &Keypoint| ((kp.x * config::WIDTH as f32) as i32, (kp.y * config::HEIGHT as f32) as i32);
+        let color = if head_tilted { 0xFF0000 } else { 0xFF00FF };
+        canvas.draw_line_blended(to_px(&pose[LEFT_EYE]), to_px(&pose[RIGHT_EYE]), 2, color, 0.8);
+    }
+
+    // Draw status text on a dark panel
+    let (status, status_color) = if engine.is_alerting() {
+        ("BAD POSTURE", 0xFFFF0000)
+    } else if engine.is_tracking_poor() {
+        ("TRACKING POOR", 0xFFFFAA00)
+    } else if engine.is_moving() {
+        ("MOVING", 0xFFAAAAAA)
+    } else {
+        ("Good Posture", 0xFF00FF00)
+    };
+    let delta_text = format!("Delta: {:.1}px", delta);
+    let eye_text = match view.raw_eye_y {
+        Some(raw_y) => format!("Eye: {:.1} raw / {:.1} smoothed", raw_y, curr_y),
+        None => format!("Eye: - raw / {:.1} smoothed", curr_y),
+    };
+    let neck_angle = engine.neck_angle().map_or("-".to_string(), |angle| format!("{:.1}", angle));
+    let neck_text = match engine.neck_angle_limit() {
+        Some(limit) => format!("Neck: {} deg (max {:.0})", neck_angle, limit),
+        None => format!("Neck: {} deg (check off)", neck_angle),
+    };
+    let shoulders_hunched = engine.rule_trigger(AlertRule::ShoulderHunch).is_some_and(|trigger| trigger.is_slouching());
+    let hunch = engine.hunch_percent().map_or("-".to_string(), |percent| format!("{:.0}", percent));
+    let hunch_text = match engine.shoulder_hunch_limit() {
+        Some(limit) => format!("Shoulders: {}% raised (max {:.0})", hunch, limit),
+        None => format!("Shoulders: {}% raised (check off)", hunch),
+    };
+    let tilt = engine.head_tilt().map_or("-".to_string(), |angle| format!("{:.1}", angle));
+    let tilt_text = match engine.head_tilt_limit() {
+        Some(limit) => format!("Tilt: {} deg (max {:.0})", tilt, limit),
+        None => format!("Tilt: {} deg (check off)", tilt),
+    };
+    let tracking_text = format!("Tracking: {:.0}%", engine.quality().score() * 100.0);
+    let panel_width = [
+        Canvas::text_width(status, 2),
+        Canvas::text_width(&delta_text, 1),
+        Canvas::text_width(&eye_text, 1),
+        Canvas::text_width(&neck_text, 1),
+        Canvas::text_width(&hunch_text, 1),
+        Canvas::text_width(&tilt_text, 1),
+        Canvas::text_width(&tracking_text, 1),
+    ]
+        .into_iter()
+        .max()
+        .unwrap_or(0) + 12;
+    canvas.fill_rect_blended(4, 4, panel_width, 100, 0x000000, 0.5);
+    canvas.draw_text(status, 10, 10, 2, status_color);
+    canvas.draw_text(&delta_text, 10, 30, 1, 0xFFFFFFFF);
+    canvas.draw_text(&eye_text, 10, 42, 1, 0xFFAAAAAA);
+    canvas.draw_text(&neck_text, 10, 54, 1, if neck_craned { 0xFFFF0000 } else { 0xFFAAAAAA });
+    canvas.draw_text(&hunch_text, 10, 66, 1, if shoulders_hunched { 0xFFFF0000 } else { 0xFFAAAAAA });
+    canvas.draw_text(&tilt_text, 10, 78, 1, if head_tilted { 0xFFFF0000 } else { 0xFFAAAAAA });
+    let tracking_color = if engine.is_tracking_poor() { 0xFFFFAA00 } else { 0xFFAAAAAA };
+    canvas.draw_text(&tracking_text, 10, 90, 1, tracking_color);
 }
 
-fn main() -> Result<()> {
-    // 1. Initialize the Overlay (Hidden at start)
-    let mut overlay = BlurOverlay::new()?;
-
-    // 2. Setup System Tray
-    let tray_menu = Menu::new();
-    let toggle_item = MenuItem::new("Show/Hide Debug Window", true, None);
-    let quit_item = MenuItem::new("Quit Posture AI", true, None);
-    tray_menu.append(&toggle_item)?;
-    tray_menu.append(&quit_item)?;
-
-    // Create a simple green icon 32x32
-    let icon_rgba = vec![0u8; 32 * 32 * 4].into_iter().enumerate().map(|(i, _)| {
-        if i % 4 == 1 { 255 } else if i % 4 == 3 { 255 } else { 0 } // Green, Alpha 255
-    }).collect::<Vec<u8>>();
-
-    let tray_icon_obj = Icon::from_rgba(icon_rgba, 32, 32)?;
-    let _tray_icon = TrayIconBuilder::new()
-        .with_menu(Box::new(tray_menu))
-        .with_tooltip("Posture AI Running")
-        .with_icon(tray_icon_obj)
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    // `--status`: report without starting (exit code 0 = running, see exit_status.rs)
+    if cli.status {
+        let running = InstanceLock::is_held();
+        let preflight = if running {
+            Ok(())
+        } else {
+            let model = SettingsStore::load(cli.config.clone(), cli.overrides()).1.settings.model();
+            let model_path = cli.model.clone()
+                .or_else(|| model_download::find(model))
+                .unwrap_or_else(|| PathBuf::from(model.path()));
+            exit_status::preflight(&model_path, camera::list_cameras().len())
+        };
+        let report = StatusReport::new(running, preflight);
+        println!("{}", report.to_json());
+        return ExitCode::from(report.exit_code);
+    }
+
+    // `--list-cameras`: the indexes `--camera` takes, with each camera's formats
+    if cli.list_cameras {
+        for camera in probe::cameras() {
+            println!("{}", camera.describe());
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    // `--probe`: cameras, model load and one inference as JSON (see probe.rs)
+    if let Some(image) = &cli.probe {
+        let settings = SettingsStore::load(cli.config.clone(), cli.overrides()).1.settings;
+        let report = probe::run(&settings, cli.model.as_deref(), image.as_deref());
+        println!("{}", report.to_json());
+        return ExitCode::from(report.exit_code);
+    }
+
+    // Log file (and console) for everything below; settings.json's level applies once loaded
+    let logging = Logging::init(config::LOG_LEVEL, config::LOG_TO_CONSOLE);
+    let result = match &cli.input {
+        Some(path) => run_replay(path, &cli, &logging),
+        None if cli.headless => run_headless(&cli, &logging),
+        None => run(&cli, &logging),
+    };
+    if let Err(e) = &result {
+        error!("{:#}", e);
+    }
+    ExitCode::from(exit_status::exit_code(&result))
+}
+
+fn run(cli: &Cli, logging: &Logging) -> Result<()> {
+    // Only one instance may own the camera and the tray icon
+    let _instance = InstanceLock::acquire()?;
+
+    // Markers left by earlier launches mean they crashed; too many in a row start safe mode
+    let launch_marker = match LaunchMarker::begin() {
+        Ok(marker) => Some(marker),
+        Err(e) => {
+            warn!("Crash detection unavailable: {}", e);
+            None
+        }
+    };
+    let safe_mode = launch_marker.as_ref().is_some_and(LaunchMarker::needs_safe_mode);
+
+    // Every timer below reads this clock (tests swap in a manual one)
+    let clock = clock::system();
+
+    // 0. Load Settings (recovering from a damaged file if needed); command-line values apply on top
+    let (mut settings_store, Loaded { mut settings, recovery }) = if safe_mode {
+        let mut settings = Settings::safe_mode();
+        cli.overrides().apply(&mut settings);
+        (SettingsStore::new(cli.config.clone(), Settings::safe_mode(), cli.overrides()), Loaded { settings, recovery: None })
+    } else {
+        SettingsStore::load(cli.config.clone(), cli.overrides())
+    };
+    logging.set_level(settings.log_level);
+    if let Some(recovery) = recovery {
+        let key = match recovery {
+            Recovery::FromBackup => "settings.restored_backup",
+            Recovery::Defaults => "settings.reset_defaults",
+        };
+        info!("{}", tr(key));
+        if let Err(e) = notifications::show_toast(&tr("notification.title"), &tr(key)) {
+            warn!("Failed to show notification: {}", e);
+        }
+    }
+
+    // Keep "Start with Windows" pointing at this executable after the old one is gone
+    match autostart::migrate() {
+        Ok(true) => info!("Start with Windows now uses this executable."),
+        Ok(false) => {}
+        Err(e) => warn!("Failed to update autostart: {:#}", e),
+    }
+
+    // 1. Tray first (gray, "starting..."), so there is feedback while the model loads
+    i18n::load(&i18n::system_locale());
+    if let (true, Some(marker)) = (safe_mode, &launch_marker) {
+        info!("Starting in safe mode after {} crashed launches.", marker.unclean_launches());
+        crash_guard::show_safe_mode_dialog(marker.unclean_launches());
+    }
+    let mut tray_status = TrayStatus::Paused;
+    let tray_icon = TrayIconBuilder::new()
+        .with_menu(Box::new(TrayMenu::starting()?))
+        .with_tooltip(tr("tooltip.starting"))
+        .with_icon(tray::status_icon(tray_status)?)
         .build()?;
 
-    // 3. Load AI & Camera
-    println!("Loading MoveNet Thunder...");
-    let mut model = Session::builder()?
-        .with_optimization_level(GraphOptimizationLevel::Level3)?
-        .commit_from_file("movenet_singlepose_thunder.onnx")?;
-
-    println!("Opening Camera...");
-    let index = CameraIndex::Index(0);
-    let requested = RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestFrameRate);
-    let mut camera = Camera::new(index, requested)?;
-    camera.open_stream()?;
-
-    // 4. Create Debug Window
-    let mut window = Window::new(
-        "Posture AI - Monitor (Check System Tray)",
-        config::WIDTH,
-        config::HEIGHT,
-        WindowOptions::default(),
+    // 2. Find cameras and load the model in the background (read once: the session is built for it)
+    let pose_model = settings.model();
+    let model_source = ModelSource::new(pose_model, &settings.model_download_url, &settings.model_sha256);
+    let session_mode = if safe_mode { SessionMode::Safe } else { settings.session_mode() };
+    let startup = Startup::spawn(session_mode, pose_model, cli.model.clone(), settings.execution_provider, model_source);
+
+    // 3. Meanwhile: the overlay (hidden at start, one window per monitor) and the debug window
+    let mut overlay = BlurOverlay::new(
+        Arc::clone(&clock),
+        settings.overlay_effect,
+        settings.overlay_foreground_monitor_only,
     )?;
-    window.set_target_fps(30);
+    overlay.set_fade_ms(settings.fade_ms);
+    overlay.set_exclusions(settings.overlay_exclusions());
+    // Closing it hides to the tray; it also pumps the thread's messages
+    let mut window = DebugWindow::new(&tr("window.title"), config::WIDTH, config::HEIGHT)?;
+
+    // 4. Wait for the background work, keeping the tray responsive
+    let Ready { cameras: available_cameras, model, accelerator } = loop {
+        window.pump();
+        if let Ok(event) = MenuEvent::receiver().try_recv() {
+            if tray::action(&event.id) == Some(TrayAction::Quit) {
+                info!("Quitting...");
+                return Ok(());
+            }
+        }
+        match startup.poll() {
+            Some(StartupMessage::Step(step)) => tray_icon.set_tooltip(Some(tr(step.tooltip_key())))?,
+            Some(StartupMessage::Download(progress)) => {
+                tray_icon.set_tooltip(Some(tr_args("tooltip.starting_download_progress", &[("progress", &progress.describe())])))?
+            }
+            Some(StartupMessage::Finished(result)) => break result?,
+            None => std::thread::sleep(Duration::from_millis(20)),
+        }
+    };
+
+    // 5. Full tray menu for the cameras that were found
+    let mut camera_index = camera::resolve_camera_index(&available_cameras, settings.camera_index);
+    let camera_names: Vec<(u32, String)> = available_cameras.iter()
+        .filter_map(|info| camera::camera_index(info).map(|index| (index, info.human_name())))
+        .collect();
+    // Filled in once the camera is open
+    let mut camera_control_list: Vec<AvailableControl> = Vec::new();
+    let mut tray_menu =
+        TrayMenu::build(&camera_names, camera_index, &camera_control_list, false, false, autostart::is_enabled(), accelerator)?;
+    tray_icon.set_menu(Some(Box::new(tray_menu.menu.clone())));
+    // Today's sitting and standing time, shown in the tooltip while running
+    let mut stance_totals = match HistoryReader::open().and_then(|history| StanceTotals::load(&history)) {
+        Ok(totals) => totals,
+        Err(e) => {
+            warn!("Failed to read today's sitting time: {:#}", e);
+            StanceTotals::default()
+        }
+    };
+    tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+    let mut locale_check = Interval::new(&clock, Duration::from_secs(10));
+
+    // Camera is opened lazily so it can be released while outside the geofence; after it was
+    // lost, reopening is retried with growing waits
+    let mut camera: Option<Camera> = None;
+    let mut camera_retry: Option<Backoff> = None;
+    // Another app holding the camera pauses detection; a fallback camera stands in for the
+    // chosen one (kept here) until it's free again
+    let mut camera_busy = false;
+    let mut fallback_for: Option<u32> = None;
+    let mut busy_check = Interval::new(&clock, Duration::from_secs(config::CAMERA_BUSY_CHECK_SECS));
+    let mut is_location_allowed = geofence::is_location_allowed();
+    let mut geofence_check = Interval::new(&clock, Duration::from_secs(config::GEOFENCE_CHECK_SECS));
+    if !is_location_allowed {
+        info!("Outside allowed networks/machines - staying dormant.");
+        tray_icon.set_tooltip(Some(tr("tooltip.dormant")))?;
+    }
+    // The same, outside the active hours
+    let mut within_active_hours = active_hours::is_active(settings.active_hours, &LocalTime::now());
+    if !within_active_hours {
+        info!("Outside active hours - staying dormant.");
+        tray_icon.set_tooltip(Some(tr("tooltip.off_hours")))?;
+    }
+    // And outside the work schedule, unless forced on from the tray
+    let mut within_schedule = schedule::is_active(settings.work_schedule.as_ref(), &LocalTime::now());
+    let mut schedule_override = false;
+    if !within_schedule {
+        info!("Outside work hours - idle.");
+        tray_icon.set_tooltip(Some(tr("tooltip.off_schedule")))?;
+    }
+
+    window.show();
+
+    // Baseline comes from a saved calibration; without one, calibrate right away
+    let rotation_degrees = settings.camera_rotation_degrees;
+    let calibration = Calibration::load(camera_index, rotation_degrees, settings.camera_flip());
+    let mut engine = configure_engine(&settings, camera_index, rotation_degrees, calibration.as_ref(), Arc::clone(&clock));
+    if engine.baseline().is_none() {
+        info!("No saved calibration.");
+        begin_calibration(&tray_icon, &mut engine)?;
+    }
+    // Frame to verdict: rotation, the model, perspective correction, smoothing (keypoint
+    // jitter) and the engine
+    let mut pipeline = PosturePipeline::new(Arc::clone(&clock), model, pose_model, engine, settings.keypoint_smoothing);
+    pipeline.set_source(pipeline::camera_source(camera_index));
+    pipeline.set_rotation(rotation_degrees);
+    pipeline.set_flip(settings.camera_flip());
+    // Perspective correction for tilted / top-down cameras (set via "Advanced Calibration")
+    pipeline.set_homography(CameraMapping::load(camera_index, rotation_degrees, settings.camera_flip()).and_then(|mapping| mapping.homography()));
+    // Calibrated keypoints, drawn as a ghost in the debug window
+    let mut baseline_pose = calibration.map(|c| c.keypoints);
+    let mut reference_marking: Option<ReferenceMarking> = None;
+
+    // Global hotkeys (Ctrl+Alt+R recalibrate, Ctrl+Alt+P pause)
+    let hotkeys = GlobalHotkeys::register()?;
+
+    // Settings window (tray "Settings..."); edits are saved at most every 2 seconds
+    let mut settings_window = SettingsWindow::new()?;
+    let mut settings_dirty = false;
+    let mut settings_save = Interval::new(&clock, Duration::from_secs(2));
+
+    // Companion web dashboard (opt-in); its edits arrive like the settings window's
+    let dashboard = if settings.dashboard_enabled {
+        match Dashboard::start(config::DASHBOARD_PORT, config::DASHBOARD_LAN, &settings) {
+            Ok(dashboard) => {
+                match dashboard.lan_url() {
+                    Some(url) => info!("Dashboard running at {}", url),
+                    None => info!("Dashboard running on port {}.", config::DASHBOARD_PORT),
+                }
+                Some(dashboard)
+            }
+            Err(e) => {
+                warn!("Dashboard disabled: {}", e);
+                let message = tr_args("dashboard.failed", &[("error", &e)]);
+                if let Err(e) = notifications::show_toast(&tr("notification.title"), &message) {
+                    warn!("Failed to show notification: {}", e);
+                }
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Local API for external dashboards and stream overlays (opt-in, this PC only)
+    let api = if settings.api_enabled {
+        match Api::start(config::API_PORT) {
+            Ok(api) => {
+                info!("Local API running at http://127.0.0.1:{}/state", config::API_PORT);
+                Some(api)
+            }
+            Err(e) => {
+                warn!("Local API disabled: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let osc = start_osc(&settings);
+
+    // Pause / snooze state (tray items, Ctrl+Alt+P and the local API)
+    let mut pause = Pause::new(Arc::clone(&clock));
+    if let Some(saved) = pause::load_saved() {
+        pause.restore(saved, local_time::unix_millis());
+    }
+    let mut was_paused = false;
+    let mut pause_tooltip_refresh = Interval::new(&clock, Duration::from_secs(30));
 
-    // Get Raw Handle to allow Hiding/Showing via Windows API
-    let raw_window_handle = window.get_window_handle();
-    let debug_hwnd = unsafe { std::mem::transmute::<_, HWND>(raw_window_handle) };
+    // Camera placement guidance (active while the user is positioning the camera)
+    let mut placement_check: Option<PlacementCheck> = None;
+    let mut placement_hint = Interval::new(&clock, Duration::from_secs(1));
+    let mut low_light = LowLightCheck::new(Arc::clone(&clock));
+
+    // Guided exercise (tray "Posture Exercise"), or the one an escalated alert waits for
+    let mut exercise: Option<ExerciseSession> = None;
+    let mut exercise_clears_alert = false;
+    let mut next_exercise = Exercise::ChinTuck;
+
+    // Debug keypoint log (off unless KEYPOINT_LOG_ENABLED)
+    let mut keypoint_logger = if config::KEYPOINT_LOG_ENABLED {
+        match KeypointLogger::open() {
+            Ok(logger) => Some(logger),
+            Err(e) => {
+                warn!("Keypoint logging disabled: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Per-minute posture statistics (daily CSV in app data)
+    let mut stats_collector = StatsCollector::new(&clock);
+
+    // Posture history database (sessions, events, calibrations, per-second summaries)
+    let mut history = match History::open() {
+        Ok(history) => Some(history),
+        Err(e) => {
+            warn!("Posture history disabled: {}", e);
+            None
+        }
+    };
+    let mut alert_tracker = AlertTracker::new();
+    // Frames around each alert (only with alert_recording_enabled)
+    let (frames_before, frames_after) = settings.episode_frames();
+    let mut episode_recorder = EpisodeRecorder::new(frames_before, frames_after, config::EPISODE_FRAME_WIDTH);
+
+    // Toasts, sound, breaks and streaks alongside the overlay
+    let mut alerts = Alerts::new(&clock, &settings, pipeline.engine(), start_break_panel(&settings));
+
+    // Frame-loop health for the "What's Wrong?" dialog
+    let mut frame_health = FrameHealth::new(Arc::clone(&clock));
+
+    // Fewer inferences while posture is stably good
+    let mut inference_rate = InferenceRate::new(Arc::clone(&clock));
+
+    // Screen recording suppression
+    let mut active_recorder = screen_capture::active_recorder();
+    let mut capture_check = Interval::new(&clock, Duration::from_secs(config::SCREEN_CAPTURE_CHECK_SECS));
+
+    // Focus Assist tones alerting down
+    let mut focus_assist = FocusAssist::current();
+    let mut focus_check = Interval::new(&clock, Duration::from_secs(config::FOCUS_ASSIST_CHECK_SECS));
+
+    // Foreground apps that keep the overlay clear or pause detection, and full-screen apps
+    let mut exempt_app: Option<(String, Exemption)> = None;
+    let mut fullscreen_app = false;
+    let mut fullscreen_toast_shown = false;
+    let mut app_check = Interval::new(&clock, Duration::from_secs(config::FOREGROUND_APP_CHECK_SECS));
+
+    // Conferencing app in a call, which gets the camera to itself
+    let mut in_call: Option<String> = None;
+    let mut call_check = Interval::new(&clock, Duration::from_secs(config::CALL_CHECK_SECS));
 
-    let mut good_posture_baseline: Option<f32> = None;
     let mut buffer: Vec<u32> = vec![0; config::WIDTH * config::HEIGHT];
-    let mut bad_posture_counter = 0;
+    // Camera frames are decoded into the same image every time
+    let mut raw_frame = RgbImage::default();
+    let mut frame_count: u64 = 0;
+
+    // Measured memory footprint, shown in the debug window
+    let mut memory_mb = processes::current_memory_mb();
+    let mut memory_check = Interval::new(&clock, Duration::from_secs(1));
 
-    // Window Visibility State
-    let mut is_debug_visible = true;
+    // Reports and maintenance run on worker threads; Quit cancels them
+    let shutdown = CancelToken::new();
+    let mut background: BackgroundTasks<PathBuf> = BackgroundTasks::new(shutdown.clone());
+    // Monday review of last week's goals and the active-hours proposal; accepted suggestions
+    // come back as setting edits
+    let mut reviews: BackgroundTasks<Vec<SettingChange>> = BackgroundTasks::new(shutdown.clone());
+    // The geofence lookup runs netsh, too slow for the frame loop
+    let mut geofence_checks: BackgroundTasks<bool> = BackgroundTasks::new(shutdown.clone());
+    let mut review_check = Interval::new(&clock, Duration::from_secs(10 * 60));
+    // Daily active-hours proposal, applied through the same path
+    let mut active_hours_proposal_check = Interval::new(&clock, Duration::from_secs(10 * 60));
+    // Webhook and other integrations get the posture events from worker threads
+    let mut outputs = Outputs { dashboard, api, osc, integrations: Integrations::start(&settings, shutdown.clone()) };
+    maintenance::spawn(shutdown, settings.recording_retention_days);
 
-    println!("Running... Minimize to tray using the Tray Icon.");
+    info!("Running... Closing the debug window keeps Posture AI in the tray.");
 
     // MAIN LOOP
-    // Note: We check `overlay.hwnd` validity because Minifb might close,
-    // but we want to keep running if hidden.
     loop {
+        // Dispatch window messages (tray menu, hotkeys, debug window) every iteration
+        window.pump();
+
         // --- A. Handle Tray Events ---
         if let Ok(event) = MenuEvent::receiver().try_recv() {
-            if event.id == quit_item.id() {
-                println!("Quitting...");
-                break;
-            } else if event.id == toggle_item.id() {
-                is_debug_visible = !is_debug_visible;
-                unsafe {
-                    if is_debug_visible {
-                        ShowWindow(debug_hwnd, SW_SHOW);
+            match tray::action(&event.id) {
+                Some(TrayAction::Quit) => {
+                    info!("Quitting...");
+                    finish_alert(&history, &mut alert_tracker);
+                    if settings_dirty {
+                        save_settings(&mut settings_store, &settings, safe_mode);
+                    }
+                    break;
+                }
+                Some(TrayAction::Pause15) => {
+                    pause.pause_for(Duration::from_secs(15 * 60));
+                    save_pause(&pause);
+                }
+                Some(TrayAction::Pause60) => {
+                    pause.pause_for(Duration::from_secs(60 * 60));
+                    save_pause(&pause);
+                }
+                Some(TrayAction::PauseIndefinitely) => {
+                    pause.pause_indefinitely();
+                    save_pause(&pause);
+                }
+                Some(TrayAction::Resume) => {
+                    pause.resume();
+                    save_pause(&pause);
+                }
+                Some(TrayAction::ToggleScheduleOverride) => {
+                    schedule_override = !schedule_override;
+                    tray_menu.set_schedule_override(schedule_override);
+                    if !within_schedule {
+                        info!("{}", if schedule_override { "Running outside work hours." } else { "Outside work hours - idle." });
+                        tray_icon.set_tooltip(Some(if schedule_override { stance_totals.tooltip() } else { tr("tooltip.off_schedule") }))?;
+                    }
+                }
+                Some(TrayAction::ToggleAutostart) => {
+                    let enable = !autostart::is_enabled();
+                    match if enable { autostart::enable() } else { autostart::disable() } {
+                        Ok(()) => info!("{}", if enable { "Starting with Windows." } else { "No longer starting with Windows." }),
+                        Err(e) => warn!("Failed to change autostart: {:#}", e),
+                    }
+                    // The item toggles itself on click; show what the registry really says
+                    tray_menu.set_autostart(autostart::is_enabled());
+                }
+                Some(TrayAction::Calibrate) => begin_calibration(&tray_icon, pipeline.engine_mut())?,
+                Some(TrayAction::ClearBaseline) => {
+                    if let Err(e) = Calibration::clear() {
+                        warn!("Failed to clear calibration: {}", e);
+                    }
+                    pipeline.engine_mut().clear_baseline();
+                    baseline_pose = None;
+                    record_history(&history, HistoryEvent::BaselineCleared);
+                    info!("Saved baseline cleared - use 'Calibrate Posture' to set a new one.");
+                    tray_icon.set_tooltip(Some(tr("tooltip.not_calibrated")))?;
+                }
+                Some(TrayAction::CheckPlacement) => {
+                    if placement_check.take().is_some() {
+                        info!("Camera placement check finished.");
+                        tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+                    } else {
+                        info!("Camera placement check - follow the hints in the debug window.");
+                        placement_check = Some(PlacementCheck::new());
+                        // Guidance is shown in the debug window, so make sure it's visible
+                        window.show();
+                    }
+                }
+                Some(TrayAction::StartExercise) if exercise.is_none() => {
+                    exercise = Some(begin_exercise(next_exercise, false));
+                    next_exercise = next_exercise.next();
+                }
+                // One is already running
+                Some(TrayAction::StartExercise) => {}
+                Some(TrayAction::MarkReference) => {
+                    info!("Advanced calibration - click the corners of a rectangle in the debug window (Esc cancels).");
+                    let marking = ReferenceMarking::new();
+                    tray_icon.set_tooltip(Some(tr(marking.prompt())))?;
+                    reference_marking = Some(marking);
+                    window.show();
+                }
+                Some(TrayAction::ClearReference) => {
+                    if let Err(e) = CameraMapping::clear() {
+                        warn!("Failed to clear camera mapping: {}", e);
+                    }
+                    if pipeline.homography().is_some() {
+                        pipeline.set_homography(None);
+                        // The baseline was measured in the corrected view
+                        info!("Tilted-camera correction removed.");
+                        begin_calibration(&tray_icon, pipeline.engine_mut())?;
+                    }
+                }
+                Some(TrayAction::OpenReport) => match history.as_mut() {
+                    Some(history) => {
+                        // The report job reads on its own connection
+                        if let Err(e) = history.flush() {
+                            warn!("Failed to write history: {}", e);
+                        }
+                        let snapshot = settings.clone();
+                        if !background.spawn("report", move |cancel| report::generate(&HistoryReader::open()?, &snapshot, cancel)) {
+                            info!("The report is still being created.");
+                        }
+                    }
+                    None => {
+                        if let Err(e) = notifications::show_toast(&tr("notification.title"), &tr("report.unavailable")) {
+                            warn!("Failed to show notification: {}", e);
+                        }
+                    }
+                },
+                Some(TrayAction::MarkFalseAlarm) => {
+                    // The alert on screen, or else the last one that ended
+                    let marked = alert_tracker.mark_false_positive() || match &history {
+                        Some(history) => history.mark_last_alert_false_positive().unwrap_or_else(|e| {
+                            warn!("Failed to mark the alert: {}", e);
+                            false
+                        }),
+                        None => false,
+                    };
+                    let key = if marked { "feedback.marked" } else { "feedback.nothing_to_mark" };
+                    info!("{}", tr(key));
+                    if let Err(e) = notifications::show_toast(&tr("notification.title"), &tr(key)) {
+                        warn!("Failed to show notification: {}", e);
+                    }
+                }
+                Some(TrayAction::AlertHistory) => match &history {
+                    Some(_) => {
+                        if !background.spawn("alert history", |cancel| alert_browser::generate(&HistoryReader::open()?, cancel)) {
+                            info!("The alert history is still being created.");
+                        }
+                    }
+                    None => {
+                        if let Err(e) = notifications::show_toast(&tr("notification.title"), &tr("report.unavailable")) {
+                            warn!("Failed to show notification: {}", e);
+                        }
+                    }
+                },
+                Some(TrayAction::OpenDashboard) => match &outputs.dashboard {
+                    Some(dashboard) => {
+                        if let Err(e) = dashboard.open() {
+                            warn!("Failed to open the dashboard: {}", e);
+                        }
+                    }
+                    None => {
+                        if let Err(e) = notifications::show_toast(&tr("notification.title"), &tr("dashboard.disabled")) {
+                            warn!("Failed to show notification: {}", e);
+                        }
+                    }
+                },
+                Some(TrayAction::OpenLogFolder) => {
+                    if let Err(e) = logging::open_folder() {
+                        warn!("Failed to open the log folder: {:#}", e);
+                    }
+                }
+                Some(TrayAction::Diagnostics) => {
+                    let snapshot = Snapshot {
+                        model_name: format!("{}, {}", pose_model.path(), accelerator.label()),
+                        camera_open: camera.is_some(),
+                        frames: frame_health.report(),
+                        overlay_enabled: settings.blur_overlay_enabled,
+                        overlay_ready: overlay.is_ready(),
+                        recorder: active_recorder.clone(),
+                        calibrated: pipeline.engine().baseline().is_some(),
+                        paused: pause.is_paused(),
+                        location_allowed: is_location_allowed,
+                        active_hours: settings.active_hours.filter(|_| !within_active_hours),
+                        work_schedule: settings.work_schedule.clone().filter(|_| !within_schedule && !schedule_override),
+                    };
+                    diagnostics::show_dialog(diagnostics::run_checks(&snapshot));
+                }
+                Some(TrayAction::SelectCamera(index)) => {
+                    tray_menu.set_selected_camera(index);
+                    settings.camera_index = Some(index);
+                    save_settings(&mut settings_store, &settings, safe_mode);
+                    if index != camera_index {
+                        info!("Switching to camera {}...", index);
+                        camera_index = index;
+                        fallback_for = None;
+                        record_history(&history, HistoryEvent::CameraChanged(index));
+                        camera = None; // Reopened with the new index on the next frame
+                        baseline_pose = switch_camera_setup(camera_index, settings.camera_rotation_degrees, &mut pipeline, &tray_icon)?;
+                    }
+                }
+                Some(TrayAction::SetCameraControl(control, level)) => {
+                    let available = camera_control_list.iter_mut().find(|available| available.control == control);
+                    match (camera.as_mut(), available) {
+                        (Some(active_camera), Some(available)) => {
+                            match camera_controls::set(active_camera, control, &available.range, level) {
+                                Ok(()) => {
+                                    available.level = Some(level);
+                                    tray_menu.set_control_level(control, level);
+                                }
+                                Err(e) => warn!("Failed to set camera {}: {}", camera_controls::key(control), e),
+                            }
+                        }
+                        _ => info!("Camera is not open - control not changed."),
+                    }
+                }
+                Some(TrayAction::OpenSettings) => {
+                    if let Err(e) = settings_window.open(&settings, &camera_names, camera_index) {
+                        warn!("Failed to open settings: {}", e);
+                    }
+                }
+                Some(TrayAction::ToggleDebugWindow) => {
+                    if window.is_visible() {
+                        window.hide();
                     } else {
-                        ShowWindow(debug_hwnd, SW_HIDE);
+                        window.show();
                     }
                 }
+                None => {}
+            }
+        }
+
+        // Rebuild the menu when the Windows display language changes
+        if locale_check.is_due() && i18n::refresh() {
+            info!("Locale changed - rebuilding tray menu.");
+            tray_menu = TrayMenu::build(&camera_names, camera_index, &camera_control_list, pause.is_paused(), schedule_override, autostart::is_enabled(), accelerator)?;
+            tray_icon.set_menu(Some(Box::new(tray_menu.menu.clone())));
+            // Other states (dormant, calibrating, placement, ...) keep their own tooltip
+            if pause.is_paused() {
+                tray_icon.set_tooltip(Some(pause.describe()))?;
             }
         }
 
-        // Also quit if Debug Window is open and ESC is pressed
-        if is_debug_visible && !window.is_open() {
-            // If user clicked X on the window, we treat it as Hide (Minimize to tray)
-            // instead of Quit, to keep the service running.
-            println!("Window closed by user - Minimizing to tray.");
-            is_debug_visible = false;
-            // Minifb destroys the window on close, so we can't just 'Hide' it if it's already destroyed.
-            // Limitation: Minifb doesn't support 'Minimize to Tray' natively well.
-            // Workaround: We break here if window is destroyed.
-            // To make it truly persistent requires a different GUI crate (winit).
-            // For now: ESC or Menu->Quit exits. Closing window exits.
-            break;
+        // --- A2. Debug Window Input ---
+        // Close and Esc hide to the tray; R recalibrates (Ctrl+Alt+R works everywhere)
+        for event in window.poll_events() {
+            match event {
+                WindowEvent::CloseRequested => info!("Debug window hidden - reopen it from the tray."),
+                WindowEvent::KeyPressed(VK_ESCAPE) if reference_marking.is_some() => {
+                    info!("Advanced calibration cancelled.");
+                    reference_marking = None;
+                    tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+                }
+                WindowEvent::KeyPressed(VK_ESCAPE) => window.hide(),
+                WindowEvent::KeyPressed(VK_R) if !pipeline.engine().is_calibrating() => {
+                    info!("Posture Reset!");
+                    begin_calibration(&tray_icon, pipeline.engine_mut())?;
+                }
+                // Advanced calibration - collect the four reference corners
+                WindowEvent::Clicked(x, y) => {
+                    let Some(marking) = reference_marking.as_mut() else { continue };
+                    match marking.add((x, y)) {
+                        Some(corners) => {
+                            reference_marking = None;
+                            match CameraMapping::new(corners, camera_index, settings.camera_rotation_degrees, settings.camera_flip()) {
+                                Ok(mapping) => {
+                                    if let Err(e) = mapping.save() {
+                                        warn!("Failed to save camera mapping: {}", e);
+                                    }
+                                    pipeline.set_homography(mapping.homography());
+                                    info!("Tilted-camera correction set.");
+                                    // Thresholds now apply to the corrected view, so measure a new baseline
+                                    begin_calibration(&tray_icon, pipeline.engine_mut())?;
+                                }
+                                Err(e) => {
+                                    info!("Advanced calibration failed: {}", e);
+                                    tray_icon.set_tooltip(Some(tr("mapping.failed")))?;
+                                }
+                            }
+                        }
+                        None => tray_icon.set_tooltip(Some(tr(marking.prompt())))?,
+                    }
+                }
+                WindowEvent::KeyPressed(_) => {}
+            }
         }
 
-        // --- B. AI Logic (Always Runs) ---
-        let frame_buffer = camera.frame()?;
-        let raw_frame = frame_buffer.decode_image::<RgbFormat>()?;
+        // --- A3. Handle Global Hotkeys and Local API Commands ---
+        match hotkeys.poll() {
+            Some(HotkeyAction::Recalibrate) => begin_calibration(&tray_icon, pipeline.engine_mut())?,
+            Some(HotkeyAction::TogglePause) => {
+                if pause.is_paused() {
+                    pause.resume();
+                } else {
+                    pause.pause_indefinitely();
+                }
+                save_pause(&pause);
+            }
+            None => {}
+        }
+        for command in outputs.api.iter().flat_map(Api::poll_commands) {
+            match command {
+                ApiCommand::Pause { minutes: Some(minutes) } => {
+                    pause.pause_for(Duration::from_secs(minutes * 60));
+                    save_pause(&pause);
+                }
+                ApiCommand::Pause { minutes: None } => {
+                    pause.pause_indefinitely();
+                    save_pause(&pause);
+                }
+                ApiCommand::Resume => {
+                    pause.resume();
+                    save_pause(&pause);
+                }
+                ApiCommand::Calibrate => begin_calibration(&tray_icon, pipeline.engine_mut())?,
+            }
+        }
 
-        // Apply camera rotation if needed (fixes upside-down cameras)
-        let processed_frame = match config::CAMERA_ROTATION_DEGREES {
-            180 => image::imageops::rotate180(&raw_frame),
-            90 => image::imageops::rotate90(&raw_frame),
-            270 => image::imageops::rotate270(&raw_frame),
-            _ => raw_frame, // 0 degrees or any other value = no rotation
-        };
+        // --- A4. Reflect Pause State in the Tray ---
+        let is_paused = pause.is_paused();
+        if is_paused != was_paused {
+            if is_paused {
+                info!("Detection paused.");
+                record_history(&history, HistoryEvent::Paused);
+                tray_icon.set_tooltip(Some(pause.describe()))?;
+            } else {
+                info!("Detection resumed.");
+                record_history(&history, HistoryEvent::Resumed);
+                tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+            }
+            tray_menu.set_paused(is_paused);
+            was_paused = is_paused;
+            pause_tooltip_refresh.reset();
+        }
+        // Refresh the countdown in the tooltip
+        if is_paused && pause_tooltip_refresh.is_due() {
+            tray_icon.set_tooltip(Some(pause.describe()))?;
+        }
+
+        // --- A5. Apply Settings Window Edits and Accepted Review Suggestions (live; saved shortly after) ---
+        let mut changes = settings_window.poll_changes();
+        if let Some(dashboard) = &outputs.dashboard {
+            changes.extend(dashboard.poll_changes());
+        }
+        while let Some(finished) = reviews.poll() {
+            match finished.result {
+                Ok(accepted) => changes.extend(accepted),
+                Err(e) if tasks::is_cancelled(&e) => {}
+                Err(e) => warn!("Failed to create the {}: {}", finished.label, e),
+            }
+        }
+        for change in changes {
+            if !settings.apply(change) {
+                continue;
+            }
+            settings_dirty = true;
+            if let Some(dashboard) = &outputs.dashboard {
+                dashboard.set_settings(&settings);
+            }
+            match change {
+                SettingChange::Sensitivity(_) => {
+                    pipeline.engine_mut().set_deviation(settings.sensitivity_px);
+                    alerts.channels.configure(&settings.alert_profiles, pipeline.engine());
+                }
+                SettingChange::NeckAngle(_) => pipeline.engine_mut().set_neck_angle(settings.neck_angle_degrees),
+                SettingChange::ShoulderHunch(_) => pipeline.engine_mut().set_shoulder_hunch(settings.shoulder_hunch_percent),
+                SettingChange::HeadTilt(_) => pipeline.engine_mut().set_head_tilt(settings.head_tilt_degrees),
+                SettingChange::DebounceMs(_) => {
+                    pipeline.engine_mut().set_debounce(Duration::from_millis(settings.debounce_ms));
+                    alerts.channels.configure(&settings.alert_profiles, pipeline.engine());
+                }
+                SettingChange::FadeMs(_) => overlay.set_fade_ms(settings.fade_ms),
+                SettingChange::OverlayEffect(effect) => {
+                    if let Err(e) = overlay.set_effect(effect) {
+                        warn!("Failed to switch overlay effect: {}", e);
+                    }
+                }
+                SettingChange::Camera(index) => {
+                    tray_menu.set_selected_camera(index);
+                    if index != camera_index {
+                        info!("Switching to camera {}...", index);
+                        camera_index = index;
+                        fallback_for = None;
+                        record_history(&history, HistoryEvent::CameraChanged(index));
+                        camera = None;
+                        baseline_pose = switch_camera_setup(camera_index, settings.camera_rotation_degrees, &mut pipeline, &tray_icon)?;
+                    }
+                }
+                SettingChange::Rotation(degrees) => {
+                    info!("Camera rotation set to {} degrees.", degrees);
+                    baseline_pose = switch_camera_setup(camera_index, degrees, &mut pipeline, &tray_icon)?;
+                }
+                SettingChange::Resolution(_) | SettingChange::FrameRate(_) | SettingChange::Exposure(_) => {
+                    // Formats and exposure are negotiated when the stream opens
+                    info!("Reopening camera {} with the new capture settings...", camera_index);
+                    camera = None;
+                }
+                SettingChange::LowMemory(low_memory) => {
+                    // Frame size, preview and recording follow now; the model at the next start
+                    info!("Low-memory mode {} - the model changes at the next start.", if low_memory { "on" } else { "off" });
+                    camera = None;
+                    let (frames_before, frames_after) = settings.episode_frames();
+                    episode_recorder = EpisodeRecorder::new(frames_before, frames_after, config::EPISODE_FRAME_WIDTH);
+                }
+                SettingChange::Mirror(_) | SettingChange::FlipVertical(_) => {
+                    // The saved baseline and corner marking are flipped along with the frames
+                    info!("Camera flip set to {:?}.", settings.camera_flip());
+                    pipeline.set_flip(settings.camera_flip());
+                    baseline_pose = switch_camera_setup(camera_index, settings.camera_rotation_degrees, &mut pipeline, &tray_icon)?;
+                }
+                SettingChange::SlouchGoalPercent(percent) => info!("Weekly goal set to at most {}% slouching.", percent),
+                SettingChange::ActiveHours(Some(hours)) => info!("Active hours set to {}.", hours.describe()),
+                SettingChange::ActiveHours(None) => info!("Active hours removed - detection runs all day."),
+            }
+        }
+        // Sliders report every step while dragged, so writes are batched
+        if settings_dirty && settings_save.is_due() {
+            settings_dirty = false;
+            save_settings(&mut settings_store, &settings, safe_mode);
+        }
+
+        // --- A6. Open Finished Reports ---
+        while let Some(finished) = background.poll() {
+            match finished.result.and_then(|path| report::open(&path)) {
+                Ok(()) => info!("Opened the {}.", finished.label),
+                Err(e) if tasks::is_cancelled(&e) => {}
+                Err(e) => warn!("Failed to create the {}: {}", finished.label, e),
+            }
+        }
+
+        // --- A7. Weekly Goal Review (first run on or after Monday) ---
+        if config::WEEKLY_REVIEW_ENABLED && review_check.is_due() {
+            let today = LocalTime::now();
+            if let (true, Some(history)) = (goals::is_due(&today, goals::last_reviewed().as_ref()), history.as_mut()) {
+                if let Err(e) = history.flush() {
+                    warn!("Failed to write history: {}", e);
+                }
+                let snapshot = settings.clone();
+                if reviews.spawn("weekly review", move |cancel| goals::run(&HistoryReader::open()?, &snapshot, cancel)) {
+                    // Once a week, even if the user just closes the box
+                    if let Err(e) = goals::mark_reviewed(&today) {
+                        warn!("Failed to remember the weekly review: {}", e);
+                    }
+                }
+            }
+        }
+
+        // --- A8. Active-Hours Proposal (once a day, learned from presence history) ---
+        if config::ACTIVE_HOURS_LEARNING_ENABLED && active_hours_proposal_check.is_due() {
+            let today = LocalTime::now();
+            if let (true, Some(history)) = (active_hours::is_check_due(&today), history.as_mut()) {
+                if let Err(e) = history.flush() {
+                    warn!("Failed to write history: {}", e);
+                }
+                let snapshot = settings.clone();
+                if reviews.spawn("active hours proposal", move |cancel| active_hours::run(&HistoryReader::open()?, &snapshot, cancel)) {
+                    if let Err(e) = active_hours::mark_checked(&today) {
+                        warn!("Failed to remember the active-hours check: {}", e);
+                    }
+                }
+            }
+        }
+
+        // --- B. Geofencing (Dormant outside allowed locations) ---
+        if geofence_check.is_due() {
+            geofence_checks.spawn("geofence check", |_| Ok(geofence::is_location_allowed()));
+        }
+        if let Some(Finished { result: Ok(allowed), .. }) = geofence_checks.poll() {
+            if allowed != is_location_allowed {
+                if allowed {
+                    info!("Entered allowed location - resuming.");
+                    record_history(&history, HistoryEvent::Active);
+                    tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+                } else {
+                    info!("Left allowed location - going dormant.");
+                    record_history(&history, HistoryEvent::Dormant);
+                    tray_icon.set_tooltip(Some(tr("tooltip.dormant")))?;
+                }
+            }
+            is_location_allowed = allowed;
+        }
+
+        // --- B2. Active Hours (Dormant outside the daily window) ---
+        let active = active_hours::is_active(settings.active_hours, &LocalTime::now());
+        if active != within_active_hours {
+            if active {
+                info!("Active hours started - resuming.");
+                record_history(&history, HistoryEvent::OnHours);
+                tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+            } else {
+                info!("Outside active hours - going dormant.");
+                record_history(&history, HistoryEvent::OffHours);
+                tray_icon.set_tooltip(Some(tr("tooltip.off_hours")))?;
+            }
+            within_active_hours = active;
+        }
+
+        // --- B3. Work Schedule (Idle outside the configured windows unless forced on) ---
+        let scheduled = schedule::is_active(settings.work_schedule.as_ref(), &LocalTime::now());
+        if scheduled != within_schedule {
+            if scheduled {
+                info!("Work hours started - resuming.");
+                record_history(&history, HistoryEvent::OnSchedule);
+                // Forcing detection on only lasts until the schedule takes over again
+                schedule_override = false;
+                tray_menu.set_schedule_override(false);
+                tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+            } else {
+                info!("Outside work hours - idle.");
+                record_history(&history, HistoryEvent::OffSchedule);
+                if !schedule_override {
+                    tray_icon.set_tooltip(Some(tr("tooltip.off_schedule")))?;
+                }
+            }
+            within_schedule = scheduled;
+        }
+        let idle_by_schedule = !within_schedule && !schedule_override;
 
-        let model_input_img = image::imageops::resize(&processed_frame, config::MOVENET_SIZE, config::MOVENET_SIZE, FilterType::Triangle);
+        // --- B4. App Exemptions (Pause or keep the overlay clear while a listed app is in front) ---
+        if app_check.is_due() {
+            let foreground = app_exemptions::foreground_exemption(&settings.app_exemptions);
+            if foreground != exempt_app {
+                match &foreground {
+                    Some((name, Exemption::Detection)) => {
+                        info!("{} in the foreground - detection paused.", name);
+                        tray_icon.set_tooltip(Some(tr_args("tooltip.exempt_app", &[("name", name)])))?;
+                    }
+                    Some((name, Exemption::Overlay)) => info!("{} in the foreground - overlay suppressed.", name),
+                    None => info!("Exempt app left the foreground - resuming."),
+                }
+                let was_exempt = matches!(exempt_app, Some((_, Exemption::Detection)));
+                if was_exempt && !matches!(foreground, Some((_, Exemption::Detection))) && !is_paused {
+                    tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+                }
+                exempt_app = foreground;
+            }
 
-        let mut input_array = Array4::<i32>::zeros((1, config::MOVENET_SIZE as usize, config::MOVENET_SIZE as usize, 3));
-        for (x, y, pixel) in model_input_img.enumerate_pixels() {
-            let [r, g, b] = pixel.0;
-            input_array[[0, y as usize, x as usize, 0]] = r as i32;
-            input_array[[0, y as usize, x as usize, 1]] = g as i32;
-            input_array[[0, y as usize, x as usize, 2]] = b as i32;
+            let fullscreen = settings.fullscreen_suppresses_overlay && fullscreen::foreground_is_fullscreen();
+            if fullscreen != fullscreen_app {
+                info!("{}", if fullscreen { "Full-screen app in front - overlay suppressed." } else { "Full screen ended - overlay re-enabled." });
+            }
+            fullscreen_app = fullscreen;
         }
 
-        let input_value = Value::from_array(input_array)?;
-        let outputs = model.run(ort::inputs![input_value])?;
-        let (_, data_slice) = outputs["output_0"].try_extract_tensor::<f32>()?;
+        // --- B5. Fallback Camera (Back to the chosen one once the other app lets go of it) ---
+        if let Some(preferred) = fallback_for {
+            if busy_check.is_due() && device_usage::webcam_users().is_empty() {
+                info!("Camera {} is free again - switching back.", preferred);
+                fallback_for = None;
+                camera_index = preferred;
+                camera = None;
+                baseline_pose = switch_camera_setup(camera_index, settings.camera_rotation_degrees, &mut pipeline, &tray_icon)?;
+            }
+        }
+
+        // --- B6. Video Calls (Paused, camera left to the call, while a call app uses camera or mic) ---
+        if settings.pause_during_calls && call_check.is_due() {
+            let call = calls::active_call(&settings.call_apps);
+            if call != in_call {
+                match &call {
+                    Some(name) => {
+                        info!("Call in {} - detection paused.", name);
+                        tray_icon.set_tooltip(Some(tr_args("tooltip.in_call", &[("name", name)])))?;
+                    }
+                    None => {
+                        info!("Call ended - resuming.");
+                        if !is_paused {
+                            tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+                        }
+                    }
+                }
+                in_call = call;
+            }
+        }
+
+        let exempt_detection = matches!(exempt_app, Some((_, Exemption::Detection)));
+        let waiting_for_camera = camera_retry.as_ref().is_some_and(|retry| !retry.is_due());
+
+        if !is_location_allowed || !within_active_hours || idle_by_schedule || is_paused || exempt_detection || in_call.is_some() || waiting_for_camera {
+            // Release the camera so other apps (and the OS privacy indicator) see it as free
+            if (!is_location_allowed || !within_active_hours || idle_by_schedule || in_call.is_some()) && camera.take().is_some() {
+                info!("Camera released.");
+            }
+            pipeline.engine_mut().reset_debounce();
+            alerts.reset();
+            inference_rate.wake();
+            finish_alert(&history, &mut alert_tracker);
+            episode_recorder.clear();
+            exercise = None;
+            exercise_clears_alert = false;
+            set_tray_status(&tray_icon, &mut tray_status, TrayStatus::Paused)?;
+            let live_state = if is_paused || exempt_detection || in_call.is_some() || camera_busy { LiveState::Paused } else { LiveState::Dormant };
+            outputs.publish_idle(live_state, pipeline.engine());
+            // Dormant time counts as a break; a pause or a call only holds the break timers
+            if live_state == LiveState::Dormant {
+                if let Some(event) = alerts.break_timer.update(false) {
+                    prompt_break(event, alerts.break_panel.as_ref(), settings.focus_assist.enforcement(focus_assist), fullscreen_app);
+                }
+            }
+            overlay.set_target_intensity(0.0);
+            overlay.update();
+            std::thread::sleep(Duration::from_millis(100));
+            continue;
+        }
+
+        if camera.is_none() {
+            // The first open is the last startup step
+            let first_open = frame_count == 0;
+            if first_open {
+                tray_icon.set_tooltip(Some(tr(StartupStep::OpeningCamera.tooltip_key())))?;
+            }
+            let mut opened = camera::open(camera_index, &settings.capture_options());
+            if let Err(e) = &opened {
+                // In use by a video call or similar: pause or switch cameras instead of failing
+                let users = device_usage::webcam_users();
+                camera_busy = !users.is_empty();
+                if camera_busy {
+                    let names = users.join(", ");
+                    warn!("Camera {} is in use by {}: {}", camera_index, names, e);
+                    tray_icon.set_tooltip(Some(tr_args("tooltip.camera_busy", &[("name", &names)])))?;
+                    if let Some(fallback) = camera::fallback_camera(&camera_names, &settings.fallback_cameras, camera_index) {
+                        info!("Switching to fallback camera {}...", fallback);
+                        match camera::open(fallback, &settings.capture_options()) {
+                            Ok(fallback_camera) => {
+                                fallback_for = fallback_for.or(Some(camera_index));
+                                camera_index = fallback;
+                                camera_busy = false;
+                                baseline_pose = switch_camera_setup(camera_index, settings.camera_rotation_degrees, &mut pipeline, &tray_icon)?;
+                                opened = Ok(fallback_camera);
+                            }
+                            Err(e) => warn!("Fallback camera {} unavailable: {}", fallback, e),
+                        }
+                    }
+                }
+            }
+            let opened = match opened {
+                Ok(opened) => opened,
+                Err(e) if first_open && !camera_busy => return Err(e).context(Failure::CameraMissing),
+                Err(e) => {
+                    let retry = camera_retry.get_or_insert_with(|| camera_backoff(&clock));
+                    retry.failed();
+                    if !camera_busy {
+                        warn!("Camera {} still unavailable (attempt {}): {}", camera_index, retry.failures(), e);
+                    }
+                    continue;
+                }
+            };
+            camera_busy = false;
+            if first_open || camera_retry.take().is_some() {
+                if !first_open {
+                    info!("Camera {} reconnected.", camera_index);
+                }
+                let key = if pipeline.engine().is_calibrating() { "tooltip.calibrating" } else { "tooltip.running" };
+                tray_icon.set_tooltip(Some(tr(key)))?;
+            }
+            // Offer the new device's controls in the tray
+            let controls = camera_controls::available(&opened);
+            if controls != camera_control_list {
+                camera_control_list = controls;
+                tray_menu = TrayMenu::build(&camera_names, camera_index, &camera_control_list, pause.is_paused(), schedule_override, autostart::is_enabled(), accelerator)?;
+                tray_icon.set_menu(Some(Box::new(tray_menu.menu.clone())));
+            }
+            camera = Some(opened);
+        }
+        let Some(active_camera) = camera.as_mut() else { continue };
+
+        // --- C. AI Logic (Runs while inside the geofence) ---
+        // Every frame is read so the stream stays current; only due ones are inferred.
+        // A lost camera (unplugged, taken by another app) is reopened; the baseline is kept.
+        let frame_buffer = match active_camera.frame() {
+            Ok(frame_buffer) => frame_buffer,
+            Err(e) => {
+                warn!("Camera lost: {} - retrying.", e);
+                camera = None;
+                camera_retry = Some(camera_backoff(&clock));
+                tray_icon.set_tooltip(Some(tr("tooltip.camera_lost")))?;
+                continue;
+            }
+        };
+        if placement_check.is_some() || exercise.is_some() || pipeline.engine().is_calibrating() {
+            inference_rate.wake();
+        }
+        if !inference_rate.start_if_due() {
+            overlay.update();
+            continue;
+        }
+        camera::decode_frame_into(&frame_buffer, &mut raw_frame)?;
+
+        // Rotated upright (fixes upside-down cameras) and run through the model
+        let detection = pipeline.detect(&raw_frame)?;
+        let Detection { frame: processed_frame, meta: frame_meta, pose: current_pose, inference_time } = &detection;
 
         // Logic
-        let kp_idx = 2;
-        let base_idx = kp_idx * 3;
-        let mut current_eye_y: Option<f32> = None;
+        frame_health.record(current_pose.as_ref(), *inference_time);
+        if let Some(logger) = keypoint_logger.as_mut() {
+            if let Err(e) = logger.log(frame_meta, current_pose.as_ref()) {
+                warn!("Keypoint logging stopped: {}", e);
+                keypoint_logger = None;
+            }
+        }
+        if settings.alert_recording_enabled {
+            if let Err(e) = episode_recorder.push(processed_frame, frame_meta) {
+                warn!("Failed to record alert frame: {}", e);
+                episode_recorder.clear();
+            }
+        }
+
+        // Placement Check - Score the view and give live guidance
+        let placement_report = placement_check.as_mut()
+            .map(|check| check.update(current_pose.as_ref(), processed_frame));
+        if let Some(report) = &placement_report {
+            if placement_hint.is_due() {
+                let hint = tr_args("tooltip.placement", &[("score", &report.score), ("hint", &tr(report.hints[0]))]);
+                info!("{}", hint);
+                tray_icon.set_tooltip(Some(&hint))?;
+            }
+        }
 
-        if data_slice.len() > base_idx + 2 {
-            let raw_y = data_slice[base_idx];
-            let score = data_slice[base_idx + 2];
-            if score > 0.3 {
-                current_eye_y = Some(raw_y * config::HEIGHT as f32);
+        // Low Light - Warn once it stays dark; keypoints need more confidence meanwhile
+        check_lighting(&mut low_light, frame_meta.brightness, &mut pipeline, &settings);
+
+        // Calibration and posture check, in the corrected view when a tilted-camera mapping is
+        // set; the raw eye height is kept for the debug window
+        let state = pipeline.judge(&detection);
+        let raw_eye_y = state.raw_eye_y;
+        for event in state.events {
+            outputs.integrations.publish(&event, pipeline.engine());
+            match event {
+                EngineEvent::CalibrationFinished(calibration) => {
+                    info!("Calibration complete ({} frames).", calibration.samples);
+                    if let Err(e) = calibration.save() {
+                        warn!("Failed to save calibration: {}", e);
+                    }
+                    if let Some(Err(e)) = history.as_ref().map(|h| h.record_calibration(&calibration)) {
+                        warn!("Failed to record calibration: {}", e);
+                    }
+                    baseline_pose = Some(calibration.keypoints);
+                    tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+                }
+                EngineEvent::CalibrationFailed => {
+                    info!("Calibration failed - make sure your face is visible and try again.");
+                    record_history(&history, HistoryEvent::CalibrationFailed);
+                    tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+                }
+                EngineEvent::AlertStarted => {
+                    record_history(&history, HistoryEvent::AlertStarted);
+                    let now_ms = local_time::unix_millis() as i64;
+                    let snapshot = if settings.alert_recording_enabled {
+                        let episode = alert_browser::recordings_dir().and_then(|dir| episode_recorder.start(&dir, now_ms));
+                        if let Err(e) = episode {
+                            warn!("Failed to record alert frames: {}", e);
+                        }
+                        // The baseline line only matches the raw frame without tilt correction
+                        let baseline_y = pipeline.engine().baseline()
+                            .filter(|_| pipeline.homography().is_none())
+                            .map(|y| y / config::HEIGHT as f32);
+                        match alert_browser::save_snapshot(processed_frame, frame_meta, current_pose.as_ref(), baseline_y, now_ms) {
+                            Ok(name) => Some(name),
+                            Err(e) => {
+                                warn!("Failed to save alert snapshot: {}", e);
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+                    let rule = pipeline.engine().alert_rule().map_or("", |rule| rule.key());
+                    let slouch_ms = pipeline.engine().slouch_duration().unwrap_or_default().as_millis() as i64;
+                    alert_tracker.start(rule, now_ms, slouch_ms, snapshot);
+                }
+                EngineEvent::AlertEnded => {
+                    record_history(&history, HistoryEvent::AlertEnded);
+                    finish_alert(&history, &mut alert_tracker);
+                }
+                EngineEvent::UserAway => {
+                    info!("Nobody in view - waiting for you to come back.");
+                    record_history(&history, HistoryEvent::Away);
+                    tray_icon.set_tooltip(Some(tr("tooltip.away")))?;
+                }
+                EngineEvent::TrackingPoor => {
+                    info!("Tracking poor (confidence {:.2}) - not judging posture.", pipeline.engine().quality().score());
+                    tray_icon.set_tooltip(Some(tr("tooltip.tracking_poor")))?;
+                }
+                EngineEvent::TrackingRecovered => {
+                    info!("Tracking recovered.");
+                    tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+                }
+                EngineEvent::UserReturned { away_for } => {
+                    info!("Welcome back (away {} s).", away_for.as_secs());
+                    record_history(&history, HistoryEvent::Returned);
+                    inference_rate.wake();
+                    if rebaseline_due(away_for) && config::AUTO_REBASELINE {
+                        info!("Recalibrating once you sit still...");
+                        tray_icon.set_tooltip(Some(tr("tooltip.calibrating")))?;
+                        pipeline.engine_mut().start_calibration_when_settled();
+                    } else {
+                        if rebaseline_due(away_for) {
+                            let message = tr_args("notification.rebaseline", &[("minutes", &(away_for.as_secs() / 60))]);
+                            info!("{}", message);
+                            if let Err(e) = notifications::show_toast(&tr("notification.title"), &message) {
+                                warn!("Failed to show notification: {}", e);
+                            }
+                        }
+                        tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+                    }
+                }
             }
         }
 
-        // Posture Check - Only trigger when slouching down (positive delta)
-        let mut is_currently_bad = false;
-        if let Some(curr_y) = current_eye_y {
-            if good_posture_baseline.is_none() {
-                good_posture_baseline = Some(curr_y);
+        let engine = pipeline.engine();
+        alerts.channels.update(engine);
+        if engine.is_alerting() {
+            alert_tracker.update(engine.delta());
+        }
+        inference_rate.observe(engine.status(), engine.delta().map(|delta| engine.deviation() - delta));
+
+        // Exercise - An alert at full strength can only be cleared by doing one
+        if config::EXERCISE_TO_CLEAR_ALERT && exercise.is_none() && state.alert_intensity >= 1.0 {
+            exercise = Some(begin_exercise(next_exercise, true));
+            exercise_clears_alert = true;
+            next_exercise = next_exercise.next();
+        }
+        if let Some(session) = exercise.as_mut() {
+            if session.update(current_pose.as_ref()) {
+                let progress = tr_args("tooltip.exercise", &[("done", &session.reps()), ("reps", &session.reps_needed())]);
+                info!("{}", progress);
+                tray_icon.set_tooltip(Some(&progress))?;
             }
-            if let Some(baseline) = good_posture_baseline {
-                let delta = curr_y - baseline;
-                // Only trigger when slouching down (positive delta)
-                if delta > config::GOOD_POSTURE_DEVIATION {
-                    is_currently_bad = true;
+            if session.is_complete() {
+                info!("{}", tr("notification.exercise_done"));
+                if let Err(e) = notifications::show_toast(&tr("notification.title"), &tr("notification.exercise_done")) {
+                    warn!("Failed to show notification: {}", e);
                 }
+                tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+                exercise = None;
+                exercise_clears_alert = false;
             }
         }
 
-        if is_currently_bad { bad_posture_counter += 1; } else { bad_posture_counter = 0; }
+        // Statistics - Per-minute aggregates appended to the daily CSV
+        if !engine.is_calibrating() && !engine.is_tracking_poor() {
+            if let Some(delta) = engine.delta() {
+                stats_collector.add_delta(delta);
+            }
+        }
+        if let Some(Err(e)) = history.as_mut().map(|h| h.record_frame(engine.status(), engine.delta(), engine.stance())) {
+            warn!("Failed to record frame summary: {}", e);
+        }
+        let now_secs = (local_time::unix_millis() / 1000) as i64;
+        if stance_totals.record(engine.stance(), now_secs, local_time::utc_offset_secs()) {
+            // Only over the plain running tooltip, not a calibration, check or exercise in progress
+            let running = matches!(
+                engine.status(),
+                PostureStatus::Good | PostureStatus::Approaching | PostureStatus::Slouching | PostureStatus::Alert
+            );
+            if running && placement_check.is_none() && exercise.is_none() && reference_marking.is_none() {
+                tray_icon.set_tooltip(Some(stance_totals.tooltip()))?;
+            }
+        }
+        if let Some(minute) = stats_collector.poll(engine.stats()) {
+            if let Err(e) = stats::append_csv(&minute) {
+                warn!("Failed to write statistics: {}", e);
+            }
+        }
 
-        if bad_posture_counter > config::DEBOUNCE_FRAMES {
-            overlay.set_target_visible(true);
+        // Tray icon mirrors the debug window colors; red once the overlay would trigger
+        let posture_status = match engine.status() {
+            PostureStatus::Alert => TrayStatus::Bad,
+            PostureStatus::Approaching | PostureStatus::Slouching => TrayStatus::Approaching,
+            PostureStatus::Away | PostureStatus::TrackingPoor => TrayStatus::Paused,
+            _ => TrayStatus::Good,
+        };
+        set_tray_status(&tray_icon, &mut tray_status, posture_status)?;
+        outputs.publish_frame(engine, current_pose.as_ref());
+
+        // Screen Recording - Never blur while the screen is being captured; toasts still alert
+        if capture_check.is_due() {
+            let recorder = screen_capture::active_recorder();
+            if recorder != active_recorder {
+                match &recorder {
+                    Some(name) => info!("Screen recording detected ({}) - overlay suppressed.", name),
+                    None => info!("Screen recording ended - overlay re-enabled."),
+                }
+            }
+            active_recorder = recorder;
+        }
+
+        // Focus Assist - Detection and history carry on; alerts are turned down per level
+        if focus_check.is_due() {
+            let level = FocusAssist::current();
+            if level != focus_assist {
+                info!("Focus Assist: {:?} - alerts {:?}.", level, settings.focus_assist.enforcement(level));
+            }
+            focus_assist = level;
+        }
+        let enforcement = settings.focus_assist.enforcement(focus_assist);
+
+        // Detection-exempt apps never get here, so any exempt app keeps the overlay clear
+        let overlay_allowed = enforcement.allows(AlertSink::Overlay) && exempt_app.is_none() && !fullscreen_app;
+        let overlay_shown = settings.blur_overlay_enabled && active_recorder.is_none() && overlay_allowed;
+
+        // Escalation Ladder - Picks the overlay's look and strength instead of the gradual fade-in
+        let escalated = alerts.escalation.update(&settings.escalation, engine.slouch_duration());
+        let severity = alerts.escalation.severity().max(exercise_clears_alert.then_some(Severity::Blur));
+        if settings.escalation.enabled {
+            // A suppressed overlay never holds back the mouse
+            if let Err(e) = overlay.set_severity(severity.filter(|_| overlay_shown)) {
+                warn!("Failed to switch the overlay effect: {}", e);
+            }
+        }
+
+        if overlay_shown {
+            // An escalated alert keeps the overlay at full strength until its exercise is done
+            let mut intensity = if settings.escalation.enabled {
+                severity.map_or(0.0, Severity::overlay_intensity)
+            } else if exercise_clears_alert {
+                1.0
+            } else {
+                alerts.channels.intensity(AlertSink::Overlay, engine)
+            };
+            if settings.too_close_alert == AlertSink::Overlay {
+                intensity = intensity.max(engine.too_close_intensity());
+            }
+            overlay.set_target_intensity(intensity);
         } else {
-            overlay.set_target_visible(false);
+            overlay.set_target_intensity(0.0);
         }
         overlay.update();
+        alerts.arbiter.set_overlay_intensity(overlay.intensity());
 
-        // --- C. Reset Key ---
-        // Only works if window is focused
-        if is_debug_visible && window.is_key_down(Key::R) {
-            good_posture_baseline = None;
-            bad_posture_counter = 0;
-            println!("Posture Reset!");
+        // Full Screen - One toast stands in for the overlay the alert would have shown
+        let overlay_alerting = alerts.channels.is_alerting(AlertSink::Overlay, engine);
+        if fullscreen_app && overlay_alerting && !fullscreen_toast_shown && settings.blur_overlay_enabled && settings.fullscreen_toast {
+            fullscreen_toast_shown = true;
+            if enforcement.allows(AlertSink::Toast) && alerts.arbiter.request(AlertSink::Toast) {
+                if let Err(e) = notifications::show_toast(&tr("notification.title"), &tr("notification.fullscreen_alert")) {
+                    warn!("Failed to show notification: {}", e);
+                }
+            }
         }
+        if !overlay_alerting {
+            fullscreen_toast_shown = false;
+        }
+
+        alerts.update(engine, &settings, &history, enforcement, escalated, fullscreen_app);
 
-        // --- D. Update Debug Window (Only if visible) ---
-        if is_debug_visible {
-            let display_img = image::imageops::resize(&processed_frame, config::WIDTH as u32, config::HEIGHT as u32, FilterType::Triangle);
+        // --- E. Update Debug Window (Only if visible) ---
+        frame_count += 1;
+        if memory_check.is_due() {
+            memory_mb = processes::current_memory_mb();
+        }
+
+        if window.is_visible() && frame_count.is_multiple_of(settings.preview_frame_interval()) {
+            let display_img = pipeline.preview(processed_frame, config::WIDTH as u32, config::HEIGHT as u32);
 
             for (i, pixel) in display_img.pixels().enumerate() {
                 let [r, g, b] = pixel.0;
                 buffer[i] = posture_ai_oc::canvas::from_u8_rgb(r, g, b);
             }
+            let footer = memory_mb.map(|mb| {
+                let mode = match (safe_mode, settings.low_memory) {
+                    (true, _) => "SAFE MODE",
+                    (false, true) => "LOW-MEM",
+                    (false, false) => "",
+                };
+                format!(
+                    "RSS {:.0}MB  {} {:.1}/s {} {}",
+                    mb,
+                    pose_model.label(),
+                    inference_rate.measured_per_sec(),
+                    accelerator.label(),
+                    mode,
+                )
+            });
+            // The preview borrowed the pipeline mutably; the overlays read the engine again
+            let view = DebugView {
+                engine: pipeline.engine(),
+                homography: pipeline.homography(),
+                pose: current_pose.as_ref(),
+                raw_eye_y,
+                baseline_pose: baseline_pose.as_deref(),
+                reference_marking: reference_marking.as_ref(),
+                placement: placement_report.as_ref(),
+                too_dark: low_light.is_dark(),
+                footer,
+            };
+            draw_debug_view(&mut Canvas { buffer: &mut buffer, width: config::WIDTH, height: config::HEIGHT }, &view);
+            window.present(&buffer);
+        }
+        pipeline.recycle(detection);
+    }
+
+    let mut stuck = background.shutdown(Duration::from_millis(config::SHUTDOWN_GRACE_MS));
+    // An open review box can't be cancelled; it closes with the process
+    stuck.extend(reviews.shutdown(Duration::ZERO));
+    stuck.extend(geofence_checks.shutdown(Duration::ZERO));
+    if !stuck.is_empty() {
+        warn!("Quitting without waiting for: {}", stuck.join(", "));
+    }
+    Ok(())
+}
 
-            if let (Some(curr_y), Some(baseline)) = (current_eye_y, good_posture_baseline) {
-                let mut canvas = Canvas { buffer: &mut buffer, width: config::WIDTH, height: config::HEIGHT };
+/// `--headless`: detection, history, toasts and sounds without the tray icon, debug window,
+/// overlay or hotkeys. Settings are read once; Ctrl+C (or the service wrapper) stops it.
+fn run_headless(cli: &Cli, logging: &Logging) -> Result<()> {
+    let _instance = InstanceLock::acquire()?;
+    let clock = clock::system();
+    let (_, Loaded { settings, recovery }) = SettingsStore::load(cli.config.clone(), cli.overrides());
+    logging.set_level(settings.log_level);
+    if recovery.is_some() {
+        warn!("Settings file was damaged; continuing with the recovered settings.");
+    }
+    i18n::load(&i18n::system_locale());
 
-                // Draw baseline (white line)
-                canvas.draw_line(0, baseline as i32, config::WIDTH as i32, baseline as i32, 0xFFFFFFFF);
+    let stop = CancelToken::new();
+    let on_ctrl_c = stop.clone();
+    ctrlc::set_handler(move || on_ctrl_c.cancel()).context("Failed to install the Ctrl+C handler")?;
 
-                // Draw current position with color coding
-                let delta = curr_y - baseline;
-                let color = if delta > config::GOOD_POSTURE_DEVIATION {
-                    // Red: Bad posture (slouching)
-                    0xFFFF0000
-                } else if delta > 0.0 {
-                    // Yellow: Approaching bad posture
-                    0xFFFFFF00
-                } else {
-                    // Green: Good posture
-                    0xFF00FF00
-                };
+    // Same background startup as the tray app, reported on the console instead
+    let pose_model = settings.model();
+    let model_source = ModelSource::new(pose_model, &settings.model_download_url, &settings.model_sha256);
+    let startup = Startup::spawn(settings.session_mode(), pose_model, cli.model.clone(), settings.execution_provider, model_source);
+    let Ready { cameras, model, .. } = loop {
+        if stop.is_cancelled() {
+            return Ok(());
+        }
+        match startup.poll() {
+            Some(StartupMessage::Step(step)) => info!("{}", tr(step.tooltip_key())),
+            Some(StartupMessage::Download(_)) => {}
+            Some(StartupMessage::Finished(result)) => break result?,
+            None => std::thread::sleep(Duration::from_millis(20)),
+        }
+    };
+
+    let camera_index = camera::resolve_camera_index(&cameras, settings.camera_index);
+    let rotation_degrees = settings.camera_rotation_degrees;
+    let calibration = Calibration::load(camera_index, rotation_degrees, settings.camera_flip());
+    let mut engine = configure_engine(&settings, camera_index, rotation_degrees, calibration.as_ref(), Arc::clone(&clock));
+    if engine.baseline().is_none() {
+        // Without a window the user can't be asked to press R; calibrate on the first frames
+        info!("No saved calibration. Calibrating - sit upright for {} seconds...", config::CALIBRATION_SECS);
+        engine.start_calibration();
+    }
+    let mut pipeline = PosturePipeline::new(Arc::clone(&clock), model, pose_model, engine, settings.keypoint_smoothing);
+    pipeline.set_source(pipeline::camera_source(camera_index));
+    pipeline.set_rotation(rotation_degrees);
+    pipeline.set_flip(settings.camera_flip());
+    pipeline.set_homography(CameraMapping::load(camera_index, rotation_degrees, settings.camera_flip()).and_then(|mapping| mapping.homography()));
 
-                canvas.draw_line(0, curr_y as i32, config::WIDTH as i32, curr_y as i32, color);
+    let mut history = match History::open() {
+        Ok(history) => Some(history),
+        Err(e) => {
+            warn!("Posture history disabled: {}", e);
+            None
+        }
+    };
+    let mut alert_tracker = AlertTracker::new();
+    let mut stats_collector = StatsCollector::new(&clock);
+    // Without an overlay only the ladder's toast step applies
+    let mut alerts = Alerts::new(&clock, &settings, pipeline.engine(), None);
+    let mut low_light = LowLightCheck::new(Arc::clone(&clock));
+    let mut inference_rate = InferenceRate::new(Arc::clone(&clock));
 
-                // Draw threshold boundaries
-                let good_upper_bound = baseline + config::GOOD_POSTURE_DEVIATION;
-                let good_lower_bound = baseline - config::GOOD_POSTURE_DEVIATION;
+    let mut camera: Option<Camera> = None;
+    let mut camera_opened = false;
+    let mut camera_retry: Option<Backoff> = None;
+    let mut is_location_allowed = geofence::is_location_allowed();
+    let mut geofence_check = Interval::new(&clock, Duration::from_secs(config::GEOFENCE_CHECK_SECS));
+    let mut geofence_checks: BackgroundTasks<bool> = BackgroundTasks::new(stop.clone());
+    let mut within_active_hours = active_hours::is_active(settings.active_hours, &LocalTime::now());
+    let mut within_schedule = schedule::is_active(settings.work_schedule.as_ref(), &LocalTime::now());
+    let mut focus_assist = FocusAssist::current();
+    let mut focus_check = Interval::new(&clock, Duration::from_secs(config::FOCUS_ASSIST_CHECK_SECS));
+    let mut in_call: Option<String> = None;
+    let mut call_check = Interval::new(&clock, Duration::from_secs(config::CALL_CHECK_SECS));
+    let mut raw_frame = RgbImage::default();
+    let mut outputs = Outputs { dashboard: None, api: None, osc: start_osc(&settings), integrations: Integrations::start(&settings, stop.clone()) };
 
-                // Draw threshold lines (semi-transparent)
-                canvas.draw_line(0, good_upper_bound as i32, config::WIDTH as i32, good_upper_bound as i32, 0x80FFFFFF);
-                canvas.draw_line(0, good_lower_bound as i32, config::WIDTH as i32, good_lower_bound as i32, 0x80FFFFFF);
+    info!("Running headless. Press Ctrl+C to stop.");
+    while !stop.is_cancelled() {
+        if geofence_check.is_due() {
+            geofence_checks.spawn("geofence check", |_| Ok(geofence::is_location_allowed()));
+        }
+        if let Some(Finished { result: Ok(allowed), .. }) = geofence_checks.poll() {
+            if allowed != is_location_allowed {
+                info!("{}", if allowed { "Entered allowed location - resuming." } else { "Left allowed location - going dormant." });
+                record_history(&history, if allowed { HistoryEvent::Active } else { HistoryEvent::Dormant });
+            }
+            is_location_allowed = allowed;
+        }
+        let active = active_hours::is_active(settings.active_hours, &LocalTime::now());
+        if active != within_active_hours {
+            info!("{}", if active { "Active hours started - resuming." } else { "Outside active hours - going dormant." });
+            record_history(&history, if active { HistoryEvent::OnHours } else { HistoryEvent::OffHours });
+            within_active_hours = active;
+        }
+        let scheduled = schedule::is_active(settings.work_schedule.as_ref(), &LocalTime::now());
+        if scheduled != within_schedule {
+            info!("{}", if scheduled { "Work hours started - resuming." } else { "Outside work hours - idle." });
+            record_history(&history, if scheduled { HistoryEvent::OnSchedule } else { HistoryEvent::OffSchedule });
+            within_schedule = scheduled;
+        }
+        if settings.pause_during_calls && call_check.is_due() {
+            let call = calls::active_call(&settings.call_apps);
+            if call != in_call {
+                match &call {
+                    Some(name) => info!("Call in {} - detection paused.", name),
+                    None => info!("Call ended - resuming."),
+                }
+                in_call = call;
+            }
+        }
 
-                // Draw status text
-                if bad_posture_counter > config::DEBOUNCE_FRAMES {
-                    draw_text(&mut canvas, "BAD POSTURE", 10, 10, 0xFFFF0000);
-                    draw_text(&mut canvas, &format!("Delta: {:.1}px", delta), 10, 30, 0xFFFFFFFF);
-                } else {
-                    draw_text(&mut canvas, "Good Posture", 10, 10, 0xFF00FF00);
-                    draw_text(&mut canvas, &format!("Delta: {:.1}px", delta), 10, 30, 0xFFFFFFFF);
+        let waiting_for_camera = camera_retry.as_ref().is_some_and(|retry| !retry.is_due());
+
+        if !is_location_allowed || !within_active_hours || !within_schedule || in_call.is_some() || waiting_for_camera {
+            if camera.take().is_some() {
+                info!("Camera released.");
+            }
+            pipeline.engine_mut().reset_debounce();
+            alerts.reset();
+            inference_rate.wake();
+            finish_alert(&history, &mut alert_tracker);
+            // Dormant time counts as a break; a call only holds the break timers
+            if !is_location_allowed || !within_active_hours || !within_schedule {
+                alerts.break_timer.update(false);
+            }
+            stop.sleep(Duration::from_millis(100));
+            continue;
+        }
+
+        let active_camera = match camera.as_mut() {
+            Some(active_camera) => active_camera,
+            None => match camera::open(camera_index, &settings.capture_options()) {
+                Ok(opened) => {
+                    if camera_retry.take().is_some() {
+                        info!("Camera {} reconnected.", camera_index);
+                    }
+                    camera_opened = true;
+                    camera.insert(opened)
+                }
+                Err(e) => {
+                    // A camera in use by another app (e.g. a video call) pauses detection until it's free
+                    let users = device_usage::webcam_users();
+                    if !camera_opened && users.is_empty() {
+                        return Err(e).context(Failure::CameraMissing);
+                    }
+                    let retry = camera_retry.get_or_insert_with(|| camera_backoff(&clock));
+                    retry.failed();
+                    if users.is_empty() {
+                        warn!("Camera {} still unavailable (attempt {}): {}", camera_index, retry.failures(), e);
+                    } else {
+                        warn!("Camera {} is in use by {} - paused (attempt {}).", camera_index, users.join(", "), retry.failures());
+                    }
+                    continue;
                 }
+            },
+        };
+        let frame_buffer = match active_camera.frame() {
+            Ok(frame_buffer) => frame_buffer,
+            Err(e) => {
+                warn!("Camera lost: {} - retrying.", e);
+                camera = None;
+                camera_retry = Some(camera_backoff(&clock));
+                continue;
             }
+        };
+        if pipeline.engine().is_calibrating() {
+            inference_rate.wake();
+        }
+        if !inference_rate.start_if_due() {
+            continue;
+        }
+        camera::decode_frame_into(&frame_buffer, &mut raw_frame)?;
+        let detection = pipeline.detect(&raw_frame)?;
+        check_lighting(&mut low_light, detection.meta.brightness, &mut pipeline, &settings);
+        let state = pipeline.judge(&detection);
+        for event in state.events {
+            outputs.integrations.publish(&event, pipeline.engine());
+            match event {
+                EngineEvent::CalibrationFinished(calibration) => {
+                    info!("Calibration complete ({} frames).", calibration.samples);
+                    if let Err(e) = calibration.save() {
+                        warn!("Failed to save calibration: {}", e);
+                    }
+                    if let Some(Err(e)) = history.as_ref().map(|h| h.record_calibration(&calibration)) {
+                        warn!("Failed to record calibration: {}", e);
+                    }
+                }
+                EngineEvent::CalibrationFailed => {
+                    info!("Calibration failed - retrying.");
+                    record_history(&history, HistoryEvent::CalibrationFailed);
+                    pipeline.engine_mut().start_calibration();
+                }
+                EngineEvent::AlertStarted => {
+                    info!("Slouching - alert started.");
+                    record_history(&history, HistoryEvent::AlertStarted);
+                    let engine = pipeline.engine();
+                    let rule = engine.alert_rule().map_or("", |rule| rule.key());
+                    let slouch_ms = engine.slouch_duration().unwrap_or_default().as_millis() as i64;
+                    alert_tracker.start(rule, local_time::unix_millis() as i64, slouch_ms, None);
+                }
+                EngineEvent::AlertEnded => {
+                    info!("Posture recovered - alert ended.");
+                    record_history(&history, HistoryEvent::AlertEnded);
+                    finish_alert(&history, &mut alert_tracker);
+                }
+                EngineEvent::UserAway => {
+                    info!("Nobody in view - waiting for you to come back.");
+                    record_history(&history, HistoryEvent::Away);
+                }
+                EngineEvent::TrackingPoor => {
+                    info!("Tracking poor (confidence {:.2}) - not judging posture.", pipeline.engine().quality().score());
+                }
+                EngineEvent::TrackingRecovered => info!("Tracking recovered."),
+                EngineEvent::UserReturned { away_for } => {
+                    info!("Welcome back (away {} s).", away_for.as_secs());
+                    record_history(&history, HistoryEvent::Returned);
+                    inference_rate.wake();
+                    // Nobody can be asked without a tray, so the new baseline is always taken
+                    if rebaseline_due(away_for) {
+                        info!("Recalibrating once you sit still...");
+                        pipeline.engine_mut().start_calibration_when_settled();
+                    }
+                }
+            }
+        }
 
-            window.update_with_buffer(&buffer, config::WIDTH, config::HEIGHT)?;
-        } else {
-            // Important: We must still update the window pump even if hidden/not drawing
-            // to keep the application responsive to OS messages.
-            window.update();
+        let engine = pipeline.engine();
+        alerts.channels.update(engine);
+        if engine.is_alerting() {
+            alert_tracker.update(engine.delta());
+        }
+        inference_rate.observe(engine.status(), engine.delta().map(|delta| engine.deviation() - delta));
+
+        if !engine.is_calibrating() && !engine.is_tracking_poor() {
+            if let Some(delta) = engine.delta() {
+                stats_collector.add_delta(delta);
+            }
+        }
+        if let Some(Err(e)) = history.as_mut().map(|h| h.record_frame(engine.status(), engine.delta(), engine.stance())) {
+            warn!("Failed to record frame summary: {}", e);
+        }
+        outputs.publish_frame(engine, detection.pose.as_ref());
+        if let Some(minute) = stats_collector.poll(engine.stats()) {
+            if let Err(e) = stats::append_csv(&minute) {
+                warn!("Failed to write statistics: {}", e);
+            }
+        }
+
+        if focus_check.is_due() {
+            focus_assist = FocusAssist::current();
+        }
+        let enforcement = settings.focus_assist.enforcement(focus_assist);
+        let escalated = alerts.escalation.update(&settings.escalation, engine.slouch_duration());
+        alerts.update(engine, &settings, &history, enforcement, escalated, false);
+        pipeline.recycle(detection);
+    }
+
+    info!("Stopping...");
+    finish_alert(&history, &mut alert_tracker);
+    Ok(())
+}
+
+/// `--input`: replays a recording through the pipeline and prints what the app would have done.
+/// Time advances by one frame interval per frame, and the recording calibrates itself on its
+/// first frames, so the same file always gives the same verdicts. Nothing is saved.
+fn run_replay(path: &Path, cli: &Cli, logging: &Logging) -> Result<()> {
+    let (_, Loaded { settings, .. }) = SettingsStore::load(cli.config.clone(), cli.overrides());
+    let fps = cli.fps;
+    logging.set_level(settings.log_level);
+    let mut input = input::open(path)?;
+    println!("Replaying {} at {} fps...", input.name(), fps);
+
+    let clock = ManualClock::new();
+    let frame_interval = Duration::from_secs(1) / fps;
+    let mut engine = configure_engine(&settings, config::CAMERA_INDEX, 0, None, clock.shared());
+    engine.start_calibration();
+    let mut pipeline = match &cli.model {
+        Some(model_file) => {
+            let (session, _) = startup::load_model(model_file, settings.session_mode(), settings.execution_provider)?;
+            PosturePipeline::new(clock.shared(), session, settings.model(), engine, settings.keypoint_smoothing)
+        }
+        None => PosturePipeline::load(clock.shared(), settings.model(), settings.execution_provider, engine, settings.keypoint_smoothing)?,
+    };
+
+    let mut last_status = None;
+    while let Some(state) = pipeline.poll(input.as_mut())? {
+        let seconds = (state.meta.sequence - 1) as f32 / fps as f32;
+        if last_status != Some(state.status) {
+            println!("[frame {} @ {:.2}s] {:?}", state.meta.sequence, seconds, state.status);
+            last_status = Some(state.status);
+        }
+        for event in &state.events {
+            match event {
+                EngineEvent::CalibrationFinished(calibration) => {
+                    println!("[frame {} @ {:.2}s] Calibrated on {} frames.", state.meta.sequence, seconds, calibration.samples)
+                }
+                EngineEvent::CalibrationFailed => {
+                    println!("[frame {} @ {:.2}s] Calibration failed - nobody upright in the first frames?", state.meta.sequence, seconds)
+                }
+                EngineEvent::AlertStarted
+                | EngineEvent::AlertEnded
+                | EngineEvent::UserAway
+                | EngineEvent::UserReturned { .. }
+                | EngineEvent::TrackingPoor
+                | EngineEvent::TrackingRecovered => {}
+            }
         }
+        clock.advance(frame_interval);
     }
 
+    let stats = pipeline.engine().stats();
+    println!(
+        "Done: {} frames, {} good, {} slouched, {} alerts.",
+        stats.frames, stats.good_frames, stats.bad_frames, stats.alerts
+    );
     Ok(())
 }
@@ -0,0 +1,122 @@
+/// Periodic housekeeping so long-running installs don't bloat the app-data directory
+
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use tracing::{info, warn};
+
+use crate::app_data;
+use crate::history;
+use crate::config::{LOG_RETENTION_DAYS, MAINTENANCE_INTERVAL_HOURS};
+use crate::episode::EPISODE_PREFIX;
+use crate::tasks::{self, CancelToken};
+
+// Leftovers from interrupted writes are removed once they are this old
+const STALE_TEMP_AGE: Duration = Duration::from_secs(60 * 60);
+// Don't compete with model/camera startup
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Starts the maintenance loop on a background thread; it ends once `cancel` fires.
+/// Saved alert frames are kept for `recording_retention_days`.
+pub fn spawn(cancel: CancelToken, recording_retention_days: u32) {
+    thread::spawn(move || {
+        if !cancel.sleep(STARTUP_DELAY) {
+            return;
+        }
+        loop {
+            match run_once(&cancel, recording_retention_days) {
+                Ok(removed) if removed > 0 => info!("Maintenance: removed {} stale file(s).", removed),
+                Ok(_) => {}
+                Err(e) if tasks::is_cancelled(&e) => return,
+                Err(e) => warn!("Maintenance failed: {}", e),
+            }
+            if !cancel.sleep(Duration::from_secs(MAINTENANCE_INTERVAL_HOURS * 60 * 60)) {
+                return;
+            }
+        }
+    });
+}
+
+/// Runs every maintenance task once, checking `cancel` between them. Returns the number of files removed.
+pub fn run_once(cancel: &CancelToken, recording_retention_days: u32) -> Result<usize> {
+    let dir = app_data::dir()?;
+    let mut removed = 0;
+
+    // 1. Orphaned temp files from interrupted writes
+    removed += prune(&dir, STALE_TEMP_AGE, |path| path.extension().is_some_and(|ext| ext == "tmp"))?;
+    cancel.check()?;
+
+    // 2. Retention limits for rotated logs and saved recordings
+    removed += prune(&dir.join("logs"), DAY * LOG_RETENTION_DAYS, |_| true)?;
+    removed += prune(&dir.join("recordings"), DAY * recording_retention_days, |_| true)?;
+    removed += prune_episodes(&dir.join("recordings"), DAY * recording_retention_days)?;
+    cancel.check()?;
+
+    // 3. Reclaim space in the history database and refresh its statistics
+    if let Err(e) = history::compact() {
+        warn!("Failed to compact history database: {}", e);
+    }
+
+    Ok(removed)
+}
+
+/// Deletes files in `dir` (non-recursive) older than `max_age` that match `filter`.
+/// A missing directory is not an error.
+fn prune(dir: &Path, max_age: Duration, filter: impl Fn(&Path) -> bool) -> Result<usize> {
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() || !filter(&path) {
+            continue;
+        }
+
+        let age = metadata.modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if age > max_age && fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Deletes episode folders (`episode-*`) in `dir` last written to more than `max_age` ago.
+/// Returns the number of folders removed.
+fn prune_episodes(dir: &Path, max_age: Duration) -> Result<usize> {
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let is_episode = entry.file_name().to_str().is_some_and(|name| name.starts_with(EPISODE_PREFIX));
+        let metadata = entry.metadata()?;
+        if !metadata.is_dir() || !is_episode {
+            continue;
+        }
+
+        let age = metadata.modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if age > max_age && fs::remove_dir_all(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
@@ -0,0 +1,132 @@
+/// First-run model download: a pose model that isn't next to the executable is fetched over
+/// HTTPS into the app-data directory and checked against a pinned SHA-256 before it is used
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use crate::app_data;
+use crate::config::MODEL_SHA256;
+use crate::error::{PostureError, Result};
+use crate::http;
+use crate::pose_model::PoseModel;
+use crate::tasks::CancelToken;
+
+const MODELS_DIR: &str = "models";
+
+/// Where a missing model is fetched from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelSource {
+    pub url: String,
+    /// Expected digest; None = not pinned, so the model isn't downloaded
+    pub sha256: Option<String>,
+}
+
+impl ModelSource {
+    /// `url_template` with "{file}" replaced by the model's file name, and the digest MODEL_SHA256
+    /// or else `pinned` (file name -> hex digest) has for it
+    pub fn new(model: PoseModel, url_template: &str, pinned: &BTreeMap<String, String>) -> Self {
+        let shipped = MODEL_SHA256.iter().find(|(file, _)| *file == model.path()).map(|(_, digest)| *digest);
+        Self {
+            url: url_template.replace("{file}", model.path()),
+            sha256: shipped.or_else(|| pinned.get(model.path()).map(String::as_str)).map(str::to_lowercase),
+        }
+    }
+}
+
+/// How much of a download has arrived
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub received: u64,
+    /// From Content-Length; None when the server didn't say
+    pub total: Option<u64>,
+}
+
+impl Progress {
+    pub fn percent(&self) -> Option<u8> {
+        self.total
+            .filter(|&total| total > 0)
+            .map(|total| (self.received.min(total) * 100 / total) as u8)
+    }
+
+    /// "42%", or "12.3 MB" while the size is unknown
+    pub fn describe(&self) -> String {
+        match self.percent() {
+            Some(percent) => format!("{}%", percent),
+            None => format!("{:.1} MB", self.received as f64 / 1_000_000.0),
+        }
+    }
+}
+
+/// The model file to load: the copy next to the executable, else a downloaded one
+pub fn find(model: PoseModel) -> Option<PathBuf> {
+    let bundled = PathBuf::from(model.path());
+    if bundled.is_file() {
+        return Some(bundled);
+    }
+    download_path(model).ok().filter(|path| path.is_file())
+}
+
+/// Where a downloaded copy of `model` is kept
+pub fn download_path(model: PoseModel) -> Result<PathBuf> {
+    let dir = app_data::dir().map_err(PostureError::Model)?.join(MODELS_DIR);
+    fs::create_dir_all(&dir).map_err(PostureError::model)?;
+    Ok(dir.join(model.path()))
+}
+
+/// Whether `digest` looks like a hex SHA-256
+pub fn is_sha256(digest: &str) -> bool {
+    digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Fails unless `data` hashes to `expected`
+pub fn verify(data: &[u8], expected: &str) -> Result<()> {
+    let actual = format!("{:x}", Sha256::digest(data));
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(PostureError::Model(anyhow!("Checksum mismatch: expected {}, got {}", expected, actual)));
+    }
+    Ok(())
+}
+
+/// Downloads `model` from `source`, verifies it and saves it to `download_path`.
+/// `on_progress` is called whenever the shown progress changes; `cancel` stops the download.
+pub fn download(model: PoseModel, source: &ModelSource, cancel: &CancelToken, mut on_progress: impl FnMut(Progress)) -> Result<PathBuf> {
+    if !source.url.starts_with("https://") {
+        return Err(PostureError::Config(format!("Model download URL must be https: {}", source.url)));
+    }
+    let expected = match &source.sha256 {
+        Some(digest) if is_sha256(digest) => digest.clone(),
+        Some(digest) => return Err(PostureError::Config(format!("Pinned SHA-256 for {} isn't a digest: {}", model.path(), digest))),
+        None => {
+            return Err(PostureError::Config(format!(
+                "No SHA-256 pinned for {}; add it to model_sha256 in settings.json or put the file next to the executable",
+                model.path()
+            )))
+        }
+    };
+
+    info!("Downloading {} from {}...", model.path(), source.url);
+    let mut shown = String::new();
+    let data = http::get(&source.url, cancel, &mut |received, total| {
+        let progress = Progress { received, total };
+        let description = progress.describe();
+        if description != shown {
+            if progress.percent().is_some_and(|percent| percent % 10 == 0) {
+                info!("  {}", description);
+            }
+            on_progress(progress);
+            shown = description;
+        }
+    })
+    .map_err(PostureError::Model)?;
+    verify(&data, &expected)?;
+
+    let path = download_path(model)?;
+    app_data::write_atomic(&path, &data).map_err(PostureError::Model)?;
+    info!("Saved {}", path.display());
+    Ok(path)
+}
@@ -0,0 +1,192 @@
+/// Minimal MQTT 3.1.1 publisher over plain TCP: QoS 0 messages, retained ones re-sent after a
+/// reconnect, a last will and keep-alive pings. Enough for smart-home brokers like Mosquitto.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use tracing::{info, warn};
+
+use crate::tasks::CancelToken;
+
+const PINGREQ: [u8; 2] = [0xC0, 0];
+const PINGRESP: [u8; 2] = [0xD0, 0];
+const DISCONNECT: [u8; 2] = [0xE0, 0];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub topic: String,
+    pub payload: String,
+    /// Kept by the broker and handed to anyone subscribing later
+    pub retain: bool,
+}
+
+impl Message {
+    pub fn new(topic: impl Into<String>, payload: impl Into<String>, retain: bool) -> Self {
+        Self { topic: topic.into(), payload: payload.into(), retain }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttOptions {
+    /// "host:port"
+    pub broker: String,
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub keep_alive: Duration,
+    /// Published by the broker when the connection drops without a goodbye
+    pub will: Option<Message>,
+    /// Wait before reconnecting after the connection failed
+    pub retry: Duration,
+}
+
+fn encode_length(mut length: usize, out: &mut Vec<u8>) {
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if length == 0 {
+            break;
+        }
+    }
+}
+
+fn encode_string(text: &str, out: &mut Vec<u8>) {
+    out.extend_from_slice(&(text.len() as u16).to_be_bytes());
+    out.extend_from_slice(text.as_bytes());
+}
+
+/// `body` behind a fixed header of type `first_byte`
+fn packet(first_byte: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = vec![first_byte];
+    encode_length(body.len(), &mut out);
+    out.extend_from_slice(body);
+    out
+}
+
+/// CONNECT with a clean session and the options' credentials and will
+pub fn connect_packet(options: &MqttOptions) -> Vec<u8> {
+    let mut flags = 0x02;
+    let mut payload = Vec::new();
+    encode_string(&options.client_id, &mut payload);
+    if let Some(will) = &options.will {
+        flags |= 0x04 | if will.retain { 0x20 } else { 0 };
+        encode_string(&will.topic, &mut payload);
+        encode_string(&will.payload, &mut payload);
+    }
+    if let Some(username) = &options.username {
+        flags |= 0x80;
+        encode_string(username, &mut payload);
+    }
+    if let Some(password) = &options.password {
+        flags |= 0x40;
+        encode_string(password, &mut payload);
+    }
+    let mut body = Vec::new();
+    encode_string("MQTT", &mut body);
+    body.push(4); // Protocol level 3.1.1
+    body.push(flags);
+    body.extend_from_slice(&(options.keep_alive.as_secs().min(u16::MAX as u64) as u16).to_be_bytes());
+    body.extend_from_slice(&payload);
+    packet(0x10, &body)
+}
+
+/// PUBLISH at QoS 0
+pub fn publish_packet(message: &Message) -> Vec<u8> {
+    let mut body = Vec::new();
+    encode_string(&message.topic, &mut body);
+    body.extend_from_slice(message.payload.as_bytes());
+    packet(0x30 | u8::from(message.retain), &body)
+}
+
+/// Publishes from a worker thread, connecting (and reconnecting) to the broker on its own.
+/// Messages sent while the broker is unreachable are dropped, apart from the latest retained
+/// one per topic, which is sent again after every reconnect.
+pub struct MqttPublisher {
+    sender: Sender<Message>,
+}
+
+impl MqttPublisher {
+    /// The thread stops with `cancel` or when the publisher is dropped
+    pub fn start(options: MqttOptions, cancel: CancelToken) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut retained = BTreeMap::new();
+            while !cancel.is_cancelled() {
+                match session(&options, &receiver, &mut retained, &cancel) {
+                    Ok(()) => break,
+                    Err(e) => {
+                        warn!("MQTT broker {}: {} - reconnecting in {} s", options.broker, e, options.retry.as_secs());
+                        // Drop what queued up meanwhile; the retained state is sent on reconnect
+                        for message in receiver.try_iter() {
+                            if message.retain {
+                                retained.insert(message.topic.clone(), message);
+                            }
+                        }
+                        if !cancel.sleep(options.retry) {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+        Self { sender }
+    }
+
+    pub fn publish(&self, message: Message) {
+        let _ = self.sender.send(message);
+    }
+}
+
+/// One connection: publishes until the publisher is dropped or cancelled (Ok) or the
+/// connection fails (Err)
+fn session(options: &MqttOptions, receiver: &Receiver<Message>, retained: &mut BTreeMap<String, Message>, cancel: &CancelToken) -> Result<()> {
+    let mut stream = TcpStream::connect(&options.broker)?;
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    stream.write_all(&connect_packet(options))?;
+    let mut connack = [0u8; 4];
+    stream.read_exact(&mut connack)?;
+    if connack[0] != 0x20 || connack[3] != 0 {
+        bail!("connection refused (code {})", connack[3]);
+    }
+    info!("Connected to MQTT broker {}.", options.broker);
+    for message in retained.values() {
+        stream.write_all(&publish_packet(message))?;
+    }
+
+    let ping_every = (options.keep_alive / 2).max(Duration::from_secs(1));
+    loop {
+        if cancel.is_cancelled() {
+            let _ = stream.write_all(&DISCONNECT);
+            return Ok(());
+        }
+        match receiver.recv_timeout(ping_every) {
+            Ok(message) => {
+                stream.write_all(&publish_packet(&message))?;
+                if message.retain {
+                    retained.insert(message.topic.clone(), message);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                stream.write_all(&PINGREQ)?;
+                let mut response = [0u8; 2];
+                stream.read_exact(&mut response)?;
+                if response != PINGRESP {
+                    bail!("unexpected answer to a ping");
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                let _ = stream.write_all(&DISCONNECT);
+                return Ok(());
+            }
+        }
+    }
+}
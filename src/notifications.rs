@@ -0,0 +1,91 @@
+/// Windows toast notifications for persistent bad posture
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use windows::core::HSTRING;
+use windows::Data::Xml::Dom::XmlDocument;
+use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+
+use crate::clock::{self, SharedClock};
+use crate::config::{TOAST_AFTER_SECS, TOAST_COOLDOWN_SECS};
+
+// Unpackaged apps have no registered AppUserModelID of their own; PowerShell's is
+// present on every Windows 10/11 install and lets toasts show without an installer.
+const APP_ID: &str = "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe";
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Shows a two-line toast in the Action Center
+pub fn show_toast(title: &str, message: &str) -> Result<()> {
+    let xml = format!(
+        "<toast><visual><binding template=\"ToastGeneric\"><text>{}</text><text>{}</text></binding></visual></toast>",
+        escape_xml(title),
+        escape_xml(message),
+    );
+    let document = XmlDocument::new()?;
+    document.LoadXml(&HSTRING::from(xml))?;
+
+    let toast = ToastNotification::CreateToastNotification(&document)?;
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(APP_ID))?.Show(&toast)?;
+    Ok(())
+}
+
+/// Decides when a slouching streak has lasted long enough for a toast
+pub struct SlouchNotifier {
+    clock: SharedClock,
+    /// Continuous slouching before a toast (TOAST_AFTER_SECS unless the toast's alert profile says otherwise)
+    delay: Duration,
+    slouching_since: Option<Instant>,
+    notified_this_streak: bool,
+    last_notified: Option<Instant>,
+}
+
+impl SlouchNotifier {
+    pub fn new(clock: SharedClock) -> Self {
+        Self {
+            clock,
+            delay: Duration::from_secs(TOAST_AFTER_SECS),
+            slouching_since: None,
+            notified_this_streak: false,
+            last_notified: None,
+        }
+    }
+
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    /// Feed once per frame. Returns how long the user has been slouching when a toast is due.
+    pub fn update(&mut self, slouching: bool) -> Option<Duration> {
+        let now = self.clock.now();
+        if !slouching {
+            self.slouching_since = None;
+            self.notified_this_streak = false;
+            return None;
+        }
+
+        let since = *self.slouching_since.get_or_insert(now);
+        let streak = now.saturating_duration_since(since);
+        let cooled_down = self.last_notified
+            .is_none_or(|last| now.saturating_duration_since(last) >= Duration::from_secs(TOAST_COOLDOWN_SECS));
+
+        if !self.notified_this_streak && streak >= self.delay && cooled_down {
+            self.notified_this_streak = true;
+            self.last_notified = Some(now);
+            return Some(streak);
+        }
+        None
+    }
+}
+
+impl Default for SlouchNotifier {
+    fn default() -> Self {
+        Self::new(clock::system())
+    }
+}
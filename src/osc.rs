@@ -0,0 +1,122 @@
+/// OSC output: every analyzed frame's keypoints sent over UDP as one OSC 1.0 bundle, so VTuber
+/// rigs, TouchDesigner patches and other creative tools can reuse the pose
+///
+/// Below the prefix (default "/posture"): `/state` (string), `/present` (int 0/1), `/delta`
+/// (float, pixels below the baseline, while calibrated), `/keypoint/<name>` (x, y, score) and
+/// `/keypoints` (all 17 as x, y, score in a row). Coordinates are frame fractions (0..1).
+
+use std::net::UdpSocket;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::config::{OSC_PREFIX, OSC_TARGET};
+use crate::dashboard::LiveState;
+use crate::integrations;
+use crate::pose::{Pose, KEYPOINT_NAMES};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OscSettings {
+    /// "host:port" the bundles are sent to
+    pub target: String,
+    /// Start of every address
+    pub prefix: String,
+}
+
+impl Default for OscSettings {
+    fn default() -> Self {
+        Self { target: OSC_TARGET.unwrap_or("127.0.0.1:9000").to_string(), prefix: OSC_PREFIX.to_string() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OscArg<'a> {
+    Int(i32),
+    Float(f32),
+    Str(&'a str),
+}
+
+/// `text` null-terminated and padded to a multiple of 4 bytes
+fn push_string(text: &str, out: &mut Vec<u8>) {
+    out.extend_from_slice(text.as_bytes());
+    out.push(0);
+    while !out.len().is_multiple_of(4) {
+        out.push(0);
+    }
+}
+
+/// One OSC message
+pub fn message(address: &str, args: &[OscArg]) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_string(address, &mut out);
+    let tags: String = std::iter::once(',')
+        .chain(args.iter().map(|arg| match arg {
+            OscArg::Int(_) => 'i',
+            OscArg::Float(_) => 'f',
+            OscArg::Str(_) => 's',
+        }))
+        .collect();
+    push_string(&tags, &mut out);
+    for arg in args {
+        match arg {
+            OscArg::Int(value) => out.extend_from_slice(&value.to_be_bytes()),
+            OscArg::Float(value) => out.extend_from_slice(&value.to_be_bytes()),
+            OscArg::Str(text) => push_string(text, &mut out),
+        }
+    }
+    out
+}
+
+/// `messages` in a bundle to be handled right away
+pub fn bundle(messages: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_string("#bundle", &mut out);
+    out.extend_from_slice(&1u64.to_be_bytes()); // Time tag 1 = immediately
+    for message in messages {
+        out.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        out.extend_from_slice(message);
+    }
+    out
+}
+
+/// The bundle sent for one frame
+pub fn frame_bundle(prefix: &str, state: LiveState, delta_px: Option<f32>, pose: Option<&Pose>) -> Vec<u8> {
+    let address = |name: &str| format!("{}/{}", prefix, name);
+    let mut messages = vec![
+        message(&address("state"), &[OscArg::Str(&integrations::state_key(state))]),
+        message(&address("present"), &[OscArg::Int(i32::from(pose.is_some()))]),
+    ];
+    if let Some(delta) = delta_px {
+        messages.push(message(&address("delta"), &[OscArg::Float(delta)]));
+    }
+    if let Some(pose) = pose {
+        for (name, kp) in KEYPOINT_NAMES.iter().zip(pose) {
+            messages.push(message(&address(&format!("keypoint/{}", name)), &[OscArg::Float(kp.x), OscArg::Float(kp.y), OscArg::Float(kp.score)]));
+        }
+        let all: Vec<OscArg> = pose.iter().flat_map(|kp| [OscArg::Float(kp.x), OscArg::Float(kp.y), OscArg::Float(kp.score)]).collect();
+        messages.push(message(&address("keypoints"), &all));
+    }
+    bundle(&messages)
+}
+
+/// Sends each frame to the configured target; UDP, so nobody listening costs nothing
+pub struct OscSender {
+    socket: UdpSocket,
+    prefix: String,
+}
+
+impl OscSender {
+    pub fn start(settings: &OscSettings) -> Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(&settings.target).with_context(|| format!("Invalid OSC target {}", settings.target))?;
+        Ok(Self { socket, prefix: settings.prefix.trim_end_matches('/').to_string() })
+    }
+
+    pub fn send_frame(&self, state: LiveState, delta_px: Option<f32>, pose: Option<&Pose>) {
+        if let Err(e) = self.socket.send(&frame_bundle(&self.prefix, state, delta_px, pose)) {
+            debug!("OSC send failed: {}", e);
+        }
+    }
+}
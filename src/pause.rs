@@ -0,0 +1,120 @@
+/// Pause / snooze state for detection, kept in `pause.json` so a reboot mid-pause doesn't
+/// bring the alerts back early
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::app_data;
+use crate::clock::{self, SharedClock};
+use crate::i18n::{tr, tr_args};
+
+const PAUSE_FILE: &str = "pause.json";
+
+/// A pause as written to disk; `Instant`s don't survive a restart, so timed pauses keep
+/// their wall-clock expiry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SavedPause {
+    Indefinitely,
+    Until { unix_ms: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PauseState {
+    Running,
+    PausedUntil(Instant),
+    PausedIndefinitely,
+}
+
+pub struct Pause {
+    clock: SharedClock,
+    state: PauseState,
+}
+
+impl Pause {
+    pub fn new(clock: SharedClock) -> Self {
+        Self { clock, state: PauseState::Running }
+    }
+
+    pub fn pause_for(&mut self, duration: Duration) {
+        self.state = PauseState::PausedUntil(self.clock.now() + duration);
+    }
+
+    pub fn pause_indefinitely(&mut self) {
+        self.state = PauseState::PausedIndefinitely;
+    }
+
+    pub fn resume(&mut self) {
+        self.state = PauseState::Running;
+    }
+
+    /// Returns true while paused. A timed pause switches back to running once it expires.
+    pub fn is_paused(&mut self) -> bool {
+        if let PauseState::PausedUntil(until) = self.state {
+            if self.clock.now() >= until {
+                self.state = PauseState::Running;
+            }
+        }
+        self.state != PauseState::Running
+    }
+
+    /// What to persist, given the current wall-clock time; None while running
+    pub fn to_saved(&self, now_unix_ms: u64) -> Option<SavedPause> {
+        match self.state {
+            PauseState::Running => None,
+            PauseState::PausedIndefinitely => Some(SavedPause::Indefinitely),
+            PauseState::PausedUntil(until) => {
+                let left = until.saturating_duration_since(self.clock.now());
+                Some(SavedPause::Until { unix_ms: now_unix_ms + left.as_millis() as u64 })
+            }
+        }
+    }
+
+    /// Continues a pause from the last session; one that expired meanwhile is dropped
+    pub fn restore(&mut self, saved: SavedPause, now_unix_ms: u64) {
+        self.state = match saved {
+            SavedPause::Indefinitely => PauseState::PausedIndefinitely,
+            SavedPause::Until { unix_ms } if unix_ms > now_unix_ms => {
+                PauseState::PausedUntil(self.clock.now() + Duration::from_millis(unix_ms - now_unix_ms))
+            }
+            SavedPause::Until { .. } => PauseState::Running,
+        };
+    }
+
+    /// Localized status for the tray tooltip
+    pub fn describe(&self) -> String {
+        match self.state {
+            PauseState::Running => tr("tooltip.running"),
+            PauseState::PausedIndefinitely => tr("tooltip.paused"),
+            PauseState::PausedUntil(until) => {
+                let minutes_left = until.saturating_duration_since(self.clock.now()).as_secs().div_ceil(60);
+                tr_args("tooltip.paused_for", &[("minutes", &minutes_left)])
+            }
+        }
+    }
+}
+
+impl Default for Pause {
+    fn default() -> Self {
+        Self::new(clock::system())
+    }
+}
+
+/// The pause saved by the last session, if any
+pub fn load_saved() -> Option<SavedPause> {
+    let path = app_data::file_path(PAUSE_FILE).ok()?;
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Writes `saved`, or removes the file when running
+pub fn store(saved: Option<SavedPause>) -> Result<()> {
+    let path = app_data::file_path(PAUSE_FILE)?;
+    match saved {
+        Some(saved) => app_data::write_atomic(&path, serde_json::to_string(&saved)?.as_bytes()),
+        None if path.exists() => Ok(fs::remove_file(path)?),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,487 @@
+/// Detection pipeline: camera frame -> rotation/flips -> crop -> pose model -> perspective correction ->
+/// smoothing -> posture engine, without any tray, window or overlay code
+///
+/// The app drives it step by step (`detect`, then `judge`) so it can use the frame and raw
+/// pose in between; other applications can embed posture detection with `poll` and hooks.
+
+use std::time::{Duration, Instant};
+
+use image::{Rgb, RgbImage};
+use ort::session::Session;
+
+use crate::accelerator::Accelerator;
+use crate::clock::SharedClock;
+use crate::config;
+use crate::engine::{self, EngineEvent, PostureEngine, PostureStatus};
+use crate::error::{PostureError, Result};
+use crate::exit_status::Failure;
+use crate::filter::{PoseFilter, Smoothing};
+use crate::homography::Homography;
+use crate::input::FrameSource;
+use crate::model_download;
+use crate::placement;
+use crate::pose::{Flip, Keypoint, Pose};
+use crate::pose_model::{PoseEstimator, PoseModel};
+use crate::primary::PrimarySubject;
+use crate::roi::{Region, RoiTracker};
+use crate::startup::{self, SessionMode};
+
+/// Where a frame came from and what was done to it, carried from capture to the verdict so
+/// logs, snapshots and diagnostics all describe the same frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameMeta {
+    /// The input the frame was read from, e.g. `camera:0`
+    pub source: String,
+    /// Counts the frames this pipeline has detected on, starting at 1
+    pub sequence: u64,
+    /// When the frame reached the pipeline (on the pipeline's clock)
+    pub captured_at: Instant,
+    pub rotation_degrees: u32,
+    /// Flips applied after the rotation
+    pub flip: Flip,
+    /// Mean luma of the frame, 0-255
+    pub brightness: f32,
+}
+
+/// One frame after the pose model
+pub struct Detection {
+    /// The camera frame, rotated upright and flipped as set
+    pub frame: RgbImage,
+    pub meta: FrameMeta,
+    /// The user's keypoints as the model found them; None = nobody (but maybe others) in view
+    pub pose: Option<Pose>,
+    pub inference_time: Duration,
+}
+
+/// The engine's verdict on one frame
+#[derive(Debug, Clone)]
+pub struct PostureState {
+    pub status: PostureStatus,
+    /// How far the eyes sit below the baseline, in preview pixels (positive = slouching)
+    pub delta: Option<f32>,
+    /// Overlay strength, 0.0 to 1.0
+    pub alert_intensity: f32,
+    /// Eye height before smoothing, in preview pixels
+    pub raw_eye_y: Option<f32>,
+    pub events: Vec<EngineEvent>,
+    /// The frame the verdict is for
+    pub meta: FrameMeta,
+}
+
+/// Rotates a camera frame clockwise by `degrees` (fixes sideways, upside-down or askew
+/// cameras). Quarter turns are exact; the rest of the angle is turned about the center with
+/// bilinear sampling, keeping the frame size and leaving the corners black.
+pub fn rotate(frame: RgbImage, degrees: u32) -> RgbImage {
+    let mut rotated = RgbImage::default();
+    rotate_into(&frame, degrees, &mut RgbImage::default(), &mut rotated);
+    rotated
+}
+
+/// `rotate` writing into `out`, with `turned` holding the quarter-turned frame of an askew
+/// rotation; both are only reallocated when the frame size changes
+pub fn rotate_into(frame: &RgbImage, degrees: u32, turned: &mut RgbImage, out: &mut RgbImage) {
+    let degrees = (degrees % 360) as i32;
+    let quarter = (degrees + 45) / 90 % 4 * 90;
+    // What's left after the nearest quarter turn, -45..=45
+    let rest = (degrees - quarter + 540) % 360 - 180;
+    if rest == 0 {
+        turn_into(frame, quarter, out);
+        return;
+    }
+    let source = if quarter == 0 {
+        frame
+    } else {
+        turn_into(frame, quarter, turned);
+        &*turned
+    };
+    let (width, height) = source.dimensions();
+    fit(out, width, height);
+    let (sin, cos) = (rest as f32).to_radians().sin_cos();
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    for (x, y, pixel) in out.enumerate_pixels_mut() {
+        // Each output pixel samples the source turned back the other way
+        let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+        let (sx, sy) = (cos * dx + sin * dy + cx - 0.5, -sin * dx + cos * dy + cy - 0.5);
+        *pixel = image::imageops::interpolate_bilinear(source, sx, sy).unwrap_or(Rgb([0, 0, 0]));
+    }
+}
+
+/// Turns `frame` clockwise by a multiple of 90 degrees into `out`
+fn turn_into(frame: &RgbImage, quarter: i32, out: &mut RgbImage) {
+    let (width, height) = frame.dimensions();
+    if quarter % 180 == 0 {
+        fit(out, width, height);
+    } else {
+        fit(out, height, width);
+    }
+    if quarter == 0 {
+        out.copy_from_slice(frame);
+        return;
+    }
+    for (x, y, pixel) in frame.enumerate_pixels() {
+        let (tx, ty) = match quarter {
+            90 => (height - 1 - y, x),
+            180 => (width - 1 - x, height - 1 - y),
+            _ => (y, width - 1 - x),
+        };
+        out.put_pixel(tx, ty, *pixel);
+    }
+}
+
+/// Scales `frame` into `out` at `width` x `height`, averaging the source pixels each output
+/// pixel covers (upscaling repeats them)
+pub fn resize_into(frame: &RgbImage, width: u32, height: u32, out: &mut RgbImage) {
+    fit(out, width, height);
+    if frame.dimensions() == (width, height) {
+        out.copy_from_slice(frame);
+        return;
+    }
+    scale_into(frame, (0, 0, frame.width(), frame.height()), out, (0, 0, width, height));
+}
+
+/// Scales the `crop` rectangle (left, top, width, height) of `frame` into the middle of a black
+/// `size` x `size` `out` without changing its aspect ratio, returning where it ended up
+pub fn letterbox_into(frame: &RgbImage, crop: (u32, u32, u32, u32), size: u32, out: &mut RgbImage) -> Letterbox {
+    fit(out, size, size);
+    let (_, _, width, height) = crop;
+    let longest = width.max(height).max(1) as f32;
+    let fitted = |side: u32| ((side as f32 * size as f32 / longest).round() as u32).clamp(1, size);
+    let (fitted_width, fitted_height) = (fitted(width), fitted(height));
+    let (left, top) = ((size - fitted_width) / 2, (size - fitted_height) / 2);
+    out.fill(0);
+    scale_into(frame, crop, out, (left, top, fitted_width, fitted_height));
+    let side = size as f32;
+    Letterbox {
+        left: left as f32 / side,
+        top: top as f32 / side,
+        width: fitted_width as f32 / side,
+        height: fitted_height as f32 / side,
+    }
+}
+
+/// Scales the `source` rectangle of `frame` into the `target` rectangle of `out` (both left,
+/// top, width, height), averaging the source pixels each output pixel covers
+fn scale_into(frame: &RgbImage, source: (u32, u32, u32, u32), out: &mut RgbImage, target: (u32, u32, u32, u32)) {
+    let (source_left, source_top, source_width, source_height) = source;
+    let (left, top, width, height) = target;
+    if source_width == 0 || source_height == 0 {
+        return;
+    }
+    // Source pixels under output pixel `i`, at least one
+    let span = |i: u32, from: u32, to: u32| {
+        let (i, from, to) = (i as u64, from as u64, to as u64);
+        let start = i * from / to;
+        let end = ((i + 1) * from).div_ceil(to).clamp(start + 1, from);
+        (start as u32, end as u32)
+    };
+    for y in 0..height {
+        let (y0, y1) = span(y, source_height, height);
+        let (y0, y1) = (source_top + y0, source_top + y1);
+        for x in 0..width {
+            let (x0, x1) = span(x, source_width, width);
+            let (x0, x1) = (source_left + x0, source_left + x1);
+            let mut sum = [0u32; 3];
+            for sy in y0..y1 {
+                for sx in x0..x1 {
+                    for (total, value) in sum.iter_mut().zip(frame.get_pixel(sx, sy).0) {
+                        *total += value as u32;
+                    }
+                }
+            }
+            let count = (x1 - x0) * (y1 - y0);
+            out.put_pixel(left + x, top + y, Rgb(sum.map(|total| ((total + count / 2) / count) as u8)));
+        }
+    }
+}
+
+/// Where the frame sits in a model input, as fractions of the input's sides. Keypoints the
+/// model finds are relative to the input; `unmap` makes them relative to the frame again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Letterbox {
+    pub left: f32,
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Letterbox {
+    /// The frame stretched over the whole input
+    pub const STRETCHED: Letterbox = Letterbox { left: 0.0, top: 0.0, width: 1.0, height: 1.0 };
+
+    /// `pose` moved from model input coordinates to frame coordinates
+    pub fn unmap(&self, pose: &Pose) -> Pose {
+        pose.map(|kp| Keypoint {
+            x: (kp.x - self.left) / self.width,
+            y: (kp.y - self.top) / self.height,
+            ..kp
+        })
+    }
+}
+
+/// Resizes `image` to `width` x `height` unless it already is; the contents are left stale
+fn fit(image: &mut RgbImage, width: u32, height: u32) {
+    if image.dimensions() != (width, height) {
+        *image = RgbImage::new(width, height);
+    }
+}
+
+/// The image buffers each frame goes through: the upright frame, the model input and the
+/// preview. They're kept from frame to frame and only reallocated when a size changes, so a
+/// steady stream runs without allocating images.
+#[derive(Default)]
+pub struct FramePipeline {
+    /// The quarter-turned frame of an askew rotation
+    turned: RgbImage,
+    /// The upright frame, lent out with each `Detection` until it's recycled
+    frame: Option<RgbImage>,
+    model_input: RgbImage,
+    preview: RgbImage,
+}
+
+impl FramePipeline {
+    /// Rotates and flips `raw_frame` into the frame buffer and lends it out
+    pub fn upright(&mut self, raw_frame: &RgbImage, degrees: u32, flip: Flip) -> RgbImage {
+        let mut frame = self.frame.take().unwrap_or_default();
+        rotate_into(raw_frame, degrees, &mut self.turned, &mut frame);
+        self::flip(&mut frame, flip);
+        frame
+    }
+
+    /// Takes back a frame lent out by `upright`, for the next one to be written into
+    pub fn recycle(&mut self, frame: RgbImage) {
+        self.frame = Some(frame);
+    }
+
+    /// `region` of `frame` scaled to the model's square input, letterboxed or stretched. Also
+    /// returns the region exactly as cropped (snapped to pixels) and where it sits in the input.
+    pub fn model_input(&mut self, frame: &RgbImage, region: Region, size: u32, letterbox: bool) -> (&RgbImage, Region, Letterbox) {
+        let (cropped, crop) = region.snap(frame.width(), frame.height());
+        let placed = if letterbox {
+            letterbox_into(frame, crop, size, &mut self.model_input)
+        } else {
+            fit(&mut self.model_input, size, size);
+            scale_into(frame, crop, &mut self.model_input, (0, 0, size, size));
+            Letterbox::STRETCHED
+        };
+        (&self.model_input, cropped, placed)
+    }
+
+    /// `frame` scaled for the debug window
+    pub fn preview(&mut self, frame: &RgbImage, width: u32, height: u32) -> &RgbImage {
+        resize_into(frame, width, height, &mut self.preview);
+        &self.preview
+    }
+}
+
+/// Flips a frame in place: horizontally (a mirror) and/or vertically
+pub fn flip(frame: &mut RgbImage, flip: Flip) {
+    if flip.horizontal {
+        image::imageops::flip_horizontal_in_place(frame);
+    }
+    if flip.vertical {
+        image::imageops::flip_vertical_in_place(frame);
+    }
+}
+
+/// `pose` with every keypoint scoring below `min_confidence` zeroed, so it counts as not detected
+pub fn drop_unsure(pose: &Pose, min_confidence: f32) -> Pose {
+    pose.map(|kp| if kp.score < min_confidence { Keypoint { score: 0.0, ..kp } } else { kp })
+}
+
+type EventHook = Box<dyn FnMut(&EngineEvent)>;
+
+pub struct PosturePipeline {
+    clock: SharedClock,
+    session: Session,
+    estimator: Box<dyn PoseEstimator>,
+    frames: FramePipeline,
+    source: String,
+    sequence: u64,
+    rotation_degrees: u32,
+    flip: Flip,
+    homography: Option<Homography>,
+    /// Where the next frame is cropped for the model
+    roi: RoiTracker,
+    /// Which of the people found is the user
+    primary: PrimarySubject,
+    /// Stricter keypoint cutoff than KEYPOINT_CONFIDENCE, e.g. in low light
+    min_confidence: Option<f32>,
+    filter: PoseFilter,
+    engine: PostureEngine,
+    hooks: Vec<EventHook>,
+}
+
+impl PosturePipeline {
+    /// `session` must have been built from `model`'s file
+    pub fn new(clock: SharedClock, session: Session, model: PoseModel, engine: PostureEngine, smoothing: Smoothing) -> Self {
+        Self {
+            clock,
+            session,
+            estimator: model.estimator(),
+            frames: FramePipeline::default(),
+            source: camera_source(0),
+            sequence: 0,
+            rotation_degrees: 0,
+            flip: Flip::default(),
+            homography: None,
+            roi: RoiTracker::default(),
+            primary: PrimarySubject::default(),
+            min_confidence: None,
+            filter: PoseFilter::new(smoothing),
+            engine,
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Loads `model` (next to the executable or previously downloaded) on the first of
+    /// `accelerator`'s providers that works
+    pub fn load(clock: SharedClock, model: PoseModel, accelerator: Accelerator, engine: PostureEngine, smoothing: Smoothing) -> Result<Self> {
+        let Some(path) = model_download::find(model) else {
+            return Err(PostureError::model(Failure::ModelMissing(model.path().to_string())));
+        };
+        let (session, _) = startup::load_model(&path, SessionMode::Full, accelerator)?;
+        Ok(Self::new(clock, session, model, engine, smoothing))
+    }
+
+    /// Calls `hook` for every event the engine emits
+    pub fn on_event(&mut self, hook: impl FnMut(&EngineEvent) + 'static) {
+        self.hooks.push(Box::new(hook));
+    }
+
+    pub fn engine(&self) -> &PostureEngine {
+        &self.engine
+    }
+
+    pub fn engine_mut(&mut self) -> &mut PostureEngine {
+        &mut self.engine
+    }
+
+    /// Names the input frames are read from (`camera_source` for cameras); shows up in every
+    /// frame's metadata
+    pub fn set_source(&mut self, source: impl Into<String>) {
+        self.source = source.into();
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn set_rotation(&mut self, degrees: u32) {
+        self.rotation_degrees = degrees;
+        self.roi.reset();
+        self.primary.reset();
+    }
+
+    /// Flips every frame after rotating it. Calibrations finished from now on are tagged with
+    /// it, so they can be flipped to match when it changes.
+    pub fn set_flip(&mut self, flip: Flip) {
+        self.flip = flip;
+        self.roi.reset();
+        self.primary.reset();
+    }
+
+    pub fn flip(&self) -> Flip {
+        self.flip
+    }
+
+    /// Keypoints scoring below `min_confidence` are ignored before judging; None = the usual cutoff
+    pub fn set_min_confidence(&mut self, min_confidence: Option<f32>) {
+        self.min_confidence = min_confidence;
+    }
+
+    /// Perspective correction for tilted cameras; poses are judged in the corrected view
+    pub fn set_homography(&mut self, homography: Option<Homography>) {
+        self.homography = homography;
+    }
+
+    pub fn homography(&self) -> Option<&Homography> {
+        self.homography.as_ref()
+    }
+
+    /// Rotates and flips `raw_frame` and runs the pose model on it
+    pub fn detect(&mut self, raw_frame: &RgbImage) -> Result<Detection> {
+        let captured_at = self.clock.now();
+        let frame = self.frames.upright(raw_frame, self.rotation_degrees, self.flip);
+        self.sequence += 1;
+        let meta = FrameMeta {
+            source: self.source.clone(),
+            sequence: self.sequence,
+            captured_at,
+            rotation_degrees: self.rotation_degrees,
+            flip: self.flip,
+            brightness: placement::average_brightness(&frame),
+        };
+        let region = if config::ROI_CROP { self.roi.region() } else { Region::FULL };
+        let (input, region, letterbox) = self.frames.model_input(&frame, region, self.estimator.input_size(), config::LETTERBOX_MODEL_INPUT);
+        self.estimator.preprocess(input)?;
+        let inference_start = self.clock.now();
+        let outputs = self.estimator.infer(&mut self.session)?;
+        let inference_time = self.clock.now().duration_since(inference_start);
+        let people: Vec<Pose> = self.estimator.parse(&outputs)?.iter()
+            .map(|pose| region.to_frame(&letterbox.unmap(pose)))
+            .collect();
+        let pose = self.primary.choose(&people);
+        self.roi.update(pose.as_ref());
+        Ok(Detection { frame, meta, pose, inference_time })
+    }
+
+    /// Corrects, smooths and judges a detected pose, calling the hooks for each event
+    pub fn judge(&mut self, detection: &Detection) -> PostureState {
+        let pose = match self.min_confidence {
+            Some(min_confidence) => detection.pose.as_ref().map(|pose| drop_unsure(pose, min_confidence)),
+            None => detection.pose,
+        };
+        let judged_pose = match &self.homography {
+            Some(homography) => pose.as_ref().map(|pose| homography.map_pose(pose)),
+            None => pose,
+        };
+        let raw_eye_y = judged_pose.as_ref().and_then(engine::eye_y);
+        let smoothed_pose = self.filter.apply(judged_pose.as_ref());
+
+        let mut events = self.engine.process(smoothed_pose.as_ref());
+        for event in &mut events {
+            if let EngineEvent::CalibrationFinished(calibration) = event {
+                calibration.flip = self.flip;
+            }
+        }
+        for event in &events {
+            for hook in &mut self.hooks {
+                hook(event);
+            }
+        }
+        PostureState {
+            status: self.engine.status(),
+            delta: self.engine.delta(),
+            alert_intensity: self.engine.alert_intensity(),
+            raw_eye_y,
+            events,
+            meta: detection.meta.clone(),
+        }
+    }
+
+    /// Grabs the next frame from `input` and runs it through the whole pipeline; None once a
+    /// recording has ended
+    pub fn poll(&mut self, input: &mut dyn FrameSource) -> Result<Option<PostureState>> {
+        let Some(raw_frame) = input.next_frame()? else { return Ok(None) };
+        self.source = input.name();
+        let detection = self.detect(&raw_frame)?;
+        let state = self.judge(&detection);
+        self.recycle(detection);
+        Ok(Some(state))
+    }
+
+    /// Hands a finished detection's frame back, so the next frame reuses its buffer
+    pub fn recycle(&mut self, detection: Detection) {
+        self.frames.recycle(detection.frame);
+    }
+
+    /// `frame` scaled for a preview, in a buffer kept across frames
+    pub fn preview(&mut self, frame: &RgbImage, width: u32, height: u32) -> &RgbImage {
+        self.frames.preview(frame, width, height)
+    }
+}
+
+/// Source name for camera `index`
+pub fn camera_source(index: u32) -> String {
+    format!("camera:{}", index)
+}
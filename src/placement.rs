@@ -0,0 +1,132 @@
+/// Camera placement check: scores how well the current view suits posture detection
+
+use image::RgbImage;
+
+use crate::config::{KEYPOINT_CONFIDENCE, PLACEMENT_EDGE_MARGIN, PLACEMENT_MAX_BRIGHTNESS, PLACEMENT_MIN_BRIGHTNESS};
+use crate::pose::{Pose, KEYPOINT_COUNT, LEFT_EAR, LEFT_EYE, LEFT_SHOULDER, NOSE, RIGHT_EAR, RIGHT_EYE, RIGHT_SHOULDER};
+
+const HEAD_KEYPOINTS: [usize; 5] = [NOSE, LEFT_EYE, RIGHT_EYE, LEFT_EAR, RIGHT_EAR];
+const SHOULDER_KEYPOINTS: [usize; 2] = [LEFT_SHOULDER, RIGHT_SHOULDER];
+
+// Running averages react over roughly the last second of frames
+const SMOOTHING: f32 = 0.1;
+// Average confidence at which a body part counts as fully visible
+const GOOD_CONFIDENCE: f32 = 0.6;
+
+pub struct PlacementReport {
+    /// 0 (unusable) to 100 (ideal)
+    pub score: u32,
+    /// Locale keys of the guidance, most important first (see `i18n::tr`)
+    pub hints: Vec<&'static str>,
+}
+
+/// Live placement scoring, smoothed over recent frames so guidance doesn't flicker
+pub struct PlacementCheck {
+    avg_confidence: [f32; KEYPOINT_COUNT],
+    avg_brightness: f32,
+}
+
+impl PlacementCheck {
+    pub fn new() -> Self {
+        Self {
+            avg_confidence: [0.0; KEYPOINT_COUNT],
+            avg_brightness: 128.0,
+        }
+    }
+
+    pub fn update(&mut self, pose: Option<&Pose>, frame: &RgbImage) -> PlacementReport {
+        for (i, avg) in self.avg_confidence.iter_mut().enumerate() {
+            let score = pose.map(|p| p[i].score).unwrap_or(0.0);
+            *avg += (score - *avg) * SMOOTHING;
+        }
+        self.avg_brightness += (average_brightness(frame) - self.avg_brightness) * SMOOTHING;
+
+        let mut score = 0.0;
+        let mut hints = Vec::new();
+
+        // 1. Head and shoulders visible (30 + 30)
+        let head_confidence = HEAD_KEYPOINTS.iter().map(|&i| self.avg_confidence[i]).sum::<f32>() / HEAD_KEYPOINTS.len() as f32;
+        let shoulder_confidence = SHOULDER_KEYPOINTS.iter().map(|&i| self.avg_confidence[i]).fold(1.0, f32::min);
+        score += 30.0 * (head_confidence / GOOD_CONFIDENCE).min(1.0);
+        score += 30.0 * (shoulder_confidence / GOOD_CONFIDENCE).min(1.0);
+        if head_confidence < KEYPOINT_CONFIDENCE {
+            hints.push("placement.face_not_detected");
+        }
+        if shoulder_confidence < KEYPOINT_CONFIDENCE {
+            hints.push("placement.shoulders_not_visible");
+        }
+
+        // 2. In-frame margins (20)
+        if let Some(p) = pose {
+            let mut margins_ok = 0;
+
+            let head_top = HEAD_KEYPOINTS.iter()
+                .filter(|&&i| p[i].is_confident())
+                .map(|&i| p[i].y)
+                .fold(f32::MAX, f32::min);
+            if head_top == f32::MAX || head_top >= PLACEMENT_EDGE_MARGIN * 2.0 {
+                margins_ok += 1;
+            } else {
+                hints.push("placement.head_near_top");
+            }
+
+            let shoulders_in_frame = SHOULDER_KEYPOINTS.iter()
+                .filter(|&&i| p[i].is_confident())
+                .all(|&i| p[i].y <= 1.0 - PLACEMENT_EDGE_MARGIN
+                    && p[i].x >= PLACEMENT_EDGE_MARGIN
+                    && p[i].x <= 1.0 - PLACEMENT_EDGE_MARGIN);
+            if shoulders_in_frame {
+                margins_ok += 1;
+            } else {
+                hints.push("placement.shoulders_cut_off");
+            }
+
+            let nose = p[NOSE];
+            if !nose.is_confident() || (nose.x - 0.5).abs() <= 0.25 {
+                margins_ok += 1;
+            } else if nose.x < 0.5 {
+                hints.push("placement.move_right");
+            } else {
+                hints.push("placement.move_left");
+            }
+
+            score += 20.0 * margins_ok as f32 / 3.0;
+        }
+
+        // 3. Lighting (20)
+        if self.avg_brightness < PLACEMENT_MIN_BRIGHTNESS {
+            hints.push("placement.too_dark");
+        } else if self.avg_brightness > PLACEMENT_MAX_BRIGHTNESS {
+            hints.push("placement.too_bright");
+        } else {
+            score += 20.0;
+        }
+
+        if hints.is_empty() {
+            hints.push("placement.looks_good");
+        }
+
+        PlacementReport {
+            score: score.round() as u32,
+            hints,
+        }
+    }
+}
+
+impl Default for PlacementCheck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Mean luma (0-255) of the frame, sampled on a sparse grid for speed
+pub fn average_brightness(frame: &RgbImage) -> f32 {
+    const STEP: usize = 8;
+    let (mut total, mut count) = (0.0f32, 0u32);
+    for pixel in frame.pixels().step_by(STEP) {
+        let [r, g, b] = pixel.0;
+        total += 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+        count += 1;
+    }
+    if count == 0 { 0.0 } else { total / count as f32 }
+}
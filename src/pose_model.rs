@@ -0,0 +1,177 @@
+/// Pose model variants and the `PoseEstimator` trait each one's input and output handling
+/// implements, so models with other tensor and keypoint layouts can be added next to MoveNet
+
+use anyhow::anyhow;
+use image::RgbImage;
+use ort::memory::Allocator;
+use ort::session::{Session, SessionOutputs};
+use ort::value::{DynValue, Tensor};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PostureError, Result};
+use crate::pose::{self, Pose};
+use crate::yolo_pose::YoloPose;
+
+/// Element type of a model's image input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputType {
+    /// RGB values 0-255 as integers
+    Int32,
+    /// RGB values as floats
+    Float32,
+}
+
+/// Gets a `Pose` out of one kind of ONNX model. Implementations map the model's own keypoints
+/// onto the 17 COCO keypoints `Pose` holds, in normalized frame coordinates.
+pub trait PoseEstimator {
+    /// Width and height of the square image `preprocess` takes
+    fn input_size(&self) -> u32;
+
+    /// Writes a camera frame, already scaled to `input_size`, into the model's input tensor,
+    /// which is kept across frames
+    fn preprocess(&mut self, frame: &RgbImage) -> Result<()>;
+
+    /// The input tensor `preprocess` last wrote; `None` before the first frame
+    fn input(&self) -> Option<&DynValue>;
+
+    /// Runs the model on the prepared input
+    fn infer<'s>(&self, session: &'s mut Session) -> Result<SessionOutputs<'s>> {
+        let input = self.input().ok_or_else(|| PostureError::model(anyhow!("No frame was preprocessed")))?;
+        session.run(ort::inputs![input]).map_err(PostureError::model)
+    }
+
+    /// Reads everyone the model found from a finished run; single-person models find at most one
+    fn parse(&self, outputs: &SessionOutputs) -> Result<Vec<Pose>>;
+}
+
+/// Pose model to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoseModel {
+    /// MoveNet SinglePose, 192px input; several times faster, slightly less precise
+    Lightning,
+    /// MoveNet SinglePose, 256px input
+    Thunder,
+    /// YOLOv8n-pose, 640px input; the most accurate, best on a GPU. Not bundled: export
+    /// `yolov8n-pose.onnx` with Ultralytics and put it next to the executable.
+    Yolov8Pose,
+}
+
+impl PoseModel {
+    pub fn path(self) -> &'static str {
+        match self {
+            PoseModel::Lightning => "movenet_singlepose_lightning.onnx",
+            PoseModel::Thunder => "movenet_singlepose_thunder.onnx",
+            PoseModel::Yolov8Pose => "yolov8n-pose.onnx",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PoseModel::Lightning => "MoveNet Lightning",
+            PoseModel::Thunder => "MoveNet Thunder",
+            PoseModel::Yolov8Pose => "YOLOv8-pose",
+        }
+    }
+
+    /// Width and height of the square input image
+    pub fn input_size(self) -> u32 {
+        match self {
+            PoseModel::Lightning => 192,
+            PoseModel::Thunder => 256,
+            PoseModel::Yolov8Pose => 640,
+        }
+    }
+
+    pub fn input_type(self) -> InputType {
+        match self {
+            PoseModel::Lightning | PoseModel::Thunder => InputType::Int32,
+            PoseModel::Yolov8Pose => InputType::Float32,
+        }
+    }
+
+    /// The input and output handling for this model
+    pub fn estimator(self) -> Box<dyn PoseEstimator> {
+        match self {
+            PoseModel::Lightning | PoseModel::Thunder => Box::new(MoveNet::new(self.input_size(), self.input_type())),
+            PoseModel::Yolov8Pose => Box::new(YoloPose::new(self.input_size())),
+        }
+    }
+}
+
+/// Allocates a model input tensor of the given shape, filled in place on later frames
+pub(crate) fn allocate_input(input_type: InputType, shape: [i64; 4]) -> Result<DynValue> {
+    let allocator = Allocator::default();
+    Ok(match input_type {
+        InputType::Int32 => Tensor::<i32>::new(&allocator, shape).map_err(PostureError::model)?.into_dyn(),
+        InputType::Float32 => Tensor::<f32>::new(&allocator, shape).map_err(PostureError::model)?.into_dyn(),
+    })
+}
+
+/// Converts packed RGB bytes into an interleaved (height, width, channel) tensor buffer of the
+/// same length in one pass over both slices
+pub fn fill_interleaved<T: Copy>(rgb: &[u8], out: &mut [T], convert: impl Fn(u8) -> T) {
+    for (value, &byte) in out.iter_mut().zip(rgb) {
+        *value = convert(byte);
+    }
+}
+
+/// Converts packed RGB bytes into a planar (channel, height, width) tensor buffer: all red
+/// values, then all green, then all blue
+pub fn fill_planar<T: Copy>(rgb: &[u8], out: &mut [T], convert: impl Fn(u8) -> T) {
+    let plane = rgb.len() / 3;
+    let (red, rest) = out.split_at_mut(plane);
+    let (green, blue) = rest.split_at_mut(plane);
+    for (((pixel, r), g), b) in rgb.chunks_exact(3).zip(red).zip(green).zip(blue) {
+        *r = convert(pixel[0]);
+        *g = convert(pixel[1]);
+        *b = convert(pixel[2]);
+    }
+}
+
+/// MoveNet SinglePose: a [1, size, size, 3] RGB input and a [1, 1, 17, 3] (y, x, score) output
+pub struct MoveNet {
+    pub input_size: u32,
+    pub input_type: InputType,
+    input: Option<DynValue>,
+}
+
+impl MoveNet {
+    pub fn new(input_size: u32, input_type: InputType) -> Self {
+        MoveNet { input_size, input_type, input: None }
+    }
+}
+
+impl PoseEstimator for MoveNet {
+    fn input_size(&self) -> u32 {
+        self.input_size
+    }
+
+    fn preprocess(&mut self, frame: &RgbImage) -> Result<()> {
+        let size = self.input_size;
+        let input = match &mut self.input {
+            Some(input) => input,
+            None => self.input.insert(allocate_input(self.input_type, [1, size as i64, size as i64, 3])?),
+        };
+        match self.input_type {
+            InputType::Int32 => {
+                let (_, data) = input.try_extract_tensor_mut::<i32>().map_err(PostureError::model)?;
+                fill_interleaved(frame.as_raw(), data, i32::from);
+            }
+            InputType::Float32 => {
+                let (_, data) = input.try_extract_tensor_mut::<f32>().map_err(PostureError::model)?;
+                fill_interleaved(frame.as_raw(), data, f32::from);
+            }
+        }
+        Ok(())
+    }
+
+    fn input(&self) -> Option<&DynValue> {
+        self.input.as_ref()
+    }
+
+    fn parse(&self, outputs: &SessionOutputs) -> Result<Vec<Pose>> {
+        let (_, data) = outputs["output_0"].try_extract_tensor::<f32>().map_err(PostureError::model)?;
+        Ok(pose::parse_movenet_output(data).into_iter().collect())
+    }
+}
@@ -0,0 +1,88 @@
+/// Primary-subject tracking: which of the people the model found is the user
+///
+/// The user is whoever is closest to where the user was last seen; with nobody to follow yet,
+/// the biggest and most central person. Someone elsewhere in the frame who is much smaller than
+/// the user (a passerby behind the desk) is ignored until the user has been missing for
+/// PRIMARY_FORGET_FRAMES frames.
+
+use crate::config::{KEYPOINT_CONFIDENCE, PRIMARY_FORGET_FRAMES, PRIMARY_MAX_JUMP, PRIMARY_MIN_SIZE_RATIO};
+use crate::pose::Pose;
+
+/// Where a person is and how big they appear, from their confident keypoints
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Subject {
+    center: (f32, f32),
+    /// Longer side of the keypoints' bounding box, as a share of the frame
+    size: f32,
+}
+
+impl Subject {
+    fn of(pose: &Pose) -> Option<Subject> {
+        let mut confident = pose.iter().filter(|kp| kp.score >= KEYPOINT_CONFIDENCE).peekable();
+        confident.peek()?;
+        let (mut left, mut top, mut right, mut bottom) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+        for kp in confident {
+            (left, top, right, bottom) = (left.min(kp.x), top.min(kp.y), right.max(kp.x), bottom.max(kp.y));
+        }
+        Some(Subject { center: ((left + right) / 2.0, (top + bottom) / 2.0), size: (right - left).max(bottom - top) })
+    }
+
+    fn distance(&self, other: (f32, f32)) -> f32 {
+        (self.center.0 - other.0).hypot(self.center.1 - other.1)
+    }
+
+    /// Bigger and closer to the middle of the frame ranks higher
+    fn prominence(&self) -> f32 {
+        self.size * (1.0 - self.distance((0.5, 0.5))).max(0.0)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PrimarySubject {
+    /// The user as last seen
+    last: Option<Subject>,
+    /// Frames in a row where only other people were found
+    misses: u32,
+}
+
+impl PrimarySubject {
+    /// The user among `people` (in frame coordinates). None when everyone found is someone
+    /// else; people without any confident keypoint are passed on as they are, so poor tracking
+    /// still shows up as such.
+    pub fn choose(&mut self, people: &[Pose]) -> Option<Pose> {
+        let subjects: Vec<(usize, Subject)> = people.iter().enumerate()
+            .filter_map(|(i, pose)| Subject::of(pose).map(|subject| (i, subject)))
+            .collect();
+        if subjects.is_empty() {
+            return people.first().copied();
+        }
+        let by_prominence = |a: &&(usize, Subject), b: &&(usize, Subject)| a.1.prominence().total_cmp(&b.1.prominence());
+        let chosen = match self.last {
+            Some(last) => subjects.iter()
+                .filter(|(_, subject)| subject.distance(last.center) <= PRIMARY_MAX_JUMP)
+                .min_by(|a, b| a.1.distance(last.center).total_cmp(&b.1.distance(last.center)))
+                .or_else(|| subjects.iter().filter(|(_, subject)| subject.size >= last.size * PRIMARY_MIN_SIZE_RATIO).max_by(by_prominence)),
+            None => subjects.iter().max_by(by_prominence),
+        };
+        match chosen {
+            Some(&(i, subject)) => {
+                self.last = Some(subject);
+                self.misses = 0;
+                Some(people[i])
+            }
+            None => {
+                self.misses += 1;
+                if self.misses >= PRIMARY_FORGET_FRAMES {
+                    self.reset();
+                }
+                None
+            }
+        }
+    }
+
+    /// Forgets the user, e.g. after the picture was turned
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.misses = 0;
+    }
+}
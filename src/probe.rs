@@ -0,0 +1,200 @@
+/// `--list-cameras` and `--probe`: what a setup looks like to the app, for triaging "it doesn't
+/// start" reports. The probe enumerates the cameras with their formats, loads the pose model
+/// and runs it once on a test image, and prints everything as one JSON report.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use image::{Rgb, RgbImage};
+use serde::Serialize;
+
+use crate::camera;
+use crate::clock;
+use crate::engine::PostureEngine;
+use crate::exit_status::{EXIT_CAMERA_MISSING, EXIT_ERROR, EXIT_MODEL_MISSING, EXIT_OK};
+use crate::model_download;
+use crate::pipeline::PosturePipeline;
+use crate::pose_model::PoseModel;
+use crate::settings::Settings;
+use crate::startup;
+
+// Stands in for a test image: inference runs, though nobody is expected in it
+const BLANK_FRAME_SIZE: (u32, u32) = (640, 480);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FormatReport {
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    /// e.g. "MJPEG", "YUYV", "NV12"
+    pub format: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CameraReport {
+    /// What `--camera` takes; None if the backend doesn't number it
+    pub index: Option<u32>,
+    pub name: String,
+    /// One of the VIRTUAL_CAMERAS, e.g. OBS
+    pub is_virtual: bool,
+    pub formats: Vec<FormatReport>,
+    /// Why the formats couldn't be read, e.g. another app holds the camera
+    pub error: Option<String>,
+}
+
+impl CameraReport {
+    /// `index: name` and one indented line per format
+    pub fn describe(&self) -> String {
+        let index = self.index.map_or("-".to_string(), |index| index.to_string());
+        let mut text = format!("{}: {}", index, self.name);
+        for format in &self.formats {
+            text.push_str(&format!("\n    {}x{} @ {} fps {}", format.width, format.height, format.fps, format.format));
+        }
+        if let Some(error) = &self.error {
+            text.push_str(&format!("\n    (formats unavailable: {})", error));
+        }
+        text
+    }
+}
+
+/// Every camera found, with the formats it offers
+pub fn cameras() -> Vec<CameraReport> {
+    camera::list_cameras().iter()
+        .map(|info| {
+            let index = camera::camera_index(info);
+            let formats = index.context("the camera has no index").and_then(|index| Ok(camera::formats(index)?));
+            let (formats, error) = match formats {
+                Ok(mut formats) => {
+                    formats.sort_by_key(|format| (std::cmp::Reverse(format.width() * format.height()), std::cmp::Reverse(format.frame_rate())));
+                    let formats = formats.iter()
+                        .map(|format| FormatReport {
+                            width: format.width(),
+                            height: format.height(),
+                            fps: format.frame_rate(),
+                            format: format.format().to_string(),
+                        })
+                        .collect();
+                    (formats, None)
+                }
+                Err(e) => (Vec::new(), Some(format!("{:#}", e))),
+            };
+            CameraReport { index, name: info.human_name(), is_virtual: camera::is_virtual(&info.human_name()), formats, error }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelReport {
+    pub model: PoseModel,
+    /// The file found (or given with `--model`); None if there is none
+    pub path: Option<String>,
+    pub loaded: bool,
+    /// Where the session runs, e.g. "DirectML" or "CPU"
+    pub accelerator: Option<&'static str>,
+    pub load_ms: Option<u64>,
+    /// Why the ONNX runtime couldn't load the model
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InferenceReport {
+    /// The test image; None = a blank frame
+    pub image: Option<String>,
+    pub inference_ms: Option<u64>,
+    pub person_found: bool,
+    pub confident_keypoints: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProbeReport {
+    pub version: &'static str,
+    pub cameras: Vec<CameraReport>,
+    pub model: ModelReport,
+    /// None when the model didn't load
+    pub inference: Option<InferenceReport>,
+    /// What the probe exits with: the startup exit codes, 1 if the model or inference failed
+    pub exit_code: u8,
+}
+
+impl ProbeReport {
+    pub fn new(cameras: Vec<CameraReport>, model: ModelReport, inference: Option<InferenceReport>) -> Self {
+        let exit_code = if model.path.is_none() {
+            EXIT_MODEL_MISSING
+        } else if !model.loaded || inference.as_ref().is_none_or(|inference| inference.error.is_some()) {
+            EXIT_ERROR
+        } else if cameras.is_empty() {
+            EXIT_CAMERA_MISSING
+        } else {
+            EXIT_OK
+        };
+        Self { version: env!("CARGO_PKG_VERSION"), cameras, model, inference, exit_code }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+/// Runs the probe with `settings`' model and provider (or `model_file`) on `image` (or a
+/// blank frame). Nothing is downloaded or saved.
+pub fn run(settings: &Settings, model_file: Option<&Path>, image: Option<&Path>) -> ProbeReport {
+    let cameras = cameras();
+    let model = settings.model();
+    let path = model_file.map(Path::to_path_buf).or_else(|| model_download::find(model)).filter(|path| path.is_file());
+    let mut report = ModelReport {
+        model,
+        path: path.as_ref().map(|path| path.display().to_string()),
+        loaded: false,
+        accelerator: None,
+        load_ms: None,
+        error: None,
+    };
+    let Some(path) = path else {
+        return ProbeReport::new(cameras, report, None);
+    };
+
+    let started = Instant::now();
+    let session = match startup::load_model(&path, settings.session_mode(), settings.execution_provider) {
+        Ok((session, accelerator)) => {
+            report.loaded = true;
+            report.accelerator = Some(accelerator.label());
+            report.load_ms = Some(millis(started.elapsed()));
+            session
+        }
+        Err(e) => {
+            report.error = Some(e.to_string());
+            return ProbeReport::new(cameras, report, None);
+        }
+    };
+
+    let clock = clock::system();
+    let engine = PostureEngine::new(0, 0, None, Arc::clone(&clock));
+    let mut pipeline = PosturePipeline::new(clock, session, model, engine, settings.keypoint_smoothing);
+    let mut inference = InferenceReport {
+        image: image.map(|image| image.display().to_string()),
+        inference_ms: None,
+        person_found: false,
+        confident_keypoints: 0,
+        error: None,
+    };
+    let frame = match image {
+        Some(image) => image::open(image).map(|image| image.to_rgb8()).with_context(|| format!("Failed to read {}", image.display())),
+        None => Ok(RgbImage::from_pixel(BLANK_FRAME_SIZE.0, BLANK_FRAME_SIZE.1, Rgb([128, 128, 128]))),
+    };
+    match frame.and_then(|frame| Ok(pipeline.detect(&frame)?)) {
+        Ok(detection) => {
+            inference.inference_ms = Some(millis(detection.inference_time));
+            inference.person_found = detection.pose.is_some();
+            inference.confident_keypoints = detection.pose.map_or(0, |pose| pose.iter().filter(|kp| kp.is_confident()).count());
+        }
+        Err(e) => inference.error = Some(format!("{:#}", e)),
+    }
+    ProbeReport::new(cameras, report, Some(inference))
+}
@@ -0,0 +1,86 @@
+/// Process helpers: enumeration (Windows Toolhelp API), the foreground app and memory use
+
+use windows::core::PWSTR;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+};
+use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+use windows::Win32::System::Threading::{
+    GetCurrentProcess, OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+/// Executable names (e.g. "obs64.exe") of all running processes
+pub fn running_process_names() -> Vec<String> {
+    let mut names = Vec::new();
+    unsafe {
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+            return names;
+        };
+
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+
+        if Process32FirstW(snapshot, &mut entry).is_ok() {
+            loop {
+                let len = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+                names.push(String::from_utf16_lossy(&entry.szExeFile[..len]));
+                if Process32NextW(snapshot, &mut entry).is_err() {
+                    break;
+                }
+            }
+        }
+
+        let _ = CloseHandle(snapshot);
+    }
+    names
+}
+
+/// Returns the first running process whose name matches one in `candidates` (case-insensitive)
+pub fn find_running<'a>(candidates: &[&'a str]) -> Option<&'a str> {
+    let running = running_process_names();
+    candidates.iter()
+        .find(|candidate| running.iter().any(|name| name.eq_ignore_ascii_case(candidate)))
+        .copied()
+}
+
+/// Executable name of the app owning the foreground window, e.g. "vlc.exe"
+pub fn foreground_process_name() -> Option<String> {
+    unsafe {
+        let window = GetForegroundWindow();
+        if window.0 == 0 {
+            return None;
+        }
+        let mut process_id = 0;
+        GetWindowThreadProcessId(window, Some(&mut process_id));
+        if process_id == 0 {
+            return None;
+        }
+
+        // Limited access is enough for the image name, even for elevated processes
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+        let mut path = [0u16; 1024];
+        let mut len = path.len() as u32;
+        let queried = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, PWSTR(path.as_mut_ptr()), &mut len);
+        let _ = CloseHandle(process);
+        queried.ok()?;
+
+        let path = String::from_utf16_lossy(&path[..len as usize]);
+        path.rsplit('\\').next().map(str::to_string)
+    }
+}
+
+/// Resident memory (working set) of this process in megabytes
+pub fn current_memory_mb() -> Option<f32> {
+    unsafe {
+        let mut counters = PROCESS_MEMORY_COUNTERS {
+            cb: std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+            ..Default::default()
+        };
+        GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, counters.cb).ok()?;
+        Some(counters.WorkingSetSize as f32 / (1024.0 * 1024.0))
+    }
+}
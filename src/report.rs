@@ -0,0 +1,347 @@
+/// Posture reports: an HTML summary of today and the last 7 days, built from the history database
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use windows::core::{w, HSTRING, PCWSTR};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Shell::ShellExecuteW;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+use crate::alert_browser;
+use crate::app_data;
+use crate::config::{SITTING_GAP_SECS, STREAK_MAX_GAP_SECS, TRENDS_WEEKS};
+use crate::feedback::{self, RuleStats};
+use crate::goals::Suggestion;
+use crate::history::{AlertRecord, HistoryEvent, HistoryReader, SecondRow};
+use crate::i18n::{tr, tr_args};
+use crate::local_time::{self, LocalTime};
+use crate::settings::Settings;
+use crate::standing::StanceDay;
+use crate::streaks::{self, DayScore};
+use crate::tasks::CancelToken;
+use crate::trends::{self, WeekTrend};
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// Aggregates for one reporting period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeriodSummary {
+    pub monitored_secs: u64,
+    pub slouching_secs: u64,
+    /// Seconds monitored, by local hour of day
+    pub monitored_by_hour: [u64; 24],
+    /// Seconds spent slouching, by local hour of day
+    pub slouching_by_hour: [u64; 24],
+    /// Number of times the overlay was triggered
+    pub triggers: u32,
+    pub longest_good_streak_secs: u64,
+}
+
+impl PeriodSummary {
+    /// Summarizes `rows` (oldest first). A second counts as slouching when most of its
+    /// frames were bad; `utc_offset_secs` places each second in its local hour.
+    pub fn from_rows(rows: &[SecondRow], triggers: u32, utc_offset_secs: i64) -> Self {
+        let mut summary = Self {
+            monitored_secs: 0,
+            slouching_secs: 0,
+            monitored_by_hour: [0; 24],
+            slouching_by_hour: [0; 24],
+            triggers,
+            longest_good_streak_secs: 0,
+        };
+
+        let mut streak: Option<(i64, i64)> = None; // (first, last) good second
+        for row in rows.iter().filter(|row| row.frames > 0) {
+            let hour = ((row.second + utc_offset_secs).rem_euclid(DAY_SECS) / 3600) as usize;
+            let slouching = row.bad_frames * 2 > row.frames;
+
+            summary.monitored_secs += 1;
+            summary.monitored_by_hour[hour] += 1;
+            if slouching {
+                summary.slouching_secs += 1;
+                summary.slouching_by_hour[hour] += 1;
+            }
+
+            streak = match streak {
+                _ if row.bad_frames > 0 => None,
+                Some((first, last)) if row.second - last <= STREAK_MAX_GAP_SECS => Some((first, row.second)),
+                _ => Some((row.second, row.second)),
+            };
+            if let Some((first, last)) = streak {
+                summary.longest_good_streak_secs = summary.longest_good_streak_secs.max((last - first + 1) as u64);
+            }
+        }
+        summary
+    }
+}
+
+/// "45 s" below a minute, otherwise like `format_duration`
+pub fn format_short_duration(secs: u64) -> String {
+    if secs < 60 {
+        tr_args("report.seconds", &[("seconds", &secs)])
+    } else {
+        format_duration(secs)
+    }
+}
+
+/// "1 h 5 min" or "42 min"
+pub fn format_duration(secs: u64) -> String {
+    let minutes = secs / 60;
+    if minutes >= 60 {
+        tr_args("report.hours_minutes", &[("hours", &(minutes / 60)), ("minutes", &(minutes % 60))])
+    } else {
+        tr_args("report.minutes", &[("minutes", &minutes)])
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn render_period(html: &mut String, title: &str, summary: &PeriodSummary) {
+    let _ = writeln!(html, "<h2>{}</h2>", escape(title));
+    if summary.monitored_secs == 0 {
+        let _ = writeln!(html, "<p class=\"empty\">{}</p>", escape(&tr("report.no_data")));
+        return;
+    }
+
+    let slouching_percent = summary.slouching_secs * 100 / summary.monitored_secs;
+    let _ = writeln!(html, "<table class=\"totals\">");
+    for (label, value) in [
+        (tr("report.monitored"), format_duration(summary.monitored_secs)),
+        (tr("report.slouching"), format!("{} ({}%)", format_duration(summary.slouching_secs), slouching_percent)),
+        (tr("report.triggers"), summary.triggers.to_string()),
+        (tr("report.longest_streak"), format_duration(summary.longest_good_streak_secs)),
+    ] {
+        let _ = writeln!(html, "<tr><th>{}</th><td>{}</td></tr>", escape(&label), escape(&value));
+    }
+    let _ = writeln!(html, "</table>");
+
+    // One bar per monitored hour; the width is the share of that hour spent slouching
+    let _ = writeln!(html, "<h3>{}</h3>\n<table class=\"hours\">", escape(&tr("report.by_hour")));
+    for hour in 0..24 {
+        let monitored = summary.monitored_by_hour[hour];
+        if monitored == 0 {
+            continue;
+        }
+        let slouching = summary.slouching_by_hour[hour];
+        let _ = writeln!(
+            html,
+            "<tr><th>{:02}:00</th><td><div class=\"bar\" style=\"width:{}%\"></div></td><td>{}</td></tr>",
+            hour,
+            slouching * 100 / monitored,
+            escape(&format_duration(slouching)),
+        );
+    }
+    let _ = writeln!(html, "</table>");
+}
+
+/// Per-rule alerts with the share marked as wrong, and the threshold changes that suggests
+fn render_rules(html: &mut String, rules: &[RuleStats], suggestions: &[Suggestion]) {
+    if rules.is_empty() {
+        return;
+    }
+    let _ = writeln!(html, "<h3>{}</h3>\n<table class=\"totals\">", escape(&tr("report.by_rule")));
+    let _ = writeln!(
+        html,
+        "<tr><th></th><th>{}</th><th>{}</th></tr>",
+        escape(&tr("report.rule_alerts")),
+        escape(&tr("report.rule_false_positives")),
+    );
+    for stats in rules {
+        let _ = writeln!(
+            html,
+            "<tr><th>{}</th><td>{}</td><td>{} ({}%)</td></tr>",
+            escape(&alert_browser::rule_label(&stats.rule)),
+            stats.alerts,
+            stats.false_positives,
+            stats.false_positive_percent(),
+        );
+    }
+    let _ = writeln!(html, "</table>");
+
+    if !suggestions.is_empty() {
+        let _ = writeln!(html, "<p>{}</p>\n<ul>", escape(&tr("review.suggestions")));
+        for suggestion in suggestions {
+            let _ = writeln!(html, "<li>{}</li>", escape(&suggestion.describe()));
+        }
+        let _ = writeln!(html, "</ul>");
+    }
+}
+
+/// One bar per week for each habit measure; each chart is scaled to its own longest week
+fn render_trends(html: &mut String, trends: &[WeekTrend]) {
+    if trends.iter().all(|week| week.sittings == 0) {
+        return;
+    }
+    let _ = writeln!(
+        html,
+        "<h3>{}</h3>\n<p class=\"generated\">{}</p>",
+        escape(&tr_args("report.trends", &[("weeks", &trends.len())])),
+        escape(&tr("report.trends_hint")),
+    );
+    let to_first_alert: Vec<Option<u64>> = trends.iter().map(|week| week.avg_secs_to_first_alert).collect();
+    let ignoring: Vec<Option<u64>> = trends.iter().map(|week| week.avg_secs_ignoring_alerts).collect();
+    for (title, class, values) in [("report.to_first_alert", "good", to_first_alert), ("report.ignoring_alerts", "bad", ignoring)] {
+        let longest = values.iter().flatten().max().copied().unwrap_or(0).max(1);
+        let _ = writeln!(html, "<h4>{}</h4>\n<table class=\"hours\">", escape(&tr(title)));
+        for (week, value) in trends.iter().zip(values) {
+            let (width, label) = match value {
+                Some(secs) => (secs * 100 / longest, format_short_duration(secs)),
+                None => (0, "-".to_string()),
+            };
+            let _ = writeln!(
+                html,
+                "<tr><th>{}</th><td><div class=\"bar {}\" style=\"width:{}%\"></div></td><td>{}</td></tr>",
+                escape(&week.week_start),
+                class,
+                width,
+                escape(&label),
+            );
+        }
+        let _ = writeln!(html, "</table>");
+    }
+}
+
+/// One bar per monitored day for its score, with the daily goals reached
+fn render_scores(html: &mut String, scores: &[DayScore]) {
+    if scores.is_empty() {
+        return;
+    }
+    let _ = writeln!(html, "<h3>{}</h3>\n<table class=\"hours\">", escape(&tr("report.score_by_day")));
+    for day in scores {
+        let mut label = day.score.to_string();
+        if day.goals_reached > 0 {
+            label = format!("{} ({})", label, tr_args("report.goals_reached", &[("count", &day.goals_reached)]));
+        }
+        let _ = writeln!(
+            html,
+            "<tr><th>{}</th><td><div class=\"bar good\" style=\"width:{}%\"></div></td><td>{}</td></tr>",
+            escape(&LocalTime::from_unix_secs(day.local_day * DAY_SECS, 0).date_string()),
+            day.score,
+            escape(&label),
+        );
+    }
+    let _ = writeln!(html, "</table>");
+}
+
+/// One bar per day for the share of the time in view spent standing
+fn render_stance(html: &mut String, days: &[StanceDay]) {
+    if days.is_empty() {
+        return;
+    }
+    let _ = writeln!(html, "<h3>{}</h3>\n<table class=\"hours\">", escape(&tr("report.stance_by_day")));
+    for day in days {
+        let total = (day.sitting_secs + day.standing_secs).max(1);
+        let label = format!(
+            "{} {}, {} {}",
+            tr("report.sitting"),
+            format_duration(day.sitting_secs),
+            tr("report.standing"),
+            format_duration(day.standing_secs),
+        );
+        let _ = writeln!(
+            html,
+            "<tr><th>{}</th><td><div class=\"bar good\" style=\"width:{}%\"></div></td><td>{}</td></tr>",
+            escape(&LocalTime::from_unix_secs(day.local_day * DAY_SECS, 0).date_string()),
+            day.standing_secs * 100 / total,
+            escape(&label),
+        );
+    }
+    let _ = writeln!(html, "</table>");
+}
+
+/// Builds the complete report page; `rules`, `suggestions`, `scores` and `stance` cover the
+/// last 7 days, `trends` the last TRENDS_WEEKS weeks
+pub fn render_html(
+    today: &PeriodSummary,
+    week: &PeriodSummary,
+    rules: &[RuleStats],
+    suggestions: &[Suggestion],
+    trends: &[WeekTrend],
+    scores: &[DayScore],
+    stance: &[StanceDay],
+) -> String {
+    let now = LocalTime::now();
+    let generated = format!("{} {:02}:{:02}", now.date_string(), now.hour, now.minute);
+
+    let mut html = String::new();
+    let _ = writeln!(
+        html,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title>\n<style>\n\
+         body {{ font-family: 'Segoe UI', sans-serif; margin: 2em auto; max-width: 640px; color: #222; }}\n\
+         th {{ text-align: left; font-weight: normal; color: #555; padding-right: 1em; }}\n\
+         .hours td:nth-child(2) {{ width: 60%; background: #eee; }}\n\
+         .bar {{ height: 12px; background: #dc3c3c; }}\n\
+         .bar.good {{ background: #2a9d3a; }}\n\
+         .empty, .generated {{ color: #777; }}\n\
+         </style></head><body>\n<h1>{title}</h1>",
+        title = escape(&tr("report.title")),
+    );
+    render_period(&mut html, &tr("report.today"), today);
+    render_period(&mut html, &tr("report.last_7_days"), week);
+    render_scores(&mut html, scores);
+    render_stance(&mut html, stance);
+    render_rules(&mut html, rules, suggestions);
+    render_trends(&mut html, trends);
+    let _ = writeln!(
+        html,
+        "<p class=\"generated\">{}</p>\n</body></html>",
+        escape(&tr_args("report.generated", &[("time", &generated)])),
+    );
+    html
+}
+
+/// Writes `reports/posture-report.html` for today and the last 7 days and returns its path.
+/// Runs on a worker thread; stops between steps once `cancel` fires.
+pub fn generate(history: &HistoryReader, settings: &Settings, cancel: &CancelToken) -> Result<PathBuf> {
+    let offset = local_time::utc_offset_secs();
+    let now_secs = (local_time::unix_millis() / 1000) as i64;
+    let local_secs = now_secs + offset;
+    let today_start_ms = (local_secs - local_secs.rem_euclid(DAY_SECS) - offset) * 1000;
+    let week_start_ms = today_start_ms - 6 * DAY_SECS * 1000;
+
+    let rows = history.seconds_since(week_start_ms)?;
+    cancel.check()?;
+    let split = rows.partition_point(|row| row.second * 1000 < today_start_ms);
+    let today = PeriodSummary::from_rows(
+        &rows[split..],
+        history.count_events_since(&HistoryEvent::AlertStarted, today_start_ms)?,
+        offset,
+    );
+    let week = PeriodSummary::from_rows(
+        &rows,
+        history.count_events_since(&HistoryEvent::AlertStarted, week_start_ms)?,
+        offset,
+    );
+    let trends_start_ms = today_start_ms - (TRENDS_WEEKS as i64 * 7) * DAY_SECS * 1000;
+    let alerts = history.alerts_since(trends_start_ms)?;
+    let recent: Vec<AlertRecord> = alerts.iter().filter(|alert| alert.started_ms >= week_start_ms).cloned().collect();
+    let rules = feedback::per_rule(&recent);
+    let suggestions = feedback::suggest(&rules, settings);
+    cancel.check()?;
+    let scores = streaks::daily_scores(&rows, &history.achievements_since(week_start_ms)?, offset);
+    let stance = history.stance_by_day(week_start_ms, offset)?;
+    let sittings = history.sittings_since(trends_start_ms, SITTING_GAP_SECS)?;
+    let trends = trends::weekly(&sittings, &alerts, now_secs, offset);
+    cancel.check()?;
+
+    let dir = app_data::dir()?.join("reports");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("posture-report.html");
+    app_data::write_atomic(&path, render_html(&today, &week, &rules, &suggestions, &trends, &scores, &stance).as_bytes())?;
+    Ok(path)
+}
+
+/// Opens the report in the default browser
+pub fn open(path: &Path) -> Result<()> {
+    let file = HSTRING::from(path);
+    let result = unsafe { ShellExecuteW(HWND::default(), w!("open"), &file, PCWSTR::null(), PCWSTR::null(), SW_SHOWNORMAL) };
+    // Values up to 32 are error codes
+    if result.0 <= 32 {
+        bail!("ShellExecute failed with code {}", result.0);
+    }
+    Ok(())
+}
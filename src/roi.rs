@@ -0,0 +1,89 @@
+/// Region-of-interest cropping: the model runs on the part of the frame around where the person
+/// was last found, so someone small in a wide-angle view fills more of its input, and on the
+/// whole frame again as soon as they're lost
+
+use crate::config::{KEYPOINT_CONFIDENCE, ROI_MARGIN, ROI_MIN_KEYPOINTS, ROI_MIN_SIZE};
+use crate::pose::{Keypoint, Pose};
+
+/// Part of a frame, as fractions of its sides
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Region {
+    pub left: f32,
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Region {
+    pub const FULL: Region = Region { left: 0.0, top: 0.0, width: 1.0, height: 1.0 };
+
+    /// The box around `pose`'s confident keypoints, grown by ROI_MARGIN on each side and to at
+    /// least ROI_MIN_SIZE, kept inside the frame. None with fewer than ROI_MIN_KEYPOINTS.
+    pub fn around(pose: &Pose) -> Option<Region> {
+        let confident = || pose.iter().filter(|kp| kp.score >= KEYPOINT_CONFIDENCE);
+        if confident().count() < ROI_MIN_KEYPOINTS {
+            return None;
+        }
+        let bounds = |coordinate: fn(&Keypoint) -> f32| {
+            confident().map(coordinate).fold((f32::MAX, f32::MIN), |(low, high), value| (low.min(value), high.max(value)))
+        };
+        let grow = |(low, high): (f32, f32)| {
+            let size = ((high - low) * (1.0 + 2.0 * ROI_MARGIN)).clamp(ROI_MIN_SIZE, 1.0);
+            let start = ((low + high) / 2.0 - size / 2.0).clamp(0.0, 1.0 - size);
+            (start, size)
+        };
+        let (left, width) = grow(bounds(|kp| kp.x));
+        let (top, height) = grow(bounds(|kp| kp.y));
+        Some(Region { left, top, width, height })
+    }
+
+    /// The pixels (left, top, width, height) this covers in a `width` x `height` frame, at
+    /// least one, and the region those pixels cover exactly
+    pub fn snap(&self, width: u32, height: u32) -> (Region, (u32, u32, u32, u32)) {
+        if width == 0 || height == 0 {
+            return (Region::FULL, (0, 0, width, height));
+        }
+        let span = |start: f32, size: f32, side: u32| {
+            let from = ((start * side as f32).floor() as u32).min(side - 1);
+            let to = (((start + size) * side as f32).ceil() as u32).clamp(from + 1, side);
+            (from, to - from)
+        };
+        let (x, w) = span(self.left, self.width, width);
+        let (y, h) = span(self.top, self.height, height);
+        let (width, height) = (width as f32, height as f32);
+        let region = Region { left: x as f32 / width, top: y as f32 / height, width: w as f32 / width, height: h as f32 / height };
+        (region, (x, y, w, h))
+    }
+
+    /// `pose` moved from coordinates within this region to frame coordinates
+    pub fn to_frame(&self, pose: &Pose) -> Pose {
+        pose.map(|kp| Keypoint {
+            x: self.left + kp.x * self.width,
+            y: self.top + kp.y * self.height,
+            ..kp
+        })
+    }
+}
+
+/// Follows the person from frame to frame
+#[derive(Debug, Default)]
+pub struct RoiTracker {
+    region: Option<Region>,
+}
+
+impl RoiTracker {
+    /// Where to crop the next frame; the whole frame while nobody is tracked
+    pub fn region(&self) -> Region {
+        self.region.unwrap_or(Region::FULL)
+    }
+
+    /// Feed each frame's pose, in frame coordinates
+    pub fn update(&mut self, pose: Option<&Pose>) {
+        self.region = pose.and_then(Region::around);
+    }
+
+    /// Back to the whole frame, e.g. after the picture was turned
+    pub fn reset(&mut self) {
+        self.region = None;
+    }
+}
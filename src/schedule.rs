@@ -0,0 +1,150 @@
+/// Work schedule: detection and the overlay only run on the configured days and hours, e.g.
+/// "Mon-Fri 09:00-18:00; Sat 10:00-13:00". Outside them the app idles with the camera released
+/// until the next window starts or it's forced on from the tray.
+
+use serde::{Deserialize, Serialize};
+
+use crate::local_time::LocalTime;
+
+const DAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// One window: the same hours on each of its days. A window ending before it starts runs past
+/// midnight into the next day (Fri 22:00-02:00 covers Saturday night until 2am).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleWindow {
+    /// Indexed by weekday, 0 = Monday
+    pub days: [bool; 7],
+    /// Minutes after midnight, start inclusive and end exclusive
+    pub start_minute: u32,
+    pub end_minute: u32,
+}
+
+impl ScheduleWindow {
+    fn contains(&self, weekday: usize, minute: u32) -> bool {
+        if self.start_minute <= self.end_minute {
+            return self.days[weekday] && (self.start_minute..self.end_minute).contains(&minute);
+        }
+        (self.days[weekday] && minute >= self.start_minute) || (self.days[(weekday + 6) % 7] && minute < self.end_minute)
+    }
+
+    fn describe(&self) -> String {
+        // Runs of consecutive days as ranges: "Mon-Fri", "Mon,Wed-Thu"
+        let mut runs = Vec::new();
+        let mut day = 0;
+        while day < 7 {
+            if !self.days[day] {
+                day += 1;
+                continue;
+            }
+            let start = day;
+            while day + 1 < 7 && self.days[day + 1] {
+                day += 1;
+            }
+            runs.push(if start == day { DAY_NAMES[day].to_string() } else { format!("{}-{}", DAY_NAMES[start], DAY_NAMES[day]) });
+            day += 1;
+        }
+        format!("{} {}-{}", runs.join(","), format_minute(self.start_minute), format_minute(self.end_minute))
+    }
+}
+
+fn format_minute(minute: u32) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+fn parse_minute(text: &str) -> Result<u32, String> {
+    let invalid = || format!("Invalid time \"{}\" (expected HH:MM)", text);
+    let (hours, minutes) = text.split_once(':').ok_or_else(invalid)?;
+    let (hours, minutes): (u32, u32) = (hours.parse().map_err(|_| invalid())?, minutes.parse().map_err(|_| invalid())?);
+    // 24:00 ends a window at midnight
+    if minutes >= 60 || hours * 60 + minutes > 24 * 60 {
+        return Err(invalid());
+    }
+    Ok(hours * 60 + minutes)
+}
+
+fn parse_day(text: &str) -> Result<usize, String> {
+    DAY_NAMES.iter()
+        .position(|name| name.eq_ignore_ascii_case(text))
+        .ok_or_else(|| format!("Unknown day \"{}\" (expected Mon, Tue, ... Sun)", text))
+}
+
+fn parse_window(text: &str) -> Result<ScheduleWindow, String> {
+    let (days_text, hours_text) = text.trim().split_once(' ')
+        .ok_or_else(|| format!("\"{}\" should look like \"Mon-Fri 09:00-18:00\"", text.trim()))?;
+
+    let mut days = [false; 7];
+    for part in days_text.split(',') {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) = (parse_day(first)?, parse_day(last)?);
+                // Sat-Mon wraps over the weekend
+                let mut day = first;
+                loop {
+                    days[day] = true;
+                    if day == last {
+                        break;
+                    }
+                    day = (day + 1) % 7;
+                }
+            }
+            None => days[parse_day(part)?] = true,
+        }
+    }
+
+    let (start, end) = hours_text.trim().split_once('-')
+        .ok_or_else(|| format!("\"{}\" should be a time range like 09:00-18:00", hours_text.trim()))?;
+    let (start_minute, end_minute) = (parse_minute(start.trim())?, parse_minute(end.trim())?);
+    if start_minute == end_minute {
+        return Err(format!("\"{}\" is an empty time range", hours_text.trim()));
+    }
+    Ok(ScheduleWindow { days, start_minute: start_minute % (24 * 60), end_minute })
+}
+
+/// The configured windows; detection runs while any of them covers the current time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct WorkSchedule {
+    pub windows: Vec<ScheduleWindow>,
+}
+
+impl WorkSchedule {
+    /// Parses windows separated by ";", each days then hours: "Mon-Fri 09:00-18:00; Sat 10:00-13:00"
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let windows = text.split(';')
+            .filter(|window| !window.trim().is_empty())
+            .map(parse_window)
+            .collect::<Result<Vec<_>, _>>()?;
+        if windows.is_empty() {
+            return Err("The work schedule has no windows".to_string());
+        }
+        Ok(Self { windows })
+    }
+
+    pub fn describe(&self) -> String {
+        self.windows.iter().map(ScheduleWindow::describe).collect::<Vec<_>>().join("; ")
+    }
+
+    pub fn contains(&self, now: &LocalTime) -> bool {
+        let (weekday, minute) = (now.weekday() as usize, now.hour as u32 * 60 + now.minute as u32);
+        self.windows.iter().any(|window| window.contains(weekday, minute))
+    }
+}
+
+impl TryFrom<String> for WorkSchedule {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, String> {
+        Self::parse(&text)
+    }
+}
+
+impl From<WorkSchedule> for String {
+    fn from(schedule: WorkSchedule) -> Self {
+        schedule.describe()
+    }
+}
+
+/// Whether detection should run at `now`; no schedule means always
+pub fn is_active(schedule: Option<&WorkSchedule>, now: &LocalTime) -> bool {
+    schedule.is_none_or(|schedule| schedule.contains(now))
+}
@@ -0,0 +1,16 @@
+/// Screen-capture detection so the overlay doesn't ruin recordings and demos. A capture counts
+/// while an app is capturing through the Windows Graphics Capture API, or while a recorder that
+/// only runs during a recording is open. Studio apps like OBS stay open to serve their virtual
+/// camera, so their process alone doesn't count.
+
+use crate::config::SCREEN_RECORDER_PROCESSES;
+use crate::device_usage;
+use crate::processes;
+
+/// Name of the app capturing the screen, if any
+pub fn active_recorder() -> Option<String> {
+    device_usage::screen_capture_users()
+        .into_iter()
+        .next()
+        .or_else(|| processes::find_running(SCREEN_RECORDER_PROCESSES).map(str::to_string))
+}
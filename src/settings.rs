@@ -0,0 +1,509 @@
+/// User settings stored as JSON in the app-data directory
+///
+/// `config.rs` holds the built-in defaults; `settings.json` overrides them per user.
+/// Writes go through a temp file + rename, and every file that parses is copied to
+/// `settings.backup.json` so a damaged file can be recovered on the next start.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::accelerator::Accelerator;
+use crate::active_hours::ActiveHours;
+use crate::alert_sinks::{AlertProfiles, AlertSink};
+use crate::app_data;
+use crate::app_exemptions::AppExemption;
+use crate::blur_overlay::{Exclusions, OverlayEffect, ScreenRect};
+use crate::breaks::BreakSettings;
+use crate::camera::{CaptureOptions, ControlSetting};
+use crate::config::{
+    ACTIVE_HOURS, ACTIVE_HOURS_AUTO_APPLY, ALERT_PRIORITY, ALERT_SNAPSHOTS_ENABLED, EPISODE_FRAMES_AFTER, EPISODE_FRAMES_BEFORE, API_ENABLED, APP_EXEMPTIONS, BLUR_OVERLAY_ENABLED,
+    CALL_APPS, CAMERA_FLIP_VERTICAL, CAMERA_MIRROR, CAMERA_ROTATION_DEGREES, DASHBOARD_ENABLED, DEBOUNCE_MS, EXECUTION_PROVIDER, FADE_MS,
+    FALLBACK_CAMERAS, FULLSCREEN_SUPPRESSES_OVERLAY, FULLSCREEN_TOAST_INSTEAD, GOOD_POSTURE_DEVIATION, GRACE_MS,
+    GOOD_POSTURE_RECOVERY, HEAD_TILT_DEGREES, IGNORE_MOVEMENT, KEYPOINT_SMOOTHING, LOW_LIGHT_RAISE_CONFIDENCE, LOG_LEVEL, LOW_MEMORY_MODE,
+    LOW_MEMORY_MODEL, LOW_MEMORY_PREVIEW_INTERVAL, LOW_MEMORY_RESOLUTION, MODEL_DOWNLOAD_URL, NECK_ANGLE_DEGREES, OVERLAY_EFFECT,
+    OVERLAY_EXCLUDE_TASKBAR, OVERLAY_FOREGROUND_MONITOR_ONLY, PAUSE_DURING_CALLS, POSE_MODEL, PREVIEW_FRAME_INTERVAL, RECORDING_RETENTION_DAYS, SHOULDER_HUNCH_PERCENT,
+    SOUND_ALERTS_ENABLED, TOAST_NOTIFICATIONS_ENABLED, TOO_CLOSE_ALERT, TOO_CLOSE_PERCENT, MQTT_BROKER, OSC_TARGET, WEBHOOK_URL, WORK_SCHEDULE,
+};
+use crate::escalation::EscalationLadder;
+use crate::filter::Smoothing;
+use crate::focus_assist::FocusAssistRules;
+use crate::goals::Goals;
+use crate::integrations::MqttSettings;
+use crate::logging::LogLevel;
+use crate::osc::OscSettings;
+use crate::pose::Flip;
+use crate::pose_model::PoseModel;
+use crate::startup::SessionMode;
+use crate::schedule::WorkSchedule;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+// Ranges offered in the settings window; edits outside them are clamped
+pub const SENSITIVITY_RANGE_PX: RangeInclusive<f32> = 2.0..=60.0;
+pub const NECK_ANGLE_RANGE_DEGREES: RangeInclusive<f32> = 10.0..=60.0;
+pub const SHOULDER_HUNCH_RANGE_PERCENT: RangeInclusive<f32> = 10.0..=60.0;
+pub const HEAD_TILT_RANGE_DEGREES: RangeInclusive<f32> = 5.0..=45.0;
+pub const DEBOUNCE_RANGE_MS: RangeInclusive<u64> = 0..=5_000;
+pub const FADE_RANGE_MS: RangeInclusive<u64> = 100..=2_000;
+pub const ROTATIONS: [u32; 4] = [0, 90, 180, 270]; // Offered in the window; settings.json takes any angle
+pub const RESOLUTIONS: [Option<(u32, u32)>; 4] = [None, Some((640, 480)), Some((1280, 720)), Some((1920, 1080))];
+pub const FRAME_RATES: [u32; 3] = [15, 30, 60];
+pub const EXPOSURE_RANGE: RangeInclusive<i64> = -11..=-2;
+pub const SLOUCH_GOAL_RANGE_PERCENT: RangeInclusive<u32> = 1..=100;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Camera picked from the tray; None = choose by CAMERA_NAME / CAMERA_INDEX
+    pub camera_index: Option<u32>,
+    /// Cameras (name substrings) to switch to while the chosen one is in use by another app
+    pub fallback_cameras: Vec<String>,
+    pub blur_overlay_enabled: bool,
+    pub overlay_effect: OverlayEffect,
+    /// Blur only the monitor showing the foreground window instead of every monitor
+    pub overlay_foreground_monitor_only: bool,
+    /// Screen areas (virtual-screen pixels) the overlay never covers, e.g. a stock ticker
+    pub overlay_exclusions: Vec<ScreenRect>,
+    /// Leave the taskbar on every monitor unblurred
+    pub overlay_exclude_taskbar: bool,
+    /// Apps that keep the overlay clear, or pause detection, while they're in the foreground
+    pub app_exemptions: Vec<AppExemption>,
+    /// Pause detection and release the camera while a call app uses the webcam or microphone
+    pub pause_during_calls: bool,
+    /// Conferencing apps: executable names, or package names for Store apps
+    pub call_apps: Vec<String>,
+    /// Keep the overlay clear while the foreground app runs full screen
+    pub fullscreen_suppresses_overlay: bool,
+    /// Show a toast instead when an alert starts during full screen
+    pub fullscreen_toast: bool,
+    /// How far alerting is turned down at each Focus Assist level
+    pub focus_assist: FocusAssistRules,
+    pub toast_notifications_enabled: bool,
+    pub sound_alerts_enabled: bool,
+    /// Alert channels, highest priority first; a busy channel silences the ones after it
+    pub alert_priority: Vec<AlertSink>,
+    /// Per-channel sensitivity/debounce overrides, e.g. a sound that fires before the overlay
+    pub alert_profiles: AlertProfiles,
+    /// Toast, then dim, then blur (then block input) the longer a slouch lasts; off by default
+    pub escalation: EscalationLadder,
+    /// Eye and stretch break reminders, apart from the posture alerts; read at startup
+    pub breaks: BreakSettings,
+    /// How far (pixels) the eyes may drop below the baseline before it counts as slouching
+    pub sensitivity_px: f32,
+    /// How far (pixels) below the baseline the eyes must get back within to end a slouch;
+    /// anything above `sensitivity_px` acts like `sensitivity_px` (no hysteresis)
+    pub recovery_px: f32,
+    /// How far (degrees) the neck may lean from vertical before it counts as craning; None = check off
+    pub neck_angle_degrees: Option<f32>,
+    /// How much (percent) the ear-to-shoulder gap may shrink from the calibrated one before the
+    /// shoulders count as hunched; None = check off
+    pub shoulder_hunch_percent: Option<f32>,
+    /// How far (degrees) the line between the eyes may roll from horizontal before the head
+    /// counts as tilted; None = check off
+    pub head_tilt_degrees: Option<f32>,
+    /// How much (percent) larger than at calibration the eye spacing may look before the user
+    /// counts as too close to the screen; None = warning off
+    pub too_close_percent: Option<f32>,
+    /// The channel that warns about sitting too close
+    pub too_close_alert: AlertSink,
+    /// How long slouching must last before the overlay triggers
+    pub debounce_ms: u64,
+    /// After sitting back up from an alert, how long brief dips are let go before one can alert again
+    pub grace_ms: u64,
+    /// Don't count slouching during large, fast movements and for a moment after
+    pub ignore_movement: bool,
+    /// Duration of a full overlay fade in/out
+    pub fade_ms: u64,
+    /// Clockwise, any whole angle below 360
+    pub camera_rotation_degrees: u32,
+    /// Frame size asked of the camera; None = whatever gives the highest frame rate
+    pub camera_resolution: Option<(u32, u32)>,
+    pub camera_fps: u32,
+    /// Flip frames horizontally
+    pub camera_mirror: bool,
+    /// Flip frames upside down
+    pub camera_flip_vertical: bool,
+    /// Automatic, locked once settled, or a fixed value in driver units
+    pub camera_exposure: ControlSetting,
+    pub keypoint_smoothing: Smoothing,
+    /// Require surer keypoints while the scene is too dark
+    pub low_light_raise_confidence: bool,
+    /// Which pose model runs unless `low_memory` is on; read at startup
+    pub pose_model: PoseModel,
+    /// Low-memory preset: MoveNet Lightning, small camera frames, a lean model session, a throttled
+    /// preview and no frames recorded around alerts. The model and session change at the next start.
+    pub low_memory: bool,
+    /// Where the pose model runs; read at startup
+    pub execution_provider: Accelerator,
+    /// Where a missing model file is downloaded from; "{file}" is replaced by its name
+    pub model_download_url: String,
+    /// SHA-256 digests by model file name for files MODEL_SHA256 doesn't pin (e.g. on a mirror)
+    pub model_sha256: BTreeMap<String, String>,
+    /// Targets for the weekly review
+    pub goals: Goals,
+    /// Daily window in which detection runs; None = always
+    pub active_hours: Option<ActiveHours>,
+    /// Apply a learned active-hours window without asking
+    pub auto_apply_active_hours: bool,
+    /// Days and hours detection runs, e.g. "Mon-Fri 09:00-18:00"; None = always
+    pub work_schedule: Option<WorkSchedule>,
+    /// Serve the web dashboard; read at startup
+    pub dashboard_enabled: bool,
+    /// Serve the local API; read at startup
+    pub api_enabled: bool,
+    /// URL posture events are POSTed to as JSON; None = off. Read at startup.
+    pub webhook_url: Option<String>,
+    /// MQTT broker posture state is published to; None = off. Read at startup.
+    pub mqtt: Option<MqttSettings>,
+    /// Where each frame's keypoints are sent over OSC; None = off. Read at startup.
+    pub osc: Option<OscSettings>,
+    /// Save camera frames of each alert (a snapshot and the frames around it); off by default
+    pub alert_recording_enabled: bool,
+    /// Days saved alert frames are kept
+    pub recording_retention_days: u32,
+    /// Least severe events written to the log
+    pub log_level: LogLevel,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            camera_index: None,
+            fallback_cameras: FALLBACK_CAMERAS.iter().map(|name| name.to_string()).collect(),
+            blur_overlay_enabled: BLUR_OVERLAY_ENABLED,
+            overlay_effect: OVERLAY_EFFECT,
+            overlay_foreground_monitor_only: OVERLAY_FOREGROUND_MONITOR_ONLY,
+            overlay_exclusions: Vec::new(),
+            overlay_exclude_taskbar: OVERLAY_EXCLUDE_TASKBAR,
+            app_exemptions: APP_EXEMPTIONS.iter().map(|&(process, exempt)| AppExemption::new(process, exempt)).collect(),
+            pause_during_calls: PAUSE_DURING_CALLS,
+            call_apps: CALL_APPS.iter().map(|app| app.to_string()).collect(),
+            fullscreen_suppresses_overlay: FULLSCREEN_SUPPRESSES_OVERLAY,
+            fullscreen_toast: FULLSCREEN_TOAST_INSTEAD,
+            focus_assist: FocusAssistRules::default(),
+            toast_notifications_enabled: TOAST_NOTIFICATIONS_ENABLED,
+            sound_alerts_enabled: SOUND_ALERTS_ENABLED,
+            alert_priority: ALERT_PRIORITY.to_vec(),
+            alert_profiles: AlertProfiles::default(),
+            escalation: EscalationLadder::default(),
+            breaks: BreakSettings::default(),
+            sensitivity_px: GOOD_POSTURE_DEVIATION,
+            recovery_px: GOOD_POSTURE_RECOVERY,
+            neck_angle_degrees: NECK_ANGLE_DEGREES,
+            shoulder_hunch_percent: SHOULDER_HUNCH_PERCENT,
+            head_tilt_degrees: HEAD_TILT_DEGREES,
+            too_close_percent: TOO_CLOSE_PERCENT,
+            too_close_alert: TOO_CLOSE_ALERT,
+            debounce_ms: DEBOUNCE_MS,
+            grace_ms: GRACE_MS,
+            ignore_movement: IGNORE_MOVEMENT,
+            fade_ms: FADE_MS,
+            camera_rotation_degrees: CAMERA_ROTATION_DEGREES,
+            camera_resolution: CaptureOptions::default().resolution,
+            camera_fps: CaptureOptions::default().fps,
+            camera_mirror: CAMERA_MIRROR,
+            camera_flip_vertical: CAMERA_FLIP_VERTICAL,
+            camera_exposure: CaptureOptions::default().exposure,
+            keypoint_smoothing: KEYPOINT_SMOOTHING,
+            low_light_raise_confidence: LOW_LIGHT_RAISE_CONFIDENCE,
+            pose_model: POSE_MODEL,
+            low_memory: LOW_MEMORY_MODE,
+            execution_provider: EXECUTION_PROVIDER,
+            model_download_url: MODEL_DOWNLOAD_URL.to_string(),
+            model_sha256: BTreeMap::new(),
+            goals: Goals::default(),
+            active_hours: ACTIVE_HOURS.map(|(start_hour, end_hour)| ActiveHours { start_hour, end_hour }),
+            auto_apply_active_hours: ACTIVE_HOURS_AUTO_APPLY,
+            work_schedule: WORK_SCHEDULE.and_then(|schedule| WorkSchedule::parse(schedule).ok()),
+            dashboard_enabled: DASHBOARD_ENABLED,
+            api_enabled: API_ENABLED,
+            webhook_url: WEBHOOK_URL.map(str::to_string),
+            mqtt: MQTT_BROKER.map(|broker| MqttSettings { broker: broker.to_string(), ..MqttSettings::default() }),
+            osc: OSC_TARGET.map(|target| OscSettings { target: target.to_string(), ..OscSettings::default() }),
+            alert_recording_enabled: ALERT_SNAPSHOTS_ENABLED,
+            recording_retention_days: RECORDING_RETENTION_DAYS,
+            log_level: LOG_LEVEL,
+        }
+    }
+}
+
+/// One edit made in the settings window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingChange {
+    Sensitivity(f32),
+    /// Limit for the neck-angle check in degrees (turns it on)
+    NeckAngle(f32),
+    /// Limit for the shoulder hunch check in percent (turns it on)
+    ShoulderHunch(f32),
+    /// Limit for the head tilt check in degrees (turns it on)
+    HeadTilt(f32),
+    DebounceMs(u64),
+    FadeMs(u64),
+    Camera(u32),
+    Rotation(u32),
+    Resolution(Option<(u32, u32)>),
+    FrameRate(u32),
+    Mirror(bool),
+    FlipVertical(bool),
+    LowMemory(bool),
+    Exposure(ControlSetting),
+    OverlayEffect(OverlayEffect),
+    SlouchGoalPercent(u32),
+    ActiveHours(Option<ActiveHours>),
+}
+
+/// How the settings were obtained when the main file couldn't be used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recovery {
+    /// `settings.json` was damaged; the last-known-good backup was restored
+    FromBackup,
+    /// Neither file could be read; defaults are in use and the damaged file was set aside
+    Defaults,
+}
+
+pub struct Loaded {
+    pub settings: Settings,
+    pub recovery: Option<Recovery>,
+}
+
+/// Values given on the command line. They apply to this run only: saving writes the file's
+/// values back for them, unless they were changed again meanwhile (e.g. a camera picked in the tray).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Overrides {
+    pub camera_index: Option<u32>,
+    pub overlay_off: bool,
+    pub log_level: Option<LogLevel>,
+}
+
+impl Overrides {
+    pub fn apply(&self, settings: &mut Settings) {
+        if let Some(index) = self.camera_index {
+            settings.camera_index = Some(index);
+        }
+        if self.overlay_off {
+            settings.blur_overlay_enabled = false;
+        }
+        if let Some(level) = self.log_level {
+            settings.log_level = level;
+        }
+    }
+
+    /// `settings` as they should be written over `file`
+    pub fn to_save(&self, settings: &Settings, file: &Settings) -> Settings {
+        let mut saved = settings.clone();
+        if self.camera_index.is_some() && settings.camera_index == self.camera_index {
+            saved.camera_index = file.camera_index;
+        }
+        if self.overlay_off && !settings.blur_overlay_enabled {
+            saved.blur_overlay_enabled = file.blur_overlay_enabled;
+        }
+        if self.log_level == Some(settings.log_level) {
+            saved.log_level = file.log_level;
+        }
+        saved
+    }
+}
+
+/// The file the settings came from (`--config` or `settings.json`) and the command-line
+/// overrides, which stay out of it
+#[derive(Debug, Clone)]
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    /// What the file holds now
+    file: Settings,
+    overrides: Overrides,
+}
+
+impl SettingsStore {
+    /// Loads `path`, or `settings.json` in the app-data directory without one, and returns the
+    /// settings to run with: the file's with `overrides` applied
+    pub fn load(path: Option<PathBuf>, overrides: Overrides) -> (Self, Loaded) {
+        let Loaded { settings: file, recovery } = match &path {
+            Some(path) => Settings::load_from(path),
+            None => Settings::load(),
+        };
+        let mut settings = file.clone();
+        overrides.apply(&mut settings);
+        (Self { path, file, overrides }, Loaded { settings, recovery })
+    }
+
+    /// A store for `settings` that came from elsewhere (safe mode's defaults), saving to `path`
+    pub fn new(path: Option<PathBuf>, file: Settings, overrides: Overrides) -> Self {
+        Self { path, file, overrides }
+    }
+
+    pub fn save(&mut self, settings: &Settings) -> Result<()> {
+        let saved = self.overrides.to_save(settings, &self.file);
+        match &self.path {
+            Some(path) => saved.save_to(path)?,
+            None => saved.save()?,
+        }
+        self.file = saved;
+        Ok(())
+    }
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    path.with_extension("backup.json")
+}
+
+fn corrupt_path(path: &Path) -> PathBuf {
+    path.with_extension("corrupt.json")
+}
+
+fn read(path: &Path) -> Result<Settings> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+impl Settings {
+    pub fn path() -> Result<PathBuf> {
+        app_data::file_path(SETTINGS_FILE)
+    }
+
+    /// Loads `settings.json` from the app-data directory (see `load_from`)
+    pub fn load() -> Loaded {
+        match Self::path() {
+            Ok(path) => Self::load_from(&path),
+            Err(e) => {
+                warn!("Settings unavailable: {}", e);
+                Loaded { settings: Self::default(), recovery: None }
+            }
+        }
+    }
+
+    /// A missing file means first start (defaults, no recovery). A damaged file is
+    /// replaced by the backup, or set aside as `*.corrupt.json` if that fails too.
+    pub fn load_from(path: &Path) -> Loaded {
+        if !path.exists() {
+            return Loaded { settings: Self::default(), recovery: None };
+        }
+
+        match read(path) {
+            Ok(settings) => {
+                // Remember this file as last-known-good
+                if let Err(e) = fs::copy(path, backup_path(path)) {
+                    warn!("Failed to back up settings: {}", e);
+                }
+                Loaded { settings, recovery: None }
+            }
+            Err(e) => {
+                warn!("Settings file is damaged: {}", e);
+                match read(&backup_path(path)) {
+                    Ok(settings) => {
+                        if let Err(e) = settings.save_to(path) {
+                            warn!("Failed to restore settings from backup: {}", e);
+                        }
+                        Loaded { settings, recovery: Some(Recovery::FromBackup) }
+                    }
+                    Err(_) => {
+                        if let Err(e) = fs::rename(path, corrupt_path(path)) {
+                            warn!("Failed to set damaged settings aside: {}", e);
+                        }
+                        Loaded { settings: Self::default(), recovery: Some(Recovery::Defaults) }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Defaults with the overlay off, used in safe mode after repeated crashes
+    pub fn safe_mode() -> Self {
+        Self { blur_overlay_enabled: false, overlay_effect: OverlayEffect::Dim, ..Self::default() }
+    }
+
+    /// Flips applied to frames after the rotation
+    pub fn camera_flip(&self) -> Flip {
+        Flip { horizontal: self.camera_mirror, vertical: self.camera_flip_vertical }
+    }
+
+    /// How the camera stream is opened, as `camera::open` takes it
+    pub fn capture_options(&self) -> CaptureOptions {
+        let resolution = if self.low_memory { Some(LOW_MEMORY_RESOLUTION) } else { self.camera_resolution };
+        CaptureOptions { resolution, fps: self.camera_fps, exposure: self.camera_exposure }
+    }
+
+    /// The pose model to load
+    pub fn model(&self) -> PoseModel {
+        if self.low_memory { LOW_MEMORY_MODEL } else { self.pose_model }
+    }
+
+    /// How the model session is built (outside safe mode)
+    pub fn session_mode(&self) -> SessionMode {
+        if self.low_memory { SessionMode::LowMemory } else { SessionMode::Full }
+    }
+
+    /// The debug window redraws every this many inferred frames
+    pub fn preview_frame_interval(&self) -> u64 {
+        if self.low_memory { LOW_MEMORY_PREVIEW_INTERVAL } else { PREVIEW_FRAME_INTERVAL }
+    }
+
+    /// Frames kept from before an alert and recorded after it starts
+    pub fn episode_frames(&self) -> (usize, usize) {
+        if self.low_memory { (0, 0) } else { (EPISODE_FRAMES_BEFORE, EPISODE_FRAMES_AFTER) }
+    }
+
+    /// The overlay exclusions, as `BlurOverlay::set_exclusions` takes them
+    pub fn overlay_exclusions(&self) -> Exclusions {
+        Exclusions { rects: self.overlay_exclusions.clone(), taskbar: self.overlay_exclude_taskbar }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::path()?)
+    }
+
+    /// Applies one edit, clamped to the offered range. Returns false if nothing changed.
+    pub fn apply(&mut self, change: SettingChange) -> bool {
+        let before = self.clone();
+        match change {
+            SettingChange::Sensitivity(px) => {
+                self.sensitivity_px = px.clamp(*SENSITIVITY_RANGE_PX.start(), *SENSITIVITY_RANGE_PX.end());
+            }
+            SettingChange::NeckAngle(degrees) => {
+                self.neck_angle_degrees = Some(degrees.clamp(*NECK_ANGLE_RANGE_DEGREES.start(), *NECK_ANGLE_RANGE_DEGREES.end()));
+            }
+            SettingChange::ShoulderHunch(percent) => {
+                let range = SHOULDER_HUNCH_RANGE_PERCENT;
+                self.shoulder_hunch_percent = Some(percent.clamp(*range.start(), *range.end()));
+            }
+            SettingChange::HeadTilt(degrees) => {
+                self.head_tilt_degrees = Some(degrees.clamp(*HEAD_TILT_RANGE_DEGREES.start(), *HEAD_TILT_RANGE_DEGREES.end()));
+            }
+            SettingChange::DebounceMs(ms) => self.debounce_ms = ms.clamp(*DEBOUNCE_RANGE_MS.start(), *DEBOUNCE_RANGE_MS.end()),
+            SettingChange::FadeMs(ms) => self.fade_ms = ms.clamp(*FADE_RANGE_MS.start(), *FADE_RANGE_MS.end()),
+            SettingChange::Camera(index) => self.camera_index = Some(index),
+            SettingChange::Rotation(degrees) if degrees < 360 => self.camera_rotation_degrees = degrees,
+            SettingChange::Rotation(_) => {}
+            SettingChange::Resolution(size) if RESOLUTIONS.contains(&size) => self.camera_resolution = size,
+            SettingChange::Resolution(_) => {}
+            SettingChange::FrameRate(fps) if FRAME_RATES.contains(&fps) => self.camera_fps = fps,
+            SettingChange::FrameRate(_) => {}
+            SettingChange::Mirror(mirror) => self.camera_mirror = mirror,
+            SettingChange::FlipVertical(flip) => self.camera_flip_vertical = flip,
+            SettingChange::LowMemory(low_memory) => self.low_memory = low_memory,
+            SettingChange::Exposure(ControlSetting::Fixed(value)) => {
+                self.camera_exposure = ControlSetting::Fixed(value.clamp(*EXPOSURE_RANGE.start(), *EXPOSURE_RANGE.end()));
+            }
+            SettingChange::Exposure(exposure) => self.camera_exposure = exposure,
+            SettingChange::OverlayEffect(effect) => self.overlay_effect = effect,
+            SettingChange::SlouchGoalPercent(percent) => {
+                self.goals.max_slouch_percent = percent.clamp(*SLOUCH_GOAL_RANGE_PERCENT.start(), *SLOUCH_GOAL_RANGE_PERCENT.end());
+            }
+            SettingChange::ActiveHours(hours) => self.active_hours = hours,
+        }
+        *self != before
+    }
+
+    /// Atomically replaces the settings file and refreshes the backup
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        app_data::write_atomic(path, json.as_bytes())?;
+        app_data::write_atomic(&backup_path(path), json.as_bytes())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,365 @@
+/// Settings window opened from the tray (raw Win32 controls)
+///
+/// Every edit is reported through `poll_changes` as soon as it is made; the main loop applies
+/// it live and saves `settings.json`. Messages arrive through the debug window's `pump`.
+
+use std::cell::RefCell;
+
+use anyhow::{bail, Result};
+use windows::core::{w, HSTRING, PCWSTR};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{GetStockObject, GetSysColorBrush, COLOR_BTNFACE, DEFAULT_GUI_FONT};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Controls::{
+    InitCommonControlsEx, BST_CHECKED, ICC_BAR_CLASSES, INITCOMMONCONTROLSEX, TBM_SETPOS, TBM_SETRANGEMAX, TBM_SETRANGEMIN,
+    TBS_HORZ, TBS_NOTICKS, TRACKBAR_CLASSW,
+};
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+use crate::blur_overlay::OverlayEffect;
+use crate::camera::ControlSetting;
+use crate::i18n::{tr, tr_args};
+use crate::settings::{
+    SettingChange, Settings, DEBOUNCE_RANGE_MS, EXPOSURE_RANGE, FADE_RANGE_MS, FRAME_RATES, RESOLUTIONS, ROTATIONS,
+    SENSITIVITY_RANGE_PX,
+};
+
+const CLASS_NAME: PCWSTR = w!("PostureSettingsClass");
+const TBM_GETPOS: u32 = WM_USER;
+
+const ID_SENSITIVITY: i32 = 101;
+const ID_DEBOUNCE: i32 = 102;
+const ID_FADE: i32 = 103;
+const ID_CAMERA: i32 = 104;
+const ID_ROTATION: i32 = 105;
+const ID_EFFECT: i32 = 106;
+const ID_CLOSE: i32 = 107;
+const ID_RESOLUTION: i32 = 108;
+const ID_FRAME_RATE: i32 = 109;
+const ID_EXPOSURE: i32 = 110;
+const ID_MIRROR: i32 = 111;
+const ID_FLIP_VERTICAL: i32 = 112;
+const ID_LOW_MEMORY: i32 = 113;
+
+// Layout in pixels: label | control | value
+const MARGIN: i32 = 16;
+const ROW_HEIGHT: i32 = 36;
+const LABEL_WIDTH: i32 = 140;
+const CONTROL_WIDTH: i32 = 200;
+const VALUE_WIDTH: i32 = 60;
+const CLIENT_WIDTH: i32 = MARGIN * 2 + LABEL_WIDTH + CONTROL_WIDTH + VALUE_WIDTH;
+const CLIENT_HEIGHT: i32 = MARGIN * 2 + ROW_HEIGHT * 13;
+
+const EFFECTS: [OverlayEffect; 4] =
+    [OverlayEffect::AcrylicBlur, OverlayEffect::Dim, OverlayEffect::Tint, OverlayEffect::RedVignette];
+
+fn effect_label(effect: OverlayEffect) -> String {
+    tr(match effect {
+        OverlayEffect::AcrylicBlur => "effect.acrylic_blur",
+        OverlayEffect::Dim => "effect.dim",
+        OverlayEffect::Tint => "effect.tint",
+        OverlayEffect::RedVignette => "effect.red_vignette",
+    })
+}
+
+fn resolution_label(resolution: Option<(u32, u32)>) -> String {
+    match resolution {
+        Some((width, height)) => format!("{}x{}", width, height),
+        None => tr("settings_window.resolution_fastest"),
+    }
+}
+
+/// Exposure drop-down entries: automatic, locked, then the fixed values from dark to bright
+fn exposures() -> Vec<ControlSetting> {
+    [ControlSetting::Auto, ControlSetting::Lock].into_iter().chain(EXPOSURE_RANGE.map(ControlSetting::Fixed)).collect()
+}
+
+fn exposure_label(exposure: ControlSetting) -> String {
+    match exposure {
+        ControlSetting::Auto => tr("settings_window.exposure_auto"),
+        ControlSetting::Lock => tr("settings_window.exposure_lock"),
+        ControlSetting::Fixed(value) => value.to_string(),
+    }
+}
+
+// Debounce and fade sliders move in 100 ms steps
+fn ms_to_steps(ms: u64) -> isize {
+    ((ms + 50) / 100) as isize
+}
+
+fn seconds_label(steps: isize) -> String {
+    tr_args("settings_window.seconds", &[("value", &format!("{:.1}", steps as f32 / 10.0))])
+}
+
+/// Shared with the window procedure through GWLP_USERDATA
+#[derive(Default)]
+struct FormState {
+    /// Camera index per drop-down entry
+    cameras: Vec<u32>,
+    /// Value labels next to the sensitivity, debounce and fade sliders
+    value_labels: [HWND; 3],
+    changes: Vec<SettingChange>,
+}
+
+pub struct SettingsWindow {
+    hwnd: HWND,
+    // Boxed so the pointer handed to the window procedure stays put
+    state: Box<RefCell<FormState>>,
+}
+
+impl SettingsWindow {
+    pub fn new() -> Result<Self> {
+        unsafe {
+            let controls = INITCOMMONCONTROLSEX {
+                dwSize: std::mem::size_of::<INITCOMMONCONTROLSEX>() as u32,
+                dwICC: ICC_BAR_CLASSES,
+            };
+            InitCommonControlsEx(&controls);
+
+            let wc = WNDCLASSW {
+                hInstance: GetModuleHandleW(None)?.into(),
+                lpszClassName: CLASS_NAME,
+                lpfnWndProc: Some(Self::wnd_proc),
+                hCursor: LoadCursorW(None, IDC_ARROW)?,
+                hbrBackground: GetSysColorBrush(COLOR_BTNFACE),
+                ..Default::default()
+            };
+            RegisterClassW(&wc);
+        }
+        Ok(Self { hwnd: HWND::default(), state: Box::default() })
+    }
+
+    /// Opens the window showing `settings`, or brings it to the front if already open
+    pub fn open(&mut self, settings: &Settings, cameras: &[(u32, String)], camera_index: u32) -> Result<()> {
+        unsafe {
+            if IsWindow(self.hwnd).as_bool() {
+                let _ = SetForegroundWindow(self.hwnd);
+                return Ok(());
+            }
+
+            let style = WS_OVERLAPPED | WS_CAPTION | WS_SYSMENU | WS_MINIMIZEBOX;
+            let mut rect = RECT { left: 0, top: 0, right: CLIENT_WIDTH, bottom: CLIENT_HEIGHT };
+            AdjustWindowRectEx(&mut rect, style, false, WINDOW_EX_STYLE::default())?;
+            let hwnd = CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                CLASS_NAME,
+                &HSTRING::from(tr("settings_window.title")),
+                style,
+                CW_USEDEFAULT,
+                CW_USEDEFAULT,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+                None,
+                None,
+                GetModuleHandleW(None)?,
+                None,
+            );
+            if hwnd.0 == 0 {
+                bail!("Failed to create the settings window");
+            }
+            self.hwnd = hwnd;
+
+            let mut state = self.state.borrow_mut();
+            state.changes.clear();
+            state.cameras = cameras.iter().map(|(index, _)| *index).collect();
+
+            let sensitivity = (settings.sensitivity_px.round() as isize, *SENSITIVITY_RANGE_PX.start() as isize, *SENSITIVITY_RANGE_PX.end() as isize);
+            let debounce = (ms_to_steps(settings.debounce_ms), ms_to_steps(*DEBOUNCE_RANGE_MS.start()), ms_to_steps(*DEBOUNCE_RANGE_MS.end()));
+            let fade = (ms_to_steps(settings.fade_ms), ms_to_steps(*FADE_RANGE_MS.start()), ms_to_steps(*FADE_RANGE_MS.end()));
+            let sliders = [
+                (ID_SENSITIVITY, "settings_window.sensitivity", sensitivity, tr_args("settings_window.pixels", &[("value", &sensitivity.0)])),
+                (ID_DEBOUNCE, "settings_window.debounce", debounce, seconds_label(debounce.0)),
+                (ID_FADE, "settings_window.fade", fade, seconds_label(fade.0)),
+            ];
+            for (row, (id, label, (position, min, max), value)) in sliders.into_iter().enumerate() {
+                let y = MARGIN + row as i32 * ROW_HEIGHT;
+                Self::label(hwnd, &tr(label), MARGIN, y);
+                let slider = Self::control(hwnd, TRACKBAR_CLASSW, "", WINDOW_STYLE(TBS_HORZ | TBS_NOTICKS), row as i32, id);
+                SendMessageW(slider, TBM_SETRANGEMIN, WPARAM(0), LPARAM(min));
+                SendMessageW(slider, TBM_SETRANGEMAX, WPARAM(0), LPARAM(max));
+                SendMessageW(slider, TBM_SETPOS, WPARAM(1), LPARAM(position));
+                state.value_labels[row] = Self::label(hwnd, &value, MARGIN + LABEL_WIDTH + CONTROL_WIDTH + 8, y);
+            }
+
+            let camera_names: Vec<String> = cameras.iter().map(|(_, name)| name.clone()).collect();
+            let selected_camera = cameras.iter().position(|(index, _)| *index == camera_index);
+            let rotations: Vec<String> = ROTATIONS.iter().map(|degrees| format!("{}°", degrees)).collect();
+            let selected_rotation = ROTATIONS.iter().position(|degrees| *degrees == settings.camera_rotation_degrees);
+            let resolutions: Vec<String> = RESOLUTIONS.iter().map(|resolution| resolution_label(*resolution)).collect();
+            let selected_resolution = RESOLUTIONS.iter().position(|resolution| *resolution == settings.camera_resolution);
+            let frame_rates: Vec<String> =
+                FRAME_RATES.iter().map(|fps| tr_args("settings_window.fps", &[("value", fps)])).collect();
+            let selected_frame_rate = FRAME_RATES.iter().position(|fps| *fps == settings.camera_fps);
+            let exposure_choices = exposures();
+            let selected_exposure = exposure_choices.iter().position(|exposure| *exposure == settings.camera_exposure);
+            let exposure_names: Vec<String> = exposure_choices.into_iter().map(exposure_label).collect();
+            let effects: Vec<String> = EFFECTS.iter().map(|effect| effect_label(*effect)).collect();
+            let selected_effect = EFFECTS.iter().position(|effect| *effect == settings.overlay_effect);
+            let combos = [
+                (ID_CAMERA, "settings_window.camera", camera_names, selected_camera),
+                (ID_ROTATION, "settings_window.rotation", rotations, selected_rotation),
+                (ID_RESOLUTION, "settings_window.resolution", resolutions, selected_resolution),
+                (ID_FRAME_RATE, "settings_window.frame_rate", frame_rates, selected_frame_rate),
+                (ID_EXPOSURE, "settings_window.exposure", exposure_names, selected_exposure),
+                (ID_EFFECT, "settings_window.effect", effects, selected_effect),
+            ];
+            for (offset, (id, label, items, selected)) in combos.into_iter().enumerate() {
+                let row = 3 + offset as i32;
+                Self::label(hwnd, &tr(label), MARGIN, MARGIN + row * ROW_HEIGHT);
+                let combo = Self::control(hwnd, w!("COMBOBOX"), "", WINDOW_STYLE(CBS_DROPDOWNLIST as u32 | WS_VSCROLL.0), row, id);
+                for item in items {
+                    SendMessageW(combo, CB_ADDSTRING, WPARAM(0), LPARAM(HSTRING::from(item).as_ptr() as isize));
+                }
+                if let Some(selected) = selected {
+                    SendMessageW(combo, CB_SETCURSEL, WPARAM(selected), LPARAM(0));
+                }
+            }
+
+            let checkboxes = [
+                (ID_MIRROR, "settings_window.mirror", settings.camera_mirror),
+                (ID_FLIP_VERTICAL, "settings_window.flip_vertical", settings.camera_flip_vertical),
+                (ID_LOW_MEMORY, "settings_window.low_memory", settings.low_memory),
+            ];
+            for (offset, (id, label, checked)) in checkboxes.into_iter().enumerate() {
+                let checkbox = Self::control(hwnd, w!("BUTTON"), &tr(label), WINDOW_STYLE(BS_AUTOCHECKBOX as u32), 9 + offset as i32, id);
+                if checked {
+                    SendMessageW(checkbox, BM_SETCHECK, WPARAM(BST_CHECKED.0 as usize), LPARAM(0));
+                }
+            }
+
+            let close = Self::control(hwnd, w!("BUTTON"), &tr("settings_window.close"), WINDOW_STYLE(BS_PUSHBUTTON as u32), 12, ID_CLOSE);
+            let _ = SetWindowPos(close, None, CLIENT_WIDTH - MARGIN - 100, MARGIN + 12 * ROW_HEIGHT, 100, 26, SWP_NOZORDER);
+            drop(state);
+
+            SetWindowLongPtrW(hwnd, GWLP_USERDATA, &*self.state as *const RefCell<FormState> as isize);
+            ShowWindow(hwnd, SW_SHOW);
+            let _ = SetForegroundWindow(hwnd);
+        }
+        Ok(())
+    }
+
+    /// Edits made since the last call
+    pub fn poll_changes(&self) -> Vec<SettingChange> {
+        std::mem::take(&mut self.state.borrow_mut().changes)
+    }
+
+    unsafe fn label(parent: HWND, text: &str, x: i32, y: i32) -> HWND {
+        let hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            w!("STATIC"),
+            &HSTRING::from(text),
+            WS_CHILD | WS_VISIBLE,
+            x,
+            y + 4,
+            LABEL_WIDTH,
+            20,
+            parent,
+            None,
+            None,
+            None,
+        );
+        SendMessageW(hwnd, WM_SETFONT, WPARAM(GetStockObject(DEFAULT_GUI_FONT).0 as usize), LPARAM(1));
+        hwnd
+    }
+
+    /// Creates a control in the middle column of `row`
+    unsafe fn control(parent: HWND, class: PCWSTR, text: &str, style: WINDOW_STYLE, row: i32, id: i32) -> HWND {
+        // Drop-downs need room for the opened list
+        let height = if style.0 & CBS_DROPDOWNLIST as u32 != 0 { 200 } else { 26 };
+        let hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class,
+            &HSTRING::from(text),
+            WS_CHILD | WS_VISIBLE | WS_TABSTOP | style,
+            MARGIN + LABEL_WIDTH,
+            MARGIN + row * ROW_HEIGHT,
+            CONTROL_WIDTH,
+            height,
+            parent,
+            HMENU(id as isize),
+            None,
+            None,
+        );
+        SendMessageW(hwnd, WM_SETFONT, WPARAM(GetStockObject(DEFAULT_GUI_FONT).0 as usize), LPARAM(1));
+        hwnd
+    }
+
+    fn on_slider(state: &mut FormState, slider: HWND) {
+        unsafe {
+            let position = SendMessageW(slider, TBM_GETPOS, WPARAM(0), LPARAM(0)).0;
+            let (row, change, value) = match GetDlgCtrlID(slider) {
+                ID_SENSITIVITY => (
+                    0,
+                    SettingChange::Sensitivity(position as f32),
+                    tr_args("settings_window.pixels", &[("value", &position)]),
+                ),
+                ID_DEBOUNCE => (1, SettingChange::DebounceMs(position as u64 * 100), seconds_label(position)),
+                ID_FADE => (2, SettingChange::FadeMs(position as u64 * 100), seconds_label(position)),
+                _ => return,
+            };
+            let _ = SetWindowTextW(state.value_labels[row], &HSTRING::from(value));
+            state.changes.push(change);
+        }
+    }
+
+    fn on_selection(state: &mut FormState, id: i32, combo: HWND) {
+        let selected = unsafe { SendMessageW(combo, CB_GETCURSEL, WPARAM(0), LPARAM(0)).0 };
+        let Ok(selected) = usize::try_from(selected) else { return };
+        let change = match id {
+            ID_CAMERA => state.cameras.get(selected).map(|index| SettingChange::Camera(*index)),
+            ID_ROTATION => ROTATIONS.get(selected).map(|degrees| SettingChange::Rotation(*degrees)),
+            ID_RESOLUTION => RESOLUTIONS.get(selected).map(|resolution| SettingChange::Resolution(*resolution)),
+            ID_FRAME_RATE => FRAME_RATES.get(selected).map(|fps| SettingChange::FrameRate(*fps)),
+            ID_EXPOSURE => exposures().get(selected).map(|exposure| SettingChange::Exposure(*exposure)),
+            ID_EFFECT => EFFECTS.get(selected).map(|effect| SettingChange::OverlayEffect(*effect)),
+            _ => None,
+        };
+        state.changes.extend(change);
+    }
+
+    extern "system" fn wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        unsafe {
+            let state = GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *const RefCell<FormState>;
+            let Some(state) = state.as_ref() else {
+                return DefWindowProcW(hwnd, msg, wparam, lparam);
+            };
+
+            match msg {
+                WM_HSCROLL => {
+                    Self::on_slider(&mut state.borrow_mut(), HWND(lparam.0));
+                    LRESULT(0)
+                }
+                WM_COMMAND => {
+                    let (id, code) = ((wparam.0 & 0xFFFF) as i32, ((wparam.0 >> 16) & 0xFFFF) as u32);
+                    if id == ID_CLOSE && code == BN_CLICKED {
+                        let _ = DestroyWindow(hwnd);
+                    } else if matches!(id, ID_MIRROR | ID_FLIP_VERTICAL | ID_LOW_MEMORY) && code == BN_CLICKED {
+                        let checked = SendMessageW(HWND(lparam.0), BM_GETCHECK, WPARAM(0), LPARAM(0)).0 == BST_CHECKED.0 as isize;
+                        let change = match id {
+                            ID_MIRROR => SettingChange::Mirror(checked),
+                            ID_FLIP_VERTICAL => SettingChange::FlipVertical(checked),
+                            _ => SettingChange::LowMemory(checked),
+                        };
+                        state.borrow_mut().changes.push(change);
+                    } else if code == CBN_SELCHANGE {
+                        Self::on_selection(&mut state.borrow_mut(), id, HWND(lparam.0));
+                    }
+                    LRESULT(0)
+                }
+                _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+            }
+        }
+    }
+}
+
+impl Drop for SettingsWindow {
+    fn drop(&mut self) {
+        unsafe {
+            if IsWindow(self.hwnd).as_bool() {
+                // Detach the state first so late messages don't reach a freed pointer
+                SetWindowLongPtrW(self.hwnd, GWLP_USERDATA, 0);
+                let _ = DestroyWindow(self.hwnd);
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+/// Live skeleton in the debug preview: every detected keypoint as a dot and the bones between
+/// them, colored from red (unsure) to green (confident), to check what the model is tracking
+
+use crate::canvas::{self, Canvas};
+use crate::config::{KEYPOINT_CONFIDENCE, SKELETON_MIN_SCORE};
+use crate::pose::*;
+
+/// MoveNet/COCO skeleton
+pub const BONES: [(usize, usize); 16] = [
+    (NOSE, LEFT_EYE),
+    (NOSE, RIGHT_EYE),
+    (LEFT_EYE, LEFT_EAR),
+    (RIGHT_EYE, RIGHT_EAR),
+    (LEFT_SHOULDER, RIGHT_SHOULDER),
+    (LEFT_SHOULDER, LEFT_ELBOW),
+    (LEFT_ELBOW, LEFT_WRIST),
+    (RIGHT_SHOULDER, RIGHT_ELBOW),
+    (RIGHT_ELBOW, RIGHT_WRIST),
+    (LEFT_SHOULDER, LEFT_HIP),
+    (RIGHT_SHOULDER, RIGHT_HIP),
+    (LEFT_HIP, RIGHT_HIP),
+    (LEFT_HIP, LEFT_KNEE),
+    (LEFT_KNEE, LEFT_ANKLE),
+    (RIGHT_HIP, RIGHT_KNEE),
+    (RIGHT_KNEE, RIGHT_ANKLE),
+];
+
+const JOINT_RADIUS: i32 = 4;
+
+/// Red below KEYPOINT_CONFIDENCE (ignored by the posture check), then yellow to green
+pub fn confidence_color(score: f32) -> u32 {
+    if score < KEYPOINT_CONFIDENCE {
+        return canvas::from_u8_rgb(230, 40, 40);
+    }
+    let t = ((score - KEYPOINT_CONFIDENCE) / (1.0 - KEYPOINT_CONFIDENCE)).clamp(0.0, 1.0);
+    canvas::from_u8_rgb((255.0 * (1.0 - t)) as u8, 220, 40)
+}
+
+/// Draws `pose` (normalized keypoints of the current frame) over the preview
+pub fn draw(canvas: &mut Canvas, pose: &[Keypoint]) {
+    let point = |index: usize| pose.get(index).filter(|kp| kp.score >= SKELETON_MIN_SCORE);
+
+    // A bone is only as trustworthy as its weaker end
+    for (a, b) in BONES {
+        if let (Some(a), Some(b)) = (point(a), point(b)) {
+            let (x0, y0) = canvas.to_display(a.x, a.y);
+            let (x1, y1) = canvas.to_display(b.x, b.y);
+            canvas.draw_line(x0, y0, x1, y1, confidence_color(a.score.min(b.score)));
+        }
+    }
+
+    for keypoint in pose.iter().filter(|kp| kp.score >= SKELETON_MIN_SCORE) {
+        let (x, y) = canvas.to_display(keypoint.x, keypoint.y);
+        canvas.fill_circle(x, y, JOINT_RADIUS, confidence_color(keypoint.score));
+    }
+}
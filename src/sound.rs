@@ -0,0 +1,75 @@
+/// Audible alerts: a WAV file or the system warning beep while the posture alert lasts
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use windows::core::{HSTRING, PCWSTR};
+use windows::Win32::Foundation::HMODULE;
+use windows::Win32::Media::Audio::{waveOutSetVolume, PlaySoundW, HWAVEOUT, SND_ASYNC, SND_FILENAME, SND_NODEFAULT};
+use windows::Win32::System::Diagnostics::Debug::MessageBeep;
+use windows::Win32::UI::WindowsAndMessaging::MB_ICONWARNING;
+
+use crate::clock::{self, SharedClock};
+use crate::config::{SOUND_FILE, SOUND_REPEAT_SECS, SOUND_VOLUME};
+
+/// Plays the configured sound without blocking; falls back to the system beep when no file is set
+pub fn play_alert() -> Result<()> {
+    if SOUND_FILE.is_empty() {
+        unsafe { MessageBeep(MB_ICONWARNING)? };
+        return Ok(());
+    }
+
+    if !Path::new(SOUND_FILE).exists() {
+        bail!("sound file not found: {}", SOUND_FILE);
+    }
+
+    // Same level on both channels; on Vista+ this only changes our own audio session
+    let level = (SOUND_VOLUME.min(100) as u32 * 0xFFFF) / 100;
+    unsafe {
+        waveOutSetVolume(HWAVEOUT(0), level | (level << 16));
+        let path = HSTRING::from(SOUND_FILE);
+        if !PlaySoundW(PCWSTR(path.as_ptr()), HMODULE::default(), SND_FILENAME | SND_ASYNC | SND_NODEFAULT).as_bool() {
+            bail!("could not play {}", SOUND_FILE);
+        }
+    }
+    Ok(())
+}
+
+/// Decides when to (re)play the alert sound during an alert
+pub struct SoundAlert {
+    clock: SharedClock,
+    last_played: Option<Instant>,
+}
+
+impl SoundAlert {
+    pub fn new(clock: SharedClock) -> Self {
+        Self { clock, last_played: None }
+    }
+
+    /// Feed once per frame. Returns true when the sound should play now.
+    pub fn update(&mut self, alerting: bool) -> bool {
+        let now = self.clock.now();
+        if !alerting {
+            self.last_played = None;
+            return false;
+        }
+
+        let due = match self.last_played {
+            None => true,
+            // A repeat interval of 0 plays once per alert
+            Some(_) if SOUND_REPEAT_SECS == 0 => false,
+            Some(last) => now.saturating_duration_since(last) >= Duration::from_secs(SOUND_REPEAT_SECS),
+        };
+        if due {
+            self.last_played = Some(now);
+        }
+        due
+    }
+}
+
+impl Default for SoundAlert {
+    fn default() -> Self {
+        Self::new(clock::system())
+    }
+}
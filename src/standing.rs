@@ -0,0 +1,84 @@
+/// Sitting and standing time: today's totals for the tray tooltip, counted per second like the
+/// history rows so both agree, and the per-day totals read back for the report
+
+use anyhow::Result;
+
+use crate::history::HistoryReader;
+use crate::i18n::{tr, tr_args};
+use crate::local_time;
+use crate::report::format_duration;
+use crate::stance::Stance;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// Seconds spent sitting and standing during one local day
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StanceDay {
+    /// Days since the Unix epoch, in local time
+    pub local_day: i64,
+    pub sitting_secs: u64,
+    pub standing_secs: u64,
+}
+
+/// Today's totals, carried over from the history at startup
+#[derive(Debug, Clone, Default)]
+pub struct StanceTotals {
+    today: StanceDay,
+    /// Last second counted (Unix seconds)
+    second: i64,
+}
+
+impl StanceTotals {
+    /// Starts from `today` as read from the history (earlier sessions of the day)
+    pub fn new(today: StanceDay) -> Self {
+        Self { today, second: 0 }
+    }
+
+    /// Today's totals so far, so a restart doesn't start them over
+    pub fn load(history: &HistoryReader) -> Result<Self> {
+        let offset = local_time::utc_offset_secs();
+        let local_day = ((local_time::unix_millis() / 1000) as i64 + offset).div_euclid(DAY_SECS);
+        let today = history.stance_by_day((local_day * DAY_SECS - offset) * 1000, offset)?
+            .pop()
+            .filter(|day| day.local_day == local_day)
+            .unwrap_or(StanceDay { local_day, ..StanceDay::default() });
+        Ok(Self::new(today))
+    }
+
+    /// Feed once per frame with the engine's stance (None = nobody in view). Each second counts
+    /// once, with the stance of its first frame. Returns true when a shown minute changed.
+    pub fn record(&mut self, stance: Option<Stance>, now_secs: i64, utc_offset_secs: i64) -> bool {
+        if now_secs == self.second {
+            return false;
+        }
+        self.second = now_secs;
+        let local_day = (now_secs + utc_offset_secs).div_euclid(DAY_SECS);
+        if local_day != self.today.local_day {
+            self.today = StanceDay { local_day, ..StanceDay::default() };
+        }
+        let secs = match stance {
+            Some(Stance::Sitting) => &mut self.today.sitting_secs,
+            Some(Stance::Standing) => &mut self.today.standing_secs,
+            None => return false,
+        };
+        *secs += 1;
+        *secs % 60 == 0
+    }
+
+    pub fn today(&self) -> StanceDay {
+        self.today
+    }
+
+    /// The tray tooltip while running, with today's totals once there are any
+    pub fn tooltip(&self) -> String {
+        let running = tr("tooltip.running");
+        if self.today.sitting_secs < 60 && self.today.standing_secs < 60 {
+            return running;
+        }
+        let today = tr_args(
+            "tooltip.stance_today",
+            &[("sitting", &format_duration(self.today.sitting_secs)), ("standing", &format_duration(self.today.standing_secs))],
+        );
+        format!("{}\n{}", running, today)
+    }
+}
@@ -0,0 +1,176 @@
+/// Progressive startup: camera enumeration and model loading run on a background thread,
+/// so the tray icon appears right away and reports progress in its tooltip
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use anyhow::{anyhow, Context, Result};
+use nokhwa::utils::CameraInfo;
+use ort::execution_providers::CPUExecutionProvider;
+use ort::session::{builder::GraphOptimizationLevel, Session};
+use tracing::{info, warn};
+
+use crate::accelerator::Accelerator;
+use crate::camera;
+use crate::error::PostureError;
+use crate::exit_status::{self, Failure};
+use crate::model_download::{self, ModelSource, Progress};
+use crate::pose_model::PoseModel;
+use crate::tasks::CancelToken;
+
+/// Startup phase shown in the tray tooltip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupStep {
+    FindingCameras,
+    /// Only on the first run, when the model file isn't there yet
+    DownloadingModel,
+    LoadingModel,
+    /// Done on the main thread once everything else is ready (the capture backend isn't Send)
+    OpeningCamera,
+}
+
+impl StartupStep {
+    pub fn tooltip_key(self) -> &'static str {
+        match self {
+            StartupStep::FindingCameras => "tooltip.starting_cameras",
+            StartupStep::DownloadingModel => "tooltip.starting_download",
+            StartupStep::LoadingModel => "tooltip.starting_model",
+            StartupStep::OpeningCamera => "tooltip.starting_camera",
+        }
+    }
+}
+
+/// How lean the model session is built
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionMode {
+    /// Full graph optimizations on the chosen accelerator
+    Full,
+    /// Low-memory mode: no pre-planned memory pattern, no prepacked weight copies, one thread
+    LowMemory,
+    /// Safe mode: CPU only, basic graph optimizations
+    Safe,
+}
+
+/// Everything the background thread prepares
+pub struct Ready {
+    pub cameras: Vec<CameraInfo>,
+    pub model: Session,
+    /// Where the model ended up running
+    pub accelerator: Accelerator,
+}
+
+pub enum StartupMessage {
+    Step(StartupStep),
+    /// Model download progress, sent while in `StartupStep::DownloadingModel`
+    Download(Progress),
+    Finished(Result<Ready>),
+}
+
+/// Dropping it (Quit while starting) cancels a model download in progress
+pub struct Startup {
+    receiver: Receiver<StartupMessage>,
+    cancel: CancelToken,
+}
+
+impl Startup {
+    /// Starts the background initialization. `model` runs on the first of `accelerator`'s
+    /// providers that loads, in a session built for `mode`.
+    /// A model file that isn't found is downloaded from `source` first; `model_file` (`--model`)
+    /// is loaded instead when given.
+    pub fn spawn(mode: SessionMode, model: PoseModel, model_file: Option<PathBuf>, accelerator: Accelerator, source: ModelSource) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = CancelToken::new();
+        let download_cancel = cancel.clone();
+        thread::spawn(move || {
+            let result = (|| {
+                let _ = sender.send(StartupMessage::Step(StartupStep::FindingCameras));
+                let cameras = camera::list_cameras();
+                let model_path = match model_file.or_else(|| model_download::find(model)) {
+                    Some(path) => path,
+                    None => {
+                        let _ = sender.send(StartupMessage::Step(StartupStep::DownloadingModel));
+                        model_download::download(model, &source, &download_cancel, |progress| {
+                            let _ = sender.send(StartupMessage::Download(progress));
+                        })
+                        .context(Failure::ModelMissing(model.path().to_string()))?
+                    }
+                };
+                exit_status::preflight(&model_path, cameras.len())?;
+
+                let _ = sender.send(StartupMessage::Step(StartupStep::LoadingModel));
+                let (model, accelerator) = load_model(&model_path, mode, accelerator)?;
+                Ok(Ready { cameras, model, accelerator })
+            })();
+            let _ = sender.send(StartupMessage::Finished(result));
+        });
+        Self { receiver, cancel }
+    }
+
+    /// The next progress message, if one arrived
+    pub fn poll(&self) -> Option<StartupMessage> {
+        match self.receiver.try_recv() {
+            Ok(message) => Some(message),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                Some(StartupMessage::Finished(Err(anyhow!("Startup thread stopped unexpectedly"))))
+            }
+        }
+    }
+}
+
+impl Drop for Startup {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Builds a session for the model file at `model`; see `Startup::spawn` for the provider order
+pub fn load_model(model: &Path, mode: SessionMode, accelerator: Accelerator) -> Result<(Session, Accelerator), PostureError> {
+    info!("Loading {}...", model.display());
+    if mode == SessionMode::Safe {
+        return Ok((build_safe_session(model).map_err(PostureError::Model)?, Accelerator::Cpu));
+    }
+    // A missing GPU, driver or runtime DLL only shows up when the session is built, so each
+    // candidate gets a full attempt and the next one takes over if it fails
+    let Some((&last, gpus)) = accelerator.candidates().split_last() else {
+        return Err(PostureError::Config("No execution provider to try".to_string()));
+    };
+    for &candidate in gpus {
+        match build_session(model, candidate, mode) {
+            Ok(session) => {
+                info!("Running the model on {}", candidate.label());
+                return Ok((session, candidate));
+            }
+            Err(e) => warn!("{} unavailable, falling back: {}", candidate.label(), e),
+        }
+    }
+    info!("Running the model on {}", last.label());
+    Ok((build_session(model, last, mode).map_err(PostureError::Model)?, last))
+}
+
+/// CPU only, basic graph optimizations
+fn build_safe_session(model: &Path) -> Result<Session> {
+    Ok(Session::builder()?
+        .with_execution_providers([CPUExecutionProvider::default().build()])?
+        .with_optimization_level(GraphOptimizationLevel::Level1)?
+        .commit_from_file(model)?)
+}
+
+fn build_session(model: &Path, accelerator: Accelerator, mode: SessionMode) -> Result<Session> {
+    let mut session_builder = Session::builder()?
+        .with_execution_providers([accelerator.execution_provider()])?
+        .with_optimization_level(GraphOptimizationLevel::Level3)?;
+    if accelerator == Accelerator::DirectMl {
+        // DirectML rejects sessions with memory patterns
+        session_builder = session_builder.with_memory_pattern(false)?;
+    }
+    if mode == SessionMode::LowMemory {
+        // Trade speed for footprint: no pre-planned memory pattern, no prepacked weight copies
+        session_builder = session_builder
+            .with_memory_pattern(false)?
+            .with_prepacking(false)?
+            .with_intra_threads(1)?;
+    }
+    Ok(session_builder.commit_from_file(model)?)
+}
@@ -0,0 +1,202 @@
+/// Streaks and daily goals: unbroken stretches of good posture and the day's upright time,
+/// celebrated when a goal is reached or a streak beats the day's best, so the app doesn't only
+/// ever point out slouching
+///
+/// Counted per wall-clock second like the history rows; what was reached is kept in the history
+/// so a restart neither repeats a toast nor forgets the day's progress.
+
+use anyhow::Result;
+
+use crate::config::{STREAK_MAX_GAP_SECS, STREAK_MILESTONES_MINS};
+use crate::engine::PostureStatus;
+use crate::goals::Goals;
+use crate::history::{HistoryReader, SecondRow};
+use crate::i18n::tr_args;
+use crate::local_time;
+use crate::report::PeriodSummary;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Achievement {
+    /// A good-posture streak reached a milestone, the best of the day so far
+    Streak { minutes: u32 },
+    /// The day's upright time reached the goal
+    GoodTimeGoal { minutes: u32 },
+    /// A streak reached the daily streak goal
+    StreakGoal { minutes: u32 },
+}
+
+impl Achievement {
+    /// Stable name stored in the history database
+    pub fn key(self) -> &'static str {
+        match self {
+            Achievement::Streak { .. } => "streak",
+            Achievement::GoodTimeGoal { .. } => "good_time_goal",
+            Achievement::StreakGoal { .. } => "streak_goal",
+        }
+    }
+
+    pub fn from_key(key: &str, minutes: u32) -> Option<Self> {
+        match key {
+            "streak" => Some(Achievement::Streak { minutes }),
+            "good_time_goal" => Some(Achievement::GoodTimeGoal { minutes }),
+            "streak_goal" => Some(Achievement::StreakGoal { minutes }),
+            _ => None,
+        }
+    }
+
+    pub fn minutes(self) -> u32 {
+        match self {
+            Achievement::Streak { minutes } | Achievement::GoodTimeGoal { minutes } | Achievement::StreakGoal { minutes } => minutes,
+        }
+    }
+
+    pub fn is_goal(self) -> bool {
+        !matches!(self, Achievement::Streak { .. })
+    }
+
+    /// The toast text
+    pub fn message(self) -> String {
+        let key = match self {
+            Achievement::Streak { .. } => "notification.streak",
+            Achievement::GoodTimeGoal { .. } => "notification.good_time_goal",
+            Achievement::StreakGoal { .. } => "notification.streak_goal",
+        };
+        tr_args(key, &[("minutes", &self.minutes())])
+    }
+}
+
+/// Today's upright time, current streak and what was already reached
+#[derive(Debug, Clone, Default)]
+pub struct StreakTracker {
+    /// Days since the Unix epoch, in local time
+    local_day: i64,
+    /// Last second counted (Unix seconds)
+    second: Option<i64>,
+    good_secs: u64,
+    /// First and last good second of the current streak
+    streak: Option<(i64, i64)>,
+    best_streak_secs: u64,
+    reached: Vec<Achievement>,
+}
+
+impl StreakTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Carries on with today's upright time and achievements from the history
+    pub fn load(history: &HistoryReader) -> Result<Self> {
+        let offset = local_time::utc_offset_secs();
+        let local_day = ((local_time::unix_millis() / 1000) as i64 + offset).div_euclid(DAY_SECS);
+        let today_start_ms = (local_day * DAY_SECS - offset) * 1000;
+        let reached: Vec<Achievement> = history.achievements_since(today_start_ms)?.into_iter().map(|(_, achievement)| achievement).collect();
+        let best_streak_secs = reached.iter()
+            .filter(|achievement| !matches!(achievement, Achievement::GoodTimeGoal { .. }))
+            .map(|achievement| achievement.minutes() as u64 * 60)
+            .max()
+            .unwrap_or(0);
+        Ok(Self {
+            local_day,
+            good_secs: history.good_secs_since(today_start_ms)?,
+            best_streak_secs,
+            reached,
+            ..Self::default()
+        })
+    }
+
+    /// Feed once per frame with the engine's status. Each second counts once, with the status of
+    /// its first frame; a slouch or a gap longer than STREAK_MAX_GAP_SECS ends the streak.
+    /// Returns what was reached this second.
+    pub fn update(&mut self, status: PostureStatus, goals: &Goals, now_secs: i64, utc_offset_secs: i64) -> Vec<Achievement> {
+        if self.second == Some(now_secs) {
+            return Vec::new();
+        }
+        self.second = Some(now_secs);
+        let local_day = (now_secs + utc_offset_secs).div_euclid(DAY_SECS);
+        if local_day != self.local_day {
+            *self = Self { local_day, second: Some(now_secs), ..Self::default() };
+        }
+
+        match status {
+            PostureStatus::Good | PostureStatus::Approaching => {}
+            PostureStatus::Slouching | PostureStatus::Alert => {
+                self.streak = None;
+                return Vec::new();
+            }
+            _ => return Vec::new(),
+        }
+        // Seconds skipped at a low inference rate count when the streak carries on over them
+        let first = match self.streak {
+            Some((first, last)) if now_secs - last <= STREAK_MAX_GAP_SECS => {
+                self.good_secs += (now_secs - last) as u64;
+                first
+            }
+            _ => {
+                self.good_secs += 1;
+                now_secs
+            }
+        };
+        self.streak = Some((first, now_secs));
+        let streak_secs = (now_secs - first + 1) as u64;
+
+        let mut reached = Vec::new();
+        if let Some(minutes) = goals.streak_minutes.filter(|&minutes| minutes > 0 && streak_secs >= minutes as u64 * 60) {
+            reached.push(Achievement::StreakGoal { minutes });
+        }
+        if let Some(minutes) = goals.good_minutes_per_day.filter(|&minutes| minutes > 0 && self.good_secs >= minutes as u64 * 60) {
+            reached.push(Achievement::GoodTimeGoal { minutes });
+        }
+        reached.retain(|achievement| !self.reached.iter().any(|done| done.key() == achievement.key()));
+        // A milestone the streak goal already celebrates isn't toasted twice
+        let milestone = STREAK_MILESTONES_MINS.iter()
+            .map(|&minutes| minutes as u64 * 60)
+            .filter(|&secs| secs > self.best_streak_secs && secs <= streak_secs)
+            .max();
+        if let Some(secs) = milestone.filter(|_| !reached.iter().any(|achievement| matches!(achievement, Achievement::StreakGoal { .. }))) {
+            reached.push(Achievement::Streak { minutes: (secs / 60) as u32 });
+        }
+        self.best_streak_secs = self.best_streak_secs.max(streak_secs);
+        self.reached.extend(&reached);
+        reached
+    }
+
+    /// Upright time today
+    pub fn good_secs(&self) -> u64 {
+        self.good_secs
+    }
+
+    /// The streak going on, if any
+    pub fn streak_secs(&self) -> Option<u64> {
+        self.streak.map(|(first, last)| (last - first + 1) as u64)
+    }
+}
+
+/// One day's score (the share of monitored time not spent slouching) and the goals reached on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayScore {
+    /// Days since the Unix epoch, in local time
+    pub local_day: i64,
+    pub score: u64,
+    pub goals_reached: u32,
+}
+
+/// Scores per monitored day, oldest first; `rows` are oldest first, `achievements` carry their
+/// time in Unix milliseconds
+pub fn daily_scores(rows: &[SecondRow], achievements: &[(i64, Achievement)], utc_offset_secs: i64) -> Vec<DayScore> {
+    let day_of = |secs: i64| (secs + utc_offset_secs).div_euclid(DAY_SECS);
+    rows.chunk_by(|a, b| day_of(a.second) == day_of(b.second))
+        .filter_map(|day_rows| {
+            let local_day = day_of(day_rows[0].second);
+            let summary = PeriodSummary::from_rows(day_rows, 0, utc_offset_secs);
+            (summary.monitored_secs > 0).then(|| DayScore {
+                local_day,
+                score: 100 - summary.slouching_secs * 100 / summary.monitored_secs,
+                goals_reached: achievements.iter()
+                    .filter(|(at_ms, achievement)| achievement.is_goal() && day_of(at_ms.div_euclid(1000)) == local_day)
+                    .count() as u32,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,129 @@
+/// Long operations (reports, maintenance) on worker threads with a shared cancellation token,
+/// so Quit never hangs waiting on a stuck query or file write
+
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+/// Returned by jobs that stopped because they were cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "operation cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Cheap to clone; all clones see the same cancellation
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<(Mutex<bool>, Condvar)>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token and wakes everyone sleeping on it
+    pub fn cancel(&self) {
+        let (cancelled, wake) = &*self.0;
+        *cancelled.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        wake.notify_all();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.0 .0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// `Err(Cancelled)` once cancelled; call between the steps of a job
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        Ok(())
+    }
+
+    /// Sleeps up to `duration`. Returns false when woken by a cancellation.
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let (cancelled, wake) = &*self.0;
+        let guard = cancelled.lock().unwrap_or_else(|e| e.into_inner());
+        let (guard, _) = wake
+            .wait_timeout_while(guard, duration, |cancelled| !*cancelled)
+            .unwrap_or_else(|e| e.into_inner());
+        !*guard
+    }
+}
+
+/// Whether `error` (or anything in its context chain) is a cancellation
+pub fn is_cancelled(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| cause.is::<Cancelled>())
+}
+
+/// A job that ran to the end (or failed)
+pub struct Finished<T> {
+    pub label: &'static str,
+    pub result: Result<T>,
+}
+
+/// Jobs producing a `T` on their own threads; results are collected with `poll` on the main loop
+pub struct BackgroundTasks<T> {
+    cancel: CancelToken,
+    sender: Sender<Finished<T>>,
+    receiver: Receiver<Finished<T>>,
+    running: Vec<(&'static str, JoinHandle<()>)>,
+}
+
+impl<T: Send + 'static> BackgroundTasks<T> {
+    /// Jobs are cancelled together with `cancel`
+    pub fn new(cancel: CancelToken) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self { cancel, sender, receiver, running: Vec::new() }
+    }
+
+    pub fn is_running(&self, label: &str) -> bool {
+        self.running.iter().any(|(running, handle)| *running == label && !handle.is_finished())
+    }
+
+    /// Starts `job` unless one with the same label is still running (returns false then),
+    /// so repeated menu clicks don't stack up behind a slow one
+    pub fn spawn(&mut self, label: &'static str, job: impl FnOnce(&CancelToken) -> Result<T> + Send + 'static) -> bool {
+        if self.is_running(label) || self.cancel.is_cancelled() {
+            return false;
+        }
+        let cancel = self.cancel.clone();
+        let sender = self.sender.clone();
+        let handle = thread::spawn(move || {
+            let result = job(&cancel);
+            let _ = sender.send(Finished { label, result });
+        });
+        self.running.push((label, handle));
+        true
+    }
+
+    /// The next finished job, if any
+    pub fn poll(&mut self) -> Option<Finished<T>> {
+        self.running.retain(|(_, handle)| !handle.is_finished());
+        self.receiver.try_recv().ok()
+    }
+
+    /// Cancels all jobs and waits up to `grace` for them to stop. Returns the labels of jobs
+    /// that didn't; their threads are abandoned and end with the process.
+    pub fn shutdown(mut self, grace: Duration) -> Vec<&'static str> {
+        self.cancel.cancel();
+        let deadline = Instant::now() + grace;
+        loop {
+            self.running.retain(|(_, handle)| !handle.is_finished());
+            if self.running.is_empty() || Instant::now() >= deadline {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        self.running.iter().map(|(label, _)| *label).collect()
+    }
+}
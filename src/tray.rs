@@ -0,0 +1,277 @@
+/// System tray: status icon rendering and the menu built from the current locale's strings
+
+use anyhow::Result;
+use tray_icon::menu::{CheckMenuItem, Menu, MenuId, MenuItem, PredefinedMenuItem, Submenu};
+use tray_icon::Icon;
+
+use nokhwa::utils::KnownCameraControl;
+
+use crate::accelerator::Accelerator;
+use crate::camera_controls::{self, AvailableControl, ControlLevel, PRESET_PERCENTS};
+use crate::i18n::{tr, tr_args};
+
+const CAMERA_ID_PREFIX: &str = "camera:";
+const CONTROL_ID_PREFIX: &str = "control:";
+const ICON_SIZE: u32 = 32;
+
+/// Posture state shown by the tray icon color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayStatus {
+    /// Green: upright
+    Good,
+    /// Yellow: drifting towards the threshold
+    Approaching,
+    /// Red: the overlay has been triggered
+    Bad,
+    /// Gray: paused or dormant
+    Paused,
+}
+
+impl TrayStatus {
+    fn color(self) -> [u8; 3] {
+        match self {
+            TrayStatus::Good => [0, 200, 0],
+            TrayStatus::Approaching => [255, 200, 0],
+            TrayStatus::Bad => [220, 0, 0],
+            TrayStatus::Paused => [128, 128, 128],
+        }
+    }
+}
+
+/// Renders the tray icon for `status`: a filled dot with a dark rim on a transparent background
+pub fn status_icon(status: TrayStatus) -> Result<Icon> {
+    let [r, g, b] = status.color();
+    let center = ICON_SIZE as f32 / 2.0;
+    let radius = center - 1.0;
+
+    let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let dx = x as f32 + 0.5 - center;
+            let dy = y as f32 + 0.5 - center;
+            let distance = (dx * dx + dy * dy).sqrt();
+
+            // One pixel of coverage falloff keeps the edge smooth
+            let alpha = (radius - distance + 0.5).clamp(0.0, 1.0);
+            // Darken the outer two pixels so the dot stands out on light taskbars
+            let shade = if distance > radius - 2.0 { 0.6 } else { 1.0 };
+            rgba.extend_from_slice(&[
+                (r as f32 * shade) as u8,
+                (g as f32 * shade) as u8,
+                (b as f32 * shade) as u8,
+                (alpha * 255.0) as u8,
+            ]);
+        }
+    }
+
+    Ok(Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE)?)
+}
+
+/// What a tray menu click asks the app to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    ToggleDebugWindow,
+    Calibrate,
+    ClearBaseline,
+    CheckPlacement,
+    MarkReference,
+    ClearReference,
+    OpenReport,
+    AlertHistory,
+    OpenDashboard,
+    /// Tag the current or last alert as a false positive
+    MarkFalseAlarm,
+    Diagnostics,
+    OpenLogFolder,
+    StartExercise,
+    OpenSettings,
+    SelectCamera(u32),
+    SetCameraControl(KnownCameraControl, ControlLevel),
+    Pause15,
+    Pause60,
+    PauseIndefinitely,
+    Resume,
+    /// Run outside the work schedule until its next window starts
+    ToggleScheduleOverride,
+    /// Add or remove the "Start with Windows" entry
+    ToggleAutostart,
+    Quit,
+}
+
+/// Item ids are fixed strings, so clicks map to actions no matter which language built the menu
+pub fn action(id: &MenuId) -> Option<TrayAction> {
+    if let Some(index) = id.0.strip_prefix(CAMERA_ID_PREFIX) {
+        return index.parse().ok().map(TrayAction::SelectCamera);
+    }
+    if let Some(rest) = id.0.strip_prefix(CONTROL_ID_PREFIX) {
+        let (key, level) = rest.split_once(':')?;
+        let level = match level {
+            "default" => ControlLevel::Default,
+            percent => ControlLevel::Percent(percent.parse().ok()?),
+        };
+        return camera_controls::from_key(key).map(|control| TrayAction::SetCameraControl(control, level));
+    }
+    match id.0.as_str() {
+        "toggle_debug" => Some(TrayAction::ToggleDebugWindow),
+        "calibrate" => Some(TrayAction::Calibrate),
+        "clear_baseline" => Some(TrayAction::ClearBaseline),
+        "check_placement" => Some(TrayAction::CheckPlacement),
+        "mark_reference" => Some(TrayAction::MarkReference),
+        "clear_reference" => Some(TrayAction::ClearReference),
+        "open_report" => Some(TrayAction::OpenReport),
+        "alert_history" => Some(TrayAction::AlertHistory),
+        "dashboard" => Some(TrayAction::OpenDashboard),
+        "false_alarm" => Some(TrayAction::MarkFalseAlarm),
+        "diagnostics" => Some(TrayAction::Diagnostics),
+        "log_folder" => Some(TrayAction::OpenLogFolder),
+        "exercise" => Some(TrayAction::StartExercise),
+        "settings" => Some(TrayAction::OpenSettings),
+        "pause_15" => Some(TrayAction::Pause15),
+        "pause_60" => Some(TrayAction::Pause60),
+        "pause_indefinitely" => Some(TrayAction::PauseIndefinitely),
+        "resume" => Some(TrayAction::Resume),
+        "schedule_override" => Some(TrayAction::ToggleScheduleOverride),
+        "autostart" => Some(TrayAction::ToggleAutostart),
+        "quit" => Some(TrayAction::Quit),
+        _ => None,
+    }
+}
+
+pub struct TrayMenu {
+    pub menu: Menu,
+    resume_item: MenuItem,
+    schedule_override_item: CheckMenuItem,
+    autostart_item: CheckMenuItem,
+    camera_items: Vec<(u32, CheckMenuItem)>,
+    control_items: Vec<(KnownCameraControl, ControlLevel, CheckMenuItem)>,
+}
+
+fn control_id(control: KnownCameraControl, level: ControlLevel) -> String {
+    let level = match level {
+        ControlLevel::Default => "default".to_string(),
+        ControlLevel::Percent(percent) => percent.to_string(),
+    };
+    format!("{}{}:{}", CONTROL_ID_PREFIX, camera_controls::key(control), level)
+}
+
+impl TrayMenu {
+    /// Builds the menu for `cameras` (index, name) and the open camera's `controls`, with a status
+    /// line naming the `accelerator` the model runs on.
+    /// Call again after a locale change or when another camera was opened.
+    pub fn build(
+        cameras: &[(u32, String)],
+        selected_camera: u32,
+        controls: &[AvailableControl],
+        paused: bool,
+        schedule_override: bool,
+        autostart: bool,
+        accelerator: Accelerator,
+    ) -> Result<Self> {
+        let menu = Menu::new();
+
+        // Camera submenu: one checkable entry per detected device
+        let camera_menu = Submenu::new(tr("tray.camera"), true);
+        let mut camera_items = Vec::new();
+        for (index, name) in cameras {
+            let id = format!("{}{}", CAMERA_ID_PREFIX, index);
+            let item = CheckMenuItem::with_id(id, name, true, *index == selected_camera, None);
+            camera_menu.append(&item)?;
+            camera_items.push((*index, item));
+        }
+        if camera_items.is_empty() {
+            camera_menu.append(&MenuItem::new(tr("tray.no_cameras"), false, None))?;
+        }
+
+        // Camera controls submenu: one submenu of presets per control the device supports
+        let controls_menu = Submenu::new(tr("tray.camera_controls"), true);
+        let mut control_items = Vec::new();
+        for available in controls {
+            let control_menu = Submenu::new(tr(&format!("camera_control.{}", camera_controls::key(available.control))), true);
+            let levels = std::iter::once(ControlLevel::Default).chain(PRESET_PERCENTS.map(ControlLevel::Percent));
+            for level in levels {
+                let label = match level {
+                    ControlLevel::Default => tr("tray.control_default"),
+                    ControlLevel::Percent(percent) => format!("{}%", percent),
+                };
+                let id = control_id(available.control, level);
+                let item = CheckMenuItem::with_id(id, label, true, available.level == Some(level), None);
+                control_menu.append(&item)?;
+                control_items.push((available.control, level, item));
+            }
+            controls_menu.append(&control_menu)?;
+        }
+        if controls.is_empty() {
+            controls_menu.append(&MenuItem::new(tr("tray.no_camera_controls"), false, None))?;
+        }
+
+        let resume_item = MenuItem::with_id("resume", tr("tray.resume"), paused, None);
+        let schedule_override_item =
+            CheckMenuItem::with_id("schedule_override", tr("tray.schedule_override"), true, schedule_override, None);
+        let autostart_item = CheckMenuItem::with_id("autostart", tr("tray.autostart"), true, autostart, None);
+
+        menu.append(&MenuItem::new(tr_args("tray.accelerator", &[("provider", &accelerator.label())]), false, None))?;
+        menu.append(&PredefinedMenuItem::separator())?;
+        menu.append(&MenuItem::with_id("toggle_debug", tr("tray.toggle_debug"), true, None))?;
+        menu.append(&MenuItem::with_id("calibrate", tr("tray.calibrate"), true, None))?;
+        menu.append(&MenuItem::with_id("clear_baseline", tr("tray.clear_baseline"), true, None))?;
+        menu.append(&MenuItem::with_id("check_placement", tr("tray.check_placement"), true, None))?;
+        menu.append(&MenuItem::with_id("exercise", tr("tray.exercise"), true, None))?;
+        menu.append(&MenuItem::with_id("mark_reference", tr("tray.mark_reference"), true, None))?;
+        menu.append(&MenuItem::with_id("clear_reference", tr("tray.clear_reference"), true, None))?;
+        menu.append(&camera_menu)?;
+        menu.append(&controls_menu)?;
+        menu.append(&MenuItem::with_id("open_report", tr("tray.open_report"), true, None))?;
+        menu.append(&MenuItem::with_id("alert_history", tr("tray.alert_history"), true, None))?;
+        menu.append(&MenuItem::with_id("dashboard", tr("tray.dashboard"), true, None))?;
+        menu.append(&MenuItem::with_id("false_alarm", tr("tray.false_alarm"), true, None))?;
+        menu.append(&MenuItem::with_id("diagnostics", tr("tray.diagnostics"), true, None))?;
+        menu.append(&MenuItem::with_id("log_folder", tr("tray.log_folder"), true, None))?;
+        menu.append(&MenuItem::with_id("settings", tr("tray.settings"), true, None))?;
+        menu.append(&autostart_item)?;
+        menu.append(&PredefinedMenuItem::separator())?;
+        menu.append(&MenuItem::with_id("pause_15", tr("tray.pause_15"), true, None))?;
+        menu.append(&MenuItem::with_id("pause_60", tr("tray.pause_60"), true, None))?;
+        menu.append(&MenuItem::with_id("pause_indefinitely", tr("tray.pause_indefinitely"), true, None))?;
+        menu.append(&resume_item)?;
+        menu.append(&schedule_override_item)?;
+        menu.append(&PredefinedMenuItem::separator())?;
+        menu.append(&MenuItem::with_id("quit", tr("tray.quit"), true, None))?;
+
+        Ok(Self { menu, resume_item, schedule_override_item, autostart_item, camera_items, control_items })
+    }
+
+    /// Menu shown while the app is still starting: a status line and Quit
+    pub fn starting() -> Result<Menu> {
+        let menu = Menu::new();
+        menu.append(&MenuItem::new(tr("tooltip.starting"), false, None))?;
+        menu.append(&PredefinedMenuItem::separator())?;
+        menu.append(&MenuItem::with_id("quit", tr("tray.quit"), true, None))?;
+        Ok(menu)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.resume_item.set_enabled(paused);
+    }
+
+    pub fn set_schedule_override(&self, schedule_override: bool) {
+        self.schedule_override_item.set_checked(schedule_override);
+    }
+
+    pub fn set_autostart(&self, autostart: bool) {
+        self.autostart_item.set_checked(autostart);
+    }
+
+    pub fn set_selected_camera(&self, index: u32) {
+        for (item_index, item) in &self.camera_items {
+            item.set_checked(*item_index == index);
+        }
+    }
+
+    pub fn set_control_level(&self, control: KnownCameraControl, level: ControlLevel) {
+        for (item_control, item_level, item) in &self.control_items {
+            if *item_control == control {
+                item.set_checked(*item_level == level);
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+/// Habit trends: per-week time from sitting down to the first slouch alert and time spent
+/// ignoring alerts, to show whether the reminders are actually changing habits
+
+use crate::config::TRENDS_WEEKS;
+use crate::history::AlertRecord;
+use crate::local_time::LocalTime;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// A stretch of time in front of the camera without a break longer than SITTING_GAP_SECS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sitting {
+    /// First and last second with a judged pose (Unix seconds)
+    pub first_second: i64,
+    pub last_second: i64,
+}
+
+/// Behavior during one week (Monday to Sunday, local time)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeekTrend {
+    /// "2024-03-04" for the Monday the week starts on
+    pub week_start: String,
+    pub sittings: u32,
+    /// Sittings that had at least one alert
+    pub sittings_with_alert: u32,
+    /// Average time from sitting down to the first slouch that led to an alert
+    pub avg_secs_to_first_alert: Option<u64>,
+    pub alerts: u32,
+    /// Average time from the overlay appearing to sitting up again
+    pub avg_secs_ignoring_alerts: Option<u64>,
+}
+
+fn average(values: &[i64]) -> Option<u64> {
+    (!values.is_empty()).then(|| (values.iter().sum::<i64>() / values.len() as i64).max(0) as u64)
+}
+
+/// The last TRENDS_WEEKS weeks up to and including the one containing `now_secs`, oldest first.
+/// Alerts marked as wrong are left out; each sitting counts in the week it started.
+pub fn weekly(sittings: &[Sitting], alerts: &[AlertRecord], now_secs: i64, utc_offset_secs: i64) -> Vec<WeekTrend> {
+    let today = LocalTime::from_unix_secs(now_secs, utc_offset_secs);
+    let this_monday = today.day_number() - today.weekday() as i64;
+    let alerts: Vec<&AlertRecord> = alerts.iter().filter(|alert| !alert.false_positive).collect();
+
+    (0..TRENDS_WEEKS as i64)
+        .rev()
+        .map(|weeks_ago| {
+            let monday = this_monday - weeks_ago * 7;
+            let start_secs = monday * DAY_SECS - utc_offset_secs;
+            let in_week = |secs: i64| (start_secs..start_secs + 7 * DAY_SECS).contains(&secs);
+
+            let week_sittings: Vec<&Sitting> = sittings.iter().filter(|sitting| in_week(sitting.first_second)).collect();
+            let to_first_alert: Vec<i64> = week_sittings.iter()
+                .filter_map(|sitting| {
+                    alerts.iter()
+                        .map(|alert| alert.slouch_started_ms / 1000)
+                        .filter(|slouch| (sitting.first_second..=sitting.last_second).contains(slouch))
+                        .min()
+                        .map(|slouch| slouch - sitting.first_second)
+                })
+                .collect();
+            let ignored: Vec<i64> = alerts.iter()
+                .filter(|alert| in_week(alert.started_ms / 1000))
+                .map(|alert| alert.response_ms() / 1000)
+                .collect();
+
+            WeekTrend {
+                week_start: LocalTime::from_unix_secs(monday * DAY_SECS, 0).date_string(),
+                sittings: week_sittings.len() as u32,
+                sittings_with_alert: to_first_alert.len() as u32,
+                avg_secs_to_first_alert: average(&to_first_alert),
+                alerts: ignored.len() as u32,
+                avg_secs_ignoring_alerts: average(&ignored),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,101 @@
+/// YOLOv8-pose support: a multi-person detector; `primary` picks which of the people is the user
+
+use image::RgbImage;
+use ort::session::SessionOutputs;
+use ort::value::DynValue;
+
+use crate::config::{YOLO_MIN_PERSON_CONFIDENCE, YOLO_NMS_IOU};
+use crate::error::{PostureError, Result};
+use crate::pose::{Keypoint, Pose, KEYPOINT_COUNT};
+use crate::pose_model::{self, InputType, PoseEstimator};
+
+// Per detection: box (cx, cy, w, h), person confidence, then (x, y, visibility) per keypoint
+const VALUES_PER_DETECTION: usize = 5 + KEYPOINT_COUNT * 3;
+
+/// YOLOv8-pose as exported by Ultralytics: a [1, 3, size, size] input scaled to 0-1 and a
+/// [1, 56, detections] output in input pixels, with keypoints already in COCO order
+pub struct YoloPose {
+    pub input_size: u32,
+    input: Option<DynValue>,
+}
+
+impl YoloPose {
+    pub fn new(input_size: u32) -> Self {
+        YoloPose { input_size, input: None }
+    }
+}
+
+impl PoseEstimator for YoloPose {
+    fn input_size(&self) -> u32 {
+        self.input_size
+    }
+
+    fn preprocess(&mut self, frame: &RgbImage) -> Result<()> {
+        let size = self.input_size;
+        let input = match &mut self.input {
+            Some(input) => input,
+            None => self.input.insert(pose_model::allocate_input(InputType::Float32, [1, 3, size as i64, size as i64])?),
+        };
+        let (_, data) = input.try_extract_tensor_mut::<f32>().map_err(PostureError::model)?;
+        pose_model::fill_planar(frame.as_raw(), data, |value| value as f32 / 255.0);
+        Ok(())
+    }
+
+    fn input(&self) -> Option<&DynValue> {
+        self.input.as_ref()
+    }
+
+    fn parse(&self, outputs: &SessionOutputs) -> Result<Vec<Pose>> {
+        let (_, data) = outputs["output0"].try_extract_tensor::<f32>().map_err(PostureError::model)?;
+        Ok(parse_yolo_pose_people(data, self.input_size))
+    }
+}
+
+/// The most confident person in a raw [1, 56, detections] output, with keypoints scaled from
+/// input pixels to 0-1. `None` when nobody reaches YOLO_MIN_PERSON_CONFIDENCE.
+pub fn parse_yolo_pose_output(data: &[f32], input_size: u32) -> Option<Pose> {
+    parse_yolo_pose_people(data, input_size).into_iter().next()
+}
+
+/// Everyone reaching YOLO_MIN_PERSON_CONFIDENCE in a raw output, most confident first. Boxes
+/// overlapping a more confident one by more than YOLO_NMS_IOU are the same person and dropped.
+pub fn parse_yolo_pose_people(data: &[f32], input_size: u32) -> Vec<Pose> {
+    let detections = data.len() / VALUES_PER_DETECTION;
+    let value = |row: usize, detection: usize| data[row * detections + detection];
+
+    let mut candidates: Vec<usize> = (0..detections).filter(|&d| value(4, d) >= YOLO_MIN_PERSON_CONFIDENCE).collect();
+    candidates.sort_by(|&a, &b| value(4, b).total_cmp(&value(4, a)));
+    let bounds = |d: usize| {
+        let (cx, cy, w, h) = (value(0, d), value(1, d), value(2, d), value(3, d));
+        (cx - w / 2.0, cy - h / 2.0, cx + w / 2.0, cy + h / 2.0)
+    };
+    let mut kept: Vec<usize> = Vec::new();
+    for candidate in candidates {
+        if kept.iter().all(|&other| overlap(bounds(candidate), bounds(other)) <= YOLO_NMS_IOU) {
+            kept.push(candidate);
+        }
+    }
+
+    let scale = input_size as f32;
+    kept.into_iter()
+        .map(|detection| {
+            let mut pose = [Keypoint::default(); KEYPOINT_COUNT];
+            for (i, keypoint) in pose.iter_mut().enumerate() {
+                let row = 5 + i * 3;
+                keypoint.x = value(row, detection) / scale;
+                keypoint.y = value(row + 1, detection) / scale;
+                keypoint.score = value(row + 2, detection);
+            }
+            pose
+        })
+        .collect()
+}
+
+/// Intersection over union of two (left, top, right, bottom) boxes; 0 for empty ones
+fn overlap(a: (f32, f32, f32, f32), b: (f32, f32, f32, f32)) -> f32 {
+    let width = (a.2.min(b.2) - a.0.max(b.0)).max(0.0);
+    let height = (a.3.min(b.3) - a.1.max(b.1)).max(0.0);
+    let intersection = width * height;
+    let union = (a.2 - a.0) * (a.3 - a.1) + (b.2 - b.0) * (b.3 - b.1) - intersection;
+    if union > 0.0 { intersection / union } else { 0.0 }
+}
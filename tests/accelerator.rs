@@ -0,0 +1,21 @@
+//! Execution providers: fallback order and the names used in settings.json.
+
+use posture_ai_oc::accelerator::Accelerator;
+use posture_ai_oc::settings::Settings;
+
+#[test]
+fn every_choice_falls_back_to_the_cpu() {
+    assert_eq!(Accelerator::Auto.candidates(), [Accelerator::DirectMl, Accelerator::Cuda, Accelerator::Cpu]);
+    assert_eq!(Accelerator::Cuda.candidates(), [Accelerator::Cuda, Accelerator::Cpu]);
+    assert_eq!(Accelerator::Cpu.candidates(), [Accelerator::Cpu]);
+    for accelerator in [Accelerator::Auto, Accelerator::DirectMl, Accelerator::Cuda, Accelerator::Cpu] {
+        assert_eq!(accelerator.candidates().last(), Some(&Accelerator::Cpu));
+    }
+}
+
+#[test]
+fn provider_is_chosen_in_settings_json() {
+    let settings: Settings = serde_json::from_str(r#"{ "execution_provider": "direct_ml" }"#).unwrap();
+    assert_eq!(settings.execution_provider, Accelerator::DirectMl);
+    assert_eq!(serde_json::to_string(&Accelerator::Cpu).unwrap(), "\"cpu\"");
+}
@@ -0,0 +1,55 @@
+//! Active hours: the daily window and learning it from presence history.
+
+use posture_ai_oc::active_hours::{self, ActiveHours, PresenceHour};
+use posture_ai_oc::config::ACTIVE_HOURS_MIN_DAYS;
+use posture_ai_oc::local_time::LocalTime;
+
+/// A full hour at the desk during `hours` on each of `days`
+fn presence(days: impl IntoIterator<Item = i64>, hours: impl IntoIterator<Item = i64> + Clone) -> Vec<PresenceHour> {
+    days.into_iter()
+        .flat_map(|day| hours.clone().into_iter().map(move |hour| PresenceHour { local_hour: day * 24 + hour, present_secs: 3600 }))
+        .collect()
+}
+
+#[test]
+fn window_contains_its_hours_and_may_wrap() {
+    let office = ActiveHours { start_hour: 8, end_hour: 18 };
+    assert!(office.contains(8) && office.contains(17));
+    assert!(!office.contains(18) && !office.contains(2));
+    assert_eq!(office.describe(), "08:00-18:00");
+
+    let night = ActiveHours { start_hour: 22, end_hour: 6 };
+    assert!(night.contains(23) && night.contains(0) && night.contains(5));
+    assert!(!night.contains(6) && !night.contains(12));
+
+    let two_am = LocalTime::from_unix_secs(2 * 3600, 0);
+    assert!(!active_hours::is_active(Some(office), &two_am));
+    assert!(active_hours::is_active(None, &two_am));
+}
+
+#[test]
+fn typical_desk_hours_are_learned_with_a_margin() {
+    // Two weeks of 9-17, plus one night the PC was left on
+    let mut history = presence(0..14, 9..17);
+    history.extend(presence([3], 0..4));
+    assert_eq!(active_hours::learn(&history), Some(ActiveHours { start_hour: 8, end_hour: 18 }));
+
+    // Short visits don't count
+    let mut visits = presence(0..14, 9..17);
+    visits.extend((0..14).map(|day| PresenceHour { local_hour: day * 24 + 21, present_secs: 60 }));
+    assert_eq!(active_hours::learn(&visits), Some(ActiveHours { start_hour: 8, end_hour: 18 }));
+
+    // Night owls get a window across midnight
+    let late = presence(0..14, (20..24).chain(0..3));
+    assert_eq!(active_hours::learn(&late), Some(ActiveHours { start_hour: 19, end_hour: 4 }));
+}
+
+#[test]
+fn nothing_is_proposed_without_enough_data_or_a_gap() {
+    let days = ACTIVE_HOURS_MIN_DAYS as i64;
+    assert_eq!(active_hours::learn(&presence(0..days - 1, 9..17)), None);
+    assert!(active_hours::learn(&presence(0..days, 9..17)).is_some());
+    // Around the clock, or only a one-hour break
+    assert_eq!(active_hours::learn(&presence(0..14, 0..24)), None);
+    assert_eq!(active_hours::learn(&presence(0..14, (0..24).filter(|hour| *hour != 4))), None);
+}
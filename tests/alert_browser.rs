@@ -0,0 +1,75 @@
+//! Alert tracking and the alert history page.
+
+use posture_ai_oc::alert_browser::{render_html, AlertTracker};
+use posture_ai_oc::history::AlertRecord;
+use posture_ai_oc::i18n;
+use posture_ai_oc::local_time::LocalTime;
+
+const HOUR_MS: i64 = 60 * 60 * 1000;
+// 2024-03-01 09:00:00 UTC
+const MORNING_MS: i64 = 1_709_283_600_000;
+
+fn alert(started_ms: i64, rule: &str) -> AlertRecord {
+    AlertRecord {
+        rule: rule.to_string(),
+        slouch_started_ms: started_ms - 500,
+        started_ms,
+        ended_ms: started_ms + 4_000,
+        peak_delta: Some(42.0),
+        snapshot: None,
+        false_positive: false,
+    }
+}
+
+#[test]
+fn tracker_measures_duration_response_and_peak() {
+    let mut tracker = AlertTracker::new();
+    assert_eq!(tracker.finish(MORNING_MS), None);
+
+    tracker.start("eye_drop", MORNING_MS, 500, Some("alert-1.png".to_string()));
+    for delta in [Some(15.0), None, Some(31.5), Some(20.0)] {
+        tracker.update(delta);
+    }
+    let alert = tracker.finish(MORNING_MS + 12_000).unwrap();
+
+    assert_eq!(alert.rule, "eye_drop");
+    assert_eq!(alert.duration_ms(), 12_500);
+    assert_eq!(alert.response_ms(), 12_000);
+    assert_eq!(alert.peak_delta, Some(31.5));
+    assert_eq!(alert.snapshot.as_deref(), Some("alert-1.png"));
+    assert_eq!(tracker.finish(MORNING_MS + 13_000), None);
+}
+
+#[test]
+fn unix_timestamps_convert_to_local_dates() {
+    let time = LocalTime::from_unix_secs(MORNING_MS / 1000, 2 * 3600);
+    assert_eq!(time.date_string(), "2024-03-01");
+    assert_eq!((time.hour, time.minute, time.second), (11, 0, 0));
+
+    // Leap day, reached by crossing midnight with a negative offset
+    let time = LocalTime::from_unix_secs(MORNING_MS / 1000, -10 * 3600);
+    assert_eq!(time.date_string(), "2024-02-29");
+    assert_eq!(time.hour, 23);
+}
+
+#[test]
+fn page_lists_alerts_with_day_and_rule_filters() {
+    i18n::load("en");
+    let alerts = [alert(MORNING_MS + 30 * HOUR_MS, "eye_drop"), alert(MORNING_MS, "eye_drop")];
+    let html = render_html(&alerts, 0);
+
+    assert_eq!(html.matches("<tr data-day=").count(), 2);
+    assert!(html.contains("<option>2024-03-01</option>"));
+    assert!(html.contains("<option>2024-03-02</option>"));
+    assert!(html.contains("<option value=\"eye_drop\">Head dropped below baseline</option>"));
+    assert!(html.contains("<td>4.5 s</td><td>4.0 s</td><td>42 px</td>"));
+    assert!(!html.contains("<img"));
+}
+
+#[test]
+fn empty_history_says_so() {
+    i18n::load("en");
+    let html = render_html(&[], 0);
+    assert!(html.contains("No alerts in the last 30 days."));
+    assert!(!html.contains("<select"));
+}
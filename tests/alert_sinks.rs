@@ -0,0 +1,138 @@
+//! Arbitration between overlay, sound and toast alerts, and per-channel alert profiles.
+
+use std::time::Duration;
+
+use posture_ai_oc::alert_sinks::{self, AlertArbiter, AlertProfile, AlertProfiles, AlertSink, ChannelTriggers};
+use posture_ai_oc::clock::ManualClock;
+use posture_ai_oc::config::{DEBOUNCE_MS, HEIGHT, SOUND_BUSY_SECS, TOAST_AFTER_SECS, TOAST_BUSY_SECS};
+use posture_ai_oc::engine::PostureEngine;
+use posture_ai_oc::pose::{Keypoint, Pose, KEYPOINT_COUNT, RIGHT_EYE};
+use posture_ai_oc::settings::Settings;
+
+use AlertSink::{Overlay, Sound, Toast};
+
+#[test]
+fn full_overlay_silences_sound_and_toast() {
+    let clock = ManualClock::new();
+    let mut arbiter = AlertArbiter::new(clock.shared(), &[Overlay, Sound, Toast]);
+
+    // Still fading in: the sound marks the start of the alert
+    arbiter.set_overlay_intensity(0.4);
+    assert!(arbiter.request(Sound));
+
+    clock.advance(Duration::from_secs(SOUND_BUSY_SECS + 1));
+    arbiter.set_overlay_intensity(1.0);
+    assert_eq!(arbiter.blocked_by(Toast), Some(Overlay));
+    assert!(!arbiter.request(Sound));
+    assert!(!arbiter.request(Toast));
+
+    arbiter.set_overlay_intensity(0.0);
+    assert!(arbiter.request(Toast));
+}
+
+#[test]
+fn busy_channels_only_block_lower_priorities() {
+    let clock = ManualClock::new();
+    let mut arbiter = AlertArbiter::new(clock.shared(), &[Overlay, Sound, Toast]);
+
+    assert!(arbiter.request(Toast));
+    assert!(arbiter.request(Sound), "a toast never holds back the sound");
+    assert!(!arbiter.request(Toast), "the sound is still playing");
+
+    clock.advance(Duration::from_secs(SOUND_BUSY_SECS));
+    assert!(!arbiter.is_busy(Sound));
+    assert!(arbiter.is_busy(Toast));
+    clock.advance(Duration::from_secs(TOAST_BUSY_SECS));
+    assert!(!arbiter.is_busy(Toast));
+}
+
+#[test]
+fn priority_is_configurable_and_normalized() {
+    let clock = ManualClock::new();
+    let mut arbiter = AlertArbiter::new(clock.shared(), &[Toast, Toast]);
+
+    assert!(arbiter.request(Toast));
+    assert!(!arbiter.request(Sound), "toast ranks above sound now");
+
+    arbiter.set_priority(&[Sound]);
+    assert!(arbiter.request(Sound));
+    assert_eq!(alert_sinks::normalize_priority(&[Toast, Sound, Toast]), vec![Toast, Sound, Overlay]);
+}
+
+#[test]
+fn priority_is_stored_by_name() {
+    let json = serde_json::to_string(&[Toast, Overlay]).unwrap();
+    assert_eq!(json, r#"["toast","overlay"]"#);
+}
+
+const BASELINE_EYE_Y: f32 = 0.40;
+
+fn pose_with_eyes_at(y: f32) -> Pose {
+    let mut pose = [Keypoint::default(); KEYPOINT_COUNT];
+    pose[RIGHT_EYE] = Keypoint { x: 0.5, y, score: 0.9 };
+    pose
+}
+
+/// Feeds the same pose to the engine and channels for `duration`, a frame every 100ms
+fn hold(clock: &ManualClock, engine: &mut PostureEngine, channels: &mut ChannelTriggers, eye_y: f32, duration: Duration) {
+    let frames = duration.as_millis() / 100;
+    for _ in 0..frames {
+        clock.advance(Duration::from_millis(100));
+        engine.process(Some(&pose_with_eyes_at(eye_y)));
+        channels.update(engine);
+    }
+}
+
+#[test]
+fn channels_without_a_profile_follow_the_engine() {
+    let clock = ManualClock::new();
+    let mut engine = PostureEngine::new(0, 0, Some(BASELINE_EYE_Y * HEIGHT as f32), clock.shared());
+    let mut channels = ChannelTriggers::new(clock.shared(), &AlertProfiles::default(), &engine);
+
+    hold(&clock, &mut engine, &mut channels, 0.50, Duration::from_millis(DEBOUNCE_MS + 500));
+    assert!(engine.is_alerting());
+    for sink in alert_sinks::ALL_SINKS {
+        assert!(channels.is_alerting(sink, &engine));
+        assert!(channels.is_slouching(sink, &engine));
+    }
+    assert_eq!(channels.intensity(AlertSink::Overlay, &engine), engine.alert_intensity());
+}
+
+#[test]
+fn a_channel_with_its_own_profile_fires_earlier_or_later() {
+    let clock = ManualClock::new();
+    let mut engine = PostureEngine::new(0, 0, Some(BASELINE_EYE_Y * HEIGHT as f32), clock.shared());
+    let profiles = AlertProfiles {
+        sound: AlertProfile { sensitivity_px: Some(5.0), debounce_ms: Some(0) },
+        overlay: AlertProfile { sensitivity_px: Some(30.0), debounce_ms: None },
+        toast: AlertProfile::default(),
+    };
+    let mut channels = ChannelTriggers::new(clock.shared(), &profiles, &engine);
+
+    // 7px below the baseline: inside the shared sensitivity, past the sound's
+    hold(&clock, &mut engine, &mut channels, 0.415, Duration::from_millis(200));
+    assert!(!engine.is_alerting());
+    assert!(channels.is_alerting(AlertSink::Sound, &engine));
+
+    // 14px: the engine alerts after its debounce, the overlay waits for 30px
+    hold(&clock, &mut engine, &mut channels, 0.43, Duration::from_millis(DEBOUNCE_MS + 500));
+    assert!(engine.is_alerting());
+    assert!(!channels.is_alerting(AlertSink::Overlay, &engine));
+    assert_eq!(channels.intensity(AlertSink::Overlay, &engine), 0.0);
+
+    hold(&clock, &mut engine, &mut channels, 0.50, Duration::from_millis(DEBOUNCE_MS + 500));
+    assert!(channels.intensity(AlertSink::Overlay, &engine) > 0.0);
+
+    channels.reset();
+    assert!(!channels.is_alerting(AlertSink::Sound, &engine));
+}
+
+#[test]
+fn profiles_are_read_from_settings_json() {
+    let settings: Settings =
+        serde_json::from_str(r#"{ "alert_profiles": { "toast": { "debounce_ms": 30000 }, "sound": { "sensitivity_px": 6.0 } } }"#).unwrap();
+    assert_eq!(settings.alert_profiles.toast_delay(), Duration::from_secs(30));
+    assert_eq!(settings.alert_profiles.sound.sensitivity_px, Some(6.0));
+    assert_eq!(settings.alert_profiles.get(AlertSink::Overlay), AlertProfile::default());
+    assert_eq!(Settings::default().alert_profiles.toast_delay(), Duration::from_secs(TOAST_AFTER_SECS));
+}
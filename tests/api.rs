@@ -0,0 +1,80 @@
+//! Local API: routes, commands, host and origin checks, and the WebSocket handshake.
+
+use std::sync::mpsc;
+
+use posture_ai_oc::api::{self, ApiCommand, ApiState};
+use posture_ai_oc::dashboard::{self, LiveState, Request};
+use posture_ai_oc::engine::EngineStats;
+use posture_ai_oc::websocket;
+
+fn request(raw: &str) -> Request {
+    dashboard::parse_request(raw.as_bytes()).unwrap()
+}
+
+fn state() -> ApiState {
+    let stats = EngineStats { frames: 10, good_frames: 6, bad_frames: 2, alerts: 1 };
+    ApiState::new(LiveState::Slouching, Some(12.5), &stats)
+}
+
+#[test]
+fn state_carries_score_delta_and_counters() {
+    let (commands, _receiver) = mpsc::channel();
+    let response = api::route(&request("GET /state HTTP/1.1\r\nHost: localhost:8766\r\n\r\n"), &state(), &commands);
+    assert_eq!(response.status, 200);
+    let json: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+    assert_eq!(json["state"], "slouching");
+    assert_eq!(json["score"], 75);
+    assert_eq!(json["delta_px"], 12.5);
+    assert_eq!((json["good_frames"].as_u64(), json["alerts"].as_u64()), (Some(6), Some(1)));
+}
+
+#[test]
+fn control_requests_become_commands() {
+    let (commands, receiver) = mpsc::channel();
+    for raw in [
+        "POST /pause HTTP/1.1\r\n\r\n",
+        "POST /pause HTTP/1.1\r\nContent-Length: 14\r\n\r\n{\"minutes\":15}",
+        "POST /resume HTTP/1.1\r\n\r\n",
+        "POST /calibrate HTTP/1.1\r\nOrigin: http://127.0.0.1:3000\r\n\r\n",
+    ] {
+        assert_eq!(api::route(&request(raw), &state(), &commands).status, 200, "{}", raw);
+    }
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [
+        ApiCommand::Pause { minutes: None },
+        ApiCommand::Pause { minutes: Some(15) },
+        ApiCommand::Resume,
+        ApiCommand::Calibrate,
+    ]);
+}
+
+#[test]
+fn bad_or_foreign_requests_are_refused() {
+    let (commands, receiver) = mpsc::channel();
+    let status = |raw: &str| api::route(&request(raw), &state(), &commands).status;
+    assert_eq!(status("POST /pause HTTP/1.1\r\nContent-Length: 13\r\n\r\n{\"minutes\":0}"), 400);
+    assert_eq!(status("POST /pause HTTP/1.1\r\nContent-Length: 2\r\n\r\n15"), 400);
+    assert_eq!(status("POST /calibrate HTTP/1.1\r\nOrigin: https://example.com\r\n\r\n"), 403);
+    assert_eq!(status("POST /calibrate HTTP/1.1\r\nOrigin: http://localhost.example.com\r\n\r\n"), 403);
+    assert_eq!(status("GET /calibrate HTTP/1.1\r\n\r\n"), 405);
+    assert_eq!(status("GET /nothing HTTP/1.1\r\n\r\n"), 404);
+    assert_eq!(receiver.try_iter().count(), 0);
+}
+
+#[test]
+fn only_requests_addressed_to_this_pc_are_served() {
+    assert!(api::host_allowed(&request("GET /state HTTP/1.1\r\nHost: 127.0.0.1:8766\r\n\r\n")));
+    assert!(api::host_allowed(&request("GET /state HTTP/1.1\r\nHost: [::1]:8766\r\n\r\n")));
+    assert!(api::host_allowed(&request("GET /state HTTP/1.1\r\n\r\n")));
+    assert!(!api::host_allowed(&request("GET /state HTTP/1.1\r\nHost: attacker.example:8766\r\n\r\n")));
+    assert!(api::origin_allowed(&request("GET /stream HTTP/1.1\r\nOrigin: null\r\n\r\n")));
+}
+
+#[test]
+fn websocket_handshake_and_frames() {
+    // The example from RFC 6455, section 1.3
+    assert_eq!(websocket::accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+    assert_eq!(websocket::text_frame("Hello"), [0x81, 5, b'H', b'e', b'l', b'l', b'o']);
+    let long = websocket::text_frame(&"x".repeat(300));
+    assert_eq!(&long[..4], [0x81, 126, 0x01, 0x2C]);
+    assert_eq!(long.len(), 4 + 300);
+}
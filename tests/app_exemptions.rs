@@ -0,0 +1,29 @@
+//! App exemptions: matching foreground process names against the configured list.
+
+use posture_ai_oc::app_exemptions::{exemption_for, AppExemption, Exemption};
+use posture_ai_oc::settings::Settings;
+
+#[test]
+fn names_match_case_insensitively_with_or_without_exe() {
+    let apps = [AppExemption::new("vlc.exe", Exemption::Overlay), AppExemption::new("Game", Exemption::Detection)];
+    assert_eq!(exemption_for(&apps, "VLC.EXE"), Some(Exemption::Overlay));
+    assert_eq!(exemption_for(&apps, "vlc"), Some(Exemption::Overlay));
+    assert_eq!(exemption_for(&apps, "game.exe"), Some(Exemption::Detection));
+    assert_eq!(exemption_for(&apps, "vlc-helper.exe"), None);
+    assert_eq!(exemption_for(&apps, "explorer.exe"), None);
+    assert_eq!(exemption_for(&[], "vlc.exe"), None);
+}
+
+#[test]
+fn first_matching_entry_wins() {
+    let apps = [AppExemption::new("Zoom.exe", Exemption::Detection), AppExemption::new("zoom", Exemption::Overlay)];
+    assert_eq!(exemption_for(&apps, "Zoom.exe"), Some(Exemption::Detection));
+}
+
+#[test]
+fn exemptions_are_stored_in_settings() {
+    let settings = Settings { app_exemptions: vec![AppExemption::new("game.exe", Exemption::Detection)], ..Settings::default() };
+    let json = serde_json::to_string(&settings).unwrap();
+    assert!(json.contains(r#""app_exemptions":[{"process":"game.exe","exempt":"detection"}]"#), "{}", json);
+    assert_eq!(serde_json::from_str::<Settings>(&json).unwrap(), settings);
+}
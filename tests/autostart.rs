@@ -0,0 +1,30 @@
+//! Start with Windows: the Run entry's command and when it needs pointing at a moved executable.
+
+use std::path::Path;
+
+use posture_ai_oc::autostart::{command_for, executable_of, needs_migration};
+
+#[test]
+fn command_is_the_quoted_executable_path() {
+    let exe = Path::new(r"C:\Program Files\Posture AI\posture_ai_oc.exe");
+    assert_eq!(command_for(exe), r#""C:\Program Files\Posture AI\posture_ai_oc.exe""#);
+}
+
+#[test]
+fn entry_executable_drops_quotes_and_arguments() {
+    assert_eq!(executable_of(r#" "C:\Apps\Posture AI\posture_ai_oc.exe" --minimized"#), Path::new(r"C:\Apps\Posture AI\posture_ai_oc.exe"));
+    assert_eq!(executable_of(r"C:\Tools\posture_ai_oc.exe --minimized"), Path::new(r"C:\Tools\posture_ai_oc.exe"));
+}
+
+#[test]
+fn only_an_entry_for_a_missing_executable_is_migrated() {
+    let command = r#""C:\Apps\Posture AI\posture_ai_oc.exe""#;
+    let old = r#""C:\Users\me\Downloads\posture_ai_oc.exe""#;
+    let gone = |_: &Path| false;
+    assert!(!needs_migration(None, command, gone));
+    assert!(!needs_migration(Some(command), command, gone));
+    assert!(!needs_migration(Some(r#""c:\apps\posture ai\POSTURE_AI_OC.exe" "#), command, gone));
+    assert!(needs_migration(Some(old), command, gone));
+    // A dev build run from target\debug leaves the installed entry alone
+    assert!(!needs_migration(Some(old), command, |_: &Path| true));
+}
@@ -0,0 +1,31 @@
+//! Overlay exclusion areas: clipping screen rectangles to one monitor's window.
+
+use posture_ai_oc::blur_overlay::ScreenRect;
+
+fn rect(left: i32, top: i32, right: i32, bottom: i32) -> ScreenRect {
+    ScreenRect { left, top, right, bottom }
+}
+
+#[test]
+fn exclusion_inside_a_monitor_becomes_window_local() {
+    let secondary = rect(1920, 0, 3840, 1080);
+    let ticker = rect(1920, 1040, 3840, 1080);
+    assert_eq!(ticker.local_to(&secondary), Some(rect(0, 1040, 1920, 1080)));
+}
+
+#[test]
+fn exclusion_spanning_monitors_is_clipped_to_each() {
+    let primary = rect(0, 0, 1920, 1080);
+    let left_of_primary = rect(-1280, 0, 0, 1024);
+    let spanning = rect(-100, 10, 100, 50);
+    assert_eq!(spanning.local_to(&primary), Some(rect(0, 10, 100, 50)));
+    assert_eq!(spanning.local_to(&left_of_primary), Some(rect(1180, 10, 1280, 50)));
+}
+
+#[test]
+fn exclusion_on_another_monitor_or_empty_is_ignored() {
+    let primary = rect(0, 0, 1920, 1080);
+    assert_eq!(rect(1920, 0, 2000, 100).local_to(&primary), None);
+    assert_eq!(rect(10, 10, 10, 50).local_to(&primary), None);
+    assert!(rect(50, 10, 40, 20).is_empty());
+}
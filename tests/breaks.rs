@@ -0,0 +1,92 @@
+//! Break reminders: eye and stretch breaks by time in view, taken by leaving the camera's view.
+
+use std::time::Duration;
+
+use posture_ai_oc::breaks::{BreakEvent, BreakKind, BreakSettings, BreakTimer};
+use posture_ai_oc::clock::ManualClock;
+use posture_ai_oc::config::{BREAK_SNOOZE_MINS, EYE_BREAK_SECS, STRETCH_BREAK_MINS, STRETCH_PROMPT_SECS};
+
+const STEP: Duration = Duration::from_secs(1);
+
+fn settings(eye: Option<u64>, stretch: Option<u64>) -> BreakSettings {
+    BreakSettings { enabled: true, eye_break_every_mins: eye, stretch_break_every_mins: stretch, ..BreakSettings::default() }
+}
+
+fn feed(timer: &mut BreakTimer, clock: &ManualClock, present: bool, duration: Duration) -> Vec<BreakEvent> {
+    let mut events = Vec::new();
+    let mut elapsed = Duration::ZERO;
+    while elapsed < duration {
+        events.extend(timer.update(present));
+        clock.advance(STEP);
+        elapsed += STEP;
+    }
+    events
+}
+
+fn minutes(minutes: u64) -> Duration {
+    Duration::from_secs(minutes * 60)
+}
+
+#[test]
+fn eye_break_is_due_after_its_interval_and_over_after_its_length() {
+    let clock = ManualClock::new();
+    let mut timer = BreakTimer::new(clock.shared(), &settings(Some(20), None));
+    assert!(feed(&mut timer, &clock, true, minutes(20)).is_empty());
+
+    let events = feed(&mut timer, &clock, true, STEP);
+    assert!(matches!(events[..], [BreakEvent::Due { kind: BreakKind::Eye, .. }]));
+    assert_eq!(timer.prompting(), Some(BreakKind::Eye));
+    assert_eq!(feed(&mut timer, &clock, true, Duration::from_secs(EYE_BREAK_SECS)), [BreakEvent::Over]);
+    assert_eq!(timer.prompting(), None);
+
+    // The next one counts from the end of the last
+    assert!(feed(&mut timer, &clock, true, minutes(19)).is_empty());
+    assert!(matches!(feed(&mut timer, &clock, true, minutes(1))[..], [BreakEvent::Due { kind: BreakKind::Eye, .. }]));
+}
+
+#[test]
+fn stretch_break_reports_the_sitting_time_and_snoozes() {
+    let clock = ManualClock::new();
+    let mut timer = BreakTimer::new(clock.shared(), &settings(None, Some(50)));
+    let events = feed(&mut timer, &clock, true, minutes(50) + STEP);
+    let [BreakEvent::Due { kind: BreakKind::Stretch, sitting_for }] = events[..] else { panic!("{:?}", events) };
+    assert_eq!(sitting_for, minutes(50));
+
+    assert_eq!(feed(&mut timer, &clock, true, Duration::from_secs(STRETCH_PROMPT_SECS)), [BreakEvent::Over]);
+    // Sat through: suggested again after the snooze, with the whole sitting counted
+    let events = feed(&mut timer, &clock, true, minutes(BREAK_SNOOZE_MINS));
+    let [BreakEvent::Due { kind: BreakKind::Stretch, sitting_for }, ..] = events[..] else { panic!("{:?}", events) };
+    assert_eq!(sitting_for, minutes(50 + BREAK_SNOOZE_MINS));
+}
+
+#[test]
+fn getting_up_ends_the_prompt_and_a_long_enough_absence_counts_as_the_break() {
+    let clock = ManualClock::new();
+    let mut timer = BreakTimer::new(clock.shared(), &settings(Some(20), Some(50)));
+    feed(&mut timer, &clock, true, minutes(50) + STEP);
+    assert_eq!(timer.prompting(), Some(BreakKind::Stretch));
+
+    assert_eq!(feed(&mut timer, &clock, false, minutes(STRETCH_BREAK_MINS)), [BreakEvent::Over]);
+    // Both timers start over, so not even an eye break for the next 20 min
+    assert!(feed(&mut timer, &clock, true, minutes(20) - STEP).is_empty());
+}
+
+#[test]
+fn a_short_absence_doesnt_restart_the_stretch_timer() {
+    let clock = ManualClock::new();
+    let mut timer = BreakTimer::new(clock.shared(), &settings(None, Some(50)));
+    feed(&mut timer, &clock, true, minutes(40));
+    feed(&mut timer, &clock, false, minutes(1));
+    let events = feed(&mut timer, &clock, true, minutes(10));
+    assert!(matches!(events[..], [BreakEvent::Due { kind: BreakKind::Stretch, .. }]));
+}
+
+#[test]
+fn disabled_reminders_stay_quiet() {
+    let clock = ManualClock::new();
+    let disabled = BreakSettings { enabled: false, ..settings(Some(20), Some(50)) };
+    let mut timer = BreakTimer::new(clock.shared(), &disabled);
+    assert!(feed(&mut timer, &clock, true, minutes(120)).is_empty());
+    let mut timer = BreakTimer::new(clock.shared(), &settings(Some(0), None));
+    assert!(feed(&mut timer, &clock, true, minutes(120)).is_empty());
+}
@@ -0,0 +1,30 @@
+//! Video calls: matching webcam and microphone users against the conferencing apps.
+
+use posture_ai_oc::calls::call_app;
+use posture_ai_oc::settings::Settings;
+
+fn apps(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn call_apps_match_case_insensitively() {
+    let users = apps(&["obs64.exe", "zoom.exe"]);
+    assert_eq!(call_app(&users, &apps(&["Zoom.exe", "Teams.exe"])), Some("zoom.exe"));
+    assert_eq!(call_app(&users, &apps(&["Teams.exe"])), None);
+    assert_eq!(call_app(&[], &apps(&["Zoom.exe"])), None);
+}
+
+#[test]
+fn store_apps_match_without_publisher_suffix() {
+    let users = apps(&["MSTeams_8wekyb3d8bbwe"]);
+    assert_eq!(call_app(&users, &apps(&["MSTeams"])), Some("MSTeams_8wekyb3d8bbwe"));
+    assert_eq!(call_app(&users, &apps(&["Teams"])), None);
+}
+
+#[test]
+fn browsers_count_by_default_for_web_calls() {
+    let settings = Settings::default();
+    assert!(settings.pause_during_calls);
+    assert_eq!(call_app(&apps(&["chrome.exe"]), &settings.call_apps), Some("chrome.exe"));
+}
@@ -0,0 +1,85 @@
+//! Camera selection: picking a fallback while the chosen camera is busy, the formats asked for
+//! and decoding the raw frames virtual cameras send.
+
+use image::Rgb;
+use nokhwa::utils::{CameraFormat, FrameFormat, RequestedFormatType};
+use posture_ai_oc::camera::{
+    convert_raw, fallback_camera, format_fallbacks, is_virtual, requested_format, CaptureOptions, ControlSetting,
+};
+
+fn cameras() -> Vec<(u32, String)> {
+    vec![(0, "Integrated Webcam".to_string()), (1, "Logitech BRIO".to_string()), (2, "OBS Virtual Camera".to_string())]
+}
+
+#[test]
+fn first_connected_match_in_preference_order_wins() {
+    let wanted = ["missing".to_string(), "obs".to_string(), "logitech".to_string()];
+    assert_eq!(fallback_camera(&cameras(), &wanted, 0), Some(2));
+}
+
+#[test]
+fn busy_camera_is_never_its_own_fallback() {
+    let wanted = ["webcam".to_string()];
+    assert_eq!(fallback_camera(&cameras(), &wanted, 0), None);
+    assert_eq!(fallback_camera(&cameras(), &wanted, 1), Some(0));
+}
+
+#[test]
+fn no_fallback_without_configured_names() {
+    assert_eq!(fallback_camera(&cameras(), &[], 0), None);
+    assert_eq!(fallback_camera(&cameras(), &[String::new()], 0), None);
+}
+
+#[test]
+fn requested_format_follows_the_capture_options() {
+    let options = CaptureOptions { resolution: Some((1280, 720)), fps: 15, exposure: ControlSetting::Auto };
+    assert_eq!(
+        requested_format(&options),
+        RequestedFormatType::Closest(CameraFormat::new_from(1280, 720, FrameFormat::MJPEG, 15))
+    );
+    let fastest = CaptureOptions { resolution: None, ..options };
+    assert_eq!(requested_format(&fastest), RequestedFormatType::AbsoluteHighestFrameRate);
+}
+
+#[test]
+fn formats_fall_back_to_whatever_the_camera_offers() {
+    let options = CaptureOptions { resolution: Some((640, 480)), fps: 30, exposure: ControlSetting::Auto };
+    let formats = format_fallbacks(&options);
+    assert_eq!(formats[0], requested_format(&options));
+    assert_eq!(formats[1], RequestedFormatType::Closest(CameraFormat::new_from(640, 480, FrameFormat::YUYV, 30)));
+    assert_eq!(formats.last(), Some(&RequestedFormatType::None));
+
+    let fastest = format_fallbacks(&CaptureOptions { resolution: None, ..options });
+    assert_eq!(fastest, [RequestedFormatType::AbsoluteHighestFrameRate, RequestedFormatType::None]);
+}
+
+#[test]
+fn virtual_cameras_are_recognized_by_name() {
+    assert!(is_virtual("OBS Virtual Camera"));
+    assert!(is_virtual("droidcam source 3"));
+    assert!(!is_virtual("Logitech BRIO"));
+}
+
+#[test]
+fn padded_yuyv_rows_decode() {
+    // 2x2 frame, each 4-byte row padded to 8 bytes: white pair on top, black pair below
+    let data = [235, 128, 235, 128, 0, 0, 0, 0, 16, 128, 16, 128, 0, 0, 0, 0];
+    let frame = convert_raw(&data, 2, 2, FrameFormat::YUYV).unwrap();
+    assert_eq!(frame.get_pixel(1, 0), &Rgb([255, 255, 255]));
+    assert_eq!(frame.get_pixel(0, 1), &Rgb([0, 0, 0]));
+}
+
+#[test]
+fn nv12_reads_the_chroma_plane_after_the_luma() {
+    // 2x2 luma, then one row of interleaved U/V: pure red-ish chroma
+    let data = [82, 82, 82, 82, 90, 240];
+    let frame = convert_raw(&data, 2, 2, FrameFormat::NV12).unwrap();
+    let Rgb([r, g, b]) = *frame.get_pixel(1, 1);
+    assert!(r > 200 && g < 30 && b < 30, "{:?}", (r, g, b));
+}
+
+#[test]
+fn short_frames_are_rejected() {
+    assert_eq!(convert_raw(&[0; 5], 2, 2, FrameFormat::RAWRGB), None);
+    assert_eq!(convert_raw(&[0; 16], 2, 2, FrameFormat::MJPEG), None);
+}
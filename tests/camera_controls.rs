@@ -0,0 +1,66 @@
+//! Camera control presets and per-camera profiles.
+
+use std::fs;
+
+use nokhwa::utils::KnownCameraControl;
+use posture_ai_oc::camera_controls::{CameraProfiles, ControlLevel, ControlRange};
+use posture_ai_oc::tray::{self, TrayAction};
+use tray_icon::menu::MenuId;
+
+const BRIGHTNESS: ControlRange = ControlRange { min: -64, max: 64, step: 1, default: 0 };
+const ZOOM: ControlRange = ControlRange { min: 100, max: 500, step: 10, default: 100 };
+
+#[test]
+fn presets_span_the_range_and_snap_to_the_step() {
+    assert_eq!(BRIGHTNESS.value(ControlLevel::Percent(0)), -64);
+    assert_eq!(BRIGHTNESS.value(ControlLevel::Percent(50)), 0);
+    assert_eq!(BRIGHTNESS.value(ControlLevel::Percent(100)), 64);
+    assert_eq!(BRIGHTNESS.value(ControlLevel::Default), 0);
+
+    // 25% of 100..500 is 200; 75% is 400 - both already on the 10-step grid
+    assert_eq!(ZOOM.value(ControlLevel::Percent(25)), 200);
+    let odd_step = ControlRange { step: 30, ..ZOOM };
+    assert_eq!(odd_step.value(ControlLevel::Percent(25)), 190);
+}
+
+#[test]
+fn saved_values_map_back_to_presets() {
+    assert_eq!(ZOOM.level_of(None), Some(ControlLevel::Default));
+    assert_eq!(ZOOM.level_of(Some(300)), Some(ControlLevel::Percent(50)));
+    assert_eq!(ZOOM.level_of(Some(310)), None);
+}
+
+#[test]
+fn profiles_are_kept_per_camera() {
+    let dir = std::env::temp_dir().join(format!("posture_ai_camera_controls_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("camera_controls.json");
+
+    let mut profiles = CameraProfiles::default();
+    profiles.set("Logitech C920", KnownCameraControl::Zoom, Some(200));
+    profiles.set("Integrated Camera", KnownCameraControl::Brightness, Some(32));
+    profiles.save_to(&path).unwrap();
+
+    let mut loaded = CameraProfiles::load_from(&path);
+    assert_eq!(loaded, profiles);
+    assert_eq!(loaded.value("Logitech C920", KnownCameraControl::Zoom), Some(200));
+    assert_eq!(loaded.value("Integrated Camera", KnownCameraControl::Zoom), None);
+
+    // Back to the driver default drops the override, and the camera once it has none left
+    loaded.set("Logitech C920", KnownCameraControl::Zoom, None);
+    assert!(!loaded.cameras.contains_key("Logitech C920"));
+}
+
+#[test]
+fn tray_ids_round_trip() {
+    let action = |id: &str| tray::action(&MenuId(id.to_string()));
+    assert_eq!(
+        action("control:zoom:75"),
+        Some(TrayAction::SetCameraControl(KnownCameraControl::Zoom, ControlLevel::Percent(75)))
+    );
+    assert_eq!(
+        action("control:white_balance:default"),
+        Some(TrayAction::SetCameraControl(KnownCameraControl::WhiteBalance, ControlLevel::Default))
+    );
+    assert_eq!(action("control:exposure:50"), None);
+}
@@ -0,0 +1,83 @@
+//! Canvas primitives and text rendering with the embedded bitmap font.
+
+use posture_ai_oc::canvas::Canvas;
+use posture_ai_oc::font;
+
+const WIDTH: usize = 64;
+const HEIGHT: usize = 32;
+const WHITE: u32 = 0xFFFFFF;
+const BACKGROUND: u32 = 0x123456;
+
+fn lit(buffer: &[u32], x: usize, y: usize) -> bool {
+    buffer[y * WIDTH + x] == WHITE
+}
+
+#[test]
+fn glyphs_are_scaled_and_shadowed() {
+    let mut buffer = vec![BACKGROUND; WIDTH * HEIGHT];
+    let mut canvas = Canvas { buffer: &mut buffer, width: WIDTH, height: HEIGHT };
+    canvas.draw_text("-", 0, 0, 2, WHITE);
+
+    // '-' is row 3, columns 0..6 of the glyph
+    assert!(lit(&buffer, 0, 6) && lit(&buffer, 1, 7) && lit(&buffer, 11, 7));
+    assert!(!lit(&buffer, 12, 6), "column 6 of the glyph is empty");
+    assert!(!lit(&buffer, 0, 5) && !lit(&buffer, 0, 8));
+    // Shadow one font pixel down and right
+    assert_eq!(buffer[9 * WIDTH + 12], 0x000000);
+    assert_eq!(Canvas::text_width("Delta", 2), 80);
+}
+
+#[test]
+fn text_is_clipped_at_the_canvas_edge() {
+    let mut buffer = vec![BACKGROUND; WIDTH * HEIGHT];
+    let mut canvas = Canvas { buffer: &mut buffer, width: WIDTH, height: HEIGHT };
+    canvas.draw_text("BAD POSTURE", WIDTH as i32 - 10, HEIGHT as i32 - 4, 3, WHITE);
+    canvas.draw_text("x", -20, -20, 1, WHITE);
+}
+
+#[test]
+fn every_translated_character_has_a_glyph() {
+    let missing = font::glyph('\u{2603}');
+    for locale in ["en", "de", "ru"] {
+        let text = std::fs::read_to_string(format!("locales/{}.txt", locale)).unwrap();
+        for c in text.chars().filter(|c| !c.is_control()) {
+            assert_ne!(font::glyph(c), missing, "no glyph for {:?} ({})", c, locale);
+        }
+    }
+}
+
+#[test]
+fn rectangles_are_outlined_filled_and_clipped() {
+    let mut buffer = vec![BACKGROUND; WIDTH * HEIGHT];
+    let mut canvas = Canvas { buffer: &mut buffer, width: WIDTH, height: HEIGHT };
+    canvas.draw_rect(2, 2, 5, 4, WHITE);
+    canvas.fill_rect(50, 20, 40, 40, WHITE);
+
+    assert!(lit(&buffer, 2, 2) && lit(&buffer, 6, 2) && lit(&buffer, 2, 5) && lit(&buffer, 6, 5));
+    assert!(!lit(&buffer, 4, 3), "outline only");
+    assert!(!lit(&buffer, 7, 2), "width includes the border");
+    assert!(lit(&buffer, 50, 20) && lit(&buffer, WIDTH - 1, HEIGHT - 1));
+    assert!(!lit(&buffer, 49, 20));
+}
+
+#[test]
+fn circles_and_blending() {
+    let mut buffer = vec![BACKGROUND; WIDTH * HEIGHT];
+    let mut canvas = Canvas { buffer: &mut buffer, width: WIDTH, height: HEIGHT };
+    canvas.draw_circle(10, 10, 5, WHITE);
+    canvas.fill_circle(40, 10, 5, WHITE);
+
+    assert!(lit(&buffer, 15, 10) && lit(&buffer, 10, 5));
+    assert!(!lit(&buffer, 10, 10), "outline leaves the center");
+    assert!(lit(&buffer, 40, 10) && lit(&buffer, 43, 13));
+    assert!(!lit(&buffer, 45, 15), "corner is outside the radius");
+
+    let mut canvas = Canvas { buffer: &mut buffer, width: WIDTH, height: HEIGHT };
+    canvas.fill_rect(0, 30, 4, 2, 0x000000);
+    canvas.plot_blend(0, 30, 0xFF8040, 0.5);
+    canvas.fill_rect_blended(1, 30, 1, 1, 0xFFFFFF, 1.0);
+    canvas.plot_blend(2, 30, 0xFFFFFF, 0.0);
+    assert_eq!(buffer[30 * WIDTH], 0x7F4020);
+    assert_eq!(buffer[30 * WIDTH + 1], 0xFFFFFF);
+    assert_eq!(buffer[30 * WIDTH + 2], 0x000000);
+}
@@ -0,0 +1,73 @@
+//! Command-line parsing.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use posture_ai_oc::cli::Cli;
+use posture_ai_oc::logging::LogLevel;
+use posture_ai_oc::settings::Overrides;
+
+#[test]
+fn no_arguments_starts_the_tray_app() {
+    let cli = Cli::try_parse_from(["posture_ai_oc"]).unwrap();
+    assert!(!cli.status && !cli.headless);
+    assert_eq!(cli.input, None);
+}
+
+#[test]
+fn headless_and_status_are_flags() {
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--headless"]).unwrap().headless);
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--status"]).unwrap().status);
+}
+
+#[test]
+fn headless_and_status_cannot_be_combined() {
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--headless", "--status"]).is_err());
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--unknown"]).is_err());
+}
+
+#[test]
+fn input_takes_a_path_and_an_optional_frame_rate() {
+    let cli = Cli::try_parse_from(["posture_ai_oc", "--input", "clip.mp4"]).unwrap();
+    assert_eq!(cli.input, Some(PathBuf::from("clip.mp4")));
+    assert_eq!(cli.fps, 30);
+    assert_eq!(Cli::try_parse_from(["posture_ai_oc", "--input", "frames", "--fps", "15"]).unwrap().fps, 15);
+}
+
+#[test]
+fn frame_rate_needs_an_input_and_input_excludes_other_modes() {
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--fps", "15"]).is_err());
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--input", "frames", "--fps", "0"]).is_err());
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--input", "frames", "--headless"]).is_err());
+}
+
+#[test]
+fn launch_options_override_the_settings() {
+    let cli = Cli::try_parse_from([
+        "posture_ai_oc", "--config", "work.json", "--camera", "1", "--model", "custom.onnx", "--no-overlay", "--log-level", "debug",
+    ])
+    .unwrap();
+    assert_eq!(cli.config, Some(PathBuf::from("work.json")));
+    assert_eq!(cli.model, Some(PathBuf::from("custom.onnx")));
+    assert_eq!(cli.overrides(), Overrides { camera_index: Some(1), overlay_off: true, log_level: Some(LogLevel::Debug) });
+    assert_eq!(Cli::try_parse_from(["posture_ai_oc"]).unwrap().overrides(), Overrides::default());
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--log-level", "loud"]).is_err());
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--camera", "-1"]).is_err());
+}
+
+#[test]
+fn list_cameras_is_a_mode_of_its_own() {
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--list-cameras"]).unwrap().list_cameras);
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--list-cameras", "--headless"]).is_err());
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--headless", "--camera", "0", "--no-overlay"]).is_ok());
+}
+
+#[test]
+fn probe_takes_an_optional_image() {
+    assert_eq!(Cli::try_parse_from(["posture_ai_oc"]).unwrap().probe, None);
+    assert_eq!(Cli::try_parse_from(["posture_ai_oc", "--probe"]).unwrap().probe, Some(None));
+    let cli = Cli::try_parse_from(["posture_ai_oc", "--probe", "me.jpg", "--model", "custom.onnx"]).unwrap();
+    assert_eq!(cli.probe, Some(Some(PathBuf::from("me.jpg"))));
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--probe", "--list-cameras"]).is_err());
+    assert!(Cli::try_parse_from(["posture_ai_oc", "--probe", "--status"]).is_err());
+}
@@ -0,0 +1,39 @@
+//! Crash-loop detection through the launch marker file.
+
+use std::fs;
+
+use posture_ai_oc::config::SAFE_MODE_AFTER_CRASHES;
+use posture_ai_oc::crash_guard::LaunchMarker;
+
+#[test]
+fn crashed_launches_add_up_until_a_clean_exit() {
+    let dir = std::env::temp_dir().join(format!("posture_ai_crash_guard_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("running.marker");
+    let _ = fs::remove_file(&path);
+
+    // A clean run leaves nothing behind
+    let marker = LaunchMarker::begin_at(&path).unwrap();
+    assert_eq!(marker.unclean_launches(), 0);
+    assert!(path.exists());
+    drop(marker);
+    assert!(!path.exists());
+
+    // Crashes skip the cleanup, so the marker stays and counts up
+    for crashes in 0..SAFE_MODE_AFTER_CRASHES {
+        let marker = LaunchMarker::begin_at(&path).unwrap();
+        assert_eq!(marker.unclean_launches(), crashes);
+        assert!(!marker.needs_safe_mode());
+        std::mem::forget(marker);
+    }
+    let marker = LaunchMarker::begin_at(&path).unwrap();
+    assert!(marker.needs_safe_mode());
+
+    // The safe-mode launch exiting cleanly resets the count
+    drop(marker);
+    let marker = LaunchMarker::begin_at(&path).unwrap();
+    assert_eq!(marker.unclean_launches(), 0);
+    drop(marker);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,69 @@
+//! Web dashboard: request parsing, settings edits from the page and the chart data.
+
+use posture_ai_oc::blur_overlay::OverlayEffect;
+use posture_ai_oc::dashboard::{self, DayTotals, SettingsForm, Summary};
+use posture_ai_oc::history::SecondRow;
+use posture_ai_oc::settings::{SettingChange, Settings};
+
+// 2024-03-01 00:00:00 UTC
+const FRIDAY_SECS: i64 = 1_709_251_200;
+
+fn row(second: i64, bad: bool) -> SecondRow {
+    SecondRow { second, frames: 10, bad_frames: if bad { 10 } else { 0 }, alerting: false }
+}
+
+#[test]
+fn requests_are_parsed_once_complete() {
+    let raw = b"POST /api/settings?token=abc&x=1 HTTP/1.1\r\nHost: pc\r\ncontent-length: 4\r\n\r\n{}\r\n";
+    // Headers or body still on the way
+    assert_eq!(dashboard::parse_request(&raw[..30]), None);
+    assert_eq!(dashboard::parse_request(&raw[..raw.len() - 1]), None);
+
+    let request = dashboard::parse_request(raw).unwrap();
+    assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/api/settings"));
+    assert_eq!(request.token.as_deref(), Some("abc"));
+    assert_eq!(request.body, b"{}\r\n");
+
+    let request = dashboard::parse_request(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+    assert_eq!((request.path.as_str(), request.token), ("/", None));
+}
+
+#[test]
+fn the_token_is_read_from_the_cookie() {
+    let request = dashboard::parse_request(b"GET /api/status HTTP/1.1\r\nCookie: theme=dark; posture_token=abc\r\n\r\n").unwrap();
+    assert_eq!(request.cookie("posture_token"), Some("abc"));
+    assert_eq!(request.cookie("posture"), None);
+
+    assert!(dashboard::token_matches("abc", "abc"));
+    assert!(!dashboard::token_matches("abd", "abc"));
+    assert!(!dashboard::token_matches("ab", "abc"));
+}
+
+#[test]
+fn page_edits_become_setting_changes() {
+    let settings = Settings::default();
+    let form = SettingsForm::from_settings(&settings);
+    assert_eq!(form.changes().len(), 5);
+
+    let form: SettingsForm = serde_json::from_str(r#"{ "sensitivity_px": 12, "overlay_effect": "dim" }"#).unwrap();
+    assert_eq!(form.changes(), [SettingChange::Sensitivity(12.0), SettingChange::OverlayEffect(OverlayEffect::Dim)]);
+    // Only the offered settings can be changed
+    assert!(serde_json::from_str::<SettingsForm>(r#"{ "camera_index": 2 }"#).is_err());
+}
+
+#[test]
+fn summary_scores_today_and_charts_the_week() {
+    let now = FRIDAY_SECS + 12 * 3600;
+    let mut rows: Vec<SecondRow> = (0..100).map(|i| row(FRIDAY_SECS - 2 * 86_400 + 9 * 3600 + i, i < 50)).collect();
+    rows.extend((0..100).map(|i| row(FRIDAY_SECS + 10 * 3600 + i, i < 20)));
+
+    let summary = Summary::from_rows(&rows, now, 0);
+    assert_eq!(summary.score, Some(80));
+    assert_eq!(summary.slouch_percent_by_hour[10], Some(20));
+    assert_eq!(summary.slouch_percent_by_hour[9], None);
+    assert_eq!(summary.days.len(), 7);
+    assert_eq!(summary.days[4], DayTotals { date: "2024-02-28".to_string(), monitored_secs: 100, slouching_secs: 50 });
+    assert_eq!(summary.days[6].date, "2024-03-01");
+
+    assert_eq!(Summary::from_rows(&[], now, 0).score, None);
+}
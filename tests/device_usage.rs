@@ -0,0 +1,25 @@
+//! Device usage: reading webcam users from consent store keys.
+
+use std::path::Path;
+
+use posture_ai_oc::device_usage::{app_name, is_in_use, key_for_exe};
+
+#[test]
+fn entries_are_in_use_until_stopped() {
+    assert!(is_in_use(133_500_000_000_000_000, 0));
+    assert!(!is_in_use(133_500_000_000_000_000, 133_500_000_100_000_000));
+    assert!(!is_in_use(0, 0));
+}
+
+#[test]
+fn desktop_apps_are_named_by_executable() {
+    assert_eq!(app_name("C:#Program Files#Zoom#bin#Zoom.exe"), "Zoom.exe");
+    assert_eq!(app_name("Microsoft.WindowsCamera_8wekyb3d8bbwe"), "Microsoft.WindowsCamera_8wekyb3d8bbwe");
+}
+
+#[test]
+fn own_executable_maps_to_its_key() {
+    let key = key_for_exe(Path::new(r"C:\Tools\PostureAI\posture_ai_oc.exe"));
+    assert_eq!(key, "C:#Tools#PostureAI#posture_ai_oc.exe");
+    assert_eq!(app_name(&key), "posture_ai_oc.exe");
+}
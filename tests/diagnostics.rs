@@ -0,0 +1,79 @@
+//! "What's Wrong?" checks against scripted app states.
+
+use std::time::Duration;
+
+use posture_ai_oc::clock::ManualClock;
+use posture_ai_oc::diagnostics::{run_checks, FrameHealth, Severity, Snapshot};
+use posture_ai_oc::i18n;
+use posture_ai_oc::pose::{Keypoint, Pose, KEYPOINT_COUNT};
+
+fn pose(score: f32) -> Pose {
+    [Keypoint { x: 0.5, y: 0.5, score }; KEYPOINT_COUNT]
+}
+
+/// A running app with `frames` recorded at 20 fps
+fn snapshot(clock: &ManualClock, frames: &[Option<Pose>]) -> Snapshot {
+    i18n::load("en");
+    let mut health = FrameHealth::new(clock.shared());
+    for frame in frames {
+        clock.advance(Duration::from_millis(50));
+        health.record(frame.as_ref(), Duration::from_millis(30));
+    }
+    Snapshot {
+        model_name: "movenet.onnx".to_string(),
+        camera_open: true,
+        frames: health.report(),
+        overlay_enabled: true,
+        overlay_ready: true,
+        recorder: None,
+        calibrated: true,
+        paused: false,
+        location_allowed: true,
+        active_hours: None,
+        work_schedule: None,
+    }
+}
+
+fn worst(snapshot: &Snapshot) -> Severity {
+    run_checks(snapshot).iter().map(|f| f.severity).max().unwrap()
+}
+
+#[test]
+fn healthy_app_reports_no_issues() {
+    let clock = ManualClock::new();
+    let snapshot = snapshot(&clock, &vec![Some(pose(0.8)); 40]);
+    assert_eq!(worst(&snapshot), Severity::Ok);
+    assert!(snapshot.frames.fps.is_some_and(|fps| (fps - 20.0).abs() < 0.5));
+}
+
+#[test]
+fn stalled_camera_is_a_problem() {
+    let clock = ManualClock::new();
+    let snapshot_before = snapshot(&clock, &vec![Some(pose(0.8)); 10]);
+    assert_eq!(worst(&snapshot_before), Severity::Ok);
+
+    let clock = ManualClock::new();
+    let mut health = FrameHealth::new(clock.shared());
+    health.record(Some(&pose(0.8)), Duration::from_millis(30));
+    clock.advance(Duration::from_secs(10));
+    let stalled = Snapshot { frames: health.report(), ..snapshot_before };
+    assert_eq!(worst(&stalled), Severity::Problem);
+}
+
+#[test]
+fn missing_or_uncertain_person_is_reported() {
+    let clock = ManualClock::new();
+    let mostly_empty: Vec<Option<Pose>> = (0..40).map(|i| (i % 4 == 0).then(|| pose(0.8))).collect();
+    assert_eq!(worst(&snapshot(&clock, &mostly_empty)), Severity::Problem);
+
+    let uncertain = snapshot(&clock, &vec![Some(pose(0.25)); 40]);
+    assert_eq!(worst(&uncertain), Severity::Warning);
+}
+
+#[test]
+fn camera_checks_are_skipped_while_paused() {
+    let clock = ManualClock::new();
+    let paused = Snapshot { paused: true, camera_open: false, ..snapshot(&clock, &[]) };
+    let findings = run_checks(&paused);
+    assert_eq!(findings.iter().map(|f| f.severity).max(), Some(Severity::Warning));
+}
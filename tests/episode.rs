@@ -0,0 +1,65 @@
+//! Alert episode recording: buffered frames before the alert, a fixed number after it.
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use image::RgbImage;
+use posture_ai_oc::episode::EpisodeRecorder;
+use posture_ai_oc::pipeline::FrameMeta;
+use posture_ai_oc::pose::Flip;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("posture_ai_episode_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn meta(sequence: u64) -> FrameMeta {
+    FrameMeta { source: "camera:0".to_string(), sequence, captured_at: Instant::now(), rotation_degrees: 0, flip: Flip::default(), brightness: 0.0 }
+}
+
+fn push_frames(recorder: &mut EpisodeRecorder, sequences: std::ops::RangeInclusive<u64>) {
+    for sequence in sequences {
+        recorder.push(&RgbImage::new(640, 480), &meta(sequence)).unwrap();
+    }
+}
+
+fn saved(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().file_name().into_string().unwrap()).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn an_episode_holds_the_frames_before_and_after_the_alert() {
+    let recordings = temp_dir("window");
+    let mut recorder = EpisodeRecorder::new(2, 3, 320);
+    push_frames(&mut recorder, 1..=5);
+
+    let dir = recorder.start(&recordings, 1_000).unwrap();
+    assert_eq!(dir.file_name().unwrap(), "episode-1000");
+    assert!(recorder.is_recording());
+    push_frames(&mut recorder, 6..=10);
+    assert!(!recorder.is_recording());
+
+    let names = saved(&dir);
+    assert_eq!(names.first().map(String::as_str), Some("frame-00000004.png"));
+    assert_eq!(names.last().map(String::as_str), Some("frame-00000008.png"));
+    assert_eq!(names.len(), 5);
+    // Frames are scaled down to the configured width
+    assert_eq!(image::open(dir.join(&names[0])).unwrap().width(), 320);
+}
+
+#[test]
+fn nothing_is_written_without_an_alert() {
+    let recordings = temp_dir("idle");
+    let mut recorder = EpisodeRecorder::new(5, 5, 320);
+    push_frames(&mut recorder, 1..=20);
+    assert!(saved(&recordings).is_empty());
+
+    // Cleared buffers don't end up in the next episode
+    recorder.clear();
+    let dir = recorder.start(&recordings, 2_000).unwrap();
+    assert!(saved(&dir).is_empty());
+}
@@ -0,0 +1,25 @@
+//! Library error categories: matching on them and keeping them through `anyhow`.
+
+use anyhow::anyhow;
+use posture_ai_oc::error::PostureError;
+use posture_ai_oc::model_download;
+
+#[test]
+fn failures_are_reported_by_category() {
+    let mismatch = model_download::verify(b"abc", &"0".repeat(64)).unwrap_err();
+    assert!(matches!(mismatch, PostureError::Model(_)));
+    assert!(mismatch.to_string().starts_with("Model error: Checksum mismatch"));
+}
+
+#[test]
+fn category_survives_conversion_to_anyhow() {
+    let error: anyhow::Error = PostureError::camera(anyhow!("device lost")).into();
+    assert!(matches!(error.downcast_ref::<PostureError>(), Some(PostureError::Camera(_))));
+}
+
+#[test]
+fn message_includes_the_cause() {
+    let error = PostureError::overlay(anyhow!("no monitor").context("Failed to create window"));
+    assert_eq!(error.to_string(), "Overlay error: Failed to create window: no monitor");
+    assert_eq!(PostureError::Config("MAX_ALPHA".to_string()).to_string(), "Invalid configuration: MAX_ALPHA");
+}
@@ -0,0 +1,46 @@
+//! Exit codes and the `--status` report.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use posture_ai_oc::config;
+use posture_ai_oc::exit_status::{self, preflight, Failure, StatusReport};
+
+#[test]
+fn failures_keep_their_code_through_context() {
+    let direct: Result<()> = Err(Failure::AlreadyRunning.into());
+    assert_eq!(exit_status::exit_code(&direct), exit_status::EXIT_ALREADY_RUNNING);
+
+    let wrapped: Result<()> = Err(anyhow!("device lost")).context(Failure::CameraMissing);
+    assert_eq!(exit_status::exit_code(&wrapped), exit_status::EXIT_CAMERA_MISSING);
+
+    let other: Result<()> = Err(anyhow!("inference failed"));
+    assert_eq!(exit_status::exit_code(&other), exit_status::EXIT_ERROR);
+    assert_eq!(exit_status::exit_code(&Ok(())), exit_status::EXIT_OK);
+}
+
+#[test]
+fn preflight_reports_the_first_problem() {
+    assert_eq!(config::validate(), Ok(()));
+
+    let missing = preflight(Path::new("no-such-model.onnx"), 1);
+    assert_eq!(missing, Err(Failure::ModelMissing("no-such-model.onnx".to_string())));
+    assert_eq!(preflight(Path::new("Cargo.toml"), 0), Err(Failure::CameraMissing));
+    assert_eq!(preflight(Path::new("Cargo.toml"), 2), Ok(()));
+}
+
+#[test]
+fn status_report_codes() {
+    let running = StatusReport::new(true, Err(Failure::CameraMissing));
+    assert_eq!(running.exit_code, exit_status::EXIT_OK);
+    assert_eq!(running.problem, None);
+
+    let idle = StatusReport::new(false, Ok(()));
+    assert_eq!(idle.exit_code, exit_status::EXIT_NOT_RUNNING);
+    assert_eq!(idle.to_json(), r#"{"running":false,"problem":null,"message":null,"exit_code":2}"#);
+
+    let broken = StatusReport::new(false, Err(Failure::ConfigInvalid("MAX_ALPHA".to_string())));
+    assert_eq!(broken.exit_code, exit_status::EXIT_CONFIG_INVALID);
+    assert_eq!(broken.problem, Some("config_invalid"));
+    assert_eq!(broken.message.as_deref(), Some("Invalid configuration: MAX_ALPHA"));
+}
@@ -0,0 +1,100 @@
+//! False-positive feedback: tagging alerts, per-rule rates and the thresholds they loosen.
+
+use posture_ai_oc::alert_browser::{self, AlertTracker};
+use posture_ai_oc::config::{FALSE_POSITIVE_LOOSEN_PERCENT, FALSE_POSITIVE_MIN_ALERTS};
+use posture_ai_oc::engine::AlertRule;
+use posture_ai_oc::feedback::{self, RuleStats};
+use posture_ai_oc::goals::{Suggestion, WeekStats, WeeklyReview};
+use posture_ai_oc::history::AlertRecord;
+use posture_ai_oc::i18n;
+use posture_ai_oc::settings::{SettingChange, Settings};
+
+// 2024-03-01 09:00:00 UTC
+const MORNING_MS: i64 = 1_709_283_600_000;
+
+fn alert(rule: &str, false_positive: bool) -> AlertRecord {
+    AlertRecord {
+        rule: rule.to_string(),
+        slouch_started_ms: MORNING_MS - 500,
+        started_ms: MORNING_MS,
+        ended_ms: MORNING_MS + 4_000,
+        peak_delta: None,
+        snapshot: None,
+        false_positive,
+    }
+}
+
+fn eye_drop(alerts: u32, false_positives: u32) -> RuleStats {
+    RuleStats { rule: AlertRule::EyeDrop.key().to_string(), alerts, false_positives }
+}
+
+#[test]
+fn tagged_alerts_are_counted_per_rule() {
+    // Tagged while on screen
+    let mut tracker = AlertTracker::new();
+    assert!(!tracker.mark_false_positive());
+    tracker.start("eye_drop", MORNING_MS, 500, None);
+    assert!(tracker.mark_false_positive());
+    let tagged = tracker.finish(MORNING_MS + 1_000).unwrap();
+    assert!(tagged.false_positive);
+    // Once it ended, the tag goes to the stored alert instead
+    assert!(!tracker.mark_false_positive());
+
+    let alerts = [tagged, alert("eye_drop", true), alert("neck_angle", false), alert("eye_drop", false)];
+    let neck = RuleStats { rule: "neck_angle".to_string(), alerts: 1, false_positives: 0 };
+    assert_eq!(feedback::per_rule(&alerts), [eye_drop(3, 2), neck]);
+    assert_eq!(eye_drop(3, 2).false_positive_percent(), 66);
+    assert_eq!(eye_drop(0, 0).false_positive_percent(), 0);
+}
+
+#[test]
+fn frequent_false_alarms_suggest_a_looser_rule() {
+    let settings = Settings { sensitivity_px: 10.0, ..Settings::default() };
+    let min = FALSE_POSITIVE_MIN_ALERTS;
+    let wrong = (min * FALSE_POSITIVE_LOOSEN_PERCENT).div_ceil(100);
+
+    assert_eq!(feedback::suggest(&[eye_drop(min, wrong)], &settings), [Suggestion::FalseAlarms {
+        rule: AlertRule::EyeDrop,
+        percent: wrong * 100 / min,
+        from: 10.0,
+        to: 12.0,
+    }]);
+    // Too few wrong, too few alerts, or a rule this version doesn't know
+    assert!(feedback::suggest(&[eye_drop(min * 10, wrong)], &settings).is_empty());
+    assert!(feedback::suggest(&[eye_drop(min - 1, min - 1)], &settings).is_empty());
+    let unknown = RuleStats { rule: "future_rule".to_string(), alerts: min, false_positives: min };
+    assert!(feedback::suggest(&[unknown], &settings).is_empty());
+}
+
+#[test]
+fn neck_false_alarms_loosen_the_neck_angle() {
+    let min = FALSE_POSITIVE_MIN_ALERTS;
+    let neck = RuleStats { rule: AlertRule::NeckAngle.key().to_string(), alerts: min, false_positives: min };
+    let settings = Settings { neck_angle_degrees: Some(25.0), ..Settings::default() };
+    let suggestions = feedback::suggest(std::slice::from_ref(&neck), &settings);
+    assert_eq!(suggestions, [Suggestion::FalseAlarms { rule: AlertRule::NeckAngle, percent: 100, from: 25.0, to: 30.0 }]);
+    assert_eq!(suggestions[0].change(), SettingChange::NeckAngle(30.0));
+
+    // Nothing to loosen while the check is off
+    let off = Settings { neck_angle_degrees: None, ..Settings::default() };
+    assert!(feedback::suggest(&[neck], &off).is_empty());
+}
+
+#[test]
+fn feedback_overrides_the_review_and_shows_in_the_alert_history() {
+    i18n::load("en");
+    // Goal beaten easily with a loosened threshold would suggest tightening it...
+    let settings = Settings { sensitivity_px: 14.0, ..Settings::default() };
+    let week = WeekStats { monitored_secs: 20 * 3600, slouching_secs: 0, alerts: 10, days_monitored: 5 };
+    let review = WeeklyReview::new(week, &settings);
+    assert!(matches!(review.suggestions[0], Suggestion::TightenThreshold { .. }));
+
+    // ...but most of its alerts were marked wrong
+    let review = review.with_feedback(&[eye_drop(10, 6)], &settings);
+    assert_eq!(review.suggestions[0], Suggestion::FalseAlarms { rule: AlertRule::EyeDrop, percent: 60, from: 14.0, to: 16.0 });
+    assert!(!review.suggestions.iter().any(|s| matches!(s, Suggestion::TightenThreshold { .. })));
+    assert!(review.text().contains("you marked 60% of the \"Head dropped below baseline\" alerts as wrong"), "{}", review.text());
+
+    let html = alert_browser::render_html(&[alert("eye_drop", true), alert("eye_drop", false)], 0);
+    assert_eq!(html.matches("(marked wrong)").count(), 1);
+}
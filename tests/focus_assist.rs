@@ -0,0 +1,41 @@
+//! Focus Assist: levels, and what each one leaves of the alerting.
+
+use posture_ai_oc::alert_sinks::AlertSink;
+use posture_ai_oc::focus_assist::{Enforcement, FocusAssist, FocusAssistRules};
+use posture_ai_oc::settings::Settings;
+
+#[test]
+fn profile_numbers_map_to_levels() {
+    assert_eq!(FocusAssist::from_profile(0), FocusAssist::Off);
+    assert_eq!(FocusAssist::from_profile(1), FocusAssist::PriorityOnly);
+    assert_eq!(FocusAssist::from_profile(2), FocusAssist::AlarmsOnly);
+    assert_eq!(FocusAssist::from_profile(7), FocusAssist::Off);
+}
+
+#[test]
+fn enforcement_limits_the_alert_channels() {
+    for sink in [AlertSink::Overlay, AlertSink::Sound, AlertSink::Toast] {
+        assert!(Enforcement::Full.allows(sink));
+        assert_eq!(Enforcement::NotificationsOnly.allows(sink), sink == AlertSink::Toast);
+        assert!(!Enforcement::Silent.allows(sink));
+    }
+}
+
+#[test]
+fn each_level_has_its_own_enforcement() {
+    let rules = FocusAssistRules { priority_only: Enforcement::Full, alarms_only: Enforcement::NotificationsOnly };
+    assert_eq!(rules.enforcement(FocusAssist::Off), Enforcement::Full);
+    assert_eq!(rules.enforcement(FocusAssist::PriorityOnly), Enforcement::Full);
+    assert_eq!(rules.enforcement(FocusAssist::AlarmsOnly), Enforcement::NotificationsOnly);
+    // Focus Assist off never turns alerting down, whatever the rules say
+    let silent = FocusAssistRules { priority_only: Enforcement::Silent, alarms_only: Enforcement::Silent };
+    assert_eq!(silent.enforcement(FocusAssist::Off), Enforcement::Full);
+}
+
+#[test]
+fn rules_are_stored_in_settings_and_missing_levels_use_defaults() {
+    let json = r#"{ "focus_assist": { "alarms_only": "notifications_only" } }"#;
+    let settings: Settings = serde_json::from_str(json).unwrap();
+    assert_eq!(settings.focus_assist.alarms_only, Enforcement::NotificationsOnly);
+    assert_eq!(settings.focus_assist.priority_only, FocusAssistRules::default().priority_only);
+}
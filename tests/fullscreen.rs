@@ -0,0 +1,26 @@
+//! Full-screen detection: whether a window fills its monitor.
+
+use posture_ai_oc::blur_overlay::ScreenRect;
+use posture_ai_oc::fullscreen::covers_monitor;
+
+fn rect(left: i32, top: i32, right: i32, bottom: i32) -> ScreenRect {
+    ScreenRect { left, top, right, bottom }
+}
+
+#[test]
+fn window_filling_or_overhanging_its_monitor_is_full_screen() {
+    let secondary = rect(1920, 0, 3840, 1080);
+    assert!(covers_monitor(&rect(1920, 0, 3840, 1080), &secondary));
+    assert!(covers_monitor(&rect(1912, -8, 3848, 1088), &secondary));
+}
+
+#[test]
+fn smaller_windows_are_not_full_screen() {
+    let primary = rect(0, 0, 1920, 1080);
+    // Maximized above the taskbar
+    assert!(!covers_monitor(&rect(0, 0, 1920, 1040), &primary));
+    assert!(!covers_monitor(&rect(100, 100, 800, 600), &primary));
+    // Full screen on the other monitor
+    assert!(!covers_monitor(&rect(1920, 0, 3840, 1080), &primary));
+    assert!(!covers_monitor(&primary, &rect(0, 0, 0, 0)));
+}
@@ -0,0 +1,97 @@
+//! Weekly goal review: stats, suggested adjustments and the Monday schedule.
+
+use posture_ai_oc::goals::{self, Suggestion, WeekStats, WeeklyReview};
+use posture_ai_oc::history::SecondRow;
+use posture_ai_oc::i18n;
+use posture_ai_oc::local_time::LocalTime;
+use posture_ai_oc::settings::{SettingChange, Settings};
+
+const HOUR: u64 = 3600;
+
+fn week(monitored_hours: u64, slouch_percent: u64, alerts: u32) -> WeekStats {
+    let monitored_secs = monitored_hours * HOUR;
+    WeekStats { monitored_secs, slouching_secs: monitored_secs * slouch_percent / 100, alerts, days_monitored: 5 }
+}
+
+fn date(text: &str) -> LocalTime {
+    LocalTime::parse_date(text).unwrap()
+}
+
+#[test]
+fn week_stats_count_local_days() {
+    let row = |second| SecondRow { second, frames: 10, bad_frames: 8, alerting: false };
+    // 23:00 and 01:00 UTC are the same local day at UTC-2
+    let rows = [row(23 * 3600), row(25 * 3600), row(50 * 3600)];
+
+    let stats = WeekStats::from_rows(&rows, 6, -2 * 3600);
+    assert_eq!(stats.monitored_secs, 3);
+    assert_eq!(stats.slouching_secs, 3);
+    assert_eq!(stats.days_monitored, 2);
+    assert_eq!(stats.alerts_per_day(), 3.0);
+}
+
+#[test]
+fn suggestions_follow_the_numbers() {
+    let settings = Settings { sensitivity_px: 10.0, debounce_ms: 500, ..Settings::default() };
+    let goal = settings.goals.max_slouch_percent;
+
+    // Flagged nearly all the time with constant alerts: looser threshold, reachable goal
+    let suggestions = goals::suggest(&week(20, 60, 200), &settings);
+    assert_eq!(suggestions, [
+        Suggestion::LoosenThreshold { from: 10.0, to: 12.0 },
+        Suggestion::RelaxedGoal { from: goal, to: goal + 5 },
+    ]);
+
+    // Little slouching but many alerts: wait longer before alerting
+    let suggestions = goals::suggest(&week(20, goal as u64, 200), &settings);
+    assert_eq!(suggestions, [Suggestion::LongerDebounce { from: 500, to: 1000 }]);
+
+    // Goal beaten easily: aim higher, and undo an earlier loosening of the threshold
+    let loosened = Settings { sensitivity_px: 14.0, ..settings.clone() };
+    let suggestions = goals::suggest(&week(20, 1, 2), &loosened);
+    assert_eq!(suggestions, [
+        Suggestion::TightenThreshold { from: 14.0, to: 12.0 },
+        Suggestion::StricterGoal { from: goal, to: goal - 5 },
+    ]);
+
+    // On target: nothing to change
+    assert!(goals::suggest(&week(20, goal as u64, 20), &settings).is_empty());
+}
+
+#[test]
+fn accepted_suggestions_apply_like_settings_edits() {
+    i18n::load("en");
+    let mut settings = Settings { sensitivity_px: 10.0, ..Settings::default() };
+    let review = WeeklyReview::new(week(20, 60, 200), &settings);
+
+    let text = review.text();
+    assert!(text.contains("✖  Slouching: 60% of the time"), "{}", text);
+    assert!(text.contains("Allow 12 px of movement instead of 10 px"), "{}", text);
+
+    for change in review.changes() {
+        assert!(settings.apply(change));
+    }
+    assert_eq!(settings.sensitivity_px, 12.0);
+    assert_eq!(settings.goals.max_slouch_percent, Settings::default().goals.max_slouch_percent + 5);
+    // Goals stay within range
+    settings.apply(SettingChange::SlouchGoalPercent(0));
+    assert_eq!(settings.goals.max_slouch_percent, 1);
+}
+
+#[test]
+fn review_is_due_once_per_week_starting_monday() {
+    let monday = date("2024-06-03");
+    assert_eq!(monday.weekday(), 0);
+    assert_eq!(date("2024-06-09").weekday(), 6);
+    assert_eq!(date("1970-01-01").day_number(), 0);
+    assert_eq!(date("2000-03-01").day_number(), 11_017);
+
+    assert!(goals::is_due(&monday, None));
+    assert!(goals::is_due(&monday, Some(&date("2024-06-02"))));
+    assert!(!goals::is_due(&monday, Some(&monday)));
+    // Reviewed Monday: not again until next Monday
+    assert!(!goals::is_due(&date("2024-06-09"), Some(&monday)));
+    assert!(goals::is_due(&date("2024-06-10"), Some(&monday)));
+    // Missed Monday: shown on the next day the app runs
+    assert!(goals::is_due(&date("2024-06-05"), Some(&date("2024-05-27"))));
+}
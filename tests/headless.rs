@@ -0,0 +1,503 @@
+//! Headless integration harness: drives the posture engine with a scripted camera,
+//! a recording overlay and a virtual clock, then asserts on alerts and stats.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use posture_ai_oc::config::{
+    AWAY_AFTER_SECS, CALIBRATION_MIN_SAMPLES, CALIBRATION_SECS, DEBOUNCE_MS, HEIGHT, OVERLAY_FULL_AFTER_SECS, OVERLAY_MIN_INTENSITY,
+    SETTLE_SECS, SOUND_REPEAT_SECS, TOAST_AFTER_SECS, TOAST_COOLDOWN_SECS,
+};
+use posture_ai_oc::clock::ManualClock;
+use posture_ai_oc::engine::{EngineEvent, PostureEngine, PostureStatus};
+use posture_ai_oc::notifications::SlouchNotifier;
+use posture_ai_oc::pause::{Pause, SavedPause};
+use posture_ai_oc::sound::SoundAlert;
+use posture_ai_oc::stats::{MinuteStats, StatsCollector};
+use posture_ai_oc::pose::{Keypoint, Pose, KEYPOINT_COUNT, LEFT_EYE, NOSE, RIGHT_EYE};
+
+const FRAME: Duration = Duration::from_millis(33);
+const UPRIGHT_EYE_Y: f32 = 0.40;
+const SLOUCHED_EYE_Y: f32 = 0.50;
+// Just past GOOD_POSTURE_DEVIATION
+const SLIGHTLY_SLOUCHED_EYE_Y: f32 = 0.43;
+// Inside GOOD_POSTURE_DEVIATION but not back within GOOD_POSTURE_RECOVERY
+const HOVERING_EYE_Y: f32 = 0.415;
+
+fn pose_with_eyes_at(y: f32) -> Pose {
+    let mut pose = [Keypoint::default(); KEYPOINT_COUNT];
+    for index in [NOSE, LEFT_EYE, RIGHT_EYE] {
+        pose[index] = Keypoint { x: 0.5, y, score: 0.9 };
+    }
+    pose
+}
+
+/// Plays back a scripted list of frames; `None` means nobody in view
+struct MockCamera {
+    frames: VecDeque<Option<Pose>>,
+}
+
+impl MockCamera {
+    fn new() -> Self {
+        Self { frames: VecDeque::new() }
+    }
+
+    fn upright(&mut self, frames: usize) -> &mut Self {
+        self.push(Some(pose_with_eyes_at(UPRIGHT_EYE_Y)), frames)
+    }
+
+    fn slouched(&mut self, frames: usize) -> &mut Self {
+        self.push(Some(pose_with_eyes_at(SLOUCHED_EYE_Y)), frames)
+    }
+
+    fn slightly_slouched(&mut self, frames: usize) -> &mut Self {
+        self.push(Some(pose_with_eyes_at(SLIGHTLY_SLOUCHED_EYE_Y)), frames)
+    }
+
+    fn hovering(&mut self, frames: usize) -> &mut Self {
+        self.push(Some(pose_with_eyes_at(HOVERING_EYE_Y)), frames)
+    }
+
+    fn empty(&mut self, frames: usize) -> &mut Self {
+        self.push(None, frames)
+    }
+
+    fn push(&mut self, pose: Option<Pose>, frames: usize) -> &mut Self {
+        self.frames.extend(std::iter::repeat_n(pose, frames));
+        self
+    }
+}
+
+/// Records what the real blur overlay would have been told
+#[derive(Default)]
+struct MockOverlay {
+    visible: bool,
+    intensity: f32,
+    times_shown: u32,
+}
+
+impl MockOverlay {
+    fn set_target_intensity(&mut self, intensity: f32) {
+        let visible = intensity > 0.0;
+        if visible && !self.visible {
+            self.times_shown += 1;
+        }
+        self.visible = visible;
+        self.intensity = intensity;
+    }
+}
+
+/// The main loop without camera, model, window or tray
+struct App {
+    engine: PostureEngine,
+    camera: MockCamera,
+    overlay: MockOverlay,
+    clock: ManualClock,
+    notifier: SlouchNotifier,
+    sound: SoundAlert,
+    stats: StatsCollector,
+    events: Vec<EngineEvent>,
+    toasts: Vec<Duration>,
+    sounds_played: u32,
+    minutes: Vec<MinuteStats>,
+}
+
+impl App {
+    fn boot(saved_baseline: Option<f32>) -> Self {
+        let clock = ManualClock::new();
+        let mut engine = PostureEngine::new(0, 0, saved_baseline, clock.shared());
+        if saved_baseline.is_none() {
+            engine.start_calibration();
+        }
+        Self {
+            engine,
+            camera: MockCamera::new(),
+            overlay: MockOverlay::default(),
+            notifier: SlouchNotifier::new(clock.shared()),
+            sound: SoundAlert::new(clock.shared()),
+            stats: StatsCollector::new(&clock.shared()),
+            clock,
+            events: Vec::new(),
+            toasts: Vec::new(),
+            sounds_played: 0,
+            minutes: Vec::new(),
+        }
+    }
+
+    /// Runs until the scripted camera runs dry
+    fn run(&mut self) {
+        while let Some(pose) = self.camera.frames.pop_front() {
+            self.clock.advance(FRAME);
+            self.events.extend(self.engine.process(pose.as_ref()));
+            self.overlay.set_target_intensity(self.engine.alert_intensity());
+            let slouching = matches!(self.engine.status(), PostureStatus::Slouching | PostureStatus::Alert);
+            self.toasts.extend(self.notifier.update(slouching));
+            if self.sound.update(self.engine.is_alerting()) {
+                self.sounds_played += 1;
+            }
+            if !self.engine.is_calibrating() {
+                if let Some(delta) = self.engine.delta() {
+                    self.stats.add_delta(delta);
+                }
+            }
+            self.minutes.extend(self.stats.poll(self.engine.stats()));
+        }
+    }
+
+    fn count(&self, matches: fn(&EngineEvent) -> bool) -> usize {
+        self.events.iter().filter(|event| matches(event)).count()
+    }
+}
+
+fn frames_for(duration: Duration) -> usize {
+    (duration.as_millis() / FRAME.as_millis()) as usize + 1
+}
+
+fn calibration_frames() -> usize {
+    frames_for(Duration::from_secs(CALIBRATION_SECS))
+}
+
+/// Slouched frames that are still forgiven; one more triggers the overlay
+fn debounce_frames() -> usize {
+    frames_for(Duration::from_millis(DEBOUNCE_MS))
+}
+
+#[test]
+fn calibrates_on_first_start_then_stays_quiet_while_upright() {
+    let mut app = App::boot(None);
+    assert_eq!(app.engine.status(), PostureStatus::Calibrating);
+
+    app.camera.upright(calibration_frames() + 300);
+    app.run();
+
+    assert_eq!(app.count(|e| matches!(e, EngineEvent::CalibrationFinished(_))), 1);
+    let baseline = app.engine.baseline().expect("baseline after calibration");
+    assert!((baseline - UPRIGHT_EYE_Y * HEIGHT as f32).abs() < 0.01);
+    assert_eq!(app.engine.status(), PostureStatus::Good);
+    assert_eq!(app.overlay.times_shown, 0);
+    assert_eq!(app.engine.stats().alerts, 0);
+}
+
+#[test]
+fn calibration_without_a_face_fails() {
+    let mut app = App::boot(None);
+    app.camera.empty(calibration_frames());
+    app.camera.upright(CALIBRATION_MIN_SAMPLES - 1);
+    app.run();
+
+    assert_eq!(app.count(|e| matches!(e, EngineEvent::CalibrationFailed)), 1);
+    assert_eq!(app.engine.baseline(), None);
+    assert_eq!(app.engine.status(), PostureStatus::NotCalibrated);
+}
+
+#[test]
+fn short_slouch_is_debounced() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    app.camera.upright(30).slouched(debounce_frames()).upright(30);
+    app.run();
+
+    assert_eq!(app.overlay.times_shown, 0);
+    assert_eq!(app.engine.stats().bad_frames, debounce_frames() as u64);
+}
+
+#[test]
+fn sustained_slouch_shows_overlay_until_upright_again() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    app.camera.upright(30).slouched(debounce_frames() + 60);
+    app.run();
+
+    assert!(app.overlay.visible);
+    assert_eq!(app.engine.status(), PostureStatus::Alert);
+    assert_eq!(app.count(|e| matches!(e, EngineEvent::AlertStarted)), 1);
+
+    app.camera.upright(1);
+    app.run();
+
+    assert!(!app.overlay.visible);
+    assert_eq!(app.count(|e| matches!(e, EngineEvent::AlertEnded)), 1);
+}
+
+#[test]
+fn hovering_near_the_threshold_does_not_toggle_the_overlay() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    // Dipping back inside the deviation doesn't restart the debounce...
+    app.camera.slightly_slouched(debounce_frames() / 2).hovering(5).slightly_slouched(debounce_frames() / 2 + 5);
+    app.run();
+    assert_eq!(app.count(|e| matches!(e, EngineEvent::AlertStarted)), 1);
+
+    // ...nor end the alert
+    for _ in 0..10 {
+        app.camera.hovering(3).slightly_slouched(3);
+    }
+    app.run();
+    assert_eq!(app.overlay.times_shown, 1);
+    assert_eq!(app.count(|e| matches!(e, EngineEvent::AlertEnded)), 0);
+    assert_eq!(app.engine.status(), PostureStatus::Alert);
+
+    // Back within the recovery distance clears it
+    app.camera.upright(1);
+    app.run();
+    assert!(!app.overlay.visible);
+    assert_eq!(app.count(|e| matches!(e, EngineEvent::AlertEnded)), 1);
+}
+
+#[test]
+fn without_hysteresis_hovering_does_toggle() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    app.engine.set_recovery(app.engine.deviation());
+    app.camera.slightly_slouched(debounce_frames() + 1);
+    for _ in 0..3 {
+        app.camera.hovering(3).slightly_slouched(debounce_frames() + 1);
+    }
+    app.run();
+    assert_eq!(app.overlay.times_shown, 4);
+}
+
+#[test]
+fn losing_the_face_ends_an_alert() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    app.camera.slouched(debounce_frames() + 10).empty(5);
+    app.run();
+
+    assert!(!app.overlay.visible);
+    assert_eq!(app.overlay.times_shown, 1);
+}
+
+#[test]
+fn stats_cover_a_scripted_session() {
+    let mut app = App::boot(None);
+    let calibration = calibration_frames();
+    app.camera
+        .upright(calibration)
+        .upright(100)
+        .slouched(debounce_frames() + 20)
+        .upright(50)
+        .slouched(debounce_frames() + 20)
+        .empty(10);
+    app.run();
+
+    let stats = app.engine.stats();
+    assert_eq!(stats.frames, (calibration + 100 + 50 + 2 * (debounce_frames() + 20) + 10) as u64);
+    assert_eq!(stats.alerts, 2);
+    assert_eq!(stats.bad_frames, 2 * (debounce_frames() + 20) as u64);
+    assert_eq!(app.overlay.times_shown, 2);
+}
+
+#[test]
+fn recalibration_pauses_judging() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    app.engine.start_calibration();
+    // Slouched "upright" pose becomes the new baseline instead of triggering the overlay
+    app.camera.slouched(calibration_frames() + 60);
+    app.run();
+
+    assert_eq!(app.overlay.times_shown, 0);
+    let baseline = app.engine.baseline().unwrap();
+    assert!((baseline - SLOUCHED_EYE_Y * HEIGHT as f32).abs() < 0.01);
+}
+
+#[test]
+fn leaving_the_desk_goes_away_and_stops_judging() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    let away_frames = frames_for(Duration::from_secs(AWAY_AFTER_SECS));
+    app.camera.slouched(debounce_frames() + 10).empty(away_frames + 30);
+    app.run();
+
+    assert_eq!(app.engine.status(), PostureStatus::Away);
+    assert_eq!(app.count(|e| matches!(e, EngineEvent::UserAway)), 1);
+    assert!(!app.overlay.visible);
+    let bad_frames = app.engine.stats().bad_frames;
+
+    // Back slouched: the away time is reported and the debounce starts over
+    app.camera.slouched(debounce_frames());
+    app.run();
+    let away_for = app.events.iter().find_map(|e| match e {
+        EngineEvent::UserReturned { away_for } => Some(*away_for),
+        _ => None,
+    });
+    assert!(away_for.is_some_and(|away_for| away_for >= Duration::from_secs(AWAY_AFTER_SECS)), "{:?}", away_for);
+    assert_eq!(app.engine.status(), PostureStatus::Slouching);
+    assert_eq!(app.engine.stats().bad_frames, bad_frames + debounce_frames() as u64);
+    assert_eq!(app.overlay.times_shown, 1);
+}
+
+#[test]
+fn brief_dropouts_are_not_away() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    app.engine.set_away_after(Duration::from_secs(2));
+    for _ in 0..5 {
+        app.camera.upright(10).empty(frames_for(Duration::from_secs(1)));
+    }
+    app.run();
+
+    assert_eq!(app.count(|e| matches!(e, EngineEvent::UserAway)), 0);
+    assert_ne!(app.engine.status(), PostureStatus::Away);
+}
+
+#[test]
+fn settled_recalibration_waits_until_the_user_sits_still() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    app.engine.start_calibration_when_settled();
+    // Still shifting around in the chair
+    for _ in 0..10 {
+        app.camera.upright(20).slouched(20);
+    }
+    app.run();
+    assert_eq!(app.engine.status(), PostureStatus::Calibrating);
+    assert_eq!(app.count(|e| matches!(e, EngineEvent::CalibrationFinished(_))), 0);
+    assert_eq!(app.engine.stats().bad_frames, 0);
+
+    // Sitting lower than before becomes the new baseline
+    app.camera.slouched(frames_for(Duration::from_secs(SETTLE_SECS)) + calibration_frames());
+    app.run();
+    assert_eq!(app.count(|e| matches!(e, EngineEvent::CalibrationFinished(_))), 1);
+    let baseline = app.engine.baseline().unwrap();
+    assert!((baseline - SLOUCHED_EYE_Y * HEIGHT as f32).abs() < 0.01);
+    assert_eq!(app.overlay.times_shown, 0);
+}
+
+#[test]
+fn long_slouch_sends_one_toast_per_cooldown() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    let toast_after = frames_for(Duration::from_secs(TOAST_AFTER_SECS));
+    app.camera.slouched(toast_after / 2).upright(10).slouched(toast_after / 2);
+    app.run();
+    assert!(app.toasts.is_empty(), "interrupted streaks must not add up");
+
+    app.camera.slouched(toast_after * 2);
+    app.run();
+    assert_eq!(app.toasts.len(), 1);
+    assert!(app.toasts[0] >= Duration::from_secs(TOAST_AFTER_SECS));
+
+    // A new streak right after is still inside the cooldown
+    app.camera.upright(10).slouched(toast_after + 1);
+    app.run();
+    assert_eq!(app.toasts.len(), 1);
+
+    app.camera.upright(frames_for(Duration::from_secs(TOAST_COOLDOWN_SECS))).slouched(toast_after + 1);
+    app.run();
+    assert_eq!(app.toasts.len(), 2);
+}
+
+#[test]
+fn sound_repeats_while_the_alert_lasts() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    let repeat = frames_for(Duration::from_secs(SOUND_REPEAT_SECS));
+    // Alert starts after the debounce, then runs for just over two repeat intervals
+    app.camera.slouched(debounce_frames() + 1 + 2 * repeat);
+    app.run();
+    assert_eq!(app.sounds_played, 3);
+
+    // Sitting up ends the alert; the next one plays right away
+    app.camera.upright(5).slouched(debounce_frames() + 1);
+    app.run();
+    assert_eq!(app.sounds_played, 4);
+}
+
+#[test]
+fn debounce_does_not_depend_on_frame_rate() {
+    // Two frames far apart count the same as many frames in between
+    let clock = ManualClock::new();
+    let mut engine = PostureEngine::new(0, 0, Some(UPRIGHT_EYE_Y * HEIGHT as f32), clock.shared());
+    let slouched = pose_with_eyes_at(SLOUCHED_EYE_Y);
+
+    engine.process(Some(&slouched));
+    clock.advance(Duration::from_millis(DEBOUNCE_MS - 1));
+    engine.process(Some(&slouched));
+    assert!(!engine.is_alerting());
+
+    clock.advance(Duration::from_millis(1));
+    engine.process(Some(&slouched));
+    assert!(engine.is_alerting());
+}
+
+#[test]
+fn overlay_intensity_escalates_with_time() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    app.camera.slightly_slouched(debounce_frames() + 1);
+    app.run();
+    let initial = app.overlay.intensity;
+    assert!((OVERLAY_MIN_INTENSITY..0.5).contains(&initial), "initial intensity {}", initial);
+
+    app.camera.slightly_slouched(frames_for(Duration::from_secs(OVERLAY_FULL_AFTER_SECS / 2)));
+    app.run();
+    assert!(app.overlay.intensity > initial && app.overlay.intensity < 1.0);
+
+    app.camera.slightly_slouched(frames_for(Duration::from_secs(OVERLAY_FULL_AFTER_SECS)));
+    app.run();
+    assert_eq!(app.overlay.intensity, 1.0);
+
+    app.camera.upright(1);
+    app.run();
+    assert_eq!(app.overlay.intensity, 0.0);
+}
+
+#[test]
+fn deeper_slouch_means_stronger_overlay() {
+    let mut slight = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    slight.camera.slightly_slouched(debounce_frames() + 1);
+    slight.run();
+
+    let mut deep = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    deep.camera.slouched(debounce_frames() + 1);
+    deep.run();
+
+    assert!(slight.overlay.visible && deep.overlay.visible);
+    assert!(deep.overlay.intensity > slight.overlay.intensity);
+}
+
+#[test]
+fn timed_pause_expires_on_the_clock() {
+    let clock = ManualClock::new();
+    let mut pause = Pause::new(clock.shared());
+    pause.pause_for(Duration::from_secs(15 * 60));
+    assert!(pause.is_paused());
+
+    clock.advance(Duration::from_secs(15 * 60 - 1));
+    assert!(pause.is_paused());
+
+    clock.advance(Duration::from_secs(1));
+    assert!(!pause.is_paused());
+}
+
+#[test]
+fn pause_survives_a_restart() {
+    const NOW_MS: u64 = 1_700_000_000_000;
+    let clock = ManualClock::new();
+    let mut pause = Pause::new(clock.shared());
+    pause.pause_for(Duration::from_secs(2 * 60 * 60));
+    clock.advance(Duration::from_secs(30 * 60));
+    let saved = pause.to_saved(NOW_MS).unwrap();
+    assert_eq!(saved, SavedPause::Until { unix_ms: NOW_MS + 90 * 60 * 1000 });
+
+    // Rebooted 10 minutes later: the rest of the pause carries on
+    let clock = ManualClock::new();
+    let mut restored = Pause::new(clock.shared());
+    restored.restore(saved, NOW_MS + 10 * 60 * 1000);
+    assert!(restored.is_paused());
+    clock.advance(Duration::from_secs(80 * 60));
+    assert!(!restored.is_paused());
+    assert_eq!(restored.to_saved(NOW_MS), None);
+
+    // An expired pause is dropped; an indefinite one stays
+    restored.restore(saved, NOW_MS + 3 * 60 * 60 * 1000);
+    assert!(!restored.is_paused());
+    restored.restore(SavedPause::Indefinitely, NOW_MS);
+    assert!(restored.is_paused());
+}
+
+#[test]
+fn minute_stats_summarize_posture() {
+    let mut app = App::boot(Some(UPRIGHT_EYE_Y * HEIGHT as f32));
+    let minute = frames_for(Duration::from_secs(60));
+    // First minute: a quarter slouched (one overlay trigger); second minute: nobody there
+    app.camera.upright(minute * 3 / 4).slouched(minute / 4 + 1).empty(minute);
+    app.run();
+
+    assert_eq!(app.minutes.len(), 1, "minutes without activity are skipped");
+    let first = app.minutes[0];
+    assert_eq!(first.overlay_triggers, 1);
+    assert!((first.bad_percent - 25.0).abs() < 1.0, "bad_percent = {}", first.bad_percent);
+    let expected_delta = (SLOUCHED_EYE_Y - UPRIGHT_EYE_Y) * HEIGHT as f32 / 4.0;
+    assert!((first.avg_delta.unwrap() - expected_delta).abs() < 1.0);
+}
@@ -0,0 +1,62 @@
+//! Recorded inputs: image folders played in name order, and input detection by path.
+
+use std::path::{Path, PathBuf};
+
+use image::{Rgb, RgbImage};
+use posture_ai_oc::error::PostureError;
+use posture_ai_oc::input::{self, parse_dimensions, FrameSource, ImageSequence};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("posture_ai_input_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Saves a 4x2 frame whose red channel is `shade`
+fn save_frame(dir: &Path, name: &str, shade: u8) {
+    RgbImage::from_pixel(4, 2, Rgb([shade, 0, 0])).save(dir.join(name)).unwrap();
+}
+
+#[test]
+fn image_folders_play_in_file_name_order_then_end() {
+    let dir = temp_dir("order");
+    save_frame(&dir, "frame-0002.png", 20);
+    save_frame(&dir, "frame-0001.png", 10);
+    save_frame(&dir, "frame-0003.png", 30);
+    std::fs::write(dir.join("notes.txt"), "not a frame").unwrap();
+
+    let mut frames = ImageSequence::open(&dir).unwrap();
+    assert_eq!(frames.remaining(), 3);
+    assert!(frames.name().starts_with("images:"));
+    let shades: Vec<u8> = std::iter::from_fn(|| frames.next_frame().unwrap()).map(|frame| frame.get_pixel(0, 0).0[0]).collect();
+    assert_eq!(shades, [10, 20, 30]);
+    assert!(frames.next_frame().unwrap().is_none());
+}
+
+#[test]
+fn folders_without_frames_and_unknown_files_are_rejected() {
+    let empty = temp_dir("empty");
+    assert!(matches!(input::open(&empty), Err(PostureError::Camera(_))));
+
+    let notes = temp_dir("unknown").join("notes.txt");
+    std::fs::write(&notes, "not a frame").unwrap();
+    assert!(matches!(input::open(&notes), Err(PostureError::Config(_))));
+    assert!(matches!(input::open(&empty.join("missing.mp4")), Err(PostureError::Config(_))));
+}
+
+#[test]
+fn a_single_image_is_a_one_frame_recording() {
+    let dir = temp_dir("single");
+    save_frame(&dir, "still.png", 50);
+    let mut still = input::open(&dir.join("still.png")).unwrap();
+    assert_eq!(still.next_frame().unwrap().map(|frame| frame.dimensions()), Some((4, 2)));
+    assert!(still.next_frame().unwrap().is_none());
+}
+
+#[test]
+fn ffprobe_dimensions_are_parsed() {
+    assert_eq!(parse_dimensions("1280x720\n"), Some((1280, 720)));
+    assert_eq!(parse_dimensions(""), None);
+    assert_eq!(parse_dimensions("N/A"), None);
+}
@@ -0,0 +1,70 @@
+//! Integrations: which engine events are passed on, the webhook JSON and delivery retries.
+
+use std::time::Duration;
+
+use posture_ai_oc::clock::ManualClock;
+use posture_ai_oc::config::WEBHOOK_RETRIES;
+use posture_ai_oc::engine::{EngineEvent, PostureEngine};
+use posture_ai_oc::i18n;
+use posture_ai_oc::integrations::{self, PostureEvent};
+use posture_ai_oc::tasks::CancelToken;
+
+#[test]
+fn engine_events_map_to_integration_events() {
+    let clock = ManualClock::new();
+    let engine = PostureEngine::new(0, 0, Some(200.0), clock.shared());
+    assert_eq!(PostureEvent::from_engine(&EngineEvent::AlertEnded, &engine), Some(PostureEvent::BadPostureEnd));
+    assert_eq!(PostureEvent::from_engine(&EngineEvent::UserAway, &engine), Some(PostureEvent::Away));
+    assert_eq!(PostureEvent::from_engine(&EngineEvent::TrackingPoor, &engine), None);
+    assert_eq!(
+        PostureEvent::from_engine(&EngineEvent::UserReturned { away_for: Duration::from_secs(60) }, &engine),
+        None
+    );
+}
+
+#[test]
+fn webhook_body_names_the_event_and_carries_a_chat_message() {
+    i18n::load("en");
+    let body = integrations::webhook_body(&PostureEvent::BadPostureStart { rule: "neck_angle".to_string() }, 1_700_000_000_000);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["event"], "bad_posture_start");
+    assert_eq!(json["rule"], "neck_angle");
+    assert_eq!(json["timestamp"], 1_700_000_000_000i64);
+    assert!(json["text"].as_str().unwrap().contains("neck_angle"));
+    assert_eq!(json["text"], json["content"]);
+
+    let calibrated: serde_json::Value =
+        serde_json::from_str(&integrations::webhook_body(&PostureEvent::Calibrated { samples: 40 }, 0)).unwrap();
+    assert_eq!((calibrated["event"].as_str(), calibrated["samples"].as_u64()), (Some("calibrated"), Some(40)));
+}
+
+#[test]
+fn failed_posts_are_retried_until_one_succeeds() {
+    let mut answers = vec![Err(anyhow::anyhow!("connection refused")), Ok(503), Ok(204)].into_iter();
+    let mut attempts = 0;
+    let mut post = |_: &str| {
+        attempts += 1;
+        answers.next().unwrap()
+    };
+    assert!(integrations::deliver(&mut post, "{}", Duration::ZERO, &CancelToken::new()).is_ok());
+    assert_eq!(attempts, 3);
+}
+
+#[test]
+fn delivery_gives_up_after_the_retries_and_on_client_errors() {
+    let mut attempts = 0;
+    let mut failing = |_: &str| {
+        attempts += 1;
+        Ok(500)
+    };
+    assert!(integrations::deliver(&mut failing, "{}", Duration::ZERO, &CancelToken::new()).is_err());
+    assert_eq!(attempts, WEBHOOK_RETRIES + 1);
+
+    let mut attempts = 0;
+    let mut rejected = |_: &str| {
+        attempts += 1;
+        Ok(404)
+    };
+    assert!(integrations::deliver(&mut rejected, "{}", Duration::ZERO, &CancelToken::new()).is_err());
+    assert_eq!(attempts, 1);
+}
@@ -0,0 +1,56 @@
+//! Low-light detection: a sustained dark scene warns once and raises the keypoint cutoff.
+
+use std::time::Duration;
+
+use posture_ai_oc::clock::ManualClock;
+use posture_ai_oc::config::{LOW_LIGHT_BRIGHTNESS, LOW_LIGHT_KEYPOINT_CONFIDENCE, LOW_LIGHT_RECOVER_BRIGHTNESS, LOW_LIGHT_SECS};
+use posture_ai_oc::lighting::LowLightCheck;
+
+const FRAME: Duration = Duration::from_millis(100);
+
+fn feed(check: &mut LowLightCheck, clock: &ManualClock, brightness: f32, duration: Duration) -> Vec<bool> {
+    let mut changes = Vec::new();
+    let mut elapsed = Duration::ZERO;
+    while elapsed < duration {
+        changes.extend(check.update(brightness));
+        clock.advance(FRAME);
+        elapsed += FRAME;
+    }
+    changes
+}
+
+#[test]
+fn dark_scene_warns_after_a_while() {
+    let clock = ManualClock::new();
+    let mut check = LowLightCheck::new(clock.shared());
+    let dark = LOW_LIGHT_BRIGHTNESS - 10.0;
+    assert!(feed(&mut check, &clock, dark, Duration::from_secs(LOW_LIGHT_SECS)).is_empty());
+    assert_eq!(check.min_confidence(true), None);
+
+    assert_eq!(feed(&mut check, &clock, dark, FRAME), [true]);
+    assert!(check.is_dark());
+    assert_eq!(check.min_confidence(true), Some(LOW_LIGHT_KEYPOINT_CONFIDENCE));
+    assert_eq!(check.min_confidence(false), None);
+}
+
+#[test]
+fn a_bright_frame_restarts_the_wait() {
+    let clock = ManualClock::new();
+    let mut check = LowLightCheck::new(clock.shared());
+    let dark = LOW_LIGHT_BRIGHTNESS - 10.0;
+    feed(&mut check, &clock, dark, Duration::from_secs(LOW_LIGHT_SECS) - FRAME);
+    feed(&mut check, &clock, LOW_LIGHT_BRIGHTNESS, FRAME);
+    assert!(feed(&mut check, &clock, dark, FRAME * 2).is_empty());
+}
+
+#[test]
+fn recovery_needs_the_upper_brightness() {
+    let clock = ManualClock::new();
+    let mut check = LowLightCheck::new(clock.shared());
+    feed(&mut check, &clock, 0.0, Duration::from_secs(LOW_LIGHT_SECS) + FRAME);
+    assert!(check.is_dark());
+
+    assert!(feed(&mut check, &clock, LOW_LIGHT_RECOVER_BRIGHTNESS, FRAME).is_empty());
+    assert_eq!(feed(&mut check, &clock, LOW_LIGHT_RECOVER_BRIGHTNESS + 1.0, FRAME), [false]);
+    assert_eq!(check.min_confidence(true), None);
+}
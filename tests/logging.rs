@@ -0,0 +1,20 @@
+//! Logging: the level setting and how it maps onto the log filter.
+
+use posture_ai_oc::logging::LogLevel;
+use posture_ai_oc::settings::Settings;
+use tracing_subscriber::filter::LevelFilter;
+
+#[test]
+fn levels_map_to_filters() {
+    assert_eq!(LevelFilter::from(LogLevel::Error), LevelFilter::ERROR);
+    assert_eq!(LevelFilter::from(LogLevel::Info), LevelFilter::INFO);
+    assert_eq!(LevelFilter::from(LogLevel::Trace), LevelFilter::TRACE);
+    assert!(LevelFilter::from(LogLevel::Debug) > LevelFilter::from(LogLevel::Warn));
+}
+
+#[test]
+fn level_is_read_from_settings() {
+    let settings: Settings = serde_json::from_str(r#"{ "log_level": "debug" }"#).unwrap();
+    assert_eq!(settings.log_level, LogLevel::Debug);
+    assert!(serde_json::from_str::<Settings>(r#"{ "log_level": "verbose" }"#).is_err());
+}
@@ -0,0 +1,47 @@
+//! First-run model download: source URLs, pinned digests and verification.
+
+use std::collections::BTreeMap;
+
+use posture_ai_oc::model_download::{self, ModelSource, Progress};
+use posture_ai_oc::pose_model::PoseModel;
+use posture_ai_oc::settings::Settings;
+use posture_ai_oc::tasks::CancelToken;
+
+#[test]
+fn source_fills_in_the_file_name_and_a_pinned_digest() {
+    let settings = Settings::default();
+    let source = ModelSource::new(PoseModel::Thunder, &settings.model_download_url, &settings.model_sha256);
+    assert!(source.url.starts_with("https://"));
+    assert!(source.url.ends_with("/movenet_singlepose_thunder.onnx"));
+
+    // YOLOv8-pose isn't released, so only a digest from settings.json pins it
+    let pinned = BTreeMap::from([("yolov8n-pose.onnx".to_string(), "ABCDEF".to_string())]);
+    let source = ModelSource::new(PoseModel::Yolov8Pose, "https://example.com/models/{file}", &pinned);
+    assert_eq!(source.url, "https://example.com/models/yolov8n-pose.onnx");
+    assert_eq!(source.sha256.as_deref(), Some("abcdef"));
+}
+
+#[test]
+fn unpinned_or_plain_http_downloads_are_refused() {
+    let digest = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+    assert!(model_download::is_sha256(digest));
+    assert!(!model_download::is_sha256("<html>Not Found</html>"));
+
+    let plain = ModelSource { url: "http://example.com/yolov8n-pose.onnx".to_string(), sha256: Some(digest.to_string()) };
+    assert!(model_download::download(PoseModel::Yolov8Pose, &plain, &CancelToken::new(), |_| {}).is_err());
+    let unpinned = ModelSource { url: "https://example.com/yolov8n-pose.onnx".to_string(), sha256: None };
+    assert!(model_download::download(PoseModel::Yolov8Pose, &unpinned, &CancelToken::new(), |_| {}).is_err());
+}
+
+#[test]
+fn downloads_that_dont_match_the_digest_are_rejected() {
+    assert!(model_download::verify(b"abc", "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD").is_ok());
+    assert!(model_download::verify(b"abd", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad").is_err());
+}
+
+#[test]
+fn progress_is_a_percentage_when_the_size_is_known() {
+    assert_eq!(Progress { received: 5_000_000, total: Some(20_000_000) }.describe(), "25%");
+    assert_eq!(Progress { received: 12_345_678, total: None }.describe(), "12.3 MB");
+    assert_eq!(Progress { received: 10, total: Some(0) }.percent(), None);
+}
@@ -0,0 +1,76 @@
+//! MQTT: packet encoding and the Home Assistant discovery configs.
+
+use std::time::Duration;
+
+use posture_ai_oc::dashboard::LiveState;
+use posture_ai_oc::engine::EngineStats;
+use posture_ai_oc::integrations::{self, MqttSettings};
+use posture_ai_oc::mqtt::{self, Message, MqttOptions};
+
+fn options() -> MqttOptions {
+    MqttOptions {
+        broker: "localhost:1883".to_string(),
+        client_id: "pa".to_string(),
+        username: None,
+        password: None,
+        keep_alive: Duration::from_secs(60),
+        will: None,
+        retry: Duration::from_secs(10),
+    }
+}
+
+#[test]
+fn connect_packet_carries_protocol_client_id_and_keep_alive() {
+    let packet = mqtt::connect_packet(&options());
+    assert_eq!(packet, [0x10, 14, 0, 4, b'M', b'Q', b'T', b'T', 4, 0x02, 0, 60, 0, 2, b'p', b'a']);
+}
+
+#[test]
+fn connect_packet_flags_will_and_credentials() {
+    let options = MqttOptions {
+        username: Some("u".to_string()),
+        password: Some("p".to_string()),
+        will: Some(Message::new("t", "off", true)),
+        ..options()
+    };
+    let packet = mqtt::connect_packet(&options);
+    // Clean session, will, retained will, password, username
+    assert_eq!(packet[9], 0x02 | 0x04 | 0x20 | 0x40 | 0x80);
+    assert_eq!(&packet[12..], [0, 2, b'p', b'a', 0, 1, b't', 0, 3, b'o', b'f', b'f', 0, 1, b'u', 0, 1, b'p']);
+}
+
+#[test]
+fn publish_packet_sets_retain_and_encodes_long_lengths() {
+    assert_eq!(mqtt::publish_packet(&Message::new("a/b", "ON", true)), [0x31, 7, 0, 3, b'a', b'/', b'b', b'O', b'N']);
+    let packet = mqtt::publish_packet(&Message::new("t", "x".repeat(200), false));
+    // 3 bytes of topic plus 200 of payload: 203 = 0x4B + 1 * 128
+    assert_eq!(&packet[..3], [0x30, 0xCB, 0x01]);
+    assert_eq!(packet.len(), 3 + 203);
+}
+
+#[test]
+fn discovery_announces_three_entities_of_one_device() {
+    let settings = MqttSettings { topic: "home/posture".to_string(), ..MqttSettings::default() };
+    let messages = settings.discovery_messages();
+    let topics: Vec<&str> = messages.iter().map(|message| message.topic.as_str()).collect();
+    assert_eq!(topics, [
+        "homeassistant/sensor/home_posture/state/config",
+        "homeassistant/sensor/home_posture/score/config",
+        "homeassistant/binary_sensor/home_posture/presence/config",
+    ]);
+    assert!(messages.iter().all(|message| message.retain));
+    let presence: serde_json::Value = serde_json::from_str(&messages[2].payload).unwrap();
+    assert_eq!(presence["state_topic"], "home/posture/presence");
+    assert_eq!(presence["availability_topic"], "home/posture/availability");
+    assert_eq!(presence["device_class"], "occupancy");
+    assert_eq!(presence["unique_id"], "home_posture_presence");
+    assert_eq!(presence["device"]["identifiers"][0], "home_posture");
+}
+
+#[test]
+fn state_and_score_are_published_as_plain_values() {
+    assert_eq!(integrations::state_key(LiveState::TrackingPoor), "tracking_poor");
+    assert_eq!(integrations::posture_score(&EngineStats::default()), None);
+    let stats = EngineStats { good_frames: 3, bad_frames: 1, ..EngineStats::default() };
+    assert_eq!(integrations::posture_score(&stats), Some(75));
+}
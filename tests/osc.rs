@@ -0,0 +1,46 @@
+//! OSC output: message and bundle encoding and what one frame sends.
+
+use posture_ai_oc::dashboard::LiveState;
+use posture_ai_oc::osc::{self, OscArg};
+use posture_ai_oc::pose::{Keypoint, Pose, KEYPOINT_COUNT};
+
+#[test]
+fn messages_pad_strings_and_write_big_endian_arguments() {
+    let packet = osc::message("/ab", &[OscArg::Int(1), OscArg::Float(1.0), OscArg::Str("hi")]);
+    let mut expected = b"/ab\0,ifs\0\0\0\0".to_vec();
+    expected.extend_from_slice(&[0, 0, 0, 1]);
+    expected.extend_from_slice(&[0x3F, 0x80, 0, 0]);
+    expected.extend_from_slice(b"hi\0\0");
+    assert_eq!(packet, expected);
+    assert_eq!(packet.len() % 4, 0);
+}
+
+#[test]
+fn bundles_are_immediate_and_size_each_message() {
+    let message = osc::message("/a", &[]);
+    let packet = osc::bundle(std::slice::from_ref(&message));
+    assert_eq!(&packet[..8], b"#bundle\0");
+    assert_eq!(&packet[8..16], [0, 0, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(&packet[16..20], (message.len() as u32).to_be_bytes());
+    assert_eq!(&packet[20..], message);
+}
+
+#[test]
+fn a_frame_sends_state_presence_delta_and_every_keypoint() {
+    let pose: Pose = [Keypoint { x: 0.5, y: 0.25, score: 1.0 }; KEYPOINT_COUNT];
+    let packet = osc::frame_bundle("/posture", LiveState::Good, Some(3.0), Some(&pose));
+    let contains = |needle: &[u8]| packet.windows(needle.len()).any(|window| window == needle);
+    assert!(contains(b"/posture/state\0\0,s\0\0good\0"));
+    assert!(contains(b"/posture/delta\0\0"));
+    assert!(contains(b"/posture/keypoint/nose\0\0,fff\0"));
+    assert!(contains(b"/posture/keypoint/right_ankle\0\0\0,fff\0"));
+    // 3 floats per keypoint in the flat message: 51 type tags after the comma
+    let tags = format!(",{}\0", "f".repeat(3 * KEYPOINT_COUNT));
+    assert!(contains(tags.as_bytes()));
+
+    let nobody = osc::frame_bundle("/posture", LiveState::Away, None, None);
+    let nobody_contains = |needle: &[u8]| nobody.windows(needle.len()).any(|window| window == needle);
+    assert!(nobody_contains(b"/posture/present\0\0\0\0,i\0\0\0\0\0\0"));
+    assert!(!nobody_contains(b"/keypoint"));
+    assert!(!nobody_contains(b"/delta"));
+}
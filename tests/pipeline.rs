@@ -0,0 +1,114 @@
+//! Detection pipeline: frame rotation, flips and scaling and letterboxing before the model, the reused frame buffers and the keypoint confidence cutoff.
+
+use image::{Rgb, RgbImage};
+use posture_ai_oc::pipeline::{self, FramePipeline, Letterbox};
+use posture_ai_oc::pose::{Flip, Keypoint, KEYPOINT_COUNT, NOSE, RIGHT_EYE};
+
+fn marked_frame() -> RgbImage {
+    // 3x2 frame with the top-left pixel marked
+    let mut frame = RgbImage::new(3, 2);
+    frame.put_pixel(0, 0, Rgb([255, 0, 0]));
+    frame
+}
+
+fn marked(frame: &RgbImage) -> Option<(u32, u32)> {
+    frame.enumerate_pixels().find(|(_, _, pixel)| pixel.0[0] == 255).map(|(x, y, _)| (x, y))
+}
+
+#[test]
+fn frames_are_rotated_clockwise_by_the_configured_angle() {
+    let quarter = pipeline::rotate(marked_frame(), 90);
+    assert_eq!(quarter.dimensions(), (2, 3));
+    assert_eq!(marked(&quarter), Some((1, 0)));
+    assert_eq!(marked(&pipeline::rotate(marked_frame(), 180)), Some((2, 1)));
+    assert_eq!(marked(&pipeline::rotate(marked_frame(), 270)), Some((0, 2)));
+}
+
+#[test]
+fn full_turns_leave_the_frame_alone() {
+    for degrees in [0, 360] {
+        assert_eq!(pipeline::rotate(marked_frame(), degrees), marked_frame());
+    }
+}
+
+#[test]
+fn other_angles_turn_about_the_center() {
+    let white = RgbImage::from_pixel(5, 5, Rgb([255, 255, 255]));
+    let askew = pipeline::rotate(white, 45);
+    assert_eq!(askew.dimensions(), (5, 5));
+    assert_eq!(askew.get_pixel(2, 2), &Rgb([255, 255, 255]));
+    assert_eq!(askew.get_pixel(0, 0), &Rgb([0, 0, 0]));
+
+    // The nearest quarter turn is taken exactly first
+    assert_eq!(pipeline::rotate(marked_frame(), 100).dimensions(), (2, 3));
+}
+
+#[test]
+fn flips_mirror_the_frame() {
+    let mut mirrored = marked_frame();
+    pipeline::flip(&mut mirrored, Flip { horizontal: true, vertical: false });
+    assert_eq!(marked(&mirrored), Some((2, 0)));
+
+    let mut both = marked_frame();
+    pipeline::flip(&mut both, Flip { horizontal: true, vertical: true });
+    assert_eq!(marked(&both), Some((2, 1)));
+}
+
+#[test]
+fn unsure_keypoints_are_dropped_below_the_cutoff() {
+    let mut pose = [Keypoint { x: 0.5, y: 0.5, score: 0.9 }; KEYPOINT_COUNT];
+    pose[RIGHT_EYE].score = 0.4;
+    let strict = pipeline::drop_unsure(&pose, 0.5);
+    assert_eq!(strict[RIGHT_EYE], Keypoint { x: 0.5, y: 0.5, score: 0.0 });
+    assert_eq!(strict[NOSE], pose[NOSE]);
+    assert_eq!(pipeline::drop_unsure(&pose, 0.3), pose);
+}
+
+#[test]
+fn scaling_averages_the_covered_pixels() {
+    let frame = RgbImage::from_fn(4, 2, |x, _| if x < 2 { Rgb([200, 0, 0]) } else { Rgb([0, 0, 100]) });
+    let mut half = RgbImage::default();
+    pipeline::resize_into(&frame, 2, 1, &mut half);
+    assert_eq!(half.pixels().copied().collect::<Vec<_>>(), [Rgb([200, 0, 0]), Rgb([0, 0, 100])]);
+
+    let mut double = RgbImage::default();
+    pipeline::resize_into(&marked_frame(), 6, 4, &mut double);
+    assert_eq!(double.dimensions(), (6, 4));
+    assert_eq!(double.get_pixel(1, 1).0, [255, 0, 0]);
+    assert_eq!(double.get_pixel(2, 0).0, [0, 0, 0]);
+}
+
+#[test]
+fn recycled_frames_are_written_in_place() {
+    let mut frames = FramePipeline::default();
+    let frame = frames.upright(&marked_frame(), 90, Flip::default());
+    assert_eq!(marked(&frame), Some((1, 0)));
+    let buffer = frame.as_ptr();
+    frames.recycle(frame);
+
+    let next = frames.upright(&marked_frame(), 90, Flip { horizontal: true, vertical: false });
+    assert_eq!(next.as_ptr(), buffer);
+    assert_eq!(marked(&next), Some((0, 0)));
+}
+
+#[test]
+fn wide_frames_are_letterboxed_into_the_model_input() {
+    let frame = RgbImage::from_pixel(4, 2, Rgb([255, 255, 255]));
+    let mut input = RgbImage::default();
+    let letterbox = pipeline::letterbox_into(&frame, (0, 0, 4, 2), 4, &mut input);
+    assert_eq!(letterbox, Letterbox { left: 0.0, top: 0.25, width: 1.0, height: 0.5 });
+    let rows: Vec<u8> = (0..4).map(|y| input.get_pixel(2, y).0[0]).collect();
+    assert_eq!(rows, [0, 255, 255, 0]);
+}
+
+#[test]
+fn letterboxed_keypoints_map_back_to_the_frame() {
+    let letterbox = Letterbox { left: 0.0, top: 0.25, width: 1.0, height: 0.5 };
+    let mut pose = [Keypoint::default(); KEYPOINT_COUNT];
+    pose[NOSE] = Keypoint { x: 0.5, y: 0.25, score: 0.9 };
+    pose[RIGHT_EYE] = Keypoint { x: 0.25, y: 0.625, score: 0.8 };
+    let on_frame = letterbox.unmap(&pose);
+    assert_eq!(on_frame[NOSE], Keypoint { x: 0.5, y: 0.0, score: 0.9 });
+    assert_eq!(on_frame[RIGHT_EYE], Keypoint { x: 0.25, y: 0.75, score: 0.8 });
+    assert_eq!(Letterbox::STRETCHED.unmap(&pose), pose);
+}
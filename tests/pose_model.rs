@@ -0,0 +1,86 @@
+//! Pose model variants: files, input sizes, choosing one in settings.json, input tensor layouts and output parsing.
+
+use posture_ai_oc::pose::{Keypoint, KEYPOINT_COUNT, NOSE};
+use posture_ai_oc::pose_model::{fill_interleaved, fill_planar, InputType, PoseModel};
+use posture_ai_oc::settings::Settings;
+use posture_ai_oc::yolo_pose::{parse_yolo_pose_output, parse_yolo_pose_people};
+
+#[test]
+fn each_variant_has_its_own_file_and_input_size() {
+    assert_eq!(PoseModel::Lightning.path(), "movenet_singlepose_lightning.onnx");
+    assert_eq!(PoseModel::Thunder.path(), "movenet_singlepose_thunder.onnx");
+    assert_eq!((PoseModel::Lightning.input_size(), PoseModel::Thunder.input_size()), (192, 256));
+    assert_eq!(PoseModel::Thunder.input_type(), InputType::Int32);
+}
+
+#[test]
+fn model_is_chosen_in_settings_json() {
+    let settings: Settings = serde_json::from_str(r#"{ "pose_model": "lightning" }"#).unwrap();
+    assert_eq!(settings.pose_model, PoseModel::Lightning);
+    assert!(serde_json::from_str::<Settings>(r#"{ "pose_model": "blazepose" }"#).is_err());
+}
+
+#[test]
+fn yolov8_pose_is_selectable_and_uses_its_own_layout() {
+    let settings: Settings = serde_json::from_str(r#"{ "pose_model": "yolov8_pose" }"#).unwrap();
+    assert_eq!(settings.pose_model, PoseModel::Yolov8Pose);
+    assert_eq!(PoseModel::Yolov8Pose.path(), "yolov8n-pose.onnx");
+    assert_eq!((PoseModel::Yolov8Pose.input_size(), PoseModel::Yolov8Pose.input_type()), (640, InputType::Float32));
+}
+
+/// A [1, 56, detections] output with one value per row and detection
+fn yolo_output(detections: &[(f32, [f32; 3])]) -> Vec<f32> {
+    let count = detections.len();
+    let mut data = vec![0.0; 56 * count];
+    for (i, &(confidence, keypoint)) in detections.iter().enumerate() {
+        data[4 * count + i] = confidence;
+        for k in 0..KEYPOINT_COUNT {
+            for (offset, value) in keypoint.iter().enumerate() {
+                data[(5 + k * 3 + offset) * count + i] = *value;
+            }
+        }
+    }
+    data
+}
+
+#[test]
+fn yolo_output_uses_the_most_confident_person_scaled_to_the_frame() {
+    let data = yolo_output(&[(0.4, [64.0, 64.0, 0.5]), (0.9, [320.0, 160.0, 0.8]), (0.1, [0.0, 0.0, 1.0])]);
+    let pose = parse_yolo_pose_output(&data, 640).unwrap();
+    assert_eq!(pose[NOSE], Keypoint { x: 0.5, y: 0.25, score: 0.8 });
+}
+
+#[test]
+fn yolo_output_without_a_confident_person_has_no_pose() {
+    assert_eq!(parse_yolo_pose_output(&yolo_output(&[(0.1, [320.0, 160.0, 0.9])]), 640), None);
+    assert_eq!(parse_yolo_pose_output(&[], 640), None);
+}
+
+#[test]
+fn yolo_output_lists_everyone_once_most_confident_first() {
+    let mut data = yolo_output(&[(0.5, [64.0, 64.0, 0.5]), (0.9, [320.0, 160.0, 0.8]), (0.8, [330.0, 170.0, 0.8])]);
+    // Boxes (cx, cy, w, h): the last two overlap almost entirely, the first is apart
+    for (i, [cx, cy, w, h]) in [[64.0, 64.0, 50.0, 50.0], [320.0, 160.0, 100.0, 200.0], [325.0, 165.0, 100.0, 200.0]].into_iter().enumerate() {
+        for (row, value) in [cx, cy, w, h].into_iter().enumerate() {
+            data[row * 3 + i] = value;
+        }
+    }
+    let people = parse_yolo_pose_people(&data, 640);
+    assert_eq!(people.len(), 2);
+    assert_eq!((people[0][NOSE].x, people[1][NOSE].x), (0.5, 0.1));
+}
+
+#[test]
+fn interleaved_input_keeps_the_pixel_order() {
+    let rgb = [1, 2, 3, 4, 5, 6];
+    let mut out = [0i32; 6];
+    fill_interleaved(&rgb, &mut out, i32::from);
+    assert_eq!(out, [1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn planar_input_splits_the_channels_and_overwrites_the_last_frame() {
+    let mut out = [9.0f32; 6];
+    fill_planar(&[0, 51, 255, 102, 153, 204], &mut out, |value| value as f32 / 255.0);
+    assert_eq!(out, [0.0, 0.4, 0.2, 0.6, 1.0, 0.8]);
+}
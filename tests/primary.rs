@@ -0,0 +1,49 @@
+//! Primary subject: following the user and ignoring passersby when several people are found.
+
+use posture_ai_oc::pose::{Keypoint, Pose, KEYPOINT_COUNT, LEFT_SHOULDER, NOSE, RIGHT_SHOULDER};
+use posture_ai_oc::primary::PrimarySubject;
+
+/// A head and shoulders centered on `x`, `size` wide
+fn person(x: f32, size: f32) -> Pose {
+    let mut pose = [Keypoint::default(); KEYPOINT_COUNT];
+    pose[NOSE] = Keypoint { x, y: 0.4, score: 0.9 };
+    pose[LEFT_SHOULDER] = Keypoint { x: x + size / 2.0, y: 0.4 + size / 2.0, score: 0.9 };
+    pose[RIGHT_SHOULDER] = Keypoint { x: x - size / 2.0, y: 0.4 + size / 2.0, score: 0.9 };
+    pose
+}
+
+#[test]
+fn the_biggest_central_person_is_picked_first() {
+    let mut primary = PrimarySubject::default();
+    let (user, passerby) = (person(0.5, 0.4), person(0.85, 0.15));
+    assert_eq!(primary.choose(&[passerby, user]), Some(user));
+}
+
+#[test]
+fn the_user_is_followed_even_when_someone_bigger_shows_up() {
+    let mut primary = PrimarySubject::default();
+    primary.choose(&[person(0.4, 0.3)]);
+    let (moved, visitor) = (person(0.45, 0.3), person(0.6, 0.5));
+    assert_eq!(primary.choose(&[visitor, moved]), Some(moved));
+}
+
+#[test]
+fn a_small_passerby_is_ignored_until_the_user_is_forgotten() {
+    let mut primary = PrimarySubject::default();
+    primary.choose(&[person(0.5, 0.4)]);
+    let passerby = person(0.1, 0.1);
+    for _ in 0..9 {
+        assert_eq!(primary.choose(&[passerby]), None);
+    }
+    // The 10th frame without the user forgets them; the next one takes whoever is there
+    assert_eq!(primary.choose(&[passerby]), None);
+    assert_eq!(primary.choose(&[passerby]), Some(passerby));
+}
+
+#[test]
+fn unsure_poses_are_passed_on() {
+    let mut primary = PrimarySubject::default();
+    let unsure = [Keypoint { x: 0.5, y: 0.5, score: 0.1 }; KEYPOINT_COUNT];
+    assert_eq!(primary.choose(&[unsure]), Some(unsure));
+    assert_eq!(primary.choose(&[]), None);
+}
@@ -0,0 +1,73 @@
+//! Probe report: exit codes and the text and JSON forms.
+
+use posture_ai_oc::exit_status::{EXIT_CAMERA_MISSING, EXIT_ERROR, EXIT_MODEL_MISSING, EXIT_OK};
+use posture_ai_oc::pose_model::PoseModel;
+use posture_ai_oc::probe::{CameraReport, FormatReport, InferenceReport, ModelReport, ProbeReport};
+
+fn camera() -> CameraReport {
+    CameraReport {
+        index: Some(0),
+        name: "Integrated Camera".to_string(),
+        is_virtual: false,
+        formats: vec![
+            FormatReport { width: 1280, height: 720, fps: 30, format: "MJPEG".to_string() },
+            FormatReport { width: 640, height: 480, fps: 30, format: "YUYV".to_string() },
+        ],
+        error: None,
+    }
+}
+
+fn model(path: Option<&str>, loaded: bool) -> ModelReport {
+    ModelReport {
+        model: PoseModel::Lightning,
+        path: path.map(str::to_string),
+        loaded,
+        accelerator: loaded.then_some("CPU"),
+        load_ms: loaded.then_some(120),
+        error: (!loaded).then(|| "Model error: bad file".to_string()),
+    }
+}
+
+fn inference(error: Option<&str>) -> InferenceReport {
+    InferenceReport {
+        image: None,
+        inference_ms: error.is_none().then_some(8),
+        person_found: false,
+        confident_keypoints: 0,
+        error: error.map(str::to_string),
+    }
+}
+
+#[test]
+fn a_working_setup_exits_with_zero() {
+    let report = ProbeReport::new(vec![camera()], model(Some("model.onnx"), true), Some(inference(None)));
+    assert_eq!(report.exit_code, EXIT_OK);
+}
+
+#[test]
+fn failures_map_to_the_startup_exit_codes() {
+    assert_eq!(ProbeReport::new(vec![camera()], model(None, false), None).exit_code, EXIT_MODEL_MISSING);
+    assert_eq!(ProbeReport::new(vec![camera()], model(Some("model.onnx"), false), None).exit_code, EXIT_ERROR);
+    let failed = Some(inference(Some("Failed to read me.jpg")));
+    assert_eq!(ProbeReport::new(vec![camera()], model(Some("model.onnx"), true), failed).exit_code, EXIT_ERROR);
+    let report = ProbeReport::new(Vec::new(), model(Some("model.onnx"), true), Some(inference(None)));
+    assert_eq!(report.exit_code, EXIT_CAMERA_MISSING);
+}
+
+#[test]
+fn cameras_are_described_with_their_formats() {
+    assert_eq!(camera().describe(), "0: Integrated Camera\n    1280x720 @ 30 fps MJPEG\n    640x480 @ 30 fps YUYV");
+    let busy = CameraReport { index: None, formats: Vec::new(), error: Some("in use".to_string()), ..camera() };
+    assert_eq!(busy.describe(), "-: Integrated Camera\n    (formats unavailable: in use)");
+}
+
+#[test]
+fn the_report_is_json() {
+    let report = ProbeReport::new(vec![camera()], model(Some("model.onnx"), true), Some(inference(None)));
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["exit_code"], 0);
+    assert_eq!(json["cameras"][0]["formats"][0]["format"], "MJPEG");
+    assert_eq!(json["model"]["accelerator"], "CPU");
+    assert_eq!(json["inference"]["inference_ms"], 8);
+    assert!(json["version"].is_string());
+}
@@ -0,0 +1,59 @@
+//! Report aggregation over stored per-second history rows.
+
+use posture_ai_oc::history::SecondRow;
+use posture_ai_oc::report::PeriodSummary;
+
+const HOUR: i64 = 3600;
+
+fn row(second: i64, bad_frames: u32) -> SecondRow {
+    SecondRow { second, frames: 10, bad_frames, alerting: false }
+}
+
+/// `count` consecutive seconds starting at `start`
+fn run(start: i64, count: i64, bad_frames: u32) -> Vec<SecondRow> {
+    (start..start + count).map(|second| row(second, bad_frames)).collect()
+}
+
+#[test]
+fn slouching_time_is_bucketed_by_local_hour() {
+    // 09:00 UTC; one minute upright, then half a minute slouched
+    let start = 9 * HOUR;
+    let rows = [run(start, 60, 0), run(start + 60, 30, 8)].concat();
+
+    let summary = PeriodSummary::from_rows(&rows, 1, 2 * HOUR);
+    assert_eq!(summary.monitored_secs, 90);
+    assert_eq!(summary.slouching_secs, 30);
+    assert_eq!(summary.triggers, 1);
+    // UTC+2 puts everything in the 11:00 bucket
+    assert_eq!(summary.monitored_by_hour[11], 90);
+    assert_eq!(summary.slouching_by_hour[11], 30);
+    assert_eq!(summary.monitored_by_hour[9], 0);
+}
+
+#[test]
+fn a_few_bad_frames_do_not_count_as_slouching() {
+    let summary = PeriodSummary::from_rows(&run(0, 10, 3), 0, 0);
+    assert_eq!(summary.monitored_secs, 10);
+    assert_eq!(summary.slouching_secs, 0);
+}
+
+#[test]
+fn longest_streak_survives_short_gaps_but_not_bad_posture_or_breaks() {
+    let rows = [
+        run(0, 100, 0),
+        // Dropped seconds (slow frames) keep the streak going
+        run(102, 50, 0),
+        // One bad second ends it
+        run(152, 1, 1),
+        run(153, 20, 0),
+        // Ten minutes away from the desk ends it too
+        run(800, 200, 0),
+    ]
+    .concat();
+
+    let summary = PeriodSummary::from_rows(&rows, 0, 0);
+    assert_eq!(summary.longest_good_streak_secs, 200);
+
+    let summary = PeriodSummary::from_rows(&rows[..150], 0, 0);
+    assert_eq!(summary.longest_good_streak_secs, 152);
+}
@@ -0,0 +1,59 @@
+//! Region-of-interest cropping: the crop around the person, snapping it to pixels and falling back to the full frame.
+
+use posture_ai_oc::pose::{Keypoint, Pose, KEYPOINT_COUNT, LEFT_SHOULDER, NOSE, RIGHT_SHOULDER};
+use posture_ai_oc::roi::{Region, RoiTracker};
+
+fn pose_at(points: &[(usize, f32, f32)]) -> Pose {
+    let mut pose = [Keypoint::default(); KEYPOINT_COUNT];
+    for &(index, x, y) in points {
+        pose[index] = Keypoint { x, y, score: 0.9 };
+    }
+    pose
+}
+
+#[test]
+fn crop_grows_around_the_confident_keypoints() {
+    let pose = pose_at(&[(NOSE, 0.5, 0.5), (LEFT_SHOULDER, 0.625, 0.75), (RIGHT_SHOULDER, 0.375, 0.75)]);
+    let region = Region::around(&pose).unwrap();
+    // 0.25 wide spread grown by 0.6 on each side
+    assert!((region.width - 0.55).abs() < 1e-6);
+    assert!((region.left + region.width / 2.0 - 0.5).abs() < 1e-6);
+    // The 0.25 tall spread grows the same way, around its own center
+    assert!((region.height - 0.55).abs() < 1e-6);
+    assert!((region.top + region.height / 2.0 - 0.625).abs() < 1e-6);
+}
+
+#[test]
+fn small_crops_are_widened_and_kept_inside_the_frame() {
+    let pose = pose_at(&[(NOSE, 0.95, 0.05), (LEFT_SHOULDER, 0.96, 0.06), (RIGHT_SHOULDER, 0.94, 0.06)]);
+    let region = Region::around(&pose).unwrap();
+    assert_eq!((region.width, region.height), (0.4, 0.4));
+    assert!((region.left + region.width - 1.0).abs() < 1e-6);
+    assert_eq!(region.top, 0.0);
+}
+
+#[test]
+fn too_few_keypoints_fall_back_to_the_full_frame() {
+    let mut tracker = RoiTracker::default();
+    tracker.update(Some(&pose_at(&[(NOSE, 0.5, 0.5), (LEFT_SHOULDER, 0.6, 0.7), (RIGHT_SHOULDER, 0.4, 0.7)])));
+    assert_ne!(tracker.region(), Region::FULL);
+
+    tracker.update(Some(&pose_at(&[(NOSE, 0.5, 0.5)])));
+    assert_eq!(tracker.region(), Region::FULL);
+
+    tracker.update(Some(&pose_at(&[(NOSE, 0.5, 0.5), (LEFT_SHOULDER, 0.6, 0.7), (RIGHT_SHOULDER, 0.4, 0.7)])));
+    tracker.update(None);
+    assert_eq!(tracker.region(), Region::FULL);
+}
+
+#[test]
+fn snapping_covers_whole_pixels_and_maps_keypoints_back() {
+    let region = Region { left: 0.25, top: 0.5, width: 0.5, height: 0.45 };
+    let (snapped, pixels) = region.snap(100, 10);
+    assert_eq!(pixels, (25, 5, 50, 5));
+    assert_eq!(snapped, Region { left: 0.25, top: 0.5, width: 0.5, height: 0.5 });
+
+    let crop = Region { left: 0.25, top: 0.5, width: 0.5, height: 0.5 };
+    let on_frame = crop.to_frame(&pose_at(&[(NOSE, 0.5, 0.5)]));
+    assert_eq!((on_frame[NOSE].x, on_frame[NOSE].y, on_frame[NOSE].score), (0.5, 0.75, 0.9));
+}
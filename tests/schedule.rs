@@ -0,0 +1,67 @@
+//! Work schedule: parsing day and hour windows and checking the current time against them.
+
+use posture_ai_oc::local_time::LocalTime;
+use posture_ai_oc::schedule::{self, WorkSchedule};
+use posture_ai_oc::settings::Settings;
+
+/// Local time on 2024-06-03 (a Monday) plus `days`, at `hour:minute`
+fn at(days: i64, hour: i64, minute: i64) -> LocalTime {
+    let monday = LocalTime::parse_date("2024-06-03").unwrap().day_number();
+    LocalTime::from_unix_secs((monday + days) * 86_400 + hour * 3600 + minute * 60, 0)
+}
+
+#[test]
+fn weekday_window_covers_office_hours() {
+    let office = WorkSchedule::parse("Mon-Fri 09:00-18:00").unwrap();
+    assert!(office.contains(&at(0, 9, 0)));
+    assert!(office.contains(&at(4, 17, 59)));
+    assert!(!office.contains(&at(0, 8, 59)));
+    assert!(!office.contains(&at(2, 18, 0)));
+    // Saturday and Sunday
+    assert!(!office.contains(&at(5, 12, 0)));
+    assert!(!office.contains(&at(6, 12, 0)));
+}
+
+#[test]
+fn several_windows_day_lists_and_midnight() {
+    let schedule = WorkSchedule::parse("mon,wed 08:30-12:00; Fri 22:00-02:00").unwrap();
+    assert!(schedule.contains(&at(0, 8, 30)));
+    assert!(!schedule.contains(&at(1, 9, 0)));
+    assert!(schedule.contains(&at(2, 11, 59)));
+    // Friday night runs into Saturday morning, but not Friday morning
+    assert!(schedule.contains(&at(4, 23, 0)));
+    assert!(schedule.contains(&at(5, 1, 59)));
+    assert!(!schedule.contains(&at(5, 2, 0)));
+    assert!(!schedule.contains(&at(4, 1, 0)));
+    assert_eq!(schedule.describe(), "Mon,Wed 08:30-12:00; Fri 22:00-02:00");
+
+    let weekend = WorkSchedule::parse("Sat-Mon 10:00-24:00").unwrap();
+    assert_eq!(weekend.describe(), "Mon,Sat-Sun 10:00-24:00");
+    assert!(weekend.contains(&at(6, 23, 59)));
+}
+
+#[test]
+fn invalid_schedules_are_rejected() {
+    for text in ["", "Mon-Fri", "Mon-Fri 9-18", "Someday 09:00-18:00", "Mon 18:00-18:00", "Mon 09:00-25:00", "Mon 09:60-10:00"] {
+        assert!(WorkSchedule::parse(text).is_err(), "{:?}", text);
+    }
+}
+
+#[test]
+fn no_schedule_means_always() {
+    assert!(schedule::is_active(None, &at(6, 3, 0)));
+    let office = WorkSchedule::parse("Mon-Fri 09:00-18:00").unwrap();
+    assert!(!schedule::is_active(Some(&office), &at(6, 3, 0)));
+}
+
+#[test]
+fn schedule_is_stored_as_text() {
+    let settings = Settings { work_schedule: Some(WorkSchedule::parse("Mon-Fri 09:00-18:00").unwrap()), ..Settings::default() };
+    let json = serde_json::to_string(&settings).unwrap();
+    assert!(json.contains("\"work_schedule\":\"Mon-Fri 09:00-18:00\""), "{}", json);
+    let loaded: Settings = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, settings);
+
+    let broken = json.replace("Mon-Fri 09:00-18:00", "whenever");
+    assert!(serde_json::from_str::<Settings>(&broken).is_err());
+}
@@ -0,0 +1,179 @@
+//! Settings persistence: atomic saves, last-known-good backup and corruption recovery.
+
+use std::fs;
+use std::path::PathBuf;
+
+use posture_ai_oc::blur_overlay::{OverlayEffect, ScreenRect};
+use posture_ai_oc::camera::ControlSetting;
+use posture_ai_oc::logging::LogLevel;
+use posture_ai_oc::pose::Flip;
+use posture_ai_oc::pose_model::PoseModel;
+use posture_ai_oc::settings::{
+    Overrides, Recovery, SettingChange, Settings, SettingsStore, DEBOUNCE_RANGE_MS, EXPOSURE_RANGE, SENSITIVITY_RANGE_PX,
+};
+
+/// Fresh directory per test so runs don't interfere
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("posture_ai_settings_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn custom_settings() -> Settings {
+    Settings {
+        camera_index: Some(2),
+        sound_alerts_enabled: true,
+        ..Settings::default()
+    }
+}
+
+#[test]
+fn missing_file_gives_defaults_without_recovery() {
+    let path = temp_dir("missing").join("settings.json");
+    let loaded = Settings::load_from(&path);
+    assert_eq!(loaded.settings, Settings::default());
+    assert_eq!(loaded.recovery, None);
+}
+
+#[test]
+fn saved_settings_round_trip_and_leave_no_temp_file() {
+    let dir = temp_dir("round_trip");
+    let path = dir.join("settings.json");
+    custom_settings().save_to(&path).unwrap();
+
+    let loaded = Settings::load_from(&path);
+    assert_eq!(loaded.settings, custom_settings());
+    assert_eq!(loaded.recovery, None);
+    assert!(fs::read_dir(&dir).unwrap().all(|entry| entry.unwrap().path().extension().unwrap() != "tmp"));
+}
+
+#[test]
+fn damaged_file_is_restored_from_backup() {
+    let path = temp_dir("backup").join("settings.json");
+    custom_settings().save_to(&path).unwrap();
+    fs::write(&path, "{ \"camera_index\": 2, \"blur_overl").unwrap();
+
+    let loaded = Settings::load_from(&path);
+    assert_eq!(loaded.settings, custom_settings());
+    assert_eq!(loaded.recovery, Some(Recovery::FromBackup));
+
+    // The main file was repaired, so the next start is clean
+    let reloaded = Settings::load_from(&path);
+    assert_eq!(reloaded.recovery, None);
+    assert_eq!(reloaded.settings, custom_settings());
+}
+
+#[test]
+fn damaged_file_without_backup_is_set_aside() {
+    let dir = temp_dir("corrupt");
+    let path = dir.join("settings.json");
+    fs::write(&path, "not json").unwrap();
+
+    let loaded = Settings::load_from(&path);
+    assert_eq!(loaded.settings, Settings::default());
+    assert_eq!(loaded.recovery, Some(Recovery::Defaults));
+    assert!(!path.exists());
+    assert_eq!(fs::read_to_string(dir.join("settings.corrupt.json")).unwrap(), "not json");
+}
+
+#[test]
+fn older_files_missing_new_fields_still_load() {
+    let path = temp_dir("partial").join("settings.json");
+    fs::write(&path, "{ \"camera_index\": 1 }").unwrap();
+
+    let loaded = Settings::load_from(&path);
+    assert_eq!(loaded.recovery, None);
+    assert_eq!(loaded.settings.camera_index, Some(1));
+    assert_eq!(loaded.settings.blur_overlay_enabled, Settings::default().blur_overlay_enabled);
+}
+
+#[test]
+fn overlay_exclusions_load_from_json() {
+    let path = temp_dir("exclusions").join("settings.json");
+    let json = r#"{ "overlay_exclusions": [{ "left": 0, "top": 1040, "right": 1920, "bottom": 1080 }], "overlay_exclude_taskbar": true }"#;
+    fs::write(&path, json).unwrap();
+
+    let exclusions = Settings::load_from(&path).settings.overlay_exclusions();
+    assert_eq!(exclusions.rects, vec![ScreenRect { left: 0, top: 1040, right: 1920, bottom: 1080 }]);
+    assert!(exclusions.taskbar);
+}
+
+#[test]
+fn edits_are_clamped_and_report_changes() {
+    let mut settings = Settings::default();
+    assert!(settings.apply(SettingChange::Sensitivity(500.0)));
+    assert_eq!(settings.sensitivity_px, *SENSITIVITY_RANGE_PX.end());
+    assert!(!settings.apply(SettingChange::Sensitivity(500.0)));
+
+    assert!(settings.apply(SettingChange::DebounceMs(60_000)));
+    assert_eq!(settings.debounce_ms, *DEBOUNCE_RANGE_MS.end());
+
+    // Any angle below a full turn is accepted
+    let rotation = settings.camera_rotation_degrees;
+    assert!(!settings.apply(SettingChange::Rotation(360)));
+    assert_eq!(settings.camera_rotation_degrees, rotation);
+    assert!(settings.apply(SettingChange::Rotation((rotation + 45) % 360)));
+
+    assert!(settings.apply(SettingChange::OverlayEffect(OverlayEffect::RedVignette)));
+    assert!(settings.apply(SettingChange::Camera(3)));
+    assert_eq!(settings.camera_index, Some(3));
+}
+
+#[test]
+fn capture_edits_stick_to_the_offered_choices() {
+    let mut settings = Settings::default();
+    assert!(!settings.apply(SettingChange::Resolution(Some((1000, 1000)))));
+    assert!(settings.apply(SettingChange::Resolution(Some((640, 480)))));
+    assert!(!settings.apply(SettingChange::FrameRate(7)));
+    assert!(settings.apply(SettingChange::FrameRate(15)));
+    assert!(settings.apply(SettingChange::Mirror(true)));
+    assert!(settings.apply(SettingChange::FlipVertical(true)));
+    assert_eq!(settings.camera_flip(), Flip { horizontal: true, vertical: true });
+    assert!(settings.apply(SettingChange::Exposure(ControlSetting::Fixed(-40))));
+    assert_eq!(settings.camera_exposure, ControlSetting::Fixed(*EXPOSURE_RANGE.start()));
+
+    let options = settings.capture_options();
+    assert_eq!((options.resolution, options.fps, options.exposure), (Some((640, 480)), 15, settings.camera_exposure));
+}
+
+#[test]
+fn low_memory_mode_replaces_the_model_frame_size_and_buffers() {
+    let mut settings = Settings { pose_model: PoseModel::Yolov8Pose, camera_resolution: Some((1920, 1080)), ..Settings::default() };
+    assert_eq!(settings.model(), PoseModel::Yolov8Pose);
+    assert!(settings.apply(SettingChange::LowMemory(true)));
+    assert_eq!(settings.model(), PoseModel::Lightning);
+    assert_eq!(settings.capture_options().resolution, Some((640, 480)));
+    assert_eq!(settings.episode_frames(), (0, 0));
+    assert!(settings.preview_frame_interval() > 1);
+
+    // The user's own choices come back with it off
+    assert!(settings.apply(SettingChange::LowMemory(false)));
+    assert_eq!(settings.model(), PoseModel::Yolov8Pose);
+    assert_eq!(settings.capture_options().resolution, Some((1920, 1080)));
+}
+
+#[test]
+fn command_line_overrides_apply_but_stay_out_of_the_file() {
+    let path = temp_dir("overrides").join("custom.json");
+    custom_settings().save_to(&path).unwrap();
+    let overrides = Overrides { camera_index: Some(5), overlay_off: true, log_level: Some(LogLevel::Debug) };
+
+    let (mut store, loaded) = SettingsStore::load(Some(path.clone()), overrides);
+    let mut settings = loaded.settings;
+    assert_eq!((settings.camera_index, settings.blur_overlay_enabled, settings.log_level), (Some(5), false, LogLevel::Debug));
+
+    settings.apply(SettingChange::DebounceMs(1_000));
+    store.save(&settings).unwrap();
+    let saved = Settings::load_from(&path).settings;
+    assert_eq!(saved.debounce_ms, 1_000);
+    assert_eq!(
+        (saved.camera_index, saved.blur_overlay_enabled, saved.log_level),
+        (Some(2), custom_settings().blur_overlay_enabled, custom_settings().log_level)
+    );
+
+    // Picked again during the run: that choice is kept
+    settings.apply(SettingChange::Camera(1));
+    store.save(&settings).unwrap();
+    assert_eq!(Settings::load_from(&path).settings.camera_index, Some(1));
+}
@@ -0,0 +1,39 @@
+use posture_ai_oc::canvas::Canvas;
+use posture_ai_oc::config::KEYPOINT_CONFIDENCE;
+use posture_ai_oc::pose::{Keypoint, LEFT_SHOULDER, RIGHT_SHOULDER};
+use posture_ai_oc::skeleton;
+
+const SIZE: usize = 100;
+
+fn keypoint(x: f32, y: f32, score: f32) -> Keypoint {
+    Keypoint { x, y, score }
+}
+
+#[test]
+fn confidence_colors_run_from_red_to_green() {
+    let unsure = skeleton::confidence_color(KEYPOINT_CONFIDENCE - 0.01);
+    let certain = skeleton::confidence_color(1.0);
+
+    assert_eq!(unsure, 0xE62828);
+    assert_eq!(certain >> 16, 0, "no red in a confident keypoint");
+    assert!(certain & 0x00FF00 > 0);
+}
+
+#[test]
+fn draws_joints_and_bones_but_skips_missed_keypoints() {
+    let mut buffer = vec![0u32; SIZE * SIZE];
+    let mut pose = [Keypoint::default(); 17];
+    pose[LEFT_SHOULDER] = keypoint(0.2, 0.5, 0.9);
+    pose[RIGHT_SHOULDER] = keypoint(0.8, 0.5, 0.9);
+
+    let mut canvas = Canvas { buffer: &mut buffer, width: SIZE, height: SIZE };
+    skeleton::draw(&mut canvas, &pose);
+
+    let color = skeleton::confidence_color(0.9);
+    // Both joints and the bone between them
+    assert_eq!(buffer[50 * SIZE + 20], color);
+    assert_eq!(buffer[50 * SIZE + 80], color);
+    assert_eq!(buffer[50 * SIZE + 50], color);
+    // Zero-score keypoints sit at (0, 0) and must not be drawn
+    assert_eq!(buffer[0], 0);
+}
@@ -0,0 +1,25 @@
+//! Today's sitting and standing totals: one count per second, starting over at local midnight.
+
+use posture_ai_oc::stance::Stance;
+use posture_ai_oc::standing::{StanceDay, StanceTotals};
+
+const DAY: i64 = 24 * 60 * 60;
+
+#[test]
+fn each_second_counts_once_and_whole_minutes_are_reported() {
+    let mut totals = StanceTotals::new(StanceDay { local_day: 0, sitting_secs: 59, standing_secs: 0 });
+    assert!(totals.record(Some(Stance::Sitting), 100, 0));
+    // More frames in the same second
+    assert!(!totals.record(Some(Stance::Standing), 100, 0));
+    assert!(!totals.record(Some(Stance::Standing), 101, 0));
+    assert!(!totals.record(None, 102, 0));
+    assert_eq!(totals.today(), StanceDay { local_day: 0, sitting_secs: 60, standing_secs: 1 });
+}
+
+#[test]
+fn a_new_local_day_starts_over() {
+    let mut totals = StanceTotals::new(StanceDay { local_day: 0, sitting_secs: 3600, standing_secs: 600 });
+    // 23:30 UTC is already the next day at UTC+1
+    totals.record(Some(Stance::Standing), DAY - 1800, 3600);
+    assert_eq!(totals.today(), StanceDay { local_day: 1, sitting_secs: 0, standing_secs: 1 });
+}
@@ -0,0 +1,82 @@
+//! Good-posture streaks, daily goals and the score per day.
+
+use posture_ai_oc::config::STREAK_MAX_GAP_SECS;
+use posture_ai_oc::engine::PostureStatus;
+use posture_ai_oc::goals::Goals;
+use posture_ai_oc::history::SecondRow;
+use posture_ai_oc::streaks::{daily_scores, Achievement, DayScore, StreakTracker};
+
+const DAY: i64 = 24 * 60 * 60;
+
+fn goals(good_minutes: Option<u32>, streak_minutes: Option<u32>) -> Goals {
+    Goals { good_minutes_per_day: good_minutes, streak_minutes, ..Goals::default() }
+}
+
+/// One frame per second from `start` for `secs` seconds
+fn feed(tracker: &mut StreakTracker, goals: &Goals, status: PostureStatus, start: i64, secs: i64) -> Vec<Achievement> {
+    (start..start + secs).flat_map(|second| tracker.update(status, goals, second, 0)).collect()
+}
+
+#[test]
+fn streak_milestones_are_celebrated_once_per_day_at_new_bests() {
+    let goals = goals(None, None);
+    let mut tracker = StreakTracker::new();
+    assert_eq!(feed(&mut tracker, &goals, PostureStatus::Good, 0, 16 * 60), [Achievement::Streak { minutes: 15 }]);
+
+    // A slouch ends the streak; matching it again isn't a new best
+    feed(&mut tracker, &goals, PostureStatus::Slouching, 16 * 60, 10);
+    assert_eq!(tracker.streak_secs(), None);
+    assert!(feed(&mut tracker, &goals, PostureStatus::Good, 17 * 60, 16 * 60).is_empty());
+    assert_eq!(feed(&mut tracker, &goals, PostureStatus::Good, 33 * 60, 15 * 60), [Achievement::Streak { minutes: 30 }]);
+}
+
+#[test]
+fn short_gaps_keep_the_streak_and_longer_ones_end_it() {
+    let goals = goals(None, None);
+    let mut tracker = StreakTracker::new();
+    feed(&mut tracker, &goals, PostureStatus::Good, 0, 60);
+    // Skipped seconds at a low inference rate
+    tracker.update(PostureStatus::Good, &goals, 62, 0);
+    assert_eq!(tracker.streak_secs(), Some(63));
+    assert_eq!(tracker.good_secs(), 63);
+
+    // Away from the desk
+    feed(&mut tracker, &goals, PostureStatus::Away, 63, 60);
+    tracker.update(PostureStatus::Good, &goals, 63 + 60 + STREAK_MAX_GAP_SECS, 0);
+    assert_eq!(tracker.streak_secs(), Some(1));
+    assert_eq!(tracker.good_secs(), 64);
+}
+
+#[test]
+fn daily_goals_are_reached_once_and_start_over_the_next_day() {
+    let goals = goals(Some(20), Some(15));
+    let mut tracker = StreakTracker::new();
+    // The streak goal replaces the 15 min milestone
+    assert_eq!(feed(&mut tracker, &goals, PostureStatus::Good, 0, 15 * 60), [Achievement::StreakGoal { minutes: 15 }]);
+    feed(&mut tracker, &goals, PostureStatus::Alert, 15 * 60, 60);
+    assert_eq!(feed(&mut tracker, &goals, PostureStatus::Good, 16 * 60, 5 * 60), [Achievement::GoodTimeGoal { minutes: 20 }]);
+    assert!(feed(&mut tracker, &goals, PostureStatus::Good, 21 * 60, 10 * 60).is_empty());
+
+    let reached = feed(&mut tracker, &goals, PostureStatus::Good, DAY, 20 * 60);
+    assert_eq!(reached, [Achievement::StreakGoal { minutes: 15 }, Achievement::GoodTimeGoal { minutes: 20 }]);
+}
+
+#[test]
+fn scores_are_given_per_day_with_the_goals_reached() {
+    let row = |second: i64, bad_frames: u32| SecondRow { second, frames: 10, bad_frames, alerting: false };
+    let rows: Vec<SecondRow> = (0..80).map(|second| row(second, 0))
+        .chain((80..100).map(|second| row(second, 10)))
+        .chain((DAY..DAY + 10).map(|second| row(second, 0)))
+        .collect();
+    let achievements = [
+        (50_000, Achievement::StreakGoal { minutes: 1 }),
+        (60_000, Achievement::Streak { minutes: 15 }),
+    ];
+    assert_eq!(
+        daily_scores(&rows, &achievements, 0),
+        [
+            DayScore { local_day: 0, score: 80, goals_reached: 1 },
+            DayScore { local_day: 1, score: 100, goals_reached: 0 },
+        ]
+    );
+}
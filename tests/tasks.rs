@@ -0,0 +1,82 @@
+//! Background jobs and their cancellation.
+
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use posture_ai_oc::tasks::{self, BackgroundTasks, CancelToken};
+
+fn wait_for<T: Send + 'static>(tasks: &mut BackgroundTasks<T>) -> tasks::Finished<T> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        if let Some(finished) = tasks.poll() {
+            return finished;
+        }
+        assert!(Instant::now() < deadline, "job never finished");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn cancelling_wakes_a_sleeping_job() {
+    let token = CancelToken::new();
+    let sleeper = token.clone();
+    let started = Instant::now();
+    let handle = thread::spawn(move || sleeper.sleep(Duration::from_secs(60)));
+
+    token.cancel();
+    assert!(!handle.join().unwrap(), "sleep should report the cancellation");
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(!token.sleep(Duration::from_secs(60)), "a cancelled token doesn't sleep at all");
+}
+
+#[test]
+fn results_arrive_by_label_and_duplicates_are_refused() {
+    let mut tasks = BackgroundTasks::new(CancelToken::new());
+    let (release, gate) = mpsc::channel::<()>();
+
+    assert!(tasks.spawn("report", move |_| {
+        let _ = gate.recv();
+        Ok(42)
+    }));
+    assert!(tasks.is_running("report"));
+    assert!(!tasks.spawn("report", |_| Ok(0)), "the same job is already running");
+
+    release.send(()).unwrap();
+    let finished = wait_for(&mut tasks);
+    assert_eq!(finished.label, "report");
+    assert_eq!(finished.result.unwrap(), 42);
+    assert!(tasks.spawn("report", |_| Ok(1)), "can run again once finished");
+}
+
+#[test]
+fn shutdown_cancels_cooperative_jobs_and_abandons_stuck_ones() {
+    let mut tasks = BackgroundTasks::new(CancelToken::new());
+    let (_keep_stuck, stuck) = mpsc::channel::<()>();
+
+    tasks.spawn("cooperative", |cancel| {
+        while cancel.sleep(Duration::from_millis(10)) {}
+        cancel.check()?;
+        Ok(())
+    });
+    tasks.spawn("stuck", move |_| {
+        let _ = stuck.recv();
+        Ok(())
+    });
+
+    let started = Instant::now();
+    let left = tasks.shutdown(Duration::from_millis(200));
+    assert_eq!(left, vec!["stuck"]);
+    assert!(started.elapsed() < Duration::from_secs(2), "quitting must not wait on the stuck job");
+}
+
+#[test]
+fn cancellation_is_recognized_through_context() {
+    let token = CancelToken::new();
+    assert!(token.check().is_ok());
+
+    token.cancel();
+    let error = token.check().unwrap_err().context("creating report");
+    assert!(tasks::is_cancelled(&error));
+    assert!(!tasks::is_cancelled(&anyhow::anyhow!("disk full")));
+}
@@ -0,0 +1,61 @@
+//! Habit trends: time to the first alert after sitting down and time spent ignoring alerts.
+
+use posture_ai_oc::config::TRENDS_WEEKS;
+use posture_ai_oc::history::AlertRecord;
+use posture_ai_oc::trends::{self, Sitting};
+
+// Monday 2024-03-04 00:00:00 UTC
+const MONDAY_SECS: i64 = 1_709_510_400;
+const WEEK_SECS: i64 = 7 * 24 * 3600;
+
+fn sitting(first_second: i64, minutes: i64) -> Sitting {
+    Sitting { first_second, last_second: first_second + minutes * 60 }
+}
+
+/// Slouch starting at `slouch_secs`, alert 1 s later, ignored for `ignored_secs`
+fn alert(slouch_secs: i64, ignored_secs: i64, false_positive: bool) -> AlertRecord {
+    AlertRecord {
+        rule: "eye_drop".to_string(),
+        slouch_started_ms: slouch_secs * 1000,
+        started_ms: (slouch_secs + 1) * 1000,
+        ended_ms: (slouch_secs + 1 + ignored_secs) * 1000,
+        peak_delta: None,
+        snapshot: None,
+        false_positive,
+    }
+}
+
+#[test]
+fn weeks_run_monday_to_sunday_oldest_first() {
+    let weeks = trends::weekly(&[], &[], MONDAY_SECS + 3 * 3600, 0);
+    assert_eq!(weeks.len(), TRENDS_WEEKS as usize);
+    assert_eq!(weeks.last().unwrap().week_start, "2024-03-04");
+    assert_eq!(weeks[weeks.len() - 2].week_start, "2024-02-26");
+    assert!(weeks.iter().all(|week| week.sittings == 0 && week.avg_secs_to_first_alert.is_none()));
+}
+
+#[test]
+fn first_alert_and_ignoring_time_are_averaged_per_week() {
+    let last_week = MONDAY_SECS - WEEK_SECS + 9 * 3600;
+    let this_week = MONDAY_SECS + 9 * 3600;
+    let sittings = [sitting(last_week, 120), sitting(last_week + 86_400, 120), sitting(this_week, 120), sitting(this_week + 86_400, 30)];
+    let alerts = [
+        // Last week: 10 and 20 minutes in, ignored 30 s and 90 s
+        alert(last_week + 600, 30, false),
+        alert(last_week + 1_800, 5, false),
+        alert(last_week + 86_400 + 1_200, 90, false),
+        // This week: 50 minutes in, ignored 10 s; the earlier one was marked wrong
+        alert(this_week + 60, 300, true),
+        alert(this_week + 3_000, 10, false),
+    ];
+
+    let weeks = trends::weekly(&sittings, &alerts, this_week + 2 * 86_400, 0);
+    let (last, this) = (&weeks[weeks.len() - 2], &weeks[weeks.len() - 1]);
+    assert_eq!((last.sittings, last.sittings_with_alert, last.alerts), (2, 2, 3));
+    assert_eq!(last.avg_secs_to_first_alert, Some(900));
+    assert_eq!(last.avg_secs_ignoring_alerts, Some(41));
+    // The short sitting without an alert only counts as a sitting
+    assert_eq!((this.sittings, this.sittings_with_alert, this.alerts), (2, 1, 1));
+    assert_eq!(this.avg_secs_to_first_alert, Some(3_000));
+    assert_eq!(this.avg_secs_ignoring_alerts, Some(10));
+}